@@ -0,0 +1,340 @@
+//! Transport-agnostic client over JSON frames.
+
+use crate::error::ClientError;
+use crate::wire::{ClientFrame, ServerFrame};
+use chrono::Utc;
+use log::{debug, warn};
+use odyssey_rs_protocol::{
+    ApprovalDecision, EventMsg, SessionId, SubmissionEnvelope, SubmissionPayload, TurnId,
+};
+use std::collections::VecDeque;
+use std::future::Future;
+use uuid::Uuid;
+
+/// Bidirectional text channel carrying JSON frames, such as a WebSocket.
+///
+/// Implementations only move strings; they need not be `Send`, so browser
+/// `WebSocket` wrappers work on wasm.
+pub trait Transport {
+    /// Send one encoded frame.
+    fn send(&mut self, frame: String) -> impl Future<Output = Result<(), ClientError>>;
+    /// Receive the next frame, or `None` once the connection is closed.
+    fn recv(&mut self) -> impl Future<Output = Result<Option<String>, ClientError>>;
+}
+
+/// Client for a remote Odyssey server.
+///
+/// Events that arrive while a request waits for its reply are buffered and
+/// returned by later `next_event` calls, so none are lost.
+pub struct OdysseyClient<T> {
+    transport: T,
+    events: VecDeque<EventMsg>,
+    user_id: Option<String>,
+}
+
+impl<T: Transport> OdysseyClient<T> {
+    /// Wrap a connected transport.
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            events: VecDeque::new(),
+            user_id: None,
+        }
+    }
+
+    /// Act as `user_id`: created sessions are owned by it and submissions attributed to it.
+    pub fn with_user(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Create a session, optionally for a specific agent, and return its id.
+    pub async fn create_session(
+        &mut self,
+        agent_id: Option<String>,
+    ) -> Result<SessionId, ClientError> {
+        let request_id = Uuid::new_v4();
+        self.send(&ClientFrame::CreateSession {
+            request_id,
+            agent_id,
+            user_id: self.user_id.clone(),
+        })
+        .await?;
+        loop {
+            match self.reply(request_id).await? {
+                ServerFrame::SessionCreated { session_id, .. } => return Ok(session_id),
+                other => warn!("unexpected reply to create_session: {other:?}"),
+            }
+        }
+    }
+
+    /// Start receiving events for a session.
+    pub async fn subscribe(&mut self, session_id: SessionId) -> Result<(), ClientError> {
+        self.send(&ClientFrame::Subscribe { session_id }).await
+    }
+
+    /// Stop receiving events for a session.
+    pub async fn unsubscribe(&mut self, session_id: SessionId) -> Result<(), ClientError> {
+        self.send(&ClientFrame::Unsubscribe { session_id }).await
+    }
+
+    /// Send a user message and return the id of the turn it starts.
+    pub async fn send_message(
+        &mut self,
+        session_id: SessionId,
+        content: impl Into<String>,
+    ) -> Result<TurnId, ClientError> {
+        self.submit(
+            session_id,
+            SubmissionPayload::UserMessage {
+                content: content.into(),
+                attachments: Vec::new(),
+            },
+        )
+        .await
+    }
+
+    /// Cancel an in-flight or queued turn.
+    pub async fn cancel_turn(
+        &mut self,
+        session_id: SessionId,
+        turn_id: TurnId,
+    ) -> Result<(), ClientError> {
+        self.submit(session_id, SubmissionPayload::CancelTurn { turn_id })
+            .await?;
+        Ok(())
+    }
+
+    /// Enqueue a submission and return its id; user messages use it as their turn id.
+    pub async fn submit(
+        &mut self,
+        session_id: SessionId,
+        payload: SubmissionPayload,
+    ) -> Result<Uuid, ClientError> {
+        let id = Uuid::new_v4();
+        self.send(&ClientFrame::Submit(SubmissionEnvelope {
+            id,
+            session_id,
+            created_at: Utc::now(),
+            idempotency_key: None,
+            user_id: self.user_id.clone(),
+            payload,
+        }))
+        .await?;
+        Ok(id)
+    }
+
+    /// Answer a pending permission request; false when it was no longer pending.
+    pub async fn resolve_permission(
+        &mut self,
+        request_id: Uuid,
+        decision: ApprovalDecision,
+    ) -> Result<bool, ClientError> {
+        self.send(&ClientFrame::ResolvePermission {
+            request_id,
+            decision,
+        })
+        .await?;
+        loop {
+            match self.reply(request_id).await? {
+                ServerFrame::PermissionResolved { resolved, .. } => return Ok(resolved),
+                other => warn!("unexpected reply to resolve_permission: {other:?}"),
+            }
+        }
+    }
+
+    /// Next event from the subscribed sessions, or `None` once the connection closes.
+    ///
+    /// Server errors not tied to a request are returned as `ClientError::Server`.
+    pub async fn next_event(&mut self) -> Result<Option<EventMsg>, ClientError> {
+        if let Some(event) = self.events.pop_front() {
+            return Ok(Some(event));
+        }
+        loop {
+            let Some(frame) = self.recv().await? else {
+                return Ok(None);
+            };
+            match frame {
+                ServerFrame::Event(event) => return Ok(Some(event)),
+                ServerFrame::Error {
+                    request_id: None,
+                    message,
+                } => {
+                    return Err(ClientError::Server {
+                        request_id: None,
+                        message,
+                    });
+                }
+                other => debug!("ignoring unsolicited frame: {other:?}"),
+            }
+        }
+    }
+
+    /// Give back the transport, dropping any buffered events.
+    pub fn into_transport(self) -> T {
+        self.transport
+    }
+
+    /// Wait for the frame answering `request_id`, buffering events on the way.
+    async fn reply(&mut self, request_id: Uuid) -> Result<ServerFrame, ClientError> {
+        loop {
+            let frame = self.recv().await?.ok_or(ClientError::Closed)?;
+            match frame {
+                ServerFrame::Event(event) => self.events.push_back(event),
+                ServerFrame::Error {
+                    request_id: Some(id),
+                    message,
+                } if id == request_id => {
+                    return Err(ClientError::Server {
+                        request_id: Some(id),
+                        message,
+                    });
+                }
+                ServerFrame::SessionCreated { request_id: id, .. }
+                | ServerFrame::PermissionResolved { request_id: id, .. }
+                    if id == request_id =>
+                {
+                    return Ok(frame);
+                }
+                other => debug!("ignoring frame while waiting for {request_id}: {other:?}"),
+            }
+        }
+    }
+
+    async fn send(&mut self, frame: &ClientFrame) -> Result<(), ClientError> {
+        let text = serde_json::to_string(frame)?;
+        self.transport.send(text).await
+    }
+
+    async fn recv(&mut self) -> Result<Option<ServerFrame>, ClientError> {
+        match self.transport.recv().await? {
+            Some(text) => Ok(Some(serde_json::from_str(&text)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OdysseyClient, Transport};
+    use crate::error::ClientError;
+    use crate::wire::{ClientFrame, ServerFrame};
+    use chrono::Utc;
+    use odyssey_rs_protocol::{EventMsg, EventPayload};
+    use pretty_assertions::assert_eq;
+    use std::collections::VecDeque;
+    use uuid::Uuid;
+
+    /// Transport that answers from a script and records what was sent.
+    #[derive(Default)]
+    struct ScriptedTransport {
+        sent: Vec<ClientFrame>,
+        incoming: VecDeque<ServerFrame>,
+        /// Reply to the next `CreateSession` with this session id.
+        create_reply: Option<Uuid>,
+    }
+
+    impl Transport for ScriptedTransport {
+        async fn send(&mut self, frame: String) -> Result<(), ClientError> {
+            let frame: ClientFrame = serde_json::from_str(&frame)?;
+            if let ClientFrame::CreateSession { request_id, .. } = &frame
+                && let Some(session_id) = self.create_reply.take()
+            {
+                self.incoming.push_back(ServerFrame::SessionCreated {
+                    request_id: *request_id,
+                    session_id,
+                });
+            }
+            self.sent.push(frame);
+            Ok(())
+        }
+
+        async fn recv(&mut self) -> Result<Option<String>, ClientError> {
+            self.incoming
+                .pop_front()
+                .map(|frame| serde_json::to_string(&frame).map_err(ClientError::from))
+                .transpose()
+        }
+    }
+
+    fn event(session_id: Uuid, delta: &str) -> ServerFrame {
+        ServerFrame::Event(EventMsg {
+            id: Uuid::new_v4(),
+            session_id,
+            seq: 0,
+            user_id: None,
+            created_at: Utc::now(),
+            payload: EventPayload::AgentMessageDelta {
+                turn_id: Uuid::nil(),
+                delta: delta.to_string(),
+            },
+        })
+    }
+
+    fn delta(event: Option<EventMsg>) -> Option<String> {
+        match event.map(|event| event.payload) {
+            Some(EventPayload::AgentMessageDelta { delta, .. }) => Some(delta),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn buffers_events_received_while_waiting_for_a_reply() {
+        let session_id = Uuid::new_v4();
+        let transport = ScriptedTransport {
+            incoming: VecDeque::from([event(session_id, "early")]),
+            create_reply: Some(session_id),
+            ..ScriptedTransport::default()
+        };
+        let mut client = OdysseyClient::new(transport).with_user("alice");
+
+        assert_eq!(
+            client.create_session(None).await.expect("create"),
+            session_id
+        );
+        let turn_id = client
+            .send_message(session_id, "hello")
+            .await
+            .expect("send");
+        assert_eq!(
+            delta(client.next_event().await.expect("event")),
+            Some("early".to_string())
+        );
+        assert_eq!(client.next_event().await.expect("closed").is_none(), true);
+
+        let sent = client.into_transport().sent;
+        assert_eq!(sent.len(), 2);
+        assert_eq!(
+            matches!(&sent[0], ClientFrame::CreateSession { user_id: Some(user), .. } if user == "alice"),
+            true
+        );
+        assert_eq!(
+            matches!(&sent[1], ClientFrame::Submit(envelope) if envelope.id == turn_id && envelope.session_id == session_id && envelope.user_id.as_deref() == Some("alice")),
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn surfaces_server_errors_for_requests() {
+        let request_id = Uuid::new_v4();
+        let transport = ScriptedTransport {
+            incoming: VecDeque::from([ServerFrame::Error {
+                request_id: Some(request_id),
+                message: "unknown approval".to_string(),
+            }]),
+            ..ScriptedTransport::default()
+        };
+        let mut client = OdysseyClient::new(transport);
+        let err = client
+            .resolve_permission(request_id, odyssey_rs_protocol::ApprovalDecision::AllowOnce)
+            .await
+            .expect_err("server error");
+        assert_eq!(err.to_string(), "server error: unknown approval");
+
+        let err = client
+            .create_session(None)
+            .await
+            .expect_err("closed before reply");
+        assert_eq!(matches!(err, ClientError::Closed), true);
+    }
+}
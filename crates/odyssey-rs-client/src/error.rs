@@ -0,0 +1,26 @@
+//! Error types for the Odyssey client.
+
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Errors returned by `OdysseyClient`.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// Transport failed to send or receive a frame.
+    #[error("transport error: {0}")]
+    Transport(String),
+    /// Frame could not be encoded or decoded.
+    #[error("invalid frame: {0}")]
+    Frame(#[from] serde_json::Error),
+    /// Server rejected a request.
+    #[error("server error: {message}")]
+    Server {
+        /// Request the error answers, if any.
+        request_id: Option<Uuid>,
+        /// Error message from the server.
+        message: String,
+    },
+    /// Transport closed before the expected reply arrived.
+    #[error("connection closed")]
+    Closed,
+}
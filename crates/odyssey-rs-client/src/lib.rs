@@ -0,0 +1,14 @@
+//! Lightweight Odyssey client for browsers and other non-embedding frontends.
+//!
+//! The crate depends only on the protocol types and serde, so it compiles to
+//! `wasm32-unknown-unknown`. Frames are exchanged as JSON text over any
+//! [`Transport`], e.g. a browser `WebSocket` or a native WebSocket/HTTP client.
+
+mod client;
+mod error;
+mod wire;
+
+pub use client::{OdysseyClient, Transport};
+pub use error::ClientError;
+pub use odyssey_rs_protocol as protocol;
+pub use wire::{ClientFrame, ServerFrame};
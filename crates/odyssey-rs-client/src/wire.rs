@@ -0,0 +1,86 @@
+//! JSON frames exchanged between a client and the Odyssey server.
+
+use odyssey_rs_protocol::{ApprovalDecision, EventMsg, SessionId, SubmissionEnvelope};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Frame sent from the client to the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "payload")]
+pub enum ClientFrame {
+    /// Create a session, optionally for a specific agent and owned by a user.
+    CreateSession {
+        request_id: Uuid,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        user_id: Option<String>,
+    },
+    /// Enqueue a submission: a user message, a context override, or a cancel.
+    Submit(SubmissionEnvelope),
+    /// Start receiving events for a session.
+    Subscribe { session_id: SessionId },
+    /// Stop receiving events for a session.
+    Unsubscribe { session_id: SessionId },
+    /// Answer a pending permission request.
+    ResolvePermission {
+        request_id: Uuid,
+        decision: ApprovalDecision,
+    },
+}
+
+/// Frame sent from the server to the client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "payload")]
+pub enum ServerFrame {
+    /// Event from a subscribed session.
+    Event(EventMsg),
+    /// Session created for a `CreateSession` request.
+    SessionCreated {
+        request_id: Uuid,
+        session_id: SessionId,
+    },
+    /// Result of a `ResolvePermission` request; false when it was no longer pending.
+    PermissionResolved { request_id: Uuid, resolved: bool },
+    /// Request failed; `request_id` is unset for errors not tied to a request.
+    Error {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<Uuid>,
+        message: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClientFrame, ServerFrame};
+    use odyssey_rs_protocol::ApprovalDecision;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn frames_use_tagged_snake_case_json() {
+        let request_id = Uuid::nil();
+        let frame = ClientFrame::ResolvePermission {
+            request_id,
+            decision: ApprovalDecision::AllowOnce,
+        };
+        assert_eq!(
+            serde_json::to_value(&frame).expect("encode"),
+            json!({
+                "type": "resolve_permission",
+                "payload": { "request_id": request_id, "decision": "allow_once" }
+            })
+        );
+
+        let frame: ServerFrame = serde_json::from_value(json!({
+            "type": "error",
+            "payload": { "message": "unknown session" }
+        }))
+        .expect("decode");
+        assert_eq!(
+            matches!(frame, ServerFrame::Error { request_id: None, message } if message == "unknown session"),
+            true
+        );
+    }
+}
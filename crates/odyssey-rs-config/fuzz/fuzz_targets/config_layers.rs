@@ -0,0 +1,39 @@
+//! Fuzz layered config loading with arbitrary requirements and runtime layers.
+//!
+//! Input is split at the first NUL byte: the requirements layer comes first,
+//! the runtime layer second. Loading must never panic, and schema errors must
+//! name the field they reject.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use odyssey_rs_config::{ConfigError, LayeredConfigOptions, OdysseyConfig};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let (requirements, runtime) = input.split_once('\0').unwrap_or(("{}", input));
+    let Ok(dir) = tempfile::tempdir() else {
+        return;
+    };
+    let requirements_path = dir.path().join("requirements.json5");
+    let runtime_path = dir.path().join("runtime.json5");
+    if std::fs::write(&requirements_path, requirements).is_err()
+        || std::fs::write(&runtime_path, runtime).is_err()
+    {
+        return;
+    }
+
+    let mut options = LayeredConfigOptions::new(dir.path()).with_runtime_path(&runtime_path);
+    options.system_config_path = None;
+    options.user_config_path = None;
+    options.requirements_path = Some(requirements_path);
+    if let Err(ConfigError::InvalidField { path, message }) =
+        OdysseyConfig::load_layered_with_options(options)
+    {
+        let field = path.rsplit_once(':').map(|(_, field)| field);
+        assert!(field.is_some_and(|field| !field.is_empty()), "{path}");
+        assert!(!message.is_empty());
+    }
+});
@@ -0,0 +1,38 @@
+//! JSON Schema generation for Odyssey config files.
+//!
+//! The schema is derived from the Rust config model so editors can offer
+//! completion and inline docs for `odyssey.json5` through its `$schema` key.
+
+use crate::OdysseyConfig;
+use serde_json::{Value, json};
+
+impl OdysseyConfig {
+    /// JSON Schema describing a config file, derived from the Rust model.
+    pub fn json_schema() -> Value {
+        let mut schema = schemars::schema_for!(OdysseyConfig).to_value();
+        if let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) {
+            properties.insert(
+                "$schema".to_string(),
+                json!({
+                    "description": "JSON Schema used by editors to validate this file.",
+                    "type": "string",
+                }),
+            );
+        }
+        // Keys consumed by the loader before the model is deserialized.
+        if let Some(properties) = schema
+            .pointer_mut("/$defs/PermissionsConfig/properties")
+            .and_then(Value::as_object_mut)
+        {
+            properties.insert(
+                "include".to_string(),
+                json!({
+                    "description": "Policy bundle files whose rules come before this layer's rules.",
+                    "type": "array",
+                    "items": { "type": "string" },
+                }),
+            );
+        }
+        schema
+    }
+}
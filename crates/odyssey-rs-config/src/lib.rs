@@ -4,12 +4,17 @@
 //! logic used by both the server and SDK.
 
 mod error;
+mod json_schema;
 mod loader;
 mod model;
 
 /// Public error type returned by config loading and validation APIs.
 pub use error::ConfigError;
+/// Config diagnostics for layered configs.
+pub use loader::{ConfigDoctor, DoctorFinding, DoctorReport, FindingSeverity, ValueOrigin};
 /// Layered config types and loader options.
-pub use loader::{ConfigLayer, ConfigLayerSource, LayeredConfig, LayeredConfigOptions};
+pub use loader::{
+    ConfigLayer, ConfigLayerSource, ConfigOrigin, LayeredConfig, LayeredConfigOptions,
+};
 /// Configuration schema models.
 pub use model::*;
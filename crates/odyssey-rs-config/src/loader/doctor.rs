@@ -0,0 +1,449 @@
+//! Config diagnostics for a workspace's layered config.
+//!
+//! Unlike the loader, the doctor does not stop at the first problem: it checks
+//! every layer, reports where each effective value came from, and flags
+//! unknown keys and permission rules that conflict with each other.
+
+use super::merge::{self, Provenance};
+use super::{
+    ConfigLayer, ConfigLayerSource, ConfigOrigin, LayeredConfigOptions, SchemaMode,
+    config_from_value, discover_layers, layer_io, policy, schema,
+};
+use crate::{ConfigError, OdysseyConfig, PermissionAction, PermissionRule};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Upper bound on unknown keys reported for a single layer.
+const MAX_UNKNOWN_KEYS_PER_LAYER: usize = 64;
+
+/// Validates a layered config and explains the effective values.
+#[derive(Debug, Clone)]
+pub struct ConfigDoctor {
+    target: DoctorTarget,
+}
+
+#[derive(Debug, Clone)]
+enum DoctorTarget {
+    Layered(LayeredConfigOptions),
+    File(PathBuf),
+}
+
+/// Severity of a doctor finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindingSeverity {
+    /// The config fails to load.
+    Error,
+    /// The config loads but likely does not do what was intended.
+    Warning,
+}
+
+/// Problem found while checking a config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoctorFinding {
+    pub severity: FindingSeverity,
+    /// Layer the problem is in; `None` for problems in the effective config.
+    pub source: Option<ConfigLayerSource>,
+    pub file: Option<PathBuf>,
+    /// Dotted field path, empty for the whole layer.
+    pub path: String,
+    pub message: String,
+}
+
+/// Layer that set an effective config value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueOrigin {
+    /// Dotted field path; arrays are reported as a whole.
+    pub path: String,
+    pub value: Value,
+    pub source: ConfigLayerSource,
+    pub file: Option<PathBuf>,
+}
+
+/// Result of a doctor run.
+#[derive(Debug, Clone, Default)]
+pub struct DoctorReport {
+    /// Layers considered, in precedence order.
+    pub layers: Vec<ConfigLayer>,
+    /// Values set by config files, sorted by path; defaults are omitted.
+    pub origins: Vec<ValueOrigin>,
+    pub findings: Vec<DoctorFinding>,
+    /// Effective config, when it loads.
+    pub config: Option<OdysseyConfig>,
+}
+
+impl ConfigDoctor {
+    /// Check the layered config described by `options`.
+    pub fn new(options: LayeredConfigOptions) -> Self {
+        Self {
+            target: DoctorTarget::Layered(options),
+        }
+    }
+
+    /// Check a single config file, as loaded by `OdysseyConfig::load_from_path`.
+    pub fn for_file(path: impl AsRef<Path>) -> Self {
+        Self {
+            target: DoctorTarget::File(path.as_ref().to_path_buf()),
+        }
+    }
+
+    /// Run every check and collect the findings.
+    pub fn run(&self) -> DoctorReport {
+        let mut report = DoctorReport::default();
+        let layers = match &self.target {
+            DoctorTarget::Layered(options) => match discover_layers(options) {
+                Ok(layers) => layers,
+                Err(err) => {
+                    report
+                        .findings
+                        .push(DoctorFinding::error(None, "", err.to_string()));
+                    return report;
+                }
+            },
+            DoctorTarget::File(path) => vec![ConfigLayer {
+                source: ConfigLayerSource::Runtime,
+                path: Some(path.clone()),
+                disabled_reason: None,
+            }],
+        };
+
+        let mut loaded = Vec::new();
+        for layer in layers {
+            if layer.disabled_reason.is_none()
+                && let Some(value) = check_layer(&layer, &mut report.findings)
+            {
+                loaded.push((layer.clone(), value));
+            }
+            report.layers.push(layer);
+        }
+
+        let requirements = loaded
+            .iter()
+            .find(|(layer, _)| layer.source == ConfigLayerSource::Requirements)
+            .map(|(_, value)| value.clone());
+        let mut merged = Value::Object(Map::new());
+        let mut provenance = Provenance::new();
+        for (layer, value) in &loaded {
+            let origin = ConfigOrigin::from(layer);
+            let constraints = match layer.source {
+                ConfigLayerSource::Requirements => None,
+                _ => requirements.as_ref(),
+            };
+            merge::merge_layer(&mut merged, value, constraints, &mut provenance, &origin);
+        }
+        report.origins = provenance
+            .into_iter()
+            .map(|(path, origin)| ValueOrigin {
+                value: value_at(&merged, &path).cloned().unwrap_or(Value::Null),
+                path,
+                source: origin.source,
+                file: origin.file,
+            })
+            .collect();
+
+        match config_from_value(merged, "effective") {
+            Ok(config) => {
+                report
+                    .findings
+                    .extend(rule_findings(&config.permissions.rules));
+                report.config = Some(config);
+            }
+            Err(err) => report
+                .findings
+                .push(DoctorFinding::error(None, "", err.to_string())),
+        }
+        report
+    }
+}
+
+impl DoctorReport {
+    /// Return true when any finding would stop the config from loading.
+    pub fn has_errors(&self) -> bool {
+        self.findings
+            .iter()
+            .any(|finding| finding.severity == FindingSeverity::Error)
+    }
+
+    /// Origin of an effective value by dotted path, e.g. `permissions.mode`.
+    pub fn origin(&self, path: &str) -> Option<&ValueOrigin> {
+        self.origins.iter().find(|origin| origin.path == path)
+    }
+}
+
+impl DoctorFinding {
+    fn error(layer: Option<&ConfigLayer>, path: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: FindingSeverity::Error,
+            source: layer.map(|layer| layer.source),
+            file: layer.and_then(|layer| layer.path.clone()),
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(path: String, message: String) -> Self {
+        Self {
+            severity: FindingSeverity::Warning,
+            source: None,
+            file: None,
+            path,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for DoctorFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            FindingSeverity::Error => "error",
+            FindingSeverity::Warning => "warning",
+        };
+        write!(f, "{severity}:")?;
+        if let Some(source) = self.source {
+            write!(f, " {}", source.as_str())?;
+        }
+        if let Some(file) = &self.file {
+            write!(f, " {}", file.display())?;
+        }
+        if !self.path.is_empty() {
+            write!(f, " {}", self.path)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Layers:")?;
+        if self.layers.is_empty() {
+            writeln!(f, "  (none; built-in defaults only)")?;
+        }
+        for layer in &self.layers {
+            let path = layer
+                .path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default();
+            match &layer.disabled_reason {
+                Some(reason) => writeln!(
+                    f,
+                    "  {:<12} {path} (disabled: {reason})",
+                    layer.source.as_str()
+                )?,
+                None => writeln!(f, "  {:<12} {path}", layer.source.as_str())?,
+            }
+        }
+        if !self.origins.is_empty() {
+            writeln!(f, "Values:")?;
+            for origin in &self.origins {
+                writeln!(
+                    f,
+                    "  {} = {}  [{}]",
+                    origin.path,
+                    origin.value,
+                    origin.source.as_str()
+                )?;
+            }
+        }
+        if self.findings.is_empty() {
+            return writeln!(f, "No problems found.");
+        }
+        writeln!(f, "Findings:")?;
+        for finding in &self.findings {
+            writeln!(f, "  {finding}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse and validate one layer, returning its value with unknown keys removed.
+///
+/// Unknown keys are reported and dropped so the rest of the layer can still be
+/// checked; any other problem excludes the layer from the effective config.
+fn check_layer(layer: &ConfigLayer, findings: &mut Vec<DoctorFinding>) -> Option<Value> {
+    let path = layer.path.as_deref()?;
+    let label = layer_io::layer_label(layer.source, path);
+    let mut value: Value = match fs::read_to_string(path)
+        .map_err(ConfigError::from)
+        .and_then(|contents| json5::from_str(&contents).map_err(ConfigError::from))
+    {
+        Ok(value) => value,
+        Err(err) => {
+            findings.push(DoctorFinding::error(Some(layer), "", err.to_string()));
+            return None;
+        }
+    };
+
+    let prefix = format!("{label}:");
+    for _ in 0..MAX_UNKNOWN_KEYS_PER_LAYER {
+        match schema::validate_layer_schema(&value, SchemaMode::Partial, &label) {
+            Ok(()) => break,
+            Err(ConfigError::InvalidField {
+                path: field,
+                message,
+            }) => {
+                let field = match field.strip_prefix(&prefix).unwrap_or(&field) {
+                    "root" => "",
+                    field => field,
+                };
+                findings.push(DoctorFinding::error(Some(layer), field, message.clone()));
+                if message != "unknown key" || !remove_at(&mut value, field) {
+                    return None;
+                }
+            }
+            Err(err) => {
+                findings.push(DoctorFinding::error(Some(layer), "", err.to_string()));
+                return None;
+            }
+        }
+    }
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    if let Err(err) =
+        policy::resolve_permission_includes(&mut value, Some(base_dir), Some(path), &label)
+    {
+        findings.push(DoctorFinding::error(Some(layer), "", err.to_string()));
+        return None;
+    }
+    Some(value)
+}
+
+/// Split a validator path like `permissions.rules[0].tool` into keys and indices.
+fn path_segments(path: &str) -> Vec<PathSegment<'_>> {
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (key, indices) = part.split_once('[').unwrap_or((part, ""));
+        segments.push(PathSegment::Key(key));
+        for index in indices.split(['[', ']']).filter(|index| !index.is_empty()) {
+            if let Ok(index) = index.parse() {
+                segments.push(PathSegment::Index(index));
+            }
+        }
+    }
+    segments
+}
+
+enum PathSegment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Value at a dotted path in the merged config.
+fn value_at<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path_segments(path)
+        .into_iter()
+        .try_fold(value, |value, segment| match segment {
+            PathSegment::Key(key) => value.get(key),
+            PathSegment::Index(index) => value.get(index),
+        })
+}
+
+/// Remove the object key at a validator path; returns false if it is not a key.
+fn remove_at(value: &mut Value, path: &str) -> bool {
+    let mut segments = path_segments(path);
+    let Some(PathSegment::Key(last)) = segments.pop() else {
+        return false;
+    };
+    let parent = segments
+        .into_iter()
+        .try_fold(value, |value, segment| match segment {
+            PathSegment::Key(key) => value.get_mut(key),
+            PathSegment::Index(index) => value.get_mut(index),
+        });
+    parent
+        .and_then(Value::as_object_mut)
+        .and_then(|map| map.remove(last))
+        .is_some()
+}
+
+/// Warnings for permission rules that duplicate or contradict each other,
+/// and errors for path globs the permission engine cannot compile.
+fn rule_findings(rules: &[PermissionRule]) -> Vec<DoctorFinding> {
+    let mut findings = Vec::new();
+    for (idx, rule) in rules.iter().enumerate() {
+        if let Some(pattern) = &rule.path
+            && let Err(err) = globset::Glob::new(pattern)
+        {
+            findings.push(DoctorFinding {
+                severity: FindingSeverity::Error,
+                source: None,
+                file: rule.source.as_ref().map(PathBuf::from),
+                path: format!("permissions.rules[{idx}].path"),
+                message: format!("invalid glob: {err}"),
+            });
+        }
+        for (other_idx, other) in rules.iter().enumerate().skip(idx + 1) {
+            if !same_target(rule, other) {
+                continue;
+            }
+            let path = format!("permissions.rules[{other_idx}]");
+            let message = if rule.action == other.action {
+                format!("duplicates rules[{idx}] ({})", describe_rule(rule))
+            } else {
+                let winner = if action_rank(rule.action) <= action_rank(other.action) {
+                    rule.action
+                } else {
+                    other.action
+                };
+                format!(
+                    "conflicts with rules[{idx}] ({}); {} wins",
+                    describe_rule(rule),
+                    action_name(winner)
+                )
+            };
+            findings.push(DoctorFinding::warning(path, message));
+        }
+    }
+    findings
+}
+
+/// Return true when two rules match exactly the same requests.
+fn same_target(left: &PermissionRule, right: &PermissionRule) -> bool {
+    left.tool == right.tool
+        && left.path == right.path
+        && left.command == right.command
+        && left.access == right.access
+        && left.git_push == right.git_push
+}
+
+/// Evaluation order of rule actions; lower wins.
+fn action_rank(action: PermissionAction) -> u8 {
+    match action {
+        PermissionAction::Deny => 0,
+        PermissionAction::Allow => 1,
+        PermissionAction::Ask => 2,
+    }
+}
+
+fn action_name(action: PermissionAction) -> &'static str {
+    match action {
+        PermissionAction::Deny => "deny",
+        PermissionAction::Allow => "allow",
+        PermissionAction::Ask => "ask",
+    }
+}
+
+/// Short rule description, e.g. `allow tool=Bash from policies/ci.json5`.
+fn describe_rule(rule: &PermissionRule) -> String {
+    let mut parts = vec![action_name(rule.action).to_string()];
+    if let Some(tool) = &rule.tool {
+        parts.push(format!("tool={tool}"));
+    }
+    if let Some(path) = &rule.path {
+        parts.push(format!("path={path}"));
+    }
+    if let Some(command) = &rule.command {
+        parts.push(format!("command={}", command.join(" ")));
+    }
+    if let Some(access) = rule.access {
+        parts.push(format!("access={access:?}").to_ascii_lowercase());
+    }
+    if let Some(git_push) = rule.git_push {
+        parts.push(format!("git_push={git_push:?}").to_ascii_lowercase());
+    }
+    if let Some(source) = &rule.source {
+        parts.push(format!("from {source}"));
+    }
+    parts.join(" ")
+}
@@ -2,7 +2,7 @@
 
 use super::{
     ConfigLayer, ConfigLayerSource, DEFAULT_CONFIG_DIR, DEFAULT_CONFIG_FILE, LoadedLayer,
-    SchemaMode, schema,
+    SchemaMode, policy, schema,
 };
 #[cfg(any(unix, windows))]
 use super::{SYSTEM_CONFIG_PATH, SYSTEM_REQUIREMENTS_PATH};
@@ -13,28 +13,6 @@ use serde_json::Value;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-/// Load an optional layer if the provided path exists.
-pub(super) fn load_optional_layer(
-    source: ConfigLayerSource,
-    path: Option<&Path>,
-) -> Result<Option<LoadedLayer>, ConfigError> {
-    let path = match path {
-        Some(path) => path,
-        None => return Ok(None),
-    };
-
-    if !path.exists() {
-        debug!(
-            "optional layer missing (source={:?}, path={})",
-            source,
-            path.display()
-        );
-        return Ok(None);
-    }
-
-    Ok(Some(load_required_layer(source, path)?))
-}
-
 /// Load and validate a required layer from disk.
 pub(super) fn load_required_layer(
     source: ConfigLayerSource,
@@ -46,9 +24,11 @@ pub(super) fn load_required_layer(
         path.display()
     );
     let contents = fs::read_to_string(path)?;
-    let value: Value = json5::from_str(&contents)?;
+    let mut value: Value = json5::from_str(&contents)?;
     let label = layer_label(source, path);
     schema::validate_layer_schema(&value, SchemaMode::Partial, &label)?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    policy::resolve_permission_includes(&mut value, Some(base_dir), Some(path), &label)?;
     Ok(LoadedLayer {
         meta: ConfigLayer {
             source,
@@ -61,16 +41,7 @@ pub(super) fn load_required_layer(
 
 /// Build a user-friendly label for schema validation errors.
 pub(super) fn layer_label(source: ConfigLayerSource, path: &Path) -> String {
-    let name = match source {
-        ConfigLayerSource::Requirements => "requirements",
-        ConfigLayerSource::System => "system",
-        ConfigLayerSource::User => "user",
-        ConfigLayerSource::Project => "project",
-        ConfigLayerSource::Cwd => "cwd",
-        ConfigLayerSource::Repo => "repo",
-        ConfigLayerSource::Runtime => "runtime",
-    };
-    format!("{name}({})", path.display())
+    format!("{}({})", source.as_str(), path.display())
 }
 
 /// Default system config path on Unix; None elsewhere.
@@ -1,6 +1,11 @@
 //! JSON merge helpers for layered configuration.
 
+use super::ConfigOrigin;
 use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Dotted field paths mapped to the layer that set them; arrays are tracked whole.
+pub(super) type Provenance = BTreeMap<String, ConfigOrigin>;
 
 /// Merge overlay values into the base, recursively overriding objects.
 pub(super) fn merge_json_values(base: &mut Value, overlay: &Value) {
@@ -63,3 +68,71 @@ pub(super) fn merge_json_with_constraints(
         }
     }
 }
+
+/// Merge a layer into `base` and record the origin of every value it sets.
+pub(super) fn merge_layer(
+    base: &mut Value,
+    overlay: &Value,
+    constraints: Option<&Value>,
+    provenance: &mut Provenance,
+    origin: &ConfigOrigin,
+) {
+    merge_json_with_constraints(base, overlay, constraints);
+    record_origins(provenance, "", overlay, constraints, origin);
+}
+
+/// Origin of a field, or of the nearest enclosing array or value that set it.
+pub(super) fn lookup_origin<'a>(
+    provenance: &'a Provenance,
+    path: &str,
+) -> Option<&'a ConfigOrigin> {
+    let mut path = path;
+    loop {
+        if let Some(origin) = provenance.get(path) {
+            return Some(origin);
+        }
+        path = &path[..path.rfind(['.', '['])?];
+    }
+}
+
+/// Record the layer that sets each leaf of `value`, following the merge rules.
+///
+/// Requirement constraints lock keys the same way [`merge_json_with_constraints`]
+/// does, and a value replacing an object drops the origins recorded beneath it.
+fn record_origins(
+    provenance: &mut Provenance,
+    path: &str,
+    value: &Value,
+    constraints: Option<&Value>,
+    origin: &ConfigOrigin,
+) {
+    if let Value::Object(map) = value {
+        let constraint_map = match constraints {
+            Some(Value::Object(map)) => Some(map),
+            Some(_) => return,
+            None => None,
+        };
+        provenance.remove(path);
+        for (key, child) in map {
+            let child_path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            match constraint_map.and_then(|map| map.get(key)) {
+                None => record_origins(provenance, &child_path, child, None, origin),
+                Some(constraint @ Value::Object(_)) => {
+                    record_origins(provenance, &child_path, child, Some(constraint), origin);
+                }
+                Some(_) => {}
+            }
+        }
+        return;
+    }
+    if constraints.is_some() {
+        return;
+    }
+    let nested = format!("{path}.");
+    provenance.retain(|key, _| !key.starts_with(&nested));
+    provenance.insert(path.to_string(), origin.clone());
+}
@@ -3,18 +3,25 @@
 //! Discovers configuration layers (system/user/project/etc), validates schema,
 //! merges them with optional constraints, and produces a final `OdysseyConfig`.
 
+mod doctor;
 mod layer_io;
 mod merge;
+mod policy;
 mod schema;
 mod utils;
 
+#[cfg(test)]
+mod proptests;
 #[cfg(test)]
 mod tests;
 
+pub use doctor::{ConfigDoctor, DoctorFinding, DoctorReport, FindingSeverity, ValueOrigin};
+
 use crate::{ConfigError, OdysseyConfig};
 use log::{debug, info, warn};
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -45,6 +52,19 @@ pub struct LayeredConfig {
     pub config: OdysseyConfig,
     /// Metadata for each layer considered during load.
     pub layers: Vec<ConfigLayer>,
+    /// Layer that set each field, keyed by dotted path; defaults are omitted.
+    pub origins: BTreeMap<String, ConfigOrigin>,
+}
+
+impl LayeredConfig {
+    /// Layer that set a field, e.g. `permissions.mode`.
+    ///
+    /// Arrays are tracked as a whole, so `permissions.rules[0]` reports the
+    /// layer that set `permissions.rules`. Returns `None` for fields left at
+    /// their defaults.
+    pub fn origin_of(&self, path: &str) -> Option<&ConfigOrigin> {
+        merge::lookup_origin(&self.origins, path)
+    }
 }
 
 /// Origin for a single config layer in the stack.
@@ -66,6 +86,21 @@ pub enum ConfigLayerSource {
     Runtime,
 }
 
+impl ConfigLayerSource {
+    /// Short lowercase layer name used in messages.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConfigLayerSource::Requirements => "requirements",
+            ConfigLayerSource::System => "system",
+            ConfigLayerSource::User => "user",
+            ConfigLayerSource::Project => "project",
+            ConfigLayerSource::Cwd => "cwd",
+            ConfigLayerSource::Repo => "repo",
+            ConfigLayerSource::Runtime => "runtime",
+        }
+    }
+}
+
 /// Metadata about a config layer, including disabled reason when untrusted.
 #[derive(Debug, Clone)]
 pub struct ConfigLayer {
@@ -77,6 +112,33 @@ pub struct ConfigLayer {
     pub disabled_reason: Option<String>,
 }
 
+/// Layer that set an effective config value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigOrigin {
+    /// Layer origin (system, user, runtime, etc).
+    pub source: ConfigLayerSource,
+    /// Layer file, if loaded from disk.
+    pub file: Option<PathBuf>,
+}
+
+impl From<&ConfigLayer> for ConfigOrigin {
+    fn from(layer: &ConfigLayer) -> Self {
+        Self {
+            source: layer.source,
+            file: layer.path.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{} layer ({})", self.source.as_str(), file.display()),
+            None => write!(f, "{} layer", self.source.as_str()),
+        }
+    }
+}
+
 /// Schema validation mode for layered configs.
 #[derive(Debug, Clone, Copy)]
 enum SchemaMode {
@@ -130,16 +192,39 @@ impl LayeredConfigOptions {
 impl OdysseyConfig {
     /// Load a single config from a path (no layering).
     pub fn load_from_path(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
-        info!("loading config from path: {}", path.as_ref().display());
+        let path = path.as_ref();
+        info!("loading config from path: {}", path.display());
         let contents = fs::read_to_string(path)?;
-        let value: Value = json5::from_str(&contents)?;
+        let mut value: Value = json5::from_str(&contents)?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        policy::resolve_permission_includes(&mut value, Some(base_dir), Some(path), "config")?;
         config_from_value(value, "config")
     }
 
     /// Load a single config from JSON5 contents (no layering).
+    ///
+    /// `permissions.include` is rejected since there is no directory to resolve
+    /// bundles against; use `load_from_str_in` for configs with includes.
     pub fn load_from_str(contents: &str) -> Result<Self, ConfigError> {
         debug!("loading config from raw contents (len={})", contents.len());
-        let value: Value = json5::from_str(contents)?;
+        let mut value: Value = json5::from_str(contents)?;
+        policy::resolve_permission_includes(&mut value, None, None, "config")?;
+        config_from_value(value, "config")
+    }
+
+    /// Load a single config from JSON5 contents, resolving `permissions.include`
+    /// relative to `base_dir`.
+    pub fn load_from_str_in(
+        contents: &str,
+        base_dir: impl AsRef<Path>,
+    ) -> Result<Self, ConfigError> {
+        debug!(
+            "loading config from raw contents (len={}, base_dir={})",
+            contents.len(),
+            base_dir.as_ref().display()
+        );
+        let mut value: Value = json5::from_str(contents)?;
+        policy::resolve_permission_includes(&mut value, Some(base_dir.as_ref()), None, "config")?;
         config_from_value(value, "config")
     }
 
@@ -160,108 +245,100 @@ impl OdysseyConfig {
     pub fn load_layered_with_options(
         options: LayeredConfigOptions,
     ) -> Result<LayeredConfig, ConfigError> {
-        let cwd = utils::normalize_path(&options.cwd)?;
-        debug!("normalized cwd for config load: {}", cwd.display());
         let mut layers = Vec::new();
         let mut merge_layers = Vec::new();
-        let mut seen_paths = HashSet::new();
-
-        let requirements = layer_io::load_optional_layer(
-            ConfigLayerSource::Requirements,
-            options.requirements_path.as_deref(),
-        )?;
-        let requirements_value = requirements.as_ref().map(|layer| layer.value.clone());
-        if let Some(layer) = requirements {
-            debug!("loaded requirements layer");
-            layers.push(layer.meta);
-        }
-
-        for (source, path) in [
-            (
-                ConfigLayerSource::System,
-                options.system_config_path.as_deref(),
-            ),
-            (ConfigLayerSource::User, options.user_config_path.as_deref()),
-        ] {
-            if let Some(layer) = layer_io::load_optional_layer(source, path)? {
-                debug!("loaded {:?} layer", source);
-                layers.push(layer.meta.clone());
-                merge_layers.push(layer);
+        let mut requirements = None;
+        for layer in discover_layers(&options)? {
+            let Some(path) = layer.path.as_deref() else {
+                continue;
+            };
+            if let Some(disabled_reason) = layer.disabled_reason.as_deref() {
+                warn!(
+                    "layer disabled (source={:?}, path={}, reason={})",
+                    layer.source,
+                    path.display(),
+                    disabled_reason
+                );
+                layers.push(layer);
+                continue;
+            }
+            let loaded = layer_io::load_required_layer(layer.source, path)?;
+            debug!(
+                "loaded layer (source={:?}, path={})",
+                layer.source,
+                path.display()
+            );
+            let origin = ConfigOrigin::from(&loaded.meta);
+            layers.push(loaded.meta);
+            if layer.source == ConfigLayerSource::Requirements {
+                requirements = Some((origin, loaded.value));
+            } else {
+                merge_layers.push((origin, loaded.value));
             }
-        }
-
-        let project_root = utils::find_project_root(&cwd, &options.project_root_markers);
-        let repo_root = project_root.clone();
-        let local_disabled_reason = None;
-        if let Some(project_root) = project_root.as_ref() {
-            debug!("resolved project root: {}", project_root.display());
-        } else {
-            debug!("project root not found; skipping project/repo layers");
-        }
-
-        let mut local_layers = Vec::new();
-        if let Some(project_root) = project_root.as_ref() {
-            let path = project_root.join(DEFAULT_CONFIG_FILE);
-            local_layers.push(LocalLayer {
-                source: ConfigLayerSource::Project,
-                path,
-                disabled_reason: local_disabled_reason.clone(),
-            });
-        }
-
-        let cwd_layer = LocalLayer {
-            source: ConfigLayerSource::Cwd,
-            path: cwd.join(DEFAULT_CONFIG_FILE),
-            disabled_reason: local_disabled_reason.clone(),
-        };
-        local_layers.push(cwd_layer);
-
-        if let Some(repo_root) = repo_root.as_ref() {
-            let path = repo_root.join(DEFAULT_CONFIG_DIR).join(DEFAULT_CONFIG_FILE);
-            local_layers.push(LocalLayer {
-                source: ConfigLayerSource::Repo,
-                path,
-                disabled_reason: local_disabled_reason.clone(),
-            });
-        }
-
-        for layer in local_layers {
-            load_local_layer(layer, &mut layers, &mut merge_layers, &mut seen_paths)?;
-        }
-
-        for runtime_path in &options.runtime_paths {
-            let loaded = layer_io::load_required_layer(ConfigLayerSource::Runtime, runtime_path)?;
-            debug!("loaded runtime layer (path={})", runtime_path.display());
-            layers.push(loaded.meta.clone());
-            merge_layers.push(loaded);
         }
 
         let mut merged = Value::Object(serde_json::Map::new());
-        if let Some(requirements_value) = &requirements_value {
-            merge::merge_json_values(&mut merged, requirements_value);
+        let mut origins = BTreeMap::new();
+        if let Some((origin, requirements_value)) = &requirements {
+            merge::merge_layer(&mut merged, requirements_value, None, &mut origins, origin);
         }
 
-        for layer in merge_layers {
-            merge::merge_json_with_constraints(
-                &mut merged,
-                &layer.value,
-                requirements_value.as_ref(),
-            );
+        let requirements_value = requirements.as_ref().map(|(_, value)| value);
+        for (origin, value) in &merge_layers {
+            merge::merge_layer(&mut merged, value, requirements_value, &mut origins, origin);
         }
 
         let config = config_from_value(merged, "effective")?;
         info!("layered config loaded (layers={})", layers.len());
-        Ok(LayeredConfig { config, layers })
+        Ok(LayeredConfig {
+            config,
+            layers,
+            origins,
+        })
     }
 
     /// Validate configuration invariants that cannot be expressed in serde.
     pub fn validate(&self) -> Result<(), ConfigError> {
         for rule in &self.permissions.rules {
-            if rule.tool.is_none() && rule.path.is_none() && rule.command.is_none() {
+            let has_target = rule.tool.is_some() || rule.path.is_some() || rule.command.is_some();
+            if rule.git_push.is_some() && (has_target || rule.access.is_some()) {
+                return Err(ConfigError::Invalid(
+                    "git_push permission rules cannot set tool, path, command, or access"
+                        .to_string(),
+                ));
+            }
+            if !has_target && rule.git_push.is_none() {
+                return Err(ConfigError::Invalid(
+                    "permission rules require tool, path, command, or git_push".to_string(),
+                ));
+            }
+        }
+
+        let mut job_ids = HashSet::new();
+        for job in &self.schedules.jobs {
+            if job.id.trim().is_empty() {
                 return Err(ConfigError::Invalid(
-                    "permission rules require tool, path, or command".to_string(),
+                    "scheduled jobs require a non-empty id".to_string(),
                 ));
             }
+            if !job_ids.insert(job.id.as_str()) {
+                return Err(ConfigError::Invalid(format!(
+                    "duplicate scheduled job id: {}",
+                    job.id
+                )));
+            }
+            if job.prompt.trim().is_empty() {
+                return Err(ConfigError::Invalid(format!(
+                    "scheduled job {} requires a non-empty prompt",
+                    job.id
+                )));
+            }
+            if let Err(err) = croner::Cron::new(&job.cron).parse() {
+                return Err(ConfigError::Invalid(format!(
+                    "scheduled job {} has an invalid cron expression: {err}",
+                    job.id
+                )));
+            }
         }
 
         Ok(())
@@ -275,14 +352,6 @@ struct LoadedLayer {
     value: Value,
 }
 
-/// Internal representation for layer candidates on disk.
-#[derive(Debug, Clone)]
-struct LocalLayer {
-    source: ConfigLayerSource,
-    path: PathBuf,
-    disabled_reason: Option<String>,
-}
-
 fn config_from_value(value: Value, label: &str) -> Result<OdysseyConfig, ConfigError> {
     schema::validate_layer_schema(&value, SchemaMode::Full, label)?;
     let config: OdysseyConfig = serde_json::from_value(value)?;
@@ -290,50 +359,96 @@ fn config_from_value(value: Value, label: &str) -> Result<OdysseyConfig, ConfigE
     Ok(config)
 }
 
-fn load_local_layer(
-    layer: LocalLayer,
-    layers: &mut Vec<ConfigLayer>,
-    merge_layers: &mut Vec<LoadedLayer>,
-    seen_paths: &mut HashSet<PathBuf>,
-) -> Result<(), ConfigError> {
-    if !layer.path.exists() {
-        debug!(
-            "skipping missing layer (source={:?}, path={})",
-            layer.source,
-            layer.path.display()
-        );
-        return Ok(());
+/// Config layer files in precedence order, requirements first.
+///
+/// Missing optional layers are skipped, and a local file reachable through
+/// several local layers is listed once. Runtime layers are always listed so
+/// a missing runtime file fails the load.
+fn discover_layers(options: &LayeredConfigOptions) -> Result<Vec<ConfigLayer>, ConfigError> {
+    let cwd = utils::normalize_path(&options.cwd)?;
+    debug!("normalized cwd for config load: {}", cwd.display());
+    let mut layers = Vec::new();
+
+    for (source, path) in [
+        (
+            ConfigLayerSource::Requirements,
+            options.requirements_path.as_deref(),
+        ),
+        (
+            ConfigLayerSource::System,
+            options.system_config_path.as_deref(),
+        ),
+        (ConfigLayerSource::User, options.user_config_path.as_deref()),
+    ] {
+        let Some(path) = path else {
+            continue;
+        };
+        if !path.exists() {
+            debug!(
+                "optional layer missing (source={:?}, path={})",
+                source,
+                path.display()
+            );
+            continue;
+        }
+        layers.push(ConfigLayer {
+            source,
+            path: Some(path.to_path_buf()),
+            disabled_reason: None,
+        });
     }
-    let unique = utils::unique_path(&layer.path);
-    if !seen_paths.insert(unique) {
-        debug!(
-            "skipping duplicate layer (source={:?}, path={})",
-            layer.source,
-            layer.path.display()
-        );
-        return Ok(());
+
+    let project_root = utils::find_project_root(&cwd, &options.project_root_markers);
+    let local_disabled_reason: Option<String> = None;
+    let mut local_layers = Vec::new();
+    if let Some(project_root) = project_root.as_ref() {
+        debug!("resolved project root: {}", project_root.display());
+        local_layers.push((
+            ConfigLayerSource::Project,
+            project_root.join(DEFAULT_CONFIG_FILE),
+        ));
+    } else {
+        debug!("project root not found; skipping project/repo layers");
     }
-    if let Some(disabled_reason) = layer.disabled_reason.clone() {
-        warn!(
-            "layer disabled (source={:?}, path={}, reason={})",
-            layer.source,
-            layer.path.display(),
-            disabled_reason
-        );
+    local_layers.push((ConfigLayerSource::Cwd, cwd.join(DEFAULT_CONFIG_FILE)));
+    if let Some(repo_root) = project_root.as_ref() {
+        local_layers.push((
+            ConfigLayerSource::Repo,
+            repo_root.join(DEFAULT_CONFIG_DIR).join(DEFAULT_CONFIG_FILE),
+        ));
+    }
+
+    let mut seen_paths = HashSet::new();
+    for (source, path) in local_layers {
+        if !path.exists() {
+            debug!(
+                "skipping missing layer (source={:?}, path={})",
+                source,
+                path.display()
+            );
+            continue;
+        }
+        if !seen_paths.insert(utils::unique_path(&path)) {
+            debug!(
+                "skipping duplicate layer (source={:?}, path={})",
+                source,
+                path.display()
+            );
+            continue;
+        }
         layers.push(ConfigLayer {
-            source: layer.source,
-            path: Some(layer.path),
-            disabled_reason: Some(disabled_reason),
+            source,
+            path: Some(path),
+            disabled_reason: local_disabled_reason.clone(),
+        });
+    }
+
+    for runtime_path in &options.runtime_paths {
+        layers.push(ConfigLayer {
+            source: ConfigLayerSource::Runtime,
+            path: Some(runtime_path.clone()),
+            disabled_reason: None,
         });
-        return Ok(());
     }
-    let loaded = layer_io::load_required_layer(layer.source, &layer.path)?;
-    debug!(
-        "loaded layer (source={:?}, path={})",
-        layer.source,
-        layer.path.display()
-    );
-    layers.push(loaded.meta.clone());
-    merge_layers.push(loaded);
-    Ok(())
+    Ok(layers)
 }
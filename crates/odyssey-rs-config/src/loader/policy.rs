@@ -0,0 +1,152 @@
+//! Permission policy bundles referenced by `permissions.include`.
+//!
+//! Bundles are JSON5 files holding `rules` (and optionally further `include`
+//! entries). They are expanded per layer before merging, so the result obeys
+//! the same precedence and constraints as inline rules. Every rule is tagged
+//! with the file it came from so denials can name their source.
+
+use super::schema;
+use super::utils;
+use crate::ConfigError;
+use log::debug;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Expand `permissions.include` in a layer and tag rules with their source file.
+///
+/// Include paths are resolved relative to `base_dir`; without one, includes are
+/// rejected rather than read from the process cwd. `source` is the file the
+/// layer was read from, if any.
+pub(super) fn resolve_permission_includes(
+    value: &mut Value,
+    base_dir: Option<&Path>,
+    source: Option<&Path>,
+    label: &str,
+) -> Result<(), ConfigError> {
+    let Some(permissions) = value.get_mut("permissions").and_then(Value::as_object_mut) else {
+        return Ok(());
+    };
+    if let Some(source) = source
+        && let Some(Value::Array(rules)) = permissions.get_mut("rules")
+    {
+        tag_rules(rules, source);
+    }
+    let Some(include) = permissions.remove("include") else {
+        return Ok(());
+    };
+    let Some(base_dir) = base_dir else {
+        return Err(schema::invalid_field(
+            label,
+            "permissions.include",
+            "includes need a base directory; load the config from a file or with load_from_str_in",
+        ));
+    };
+
+    let mut resolver = IncludeResolver {
+        stack: source.map(utils::unique_path).into_iter().collect(),
+        seen: HashSet::new(),
+        rules: Vec::new(),
+    };
+    resolver.resolve_all(&include, base_dir, label, "permissions.include")?;
+    let mut rules = resolver.rules;
+    if let Some(Value::Array(own)) = permissions.remove("rules") {
+        rules.extend(own);
+    }
+    permissions.insert("rules".to_string(), Value::Array(rules));
+    Ok(())
+}
+
+/// Depth-first include expansion with cycle detection.
+struct IncludeResolver {
+    /// Files currently being expanded, outermost first.
+    stack: Vec<PathBuf>,
+    /// Files already expanded; repeated includes are skipped.
+    seen: HashSet<PathBuf>,
+    /// Collected rules in include order.
+    rules: Vec<Value>,
+}
+
+impl IncludeResolver {
+    fn resolve_all(
+        &mut self,
+        include: &Value,
+        base_dir: &Path,
+        label: &str,
+        field: &str,
+    ) -> Result<(), ConfigError> {
+        let Value::Array(entries) = include else {
+            return Err(schema::invalid_field(label, field, "expected array"));
+        };
+        for (idx, entry) in entries.iter().enumerate() {
+            let field = format!("{field}[{idx}]");
+            let Some(entry) = entry.as_str() else {
+                return Err(schema::invalid_field(label, &field, "expected string"));
+            };
+            self.resolve(&base_dir.join(entry), label, &field)?;
+        }
+        Ok(())
+    }
+
+    fn resolve(&mut self, path: &Path, label: &str, field: &str) -> Result<(), ConfigError> {
+        let unique = utils::unique_path(path);
+        if self.stack.contains(&unique) {
+            let chain = self
+                .stack
+                .iter()
+                .chain(std::iter::once(&unique))
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(schema::invalid_field(
+                label,
+                field,
+                &format!("include cycle: {chain}"),
+            ));
+        }
+        if !self.seen.insert(unique.clone()) {
+            debug!("skipping repeated policy bundle (path={})", path.display());
+            return Ok(());
+        }
+
+        debug!("loading policy bundle (path={})", path.display());
+        let contents = fs::read_to_string(path).map_err(|err| {
+            schema::invalid_field(
+                label,
+                field,
+                &format!("failed to read policy bundle {}: {err}", path.display()),
+            )
+        })?;
+        let mut bundle: Value = json5::from_str(&contents)?;
+        let bundle_label = format!("policy({})", path.display());
+        schema::validate_permission_bundle(&bundle, &bundle_label)?;
+
+        self.stack.push(unique);
+        if let Some(include) = bundle.get("include") {
+            let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+            self.resolve_all(include, base_dir, &bundle_label, "include")?;
+        }
+        self.stack.pop();
+
+        if let Some(Value::Array(rules)) = bundle.get_mut("rules") {
+            tag_rules(rules, path);
+            self.rules.append(rules);
+        }
+        Ok(())
+    }
+}
+
+/// Record the source file on rules that do not already carry one.
+fn tag_rules(rules: &mut [Value], source: &Path) {
+    for rule in rules {
+        if let Value::Object(map) = rule
+            && !map.contains_key("source")
+        {
+            map.insert(
+                "source".to_string(),
+                Value::String(source.display().to_string()),
+            );
+        }
+    }
+}
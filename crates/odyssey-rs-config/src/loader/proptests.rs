@@ -0,0 +1,225 @@
+//! Property tests for layer merging and schema validation.
+
+use super::merge::{merge_json_values, merge_json_with_constraints};
+use super::schema::validate_layer_schema;
+use super::{SchemaMode, config_from_value};
+use crate::ConfigError;
+use proptest::prelude::*;
+use proptest::sample::select;
+use serde_json::{Map, Value};
+
+/// Top-level keys, so generated layers reach the section validators.
+const SECTION_KEYS: &[&str] = &[
+    "$schema",
+    "orchestrator",
+    "agents",
+    "tools",
+    "permissions",
+    "memory",
+    "skills",
+    "sandbox",
+    "sessions",
+    "artifacts",
+    "models",
+    "guardrails",
+    "schedules",
+    "triggers",
+    "sharing",
+    "prompts",
+    "ui",
+    "providers",
+    "secrets",
+    "server",
+];
+
+/// Nested keys used by several sections, so generated layers overlap.
+const FIELD_KEYS: &[&str] = &[
+    "mode",
+    "rules",
+    "enabled",
+    "path",
+    "allow",
+    "deny",
+    "id",
+    "name",
+    "provider",
+    "model",
+    "action",
+    "tool",
+    "command",
+    "tokens",
+    "role",
+    "user_id",
+    "cron",
+    "prompt",
+    "limits",
+    "network",
+    "filesystem",
+    "output_policy",
+    "web",
+    "max_bytes",
+];
+
+/// String values that some validators accept.
+const STRING_VALUES: &[&str] = &[
+    "",
+    "default",
+    "plan",
+    "allow",
+    "deny",
+    "ask",
+    "read",
+    "write",
+    "* * * * *",
+    "env:KEY",
+    "openai",
+    "gpt-4o",
+    "viewer",
+    "admin",
+    "workspace_write",
+    "./docs",
+];
+
+fn json_key() -> impl Strategy<Value = String> {
+    prop_oneof![
+        select(FIELD_KEYS).prop_map(str::to_string),
+        select(SECTION_KEYS).prop_map(str::to_string),
+        "[a-z_]{1,8}",
+    ]
+}
+
+fn json_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::from),
+        (-1.0e6_f64..1.0e6).prop_map(Value::from),
+        select(STRING_VALUES).prop_map(Value::from),
+        "[ -~]{0,12}".prop_map(Value::from),
+    ];
+    leaf.prop_recursive(4, 64, 6, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+            prop::collection::btree_map(json_key(), inner, 0..6)
+                .prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+/// A config layer: an object keyed mostly by real section names.
+fn json_layer() -> impl Strategy<Value = Value> {
+    prop::collection::btree_map(
+        prop_oneof![select(SECTION_KEYS).prop_map(str::to_string), json_key()],
+        json_value(),
+        0..5,
+    )
+    .prop_map(|map| Value::Object(map.into_iter().collect::<Map<_, _>>()))
+}
+
+/// Render a value as JSON5 with bare identifier keys and trailing commas.
+fn to_json5(value: &Value) -> String {
+    match value {
+        Value::Array(items) => {
+            let items = items.iter().map(to_json5).collect::<Vec<_>>();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(map) => {
+            let mut out = String::from("{");
+            for (key, item) in map {
+                let bare = key
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '$');
+                let key = if bare {
+                    key.clone()
+                } else {
+                    Value::from(key.as_str()).to_string()
+                };
+                out.push_str(&format!("\n  {key}: {},", to_json5(item)));
+            }
+            out.push_str("\n}");
+            out
+        }
+        other => other.to_string(),
+    }
+}
+
+/// JSON pointers to every non-object constraint, i.e. every locked value.
+fn locked_pointers(constraints: &Value, prefix: &str, out: &mut Vec<String>) {
+    let Value::Object(map) = constraints else {
+        out.push(prefix.to_string());
+        return;
+    };
+    for (key, child) in map {
+        let escaped = key.replace('~', "~0").replace('/', "~1");
+        locked_pointers(child, &format!("{prefix}/{escaped}"), out);
+    }
+}
+
+/// Schema errors must name the layer and the offending field.
+fn assert_error_has_path(result: Result<(), ConfigError>, layer: &str) {
+    match result {
+        Ok(()) => {}
+        Err(ConfigError::InvalidField { path, message }) => {
+            let field = path.strip_prefix(&format!("{layer}:"));
+            assert!(
+                field.is_some_and(|field| !field.is_empty()),
+                "missing path: {path}"
+            );
+            assert!(!message.is_empty());
+        }
+        Err(err) => panic!("schema error without a path: {err}"),
+    }
+}
+
+proptest! {
+    /// Merging without constraints matches the plain recursive merge.
+    #[test]
+    fn unconstrained_merge_matches_plain_merge(base in json_value(), overlay in json_value()) {
+        let mut constrained = base.clone();
+        let mut plain = base;
+        merge_json_with_constraints(&mut constrained, &overlay, None);
+        merge_json_values(&mut plain, &overlay);
+        prop_assert_eq!(constrained, plain);
+    }
+
+    /// Layers never change a value locked by the requirements layer.
+    #[test]
+    fn constraints_are_never_overridden(
+        base in json_layer(),
+        overlay in json_layer(),
+        constraints in json_layer(),
+    ) {
+        let mut merged = base.clone();
+        merge_json_with_constraints(&mut merged, &overlay, Some(&constraints));
+        let mut pointers = Vec::new();
+        locked_pointers(&constraints, "", &mut pointers);
+        for pointer in pointers {
+            prop_assert_eq!(merged.pointer(&pointer), base.pointer(&pointer), "{}", pointer);
+        }
+    }
+
+    /// Schema validation of a single layer reports every error with a field path.
+    #[test]
+    fn layer_schema_errors_carry_a_path(layer in json_layer()) {
+        assert_error_has_path(validate_layer_schema(&layer, SchemaMode::Partial, "runtime"), "runtime");
+    }
+
+    /// Generated JSON5 layers parse back, merge, and validate without panicking.
+    #[test]
+    fn json5_layers_merge_and_validate(
+        requirements in json_layer(),
+        layers in prop::collection::vec(json_layer(), 1..4),
+    ) {
+        let mut merged = Value::Object(Map::new());
+        merge_json_with_constraints(&mut merged, &requirements, None);
+        for layer in &layers {
+            let parsed: Value = json5::from_str(&to_json5(layer)).expect("parse generated json5");
+            prop_assert_eq!(&parsed, layer);
+            merge_json_with_constraints(&mut merged, &parsed, Some(&requirements));
+        }
+        match config_from_value(merged, "effective") {
+            Ok(_) | Err(ConfigError::DecodeFailed(_)) | Err(ConfigError::Invalid(_)) => {}
+            Err(err) => assert_error_has_path(Err(err), "effective"),
+        }
+    }
+}
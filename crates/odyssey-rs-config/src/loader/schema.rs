@@ -1,7 +1,7 @@
 //! Schema validation helpers for Odyssey JSON5 configuration.
 
 use super::SchemaMode;
-use crate::ConfigError;
+use crate::{ApprovalPolicyProfile, ConfigError};
 use serde_json::{Map, Value};
 
 /// Validate a single config layer against the schema.
@@ -13,6 +13,7 @@ pub(super) fn validate_layer_schema(
     let map = expect_object(value, layer, "")?;
     let allowed = [
         "$schema",
+        "offline",
         "orchestrator",
         "agents",
         "tools",
@@ -21,12 +22,29 @@ pub(super) fn validate_layer_schema(
         "skills",
         "sandbox",
         "sessions",
+        "artifacts",
+        "models",
+        "guardrails",
+        "budget",
+        "schedules",
+        "triggers",
+        "sharing",
+        "prompts",
+        "prompt_profiles",
+        "locale",
+        "ui",
+        "providers",
+        "secrets",
+        "server",
     ];
     ensure_allowed_keys(map, &allowed, layer, "")?;
 
     if let Some(value) = map.get("$schema") {
         expect_string(value, layer, "$schema")?;
     }
+    if let Some(value) = map.get("offline") {
+        expect_bool(value, layer, "offline")?;
+    }
     if let Some(value) = map.get("orchestrator") {
         validate_orchestrator(value, layer, "orchestrator")?;
     }
@@ -51,6 +69,48 @@ pub(super) fn validate_layer_schema(
     if let Some(value) = map.get("sessions") {
         validate_sessions(value, layer, "sessions")?;
     }
+    if let Some(value) = map.get("artifacts") {
+        validate_artifacts(value, layer, "artifacts")?;
+    }
+    if let Some(value) = map.get("models") {
+        validate_models(value, layer, "models")?;
+    }
+    if let Some(value) = map.get("guardrails") {
+        validate_guardrails(value, layer, "guardrails")?;
+    }
+    if let Some(value) = map.get("budget") {
+        validate_budget(value, layer, "budget")?;
+    }
+    if let Some(value) = map.get("schedules") {
+        validate_schedules(value, layer, "schedules")?;
+    }
+    if let Some(value) = map.get("triggers") {
+        validate_triggers(value, layer, "triggers")?;
+    }
+    if let Some(value) = map.get("sharing") {
+        validate_sharing(value, layer, "sharing")?;
+    }
+    if let Some(value) = map.get("prompts") {
+        validate_prompts(value, layer, "prompts")?;
+    }
+    if let Some(value) = map.get("prompt_profiles") {
+        validate_prompt_profiles(value, layer, "prompt_profiles")?;
+    }
+    if let Some(value) = map.get("locale") {
+        validate_locale(value, layer, "locale")?;
+    }
+    if let Some(value) = map.get("ui") {
+        validate_ui(value, layer, "ui")?;
+    }
+    if let Some(value) = map.get("providers") {
+        validate_providers(value, layer, "providers")?;
+    }
+    if let Some(value) = map.get("secrets") {
+        validate_secrets(value, layer, "secrets")?;
+    }
+    if let Some(value) = map.get("server") {
+        validate_server(value, layer, "server")?;
+    }
 
     Ok(())
 }
@@ -91,6 +151,18 @@ fn validate_orchestrator(value: &Value, layer: &str, path: &str) -> Result<(), C
             "system_prompt",
             "append_system_prompt",
             "subagent_window_size",
+            "simulation",
+            "max_concurrent_turns",
+            "session_busy",
+            "max_react_iterations",
+            "max_repeated_tool_calls",
+            "prompt_cache",
+            "prompt_cache_key",
+            "watch_workspace",
+            "probe_environment",
+            "idempotency_window_secs",
+            "stall_timeout_secs",
+            "prompt_profile",
         ],
         layer,
         path,
@@ -105,9 +177,57 @@ fn validate_orchestrator(value: &Value, layer: &str, path: &str) -> Result<(), C
     if let Some(value) = map.get("subagent_window_size") {
         expect_u64(value, layer, &join_path(path, "subagent_window_size"))?;
     }
+    if let Some(value) = map.get("simulation") {
+        expect_bool(value, layer, &join_path(path, "simulation"))?;
+    }
+    if let Some(value) = map.get("max_concurrent_turns") {
+        expect_u64(value, layer, &join_path(path, "max_concurrent_turns"))?;
+    }
+    if let Some(value) = map.get("session_busy") {
+        validate_session_busy_policy(value, layer, &join_path(path, "session_busy"))?;
+    }
+    if let Some(value) = map.get("max_react_iterations") {
+        expect_u64(value, layer, &join_path(path, "max_react_iterations"))?;
+    }
+    if let Some(value) = map.get("max_repeated_tool_calls") {
+        expect_u64(value, layer, &join_path(path, "max_repeated_tool_calls"))?;
+    }
+    if let Some(value) = map.get("prompt_cache") {
+        expect_bool(value, layer, &join_path(path, "prompt_cache"))?;
+    }
+    if let Some(value) = map.get("prompt_cache_key") {
+        expect_string(value, layer, &join_path(path, "prompt_cache_key"))?;
+    }
+    if let Some(value) = map.get("watch_workspace") {
+        expect_bool(value, layer, &join_path(path, "watch_workspace"))?;
+    }
+    if let Some(value) = map.get("probe_environment") {
+        expect_bool(value, layer, &join_path(path, "probe_environment"))?;
+    }
+    if let Some(value) = map.get("idempotency_window_secs") {
+        expect_u64(value, layer, &join_path(path, "idempotency_window_secs"))?;
+    }
+    if let Some(value) = map.get("stall_timeout_secs") {
+        expect_u64(value, layer, &join_path(path, "stall_timeout_secs"))?;
+    }
+    if let Some(value) = map.get("prompt_profile") {
+        expect_string(value, layer, &join_path(path, "prompt_profile"))?;
+    }
     Ok(())
 }
 
+/// Validate session busy policy values.
+fn validate_session_busy_policy(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let Some(policy) = value.as_str() else {
+        return Err(invalid_field(layer, path, "expected string"));
+    };
+    if matches!(policy, "queue" | "reject") {
+        Ok(())
+    } else {
+        Err(invalid_field(layer, path, "invalid session busy policy"))
+    }
+}
+
 /// Validate a single agent definition.
 fn validate_agent(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
     let map = expect_object(value, layer, path)?;
@@ -190,11 +310,19 @@ fn validate_tool_policy(value: &Value, layer: &str, path: &str) -> Result<(), Co
 /// Validate the global tools block.
 fn validate_tools(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
     let map = expect_object(value, layer, path)?;
-    ensure_allowed_keys(map, &["output_policy"], layer, path)?;
+    ensure_allowed_keys(map, &["output_policy", "web"], layer, path)?;
 
     if let Some(value) = map.get("output_policy") {
         validate_tool_output_policy(value, layer, &join_path(path, "output_policy"))?;
     }
+    if let Some(value) = map.get("web") {
+        let web_path = join_path(path, "web");
+        let web = expect_object(value, layer, &web_path)?;
+        ensure_allowed_keys(web, &["api_key_ref"], layer, &web_path)?;
+        if let Some(value) = web.get("api_key_ref") {
+            validate_secret_ref(value, layer, &join_path(&web_path, "api_key_ref"))?;
+        }
+    }
     Ok(())
 }
 
@@ -203,6 +331,8 @@ fn validate_tool_output_policy(value: &Value, layer: &str, path: &str) -> Result
     let map = expect_object(value, layer, path)?;
     let allowed = [
         "max_string_bytes",
+        "max_string_tokens",
+        "max_image_bytes",
         "max_array_len",
         "max_object_entries",
         "redact_keys",
@@ -214,6 +344,12 @@ fn validate_tool_output_policy(value: &Value, layer: &str, path: &str) -> Result
     if let Some(value) = map.get("max_string_bytes") {
         expect_u64(value, layer, &join_path(path, "max_string_bytes"))?;
     }
+    if let Some(value) = map.get("max_string_tokens") {
+        expect_u64(value, layer, &join_path(path, "max_string_tokens"))?;
+    }
+    if let Some(value) = map.get("max_image_bytes") {
+        expect_u64(value, layer, &join_path(path, "max_image_bytes"))?;
+    }
     if let Some(value) = map.get("max_array_len") {
         expect_u64(value, layer, &join_path(path, "max_array_len"))?;
     }
@@ -235,17 +371,103 @@ fn validate_tool_output_policy(value: &Value, layer: &str, path: &str) -> Result
 /// Validate the global permissions block.
 fn validate_permissions(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
     let map = expect_object(value, layer, path)?;
-    ensure_allowed_keys(map, &["mode", "rules"], layer, path)?;
+    ensure_allowed_keys(
+        map,
+        &[
+            "mode",
+            "include",
+            "rules",
+            "webhook",
+            "approval_timeout_secs",
+            "timeout_decision",
+            "profile",
+        ],
+        layer,
+        path,
+    )?;
 
     if let Some(value) = map.get("mode") {
         validate_permission_mode(value, layer, &join_path(path, "mode"))?;
     }
+    if let Some(value) = map.get("include") {
+        validate_string_array(value, layer, &join_path(path, "include"))?;
+    }
     if let Some(value) = map.get("rules") {
         let arr = expect_array(value, layer, &join_path(path, "rules"))?;
         for (idx, entry) in arr.iter().enumerate() {
             validate_permission_rule(entry, layer, &format!("{path}.rules[{idx}]"))?;
         }
     }
+    if let Some(value) = map.get("webhook") {
+        validate_approval_webhook(value, layer, &join_path(path, "webhook"))?;
+    }
+    if let Some(value) = map.get("approval_timeout_secs") {
+        expect_u64(value, layer, &join_path(path, "approval_timeout_secs"))?;
+    }
+    if let Some(value) = map.get("timeout_decision") {
+        let decision_path = join_path(path, "timeout_decision");
+        let Some(decision) = value.as_str() else {
+            return Err(invalid_field(layer, &decision_path, "expected string"));
+        };
+        if !matches!(decision, "deny" | "allow") {
+            return Err(invalid_field(
+                layer,
+                &decision_path,
+                "invalid timeout decision",
+            ));
+        }
+    }
+    if let Some(value) = map.get("profile") {
+        let profile_path = join_path(path, "profile");
+        let Some(profile) = value.as_str() else {
+            return Err(invalid_field(layer, &profile_path, "expected string"));
+        };
+        if let Err(err) = profile.parse::<ApprovalPolicyProfile>() {
+            return Err(invalid_field(layer, &profile_path, &err));
+        }
+    }
+    Ok(())
+}
+
+/// Validate approval webhook settings.
+fn validate_approval_webhook(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(
+        map,
+        &["url", "timeout_secs", "fallback", "headers"],
+        layer,
+        path,
+    )?;
+
+    let url_path = join_path(path, "url");
+    let url = map
+        .get("url")
+        .ok_or_else(|| invalid_field(layer, &url_path, "missing required field"))?;
+    expect_string(url, layer, &url_path)?;
+
+    if let Some(value) = map.get("timeout_secs") {
+        expect_u64(value, layer, &join_path(path, "timeout_secs"))?;
+    }
+    if let Some(value) = map.get("fallback") {
+        let fallback_path = join_path(path, "fallback");
+        let Some(fallback) = value.as_str() else {
+            return Err(invalid_field(layer, &fallback_path, "expected string"));
+        };
+        if !matches!(fallback, "deny" | "allow") {
+            return Err(invalid_field(
+                layer,
+                &fallback_path,
+                "invalid webhook fallback",
+            ));
+        }
+    }
+    if let Some(value) = map.get("headers") {
+        let headers_path = join_path(path, "headers");
+        let headers = expect_object(value, layer, &headers_path)?;
+        for (key, value) in headers {
+            expect_string(value, layer, &join_path(&headers_path, key))?;
+        }
+    }
     Ok(())
 }
 
@@ -256,7 +478,7 @@ fn validate_permission_mode(value: &Value, layer: &str, path: &str) -> Result<()
     };
     if matches!(
         mode,
-        "default" | "accept_edits" | "bypass_permissions" | "plan"
+        "default" | "accept_edits" | "bypass_permissions" | "plan" | "confirm_batch"
     ) {
         Ok(())
     } else {
@@ -267,7 +489,9 @@ fn validate_permission_mode(value: &Value, layer: &str, path: &str) -> Result<()
 /// Validate a single permission rule entry.
 fn validate_permission_rule(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
     let map = expect_object(value, layer, path)?;
-    let allowed = ["action", "tool", "path", "command", "access"];
+    let allowed = [
+        "action", "tool", "path", "command", "access", "git_push", "source",
+    ];
     ensure_allowed_keys(map, &allowed, layer, path)?;
 
     let action_path = join_path(path, "action");
@@ -288,6 +512,29 @@ fn validate_permission_rule(value: &Value, layer: &str, path: &str) -> Result<()
     if let Some(value) = map.get("access") {
         validate_path_access(value, layer, &join_path(path, "access"))?;
     }
+    if let Some(value) = map.get("git_push") {
+        validate_git_push_access(value, layer, &join_path(path, "git_push"))?;
+    }
+    if let Some(value) = map.get("source") {
+        expect_string(value, layer, &join_path(path, "source"))?;
+    }
+    Ok(())
+}
+
+/// Validate a permission policy bundle file.
+pub(super) fn validate_permission_bundle(value: &Value, layer: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, "")?;
+    ensure_allowed_keys(map, &["$schema", "include", "rules"], layer, "")?;
+
+    if let Some(value) = map.get("include") {
+        validate_string_array(value, layer, "include")?;
+    }
+    if let Some(value) = map.get("rules") {
+        let arr = expect_array(value, layer, "rules")?;
+        for (idx, entry) in arr.iter().enumerate() {
+            validate_permission_rule(entry, layer, &format!("rules[{idx}]"))?;
+        }
+    }
     Ok(())
 }
 
@@ -315,6 +562,18 @@ fn validate_path_access(value: &Value, layer: &str, path: &str) -> Result<(), Co
     }
 }
 
+/// Validate git push rule values.
+fn validate_git_push_access(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let Some(access) = value.as_str() else {
+        return Err(invalid_field(layer, path, "expected string"));
+    };
+    if matches!(access, "push" | "force") {
+        Ok(())
+    } else {
+        Err(invalid_field(layer, path, "invalid git push access"))
+    }
+}
+
 /// Validate the global memory block.
 fn validate_memory(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
     let map = expect_object(value, layer, path)?;
@@ -326,6 +585,7 @@ fn validate_memory(value: &Value, layer: &str, path: &str) -> Result<(), ConfigE
         "capture",
         "recall",
         "compaction",
+        "project",
         "instruction_roots",
     ];
     ensure_allowed_keys(map, &allowed, layer, path)?;
@@ -354,6 +614,9 @@ fn validate_memory(value: &Value, layer: &str, path: &str) -> Result<(), ConfigE
     if let Some(value) = map.get("compaction") {
         validate_memory_compaction(value, layer, &join_path(path, "compaction"))?;
     }
+    if let Some(value) = map.get("project") {
+        validate_project_memory(value, layer, &join_path(path, "project"))?;
+    }
     Ok(())
 }
 
@@ -402,7 +665,7 @@ fn validate_memory_capture(value: &Value, layer: &str, path: &str) -> Result<(),
 /// Validate memory recall configuration.
 fn validate_memory_recall(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
     let map = expect_object(value, layer, path)?;
-    let allowed = ["mode", "text_weight", "vector_weight", "min_score"];
+    let allowed = ["mode", "text_weight", "vector_weight", "min_score", "debug"];
     ensure_allowed_keys(map, &allowed, layer, path)?;
 
     if let Some(value) = map.get("mode") {
@@ -417,6 +680,9 @@ fn validate_memory_recall(value: &Value, layer: &str, path: &str) -> Result<(),
     if let Some(value) = map.get("min_score") {
         expect_f64(value, layer, &join_path(path, "min_score"))?;
     }
+    if let Some(value) = map.get("debug") {
+        expect_bool(value, layer, &join_path(path, "debug"))?;
+    }
     Ok(())
 }
 
@@ -440,6 +706,7 @@ fn validate_memory_compaction(value: &Value, layer: &str, path: &str) -> Result<
         "max_messages",
         "summary_max_chars",
         "max_total_chars",
+        "summarizer",
     ];
     ensure_allowed_keys(map, &allowed, layer, path)?;
 
@@ -455,6 +722,53 @@ fn validate_memory_compaction(value: &Value, layer: &str, path: &str) -> Result<
     if let Some(value) = map.get("max_total_chars") {
         expect_u64(value, layer, &join_path(path, "max_total_chars"))?;
     }
+    if let Some(value) = map.get("summarizer") {
+        validate_memory_summarizer(value, layer, &join_path(path, "summarizer"))?;
+    }
+    Ok(())
+}
+
+/// Validate memory summarizer values.
+fn validate_memory_summarizer(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let Some(summarizer) = value.as_str() else {
+        return Err(invalid_field(layer, path, "expected string"));
+    };
+    if matches!(summarizer, "truncate" | "llm") {
+        Ok(())
+    } else {
+        Err(invalid_field(layer, path, "invalid memory summarizer"))
+    }
+}
+
+/// Validate project memory configuration.
+fn validate_project_memory(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    let allowed = ["enabled", "namespace", "recall_k", "max_facts_per_turn"];
+    ensure_allowed_keys(map, &allowed, layer, path)?;
+
+    if let Some(value) = map.get("enabled") {
+        expect_bool(value, layer, &join_path(path, "enabled"))?;
+    }
+    if let Some(value) = map.get("namespace") {
+        let namespace_path = join_path(path, "namespace");
+        expect_string(value, layer, &namespace_path)?;
+        if value
+            .as_str()
+            .is_some_and(|namespace| namespace.trim().is_empty())
+        {
+            return Err(invalid_field(
+                layer,
+                &namespace_path,
+                "project memory namespace must not be empty",
+            ));
+        }
+    }
+    if let Some(value) = map.get("recall_k") {
+        expect_u64(value, layer, &join_path(path, "recall_k"))?;
+    }
+    if let Some(value) = map.get("max_facts_per_turn") {
+        expect_u64(value, layer, &join_path(path, "max_facts_per_turn"))?;
+    }
     Ok(())
 }
 
@@ -468,6 +782,10 @@ fn validate_skills(value: &Value, layer: &str, path: &str) -> Result<(), ConfigE
         "paths",
         "allow",
         "deny",
+        "remotes",
+        "cache_dir",
+        "remote_allow",
+        "remote_deny",
     ];
     ensure_allowed_keys(map, &allowed, layer, path)?;
 
@@ -489,6 +807,41 @@ fn validate_skills(value: &Value, layer: &str, path: &str) -> Result<(), ConfigE
     if let Some(value) = map.get("deny") {
         validate_string_array(value, layer, &join_path(path, "deny"))?;
     }
+    if let Some(value) = map.get("remotes") {
+        let arr = expect_array(value, layer, &join_path(path, "remotes"))?;
+        for (idx, entry) in arr.iter().enumerate() {
+            validate_skill_remote(entry, layer, &format!("{path}.remotes[{idx}]"))?;
+        }
+    }
+    if let Some(value) = map.get("cache_dir") {
+        expect_string(value, layer, &join_path(path, "cache_dir"))?;
+    }
+    if let Some(value) = map.get("remote_allow") {
+        validate_string_array(value, layer, &join_path(path, "remote_allow"))?;
+    }
+    if let Some(value) = map.get("remote_deny") {
+        validate_string_array(value, layer, &join_path(path, "remote_deny"))?;
+    }
+    Ok(())
+}
+
+/// Validate a remote skill source entry.
+fn validate_skill_remote(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["url", "ref", "subdir"], layer, path)?;
+
+    let url_path = join_path(path, "url");
+    let url = map
+        .get("url")
+        .ok_or_else(|| invalid_field(layer, &url_path, "missing required field"))?;
+    expect_string(url, layer, &url_path)?;
+
+    if let Some(value) = map.get("ref") {
+        expect_string(value, layer, &join_path(path, "ref"))?;
+    }
+    if let Some(value) = map.get("subdir") {
+        expect_string(value, layer, &join_path(path, "subdir"))?;
+    }
     Ok(())
 }
 
@@ -525,12 +878,16 @@ fn validate_sandbox(value: &Value, layer: &str, path: &str) -> Result<(), Config
         "network",
         "env",
         "limits",
+        "pool_idle_secs",
     ];
     ensure_allowed_keys(map, &allowed, layer, path)?;
 
     if let Some(value) = map.get("enabled") {
         expect_bool(value, layer, &join_path(path, "enabled"))?;
     }
+    if let Some(value) = map.get("pool_idle_secs") {
+        expect_u64(value, layer, &join_path(path, "pool_idle_secs"))?;
+    }
     if let Some(value) = map.get("provider") {
         expect_string(value, layer, &join_path(path, "provider"))?;
     }
@@ -574,6 +931,7 @@ fn validate_sandbox_filesystem(value: &Value, layer: &str, path: &str) -> Result
         "deny_write",
         "allow_exec",
         "deny_exec",
+        "follow_symlinks",
     ];
     ensure_allowed_keys(map, &allowed, layer, path)?;
 
@@ -589,6 +947,9 @@ fn validate_sandbox_filesystem(value: &Value, layer: &str, path: &str) -> Result
             validate_string_array(value, layer, &join_path(path, key))?;
         }
     }
+    if let Some(value) = map.get("follow_symlinks") {
+        expect_bool(value, layer, &join_path(path, "follow_symlinks"))?;
+    }
 
     Ok(())
 }
@@ -682,11 +1043,19 @@ fn validate_agent_permissions(value: &Value, layer: &str, path: &str) -> Result<
 /// Validate session persistence configuration.
 fn validate_sessions(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
     let map = expect_object(value, layer, path)?;
-    ensure_allowed_keys(map, &["enabled", "provider", "path"], layer, path)?;
+    ensure_allowed_keys(
+        map,
+        &["enabled", "provider", "path", "force_lock"],
+        layer,
+        path,
+    )?;
 
     if let Some(value) = map.get("enabled") {
         expect_bool(value, layer, &join_path(path, "enabled"))?;
     }
+    if let Some(value) = map.get("force_lock") {
+        expect_bool(value, layer, &join_path(path, "force_lock"))?;
+    }
     if let Some(value) = map.get("provider") {
         expect_string(value, layer, &join_path(path, "provider"))?;
     }
@@ -696,6 +1065,659 @@ fn validate_sessions(value: &Value, layer: &str, path: &str) -> Result<(), Confi
     Ok(())
 }
 
+/// Validate artifact store configuration.
+fn validate_artifacts(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["enabled", "path"], layer, path)?;
+
+    if let Some(value) = map.get("enabled") {
+        expect_bool(value, layer, &join_path(path, "enabled"))?;
+    }
+    if let Some(value) = map.get("path") {
+        expect_string(value, layer, &join_path(path, "path"))?;
+    }
+    Ok(())
+}
+
+/// Validate model catalog configuration.
+fn validate_models(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["catalog", "cache_path"], layer, path)?;
+
+    if let Some(list) = map.get("catalog") {
+        let arr = expect_array(list, layer, &join_path(path, "catalog"))?;
+        for (idx, entry) in arr.iter().enumerate() {
+            validate_model_catalog_entry(entry, layer, &format!("{path}.catalog[{idx}]"))?;
+        }
+    }
+    if let Some(value) = map.get("cache_path") {
+        expect_string(value, layer, &join_path(path, "cache_path"))?;
+    }
+    Ok(())
+}
+
+/// Validate a single model catalog entry.
+fn validate_model_catalog_entry(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(
+        map,
+        &[
+            "provider",
+            "name",
+            "context_window",
+            "max_output_tokens",
+            "supports_tools",
+            "supports_vision",
+            "chars_per_token",
+        ],
+        layer,
+        path,
+    )?;
+
+    for key in ["provider", "name"] {
+        let key_path = join_path(path, key);
+        let value = map
+            .get(key)
+            .ok_or_else(|| invalid_field(layer, &key_path, "missing required field"))?;
+        expect_string(value, layer, &key_path)?;
+    }
+    let window_path = join_path(path, "context_window");
+    let window = map
+        .get("context_window")
+        .ok_or_else(|| invalid_field(layer, &window_path, "missing required field"))?;
+    expect_u64(window, layer, &window_path)?;
+
+    if let Some(value) = map.get("max_output_tokens") {
+        expect_u64(value, layer, &join_path(path, "max_output_tokens"))?;
+    }
+    if let Some(value) = map.get("supports_tools") {
+        expect_bool(value, layer, &join_path(path, "supports_tools"))?;
+    }
+    if let Some(value) = map.get("supports_vision") {
+        expect_bool(value, layer, &join_path(path, "supports_vision"))?;
+    }
+    if let Some(value) = map.get("chars_per_token") {
+        expect_u64(value, layer, &join_path(path, "chars_per_token"))?;
+    }
+    Ok(())
+}
+
+/// Validate content guardrail configuration.
+fn validate_guardrails(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["enabled", "rules"], layer, path)?;
+
+    if let Some(value) = map.get("enabled") {
+        expect_bool(value, layer, &join_path(path, "enabled"))?;
+    }
+    if let Some(list) = map.get("rules") {
+        let arr = expect_array(list, layer, &join_path(path, "rules"))?;
+        for (idx, entry) in arr.iter().enumerate() {
+            validate_guardrail_rule(entry, layer, &format!("{path}.rules[{idx}]"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Validate spending limits and model prices.
+fn validate_budget(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(
+        map,
+        &["max_session_usd", "max_daily_usd", "warn_ratio", "models"],
+        layer,
+        path,
+    )?;
+
+    for key in ["max_session_usd", "max_daily_usd"] {
+        if let Some(value) = map.get(key) {
+            expect_usd(value, layer, &join_path(path, key))?;
+        }
+    }
+    if let Some(value) = map.get("warn_ratio") {
+        let ratio_path = join_path(path, "warn_ratio");
+        match value.as_f64() {
+            Some(ratio) if ratio > 0.0 && ratio <= 1.0 => {}
+            _ => {
+                return Err(invalid_field(
+                    layer,
+                    &ratio_path,
+                    "expected number in (0, 1]",
+                ));
+            }
+        }
+    }
+    if let Some(value) = map.get("models") {
+        let models_path = join_path(path, "models");
+        let models = expect_object(value, layer, &models_path)?;
+        for (key, entry) in models {
+            let entry_path = join_path(&models_path, key);
+            match key.split_once('/') {
+                Some((provider, name)) if !provider.is_empty() && !name.is_empty() => {}
+                _ => {
+                    return Err(invalid_field(
+                        layer,
+                        &entry_path,
+                        "expected key of the form provider/name",
+                    ));
+                }
+            }
+            let entry = expect_object(entry, layer, &entry_path)?;
+            let keys = [
+                "input_usd_per_mtok",
+                "cached_input_usd_per_mtok",
+                "output_usd_per_mtok",
+                "max_session_usd",
+                "max_daily_usd",
+            ];
+            ensure_allowed_keys(entry, &keys, layer, &entry_path)?;
+            for key in keys {
+                if let Some(value) = entry.get(key) {
+                    expect_usd(value, layer, &join_path(&entry_path, key))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Expect a non-negative USD amount.
+fn expect_usd(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    match value.as_f64() {
+        Some(amount) if amount >= 0.0 => Ok(()),
+        _ => Err(invalid_field(layer, path, "expected non-negative number")),
+    }
+}
+
+/// Validate a single guardrail rule.
+fn validate_guardrail_rule(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(
+        map,
+        &["name", "patterns", "keywords", "targets"],
+        layer,
+        path,
+    )?;
+
+    let name_path = join_path(path, "name");
+    let name = map
+        .get("name")
+        .ok_or_else(|| invalid_field(layer, &name_path, "missing required field"))?;
+    expect_string(name, layer, &name_path)?;
+    for key in ["patterns", "keywords"] {
+        if let Some(value) = map.get(key) {
+            validate_string_array(value, layer, &join_path(path, key))?;
+        }
+    }
+    if !map.contains_key("patterns") && !map.contains_key("keywords") {
+        return Err(invalid_field(layer, path, "expected patterns or keywords"));
+    }
+    if let Some(value) = map.get("targets") {
+        let targets_path = join_path(path, "targets");
+        let arr = expect_array(value, layer, &targets_path)?;
+        for (idx, entry) in arr.iter().enumerate() {
+            match entry.as_str() {
+                Some("agent_output" | "tool_arguments") => {}
+                _ => {
+                    return Err(invalid_field(
+                        layer,
+                        &format!("{targets_path}[{idx}]"),
+                        "expected agent_output or tool_arguments",
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate scheduled run configuration.
+fn validate_schedules(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["path", "jobs"], layer, path)?;
+
+    if let Some(value) = map.get("path") {
+        expect_string(value, layer, &join_path(path, "path"))?;
+    }
+    if let Some(list) = map.get("jobs") {
+        let arr = expect_array(list, layer, &join_path(path, "jobs"))?;
+        for (idx, entry) in arr.iter().enumerate() {
+            validate_scheduled_job(entry, layer, &format!("{path}.jobs[{idx}]"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Validate a single scheduled job.
+fn validate_scheduled_job(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(
+        map,
+        &["id", "cron", "prompt", "agent", "llm", "enabled"],
+        layer,
+        path,
+    )?;
+
+    for key in ["id", "cron", "prompt"] {
+        let field_path = join_path(path, key);
+        let value = map
+            .get(key)
+            .ok_or_else(|| invalid_field(layer, &field_path, "missing required field"))?;
+        expect_string(value, layer, &field_path)?;
+    }
+    for key in ["agent", "llm"] {
+        if let Some(value) = map.get(key) {
+            expect_string(value, layer, &join_path(path, key))?;
+        }
+    }
+    if let Some(value) = map.get("enabled") {
+        expect_bool(value, layer, &join_path(path, "enabled"))?;
+    }
+    Ok(())
+}
+
+/// Validate the "triggers" block.
+fn validate_triggers(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    for (id, entry) in map {
+        let entry_path = join_path(path, id);
+        if id.is_empty() || id.chars().any(|ch| ch.is_whitespace() || ch == '/') {
+            return Err(invalid_field(
+                layer,
+                &entry_path,
+                "trigger ids must not be empty or contain whitespace or '/'",
+            ));
+        }
+        validate_trigger(entry, layer, &entry_path)?;
+    }
+    Ok(())
+}
+
+/// Validate a single webhook trigger.
+fn validate_trigger(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(
+        map,
+        &[
+            "prompt",
+            "events",
+            "agent",
+            "llm",
+            "profile",
+            "max_concurrent",
+        ],
+        layer,
+        path,
+    )?;
+
+    let prompt_path = join_path(path, "prompt");
+    let prompt = map
+        .get("prompt")
+        .ok_or_else(|| invalid_field(layer, &prompt_path, "missing required field"))?;
+    expect_string(prompt, layer, &prompt_path)?;
+    if let Some(value) = map.get("events") {
+        validate_string_array(value, layer, &join_path(path, "events"))?;
+    }
+    for key in ["agent", "llm"] {
+        if let Some(value) = map.get(key) {
+            expect_string(value, layer, &join_path(path, key))?;
+        }
+    }
+    if let Some(value) = map.get("profile") {
+        let profile_path = join_path(path, "profile");
+        let Some(profile) = value.as_str() else {
+            return Err(invalid_field(layer, &profile_path, "expected string"));
+        };
+        if let Err(err) = profile.parse::<ApprovalPolicyProfile>() {
+            return Err(invalid_field(layer, &profile_path, &err));
+        }
+    }
+    if let Some(value) = map.get("max_concurrent") {
+        let max_path = join_path(path, "max_concurrent");
+        expect_u64(value, layer, &max_path)?;
+        if value.as_u64() == Some(0) {
+            return Err(invalid_field(layer, &max_path, "expected at least 1"));
+        }
+    }
+    Ok(())
+}
+
+/// Validate session sharing configuration.
+fn validate_sharing(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["redact_patterns", "upload"], layer, path)?;
+
+    if let Some(value) = map.get("redact_patterns") {
+        validate_string_array(value, layer, &join_path(path, "redact_patterns"))?;
+    }
+    if let Some(value) = map.get("upload") {
+        validate_share_upload(value, layer, &join_path(path, "upload"))?;
+    }
+    Ok(())
+}
+
+/// Validate the share upload endpoint.
+fn validate_share_upload(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["url", "timeout_secs", "headers"], layer, path)?;
+
+    let url_path = join_path(path, "url");
+    let url = map
+        .get("url")
+        .ok_or_else(|| invalid_field(layer, &url_path, "missing required field"))?;
+    expect_string(url, layer, &url_path)?;
+    if let Some(value) = map.get("timeout_secs") {
+        expect_u64(value, layer, &join_path(path, "timeout_secs"))?;
+    }
+    if let Some(value) = map.get("headers") {
+        let headers_path = join_path(path, "headers");
+        let headers = expect_object(value, layer, &headers_path)?;
+        for (key, value) in headers {
+            expect_string(value, layer, &join_path(&headers_path, key))?;
+        }
+    }
+    Ok(())
+}
+
+/// Validate the "prompts" block.
+fn validate_prompts(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    for (name, entry) in map {
+        let entry_path = join_path(path, name);
+        if name.is_empty() || name.chars().any(|ch| ch.is_whitespace() || ch == '/') {
+            return Err(invalid_field(
+                layer,
+                &entry_path,
+                "preset names must not be empty or contain whitespace or '/'",
+            ));
+        }
+        validate_prompt_preset(entry, layer, &entry_path)?;
+    }
+    Ok(())
+}
+
+/// Validate a single prompt preset.
+fn validate_prompt_preset(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["description", "template", "args"], layer, path)?;
+
+    if let Some(value) = map.get("description") {
+        expect_string(value, layer, &join_path(path, "description"))?;
+    }
+    let template_path = join_path(path, "template");
+    let template = map
+        .get("template")
+        .ok_or_else(|| invalid_field(layer, &template_path, "missing required field"))?;
+    expect_string(template, layer, &template_path)?;
+    if let Some(value) = map.get("args") {
+        validate_string_array(value, layer, &join_path(path, "args"))?;
+    }
+    Ok(())
+}
+
+/// Names of the built-in prompt profiles, which user-defined profiles may not reuse.
+const BUILTIN_PROMPT_PROFILES: [&str; 5] = [
+    "orchestrator_default",
+    "subagent_focused",
+    "minimal",
+    "code_agent",
+    "planning_agent",
+];
+
+/// Validate the "prompt_profiles" block.
+fn validate_prompt_profiles(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    for (name, entry) in map {
+        let entry_path = join_path(path, name);
+        if name.is_empty() || name.chars().any(char::is_whitespace) {
+            return Err(invalid_field(
+                layer,
+                &entry_path,
+                "profile names must not be empty or contain whitespace",
+            ));
+        }
+        if BUILTIN_PROMPT_PROFILES.contains(&name.as_str()) {
+            return Err(invalid_field(
+                layer,
+                &entry_path,
+                "profile name is reserved for a built-in profile",
+            ));
+        }
+        validate_prompt_profile(entry, layer, &entry_path)?;
+    }
+    Ok(())
+}
+
+/// Validate a single user-defined prompt profile.
+fn validate_prompt_profile(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(
+        map,
+        &["sections", "bootstrap_files", "guidance"],
+        layer,
+        path,
+    )?;
+
+    let sections_path = join_path(path, "sections");
+    let sections = map
+        .get("sections")
+        .ok_or_else(|| invalid_field(layer, &sections_path, "missing required field"))?;
+    let sections = expect_array(sections, layer, &sections_path)?;
+    for (idx, section) in sections.iter().enumerate() {
+        match section.as_str() {
+            Some("identity" | "instructions" | "skills" | "environment" | "memory") => {}
+            _ => {
+                return Err(invalid_field(
+                    layer,
+                    &format!("{sections_path}[{idx}]"),
+                    "expected one of: identity, instructions, skills, environment, memory",
+                ));
+            }
+        }
+    }
+    if let Some(value) = map.get("bootstrap_files") {
+        expect_bool(value, layer, &join_path(path, "bootstrap_files"))?;
+    }
+    if let Some(value) = map.get("guidance") {
+        expect_string(value, layer, &join_path(path, "guidance"))?;
+    }
+    Ok(())
+}
+
+/// Validate the "locale" block.
+fn validate_locale(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(
+        map,
+        &["enabled", "timezone", "locale", "units"],
+        layer,
+        path,
+    )?;
+
+    if let Some(value) = map.get("enabled") {
+        expect_bool(value, layer, &join_path(path, "enabled"))?;
+    }
+    if let Some(value) = map.get("timezone") {
+        let timezone_path = join_path(path, "timezone");
+        match value.as_str() {
+            Some(zone) if zone.eq_ignore_ascii_case("local") || is_utc_offset(zone) => {}
+            _ => {
+                return Err(invalid_field(
+                    layer,
+                    &timezone_path,
+                    "expected local, UTC, or an offset such as +05:30",
+                ));
+            }
+        }
+    }
+    if let Some(value) = map.get("locale") {
+        expect_string(value, layer, &join_path(path, "locale"))?;
+    }
+    if let Some(value) = map.get("units") {
+        match value.as_str() {
+            Some("metric" | "imperial") => {}
+            _ => {
+                return Err(invalid_field(
+                    layer,
+                    &join_path(path, "units"),
+                    "expected one of: metric, imperial",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether `zone` is `UTC` or an offset of the form `+HH:MM` / `-HH:MM`.
+fn is_utc_offset(zone: &str) -> bool {
+    if zone.eq_ignore_ascii_case("utc") {
+        return true;
+    }
+    let bytes = zone.as_bytes();
+    bytes.len() == 6
+        && matches!(bytes[0], b'+' | b'-')
+        && bytes[3] == b':'
+        && [1, 2, 4, 5].iter().all(|idx| bytes[*idx].is_ascii_digit())
+}
+
+/// Validate the "ui" block.
+fn validate_ui(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["notifications"], layer, path)?;
+
+    if let Some(value) = map.get("notifications") {
+        validate_notifications(value, layer, &join_path(path, "notifications"))?;
+    }
+    Ok(())
+}
+
+/// Validate the "providers" block.
+fn validate_providers(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    for (name, entry) in map {
+        let entry_path = join_path(path, name);
+        let provider = expect_object(entry, layer, &entry_path)?;
+        ensure_allowed_keys(provider, &["api_key_ref"], layer, &entry_path)?;
+        if let Some(value) = provider.get("api_key_ref") {
+            validate_secret_ref(value, layer, &join_path(&entry_path, "api_key_ref"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Validate the "secrets" block.
+fn validate_secrets(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["file", "keyring_service"], layer, path)?;
+
+    for key in ["file", "keyring_service"] {
+        if let Some(value) = map.get(key) {
+            expect_string(value, layer, &join_path(path, key))?;
+        }
+    }
+    Ok(())
+}
+
+/// Validate the "server" block.
+fn validate_server(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["tokens", "open"], layer, path)?;
+    if let Some(value) = map.get("open") {
+        expect_bool(value, layer, &join_path(path, "open"))?;
+    }
+
+    if let Some(list) = map.get("tokens") {
+        let arr = expect_array(list, layer, &join_path(path, "tokens"))?;
+        for (idx, entry) in arr.iter().enumerate() {
+            validate_api_token(entry, layer, &format!("{path}.tokens[{idx}]"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Validate a single server API token entry.
+fn validate_api_token(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(map, &["token_ref", "user_id", "role"], layer, path)?;
+
+    let field = |key: &str| {
+        map.get(key)
+            .ok_or_else(|| invalid_field(layer, &join_path(path, key), "missing required field"))
+    };
+    validate_secret_ref(field("token_ref")?, layer, &join_path(path, "token_ref"))?;
+    expect_string(field("user_id")?, layer, &join_path(path, "user_id"))?;
+    match field("role")?.as_str() {
+        Some("viewer" | "operator" | "admin") => Ok(()),
+        _ => Err(invalid_field(
+            layer,
+            &join_path(path, "role"),
+            "expected viewer, operator, or admin",
+        )),
+    }
+}
+
+/// Validate a `scheme:name` secret reference.
+fn validate_secret_ref(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let Some(reference) = value.as_str() else {
+        return Err(invalid_field(layer, path, "expected string"));
+    };
+    match reference.split_once(':') {
+        Some(("env" | "file" | "keyring", name)) if !name.is_empty() => Ok(()),
+        _ => Err(invalid_field(
+            layer,
+            path,
+            "expected a secret reference like env:NAME, file:NAME, or keyring:NAME",
+        )),
+    }
+}
+
+/// Validate TUI notification configuration.
+fn validate_notifications(value: &Value, layer: &str, path: &str) -> Result<(), ConfigError> {
+    let map = expect_object(value, layer, path)?;
+    ensure_allowed_keys(
+        map,
+        &["enabled", "method", "events", "only_when_unfocused"],
+        layer,
+        path,
+    )?;
+
+    for key in ["enabled", "only_when_unfocused"] {
+        if let Some(value) = map.get(key) {
+            expect_bool(value, layer, &join_path(path, key))?;
+        }
+    }
+    if let Some(value) = map.get("method") {
+        let method_path = join_path(path, "method");
+        match value.as_str() {
+            Some("auto" | "desktop" | "osc9" | "bell") => {}
+            _ => {
+                return Err(invalid_field(
+                    layer,
+                    &method_path,
+                    "expected auto, desktop, osc9, or bell",
+                ));
+            }
+        }
+    }
+    if let Some(value) = map.get("events") {
+        let events_path = join_path(path, "events");
+        let arr = expect_array(value, layer, &events_path)?;
+        for (idx, entry) in arr.iter().enumerate() {
+            match entry.as_str() {
+                Some("turn_completed" | "error" | "approval_requested") => {}
+                _ => {
+                    return Err(invalid_field(
+                        layer,
+                        &format!("{events_path}[{idx}]"),
+                        "expected turn_completed, error, or approval_requested",
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Expect a JSON object or return a typed error.
 fn expect_object<'a>(
     value: &'a Value,
@@ -799,7 +1821,7 @@ fn join_path(prefix: &str, key: &str) -> String {
 }
 
 /// Build a structured invalid-field error.
-fn invalid_field(layer: &str, path: &str, message: &str) -> ConfigError {
+pub(super) fn invalid_field(layer: &str, path: &str, message: &str) -> ConfigError {
     let normalized_path = if path.is_empty() { "root" } else { path };
     ConfigError::InvalidField {
         path: format!("{layer}:{normalized_path}"),
@@ -40,6 +40,307 @@ fn rejects_invalid_permission_mode() {
     assert!(msg.contains("permissions.mode"));
 }
 
+/// Accept the batch confirmation permission mode.
+#[test]
+fn parses_confirm_batch_permission_mode() {
+    let config = OdysseyConfig::load_from_str(r#"{ permissions: { mode: "confirm_batch" } }"#)
+        .expect("config");
+    assert_eq!(config.permissions.mode, crate::PermissionMode::ConfirmBatch);
+}
+
+/// Parse project memory settings and reject an empty namespace.
+#[test]
+fn parses_project_memory() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.memory.project.enabled, false);
+    assert_eq!(config.memory.project.recall_k, 8);
+
+    let config = OdysseyConfig::load_from_str(
+        r#"{ memory: { project: { enabled: true, namespace: "acme", max_facts_per_turn: 1 } } }"#,
+    )
+    .expect("config");
+    assert_eq!(config.memory.project.enabled, true);
+    assert_eq!(config.memory.project.namespace.as_deref(), Some("acme"));
+    assert_eq!(config.memory.project.max_facts_per_turn, 1);
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ memory: { project: { namespace: " " } } }"#).unwrap_err();
+    assert!(format!("{err}").contains("memory.project.namespace"));
+}
+
+/// Default to truncating summaries and accept the LLM summarizer.
+#[test]
+fn parses_memory_summarizer() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(
+        config.memory.compaction.summarizer,
+        crate::MemorySummarizerKind::Truncate
+    );
+
+    let config =
+        OdysseyConfig::load_from_str(r#"{ memory: { compaction: { summarizer: "llm" } } }"#)
+            .expect("config");
+    assert_eq!(
+        config.memory.compaction.summarizer,
+        crate::MemorySummarizerKind::Llm
+    );
+
+    let err = OdysseyConfig::load_from_str(r#"{ memory: { compaction: { summarizer: "gpt" } } }"#)
+        .unwrap_err();
+    assert!(format!("{err}").contains("memory.compaction.summarizer"));
+}
+
+/// Keep recall debugging off by default and validate the toggle.
+#[test]
+fn parses_memory_recall_debug_flag() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.memory.recall.debug, false);
+    let config =
+        OdysseyConfig::load_from_str(r#"{ memory: { recall: { debug: true } } }"#).expect("config");
+    assert_eq!(config.memory.recall.debug, true);
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ memory: { recall: { debug: "on" } } }"#).unwrap_err();
+    assert!(format!("{err}").contains("memory.recall.debug"));
+}
+
+/// Keep the workspace lock enforced by default and validate the override.
+#[test]
+fn parses_sessions_force_lock() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.sessions.force_lock, false);
+    let config =
+        OdysseyConfig::load_from_str(r#"{ sessions: { enabled: true, force_lock: true } }"#)
+            .expect("config");
+    assert_eq!(config.sessions.force_lock, true);
+
+    let err = OdysseyConfig::load_from_str(r#"{ sessions: { force_lock: "yes" } }"#).unwrap_err();
+    assert!(format!("{err}").contains("sessions.force_lock"));
+}
+
+/// Parse the orchestrator simulation flag and reject non-boolean values.
+#[test]
+fn parses_orchestrator_simulation_flag() {
+    let config =
+        OdysseyConfig::load_from_str(r#"{ orchestrator: { simulation: true } }"#).expect("config");
+    assert_eq!(config.orchestrator.simulation, true);
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ orchestrator: { simulation: "yes" } }"#).unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("orchestrator.simulation"));
+}
+
+/// Keep the workspace watcher off by default and validate the toggle.
+#[test]
+fn parses_watch_workspace_flag() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.orchestrator.watch_workspace, false);
+    let config = OdysseyConfig::load_from_str(r#"{ orchestrator: { watch_workspace: true } }"#)
+        .expect("config");
+    assert_eq!(config.orchestrator.watch_workspace, true);
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ orchestrator: { watch_workspace: 1 } }"#).unwrap_err();
+    assert!(format!("{err}").contains("orchestrator.watch_workspace"));
+}
+
+/// Keep the environment probe off by default and validate the toggle.
+#[test]
+fn parses_probe_environment_flag() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.orchestrator.probe_environment, false);
+    let config = OdysseyConfig::load_from_str(r#"{ orchestrator: { probe_environment: true } }"#)
+        .expect("config");
+    assert_eq!(config.orchestrator.probe_environment, true);
+
+    let err = OdysseyConfig::load_from_str(r#"{ orchestrator: { probe_environment: "yes" } }"#)
+        .unwrap_err();
+    assert!(format!("{err}").contains("orchestrator.probe_environment"));
+}
+
+/// Leave the idempotency window unset by default and validate its type.
+#[test]
+fn parses_idempotency_window() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.orchestrator.idempotency_window_secs, None);
+    let config =
+        OdysseyConfig::load_from_str(r#"{ orchestrator: { idempotency_window_secs: 30 } }"#)
+            .expect("config");
+    assert_eq!(config.orchestrator.idempotency_window_secs, Some(30));
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ orchestrator: { idempotency_window_secs: "soon" } }"#)
+            .unwrap_err();
+    assert!(format!("{err}").contains("orchestrator.idempotency_window_secs"));
+}
+
+/// Leave the stall timeout unset by default and validate its type.
+#[test]
+fn parses_stall_timeout() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.orchestrator.stall_timeout_secs, None);
+    let config = OdysseyConfig::load_from_str(r#"{ orchestrator: { stall_timeout_secs: 0 } }"#)
+        .expect("config");
+    assert_eq!(config.orchestrator.stall_timeout_secs, Some(0));
+
+    let err = OdysseyConfig::load_from_str(r#"{ orchestrator: { stall_timeout_secs: "long" } }"#)
+        .unwrap_err();
+    assert!(format!("{err}").contains("orchestrator.stall_timeout_secs"));
+}
+
+/// Parse scheduled jobs and reject missing fields, duplicate ids, and bad cron expressions.
+#[test]
+fn parses_scheduled_jobs() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{ schedules: { path: "schedules.json", jobs: [
+            { id: "nightly", cron: "0 2 * * *", prompt: "Report repo health", agent: "odyssey-orchestrator" },
+            { id: "weekly", cron: "@weekly", prompt: "Summarize open TODOs", llm: "local", enabled: false },
+        ] } }"#,
+    )
+    .expect("config");
+    assert_eq!(config.schedules.path.as_deref(), Some("schedules.json"));
+    assert_eq!(config.schedules.jobs.len(), 2);
+    assert_eq!(config.schedules.jobs[0].enabled, true);
+    assert_eq!(config.schedules.jobs[0].llm, None);
+    assert_eq!(config.schedules.jobs[1].llm.as_deref(), Some("local"));
+    assert_eq!(config.schedules.jobs[1].enabled, false);
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ schedules: { jobs: [{ id: "a", cron: "@daily" }] } }"#)
+            .unwrap_err();
+    assert!(format!("{err}").contains("schedules.jobs[0].prompt"));
+
+    let err = OdysseyConfig::load_from_str(
+        r#"{ schedules: { jobs: [
+            { id: "a", cron: "@daily", prompt: "x" },
+            { id: "a", cron: "@hourly", prompt: "y" },
+        ] } }"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("duplicate scheduled job id: a"));
+
+    let err = OdysseyConfig::load_from_str(
+        r#"{ schedules: { jobs: [{ id: "a", cron: "every night", prompt: "x" }] } }"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("invalid cron expression"));
+}
+
+/// Parse webhook triggers with headless defaults and reject invalid limits and ids.
+#[test]
+fn parses_triggers() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{ triggers: {
+            "github-issue": {
+                prompt: "Triage issue {{payload.issue.title}}",
+                events: ["issues.opened"],
+                profile: "ci-trusted",
+                max_concurrent: 2,
+            },
+            deploy: { prompt: "Check the deploy: {{payload}}" },
+        } }"#,
+    )
+    .expect("config");
+    let issue = &config.triggers["github-issue"];
+    assert_eq!(issue.events, vec!["issues.opened".to_string()]);
+    assert_eq!(issue.profile, crate::ApprovalPolicyProfile::CiTrusted);
+    assert_eq!(issue.max_concurrent, 2);
+    let deploy = &config.triggers["deploy"];
+    assert_eq!(deploy.profile, crate::ApprovalPolicyProfile::CiSafe);
+    assert_eq!(deploy.max_concurrent, 1);
+    assert_eq!(deploy.events, Vec::<String>::new());
+
+    let err = OdysseyConfig::load_from_str(
+        r#"{ triggers: { deploy: { prompt: "x", max_concurrent: 0 } } }"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("triggers.deploy.max_concurrent"));
+    let err =
+        OdysseyConfig::load_from_str(r#"{ triggers: { "a b": { prompt: "x" } } }"#).unwrap_err();
+    assert!(format!("{err}").contains("trigger ids must not be empty"));
+    let err =
+        OdysseyConfig::load_from_str(r#"{ triggers: { deploy: { events: [] } } }"#).unwrap_err();
+    assert!(format!("{err}").contains("triggers.deploy.prompt"));
+}
+
+/// Parse session sharing settings and require an upload URL.
+#[test]
+fn parses_sharing_settings() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{ sharing: {
+            redact_patterns: ["internal-[0-9]+"],
+            upload: { url: "https://share.example.com/bundles", headers: { authorization: "Bearer t" } },
+        } }"#,
+    )
+    .expect("config");
+    assert_eq!(
+        config.sharing.redact_patterns,
+        vec!["internal-[0-9]+".to_string()]
+    );
+    let upload = config.sharing.upload.expect("upload");
+    assert_eq!(upload.url, "https://share.example.com/bundles");
+    assert_eq!(upload.timeout_secs, 30);
+    assert_eq!(upload.headers["authorization"], "Bearer t");
+
+    let err = OdysseyConfig::load_from_str(r#"{ sharing: { upload: { timeout_secs: 5 } } }"#)
+        .unwrap_err();
+    assert!(format!("{err}").contains("sharing.upload.url"));
+}
+
+/// Parse turn concurrency settings and reject unknown busy policies.
+#[test]
+fn parses_turn_concurrency_settings() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{ orchestrator: { max_concurrent_turns: 4, session_busy: "reject" } }"#,
+    )
+    .expect("config");
+    assert_eq!(config.orchestrator.max_concurrent_turns, Some(4));
+    assert_eq!(
+        config.orchestrator.session_busy,
+        crate::SessionBusyPolicy::Reject
+    );
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ orchestrator: { session_busy: "drop" } }"#).unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("orchestrator.session_busy"));
+}
+
+/// Parse ReAct stop conditions and reject non-integer limits.
+#[test]
+fn parses_react_stop_conditions() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{ orchestrator: { max_react_iterations: 25, max_repeated_tool_calls: 3 } }"#,
+    )
+    .expect("config");
+    assert_eq!(config.orchestrator.max_react_iterations, Some(25));
+    assert_eq!(config.orchestrator.max_repeated_tool_calls, Some(3));
+
+    let err = OdysseyConfig::load_from_str(r#"{ orchestrator: { max_react_iterations: "many" } }"#)
+        .unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("orchestrator.max_react_iterations"));
+}
+
+/// Parse artifact store settings and reject unknown keys.
+#[test]
+fn parses_artifacts_config() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{ artifacts: { enabled: true, path: "./.odyssey/artifacts" } }"#,
+    )
+    .expect("config");
+    assert_eq!(config.artifacts.enabled, true);
+    assert_eq!(
+        config.artifacts.path.as_deref(),
+        Some("./.odyssey/artifacts")
+    );
+
+    let err = OdysseyConfig::load_from_str(r#"{ artifacts: { provider: "s3" } }"#).unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("artifacts"));
+}
+
 /// Ensure repo config takes precedence over cwd config.
 #[test]
 fn layered_config_prefers_repo_over_cwd() {
@@ -193,3 +494,858 @@ fn constraints_prevent_list_override() {
     let layered = OdysseyConfig::load_layered_with_options(options).expect("layered");
     assert_eq!(layered.config.skills.paths, vec!["core".to_string()]);
 }
+
+/// Track which layer set each effective field.
+#[test]
+fn layered_config_tracks_origins() {
+    let temp = TempDir::new().expect("tmp");
+    let root = temp.path();
+    let project_root = root.join("project");
+    fs::create_dir_all(project_root.join(".git")).expect("git");
+
+    let system_config = root.join("system.json5");
+    write_json5(
+        &system_config,
+        r#"{
+            permissions: { mode: "plan", rules: [{ action: "allow", tool: "Read" }] },
+            tools: { output_policy: { replacement: "system" } },
+        }"#,
+    );
+    let requirements = root.join("requirements.json5");
+    write_json5(
+        &requirements,
+        r#"{ tools: { output_policy: { replacement: "locked" } } }"#,
+    );
+    let repo_config = project_root.join(".odyssey/odyssey.json5");
+    write_json5(&repo_config, r#"{ permissions: { mode: "accept_edits" } }"#);
+
+    let mut options = LayeredConfigOptions::new(&project_root);
+    options.system_config_path = Some(system_config.clone());
+    options.user_config_path = None;
+    options.requirements_path = Some(requirements.clone());
+
+    let layered = OdysseyConfig::load_layered_with_options(options).expect("layered");
+    assert_eq!(
+        layered.origin_of("permissions.mode"),
+        Some(&ConfigOrigin {
+            source: ConfigLayerSource::Repo,
+            file: Some(repo_config),
+        })
+    );
+    assert_eq!(
+        layered
+            .origin_of("permissions.rules[0].tool")
+            .map(|origin| origin.source),
+        Some(ConfigLayerSource::System)
+    );
+    let locked = layered
+        .origin_of("tools.output_policy.replacement")
+        .expect("locked origin");
+    assert_eq!(locked.source, ConfigLayerSource::Requirements);
+    assert_eq!(
+        locked.to_string(),
+        format!("requirements layer ({})", requirements.display())
+    );
+    assert_eq!(layered.origin_of("permissions"), None);
+    assert_eq!(layered.origin_of("memory.enabled"), None);
+}
+
+/// Parse prompt cache settings and reject non-boolean toggles.
+#[test]
+fn parses_prompt_cache_settings() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{ orchestrator: { prompt_cache: true, prompt_cache_key: "repo-main" } }"#,
+    )
+    .expect("config");
+    assert_eq!(config.orchestrator.prompt_cache, true);
+    assert_eq!(
+        config.orchestrator.prompt_cache_key.as_deref(),
+        Some("repo-main")
+    );
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ orchestrator: { prompt_cache: "yes" } }"#).unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("orchestrator.prompt_cache"));
+}
+
+/// Parse model catalog entries and require a context window.
+#[test]
+fn parses_model_catalog() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{ models: { catalog: [
+            { provider: "openai", name: "gpt-custom", context_window: 64000, max_output_tokens: 4096 }
+        ] } }"#,
+    )
+    .expect("config");
+    let entry = &config.models.catalog[0];
+    assert_eq!(entry.name, "gpt-custom");
+    assert_eq!(entry.context_window, 64000);
+    assert_eq!(entry.max_output_tokens, Some(4096));
+    assert_eq!(entry.supports_tools, true);
+    assert_eq!(entry.supports_vision, false);
+
+    let err = OdysseyConfig::load_from_str(
+        r#"{ models: { catalog: [{ provider: "openai", name: "gpt-custom" }] } }"#,
+    )
+    .unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("models.catalog[0].context_window"));
+
+    let config = OdysseyConfig::load_from_str(r#"{ models: { cache_path: "/srv/models" } }"#)
+        .expect("config");
+    assert_eq!(config.models.cache_path.as_deref(), Some("/srv/models"));
+    let err = OdysseyConfig::load_from_str(r#"{ models: { cache_path: 1 } }"#).unwrap_err();
+    assert!(format!("{err}").contains("models.cache_path"));
+}
+
+/// Parse token-budgeted tool output and per-model token estimates.
+#[test]
+fn parses_token_output_budget() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.tools.output_policy.max_string_tokens, None);
+    assert_eq!(config.tools.output_policy.max_image_bytes, 5 * 1024 * 1024);
+    let config = OdysseyConfig::load_from_str(
+        r#"{
+            tools: { output_policy: { max_string_tokens: 4000 } },
+            models: { catalog: [
+                { provider: "anthropic", name: "claude-x", context_window: 200000, chars_per_token: 3 }
+            ] }
+        }"#,
+    )
+    .expect("config");
+    assert_eq!(config.tools.output_policy.max_string_tokens, Some(4000));
+    assert_eq!(config.models.catalog[0].chars_per_token, Some(3));
+
+    let err = OdysseyConfig::load_from_str(
+        r#"{ tools: { output_policy: { max_string_tokens: "lots" } } }"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("tools.output_policy.max_string_tokens"));
+}
+
+/// Parse git push rules and reject ones that also set other targets.
+#[test]
+fn parses_git_push_rules() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{ permissions: { rules: [
+            { action: "allow", git_push: "push" },
+            { action: "deny", git_push: "force" }
+        ] } }"#,
+    )
+    .expect("config");
+    let kinds = config
+        .permissions
+        .rules
+        .iter()
+        .map(|rule| rule.git_push)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            Some(crate::GitPushAccess::Push),
+            Some(crate::GitPushAccess::Force)
+        ]
+    );
+
+    let err = OdysseyConfig::load_from_str(
+        r#"{ permissions: { rules: [{ action: "allow", git_push: "always" }] } }"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("permissions.rules[0].git_push"));
+    let err = OdysseyConfig::load_from_str(
+        r#"{ permissions: { rules: [{ action: "allow", tool: "GitCommit", git_push: "push" }] } }"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("git_push permission rules cannot set"));
+}
+
+/// Parse approval webhook settings and reject unknown fallbacks.
+#[test]
+fn parses_permission_webhook() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{ permissions: { webhook: {
+            url: "https://approvals.example.com/odyssey",
+            fallback: "allow",
+            headers: { authorization: "Bearer token" }
+        } } }"#,
+    )
+    .expect("config");
+    let webhook = config.permissions.webhook.expect("webhook");
+    assert_eq!(webhook.url, "https://approvals.example.com/odyssey");
+    assert_eq!(webhook.timeout_secs, 60);
+    assert_eq!(webhook.fallback, crate::WebhookFallback::Allow);
+    assert_eq!(
+        webhook.headers.get("authorization").map(String::as_str),
+        Some("Bearer token")
+    );
+
+    let err = OdysseyConfig::load_from_str(
+        r#"{ permissions: { webhook: { url: "http://localhost", fallback: "ask" } } }"#,
+    )
+    .unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("permissions.webhook.fallback"));
+}
+
+/// Parse approval timeout settings and reject unknown timeout decisions.
+#[test]
+fn parses_permission_approval_timeout() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.permissions.approval_timeout_secs, None);
+    assert_eq!(
+        config.permissions.timeout_decision,
+        crate::ApprovalTimeoutDecision::Deny
+    );
+
+    let config = OdysseyConfig::load_from_str(
+        r#"{ permissions: { approval_timeout_secs: 30, timeout_decision: "allow" } }"#,
+    )
+    .expect("config");
+    assert_eq!(config.permissions.approval_timeout_secs, Some(30));
+    assert_eq!(
+        config.permissions.timeout_decision,
+        crate::ApprovalTimeoutDecision::Allow
+    );
+
+    let err = OdysseyConfig::load_from_str(r#"{ permissions: { timeout_decision: "ask" } }"#)
+        .unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("permissions.timeout_decision"));
+}
+
+/// Default to the interactive approval profile and reject unknown profile names.
+#[test]
+fn parses_approval_profile() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(
+        config.permissions.profile,
+        crate::ApprovalPolicyProfile::Interactive
+    );
+
+    let config =
+        OdysseyConfig::load_from_str(r#"{ permissions: { profile: "ci-safe" } }"#).expect("config");
+    assert_eq!(
+        config.permissions.profile,
+        crate::ApprovalPolicyProfile::CiSafe
+    );
+    assert_eq!(
+        "ci-trusted".parse::<crate::ApprovalPolicyProfile>(),
+        Ok(crate::ApprovalPolicyProfile::CiTrusted)
+    );
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ permissions: { profile: "ci_safe" } }"#).unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("permissions.profile"));
+    assert!(msg.contains("expected one of: interactive, ci-safe, ci-trusted"));
+}
+
+/// Keep guardrails off with built-in rules by default and replace rules when configured.
+#[test]
+fn parses_guardrails() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.guardrails.enabled, false);
+    assert_eq!(
+        config
+            .guardrails
+            .rules
+            .iter()
+            .map(|rule| rule.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["destructive-rm", "env-file-access", "private-key"]
+    );
+
+    let config = OdysseyConfig::load_from_str(
+        r#"{ guardrails: { enabled: true, rules: [
+            { name: "no-curl", keywords: ["curl "], targets: ["tool_arguments"] },
+        ] } }"#,
+    )
+    .expect("config");
+    assert_eq!(config.guardrails.enabled, true);
+    assert_eq!(
+        config.guardrails.rules,
+        vec![crate::GuardrailRule {
+            name: "no-curl".to_string(),
+            patterns: Vec::new(),
+            keywords: vec!["curl ".to_string()],
+            targets: vec![odyssey_rs_protocol::GuardrailTarget::ToolArguments],
+        }]
+    );
+
+    let err = OdysseyConfig::load_from_str(r#"{ guardrails: { rules: [{ name: "empty" }] } }"#)
+        .unwrap_err();
+    assert!(format!("{err}").contains("guardrails.rules[0]"));
+    let err = OdysseyConfig::load_from_str(
+        r#"{ guardrails: { rules: [{ name: "x", keywords: ["a"], targets: ["input"] }] } }"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("guardrails.rules[0].targets[0]"));
+}
+
+/// Parse budget limits and model prices and reject negative amounts or bad keys.
+#[test]
+fn parses_budget() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.budget, crate::BudgetConfig::default());
+
+    let config = OdysseyConfig::load_from_str(
+        r#"{ budget: {
+            max_session_usd: 2,
+            max_daily_usd: 10.5,
+            warn_ratio: 0.9,
+            models: { "openai/gpt-4.1": {
+                input_usd_per_mtok: 2,
+                output_usd_per_mtok: 8,
+                max_daily_usd: 5,
+            } },
+        } }"#,
+    )
+    .expect("config");
+    assert_eq!(config.budget.max_session_usd, Some(2.0));
+    assert_eq!(config.budget.max_daily_usd, Some(10.5));
+    assert_eq!(config.budget.warn_ratio, Some(0.9));
+    assert_eq!(
+        config.budget.models.get("openai/gpt-4.1"),
+        Some(&crate::ModelBudgetConfig {
+            input_usd_per_mtok: 2.0,
+            cached_input_usd_per_mtok: None,
+            output_usd_per_mtok: 8.0,
+            max_session_usd: None,
+            max_daily_usd: Some(5.0),
+        })
+    );
+
+    let err = OdysseyConfig::load_from_str(r#"{ budget: { max_daily_usd: -1 } }"#).unwrap_err();
+    assert!(format!("{err}").contains("budget.max_daily_usd"));
+    let err = OdysseyConfig::load_from_str(r#"{ budget: { warn_ratio: 1.5 } }"#).unwrap_err();
+    assert!(format!("{err}").contains("budget.warn_ratio"));
+    let err =
+        OdysseyConfig::load_from_str(r#"{ budget: { models: { "gpt-4.1": {} } } }"#).unwrap_err();
+    assert!(format!("{err}").contains("budget.models.gpt-4.1"));
+}
+
+/// Parse user-defined prompt profiles and reject reserved names or unknown sections.
+#[test]
+fn parses_prompt_profiles() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{
+            orchestrator: { prompt_profile: "terse" },
+            prompt_profiles: { terse: {
+                sections: ["identity", "environment"],
+                guidance: "Answer in one line.",
+            } },
+        }"#,
+    )
+    .expect("config");
+    assert_eq!(config.orchestrator.prompt_profile.as_deref(), Some("terse"));
+    assert_eq!(
+        config.prompt_profiles.get("terse"),
+        Some(&crate::PromptProfileConfig {
+            sections: vec![
+                crate::PromptSectionName::Identity,
+                crate::PromptSectionName::Environment,
+            ],
+            bootstrap_files: false,
+            guidance: Some("Answer in one line.".to_string()),
+        })
+    );
+
+    let err = OdysseyConfig::load_from_str(r#"{ prompt_profiles: { minimal: { sections: [] } } }"#)
+        .unwrap_err();
+    assert!(format!("{err}").contains("prompt_profiles.minimal"));
+    let err =
+        OdysseyConfig::load_from_str(r#"{ prompt_profiles: { x: { sections: ["tools"] } } }"#)
+            .unwrap_err();
+    assert!(format!("{err}").contains("prompt_profiles.x.sections[0]"));
+    let err = OdysseyConfig::load_from_str(r#"{ prompt_profiles: { x: {} } }"#).unwrap_err();
+    assert!(format!("{err}").contains("prompt_profiles.x.sections"));
+}
+
+/// Parse the offline flag and reject non-boolean values.
+#[test]
+fn parses_offline() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.offline, false);
+    let config = OdysseyConfig::load_from_str("{ offline: true }").expect("config");
+    assert_eq!(config.offline, true);
+    let err = OdysseyConfig::load_from_str(r#"{ offline: "yes" }"#).unwrap_err();
+    assert!(format!("{err}").contains("offline"));
+}
+
+/// Enable locale context by default and reject unknown time zones or units.
+#[test]
+fn parses_locale() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.locale, crate::LocaleConfig::default());
+    assert_eq!(config.locale.enabled, true);
+
+    let config = OdysseyConfig::load_from_str(
+        r#"{ locale: { timezone: "+05:30", locale: "en-IN", units: "metric" } }"#,
+    )
+    .expect("config");
+    assert_eq!(config.locale.timezone.as_deref(), Some("+05:30"));
+    assert_eq!(config.locale.locale.as_deref(), Some("en-IN"));
+    assert_eq!(config.locale.units, Some(crate::MeasurementUnits::Metric));
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ locale: { timezone: "Europe/Berlin" } }"#).unwrap_err();
+    assert!(format!("{err}").contains("locale.timezone"));
+    let err = OdysseyConfig::load_from_str(r#"{ locale: { units: "nautical" } }"#).unwrap_err();
+    assert!(format!("{err}").contains("locale.units"));
+}
+
+/// Parse prompt presets keyed by name and reject invalid names or missing templates.
+#[test]
+fn parses_prompt_presets() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert!(config.prompts.is_empty());
+
+    let config = OdysseyConfig::load_from_str(
+        r#"{ prompts: { review: {
+            description: "Review a file",
+            template: "Review {{file}} for bugs.",
+            args: ["file"],
+        } } }"#,
+    )
+    .expect("config");
+    assert_eq!(
+        config.prompts.get("review"),
+        Some(&crate::PromptPresetConfig {
+            description: Some("Review a file".to_string()),
+            template: "Review {{file}} for bugs.".to_string(),
+            args: vec!["file".to_string()],
+        })
+    );
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ prompts: { review: { args: ["file"] } } }"#).unwrap_err();
+    assert!(format!("{err}").contains("prompts.review.template"));
+    let err = OdysseyConfig::load_from_str(r#"{ prompts: { "code review": { template: "x" } } }"#)
+        .unwrap_err();
+    assert!(format!("{err}").contains("prompts.code review"));
+}
+
+/// Default the sandbox pool idle timeout and accept overrides.
+#[test]
+fn parses_sandbox_pool_idle_secs() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.sandbox.pool_idle_secs, 300);
+
+    let config =
+        OdysseyConfig::load_from_str(r#"{ sandbox: { pool_idle_secs: 0 } }"#).expect("config");
+    assert_eq!(config.sandbox.pool_idle_secs, 0);
+
+    let err = OdysseyConfig::load_from_str(r#"{ sandbox: { pool_idle_secs: "5m" } }"#).unwrap_err();
+    assert!(format!("{err}").contains("sandbox.pool_idle_secs"));
+}
+
+/// Default to following symlinks and reject non-boolean overrides.
+#[test]
+fn parses_sandbox_follow_symlinks() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.sandbox.filesystem.follow_symlinks, true);
+
+    let config =
+        OdysseyConfig::load_from_str(r#"{ sandbox: { filesystem: { follow_symlinks: false } } }"#)
+            .expect("config");
+    assert_eq!(config.sandbox.filesystem.follow_symlinks, false);
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ sandbox: { filesystem: { follow_symlinks: "no" } } }"#)
+            .unwrap_err();
+    assert!(format!("{err}").contains("sandbox.filesystem.follow_symlinks"));
+}
+
+/// Parse remote skill sources and require a URL for each.
+#[test]
+fn parses_skill_remotes() {
+    let config = OdysseyConfig::load_from_str(
+        r#"{ skills: { remotes: [{
+            url: "https://github.com/acme/skills.git",
+            ref: "main",
+            subdir: "skills"
+        }], remote_deny: ["https://untrusted.example.com/*"] } }"#,
+    )
+    .expect("config");
+    let remote = &config.skills.remotes[0];
+    assert_eq!(remote.url, "https://github.com/acme/skills.git");
+    assert_eq!(remote.reference.as_deref(), Some("main"));
+    assert_eq!(remote.subdir.as_deref(), Some("skills"));
+    assert_eq!(config.skills.remote_allow, vec!["*".to_string()]);
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ skills: { remotes: [{ ref: "main" }] } }"#).unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("skills.remotes[0].url"));
+}
+
+/// Expand policy bundles from a layer and record the file each rule came from.
+#[test]
+fn includes_permission_policy_bundles() {
+    let temp = TempDir::new().expect("tmp");
+    let root = temp.path();
+    let cwd = root.join("project");
+    fs::create_dir_all(cwd.join(".git")).expect("git");
+
+    let base = cwd.join("policies").join("base.json5");
+    write_json5(&base, r#"{ rules: [{ action: "deny", tool: "Bash" }] }"#);
+    let ci = cwd.join("policies").join("ci.json5");
+    write_json5(
+        &ci,
+        r#"{ include: ["base.json5"], rules: [{ action: "ask", tool: "Write" }] }"#,
+    );
+    let project_config = cwd.join(DEFAULT_CONFIG_FILE);
+    write_json5(
+        &project_config,
+        r#"{ permissions: {
+            include: ["policies/ci.json5"],
+            rules: [{ action: "allow", tool: "Read" }]
+        } }"#,
+    );
+
+    let mut options = LayeredConfigOptions::new(&cwd);
+    options.system_config_path = None;
+    options.user_config_path = None;
+    options.requirements_path = None;
+    let layered = OdysseyConfig::load_layered_with_options(options).expect("layered");
+    let rules = &layered.config.permissions.rules;
+    let tools = rules
+        .iter()
+        .map(|rule| rule.tool.as_deref().unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(tools, vec!["Bash", "Write", "Read"]);
+    assert_eq!(rules[0].source, Some(base.display().to_string()));
+    assert_eq!(rules[1].source, Some(ci.display().to_string()));
+    assert_eq!(rules[2].source, Some(project_config.display().to_string()));
+}
+
+/// Reject policy bundles that include each other.
+#[test]
+fn rejects_permission_include_cycles() {
+    let temp = TempDir::new().expect("tmp");
+    let root = temp.path();
+    write_json5(&root.join("a.json5"), r#"{ include: ["b.json5"] }"#);
+    write_json5(&root.join("b.json5"), r#"{ include: ["a.json5"] }"#);
+    let config_path = root.join(DEFAULT_CONFIG_FILE);
+    write_json5(&config_path, r#"{ permissions: { include: ["a.json5"] } }"#);
+
+    let err = OdysseyConfig::load_from_path(&config_path).unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("include cycle"));
+    assert!(msg.contains("a.json5 ->"));
+
+    write_json5(
+        &config_path,
+        r#"{ permissions: { include: ["missing.json5"] } }"#,
+    );
+    let err = OdysseyConfig::load_from_path(&config_path).unwrap_err();
+    let msg = format!("{err}");
+    assert!(msg.contains("permissions.include[0]"));
+}
+
+/// Resolve includes in raw contents only against an explicit base directory.
+#[test]
+fn load_from_str_requires_base_dir_for_includes() {
+    let temp = TempDir::new().expect("tmp");
+    write_json5(
+        &temp.path().join("base.json5"),
+        r#"{ rules: [{ action: "deny", tool: "Bash" }] }"#,
+    );
+    let contents = r#"{ permissions: { include: ["base.json5"] } }"#;
+    let err = OdysseyConfig::load_from_str(contents).unwrap_err();
+    assert!(format!("{err}").contains("permissions.include"));
+
+    let config = OdysseyConfig::load_from_str_in(contents, temp.path()).expect("config");
+    assert_eq!(config.permissions.rules.len(), 1);
+    assert_eq!(config.permissions.rules[0].tool.as_deref(), Some("Bash"));
+}
+
+/// Keep notifications off by default and validate methods and events.
+#[test]
+fn parses_ui_notifications() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(
+        config.ui.notifications,
+        crate::NotificationsConfig::default()
+    );
+    assert_eq!(config.ui.notifications.enabled, false);
+    assert_eq!(config.ui.notifications.only_when_unfocused, true);
+
+    let config = OdysseyConfig::load_from_str(
+        r#"{ ui: { notifications: {
+            enabled: true,
+            method: "osc9",
+            events: ["approval_requested"],
+            only_when_unfocused: false,
+        } } }"#,
+    )
+    .expect("config");
+    assert_eq!(
+        config.ui.notifications,
+        crate::NotificationsConfig {
+            enabled: true,
+            method: crate::NotificationMethod::Osc9,
+            events: vec![crate::NotificationEvent::ApprovalRequested],
+            only_when_unfocused: false,
+        }
+    );
+
+    let err = OdysseyConfig::load_from_str(r#"{ ui: { notifications: { method: "popup" } } }"#)
+        .unwrap_err();
+    assert!(format!("{err}").contains("ui.notifications.method"));
+    let err = OdysseyConfig::load_from_str(r#"{ ui: { notifications: { events: ["done"] } } }"#)
+        .unwrap_err();
+    assert!(format!("{err}").contains("ui.notifications.events[0]"));
+}
+
+/// Parse provider and web api key references and reject unknown secret schemes.
+#[test]
+fn parses_secret_references() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.secrets, crate::SecretsConfig::default());
+    assert_eq!(config.secrets.keyring_service, "odyssey");
+    assert_eq!(config.providers.is_empty(), true);
+
+    let config = OdysseyConfig::load_from_str(
+        r#"{
+            providers: { openai: { api_key_ref: "keyring:openai" } },
+            tools: { web: { api_key_ref: "env:SEARCH_API_KEY" } },
+            secrets: { file: ".odyssey/secrets.json", keyring_service: "odyssey-dev" },
+        }"#,
+    )
+    .expect("config");
+    assert_eq!(
+        config.providers.get("openai"),
+        Some(&crate::ProviderConfig {
+            api_key_ref: Some("keyring:openai".to_string()),
+        })
+    );
+    assert_eq!(
+        config.tools.web.api_key_ref.as_deref(),
+        Some("env:SEARCH_API_KEY")
+    );
+    assert_eq!(
+        config.secrets.file.as_deref(),
+        Some(".odyssey/secrets.json")
+    );
+    assert_eq!(config.secrets.keyring_service, "odyssey-dev");
+
+    let err =
+        OdysseyConfig::load_from_str(r#"{ providers: { openai: { api_key_ref: "sk-live" } } }"#)
+            .unwrap_err();
+    assert!(format!("{err}").contains("providers.openai.api_key_ref"));
+    let err = OdysseyConfig::load_from_str(r#"{ tools: { web: { api_key_ref: "vault:x" } } }"#)
+        .unwrap_err();
+    assert!(format!("{err}").contains("tools.web.api_key_ref"));
+    let err = OdysseyConfig::load_from_str(r#"{ secrets: { path: "x" } }"#).unwrap_err();
+    assert!(format!("{err}").contains("secrets.path"));
+}
+
+/// Parse server API tokens and reject unknown roles or missing fields.
+#[test]
+fn parses_server_tokens() {
+    let config = OdysseyConfig::load_from_str("{}").expect("config");
+    assert_eq!(config.server.tokens.is_empty(), true);
+    assert_eq!(config.server.open, false);
+    let config = OdysseyConfig::load_from_str("{ server: { open: true } }").expect("config");
+    assert_eq!(config.server.open, true);
+
+    let config = OdysseyConfig::load_from_str(
+        r#"{
+            server: {
+                tokens: [
+                    { token_ref: "env:ODYSSEY_ADMIN_TOKEN", user_id: "alice", role: "admin" },
+                    { token_ref: "file:viewer", user_id: "bob", role: "viewer" },
+                ],
+            },
+        }"#,
+    )
+    .expect("config");
+    assert_eq!(
+        config.server.tokens,
+        vec![
+            crate::ApiTokenConfig {
+                token_ref: "env:ODYSSEY_ADMIN_TOKEN".to_string(),
+                user_id: "alice".to_string(),
+                role: crate::ServerRole::Admin,
+            },
+            crate::ApiTokenConfig {
+                token_ref: "file:viewer".to_string(),
+                user_id: "bob".to_string(),
+                role: crate::ServerRole::Viewer,
+            },
+        ]
+    );
+    assert_eq!(
+        crate::ServerRole::Viewer < crate::ServerRole::Operator,
+        true
+    );
+
+    let err = OdysseyConfig::load_from_str(
+        r#"{ server: { tokens: [{ token_ref: "env:T", user_id: "a", role: "root" }] } }"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("server.tokens[0].role"));
+    let err = OdysseyConfig::load_from_str(
+        r#"{ server: { tokens: [{ token_ref: "env:T", role: "admin" }] } }"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("server.tokens[0].user_id"));
+    let err = OdysseyConfig::load_from_str(
+        r#"{ server: { tokens: [{ token_ref: "hunter2", user_id: "a", role: "admin" }] } }"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("server.tokens[0].token_ref"));
+    let err = OdysseyConfig::load_from_str(r#"{ server: { open: "yes" } }"#).unwrap_err();
+    assert!(format!("{err}").contains("server.open"));
+}
+
+/// Report which layer set each effective value, honoring requirement locks.
+#[test]
+fn doctor_reports_value_origins() {
+    let temp = TempDir::new().expect("tmp");
+    let root = temp.path();
+    let project_root = root.join("project");
+    fs::create_dir_all(project_root.join(".git")).expect("git");
+
+    let system_config = root.join("system.json5");
+    write_json5(
+        &system_config,
+        r#"{ permissions: { mode: "plan" }, skills: { paths: ["system"] } }"#,
+    );
+    let requirements = root.join("requirements.json5");
+    write_json5(&requirements, r#"{ skills: { paths: ["core"] } }"#);
+    write_json5(
+        &project_root.join(".odyssey/odyssey.json5"),
+        r#"{ permissions: { mode: "accept_edits" }, skills: { paths: ["repo"] } }"#,
+    );
+
+    let mut options = LayeredConfigOptions::new(&project_root);
+    options.system_config_path = Some(system_config.clone());
+    options.user_config_path = None;
+    options.requirements_path = Some(requirements.clone());
+
+    let report = ConfigDoctor::new(options).run();
+    assert_eq!(report.findings, Vec::new());
+    assert_eq!(report.has_errors(), false);
+    assert_eq!(report.layers.len(), 3);
+
+    let mode = report.origin("permissions.mode").expect("mode origin");
+    assert_eq!(mode.source, ConfigLayerSource::Repo);
+    assert_eq!(mode.value, serde_json::json!("accept_edits"));
+    let paths = report.origin("skills.paths").expect("paths origin");
+    assert_eq!(paths.source, ConfigLayerSource::Requirements);
+    assert_eq!(paths.file, Some(requirements));
+    assert_eq!(paths.value, serde_json::json!(["core"]));
+    assert_eq!(report.origin("tools.output_policy.replacement"), None);
+    assert!(report.config.is_some());
+}
+
+/// Report every unknown key instead of stopping at the first one.
+#[test]
+fn doctor_reports_unknown_keys() {
+    let temp = TempDir::new().expect("tmp");
+    let path = temp.path().join("odyssey.json5");
+    write_json5(
+        &path,
+        r#"{
+            unexpected: true,
+            permissions: { mode: "plan", rules: [{ action: "allow", tool: "Read", typo: 1 }] },
+        }"#,
+    );
+
+    let report = ConfigDoctor::for_file(&path).run();
+    let paths = report
+        .findings
+        .iter()
+        .map(|finding| {
+            (
+                finding.severity,
+                finding.path.as_str(),
+                finding.message.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec![
+            (FindingSeverity::Error, "unexpected", "unknown key"),
+            (
+                FindingSeverity::Error,
+                "permissions.rules[0].typo",
+                "unknown key"
+            ),
+        ]
+    );
+    assert_eq!(report.has_errors(), true);
+    assert_eq!(
+        report
+            .origin("permissions.mode")
+            .map(|origin| origin.source),
+        Some(ConfigLayerSource::Runtime)
+    );
+}
+
+/// Warn about duplicate and conflicting permission rules.
+#[test]
+fn doctor_flags_conflicting_rules() {
+    let temp = TempDir::new().expect("tmp");
+    let path = temp.path().join("odyssey.json5");
+    write_json5(
+        &path,
+        r#"{ permissions: { rules: [
+            { action: "allow", tool: "Bash", command: ["git", "push"] },
+            { action: "deny", tool: "Bash", command: ["git", "push"] },
+            { action: "ask", tool: "Read" },
+            { action: "ask", tool: "Read" },
+            { action: "allow", path: "src/[" },
+        ] } }"#,
+    );
+
+    let report = ConfigDoctor::for_file(&path).run();
+    let findings = report
+        .findings
+        .iter()
+        .map(|finding| (finding.severity, finding.path.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        findings,
+        vec![
+            (FindingSeverity::Warning, "permissions.rules[1]"),
+            (FindingSeverity::Warning, "permissions.rules[3]"),
+            (FindingSeverity::Error, "permissions.rules[4].path"),
+        ]
+    );
+    let conflict = &report.findings[0].message;
+    assert!(
+        conflict.starts_with("conflicts with rules[0] (allow tool=Bash command=git push from ")
+    );
+    assert!(conflict.ends_with("; deny wins"));
+    assert!(
+        report.findings[1]
+            .message
+            .starts_with("duplicates rules[2]")
+    );
+}
+
+/// Keep the published JSON Schema in sync with the config model.
+#[test]
+fn json_schema_matches_docs() {
+    let schema = OdysseyConfig::json_schema();
+    for pointer in [
+        "/properties/$schema",
+        "/properties/ui",
+        "/$defs/PermissionsConfig/properties/include",
+        "/$defs/PermissionsConfig/properties/profile",
+    ] {
+        assert!(schema.pointer(pointer).is_some(), "missing {pointer}");
+    }
+    let published: Value =
+        serde_json::from_str(include_str!("../../../../docs/src/odyssey.schema.json"))
+            .expect("published schema");
+    assert_eq!(
+        published, schema,
+        "regenerate with `cargo run -p odyssey-rs-tui -- config schema > docs/src/odyssey.schema.json`"
+    );
+}
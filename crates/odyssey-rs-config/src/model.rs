@@ -1,14 +1,18 @@
 //! Configuration schema for Odyssey.
 
-use odyssey_rs_protocol::SandboxMode;
+use odyssey_rs_protocol::{GuardrailTarget, SandboxMode};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Root config for the Odyssey SDK.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct OdysseyConfig {
     #[serde(default, rename = "$schema")]
     pub schema: Option<String>,
+    /// Hide network tools, deny sandbox network access, and allow only local LLM providers.
+    #[serde(default)]
+    pub offline: bool,
     #[serde(default)]
     pub orchestrator: OrchestratorConfig,
     #[serde(default)]
@@ -23,6 +27,38 @@ pub struct OdysseyConfig {
     pub sandbox: SandboxConfig,
     #[serde(default)]
     pub sessions: SessionsConfig,
+    #[serde(default)]
+    pub artifacts: ArtifactsConfig,
+    #[serde(default)]
+    pub models: ModelsConfig,
+    #[serde(default)]
+    pub guardrails: GuardrailsConfig,
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    #[serde(default)]
+    pub schedules: SchedulesConfig,
+    /// Webhook triggers keyed by the id inbound events are routed by.
+    #[serde(default)]
+    pub triggers: BTreeMap<String, TriggerConfig>,
+    #[serde(default)]
+    pub sharing: SharingConfig,
+    /// Named prompt presets keyed by the slash command that expands them.
+    #[serde(default)]
+    pub prompts: BTreeMap<String, PromptPresetConfig>,
+    /// User-defined system prompt profiles keyed by name.
+    #[serde(default)]
+    pub prompt_profiles: BTreeMap<String, PromptProfileConfig>,
+    #[serde(default)]
+    pub locale: LocaleConfig,
+    #[serde(default)]
+    pub ui: UiConfig,
+    /// LLM provider settings keyed by provider name (for example `openai`).
+    #[serde(default)]
+    pub providers: BTreeMap<String, ProviderConfig>,
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
 }
 
 impl OdysseyConfig {
@@ -88,6 +124,96 @@ impl OdysseyConfigBuilder {
         self
     }
 
+    /// Replace the artifact store configuration.
+    pub fn artifacts(mut self, artifacts: ArtifactsConfig) -> Self {
+        self.config.artifacts = artifacts;
+        self
+    }
+
+    /// Replace the model catalog configuration.
+    pub fn models(mut self, models: ModelsConfig) -> Self {
+        self.config.models = models;
+        self
+    }
+
+    /// Replace the content guardrail configuration.
+    pub fn guardrails(mut self, guardrails: GuardrailsConfig) -> Self {
+        self.config.guardrails = guardrails;
+        self
+    }
+
+    /// Replace the spending budget configuration.
+    pub fn budget(mut self, budget: BudgetConfig) -> Self {
+        self.config.budget = budget;
+        self
+    }
+
+    /// Replace the scheduled run configuration.
+    pub fn schedules(mut self, schedules: SchedulesConfig) -> Self {
+        self.config.schedules = schedules;
+        self
+    }
+
+    /// Add or replace a webhook trigger.
+    pub fn trigger(mut self, id: impl Into<String>, trigger: TriggerConfig) -> Self {
+        self.config.triggers.insert(id.into(), trigger);
+        self
+    }
+
+    /// Replace the session sharing configuration.
+    pub fn sharing(mut self, sharing: SharingConfig) -> Self {
+        self.config.sharing = sharing;
+        self
+    }
+
+    /// Add or replace a named prompt preset.
+    pub fn prompt(mut self, name: impl Into<String>, preset: PromptPresetConfig) -> Self {
+        self.config.prompts.insert(name.into(), preset);
+        self
+    }
+
+    /// Add or replace a user-defined system prompt profile.
+    pub fn prompt_profile(mut self, name: impl Into<String>, profile: PromptProfileConfig) -> Self {
+        self.config.prompt_profiles.insert(name.into(), profile);
+        self
+    }
+
+    /// Run without network access (see `OdysseyConfig::offline`).
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.config.offline = offline;
+        self
+    }
+
+    /// Replace the date, time zone, and locale context configuration.
+    pub fn locale(mut self, locale: LocaleConfig) -> Self {
+        self.config.locale = locale;
+        self
+    }
+
+    /// Replace the terminal UI configuration.
+    pub fn ui(mut self, ui: UiConfig) -> Self {
+        self.config.ui = ui;
+        self
+    }
+
+    /// Add or replace the settings of an LLM provider.
+    pub fn provider(mut self, name: impl Into<String>, provider: ProviderConfig) -> Self {
+        self.config.providers.insert(name.into(), provider);
+        self
+    }
+
+    /// Replace the secret backend configuration.
+    pub fn secrets(mut self, secrets: SecretsConfig) -> Self {
+        self.config.secrets = secrets;
+        self
+    }
+
+    /// Replace the server API configuration.
+    pub fn server(mut self, server: ServerConfig) -> Self {
+        self.config.server = server;
+        self
+    }
+
     /// Finalize and return the built `OdysseyConfig`.
     pub fn build(self) -> OdysseyConfig {
         self.config
@@ -95,27 +221,74 @@ impl OdysseyConfigBuilder {
 }
 
 /// Configuration for the built-in Odyssey orchestrator agent.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct OrchestratorConfig {
     #[serde(default)]
     pub additional_instruction_prompt: Option<String>,
     #[serde(default = "default_subagent_window_size")]
     pub subagent_window_size: usize,
+    #[serde(default)]
+    pub simulation: bool,
+    #[serde(default)]
+    pub max_concurrent_turns: Option<usize>,
+    #[serde(default)]
+    pub session_busy: SessionBusyPolicy,
+    #[serde(default)]
+    pub max_react_iterations: Option<usize>,
+    #[serde(default)]
+    pub max_repeated_tool_calls: Option<usize>,
+    /// Report prompt cache hits in usage events and expose a cache key in turn metadata.
+    ///
+    /// The key is not sent to providers; caching relies on providers that cache
+    /// byte-stable prompt prefixes on their own.
+    #[serde(default)]
+    pub prompt_cache: bool,
+    /// Prefix of the cache key in turn metadata (unset = `odyssey`).
+    #[serde(default)]
+    pub prompt_cache_key: Option<String>,
+    /// Watch the workspace and tell the agent about files changed outside its tools.
+    #[serde(default)]
+    pub watch_workspace: bool,
+    /// Probe the host at startup and report it as `environment` in turn metadata.
+    #[serde(default)]
+    pub probe_environment: bool,
+    /// Seconds a submission idempotency key is remembered (unset = 600, 0 = off).
+    #[serde(default)]
+    pub idempotency_window_secs: Option<u64>,
+    /// Seconds a turn may go without events before it is reported as stalled
+    /// (unset = 300, 0 = off).
+    #[serde(default)]
+    pub stall_timeout_secs: Option<u64>,
+    /// Prompt profile of the default agent: a built-in profile or a key of
+    /// `prompt_profiles` (unset = `orchestrator_default`).
+    #[serde(default)]
+    pub prompt_profile: Option<String>,
 }
 
 fn default_subagent_window_size() -> usize {
     20
 }
 
+/// Behavior when a turn is started in a session that is already running one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionBusyPolicy {
+    /// Wait for the running turn to finish.
+    #[default]
+    Queue,
+    /// Fail immediately with a session busy error.
+    Reject,
+}
+
 /// Model provider configuration for an agent.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ModelConfig {
     pub provider: String,
     pub name: String,
 }
 
 /// Tool allow/deny policy for a single agent.
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
 pub struct ToolPolicy {
     #[serde(default)]
     pub allow: Vec<String>,
@@ -134,17 +307,33 @@ impl ToolPolicy {
 }
 
 /// Global tool configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct ToolsConfig {
     #[serde(default)]
     pub output_policy: ToolOutputPolicyConfig,
+    #[serde(default)]
+    pub web: WebToolsConfig,
+}
+
+/// Settings for the web tools.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct WebToolsConfig {
+    /// Secret reference for the search API key, such as `keyring:brave`.
+    #[serde(default)]
+    pub api_key_ref: Option<String>,
 }
 
 /// Output policy for tool results.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ToolOutputPolicyConfig {
     #[serde(default = "default_max_string_bytes")]
     pub max_string_bytes: usize,
+    /// Token budget for string fields; replaces `max_string_bytes` when set.
+    #[serde(default)]
+    pub max_string_tokens: Option<usize>,
+    /// Largest image the Read tool returns inline for vision-capable models; 0 disables.
+    #[serde(default = "default_max_image_bytes")]
+    pub max_image_bytes: usize,
     #[serde(default = "default_max_array_len")]
     pub max_array_len: usize,
     #[serde(default = "default_max_object_entries")]
@@ -161,6 +350,8 @@ impl Default for ToolOutputPolicyConfig {
     fn default() -> Self {
         Self {
             max_string_bytes: default_max_string_bytes(),
+            max_string_tokens: None,
+            max_image_bytes: default_max_image_bytes(),
             max_array_len: default_max_array_len(),
             max_object_entries: default_max_object_entries(),
             redact_keys: Vec::new(),
@@ -175,6 +366,11 @@ fn default_max_string_bytes() -> usize {
     32 * 1024
 }
 
+/// Default maximum size of images returned inline by file tools.
+fn default_max_image_bytes() -> usize {
+    5 * 1024 * 1024
+}
+
 /// Default maximum array length for tool output.
 fn default_max_array_len() -> usize {
     256
@@ -191,7 +387,7 @@ fn default_redaction_replacement() -> String {
 }
 
 /// Memory backend configuration for an agent or global defaults.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MemoryConfig {
     #[serde(default = "default_memory_provider")]
     pub provider: String,
@@ -206,6 +402,8 @@ pub struct MemoryConfig {
     #[serde(default)]
     pub compaction: MemoryCompactionPolicy,
     #[serde(default)]
+    pub project: ProjectMemoryConfig,
+    #[serde(default)]
     pub instruction_roots: Vec<String>,
 }
 
@@ -218,6 +416,7 @@ impl Default for MemoryConfig {
             capture: MemoryCapturePolicy::default(),
             recall: MemoryRecallConfig::default(),
             compaction: MemoryCompactionPolicy::default(),
+            project: ProjectMemoryConfig::default(),
             instruction_roots: Vec::new(),
         }
     }
@@ -234,7 +433,7 @@ fn default_recall_k() -> usize {
 }
 
 /// Capture policy used by memory providers.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MemoryCapturePolicy {
     #[serde(default = "default_capture_messages")]
     pub capture_messages: bool,
@@ -285,7 +484,7 @@ fn default_secret_entropy_threshold() -> f32 {
 }
 
 /// Recall scoring configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MemoryRecallConfig {
     #[serde(default)]
     pub mode: MemoryRecallMode,
@@ -295,6 +494,8 @@ pub struct MemoryRecallConfig {
     pub vector_weight: f32,
     #[serde(default)]
     pub min_score: Option<f32>,
+    #[serde(default)]
+    pub debug: bool,
 }
 
 impl Default for MemoryRecallConfig {
@@ -304,6 +505,7 @@ impl Default for MemoryRecallConfig {
             text_weight: default_text_weight(),
             vector_weight: default_vector_weight(),
             min_score: None,
+            debug: false,
         }
     }
 }
@@ -319,7 +521,7 @@ fn default_vector_weight() -> f32 {
 }
 
 /// Recall mode selection for memory search.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum MemoryRecallMode {
     #[default]
@@ -329,7 +531,7 @@ pub enum MemoryRecallMode {
 }
 
 /// Compaction policy for long sessions.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MemoryCompactionPolicy {
     #[serde(default)]
     pub enabled: bool,
@@ -339,6 +541,8 @@ pub struct MemoryCompactionPolicy {
     pub summary_max_chars: usize,
     #[serde(default)]
     pub max_total_chars: Option<usize>,
+    #[serde(default)]
+    pub summarizer: MemorySummarizerKind,
 }
 
 impl Default for MemoryCompactionPolicy {
@@ -348,10 +552,22 @@ impl Default for MemoryCompactionPolicy {
             max_messages: default_compaction_max_messages(),
             summary_max_chars: default_compaction_summary_chars(),
             max_total_chars: None,
+            summarizer: MemorySummarizerKind::default(),
         }
     }
 }
 
+/// How compacted memory records are summarized.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MemorySummarizerKind {
+    /// Concatenate and truncate the records inline while the turn runs.
+    #[default]
+    Truncate,
+    /// Summarize with the agent's LLM in a background job after the turn.
+    Llm,
+}
+
 /// Default maximum message count before compaction.
 fn default_compaction_max_messages() -> usize {
     40
@@ -362,8 +578,42 @@ fn default_compaction_summary_chars() -> usize {
     1500
 }
 
+/// Durable facts distilled from completed turns and recalled by new sessions.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectMemoryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default = "default_project_recall_k")]
+    pub recall_k: usize,
+    #[serde(default = "default_project_max_facts_per_turn")]
+    pub max_facts_per_turn: usize,
+}
+
+impl Default for ProjectMemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            namespace: None,
+            recall_k: default_project_recall_k(),
+            max_facts_per_turn: default_project_max_facts_per_turn(),
+        }
+    }
+}
+
+/// Default number of project facts recalled into the system prompt.
+fn default_project_recall_k() -> usize {
+    8
+}
+
+/// Default maximum facts distilled from one turn.
+fn default_project_max_facts_per_turn() -> usize {
+    3
+}
+
 /// Per-agent sandbox overrides.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct AgentSandboxConfig {
     #[serde(default)]
     pub enabled: Option<bool>,
@@ -374,14 +624,14 @@ pub struct AgentSandboxConfig {
 }
 
 /// Per-agent permission overrides.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct AgentPermissionsConfig {
     #[serde(default)]
     pub mode: Option<PermissionMode>,
 }
 
 /// Skill discovery configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SkillsConfig {
     #[serde(default, alias = "settingSources")]
     pub setting_sources: Vec<SettingSource>,
@@ -391,6 +641,14 @@ pub struct SkillsConfig {
     pub allow: Vec<String>,
     #[serde(default)]
     pub deny: Vec<String>,
+    #[serde(default)]
+    pub remotes: Vec<SkillRemote>,
+    #[serde(default)]
+    pub cache_dir: Option<String>,
+    #[serde(default = "default_skill_allow")]
+    pub remote_allow: Vec<String>,
+    #[serde(default)]
+    pub remote_deny: Vec<String>,
 }
 
 impl Default for SkillsConfig {
@@ -400,6 +658,10 @@ impl Default for SkillsConfig {
             paths: Vec::new(),
             allow: default_skill_allow(),
             deny: Vec::new(),
+            remotes: Vec::new(),
+            cache_dir: None,
+            remote_allow: default_skill_allow(),
+            remote_deny: Vec::new(),
         }
     }
 }
@@ -408,7 +670,19 @@ fn default_skill_allow() -> Vec<String> {
     vec!["*".to_string()]
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// Git repository that provides skills, fetched into the skill cache.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct SkillRemote {
+    pub url: String,
+    /// Branch, tag, or commit to check out (defaults to the remote HEAD).
+    #[serde(default, rename = "ref")]
+    pub reference: Option<String>,
+    /// Directory inside the repository to scan for skills.
+    #[serde(default)]
+    pub subdir: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SettingSource {
     User,
@@ -417,16 +691,122 @@ pub enum SettingSource {
 }
 
 /// Global permission rules applied before tool/sandbox checks.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct PermissionsConfig {
     #[serde(default)]
     pub mode: PermissionMode,
     #[serde(default)]
     pub rules: Vec<PermissionRule>,
+    #[serde(default)]
+    pub webhook: Option<ApprovalWebhookConfig>,
+    /// Seconds to wait for an approval before applying `timeout_decision`; unset waits forever.
+    #[serde(default)]
+    pub approval_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub timeout_decision: ApprovalTimeoutDecision,
+    /// Preset applied after rules; non-interactive profiles never wait for approval.
+    #[serde(default)]
+    pub profile: ApprovalPolicyProfile,
+}
+
+/// Approval policy preset layered under the configured rules.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApprovalPolicyProfile {
+    /// Ask for approval as configured.
+    #[default]
+    Interactive,
+    /// Allow workspace reads; deny external paths, network access, and anything that would ask.
+    CiSafe,
+    /// Allow workspace reads, writes, and commands; deny external paths, network access, and
+    /// anything that would ask.
+    CiTrusted,
+}
+
+impl ApprovalPolicyProfile {
+    /// All profiles, in the order shown in help text.
+    pub const ALL: [ApprovalPolicyProfile; 3] = [
+        ApprovalPolicyProfile::Interactive,
+        ApprovalPolicyProfile::CiSafe,
+        ApprovalPolicyProfile::CiTrusted,
+    ];
+
+    /// Profile name as written in config and on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApprovalPolicyProfile::Interactive => "interactive",
+            ApprovalPolicyProfile::CiSafe => "ci-safe",
+            ApprovalPolicyProfile::CiTrusted => "ci-trusted",
+        }
+    }
+
+    /// Return true when approval requests may wait for a human.
+    pub fn is_interactive(self) -> bool {
+        matches!(self, ApprovalPolicyProfile::Interactive)
+    }
+}
+
+impl std::fmt::Display for ApprovalPolicyProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ApprovalPolicyProfile {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.as_str() == value)
+            .ok_or_else(|| {
+                let names = Self::ALL.map(ApprovalPolicyProfile::as_str).join(", ");
+                format!("unknown approval profile '{value}' (expected one of: {names})")
+            })
+    }
+}
+
+/// Decision applied when an approval request times out.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalTimeoutDecision {
+    /// Deny the request.
+    #[default]
+    Deny,
+    /// Allow the request once.
+    Allow,
+}
+
+/// Remote endpoint that resolves approval requests.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ApprovalWebhookConfig {
+    pub url: String,
+    #[serde(default = "default_webhook_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default)]
+    pub fallback: WebhookFallback,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// Default time to wait for a webhook decision.
+fn default_webhook_timeout_secs() -> u64 {
+    60
+}
+
+/// Decision applied when the webhook fails or times out.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFallback {
+    /// Deny the request.
+    #[default]
+    Deny,
+    /// Allow the request once.
+    Allow,
 }
 
 /// Permission mode applied before callbacks.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PermissionMode {
     #[default]
@@ -434,10 +814,11 @@ pub enum PermissionMode {
     AcceptEdits,
     BypassPermissions,
     Plan,
+    ConfirmBatch,
 }
 
 /// Single permission rule (tool, path, or command matching).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PermissionRule {
     pub action: PermissionAction,
     #[serde(default)]
@@ -448,6 +829,22 @@ pub struct PermissionRule {
     pub command: Option<Vec<String>>,
     #[serde(default)]
     pub access: Option<PathAccess>,
+    /// Git pushes this rule matches; push rules match nothing else.
+    #[serde(default)]
+    pub git_push: Option<GitPushAccess>,
+    /// File the rule was loaded from, set by the config loader.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Kind of git push a permission rule matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GitPushAccess {
+    /// Regular pushes.
+    Push,
+    /// Force pushes (`--force-with-lease`).
+    Force,
 }
 
 /// Re-export protocol path access (used in permission rules).
@@ -456,7 +853,7 @@ pub use odyssey_rs_protocol::PathAccess;
 pub use odyssey_rs_protocol::PermissionAction;
 
 /// Top-level sandbox configuration applied to all tools.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SandboxConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -472,6 +869,9 @@ pub struct SandboxConfig {
     pub env: SandboxEnv,
     #[serde(default)]
     pub limits: SandboxLimits,
+    /// Seconds a pooled sandbox handle may sit idle before shutdown; 0 disables pooling.
+    #[serde(default = "default_pool_idle_secs")]
+    pub pool_idle_secs: u64,
 }
 
 impl Default for SandboxConfig {
@@ -484,16 +884,21 @@ impl Default for SandboxConfig {
             network: SandboxNetwork::default(),
             env: SandboxEnv::default(),
             limits: SandboxLimits::default(),
+            pool_idle_secs: default_pool_idle_secs(),
         }
     }
 }
 
+fn default_pool_idle_secs() -> u64 {
+    300
+}
+
 /// Default sandbox mode for tool execution.
 fn default_sandbox_mode() -> SandboxMode {
     SandboxMode::WorkspaceWrite
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SandboxFilesystem {
     #[serde(default)]
     pub allow_read: Vec<String>,
@@ -507,9 +912,30 @@ pub struct SandboxFilesystem {
     pub allow_exec: Vec<String>,
     #[serde(default)]
     pub deny_exec: Vec<String>,
+    /// Follow symlinks inside the workspace; targets are always checked against policy.
+    #[serde(default = "default_follow_symlinks")]
+    pub follow_symlinks: bool,
+}
+
+impl Default for SandboxFilesystem {
+    fn default() -> Self {
+        Self {
+            allow_read: Vec::new(),
+            deny_read: Vec::new(),
+            allow_write: Vec::new(),
+            deny_write: Vec::new(),
+            allow_exec: Vec::new(),
+            deny_exec: Vec::new(),
+            follow_symlinks: default_follow_symlinks(),
+        }
+    }
+}
+
+fn default_follow_symlinks() -> bool {
+    true
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct SandboxNetwork {
     #[serde(default)]
     pub allow_domains: Vec<String>,
@@ -517,7 +943,7 @@ pub struct SandboxNetwork {
     pub deny_domains: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct SandboxEnv {
     #[serde(default)]
     pub allow: Vec<String>,
@@ -527,7 +953,7 @@ pub struct SandboxEnv {
     pub set: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct SandboxLimits {
     #[serde(default)]
     pub cpu_seconds: Option<u64>,
@@ -540,10 +966,477 @@ pub struct SandboxLimits {
 }
 
 /// Session persistence settings.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct SessionsConfig {
     #[serde(default)]
     pub enabled: bool,
     #[serde(default)]
     pub path: Option<String>,
+    /// Take over the workspace lock even while another process holds it.
+    #[serde(default)]
+    pub force_lock: bool,
+}
+
+/// Shared artifact store settings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct ArtifactsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// Recurring headless runs executed by the scheduler.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct SchedulesConfig {
+    /// File storing jobs registered at runtime; kept in memory when unset.
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub jobs: Vec<ScheduledJobConfig>,
+}
+
+/// A prompt run on a cron schedule.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ScheduledJobConfig {
+    pub id: String,
+    /// Cron expression (`min hour day month weekday`, or `@daily` style) in local time.
+    pub cron: String,
+    pub prompt: String,
+    /// Agent to run; the default agent when unset.
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// LLM to run with; the agent's default when unset.
+    #[serde(default)]
+    pub llm: Option<String>,
+    #[serde(default = "default_job_enabled")]
+    pub enabled: bool,
+}
+
+/// Scheduled jobs are enabled unless turned off.
+fn default_job_enabled() -> bool {
+    true
+}
+
+/// Run started by an inbound webhook event.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct TriggerConfig {
+    /// Prompt template; `{{event}}`, `{{payload}}`, and `{{payload.field.path}}` are filled in.
+    pub prompt: String,
+    /// Event names that start a run, e.g. `issues.opened`; any event when empty.
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Agent to run; the default agent when unset.
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// LLM to run with; the agent's default when unset.
+    #[serde(default)]
+    pub llm: Option<String>,
+    /// Approval profile applied to the triggered session.
+    #[serde(default = "default_trigger_profile")]
+    pub profile: ApprovalPolicyProfile,
+    /// Runs of this trigger allowed at once; further events are rejected.
+    #[serde(default = "default_trigger_max_concurrent")]
+    pub max_concurrent: usize,
+}
+
+/// Nobody answers approvals for webhook runs.
+fn default_trigger_profile() -> ApprovalPolicyProfile {
+    ApprovalPolicyProfile::CiSafe
+}
+
+/// One run per trigger at a time by default.
+fn default_trigger_max_concurrent() -> usize {
+    1
+}
+
+/// Redaction and upload settings for shared session bundles.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+pub struct SharingConfig {
+    /// Extra regular expressions redacted from shared bundles.
+    #[serde(default)]
+    pub redact_patterns: Vec<String>,
+    /// Endpoint that hosts uploaded bundles; bundles stay local when unset.
+    #[serde(default)]
+    pub upload: Option<ShareUploadConfig>,
+}
+
+/// Remote endpoint that stores shared bundles and returns their public URL.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ShareUploadConfig {
+    pub url: String,
+    #[serde(default = "default_share_upload_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// Default time to wait for an upload.
+fn default_share_upload_timeout_secs() -> u64 {
+    30
+}
+
+/// Content guardrails scanning agent output and tool arguments.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GuardrailsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Rules to enforce; replaces the built-in rules when set.
+    #[serde(default = "default_guardrail_rules")]
+    pub rules: Vec<GuardrailRule>,
+}
+
+impl Default for GuardrailsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rules: default_guardrail_rules(),
+        }
+    }
+}
+
+/// Spending limits enforced from provider-reported token usage.
+///
+/// Limits are in USD and unset limits do not apply. Spend is priced with
+/// `models`; usage of models without a price costs nothing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct BudgetConfig {
+    /// Most one session may spend across all models.
+    #[serde(default)]
+    pub max_session_usd: Option<f64>,
+    /// Most all sessions may spend per UTC day.
+    #[serde(default)]
+    pub max_daily_usd: Option<f64>,
+    /// Fraction of a limit at which a `BudgetWarning` is emitted (unset = 0.8).
+    #[serde(default)]
+    pub warn_ratio: Option<f64>,
+    /// Prices and limits per model, keyed by `provider/name`.
+    #[serde(default)]
+    pub models: BTreeMap<String, ModelBudgetConfig>,
+    /// File keeping spend across restarts, relative to the workspace (unset = `.odyssey/budget.json`).
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// Price and limits of one model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ModelBudgetConfig {
+    /// USD per million prompt tokens.
+    #[serde(default)]
+    pub input_usd_per_mtok: f64,
+    /// USD per million prompt tokens served from cache (unset = input price).
+    #[serde(default)]
+    pub cached_input_usd_per_mtok: Option<f64>,
+    /// USD per million completion tokens.
+    #[serde(default)]
+    pub output_usd_per_mtok: f64,
+    /// Most one session may spend on this model.
+    #[serde(default)]
+    pub max_session_usd: Option<f64>,
+    /// Most all sessions may spend on this model per UTC day.
+    #[serde(default)]
+    pub max_daily_usd: Option<f64>,
+}
+
+/// Blocklist rule matched against guardrail targets.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct GuardrailRule {
+    pub name: String,
+    /// Regular expressions; any match triggers the rule.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Case-insensitive substrings; any match triggers the rule.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default = "default_guardrail_targets")]
+    pub targets: Vec<GuardrailTarget>,
+}
+
+fn default_guardrail_targets() -> Vec<GuardrailTarget> {
+    vec![GuardrailTarget::AgentOutput, GuardrailTarget::ToolArguments]
+}
+
+/// Built-in rules against destructive commands and secret exfiltration.
+fn default_guardrail_rules() -> Vec<GuardrailRule> {
+    vec![
+        GuardrailRule {
+            name: "destructive-rm".to_string(),
+            patterns: vec![
+                r"\brm\s+-[a-zA-Z]*(?:rf|fr)[a-zA-Z]*\s+(?:--\s+)?/(?:\*|\s|$)".to_string(),
+            ],
+            keywords: Vec::new(),
+            targets: vec![GuardrailTarget::ToolArguments],
+        },
+        GuardrailRule {
+            name: "env-file-access".to_string(),
+            patterns: vec![r#"(?:^|[\s/"'=])\.env(?:\.local)?(?:$|[\s"';|&>])"#.to_string()],
+            keywords: Vec::new(),
+            targets: vec![GuardrailTarget::ToolArguments],
+        },
+        GuardrailRule {
+            name: "private-key".to_string(),
+            patterns: vec![r"-----BEGIN [A-Z ]*PRIVATE KEY-----".to_string()],
+            keywords: Vec::new(),
+            targets: default_guardrail_targets(),
+        },
+    ]
+}
+
+/// Reusable prompt expanded from a slash command such as `/review <file>`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct PromptPresetConfig {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Prompt text; `{{name}}` inserts an argument and `{{args}}` the full argument string.
+    pub template: String,
+    /// Positional argument names; the last one takes the remaining text.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// System prompt sections selected by a user-defined prompt profile.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct PromptProfileConfig {
+    /// Sections to include, in render order.
+    pub sections: Vec<PromptSectionName>,
+    /// Include workspace bootstrap files (AGENTS.md, SOUL.md, ...) with the instructions.
+    #[serde(default)]
+    pub bootstrap_files: bool,
+    /// Guidance appended to the identity section.
+    #[serde(default)]
+    pub guidance: Option<String>,
+}
+
+/// Section of a built system prompt.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptSectionName {
+    Identity,
+    Instructions,
+    Skills,
+    Environment,
+    Memory,
+}
+
+/// Current date and time, time zone, locale, and units given to agents.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct LocaleConfig {
+    /// Add the context to turn metadata and the prompt environment section.
+    #[serde(default = "default_locale_enabled")]
+    pub enabled: bool,
+    /// `local`, `UTC`, or a fixed offset such as `+05:30` (unset = local).
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// BCP 47 tag such as `en-GB` (unset = from `LC_ALL`, `LC_TIME`, or `LANG`).
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Measurement units (unset = imperial for US locales, metric otherwise).
+    #[serde(default)]
+    pub units: Option<MeasurementUnits>,
+}
+
+impl Default for LocaleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_locale_enabled(),
+            timezone: None,
+            locale: None,
+            units: None,
+        }
+    }
+}
+
+fn default_locale_enabled() -> bool {
+    true
+}
+
+/// Unit system agents should use for measurements.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MeasurementUnits {
+    Metric,
+    Imperial,
+}
+
+/// Terminal UI settings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct UiConfig {
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+}
+
+/// Notifications raised by the TUI while the terminal is in the background.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct NotificationsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub method: NotificationMethod,
+    /// Events that raise a notification.
+    #[serde(default = "default_notification_events")]
+    pub events: Vec<NotificationEvent>,
+    /// Skip notifications while the terminal has focus.
+    #[serde(default = "default_only_when_unfocused")]
+    pub only_when_unfocused: bool,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            method: NotificationMethod::default(),
+            events: default_notification_events(),
+            only_when_unfocused: default_only_when_unfocused(),
+        }
+    }
+}
+
+/// Delivery mechanism for TUI notifications.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationMethod {
+    /// Desktop notification locally, OSC 9 over SSH or when unavailable.
+    #[default]
+    Auto,
+    /// Native desktop notification.
+    Desktop,
+    /// OSC 9 terminal escape sequence.
+    Osc9,
+    /// Terminal bell.
+    Bell,
+}
+
+/// Events that can raise a TUI notification.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// A turn finished.
+    TurnCompleted,
+    /// A turn failed.
+    Error,
+    /// A tool call is waiting for approval.
+    ApprovalRequested,
+}
+
+/// Notify on every event by default.
+fn default_notification_events() -> Vec<NotificationEvent> {
+    vec![
+        NotificationEvent::TurnCompleted,
+        NotificationEvent::Error,
+        NotificationEvent::ApprovalRequested,
+    ]
+}
+
+/// Only notify while the terminal is unfocused by default.
+fn default_only_when_unfocused() -> bool {
+    true
+}
+
+/// Model catalog settings layered over the built-in catalog.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct ModelsConfig {
+    #[serde(default)]
+    pub catalog: Vec<ModelCatalogEntry>,
+    /// Directory of downloaded GGUF models (unset = `~/.odyssey/models`).
+    #[serde(default)]
+    pub cache_path: Option<String>,
+}
+
+/// Capabilities of a single model.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ModelCatalogEntry {
+    pub provider: String,
+    pub name: String,
+    pub context_window: usize,
+    #[serde(default)]
+    pub max_output_tokens: Option<usize>,
+    #[serde(default = "default_supports_tools")]
+    pub supports_tools: bool,
+    #[serde(default)]
+    pub supports_vision: bool,
+    /// Average characters per token, used to estimate this model's token counts.
+    #[serde(default)]
+    pub chars_per_token: Option<usize>,
+}
+
+impl ModelCatalogEntry {
+    /// Tokens available for the prompt after reserving room for output.
+    pub fn input_token_budget(&self) -> usize {
+        self.context_window
+            .saturating_sub(self.max_output_tokens.unwrap_or(0))
+    }
+}
+
+/// Default tool support for catalog entries.
+fn default_supports_tools() -> bool {
+    true
+}
+
+/// Settings for a single LLM provider.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+pub struct ProviderConfig {
+    /// Secret reference for the API key (`env:NAME`, `file:NAME`, or `keyring:NAME`).
+    #[serde(default)]
+    pub api_key_ref: Option<String>,
+}
+
+/// Backends used to resolve `api_key_ref` secret references.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct SecretsConfig {
+    /// JSON file mapping names to secrets, read for `file:NAME` references.
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Keyring service that `keyring:NAME` entries are stored under.
+    #[serde(default = "default_keyring_service")]
+    pub keyring_service: String,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            file: None,
+            keyring_service: default_keyring_service(),
+        }
+    }
+}
+
+/// Server API settings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+pub struct ServerConfig {
+    /// API tokens accepted by the server.
+    #[serde(default)]
+    pub tokens: Vec<ApiTokenConfig>,
+    /// Accept unauthenticated requests as an anonymous admin when no tokens are configured.
+    #[serde(default)]
+    pub open: bool,
+}
+
+/// API token granting a user a role on the server API.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ApiTokenConfig {
+    /// Secret reference holding the token (`env:NAME`, `file:NAME`, or `keyring:NAME`).
+    pub token_ref: String,
+    /// User the token authenticates as.
+    pub user_id: String,
+    /// What the token may do.
+    pub role: ServerRole,
+}
+
+/// Access level of a server API token; each role includes the ones before it.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerRole {
+    /// List sessions and read their events.
+    Viewer,
+    /// Also create sessions, submit turns, and resolve approvals.
+    Operator,
+    /// Also change configuration at runtime.
+    Admin,
+}
+
+/// Default keyring service name.
+fn default_keyring_service() -> String {
+    "odyssey".to_string()
 }
@@ -0,0 +1,166 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use odyssey_rs_config::{
+    ApprovalPolicyProfile, ApprovalTimeoutDecision, PermissionAction, PermissionMode,
+    PermissionRule, PermissionsConfig,
+};
+use odyssey_rs_core::state::{JsonlStateStore, MessageRecord, StateStore};
+use odyssey_rs_core::{
+    AsyncEventSink, BufferedEventSink, EventSink, OverflowPolicy, PermissionEngine,
+};
+use odyssey_rs_protocol::{EventMsg, EventPayload, PathAccess, PermissionRequest};
+use odyssey_rs_tools::{PermissionChecker, PermissionContext};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+const FANOUT_EVENTS: u64 = 10_000;
+const PERMISSION_RULES: usize = 1_000;
+const SESSION_MESSAGES: usize = 10_000;
+
+#[derive(Default)]
+struct CountingSink {
+    delivered: AtomicU64,
+}
+
+#[async_trait]
+impl AsyncEventSink for CountingSink {
+    async fn emit(&self, _event: EventMsg) {
+        self.delivered.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn delta_event(session_id: Uuid, turn_id: Uuid) -> EventMsg {
+    EventMsg {
+        id: Uuid::new_v4(),
+        session_id,
+        seq: 0,
+        user_id: None,
+        created_at: Utc::now(),
+        payload: EventPayload::AgentMessageDelta {
+            turn_id,
+            delta: "token ".to_string(),
+        },
+    }
+}
+
+fn bench_event_fanout(criterion: &mut Criterion) {
+    let runtime = Runtime::new().expect("tokio runtime");
+    let counter = Arc::new(CountingSink::default());
+    let sink = BufferedEventSink::new(counter.clone(), 1024, OverflowPolicy::Block)
+        .expect("buffered sink");
+    let (session_id, turn_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let mut group = criterion.benchmark_group("event_fanout");
+    group.throughput(Throughput::Elements(FANOUT_EVENTS));
+    group.bench_function("buffered_sink_10k_deltas", |bencher| {
+        bencher.iter(|| {
+            for _ in 0..FANOUT_EVENTS {
+                sink.emit(delta_event(session_id, turn_id));
+            }
+            runtime.block_on(sink.flush());
+        });
+    });
+    group.finish();
+    assert_eq!(sink.dropped(), 0);
+}
+
+fn bench_permission_matching(criterion: &mut Criterion) {
+    let runtime = Runtime::new().expect("tokio runtime");
+    // Every request scans all deny rules before reaching the allow rule.
+    let mut rules = (0..PERMISSION_RULES - 1)
+        .map(|index| PermissionRule {
+            action: PermissionAction::Deny,
+            tool: None,
+            path: Some(format!("vendor/pkg{index}/**")),
+            command: None,
+            access: None,
+            git_push: None,
+            source: None,
+        })
+        .collect::<Vec<_>>();
+    rules.push(PermissionRule {
+        action: PermissionAction::Allow,
+        tool: None,
+        path: Some("src/**".to_string()),
+        command: None,
+        access: None,
+        git_push: None,
+        source: None,
+    });
+    let engine = PermissionEngine::new(PermissionsConfig {
+        mode: PermissionMode::Default,
+        rules,
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
+    })
+    .expect("permission engine");
+    let ctx = PermissionContext {
+        session_id: Uuid::nil(),
+        agent_id: "agent".to_string(),
+        tool_name: None,
+        turn_id: None,
+    };
+
+    criterion.bench_function("permission_match_1k_rules", |bencher| {
+        bencher.iter(|| {
+            let outcome = runtime
+                .block_on(engine.authorize(
+                    &ctx,
+                    PermissionRequest::Path {
+                        path: "src/orchestrator/mod.rs".to_string(),
+                        mode: PathAccess::Read,
+                    },
+                ))
+                .expect("authorize");
+            assert!(outcome.allowed);
+        });
+    });
+}
+
+fn bench_session_load(criterion: &mut Criterion) {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let store = JsonlStateStore::new(temp.path()).expect("state store");
+    let session_id = Uuid::new_v4();
+    store
+        .record_session(session_id, "agent", None, Utc::now())
+        .expect("record session");
+    for index in 0..SESSION_MESSAGES {
+        let role = if index % 2 == 0 { "user" } else { "assistant" };
+        store
+            .append_message(
+                session_id,
+                &MessageRecord {
+                    role: role.to_string(),
+                    content: format!("message {index}: {}", "lorem ipsum ".repeat(16)),
+                    created_at: Utc::now(),
+                    turn_id: None,
+                    superseded: false,
+                    user_id: None,
+                },
+            )
+            .expect("append message");
+    }
+
+    criterion.bench_function("session_load_10k_messages", |bencher| {
+        bencher.iter(|| {
+            let session = store
+                .load_session(session_id)
+                .expect("load session")
+                .expect("session exists");
+            assert_eq!(session.messages.len(), SESSION_MESSAGES);
+        });
+    });
+}
+
+criterion_group!(
+    orchestrator_benches,
+    bench_event_fanout,
+    bench_permission_matching,
+    bench_session_load
+);
+criterion_main!(orchestrator_benches);
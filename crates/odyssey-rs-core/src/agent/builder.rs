@@ -15,6 +15,7 @@ use odyssey_rs_memory::MemoryProvider;
 use serde_json::Value;
 
 use crate::agent::AgentInstance;
+use crate::orchestrator::prompt::BuiltPrompt;
 use crate::types::{AgentID, OdysseyAgentRuntime};
 
 #[derive(Clone)]
@@ -23,6 +24,7 @@ pub struct AgentBuilder<T> {
     inner: Arc<T>,
     tool_policy: ToolPolicy,
     memory_provider: Arc<dyn MemoryProvider>,
+    prompt_sections: Option<BuiltPrompt>,
 }
 
 impl<T> std::fmt::Debug for AgentBuilder<T>
@@ -34,6 +36,7 @@ where
             .field("id", &self.id)
             .field("inner", &self.inner)
             .field("tool_policy", &self.tool_policy)
+            .field("prompt_sections", &self.prompt_sections)
             .finish()
     }
 }
@@ -53,9 +56,24 @@ where
             inner: agent,
             tool_policy: ToolPolicy::allow_all(),
             memory_provider,
+            prompt_sections: None,
         }
     }
 
+    /// Restrict the tools this agent may call.
+    pub fn with_tool_policy(mut self, tool_policy: ToolPolicy) -> Self {
+        self.tool_policy = tool_policy;
+        self
+    }
+
+    /// Record the sections the agent's system prompt was built from, so
+    /// [`Orchestrator::effective_prompt`](crate::Orchestrator::effective_prompt)
+    /// can attribute each part of it.
+    pub fn with_prompt_sections(mut self, sections: BuiltPrompt) -> Self {
+        self.prompt_sections = Some(sections);
+        self
+    }
+
     /// Return the configured agent id.
     pub fn id(&self) -> &str {
         &self.id
@@ -66,6 +84,11 @@ where
         &self.tool_policy
     }
 
+    /// Return the recorded system prompt sections.
+    fn prompt_sections(&self) -> Option<&BuiltPrompt> {
+        self.prompt_sections.as_ref()
+    }
+
     /// Return the memory provider for this default agent.
     fn memory_provider(&self) -> Arc<dyn MemoryProvider> {
         self.memory_provider.clone()
@@ -189,6 +212,10 @@ where
     fn memory_provider(&self) -> Arc<dyn MemoryProvider> {
         self.memory_provider()
     }
+
+    fn prompt_sections(&self) -> Option<BuiltPrompt> {
+        self.prompt_sections().cloned()
+    }
 }
 
 #[cfg(test)]
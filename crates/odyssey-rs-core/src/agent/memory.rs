@@ -28,6 +28,8 @@ pub struct OdysseyMemoryAdapter {
     recall_limit: Option<usize>,
     max_ephemeral: usize,
     ephemeral: VecDeque<MemoryRecord>,
+    /// Stored records from `prefetch`, with the limit they were fetched for.
+    prefetched: Option<(usize, Vec<MemoryRecord>)>,
 }
 
 impl OdysseyMemoryAdapter {
@@ -54,9 +56,30 @@ impl OdysseyMemoryAdapter {
             recall_limit,
             max_ephemeral,
             ephemeral: VecDeque::new(),
+            prefetched: None,
         }
     }
 
+    /// Recall stored history once before the turn starts.
+    ///
+    /// Later recalls in the turn reuse these records instead of querying the
+    /// provider again, so callers can size the prompt from what the agent sends.
+    pub async fn prefetch(&mut self) -> Result<Vec<ChatMessage>, LLMError> {
+        let limit = self.recall_limit.unwrap_or(0);
+        if limit > 0 {
+            let stored = self.fetch_stored(limit).await?;
+            self.prefetched = Some((limit, stored));
+        }
+        self.recall("", None).await
+    }
+
+    async fn fetch_stored(&self, limit: usize) -> Result<Vec<MemoryRecord>, LLMError> {
+        self.provider
+            .recall_with_options(self.session_id, None, limit, self.recall_options)
+            .await
+            .map_err(|err| LLMError::ProviderError(err.to_string()))
+    }
+
     fn should_persist_message(&self, message: &ChatMessage) -> bool {
         if is_tool_result_message(message) {
             return self.capture_policy.capture_tool_output;
@@ -167,12 +190,15 @@ impl AutoAgentsMemoryProvider for OdysseyMemoryAdapter {
         let limit = limit.or(self.recall_limit).unwrap_or(0);
         let mut records = Vec::new();
         if limit > 0 {
-            let fetch_limit = limit.saturating_add(self.ephemeral.len());
-            let stored = self
-                .provider
-                .recall_with_options(self.session_id, None, fetch_limit, self.recall_options)
-                .await
-                .map_err(|err| LLMError::ProviderError(err.to_string()))?;
+            // Prefetched records predate every ephemeral one, so the newest
+            // `limit` of them still cover the window after new messages arrive.
+            let stored = match &self.prefetched {
+                Some((fetched, stored)) if *fetched >= limit => stored.clone(),
+                _ => {
+                    self.fetch_stored(limit.saturating_add(self.ephemeral.len()))
+                        .await?
+                }
+            };
             let cutoff = self.ephemeral.front().map(|record| record.created_at);
             records = if let Some(cutoff) = cutoff {
                 stored
@@ -367,6 +393,7 @@ mod tests {
     #[derive(Default)]
     struct RecordingProvider {
         records: Mutex<Vec<MemoryRecord>>,
+        recalls: Mutex<usize>,
     }
 
     #[async_trait::async_trait]
@@ -382,6 +409,7 @@ mod tests {
             _query: Option<&str>,
             _limit: usize,
         ) -> Result<Vec<MemoryRecord>, odyssey_rs_memory::MemoryError> {
+            *self.recalls.lock() += 1;
             Ok(self
                 .records
                 .lock()
@@ -462,6 +490,52 @@ mod tests {
         assert_eq!(stored[0].metadata["kind"], json!("message"));
     }
 
+    #[tokio::test]
+    async fn recall_reuses_prefetched_history() {
+        let session_id = Uuid::new_v4();
+        let provider = Arc::new(RecordingProvider::default());
+        let mut earlier = OdysseyMemoryAdapter::new(
+            session_id,
+            "agent".to_string(),
+            provider.clone(),
+            MemoryCapturePolicy::default(),
+            MemoryCompactionPolicy::default(),
+            MemoryRecallOptions::default(),
+            Some(5),
+        );
+        let message = |content: &str| ChatMessage {
+            role: ChatRole::User,
+            message_type: MessageType::Text,
+            content: content.to_string(),
+        };
+        earlier
+            .remember(&message("earlier"))
+            .await
+            .expect("remember");
+
+        let mut adapter = OdysseyMemoryAdapter::new(
+            session_id,
+            "agent".to_string(),
+            provider.clone(),
+            MemoryCapturePolicy::default(),
+            MemoryCompactionPolicy::default(),
+            MemoryRecallOptions::default(),
+            Some(5),
+        );
+        *provider.recalls.lock() = 0;
+        let prefetched = adapter.prefetch().await.expect("prefetch");
+        assert_eq!(prefetched.len(), 1);
+
+        adapter.remember(&message("now")).await.expect("remember");
+        let recalled = adapter.recall("now", None).await.expect("recall");
+        let contents = recalled
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(contents, vec!["earlier", "now"]);
+        assert_eq!(*provider.recalls.lock(), 1);
+    }
+
     #[test]
     fn helper_functions_cover_tool_call_paths() {
         let call = tool_call("Write", "");
@@ -10,6 +10,7 @@ use odyssey_rs_config::ToolPolicy;
 use odyssey_rs_memory::MemoryProvider;
 use std::{fmt::Debug, sync::Arc};
 
+use crate::orchestrator::prompt::BuiltPrompt;
 use crate::types::OdysseyAgentRuntime;
 
 pub mod builder;
@@ -25,6 +26,11 @@ pub trait AgentInstance: OdysseyAgentRuntime {
 
     /// Memory provider used to persist and recall session state.
     fn memory_provider(&self) -> Arc<dyn MemoryProvider>;
+
+    /// Sections the system prompt was built from, if recorded.
+    fn prompt_sections(&self) -> Option<BuiltPrompt> {
+        None
+    }
 }
 
 /// Odyssey agent wrapper used by the AutoAgents runtime.
@@ -0,0 +1,207 @@
+//! Approval handler that forwards requests to an HTTP webhook.
+//!
+//! Each approval request is POSTed as JSON to the configured URL. The endpoint
+//! answers with `{ "decision": "allow_once" | "allow_always" | "deny" }`.
+//! Transport errors, non-success statuses, malformed bodies, and timeouts all
+//! resolve to the configured fallback decision.
+
+use crate::error::OdysseyCoreError;
+use crate::permissions::{ApprovalHandler, ApprovalRequest};
+use async_trait::async_trait;
+use log::{info, warn};
+use odyssey_rs_config::{ApprovalWebhookConfig, WebhookFallback};
+use odyssey_rs_protocol::ApprovalDecision;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Response body expected from the webhook.
+#[derive(Debug, Deserialize)]
+struct WebhookDecision {
+    decision: ApprovalDecision,
+}
+
+/// Approval handler that routes decisions through an HTTP endpoint.
+#[derive(Debug, Clone)]
+pub struct WebhookApprovalHandler {
+    client: reqwest::Client,
+    url: String,
+    timeout: Duration,
+    fallback: ApprovalDecision,
+}
+
+impl WebhookApprovalHandler {
+    /// Build a handler from permissions config.
+    pub fn from_config(config: &ApprovalWebhookConfig) -> Result<Self, OdysseyCoreError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|err| {
+                OdysseyCoreError::Permission(format!("invalid webhook header {name}: {err}"))
+            })?;
+            let value = HeaderValue::from_str(value).map_err(|err| {
+                OdysseyCoreError::Permission(format!("invalid webhook header value: {err}"))
+            })?;
+            headers.insert(name, value);
+        }
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|err| OdysseyCoreError::Permission(err.to_string()))?;
+        let fallback = match config.fallback {
+            WebhookFallback::Deny => ApprovalDecision::Deny,
+            WebhookFallback::Allow => ApprovalDecision::AllowOnce,
+        };
+        Ok(Self {
+            client,
+            url: config.url.clone(),
+            timeout: Duration::from_secs(config.timeout_secs),
+            fallback,
+        })
+    }
+
+    /// POST the request and parse the decision.
+    async fn post(&self, request: &ApprovalRequest) -> Result<ApprovalDecision, String> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(request)
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("webhook returned {status}"));
+        }
+        let body: WebhookDecision = response.json().await.map_err(|err| err.to_string())?;
+        Ok(body.decision)
+    }
+}
+
+#[async_trait]
+impl ApprovalHandler for WebhookApprovalHandler {
+    async fn request_approval(&self, request: ApprovalRequest) -> ApprovalDecision {
+        info!(
+            "forwarding approval to webhook (request_id={})",
+            request.request_id
+        );
+        match tokio::time::timeout(self.timeout, self.post(&request)).await {
+            Ok(Ok(decision)) => decision,
+            Ok(Err(err)) => {
+                warn!(
+                    "approval webhook failed; using fallback (request_id={}, fallback={:?}): {}",
+                    request.request_id, self.fallback, err
+                );
+                self.fallback
+            }
+            Err(_) => {
+                warn!(
+                    "approval webhook timed out; using fallback (request_id={}, fallback={:?})",
+                    request.request_id, self.fallback
+                );
+                self.fallback
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WebhookApprovalHandler;
+    use crate::permissions::{ApprovalHandler, ApprovalRequest};
+    use odyssey_rs_config::{ApprovalWebhookConfig, WebhookFallback};
+    use odyssey_rs_protocol::{ApprovalDecision, PermissionAction, PermissionRequest, ToolRisk};
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use uuid::Uuid;
+
+    /// Serve a single HTTP response and return the captured request text.
+    async fn serve_once(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let url = format!("http://{}/approve", listener.local_addr().expect("addr"));
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.expect("accept");
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            loop {
+                let read = socket.read(&mut buffer).await.expect("read");
+                request.extend_from_slice(&buffer[..read]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())
+                                .flatten()
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+                if read == 0 {
+                    break;
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.expect("write");
+            String::from_utf8_lossy(&request).to_string()
+        });
+        (url, handle)
+    }
+
+    fn config(url: String, fallback: WebhookFallback) -> ApprovalWebhookConfig {
+        ApprovalWebhookConfig {
+            url,
+            timeout_secs: 5,
+            fallback,
+            headers: HashMap::from([("x-team".to_string(), "infra".to_string())]),
+        }
+    }
+
+    fn request() -> ApprovalRequest {
+        ApprovalRequest {
+            request_id: Uuid::new_v4(),
+            session_id: Uuid::nil(),
+            agent_id: "agent".to_string(),
+            turn_id: None,
+            action: PermissionAction::Ask,
+            request: PermissionRequest::Tool {
+                name: "Bash".to_string(),
+            },
+            risk: Some(ToolRisk::Destructive),
+        }
+    }
+
+    #[tokio::test]
+    async fn webhook_decision_is_returned() {
+        let (url, server) = serve_once("200 OK", r#"{"decision":"allow_always"}"#).await;
+        let handler = WebhookApprovalHandler::from_config(&config(url, WebhookFallback::Deny))
+            .expect("handler");
+        let decision = handler.request_approval(request()).await;
+        assert_eq!(decision, ApprovalDecision::AllowAlways);
+        let captured = server.await.expect("server").to_ascii_lowercase();
+        assert!(captured.contains("x-team: infra"));
+        assert!(captured.contains("\"agent_id\":\"agent\""));
+        assert!(captured.contains("\"risk\":\"destructive\""));
+    }
+
+    #[tokio::test]
+    async fn webhook_failures_use_fallback() {
+        let (url, server) = serve_once("500 Internal Server Error", "{}").await;
+        let handler = WebhookApprovalHandler::from_config(&config(url, WebhookFallback::Allow))
+            .expect("handler");
+        let decision = handler.request_approval(request()).await;
+        assert_eq!(decision, ApprovalDecision::AllowOnce);
+        server.await.expect("server");
+    }
+}
@@ -0,0 +1,221 @@
+//! Workspace-scoped artifact store shared across sessions.
+//!
+//! Artifacts are stored as files under `<root>/files/<id>` with a JSON index at
+//! `<root>/index.json`. The index is re-read on every operation so artifacts
+//! published by other sessions or processes are visible immediately.
+
+use crate::error::OdysseyCoreError;
+use chrono::Utc;
+use log::{debug, info};
+use odyssey_rs_protocol::{
+    ArtifactProvider, ArtifactPublish, ArtifactSummary, ToolError, artifact_name,
+};
+use parking_lot::Mutex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+const INDEX_FILE: &str = "index.json";
+const FILES_DIR: &str = "files";
+
+/// File-backed store for named artifacts.
+#[derive(Debug)]
+pub struct ArtifactStore {
+    root: PathBuf,
+    /// Serializes index updates within this process.
+    write_lock: Mutex<()>,
+}
+
+impl ArtifactStore {
+    /// Open (or create) an artifact store rooted at `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self, OdysseyCoreError> {
+        let root = root.into();
+        fs::create_dir_all(root.join(FILES_DIR))?;
+        info!("artifact store ready (root={})", root.display());
+        Ok(Self {
+            root,
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    /// Return the store root directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// List artifacts sorted by name.
+    pub fn list(&self) -> Result<Vec<ArtifactSummary>, OdysseyCoreError> {
+        let mut index = self.read_index()?;
+        index.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(index)
+    }
+
+    /// Publish an artifact, replacing any previous artifact with the same name.
+    pub fn publish(&self, request: ArtifactPublish) -> Result<ArtifactSummary, OdysseyCoreError> {
+        let name = request.name.trim().to_string();
+        if name.is_empty() {
+            return Err(OdysseyCoreError::Artifact(
+                "artifact name cannot be empty".to_string(),
+            ));
+        }
+        let _guard = self.write_lock.lock();
+        let summary = ArtifactSummary {
+            id: Uuid::new_v4(),
+            name,
+            description: request.description,
+            media_type: request.media_type,
+            size: request.content.len() as u64,
+            session_id: request.session_id,
+            created_at: Utc::now(),
+            metadata: request.metadata,
+        };
+        fs::write(self.content_path(summary.id), &request.content)?;
+
+        let mut index = self.read_index()?;
+        if let Some(position) = index.iter().position(|entry| entry.name == summary.name) {
+            let previous = index.remove(position);
+            if let Err(err) = fs::remove_file(self.content_path(previous.id)) {
+                debug!("failed to remove replaced artifact content: {err}");
+            }
+        }
+        index.push(summary.clone());
+        self.write_index(&index)?;
+        info!(
+            "published artifact (name={}, size={})",
+            summary.name, summary.size
+        );
+        Ok(summary)
+    }
+
+    /// Fetch an artifact and its content by name or `artifact://` reference.
+    pub fn get(&self, name: &str) -> Result<(ArtifactSummary, Vec<u8>), OdysseyCoreError> {
+        let name = artifact_name(name);
+        let summary = self
+            .read_index()?
+            .into_iter()
+            .find(|entry| entry.name == name)
+            .ok_or_else(|| OdysseyCoreError::Artifact(format!("unknown artifact: {name}")))?;
+        let content = fs::read(self.content_path(summary.id))?;
+        Ok((summary, content))
+    }
+
+    /// Delete an artifact by name or reference. Returns false if it did not exist.
+    pub fn delete(&self, name: &str) -> Result<bool, OdysseyCoreError> {
+        let name = artifact_name(name);
+        let _guard = self.write_lock.lock();
+        let mut index = self.read_index()?;
+        let Some(position) = index.iter().position(|entry| entry.name == name) else {
+            return Ok(false);
+        };
+        let removed = index.remove(position);
+        self.write_index(&index)?;
+        let path = self.content_path(removed.id);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        info!("deleted artifact (name={})", name);
+        Ok(true)
+    }
+
+    fn content_path(&self, id: Uuid) -> PathBuf {
+        self.root.join(FILES_DIR).join(id.to_string())
+    }
+
+    fn read_index(&self) -> Result<Vec<ArtifactSummary>, OdysseyCoreError> {
+        let path = self.root.join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let data = fs::read_to_string(path)?;
+        serde_json::from_str(&data).map_err(|err| OdysseyCoreError::Artifact(err.to_string()))
+    }
+
+    fn write_index(&self, index: &[ArtifactSummary]) -> Result<(), OdysseyCoreError> {
+        let data = serde_json::to_string_pretty(index)
+            .map_err(|err| OdysseyCoreError::Artifact(err.to_string()))?;
+        let tmp = self.root.join(format!("{INDEX_FILE}.tmp"));
+        fs::write(&tmp, data)?;
+        fs::rename(tmp, self.root.join(INDEX_FILE))?;
+        Ok(())
+    }
+}
+
+impl ArtifactProvider for ArtifactStore {
+    fn list(&self) -> Result<Vec<ArtifactSummary>, ToolError> {
+        ArtifactStore::list(self).map_err(|err| ToolError::ExecutionFailed(err.to_string()))
+    }
+
+    fn publish(&self, request: ArtifactPublish) -> Result<ArtifactSummary, ToolError> {
+        ArtifactStore::publish(self, request)
+            .map_err(|err| ToolError::ExecutionFailed(err.to_string()))
+    }
+
+    fn get(&self, name: &str) -> Result<(ArtifactSummary, Vec<u8>), ToolError> {
+        ArtifactStore::get(self, name).map_err(|err| ToolError::ExecutionFailed(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArtifactStore;
+    use odyssey_rs_protocol::ArtifactPublish;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::tempdir;
+    use uuid::Uuid;
+
+    fn publish_request(name: &str, content: &str) -> ArtifactPublish {
+        ArtifactPublish {
+            name: name.to_string(),
+            content: content.as_bytes().to_vec(),
+            media_type: Some("text/plain".to_string()),
+            metadata: json!({ "kind": "log" }),
+            ..ArtifactPublish::default()
+        }
+    }
+
+    #[test]
+    fn publish_replaces_and_is_visible_to_other_stores() {
+        let temp = tempdir().expect("tempdir");
+        let store = ArtifactStore::new(temp.path()).expect("store");
+        let first = store
+            .publish(publish_request("build-log", "v1"))
+            .expect("publish");
+        let second = store
+            .publish(ArtifactPublish {
+                session_id: Some(Uuid::new_v4()),
+                ..publish_request("build-log", "v2")
+            })
+            .expect("publish");
+        assert_eq!(first.id == second.id, false);
+
+        let reopened = ArtifactStore::new(temp.path()).expect("reopen");
+        let list = reopened.list().expect("list");
+        assert_eq!(list, vec![second.clone()]);
+        let (summary, content) = reopened.get(&second.reference()).expect("get");
+        assert_eq!(summary.size, 2);
+        assert_eq!(content, b"v2".to_vec());
+        assert_eq!(
+            temp.path()
+                .join("files")
+                .join(first.id.to_string())
+                .exists(),
+            false
+        );
+    }
+
+    #[test]
+    fn rejects_empty_names_and_unknown_artifacts() {
+        let temp = tempdir().expect("tempdir");
+        let store = ArtifactStore::new(temp.path()).expect("store");
+        assert!(store.publish(publish_request("  ", "data")).is_err());
+        assert!(store.get("missing").is_err());
+        assert_eq!(store.delete("missing").expect("delete"), false);
+
+        store
+            .publish(publish_request("report", "data"))
+            .expect("publish");
+        assert_eq!(store.delete("artifact://report").expect("delete"), true);
+        assert_eq!(store.list().expect("list").is_empty(), true);
+    }
+}
@@ -0,0 +1,111 @@
+//! Crash-safe file replacement, quarantine of corrupt JSONL lines, and
+//! cross-process file locks.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Replace `path` with `contents` through a synced temp file and a rename, so
+/// a crash leaves either the old or the new file.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp = with_suffix(path, "tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+    sync_parent(path);
+    Ok(())
+}
+
+/// Append unreadable `lines` of `path` to `<path>.corrupt` and return its path.
+pub(crate) fn quarantine_lines(path: &Path, lines: &[&[u8]]) -> io::Result<PathBuf> {
+    let quarantine = with_suffix(path, "corrupt");
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&quarantine)?;
+    for line in lines {
+        file.write_all(line)?;
+        file.write_all(b"\n")?;
+    }
+    file.sync_all()?;
+    Ok(quarantine)
+}
+
+/// Block until this process holds the exclusive lock of `<path>.lock`.
+///
+/// The lock is advisory and released when the returned file is dropped.
+pub(crate) fn lock_exclusive(path: &Path) -> io::Result<File> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(with_suffix(path, "lock"))?;
+    file.lock()?;
+    Ok(file)
+}
+
+/// Split `contents` into its newline-terminated lines and the unterminated tail.
+pub(crate) fn split_complete(contents: &[u8]) -> (impl Iterator<Item = &[u8]>, &[u8]) {
+    let complete = contents
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |pos| pos + 1);
+    let lines = contents[..complete]
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.trim_ascii().is_empty());
+    (lines, &contents[complete..])
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Persist the rename itself; best effort because not every filesystem allows it.
+#[cfg(unix)]
+fn sync_parent(path: &Path) {
+    if let Some(parent) = path.parent()
+        && let Ok(dir) = File::open(parent)
+    {
+        let _ = dir.sync_all();
+    }
+}
+
+/// Directories cannot be synced on this platform.
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) {}
+
+#[cfg(test)]
+mod tests {
+    use super::{quarantine_lines, split_complete, write_atomic};
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn replaces_files_and_appends_quarantine() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("data.jsonl");
+        write_atomic(&path, b"old\n").expect("write");
+        write_atomic(&path, b"new\n").expect("replace");
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), "new\n");
+        assert_eq!(temp.path().join("data.jsonl.tmp").exists(), false);
+
+        quarantine_lines(&path, &[b"bad"]).expect("quarantine");
+        let quarantine = quarantine_lines(&path, &[b"worse"]).expect("quarantine");
+        assert_eq!(quarantine, temp.path().join("data.jsonl.corrupt"));
+        assert_eq!(
+            std::fs::read_to_string(quarantine).expect("read"),
+            "bad\nworse\n"
+        );
+    }
+
+    #[test]
+    fn splits_complete_lines_from_tail() {
+        let (lines, tail) = split_complete(b"a\n\nb\npartial");
+        assert_eq!(lines.collect::<Vec<_>>(), vec![&b"a"[..], &b"b"[..]]);
+        assert_eq!(tail, b"partial");
+    }
+}
@@ -0,0 +1,136 @@
+//! File attachments for user messages, such as `@path` mentions in the TUI.
+
+use crate::error::OdysseyCoreError;
+use odyssey_rs_protocol::Attachment;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Largest file attached in full; larger files are attached as an excerpt.
+pub const MAX_ATTACHMENT_BYTES: usize = 64 * 1024;
+/// Lines kept in the excerpt of a large file.
+const EXCERPT_LINES: usize = 200;
+
+/// Read a workspace file into an attachment.
+///
+/// `path` is resolved against `root` and must stay inside it. Files over
+/// [`MAX_ATTACHMENT_BYTES`] are cut to their first lines with a note giving
+/// the full size.
+pub fn read_attachment(root: &Path, path: &str) -> Result<Attachment, OdysseyCoreError> {
+    let root = root.canonicalize()?;
+    let resolved = root.join(path).canonicalize()?;
+    if !resolved.starts_with(&root) {
+        return Err(OdysseyCoreError::Parse(format!(
+            "attachment {path} is outside the workspace"
+        )));
+    }
+    let bytes = std::fs::read(&resolved)?;
+    let text = String::from_utf8(bytes)
+        .map_err(|_| OdysseyCoreError::Parse(format!("attachment {path} is not a text file")))?;
+    if text.len() <= MAX_ATTACHMENT_BYTES {
+        return Ok(Attachment {
+            path: path.to_string(),
+            content: text,
+            truncated: false,
+        });
+    }
+    let mut excerpt = String::new();
+    for line in text.lines().take(EXCERPT_LINES) {
+        if excerpt.len() + line.len() >= MAX_ATTACHMENT_BYTES {
+            break;
+        }
+        excerpt.push_str(line);
+        excerpt.push('\n');
+    }
+    let total_lines = text.lines().count();
+    let kept_lines = excerpt.lines().count();
+    let _ = write!(
+        excerpt,
+        "[excerpt: first {kept_lines} of {total_lines} lines, {} bytes total]",
+        text.len()
+    );
+    Ok(Attachment {
+        path: path.to_string(),
+        content: excerpt,
+        truncated: true,
+    })
+}
+
+/// Append attachments to the user input as tagged blocks.
+pub fn render_with_attachments(input: &str, attachments: &[Attachment]) -> String {
+    let mut rendered = input.to_string();
+    for attachment in attachments {
+        let truncated = if attachment.truncated {
+            " truncated=\"true\""
+        } else {
+            ""
+        };
+        let _ = write!(
+            rendered,
+            "\n\n<attachment path=\"{}\"{truncated}>\n{}\n</attachment>",
+            attachment.path,
+            attachment.content.trim_end_matches('\n')
+        );
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_ATTACHMENT_BYTES, read_attachment, render_with_attachments};
+    use odyssey_rs_protocol::Attachment;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn renders_attachments_after_the_input() {
+        let attachments = vec![
+            Attachment {
+                path: "src/lib.rs".to_string(),
+                content: "fn main() {}\n".to_string(),
+                truncated: false,
+            },
+            Attachment {
+                path: "big.log".to_string(),
+                content: "line 1".to_string(),
+                truncated: true,
+            },
+        ];
+        assert_eq!(
+            render_with_attachments("explain @src/lib.rs", &attachments),
+            "explain @src/lib.rs\n\n<attachment path=\"src/lib.rs\">\nfn main() {}\n</attachment>\n\n<attachment path=\"big.log\" truncated=\"true\">\nline 1\n</attachment>"
+        );
+        assert_eq!(render_with_attachments("hi", &[]), "hi");
+    }
+
+    #[test]
+    fn large_files_are_attached_as_excerpts() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("small.txt"), "hello\n").expect("write");
+        let line = "x".repeat(99);
+        let big = format!("{line}\n").repeat(MAX_ATTACHMENT_BYTES / 100 + 10);
+        std::fs::write(temp.path().join("big.txt"), &big).expect("write");
+
+        let small = read_attachment(temp.path(), "small.txt").expect("small");
+        assert_eq!(small.content, "hello\n");
+        assert_eq!(small.truncated, false);
+
+        let excerpt = read_attachment(temp.path(), "big.txt").expect("big");
+        assert_eq!(excerpt.truncated, true);
+        assert_eq!(excerpt.content.lines().count(), 201);
+        assert!(excerpt.content.ends_with(&format!(
+            "[excerpt: first 200 of {} lines, {} bytes total]",
+            MAX_ATTACHMENT_BYTES / 100 + 10,
+            big.len()
+        )));
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_workspace() {
+        let temp = tempdir().expect("tempdir");
+        let workspace = temp.path().join("workspace");
+        std::fs::create_dir_all(&workspace).expect("dir");
+        std::fs::write(temp.path().join("secret.txt"), "secret").expect("write");
+        assert!(read_attachment(&workspace, "../secret.txt").is_err());
+        assert!(read_attachment(&workspace, "missing.txt").is_err());
+    }
+}
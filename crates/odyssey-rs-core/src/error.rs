@@ -1,6 +1,9 @@
 //! Error types for the core orchestrator crate.
 
 use crate::types::SessionId;
+use odyssey_rs_protocol::{BudgetLimit, ToolError, TurnId};
+use odyssey_rs_tools::SecretError;
+use std::path::PathBuf;
 use thiserror::Error;
 
 /// Errors returned by orchestrator operations.
@@ -9,9 +12,18 @@ pub enum OdysseyCoreError {
     /// Session id is unknown to the orchestrator.
     #[error("unknown session: {0}")]
     UnknownSession(SessionId),
+    /// Session is already running a turn.
+    #[error("session busy: {0}")]
+    SessionBusy(SessionId),
     /// Agent id is unknown to the orchestrator.
     #[error("unknown agent: {0}")]
     UnknownAgent(String),
+    /// Prompt preset name is not configured.
+    #[error("unknown prompt preset: {0}")]
+    UnknownPromptPreset(String),
+    /// Prompt profile is neither built in nor configured.
+    #[error("unknown prompt profile: {0}")]
+    UnknownPromptProfile(String),
     /// Permission enforcement failed.
     #[error("permission error: {0}")]
     Permission(String),
@@ -21,6 +33,29 @@ pub enum OdysseyCoreError {
     /// State store error.
     #[error("state error: {0}")]
     State(String),
+    /// Prompt is too large for the model's context window.
+    #[error(
+        "context window exceeded for {model}: ~{estimated_tokens} prompt tokens, limit {limit}"
+    )]
+    ContextWindowExceeded {
+        model: String,
+        estimated_tokens: usize,
+        limit: usize,
+    },
+    /// A spending limit is reached; `Orchestrator::allow_budget_overrun` lets one more turn run.
+    #[error("budget exceeded: {0}")]
+    BudgetExceeded(BudgetLimit),
+    /// Offline mode rejects an LLM that was not registered as local.
+    #[error(
+        "offline mode allows only local LLM providers; {0} was not registered with register_local_llm_provider"
+    )]
+    OfflineProvider(String),
+    /// Orchestrator is shutting down and rejects new turns.
+    #[error("orchestrator is shutting down")]
+    ShuttingDown,
+    /// Turn stopped by an iteration limit, loop detection, or shutdown.
+    #[error("turn aborted: {0}")]
+    TurnAborted(String),
     /// Agent execution error.
     #[error("executor error: {0}")]
     Executor(String),
@@ -30,7 +65,52 @@ pub enum OdysseyCoreError {
     /// IO error.
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
+    /// Artifact store error.
+    #[error("artifact error: {0}")]
+    Artifact(String),
+    /// Lifecycle hook rejected the turn.
+    #[error("hook error: {0}")]
+    Hook(String),
+    /// Record/replay error.
+    #[error("replay error: {0}")]
+    Replay(String),
+    /// Output schema is invalid or the response never matched it.
+    #[error("output schema error: {0}")]
+    OutputSchema(String),
+    /// Turn id is not part of the session transcript.
+    #[error("unknown turn: {0}")]
+    UnknownTurn(TurnId),
+    /// Session has no user turn that can be regenerated.
+    #[error("nothing to regenerate in session: {0}")]
+    NothingToRegenerate(SessionId),
+    /// Tool called outside a turn is unknown to the agent or failed.
+    #[error("tool error: {0}")]
+    Tool(ToolError),
+    /// Trigger id is not configured.
+    #[error("unknown trigger: {0}")]
+    UnknownTrigger(String),
+    /// Trigger is already running its maximum number of events.
+    #[error("trigger busy: {0}")]
+    TriggerBusy(String),
+    /// Scheduled job is invalid, unknown, or cannot be changed.
+    #[error("schedule error: {0}")]
+    Schedule(String),
+    /// Session bundle could not be rendered or uploaded.
+    #[error("share error: {0}")]
+    Share(String),
+    /// Local model could not be listed, downloaded, verified, or deleted.
+    #[error("model manager error: {0}")]
+    ModelManager(String),
+    /// Another Odyssey process holds the workspace lock.
+    #[error(
+        "workspace is in use by another Odyssey process (pid {pid}); stop it or pass --force to take over {}",
+        path.display()
+    )]
+    WorkspaceLocked { pid: u32, path: PathBuf },
     /// Config or parsing error.
     #[error("parse error: {0}")]
     Parse(String),
+    /// Secret reference could not be resolved.
+    #[error("secret error: {0}")]
+    Secret(#[from] SecretError),
 }
@@ -0,0 +1,434 @@
+//! Buffered dispatch from the synchronous [`EventSink`] to an [`AsyncEventSink`].
+//!
+//! The orchestrator emits events synchronously and never waits on listeners.
+//! [`BufferedEventSink`] queues those events and hands them, one at a time and
+//! in order, to an async sink on a dedicated dispatcher thread. When the queue
+//! is full the [`OverflowPolicy`] decides whether to drop the oldest event,
+//! block the emitter, or spill events to a file until the sink catches up.
+
+use crate::error::OdysseyCoreError;
+use log::{debug, warn};
+use odyssey_rs_protocol::{AsyncEventSink, EventMsg, EventSink};
+use parking_lot::{Condvar, Mutex};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// What [`BufferedEventSink`] does with an event that arrives while its queue is full.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drop the oldest queued event to make room; counted by [`BufferedEventSink::dropped`].
+    DropOldest,
+    /// Block the emitting thread until the sink frees a slot.
+    ///
+    /// Async callers should use [`BufferedEventSink::emit_async`], which waits on
+    /// a blocking thread. A synchronous `emit` on a multi-threaded tokio worker
+    /// waits in `block_in_place`; on a current-thread runtime it stalls that runtime.
+    Block,
+    /// Append events to a JSON lines file and replay them, in order, once the queue drains.
+    SpillToDisk { path: PathBuf },
+}
+
+/// [`EventSink`] that delivers every event to an [`AsyncEventSink`] in order.
+///
+/// Dropping the sink stops intake; events already queued or spilled are still
+/// delivered before the dispatcher thread exits.
+pub struct BufferedEventSink {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    state: Mutex<DispatchState>,
+    changed: Condvar,
+    capacity: usize,
+    overflow: OverflowPolicy,
+}
+
+#[derive(Default)]
+struct DispatchState {
+    queue: VecDeque<EventMsg>,
+    /// Open spill file for `SpillToDisk`.
+    spill: Option<File>,
+    /// Events written to the spill file and not yet delivered.
+    spilled: usize,
+    /// Events taken by the dispatcher and not yet delivered.
+    in_flight: usize,
+    dropped: u64,
+    /// Set when the sink is dropped.
+    closed: bool,
+    /// Set when the dispatcher thread has exited.
+    stopped: bool,
+}
+
+impl DispatchState {
+    fn is_idle(&self) -> bool {
+        self.queue.is_empty() && self.spilled == 0 && self.in_flight == 0
+    }
+}
+
+impl BufferedEventSink {
+    /// Start a dispatcher that buffers up to `capacity` events in memory.
+    ///
+    /// The async sink runs on its own thread with a single-threaded tokio
+    /// runtime, so `Block` never stalls the runtime that drives the sink.
+    pub fn new(
+        sink: Arc<dyn AsyncEventSink>,
+        capacity: usize,
+        overflow: OverflowPolicy,
+    ) -> Result<Self, OdysseyCoreError> {
+        let mut state = DispatchState::default();
+        if let OverflowPolicy::SpillToDisk { path } = &overflow {
+            let file = OpenOptions::new()
+                .create(true)
+                .read(true)
+                .write(true)
+                .truncate(true)
+                .open(path)?;
+            state.spill = Some(file);
+        }
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let shared = Arc::new(Shared {
+            state: Mutex::new(state),
+            changed: Condvar::new(),
+            capacity: capacity.max(1),
+            overflow,
+        });
+        let dispatcher = shared.clone();
+        std::thread::Builder::new()
+            .name("odyssey-event-dispatch".to_string())
+            .spawn(move || {
+                let _stopped = StopGuard(dispatcher.clone());
+                while let Some(batch) = dispatcher.next_batch() {
+                    for event in batch {
+                        runtime.block_on(sink.emit(event));
+                    }
+                    let mut state = dispatcher.state.lock();
+                    state.in_flight = 0;
+                    dispatcher.changed.notify_all();
+                }
+                debug!("event dispatcher stopped");
+            })?;
+        debug!(
+            "event dispatcher started (capacity={}, overflow={:?})",
+            shared.capacity, shared.overflow
+        );
+        Ok(Self { shared })
+    }
+
+    /// Events dropped because the queue overflowed, a spill failed, or the dispatcher stopped.
+    pub fn dropped(&self) -> u64 {
+        self.shared.state.lock().dropped
+    }
+
+    /// Queue an event from async code; under `Block` the wait for a slot runs on a blocking thread.
+    pub async fn emit_async(&self, event: EventMsg) {
+        let shared = self.shared.clone();
+        if let Err(err) = tokio::task::spawn_blocking(move || shared.push(event)).await {
+            warn!("event emit failed: {err}");
+        }
+    }
+
+    /// Wait until every event emitted so far has been delivered.
+    pub async fn flush(&self) {
+        let shared = self.shared.clone();
+        let waited = tokio::task::spawn_blocking(move || {
+            let mut state = shared.state.lock();
+            while !state.is_idle() && !state.stopped {
+                shared.changed.wait(&mut state);
+            }
+        })
+        .await;
+        if let Err(err) = waited {
+            warn!("event flush failed: {err}");
+        }
+    }
+}
+
+impl EventSink for BufferedEventSink {
+    /// Queue an event, applying the overflow policy when the queue is full.
+    fn emit(&self, event: EventMsg) {
+        // Waiting for a slot must not park a tokio worker that other tasks need.
+        let on_worker = tokio::runtime::Handle::try_current().is_ok_and(|handle| {
+            handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread
+        });
+        if self.shared.overflow == OverflowPolicy::Block && on_worker {
+            tokio::task::block_in_place(|| self.shared.push(event));
+        } else {
+            self.shared.push(event);
+        }
+    }
+}
+
+impl Drop for BufferedEventSink {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock();
+        state.closed = true;
+        self.shared.changed.notify_all();
+    }
+}
+
+impl Shared {
+    /// Queue an event, applying the overflow policy when the queue is full.
+    fn push(&self, event: EventMsg) {
+        let mut state = self.state.lock();
+        if state.stopped {
+            state.dropped += 1;
+            return;
+        }
+        match &self.overflow {
+            OverflowPolicy::DropOldest => {
+                if state.queue.len() >= self.capacity {
+                    state.queue.pop_front();
+                    state.dropped += 1;
+                    debug!("event queue full; dropped oldest event");
+                }
+                state.queue.push_back(event);
+            }
+            OverflowPolicy::Block => {
+                while state.queue.len() >= self.capacity && !state.stopped {
+                    self.changed.wait(&mut state);
+                }
+                if state.stopped {
+                    state.dropped += 1;
+                    return;
+                }
+                state.queue.push_back(event);
+            }
+            OverflowPolicy::SpillToDisk { .. } => {
+                // Once spilling starts, later events follow them to disk to keep order.
+                if state.spilled == 0 && state.queue.len() < self.capacity {
+                    state.queue.push_back(event);
+                } else {
+                    match spill_event(&mut state, &event) {
+                        Ok(()) => state.spilled += 1,
+                        Err(err) => {
+                            warn!("failed to spill event {}: {err}", event.id);
+                            state.dropped += 1;
+                        }
+                    }
+                }
+            }
+        }
+        self.changed.notify_all();
+    }
+
+    /// Wait for the next events to deliver, or `None` once closed and drained.
+    fn next_batch(&self) -> Option<Vec<EventMsg>> {
+        let mut state = self.state.lock();
+        loop {
+            if let Some(event) = state.queue.pop_front() {
+                state.in_flight = 1;
+                self.changed.notify_all();
+                return Some(vec![event]);
+            }
+            if state.spilled > 0 {
+                let expected = state.spilled;
+                state.spilled = 0;
+                match drain_spill(&mut state) {
+                    Ok(events) => {
+                        if events.len() < expected {
+                            state.dropped += (expected - events.len()) as u64;
+                        }
+                        state.in_flight = events.len();
+                        return Some(events);
+                    }
+                    Err(err) => {
+                        warn!("failed to read spilled events: {err}");
+                        state.dropped += expected as u64;
+                        continue;
+                    }
+                }
+            }
+            if state.closed {
+                return None;
+            }
+            self.changed.wait(&mut state);
+        }
+    }
+}
+
+/// Marks the dispatcher stopped when its thread exits, even by panic, so emitters never block on it.
+struct StopGuard(Arc<Shared>);
+
+impl Drop for StopGuard {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock();
+        state.stopped = true;
+        state.dropped += (state.queue.len() + state.spilled) as u64;
+        state.queue.clear();
+        state.spilled = 0;
+        state.in_flight = 0;
+        self.0.changed.notify_all();
+    }
+}
+
+fn spill_event(state: &mut DispatchState, event: &EventMsg) -> std::io::Result<()> {
+    let Some(file) = state.spill.as_mut() else {
+        return Err(std::io::Error::other("no spill file"));
+    };
+    let mut line = serde_json::to_vec(event)?;
+    line.push(b'\n');
+    file.seek(SeekFrom::End(0))?;
+    file.write_all(&line)
+}
+
+/// Read every spilled event and empty the spill file.
+fn drain_spill(state: &mut DispatchState) -> std::io::Result<Vec<EventMsg>> {
+    let Some(file) = state.spill.as_mut() else {
+        return Ok(Vec::new());
+    };
+    file.seek(SeekFrom::Start(0))?;
+    let mut events = Vec::new();
+    for line in BufReader::new(&mut *file).lines() {
+        match serde_json::from_str(&line?) {
+            Ok(event) => events.push(event),
+            Err(err) => warn!("skipping corrupt spilled event: {err}"),
+        }
+    }
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BufferedEventSink, OverflowPolicy};
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use odyssey_rs_protocol::{AsyncEventSink, EventMsg, EventPayload, EventSink};
+    use parking_lot::Mutex;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::Semaphore;
+    use uuid::Uuid;
+
+    /// Async sink that records deltas, waiting for a permit per event when gated.
+    #[derive(Default)]
+    struct RecordingSink {
+        received: Mutex<Vec<String>>,
+        gate: Option<Arc<Semaphore>>,
+    }
+
+    #[async_trait]
+    impl AsyncEventSink for RecordingSink {
+        async fn emit(&self, event: EventMsg) {
+            if let Some(gate) = &self.gate
+                && let Ok(permit) = gate.acquire().await
+            {
+                permit.forget();
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            if let EventPayload::AgentMessageDelta { delta, .. } = event.payload {
+                self.received.lock().push(delta);
+            }
+        }
+    }
+
+    fn delta(index: usize) -> EventMsg {
+        EventMsg {
+            id: Uuid::new_v4(),
+            session_id: Uuid::nil(),
+            seq: 0,
+            user_id: None,
+            created_at: Utc::now(),
+            payload: EventPayload::AgentMessageDelta {
+                turn_id: Uuid::nil(),
+                delta: index.to_string(),
+            },
+        }
+    }
+
+    fn expected(range: std::ops::Range<usize>) -> Vec<String> {
+        range.map(|index| index.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn block_and_spill_deliver_every_event_in_order() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        for overflow in [
+            OverflowPolicy::Block,
+            OverflowPolicy::SpillToDisk {
+                path: temp.path().join("spill.jsonl"),
+            },
+        ] {
+            let recorder = Arc::new(RecordingSink::default());
+            let sink =
+                BufferedEventSink::new(recorder.clone(), 2, overflow.clone()).expect("dispatcher");
+            for index in 0..50 {
+                sink.emit(delta(index));
+            }
+            sink.flush().await;
+            assert_eq!(*recorder.received.lock(), expected(0..50), "{overflow:?}");
+            assert_eq!(sink.dropped(), 0);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn block_does_not_starve_other_tasks_on_the_worker() {
+        let gate = Arc::new(Semaphore::new(0));
+        let recorder = Arc::new(RecordingSink {
+            gate: Some(gate.clone()),
+            ..RecordingSink::default()
+        });
+        let sink = Arc::new(
+            BufferedEventSink::new(recorder.clone(), 1, OverflowPolicy::Block).expect("dispatcher"),
+        );
+        let emitter = {
+            let sink = sink.clone();
+            tokio::spawn(async move {
+                for index in 0..5 {
+                    sink.emit(delta(index));
+                }
+            })
+        };
+        // Only runs if the blocked emitter has handed the single worker back.
+        let releaser = tokio::spawn(async move { gate.add_permits(6) });
+        releaser.await.expect("releaser");
+        emitter.await.expect("emitter");
+
+        let async_emitter = {
+            let sink = sink.clone();
+            tokio::spawn(async move { sink.emit_async(delta(5)).await })
+        };
+        async_emitter.await.expect("async emitter");
+        sink.flush().await;
+        assert_eq!(*recorder.received.lock(), expected(0..6));
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_keeps_the_newest_events() {
+        let gate = Arc::new(Semaphore::new(0));
+        let recorder = Arc::new(RecordingSink {
+            gate: Some(gate.clone()),
+            ..RecordingSink::default()
+        });
+        let sink = BufferedEventSink::new(recorder.clone(), 3, OverflowPolicy::DropOldest)
+            .expect("dispatcher");
+        sink.emit(delta(0));
+        // Wait until the dispatcher holds event 0, so the queue only has later events.
+        while sink.shared.state.lock().in_flight == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        for index in 1..10 {
+            sink.emit(delta(index));
+        }
+        gate.add_permits(10);
+        sink.flush().await;
+
+        assert_eq!(
+            *recorder.received.lock(),
+            vec![
+                "0".to_string(),
+                "7".to_string(),
+                "8".to_string(),
+                "9".to_string()
+            ]
+        );
+        assert_eq!(sink.dropped(), 6);
+    }
+}
@@ -0,0 +1,157 @@
+//! Lifecycle hooks invoked around turns and tool calls.
+
+use crate::error::OdysseyCoreError;
+use crate::types::SessionId;
+use async_trait::async_trait;
+use odyssey_rs_protocol::{EventSink, ToolError, TurnId};
+use odyssey_rs_tools::{ToolCallHook, ToolContext, ToolHookDecision};
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Turn state passed to lifecycle hooks.
+#[derive(Clone)]
+pub struct TurnHookContext {
+    /// Session running the turn.
+    pub session_id: SessionId,
+    /// Agent handling the turn.
+    pub agent_id: String,
+    /// Turn id, shared with emitted events.
+    pub turn_id: TurnId,
+    /// User input; `before_turn` may rewrite it.
+    pub input: String,
+    /// Object merged into the turn context metadata.
+    pub metadata: Value,
+    /// Sink receiving the turn's events, for hooks that report their own.
+    pub event_sink: Option<Arc<dyn EventSink>>,
+}
+
+/// Hook for non-permission concerns such as logging, billing, or guardrails.
+///
+/// Every method has a no-op default, so implementations only override the
+/// callbacks they need. Hooks run in registration order.
+#[async_trait]
+pub trait LifecycleHook: Send + Sync {
+    /// Called before the turn starts; an error rejects the turn.
+    async fn before_turn(&self, _ctx: &mut TurnHookContext) -> Result<(), OdysseyCoreError> {
+        Ok(())
+    }
+
+    /// Called with the final response before it is saved; an error fails the turn.
+    async fn review_response(
+        &self,
+        _ctx: &TurnHookContext,
+        _response: &str,
+    ) -> Result<(), OdysseyCoreError> {
+        Ok(())
+    }
+
+    /// Called after the turn finished with its response or error.
+    async fn after_turn(&self, _ctx: &TurnHookContext, _result: Result<&str, &OdysseyCoreError>) {}
+
+    /// Called before a tool runs; may rewrite arguments or veto the call.
+    async fn before_tool(
+        &self,
+        _ctx: &ToolContext,
+        _tool_name: &str,
+        _args: &mut Value,
+    ) -> ToolHookDecision {
+        ToolHookDecision::Continue
+    }
+
+    /// Called after a tool returned, before output policies apply.
+    async fn after_tool(
+        &self,
+        _ctx: &ToolContext,
+        _tool_name: &str,
+        _args: &Value,
+        _result: &Result<Value, ToolError>,
+    ) {
+    }
+}
+
+/// Registered lifecycle hooks shared by the orchestrator and its turns.
+#[derive(Clone, Default)]
+pub(crate) struct LifecycleHooks {
+    hooks: Arc<RwLock<Vec<Arc<dyn LifecycleHook>>>>,
+}
+
+impl LifecycleHooks {
+    /// Register a hook after the existing ones.
+    pub(crate) fn add(&self, hook: Arc<dyn LifecycleHook>) {
+        self.hooks.write().push(hook);
+    }
+
+    /// Return true when no hooks are registered.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.hooks.read().is_empty()
+    }
+
+    fn snapshot(&self) -> Vec<Arc<dyn LifecycleHook>> {
+        self.hooks.read().clone()
+    }
+
+    /// Run `before_turn` on every hook, stopping at the first error.
+    pub(crate) async fn before_turn(
+        &self,
+        ctx: &mut TurnHookContext,
+    ) -> Result<(), OdysseyCoreError> {
+        for hook in self.snapshot() {
+            hook.before_turn(ctx).await?;
+        }
+        Ok(())
+    }
+
+    /// Run `review_response` on every hook, stopping at the first error.
+    pub(crate) async fn review_response(
+        &self,
+        ctx: &TurnHookContext,
+        response: &str,
+    ) -> Result<(), OdysseyCoreError> {
+        for hook in self.snapshot() {
+            hook.review_response(ctx, response).await?;
+        }
+        Ok(())
+    }
+
+    /// Run `after_turn` on every hook.
+    pub(crate) async fn after_turn(
+        &self,
+        ctx: &TurnHookContext,
+        result: Result<&str, &OdysseyCoreError>,
+    ) {
+        for hook in self.snapshot() {
+            hook.after_turn(ctx, result).await;
+        }
+    }
+}
+
+#[async_trait]
+impl ToolCallHook for LifecycleHooks {
+    async fn before_tool(
+        &self,
+        ctx: &ToolContext,
+        tool_name: &str,
+        args: &mut Value,
+    ) -> ToolHookDecision {
+        for hook in self.snapshot() {
+            let decision = hook.before_tool(ctx, tool_name, args).await;
+            if let ToolHookDecision::Veto { .. } = decision {
+                return decision;
+            }
+        }
+        ToolHookDecision::Continue
+    }
+
+    async fn after_tool(
+        &self,
+        ctx: &ToolContext,
+        tool_name: &str,
+        args: &Value,
+        result: &Result<Value, ToolError>,
+    ) {
+        for hook in self.snapshot() {
+            hook.after_tool(ctx, tool_name, args, result).await;
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Import transcripts from other agent CLIs into Odyssey sessions.
+
+use crate::error::OdysseyCoreError;
+use crate::types::{Message, Role};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Maximum characters kept for tool arguments and results, matching live sessions.
+const MAX_TOOL_LOG_CHARS: usize = 2000;
+
+/// Transcript format accepted by `Orchestrator::import_session`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionImportFormat {
+    /// Claude Code project JSONL (`~/.claude/projects/<project>/<session>.jsonl`).
+    ClaudeCode,
+    /// Codex rollout JSONL (`~/.codex/sessions/.../rollout-*.jsonl`).
+    Codex,
+}
+
+/// Convert a JSONL transcript into session messages.
+///
+/// User and assistant text become messages with the same roles. Tool calls
+/// become system messages in the `tool <name>` / `args:` / `result:` layout the
+/// runtime records for live sessions. Entries without text, such as reasoning
+/// or metadata records, are skipped.
+pub fn parse_transcript(
+    text: &str,
+    format: SessionImportFormat,
+) -> Result<Vec<Message>, OdysseyCoreError> {
+    let mut transcript = Transcript::default();
+    for (idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: Value = serde_json::from_str(line).map_err(|err| {
+            OdysseyCoreError::Parse(format!("transcript line {}: {err}", idx + 1))
+        })?;
+        match format {
+            SessionImportFormat::ClaudeCode => transcript.push_claude_code(&entry),
+            SessionImportFormat::Codex => transcript.push_codex(&entry),
+        }
+    }
+    if transcript.messages.is_empty() {
+        return Err(OdysseyCoreError::Parse(
+            "transcript contains no messages".to_string(),
+        ));
+    }
+    Ok(transcript.messages)
+}
+
+/// Messages collected so far and the tool calls still waiting for a result.
+#[derive(Default)]
+struct Transcript {
+    messages: Vec<Message>,
+    /// Tool call id to the index of its system message.
+    pending_tools: HashMap<String, usize>,
+}
+
+impl Transcript {
+    /// Handle one Claude Code entry: `{ type, timestamp, message: { role, content } }`.
+    fn push_claude_code(&mut self, entry: &Value) {
+        let role = match entry["type"].as_str() {
+            Some("user") => Role::User,
+            Some("assistant") => Role::Assistant,
+            _ => return,
+        };
+        let created_at = timestamp(entry);
+        match &entry["message"]["content"] {
+            Value::String(text) => self.push_text(role, text, created_at),
+            Value::Array(blocks) => {
+                for block in blocks {
+                    match block["type"].as_str() {
+                        Some("text") => {
+                            self.push_text(role.clone(), str_field(block, "text"), created_at)
+                        }
+                        Some("tool_use") => self.push_tool_call(
+                            str_field(block, "id"),
+                            str_field(block, "name"),
+                            &block["input"],
+                            created_at,
+                        ),
+                        Some("tool_result") => self.push_tool_result(
+                            str_field(block, "tool_use_id"),
+                            &block["content"],
+                            created_at,
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle one Codex entry, either wrapped as `{ type: "response_item", payload }`
+    /// or a bare response item from older rollouts.
+    fn push_codex(&mut self, entry: &Value) {
+        let item = match entry["type"].as_str() {
+            Some("response_item") => &entry["payload"],
+            _ => entry,
+        };
+        let created_at = timestamp(entry);
+        match item["type"].as_str() {
+            Some("message") => {
+                let role = match item["role"].as_str() {
+                    Some("user") => Role::User,
+                    Some("assistant") => Role::Assistant,
+                    _ => return,
+                };
+                let text = match &item["content"] {
+                    Value::Array(blocks) => blocks
+                        .iter()
+                        .filter(|block| {
+                            matches!(block["type"].as_str(), Some("input_text" | "output_text"))
+                        })
+                        .map(|block| str_field(block, "text"))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    Value::String(text) => text.clone(),
+                    _ => String::new(),
+                };
+                self.push_text(role, &text, created_at);
+            }
+            Some("function_call") => {
+                // Codex encodes arguments as a JSON string.
+                let args = item["arguments"]
+                    .as_str()
+                    .and_then(|raw| serde_json::from_str(raw).ok())
+                    .unwrap_or_else(|| item["arguments"].clone());
+                self.push_tool_call(
+                    str_field(item, "call_id"),
+                    str_field(item, "name"),
+                    &args,
+                    created_at,
+                );
+            }
+            Some("function_call_output") => {
+                let output = match &item["output"] {
+                    Value::Object(map) => map.get("content").cloned().unwrap_or(Value::Null),
+                    other => other.clone(),
+                };
+                self.push_tool_result(str_field(item, "call_id"), &output, created_at);
+            }
+            _ => {}
+        }
+    }
+
+    fn push_text(&mut self, role: Role, text: &str, created_at: DateTime<Utc>) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        self.messages.push(Message {
+            role,
+            content: text.to_string(),
+            created_at,
+            turn_id: None,
+            superseded: false,
+            user_id: None,
+        });
+    }
+
+    fn push_tool_call(&mut self, id: &str, name: &str, args: &Value, created_at: DateTime<Utc>) {
+        let args = truncate(&serde_json::to_string(args).unwrap_or_default());
+        if !id.is_empty() {
+            self.pending_tools
+                .insert(id.to_string(), self.messages.len());
+        }
+        self.messages.push(Message {
+            role: Role::System,
+            content: format!("tool {name}\nargs: {args}"),
+            created_at,
+            turn_id: None,
+            superseded: false,
+            user_id: None,
+        });
+    }
+
+    fn push_tool_result(&mut self, id: &str, content: &Value, created_at: DateTime<Utc>) {
+        let result = truncate(&result_text(content));
+        match self.pending_tools.remove(id) {
+            Some(index) => {
+                if !result.is_empty() {
+                    let message = &mut self.messages[index];
+                    message.content.push_str("\nresult: ");
+                    message.content.push_str(&result);
+                }
+            }
+            None => self.push_text(
+                Role::System,
+                &format!("tool result\nresult: {result}"),
+                created_at,
+            ),
+        }
+    }
+}
+
+/// Flatten tool result content: plain strings, text blocks, or raw JSON.
+fn result_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        Value::Array(blocks) if blocks.iter().all(|block| block["text"].is_string()) => blocks
+            .iter()
+            .map(|block| str_field(block, "text"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => serde_json::to_string(other).unwrap_or_default(),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_TOOL_LOG_CHARS {
+        text.to_string()
+    } else {
+        let truncated: String = text.chars().take(MAX_TOOL_LOG_CHARS).collect();
+        format!("{truncated}…")
+    }
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value[key].as_str().unwrap_or_default()
+}
+
+/// RFC 3339 `timestamp` of an entry, or now when missing.
+fn timestamp(entry: &Value) -> DateTime<Utc> {
+    entry["timestamp"]
+        .as_str()
+        .and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SessionImportFormat, parse_transcript};
+    use crate::types::Role;
+    use pretty_assertions::assert_eq;
+
+    fn summarize(text: &str, format: SessionImportFormat) -> Vec<(Role, String)> {
+        parse_transcript(text, format)
+            .expect("transcript")
+            .into_iter()
+            .map(|message| (message.role, message.content))
+            .collect()
+    }
+
+    #[test]
+    fn parses_claude_code_transcripts() {
+        let text = r#"{"type":"summary","summary":"Fix tests"}
+{"type":"user","timestamp":"2025-06-01T10:00:00Z","message":{"role":"user","content":"list files"}}
+{"type":"assistant","timestamp":"2025-06-01T10:00:01Z","message":{"role":"assistant","content":[{"type":"thinking","thinking":"..."},{"type":"text","text":"Listing."},{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"ls"}}]}}
+{"type":"user","timestamp":"2025-06-01T10:00:02Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":[{"type":"text","text":"Cargo.toml"}]}]}}
+{"type":"assistant","timestamp":"2025-06-01T10:00:03Z","message":{"role":"assistant","content":[{"type":"text","text":"One file."}]}}
+"#;
+        assert_eq!(
+            summarize(text, SessionImportFormat::ClaudeCode),
+            vec![
+                (Role::User, "list files".to_string()),
+                (Role::Assistant, "Listing.".to_string()),
+                (
+                    Role::System,
+                    "tool Bash\nargs: {\"command\":\"ls\"}\nresult: Cargo.toml".to_string()
+                ),
+                (Role::Assistant, "One file.".to_string()),
+            ]
+        );
+        let messages = parse_transcript(text, SessionImportFormat::ClaudeCode).expect("transcript");
+        assert_eq!(
+            messages[0].created_at.to_rfc3339(),
+            "2025-06-01T10:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn parses_codex_rollouts() {
+        let text = r#"{"timestamp":"2025-06-01T10:00:00Z","type":"session_meta","payload":{"id":"abc"}}
+{"timestamp":"2025-06-01T10:00:01Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"run tests"}]}}
+{"timestamp":"2025-06-01T10:00:02Z","type":"response_item","payload":{"type":"reasoning","summary":[]}}
+{"timestamp":"2025-06-01T10:00:03Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"cargo\",\"test\"]}","call_id":"call_1"}}
+{"timestamp":"2025-06-01T10:00:04Z","type":"response_item","payload":{"type":"function_call_output","call_id":"call_1","output":"ok"}}
+{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Tests pass."}]}
+"#;
+        assert_eq!(
+            summarize(text, SessionImportFormat::Codex),
+            vec![
+                (Role::User, "run tests".to_string()),
+                (
+                    Role::System,
+                    "tool shell\nargs: {\"command\":[\"cargo\",\"test\"]}\nresult: ok".to_string()
+                ),
+                (Role::Assistant, "Tests pass.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_or_empty_transcripts() {
+        let err = parse_transcript("{}\nnot json", SessionImportFormat::Codex).expect_err("json");
+        assert!(err.to_string().contains("line 2"));
+        assert!(parse_transcript("{}\n", SessionImportFormat::ClaudeCode).is_err());
+    }
+}
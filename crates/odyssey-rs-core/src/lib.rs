@@ -3,13 +3,32 @@
 //! This crate owns the orchestrator, session handling, permissions, and agent
 //! runtime integration used by the server and SDK.
 
+#[cfg(feature = "webhook-approvals")]
+pub mod approval_webhook;
+pub mod artifacts;
+mod atomic_file;
+pub mod attachments;
 pub mod error;
+pub mod event_dispatch;
+pub mod guardrails;
+pub mod hooks;
+pub mod import;
 pub mod instructions;
+mod locale;
+pub mod model_catalog;
+pub mod model_manager;
 pub mod orchestrator;
 mod permission_store;
 pub mod permissions;
+pub mod prompts;
+pub mod replay;
+pub mod secrets;
+pub mod share;
 pub mod skills;
+pub mod templates;
 pub mod types;
+pub mod workspace_lock;
+pub mod workspace_watch;
 
 pub mod agent;
 pub mod state;
@@ -17,11 +36,51 @@ pub mod tools;
 
 pub use agent::OdysseyAgent;
 pub use agent::builder::AgentBuilder;
+/// HTTP webhook approval handler.
+#[cfg(feature = "webhook-approvals")]
+pub use approval_webhook::WebhookApprovalHandler;
+/// Shared artifact store.
+pub use artifacts::ArtifactStore;
+/// Buffered delivery to async event sinks.
+pub use event_dispatch::{BufferedEventSink, OverflowPolicy};
+/// Built-in content guardrail hook.
+pub use guardrails::GuardrailHook;
+/// Lifecycle hooks around turns and tool calls.
+pub use hooks::{LifecycleHook, TurnHookContext};
+/// Transcript import from other agent CLIs.
+pub use import::SessionImportFormat;
+/// Model capability catalog.
+pub use model_catalog::ModelCatalog;
+/// Cache of GGUF models for local LLM providers.
+pub use model_manager::{CachedModel, ModelDownloadProgress, ModelDownloadRequest, ModelManager};
 /// Orchestrator facade and default agent helpers.
-pub use odyssey_rs_protocol::EventSink;
+pub use odyssey_rs_protocol::{AsyncEventSink, EventSink};
+pub use odyssey_rs_tools::ToolHookDecision;
+/// Secret resolution for `api_key_ref` config entries.
+pub use odyssey_rs_tools::{SecretError, SecretResolver, SecretsProvider};
 pub use orchestrator::LLMEntry;
 pub use orchestrator::{
-    DEFAULT_AGENT_ID, Orchestrator, RunResult, RunStream, SystemPromptMode, prompt::PromptBuilder,
+    BudgetUsage, DEFAULT_AGENT_ID, DebateContribution, DebateEvent, DebateRequest, DebateResult,
+    DebateStopReason, DebateStream, DebateTermination, HealthError, Orchestrator,
+    OrchestratorHealth, OutputSchema, RetriedTurn, RunMetrics, RunResult, RunStream,
+    ScheduledJobInfo, ScheduledRun, Scheduler, SessionHealth, ShutdownReport, SubmissionReceipt,
+    SystemPromptMode, TriggerEvent, TriggerInfo, TriggerRouter, TriggeredRun, TurnHealth,
+    prompt::{
+        BuiltPrompt, EffectivePrompt, PromptBuilder, PromptSection, PromptSectionKind, SystemPrompt,
+    },
 };
 /// Permission hooks and enforcement primitives.
 pub use permissions::{ApprovalHandler, HookDecision, PermissionEngine, PermissionHook};
+/// Named prompt presets.
+pub use prompts::PromptPreset;
+/// Deterministic record/replay harness.
+pub use replay::{
+    ReplayLLM, ReplayLog, ReplayMode, ReplayPlayer, ReplayRecorder, ReplayToolRouter,
+};
+pub use secrets::{provider_api_key, secret_resolver};
+/// Redacted HTML session bundles.
+pub use share::{ShareOptions, SharedSession};
+/// Ready-made agent profiles.
+pub use templates::{AGENT_TEMPLATES, AgentTemplate, agent_template};
+/// Watcher for files changed outside the agent's tools.
+pub use workspace_watch::WorkspaceWatcher;
@@ -0,0 +1,245 @@
+//! Current date and time, time zone, locale, and units given to agents.
+//!
+//! Resolved once from `locale` config, falling back to the host time zone and
+//! the `LC_ALL`, `LC_TIME`, or `LANG` environment variables.
+
+use chrono::{DateTime, FixedOffset, Local, Offset, Utc};
+use log::warn;
+use odyssey_rs_config::{LocaleConfig, MeasurementUnits};
+use serde_json::{Value, json};
+
+/// Locale used when neither config nor the environment names one.
+const DEFAULT_LOCALE: &str = "en-US";
+/// Regions that use imperial units by default.
+const IMPERIAL_REGIONS: [&str; 3] = ["US", "LR", "MM"];
+
+/// Time zone used to render the current time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Zone {
+    /// Host time zone, with its IANA name when known.
+    Local { name: Option<String> },
+    /// Fixed offset from config, such as `UTC` or `+05:30`.
+    Fixed { name: String, offset: FixedOffset },
+}
+
+/// Resolved locale context; `None` from `from_config` when disabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LocaleContext {
+    zone: Zone,
+    locale: String,
+    units: MeasurementUnits,
+}
+
+impl LocaleContext {
+    /// Resolve `config` against the host, or `None` when the context is turned off.
+    pub(crate) fn from_config(config: &LocaleConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let zone = match config.timezone.as_deref() {
+            Some(name) if !name.eq_ignore_ascii_case("local") => match parse_offset(name) {
+                Some(offset) => Zone::Fixed {
+                    name: name.to_string(),
+                    offset,
+                },
+                None => {
+                    warn!("ignoring unsupported locale.timezone {name}; using local time");
+                    Zone::Local {
+                        name: local_zone_name(),
+                    }
+                }
+            },
+            _ => Zone::Local {
+                name: local_zone_name(),
+            },
+        };
+        let locale = config
+            .locale
+            .as_deref()
+            .and_then(normalize_locale)
+            .or_else(env_locale)
+            .unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+        let units = config.units.unwrap_or_else(|| default_units(&locale));
+        Some(Self {
+            zone,
+            locale,
+            units,
+        })
+    }
+
+    /// `now` in the configured time zone.
+    pub(crate) fn local_time(&self, now: DateTime<Utc>) -> DateTime<FixedOffset> {
+        let offset = match &self.zone {
+            Zone::Local { .. } => now.with_timezone(&Local).offset().fix(),
+            Zone::Fixed { offset, .. } => *offset,
+        };
+        now.with_timezone(&offset)
+    }
+
+    /// Lines for the prompt environment section.
+    pub(crate) fn describe(&self, now: DateTime<Utc>) -> String {
+        let local = self.local_time(now);
+        let time = local.format("%Y-%m-%d %H:%M (%A)");
+        let offset = local.format("%:z");
+        let zone = self.zone_name();
+        let locale = &self.locale;
+        let units = units_name(self.units);
+        format!("{time} {zone} (UTC{offset})\nLocale: {locale}; units: {units}")
+    }
+
+    /// `locale` entry of the turn context metadata.
+    pub(crate) fn metadata(&self, now: DateTime<Utc>) -> Value {
+        let local = self.local_time(now);
+        json!({
+            "now": local.to_rfc3339(),
+            "timezone": self.zone_name(),
+            "utc_offset": local.format("%:z").to_string(),
+            "locale": self.locale,
+            "units": units_name(self.units),
+        })
+    }
+
+    fn zone_name(&self) -> &str {
+        match &self.zone {
+            Zone::Local { name } => name.as_deref().unwrap_or("local"),
+            Zone::Fixed { name, .. } => name,
+        }
+    }
+}
+
+fn units_name(units: MeasurementUnits) -> &'static str {
+    match units {
+        MeasurementUnits::Metric => "metric",
+        MeasurementUnits::Imperial => "imperial",
+    }
+}
+
+/// Parse `UTC` or `+HH:MM` / `-HH:MM`.
+fn parse_offset(zone: &str) -> Option<FixedOffset> {
+    if zone.eq_ignore_ascii_case("utc") {
+        return FixedOffset::east_opt(0);
+    }
+    let (sign, rest) = match zone.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let (hours, minutes) = rest.split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// IANA name of the host time zone from `TZ` or the `/etc/localtime` link.
+fn local_zone_name() -> Option<String> {
+    if let Ok(tz) = std::env::var("TZ") {
+        let tz = tz.trim_start_matches(':');
+        if !tz.is_empty() {
+            return Some(tz.to_string());
+        }
+    }
+    let target = std::fs::read_link("/etc/localtime").ok()?;
+    let target = target.to_string_lossy();
+    let (_, name) = target.split_once("zoneinfo/")?;
+    Some(name.to_string())
+}
+
+/// First usable locale from `LC_ALL`, `LC_TIME`, or `LANG`.
+fn env_locale() -> Option<String> {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .find_map(|value| normalize_locale(&value))
+}
+
+/// Turn `en_US.UTF-8` into `en-US`; `C` and `POSIX` name no locale.
+fn normalize_locale(value: &str) -> Option<String> {
+    let tag = value
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .replace('_', "-");
+    match tag.as_str() {
+        "" | "C" | "POSIX" => None,
+        _ => Some(tag),
+    }
+}
+
+fn default_units(locale: &str) -> MeasurementUnits {
+    let region = locale.rsplit_once('-').map(|(_, region)| region);
+    if region.is_some_and(|region| IMPERIAL_REGIONS.contains(&region.to_ascii_uppercase().as_str()))
+    {
+        MeasurementUnits::Imperial
+    } else {
+        MeasurementUnits::Metric
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocaleContext, default_units, normalize_locale, parse_offset};
+    use chrono::{TimeZone, Utc};
+    use odyssey_rs_config::{LocaleConfig, MeasurementUnits};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn parses_offsets_and_locales() {
+        assert_eq!(parse_offset("UTC").map(|o| o.local_minus_utc()), Some(0));
+        assert_eq!(
+            parse_offset("+05:30").map(|o| o.local_minus_utc()),
+            Some(19_800)
+        );
+        assert_eq!(
+            parse_offset("-08:00").map(|o| o.local_minus_utc()),
+            Some(-28_800)
+        );
+        assert_eq!(parse_offset("Europe/Berlin"), None);
+        assert_eq!(parse_offset("+5:30"), None);
+
+        assert_eq!(normalize_locale("en_GB.UTF-8").as_deref(), Some("en-GB"));
+        assert_eq!(normalize_locale("de_DE@euro").as_deref(), Some("de-DE"));
+        assert_eq!(normalize_locale("C.UTF-8"), None);
+        assert_eq!(default_units("en-US"), MeasurementUnits::Imperial);
+        assert_eq!(default_units("en-GB"), MeasurementUnits::Metric);
+        assert_eq!(default_units("fr"), MeasurementUnits::Metric);
+    }
+
+    #[test]
+    fn renders_configured_zone() {
+        let disabled = LocaleConfig {
+            enabled: false,
+            ..LocaleConfig::default()
+        };
+        assert_eq!(LocaleContext::from_config(&disabled), None);
+
+        let context = LocaleContext::from_config(&LocaleConfig {
+            timezone: Some("+05:30".to_string()),
+            locale: Some("en_IN".to_string()),
+            ..LocaleConfig::default()
+        })
+        .expect("enabled");
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 20, 0, 0).unwrap();
+        assert_eq!(
+            context.describe(now),
+            "2025-03-02 01:30 (Sunday) +05:30 (UTC+05:30)\nLocale: en-IN; units: metric"
+        );
+        assert_eq!(
+            context.metadata(now),
+            json!({
+                "now": "2025-03-02T01:30:00+05:30",
+                "timezone": "+05:30",
+                "utc_offset": "+05:30",
+                "locale": "en-IN",
+                "units": "metric",
+            })
+        );
+    }
+}
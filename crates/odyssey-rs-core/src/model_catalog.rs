@@ -0,0 +1,190 @@
+//! Model capabilities used to budget prompts before they reach the provider.
+//!
+//! The catalog ships with entries for common OpenAI and Anthropic models and
+//! is extended or overridden by `models.catalog` in config. Token counts are
+//! estimated from character length, which is close enough to reject clearly
+//! oversized prompts and derive compaction thresholds.
+
+use crate::error::OdysseyCoreError;
+pub use odyssey_rs_config::ModelCatalogEntry;
+use odyssey_rs_config::ModelsConfig;
+
+/// Approximate characters per token used for estimates.
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Share of the input budget memory may fill before compaction kicks in.
+const COMPACTION_BUDGET_PERCENT: usize = 80;
+
+/// Catalog of known model capabilities.
+#[derive(Debug, Clone, Default)]
+pub struct ModelCatalog {
+    entries: Vec<ModelCatalogEntry>,
+}
+
+impl ModelCatalog {
+    /// Build the catalog of built-in model entries.
+    pub fn builtin() -> Self {
+        let entries = BUILTIN_MODELS
+            .iter()
+            .map(
+                |(provider, name, context_window, max_output_tokens, supports_vision)| {
+                    ModelCatalogEntry {
+                        provider: (*provider).to_string(),
+                        name: (*name).to_string(),
+                        context_window: *context_window,
+                        max_output_tokens: Some(*max_output_tokens),
+                        supports_tools: true,
+                        supports_vision: *supports_vision,
+                        chars_per_token: None,
+                    }
+                },
+            )
+            .collect();
+        Self { entries }
+    }
+
+    /// Build the catalog from built-in entries overlaid with config entries.
+    pub fn from_config(config: &ModelsConfig) -> Self {
+        let mut catalog = Self::builtin();
+        for entry in &config.catalog {
+            catalog.insert(entry.clone());
+        }
+        catalog
+    }
+
+    /// Insert or replace an entry for a provider/model pair.
+    pub fn insert(&mut self, entry: ModelCatalogEntry) {
+        self.entries.retain(|existing| {
+            !(existing.provider.eq_ignore_ascii_case(&entry.provider)
+                && existing.name.eq_ignore_ascii_case(&entry.name))
+        });
+        self.entries.push(entry);
+    }
+
+    /// Return all catalog entries.
+    pub fn entries(&self) -> &[ModelCatalogEntry] {
+        &self.entries
+    }
+
+    /// Look up a model by provider and name.
+    ///
+    /// Exact matches win; otherwise the longest catalog name that prefixes the
+    /// requested name is used, so dated snapshots resolve to their family.
+    pub fn get(&self, provider: &str, name: &str) -> Option<&ModelCatalogEntry> {
+        let name = name.to_ascii_lowercase();
+        let candidates = self
+            .entries
+            .iter()
+            .filter(|entry| entry.provider.eq_ignore_ascii_case(provider));
+        let mut best: Option<&ModelCatalogEntry> = None;
+        for entry in candidates {
+            let entry_name = entry.name.to_ascii_lowercase();
+            if entry_name == name {
+                return Some(entry);
+            }
+            if name.starts_with(&entry_name)
+                && best.is_none_or(|current| current.name.len() < entry.name.len())
+            {
+                best = Some(entry);
+            }
+        }
+        best
+    }
+}
+
+/// Memory character limit that keeps recall within a model's input budget.
+pub fn compaction_char_budget(entry: &ModelCatalogEntry) -> usize {
+    entry.input_token_budget() * CHARS_PER_TOKEN * COMPACTION_BUDGET_PERCENT / 100
+}
+
+/// Reject prompts whose estimated size exceeds a model's input budget.
+pub fn check_prompt_tokens(
+    entry: &ModelCatalogEntry,
+    estimated_tokens: usize,
+) -> Result<(), OdysseyCoreError> {
+    let limit = entry.input_token_budget();
+    if estimated_tokens > limit {
+        return Err(OdysseyCoreError::ContextWindowExceeded {
+            model: format!("{}/{}", entry.provider, entry.name),
+            estimated_tokens,
+            limit,
+        });
+    }
+    Ok(())
+}
+
+/// Estimate the token count of a text.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Built-in entries: (provider, name, context window, max output tokens, vision).
+const BUILTIN_MODELS: &[(&str, &str, usize, usize, bool)] = &[
+    ("openai", "gpt-5", 400_000, 128_000, true),
+    ("openai", "gpt-4.1", 1_047_576, 32_768, true),
+    ("openai", "gpt-4o", 128_000, 16_384, true),
+    ("openai", "o3", 200_000, 100_000, true),
+    ("openai", "o4-mini", 200_000, 100_000, true),
+    ("anthropic", "claude-opus-4", 200_000, 32_000, true),
+    ("anthropic", "claude-sonnet-4", 200_000, 64_000, true),
+    ("anthropic", "claude-3-5-haiku", 200_000, 8_192, true),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{ModelCatalog, check_prompt_tokens, compaction_char_budget, estimate_tokens};
+    use crate::error::OdysseyCoreError;
+    use odyssey_rs_config::{ModelCatalogEntry, ModelsConfig};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn lookup_prefers_exact_then_longest_prefix() {
+        let catalog = ModelCatalog::builtin();
+        let mini = catalog
+            .get("openai", "gpt-4o-mini-2024-07-18")
+            .expect("model");
+        assert_eq!(mini.name, "gpt-4o");
+        let exact = catalog.get("OpenAI", "GPT-4.1").expect("model");
+        assert_eq!(exact.name, "gpt-4.1");
+        assert_eq!(catalog.get("openai", "unknown").is_none(), true);
+        assert_eq!(catalog.get("ollama", "gpt-4.1").is_none(), true);
+    }
+
+    #[test]
+    fn config_entries_override_builtins() {
+        let config = ModelsConfig {
+            catalog: vec![ModelCatalogEntry {
+                provider: "openai".to_string(),
+                name: "gpt-4o".to_string(),
+                context_window: 1_000,
+                max_output_tokens: Some(200),
+                supports_tools: false,
+                supports_vision: false,
+                chars_per_token: None,
+            }],
+            ..ModelsConfig::default()
+        };
+        let catalog = ModelCatalog::from_config(&config);
+        let entry = catalog.get("openai", "gpt-4o").expect("model");
+        assert_eq!(entry.context_window, 1_000);
+        assert_eq!(entry.input_token_budget(), 800);
+        assert_eq!(compaction_char_budget(entry), 2_560);
+        assert_eq!(check_prompt_tokens(entry, 800).is_ok(), true);
+        let err = check_prompt_tokens(entry, 801).expect_err("too large");
+        assert!(matches!(
+            err,
+            OdysseyCoreError::ContextWindowExceeded {
+                estimated_tokens: 801,
+                limit: 800,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn estimates_tokens_from_characters() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+}
@@ -0,0 +1,562 @@
+//! Cache of GGUF models for the local llama.cpp provider.
+//!
+//! Models live under `<root>/<owner>--<name>/<file>.gguf`, one directory per
+//! HuggingFace repo, with the verified SHA-256 in a `<file>.gguf.sha256`
+//! sidecar. Downloads stream into a `.part` file that is renamed into place
+//! only after its hash matched, so an interrupted download never shows up as
+//! a cached model. Downloading needs the `model-downloads` feature.
+
+use crate::error::OdysseyCoreError;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Extension of model files managed by the cache.
+const MODEL_EXTENSION: &str = "gguf";
+/// Extension of the sidecar holding a model's SHA-256.
+const SHA256_EXTENSION: &str = "sha256";
+/// Extension of a download in progress.
+const PART_EXTENSION: &str = "part";
+/// Separator between repo owner and name in cache directory names.
+const REPO_SEPARATOR: &str = "--";
+/// Default HuggingFace endpoint; `HF_ENDPOINT` overrides it.
+#[cfg(feature = "model-downloads")]
+const DEFAULT_HF_ENDPOINT: &str = "https://huggingface.co";
+/// Bytes read per chunk when hashing a cached model.
+const HASH_CHUNK_BYTES: usize = 1 << 20;
+
+/// Model file present in the cache.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedModel {
+    /// HuggingFace repo id, e.g. `Qwen/Qwen2.5-Coder-7B-Instruct-GGUF`.
+    pub repo_id: String,
+    /// GGUF file name within the repo.
+    pub filename: String,
+    /// Absolute path of the cached file.
+    pub path: PathBuf,
+    /// File size in bytes.
+    pub size_bytes: u64,
+    /// Recorded SHA-256, or `None` when the file was never verified.
+    pub sha256: Option<String>,
+}
+
+/// GGUF file to fetch from HuggingFace.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelDownloadRequest {
+    /// HuggingFace repo id.
+    pub repo_id: String,
+    /// GGUF file name within the repo.
+    pub filename: String,
+    /// Branch, tag, or commit; defaults to `main`.
+    pub revision: Option<String>,
+    /// Expected SHA-256; defaults to the hash HuggingFace reports for the file.
+    pub sha256: Option<String>,
+}
+
+/// Progress of a model download, reported after each received chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelDownloadProgress {
+    /// HuggingFace repo id.
+    pub repo_id: String,
+    /// GGUF file name within the repo.
+    pub filename: String,
+    /// Bytes received so far.
+    pub downloaded_bytes: u64,
+    /// Total size when the server reported one.
+    pub total_bytes: Option<u64>,
+}
+
+impl ModelDownloadProgress {
+    /// Completed percentage, when the total size is known.
+    pub fn percent(&self) -> Option<u8> {
+        let total = self.total_bytes.filter(|total| *total > 0)?;
+        Some((self.downloaded_bytes.min(total) * 100 / total) as u8)
+    }
+}
+
+/// Lists, downloads, verifies, and deletes cached GGUF models.
+#[derive(Debug, Clone)]
+pub struct ModelManager {
+    root: PathBuf,
+}
+
+impl ModelManager {
+    /// Manage the cache rooted at `root`; the directory is created on first download.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Cache root directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Path a model is cached at, whether or not it exists.
+    pub fn model_path(&self, repo_id: &str, filename: &str) -> Result<PathBuf, OdysseyCoreError> {
+        validate_repo_id(repo_id)?;
+        validate_filename(filename)?;
+        Ok(self
+            .root
+            .join(repo_id.replacen('/', REPO_SEPARATOR, 1))
+            .join(filename))
+    }
+
+    /// Path of a cached model, or `None` when it is not downloaded.
+    pub fn cached_path(&self, repo_id: &str, filename: &str) -> Option<PathBuf> {
+        self.model_path(repo_id, filename)
+            .ok()
+            .filter(|path| path.is_file())
+    }
+
+    /// Cached models sorted by repo and file name.
+    pub fn list(&self) -> Result<Vec<CachedModel>, OdysseyCoreError> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(OdysseyCoreError::Io(err)),
+        };
+        let mut models = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let dir_name = entry.file_name().to_string_lossy().to_string();
+            let Some((owner, name)) = dir_name.split_once(REPO_SEPARATOR) else {
+                continue;
+            };
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let repo_id = format!("{owner}/{name}");
+            for file in fs::read_dir(entry.path())? {
+                let file = file?;
+                let path = file.path();
+                if !is_model_file(&path) || !file.file_type()?.is_file() {
+                    continue;
+                }
+                models.push(CachedModel {
+                    repo_id: repo_id.clone(),
+                    filename: file.file_name().to_string_lossy().to_string(),
+                    size_bytes: file.metadata()?.len(),
+                    sha256: read_sidecar(&path),
+                    path,
+                });
+            }
+        }
+        models.sort_by(|a, b| (&a.repo_id, &a.filename).cmp(&(&b.repo_id, &b.filename)));
+        Ok(models)
+    }
+
+    /// Hash a cached model, check it against `expected`, and record the hash.
+    ///
+    /// Without `expected` the file is checked against its recorded hash, if any.
+    /// A mismatch leaves the file and its sidecar untouched.
+    pub async fn verify(
+        &self,
+        repo_id: &str,
+        filename: &str,
+        expected: Option<&str>,
+    ) -> Result<CachedModel, OdysseyCoreError> {
+        let path = self.model_path(repo_id, filename)?;
+        if !path.is_file() {
+            return Err(OdysseyCoreError::ModelManager(format!(
+                "{repo_id}/{filename} is not cached"
+            )));
+        }
+        let expected = match expected {
+            Some(expected) => Some(parse_sha256(expected)?),
+            None => read_sidecar(&path),
+        };
+        let hash_path = path.clone();
+        let actual = tokio::task::spawn_blocking(move || hash_file(&hash_path))
+            .await
+            .map_err(|err| OdysseyCoreError::ModelManager(format!("hashing failed: {err}")))??;
+        check_sha256(repo_id, filename, expected.as_deref(), &actual)?;
+        write_sidecar(&path, &actual)?;
+        info!("verified cached model {repo_id}/{filename} (sha256={actual})");
+        Ok(CachedModel {
+            repo_id: repo_id.to_string(),
+            filename: filename.to_string(),
+            size_bytes: fs::metadata(&path)?.len(),
+            sha256: Some(actual),
+            path,
+        })
+    }
+
+    /// Remove a cached model and its hash; returns false when it was not cached.
+    ///
+    /// The repo directory is removed once no files are left in it.
+    pub fn delete(&self, repo_id: &str, filename: &str) -> Result<bool, OdysseyCoreError> {
+        let path = self.model_path(repo_id, filename)?;
+        let removed = match fs::remove_file(&path) {
+            Ok(()) => true,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => false,
+            Err(err) => return Err(OdysseyCoreError::Io(err)),
+        };
+        for extra in [sidecar_path(&path), part_path(&path)] {
+            if let Err(err) = fs::remove_file(&extra)
+                && err.kind() != std::io::ErrorKind::NotFound
+            {
+                warn!("failed to remove {}: {err}", extra.display());
+            }
+        }
+        if let Some(dir) = path.parent()
+            && fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_none())
+        {
+            let _ = fs::remove_dir(dir);
+        }
+        if removed {
+            info!("deleted cached model {repo_id}/{filename}");
+        }
+        Ok(removed)
+    }
+
+    /// Download a model from HuggingFace, reporting progress to `on_progress`.
+    ///
+    /// The file is hashed while it streams and kept only when the hash matches
+    /// the requested one, or the `X-Linked-Etag` HuggingFace reports for LFS
+    /// files. `HF_TOKEN` is sent as a bearer token for gated repos.
+    #[cfg(feature = "model-downloads")]
+    pub async fn download(
+        &self,
+        request: &ModelDownloadRequest,
+        mut on_progress: impl FnMut(ModelDownloadProgress) + Send,
+    ) -> Result<CachedModel, OdysseyCoreError> {
+        use futures_util::StreamExt;
+        use tokio::io::AsyncWriteExt;
+
+        let ModelDownloadRequest {
+            repo_id,
+            filename,
+            revision,
+            sha256,
+        } = request;
+        let path = self.model_path(repo_id, filename)?;
+        let mut expected = sha256.as_deref().map(parse_sha256).transpose()?;
+        let endpoint = std::env::var("HF_ENDPOINT")
+            .ok()
+            .filter(|endpoint| !endpoint.is_empty())
+            .unwrap_or_else(|| DEFAULT_HF_ENDPOINT.to_string());
+        let revision = revision.as_deref().unwrap_or("main");
+        let url = format!(
+            "{}/{repo_id}/resolve/{revision}/{filename}",
+            endpoint.trim_end_matches('/')
+        );
+        let client = reqwest::Client::builder()
+            .build()
+            .map_err(|err| OdysseyCoreError::ModelManager(err.to_string()))?;
+        let token = std::env::var("HF_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty());
+        let authorize = |builder: reqwest::RequestBuilder| match &token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        };
+
+        if expected.is_none() {
+            expected = linked_etag(&url, token.as_deref()).await;
+        }
+        info!("downloading model {repo_id}/{filename} from {url}");
+        let response = authorize(client.get(&url))
+            .send()
+            .await
+            .map_err(|err| OdysseyCoreError::ModelManager(format!("download failed: {err}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(OdysseyCoreError::ModelManager(format!(
+                "{url} returned {status}"
+            )));
+        }
+        let total_bytes = response.content_length();
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let part = part_path(&path);
+        let mut file = tokio::fs::File::create(&part).await?;
+        let mut hasher = Sha256::new();
+        let mut downloaded_bytes = 0u64;
+        let mut stream = response.bytes_stream();
+        on_progress(ModelDownloadProgress {
+            repo_id: repo_id.clone(),
+            filename: filename.clone(),
+            downloaded_bytes,
+            total_bytes,
+        });
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    drop(file);
+                    let _ = tokio::fs::remove_file(&part).await;
+                    return Err(OdysseyCoreError::ModelManager(format!(
+                        "download interrupted: {err}"
+                    )));
+                }
+            };
+            file.write_all(&chunk).await?;
+            hasher.update(&chunk);
+            downloaded_bytes += chunk.len() as u64;
+            on_progress(ModelDownloadProgress {
+                repo_id: repo_id.clone(),
+                filename: filename.clone(),
+                downloaded_bytes,
+                total_bytes,
+            });
+        }
+        file.flush().await?;
+        drop(file);
+
+        let actual = hex(&hasher.finalize());
+        if let Err(err) = check_sha256(repo_id, filename, expected.as_deref(), &actual) {
+            let _ = tokio::fs::remove_file(&part).await;
+            return Err(err);
+        }
+        if expected.is_none() {
+            warn!("no published sha256 for {repo_id}/{filename}; recording {actual} unchecked");
+        }
+        tokio::fs::rename(&part, &path).await?;
+        write_sidecar(&path, &actual)?;
+        info!("downloaded model {repo_id}/{filename} ({downloaded_bytes} bytes, sha256={actual})");
+        Ok(CachedModel {
+            repo_id: repo_id.clone(),
+            filename: filename.clone(),
+            path,
+            size_bytes: downloaded_bytes,
+            sha256: Some(actual),
+        })
+    }
+
+    /// Reject downloads in builds without an HTTP client.
+    #[cfg(not(feature = "model-downloads"))]
+    pub async fn download(
+        &self,
+        request: &ModelDownloadRequest,
+        _on_progress: impl FnMut(ModelDownloadProgress) + Send,
+    ) -> Result<CachedModel, OdysseyCoreError> {
+        Err(OdysseyCoreError::ModelManager(format!(
+            "downloading {}/{} requires the model-downloads feature",
+            request.repo_id, request.filename
+        )))
+    }
+}
+
+/// SHA-256 HuggingFace reports for an LFS file, read without following the redirect.
+#[cfg(feature = "model-downloads")]
+async fn linked_etag(url: &str, token: Option<&str>) -> Option<String> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .ok()?;
+    let mut request = client.head(url);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(err) => {
+            log::debug!("could not read published sha256 from {url}: {err}");
+            return None;
+        }
+    };
+    let etag = response.headers().get("x-linked-etag")?.to_str().ok()?;
+    parse_sha256(etag.trim_matches('"')).ok()
+}
+
+/// Require `owner/name` made of letters, digits, `.`, `_`, and `-`.
+fn validate_repo_id(repo_id: &str) -> Result<(), OdysseyCoreError> {
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && !part.starts_with('.')
+            && !part.contains(REPO_SEPARATOR)
+            && part
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-'))
+    };
+    match repo_id.split_once('/') {
+        Some((owner, name)) if valid_part(owner) && valid_part(name) => Ok(()),
+        _ => Err(OdysseyCoreError::ModelManager(format!(
+            "invalid repo id {repo_id}; expected owner/name"
+        ))),
+    }
+}
+
+/// Require a plain `.gguf` file name.
+fn validate_filename(filename: &str) -> Result<(), OdysseyCoreError> {
+    if filename.starts_with('.')
+        || filename.contains(['/', '\\'])
+        || !is_model_file(Path::new(filename))
+    {
+        return Err(OdysseyCoreError::ModelManager(format!(
+            "invalid model file {filename}; expected a .{MODEL_EXTENSION} file name"
+        )));
+    }
+    Ok(())
+}
+
+fn is_model_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(MODEL_EXTENSION))
+}
+
+fn sidecar_path(path: &Path) -> PathBuf {
+    append_extension(path, SHA256_EXTENSION)
+}
+
+fn part_path(path: &Path) -> PathBuf {
+    append_extension(path, PART_EXTENSION)
+}
+
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+fn read_sidecar(path: &Path) -> Option<String> {
+    let contents = fs::read_to_string(sidecar_path(path)).ok()?;
+    parse_sha256(contents.trim()).ok()
+}
+
+fn write_sidecar(path: &Path, sha256: &str) -> Result<(), OdysseyCoreError> {
+    fs::write(sidecar_path(path), format!("{sha256}\n"))?;
+    Ok(())
+}
+
+/// Lowercase a 64-digit hex SHA-256.
+fn parse_sha256(value: &str) -> Result<String, OdysseyCoreError> {
+    if value.len() == 64 && value.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        Ok(value.to_ascii_lowercase())
+    } else {
+        Err(OdysseyCoreError::ModelManager(format!(
+            "invalid sha256 {value}; expected 64 hex digits"
+        )))
+    }
+}
+
+fn check_sha256(
+    repo_id: &str,
+    filename: &str,
+    expected: Option<&str>,
+    actual: &str,
+) -> Result<(), OdysseyCoreError> {
+    match expected {
+        Some(expected) if expected != actual => Err(OdysseyCoreError::ModelManager(format!(
+            "sha256 mismatch for {repo_id}/{filename}: expected {expected}, got {actual}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn hash_file(path: &Path) -> Result<String, OdysseyCoreError> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; HASH_CHUNK_BYTES];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ModelDownloadProgress, ModelManager, hex};
+    use pretty_assertions::assert_eq;
+    use sha2::{Digest, Sha256};
+    use std::fs;
+    use tempfile::tempdir;
+
+    const REPO: &str = "Qwen/Qwen2.5-Coder-7B-Instruct-GGUF";
+    const FILE: &str = "model-q8_0.gguf";
+
+    #[tokio::test]
+    async fn lists_verifies_and_deletes_cached_models() {
+        let temp = tempdir().expect("tempdir");
+        let manager = ModelManager::new(temp.path());
+        assert_eq!(manager.list().expect("list"), Vec::new());
+
+        let path = manager.model_path(REPO, FILE).expect("path");
+        fs::create_dir_all(path.parent().expect("parent")).expect("mkdir");
+        fs::write(&path, b"gguf").expect("write");
+        fs::write(path.with_extension("gguf.part"), b"partial").expect("write part");
+        let models = manager.list().expect("list");
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].repo_id, REPO);
+        assert_eq!(models[0].filename, FILE);
+        assert_eq!(models[0].size_bytes, 4);
+        assert_eq!(models[0].sha256, None);
+
+        let sha256 = hex(&Sha256::digest(b"gguf"));
+        let wrong = "0".repeat(64);
+        let err = manager
+            .verify(REPO, FILE, Some(&wrong))
+            .await
+            .expect_err("mismatch");
+        assert_eq!(err.to_string().contains("sha256 mismatch"), true);
+        let verified = manager
+            .verify(REPO, FILE, Some(&sha256.to_uppercase()))
+            .await
+            .expect("verify");
+        assert_eq!(verified.sha256.as_deref(), Some(sha256.as_str()));
+        assert_eq!(manager.list().expect("list")[0].sha256, Some(sha256));
+
+        fs::write(&path, b"tampered").expect("tamper");
+        assert_eq!(manager.verify(REPO, FILE, None).await.is_err(), true);
+
+        assert_eq!(manager.delete(REPO, FILE).expect("delete"), true);
+        assert_eq!(manager.delete(REPO, FILE).expect("delete"), false);
+        assert_eq!(manager.list().expect("list"), Vec::new());
+        assert_eq!(fs::read_dir(temp.path()).expect("read").count(), 0);
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_cache() {
+        let manager = ModelManager::new("/models");
+        for (repo, file) in [
+            ("../etc", FILE),
+            ("owner", FILE),
+            ("owner/a--b", FILE),
+            (REPO, "../model.gguf"),
+            (REPO, "model.bin"),
+            (REPO, ".gguf"),
+        ] {
+            assert_eq!(
+                manager.model_path(repo, file).is_err(),
+                true,
+                "{repo} {file}"
+            );
+        }
+        assert_eq!(
+            manager.model_path(REPO, FILE).expect("path"),
+            std::path::PathBuf::from("/models/Qwen--Qwen2.5-Coder-7B-Instruct-GGUF").join(FILE)
+        );
+    }
+
+    #[test]
+    fn progress_percent_requires_total() {
+        let progress = ModelDownloadProgress {
+            repo_id: REPO.to_string(),
+            filename: FILE.to_string(),
+            downloaded_bytes: 50,
+            total_bytes: Some(200),
+        };
+        assert_eq!(progress.percent(), Some(25));
+        assert_eq!(
+            ModelDownloadProgress {
+                total_bytes: None,
+                ..progress
+            }
+            .percent(),
+            None
+        );
+    }
+}
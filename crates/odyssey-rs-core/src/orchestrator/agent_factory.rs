@@ -246,6 +246,8 @@ where
             sink.emit(EventMsg {
                 id: Uuid::new_v4(),
                 session_id: input.session_id,
+                seq: 0,
+                user_id: None,
                 created_at: chrono::Utc::now(),
                 payload: EventPayload::TurnStarted {
                     turn_id,
@@ -279,6 +281,8 @@ where
             sink.emit(EventMsg {
                 id: Uuid::new_v4(),
                 session_id: input.session_id,
+                seq: 0,
+                user_id: None,
                 created_at: chrono::Utc::now(),
                 payload: EventPayload::TurnCompleted {
                     turn_id,
@@ -0,0 +1,372 @@
+//! Batch confirmation of mutating tool calls (`confirm_batch` permission mode).
+//!
+//! Each model response proposes a batch of tool calls. Before the first
+//! mutating call of a batch runs, the user confirms the mutating calls of the
+//! whole batch at once; the answer covers the rest of that batch. The next LLM
+//! call starts a new batch.
+
+use crate::permissions::PermissionEngine;
+use async_trait::async_trait;
+use autoagents_core::tool::{ToolCallError, ToolRuntime, ToolT};
+use autoagents_llm::chat::{
+    ChatMessage, ChatProvider, ChatResponse, StreamChunk, StreamResponse, StructuredOutputFormat,
+    Tool as LLMTool,
+};
+use autoagents_llm::completion::{CompletionProvider, CompletionRequest, CompletionResponse};
+use autoagents_llm::embedding::EmbeddingProvider;
+use autoagents_llm::error::LLMError;
+use autoagents_llm::models::ModelsProvider;
+use autoagents_llm::{LLMProvider, ToolCall};
+use futures_util::{Stream, StreamExt};
+use log::info;
+use odyssey_rs_protocol::{EventSink, ProposedToolCall, ToolError};
+use odyssey_rs_tools::{PermissionContext, PermissionOutcome};
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+
+type ChunkStream = Pin<Box<dyn Stream<Item = Result<StreamChunk, LLMError>> + Send>>;
+
+/// Per-turn batch state shared by the LLM and tool wrappers.
+pub(crate) struct BatchGate {
+    permissions: Arc<PermissionEngine>,
+    ctx: PermissionContext,
+    event_sink: Option<Arc<dyn EventSink>>,
+    /// Names of the tools that need confirmation.
+    mutating: HashSet<String>,
+    /// Tool calls proposed by the latest model response.
+    proposed: Mutex<Vec<ProposedToolCall>>,
+    /// Answer for the current batch; held while asking so parallel calls wait.
+    decision: tokio::sync::Mutex<Option<PermissionOutcome>>,
+}
+
+impl BatchGate {
+    /// Create a gate for one turn, confirming calls to the `mutating` tools.
+    pub(crate) fn new(
+        permissions: Arc<PermissionEngine>,
+        ctx: PermissionContext,
+        event_sink: Option<Arc<dyn EventSink>>,
+        mutating: HashSet<String>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            permissions,
+            ctx,
+            event_sink,
+            mutating,
+            proposed: Mutex::new(Vec::new()),
+            decision: tokio::sync::Mutex::new(None),
+        })
+    }
+
+    /// Start a new batch before an LLM call.
+    async fn begin_batch(&self) {
+        self.proposed.lock().clear();
+        *self.decision.lock().await = None;
+    }
+
+    /// Record tool calls proposed by the model.
+    fn record(&self, calls: &[ToolCall]) {
+        self.proposed.lock().extend(calls.iter().map(|call| {
+            ProposedToolCall {
+                tool_name: call.function.name.clone(),
+                arguments: serde_json::from_str(&call.function.arguments)
+                    .unwrap_or_else(|_| Value::String(call.function.arguments.clone())),
+            }
+        }));
+    }
+
+    /// Confirm the current batch once, before its first mutating call runs.
+    ///
+    /// Falls back to the call itself when the provider did not report the batch.
+    async fn confirm(&self, tool_name: &str, args: &Value) -> Result<PermissionOutcome, ToolError> {
+        let mut decision = self.decision.lock().await;
+        if let Some(outcome) = decision.as_ref() {
+            return Ok(outcome.clone());
+        }
+        let mut calls = self
+            .proposed
+            .lock()
+            .iter()
+            .filter(|call| self.mutating.contains(&call.tool_name))
+            .cloned()
+            .collect::<Vec<_>>();
+        if calls.is_empty() {
+            calls.push(ProposedToolCall {
+                tool_name: tool_name.to_string(),
+                arguments: args.clone(),
+            });
+        }
+        info!(
+            "confirming tool batch (session_id={}, turn_id={:?}, calls={})",
+            self.ctx.session_id,
+            self.ctx.turn_id,
+            calls.len()
+        );
+        let outcome = self
+            .permissions
+            .confirm_batch(&self.ctx, calls, self.event_sink.clone())
+            .await?;
+        *decision = Some(outcome.clone());
+        Ok(outcome)
+    }
+}
+
+/// LLM provider that starts a batch per call and records the proposed tool calls.
+struct BatchLLM {
+    inner: Arc<dyn LLMProvider>,
+    gate: Arc<BatchGate>,
+}
+
+#[async_trait]
+impl ChatProvider for BatchLLM {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.gate.begin_batch().await;
+        let response = self
+            .inner
+            .chat_with_tools(messages, tools, json_schema)
+            .await?;
+        if let Some(calls) = response.tool_calls() {
+            self.gate.record(&calls);
+        }
+        Ok(response)
+    }
+
+    async fn chat_with_web_search(&self, input: String) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.gate.begin_batch().await;
+        self.inner.chat_with_web_search(input).await
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, LLMError>> + Send>>, LLMError> {
+        self.gate.begin_batch().await;
+        self.inner.chat_stream(messages, json_schema).await
+    }
+
+    async fn chat_stream_struct(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamResponse, LLMError>> + Send>>, LLMError>
+    {
+        self.gate.begin_batch().await;
+        self.inner
+            .chat_stream_struct(messages, tools, json_schema)
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<ChunkStream, LLMError> {
+        self.gate.begin_batch().await;
+        let inner = self
+            .inner
+            .chat_stream_with_tools(messages, tools, json_schema)
+            .await?;
+        let gate = self.gate.clone();
+        Ok(Box::pin(inner.inspect(move |chunk| {
+            if let Ok(StreamChunk::ToolUseComplete { tool_call, .. }) = chunk {
+                gate.record(std::slice::from_ref(tool_call));
+            }
+        })))
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for BatchLLM {
+    async fn complete(
+        &self,
+        req: &CompletionRequest,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req, json_schema).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for BatchLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
+
+#[async_trait]
+impl ModelsProvider for BatchLLM {}
+
+impl LLMProvider for BatchLLM {}
+
+/// Mutating tool that waits for its batch to be confirmed before executing.
+struct BatchTool {
+    inner: Arc<dyn ToolT>,
+    gate: Arc<BatchGate>,
+}
+
+impl fmt::Debug for BatchTool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchTool")
+            .field("name", &self.inner.name())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ToolRuntime for BatchTool {
+    async fn execute(&self, args: Value) -> Result<Value, ToolCallError> {
+        let outcome = self
+            .gate
+            .confirm(self.inner.name(), &args)
+            .await
+            .map_err(|err| ToolCallError::RuntimeError(Box::new(err)))?;
+        if !outcome.allowed {
+            let reason = outcome
+                .reason
+                .unwrap_or_else(|| "tool batch declined".to_string());
+            return Err(ToolCallError::RuntimeError(Box::new(
+                ToolError::PermissionDenied(reason),
+            )));
+        }
+        self.inner.execute(args).await
+    }
+}
+
+impl ToolT for BatchTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn args_schema(&self) -> Value {
+        self.inner.args_schema()
+    }
+}
+
+/// Wrap an LLM and the gate's mutating tools so tool calls are confirmed per batch.
+pub(crate) fn confirm_batches(
+    gate: &Arc<BatchGate>,
+    llm: Arc<dyn LLMProvider>,
+    tools: Vec<Arc<dyn ToolT>>,
+) -> (Arc<dyn LLMProvider>, Vec<Arc<dyn ToolT>>) {
+    let llm: Arc<dyn LLMProvider> = Arc::new(BatchLLM {
+        inner: llm,
+        gate: gate.clone(),
+    });
+    let tools = tools
+        .into_iter()
+        .map(|tool| {
+            if gate.mutating.contains(tool.name()) {
+                Arc::new(BatchTool {
+                    inner: tool,
+                    gate: gate.clone(),
+                }) as Arc<dyn ToolT>
+            } else {
+                tool
+            }
+        })
+        .collect();
+    (llm, tools)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BatchGate;
+    use crate::permissions::PermissionEngine;
+    use autoagents_llm::{FunctionCall, ToolCall};
+    use odyssey_rs_config::{
+        ApprovalPolicyProfile, ApprovalTimeoutDecision, PermissionMode, PermissionsConfig,
+    };
+    use odyssey_rs_protocol::{ApprovalDecision, PermissionRequest};
+    use odyssey_rs_test_utils::ScriptedApprovalHandler;
+    use odyssey_rs_tools::PermissionContext;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    fn call(name: &str, arguments: &str) -> ToolCall {
+        ToolCall {
+            id: format!("call_{name}"),
+            call_type: "function".to_string(),
+            function: FunctionCall {
+                name: name.to_string(),
+                arguments: arguments.to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn confirms_each_batch_once() {
+        let engine = Arc::new(
+            PermissionEngine::new(PermissionsConfig {
+                mode: PermissionMode::ConfirmBatch,
+                rules: Vec::new(),
+                webhook: None,
+                approval_timeout_secs: None,
+                timeout_decision: ApprovalTimeoutDecision::Deny,
+                profile: ApprovalPolicyProfile::Interactive,
+            })
+            .expect("engine"),
+        );
+        let handler = Arc::new(ScriptedApprovalHandler::new([
+            ApprovalDecision::AllowOnce,
+            ApprovalDecision::Deny,
+        ]));
+        engine.set_approval_handler(Some(handler.clone()));
+        let ctx = PermissionContext {
+            session_id: Uuid::new_v4(),
+            agent_id: "agent".to_string(),
+            tool_name: None,
+            turn_id: Some(Uuid::new_v4()),
+        };
+        let mutating = ["Write", "Bash"].map(str::to_string).into_iter().collect();
+        let gate = BatchGate::new(engine, ctx, None, mutating);
+
+        gate.begin_batch().await;
+        gate.record(&[
+            call("Read", r#"{"path":"a.txt"}"#),
+            call("Write", r#"{"path":"b.txt","content":"hi"}"#),
+            call("Bash", r#"{"command":"cargo test"}"#),
+        ]);
+        let args = json!({ "path": "b.txt", "content": "hi" });
+        let first = gate.confirm("Write", &args).await.expect("confirm");
+        let second = gate
+            .confirm("Bash", &json!({ "command": "cargo test" }))
+            .await
+            .expect("confirm");
+        assert_eq!((first.allowed, second.allowed), (true, true));
+        let requests = handler.requests();
+        assert_eq!(requests.len(), 1);
+        match &requests[0].request {
+            PermissionRequest::ToolBatch { calls } => {
+                let names = calls
+                    .iter()
+                    .map(|call| call.tool_name.as_str())
+                    .collect::<Vec<_>>();
+                assert_eq!(names, vec!["Write", "Bash"]);
+                assert_eq!(calls[0].arguments, args);
+            }
+            other => panic!("unexpected request: {other:?}"),
+        }
+
+        gate.begin_batch().await;
+        let denied = gate.confirm("Write", &args).await.expect("confirm");
+        assert_eq!(denied.allowed, false);
+        assert_eq!(handler.requests().len(), 2);
+    }
+}
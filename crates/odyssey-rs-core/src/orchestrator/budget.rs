@@ -0,0 +1,485 @@
+//! Spending limits enforced from provider-reported token usage.
+//!
+//! Each `TokenUsage` report is priced with `budget.models` and added to the
+//! session and UTC day totals, overall and per model. Crossing `warn_ratio` of
+//! a limit emits one `BudgetWarning`. Once a limit is reached, new turns fail
+//! with `BudgetExceeded` until `Orchestrator::allow_budget_overrun` lets one
+//! more turn of the session run; the turn in flight is never cut off.
+//!
+//! Totals are saved to `budget.path` (default `.odyssey/budget.json` in the
+//! workspace) after every priced call and reloaded before limits are checked,
+//! so they survive restarts and are shared by processes in one workspace.
+//! Writers hold the file's `.lock` sibling and replace it atomically.
+
+use crate::atomic_file::{lock_exclusive, write_atomic};
+use crate::types::SessionId;
+use chrono::{NaiveDate, Utc};
+use log::{debug, info, warn};
+use odyssey_rs_config::BudgetConfig;
+use odyssey_rs_protocol::{BudgetLimit, BudgetScope, TokenUsage};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// Fraction of a limit that triggers a warning when not configured.
+const DEFAULT_WARN_RATIO: f64 = 0.8;
+
+/// Budget file in the workspace when `budget.path` is unset.
+const DEFAULT_BUDGET_PATH: &str = ".odyssey/budget.json";
+
+/// Spend reported by `Orchestrator::budget_usage`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetUsage {
+    /// USD spent by the session.
+    pub session_usd: f64,
+    /// USD spent by all sessions in the current UTC day.
+    pub daily_usd: f64,
+}
+
+/// Spend in one scope, overall and per model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Spend {
+    total: f64,
+    models: HashMap<String, f64>,
+}
+
+impl Spend {
+    fn add(&mut self, model: Option<&str>, cost: f64) {
+        self.total += cost;
+        if let Some(model) = model {
+            *self.models.entry(model.to_string()).or_default() += cost;
+        }
+    }
+
+    fn get(&self, model: Option<&str>) -> f64 {
+        match model {
+            Some(model) => self.models.get(model).copied().unwrap_or(0.0),
+            None => self.total,
+        }
+    }
+}
+
+/// Limit already warned about; `session` is `None` for daily limits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct WarnKey {
+    session: Option<SessionId>,
+    model: Option<String>,
+}
+
+/// Totals kept in the budget file.
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedSpend {
+    day: NaiveDate,
+    daily: Spend,
+    sessions: HashMap<SessionId, Spend>,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    day: NaiveDate,
+    daily: Spend,
+    sessions: HashMap<SessionId, Spend>,
+    warned: HashSet<WarnKey>,
+    /// Sessions allowed to start one turn past an exceeded limit.
+    overruns: HashSet<SessionId>,
+}
+
+impl BudgetState {
+    /// Start a new daily total when the UTC day changed.
+    fn roll_to(&mut self, today: NaiveDate) {
+        if self.day == today {
+            return;
+        }
+        debug!("starting daily budget for {today}");
+        self.day = today;
+        self.daily = Spend::default();
+        self.warned.retain(|key| key.session.is_some());
+    }
+}
+
+/// Tracks spend and enforces `budget` limits.
+#[derive(Debug)]
+pub(crate) struct BudgetTracker {
+    config: BudgetConfig,
+    /// Budget file; `None` keeps totals in memory.
+    path: Option<PathBuf>,
+    state: Mutex<BudgetState>,
+}
+
+impl BudgetTracker {
+    /// Tracker whose totals are kept only in memory.
+    pub(crate) fn new(config: BudgetConfig) -> Self {
+        Self {
+            config,
+            path: None,
+            state: Mutex::new(BudgetState {
+                day: Utc::now().date_naive(),
+                daily: Spend::default(),
+                sessions: HashMap::new(),
+                warned: HashSet::new(),
+                overruns: HashSet::new(),
+            }),
+        }
+    }
+
+    /// Tracker saving totals to `budget.path`, resolved against `workspace_root`.
+    pub(crate) fn load(config: BudgetConfig, workspace_root: &Path) -> Self {
+        let path = workspace_root.join(config.path.as_deref().unwrap_or(DEFAULT_BUDGET_PATH));
+        let tracker = Self {
+            path: Some(path),
+            ..Self::new(config)
+        };
+        tracker.reload(&mut tracker.state.lock());
+        tracker
+    }
+
+    /// Add the cost of `usage` and return the limits that crossed the warning threshold.
+    pub(crate) fn record(
+        &self,
+        session_id: SessionId,
+        model: Option<&str>,
+        usage: &TokenUsage,
+    ) -> Vec<BudgetLimit> {
+        let cost = self.cost(model, usage);
+        if cost <= 0.0 {
+            return Vec::new();
+        }
+        let warn_ratio = self.config.warn_ratio.unwrap_or(DEFAULT_WARN_RATIO);
+        let mut state = self.state.lock();
+        // Add to the latest totals under the file lock so concurrent writers do not lose spend.
+        let _lock = self.path.as_deref().and_then(|path| {
+            lock_budget(path)
+                .inspect_err(|err| {
+                    warn!(
+                        "failed to lock budget file (path={}): {err}",
+                        path.display()
+                    )
+                })
+                .ok()
+        });
+        self.reload(&mut state);
+        state.roll_to(Utc::now().date_naive());
+        state.daily.add(model, cost);
+        state
+            .sessions
+            .entry(session_id)
+            .or_default()
+            .add(model, cost);
+        self.save(&state);
+        let limits = self.limits(&state, session_id, model);
+        limits
+            .into_iter()
+            .filter(|limit| limit.spent_usd >= limit.limit_usd * warn_ratio)
+            .filter(|limit| {
+                state.warned.insert(WarnKey {
+                    session: (limit.scope == BudgetScope::Session).then_some(session_id),
+                    model: limit.model.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Fail with the first reached limit unless the session was allowed an overrun.
+    pub(crate) fn check(
+        &self,
+        session_id: SessionId,
+        model: Option<&str>,
+    ) -> Result<(), BudgetLimit> {
+        let mut state = self.state.lock();
+        self.reload(&mut state);
+        state.roll_to(Utc::now().date_naive());
+        let Some(limit) = self
+            .limits(&state, session_id, model)
+            .into_iter()
+            .find(|limit| limit.spent_usd >= limit.limit_usd)
+        else {
+            return Ok(());
+        };
+        if state.overruns.remove(&session_id) {
+            info!("running turn past exceeded budget (session_id={session_id}): {limit}");
+            return Ok(());
+        }
+        warn!("budget exceeded (session_id={session_id}): {limit}");
+        Err(limit)
+    }
+
+    /// Let the next turn of `session_id` that would be refused run anyway.
+    pub(crate) fn allow_overrun(&self, session_id: SessionId) {
+        self.state.lock().overruns.insert(session_id);
+    }
+
+    /// Spend of `session_id` and of the current day.
+    pub(crate) fn usage(&self, session_id: SessionId) -> BudgetUsage {
+        let mut state = self.state.lock();
+        self.reload(&mut state);
+        state.roll_to(Utc::now().date_naive());
+        BudgetUsage {
+            session_usd: state
+                .sessions
+                .get(&session_id)
+                .map_or(0.0, |spend| spend.total),
+            daily_usd: state.daily.total,
+        }
+    }
+
+    /// Replace the totals with those in the budget file, which other processes may have updated.
+    fn reload(&self, state: &mut BudgetState) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let contents = match fs::read(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return,
+            Err(err) => {
+                warn!(
+                    "failed to read budget file (path={}): {err}",
+                    path.display()
+                );
+                return;
+            }
+        };
+        match serde_json::from_slice::<SavedSpend>(&contents) {
+            Ok(saved) => {
+                state.day = saved.day;
+                state.daily = saved.daily;
+                state.sessions = saved.sessions;
+            }
+            Err(err) => warn!(
+                "ignoring unreadable budget file (path={}): {err}",
+                path.display()
+            ),
+        }
+    }
+
+    /// Write the totals to the budget file; callers hold its lock.
+    fn save(&self, state: &BudgetState) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let saved = SavedSpend {
+            day: state.day,
+            daily: state.daily.clone(),
+            sessions: state.sessions.clone(),
+        };
+        let result = serde_json::to_vec(&saved)
+            .map_err(io::Error::other)
+            .and_then(|contents| write_atomic(path, &contents));
+        if let Err(err) = result {
+            warn!(
+                "failed to save budget file (path={}): {err}",
+                path.display()
+            );
+        }
+    }
+
+    /// USD cost of one call; models without a price cost nothing.
+    fn cost(&self, model: Option<&str>, usage: &TokenUsage) -> f64 {
+        let Some(price) = model.and_then(|model| self.config.models.get(model)) else {
+            return 0.0;
+        };
+        let cached = usage
+            .cached_prompt_tokens
+            .unwrap_or(0)
+            .min(usage.prompt_tokens);
+        let cached_price = price
+            .cached_input_usd_per_mtok
+            .unwrap_or(price.input_usd_per_mtok);
+        ((usage.prompt_tokens - cached) as f64 * price.input_usd_per_mtok
+            + cached as f64 * cached_price
+            + usage.completion_tokens as f64 * price.output_usd_per_mtok)
+            / 1_000_000.0
+    }
+
+    /// Configured limits that apply to a turn of `session_id` on `model`.
+    fn limits(
+        &self,
+        state: &BudgetState,
+        session_id: SessionId,
+        model: Option<&str>,
+    ) -> Vec<BudgetLimit> {
+        let session = state.sessions.get(&session_id);
+        let spent = |scope: BudgetScope, model: Option<&str>| match scope {
+            BudgetScope::Session => session.map_or(0.0, |spend| spend.get(model)),
+            BudgetScope::Daily => state.daily.get(model),
+        };
+        let model_limits = model.and_then(|name| {
+            self.config
+                .models
+                .get(name)
+                .map(|config| (name, config.max_session_usd, config.max_daily_usd))
+        });
+        let mut candidates = vec![
+            (BudgetScope::Session, None, self.config.max_session_usd),
+            (BudgetScope::Daily, None, self.config.max_daily_usd),
+        ];
+        if let Some((name, max_session, max_daily)) = model_limits {
+            candidates.push((BudgetScope::Session, Some(name), max_session));
+            candidates.push((BudgetScope::Daily, Some(name), max_daily));
+        }
+        candidates
+            .into_iter()
+            .filter_map(|(scope, model, limit)| {
+                limit.map(|limit_usd| BudgetLimit {
+                    scope,
+                    model: model.map(str::to_string),
+                    spent_usd: spent(scope, model),
+                    limit_usd,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Create the budget file's directory and take its lock.
+fn lock_budget(path: &Path) -> io::Result<fs::File> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    lock_exclusive(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BudgetTracker, BudgetUsage};
+    use chrono::{Days, Utc};
+    use odyssey_rs_config::{BudgetConfig, ModelBudgetConfig};
+    use odyssey_rs_protocol::{BudgetScope, TokenUsage};
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+    use uuid::Uuid;
+
+    const MODEL: &str = "openai/gpt-4.1";
+
+    fn tracker(config: BudgetConfig) -> BudgetTracker {
+        BudgetTracker::new(BudgetConfig {
+            models: BTreeMap::from([(
+                MODEL.to_string(),
+                ModelBudgetConfig {
+                    input_usd_per_mtok: 2.0,
+                    cached_input_usd_per_mtok: Some(0.5),
+                    output_usd_per_mtok: 8.0,
+                    ..config.models.get(MODEL).cloned().unwrap_or_default()
+                },
+            )]),
+            ..config
+        })
+    }
+
+    fn usage(prompt_tokens: u64, completion_tokens: u64) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+            ..TokenUsage::default()
+        }
+    }
+
+    #[test]
+    fn prices_usage_and_ignores_unpriced_models() {
+        let tracker = tracker(BudgetConfig::default());
+        let session_id = Uuid::new_v4();
+        let cached = TokenUsage {
+            cached_prompt_tokens: Some(500_000),
+            ..usage(1_000_000, 100_000)
+        };
+        tracker.record(session_id, Some(MODEL), &cached);
+        tracker.record(session_id, Some("openai/unpriced"), &usage(1_000_000, 0));
+        tracker.record(session_id, None, &usage(1_000_000, 0));
+        assert_eq!(
+            tracker.usage(session_id),
+            BudgetUsage {
+                session_usd: 2.05,
+                daily_usd: 2.05,
+            }
+        );
+    }
+
+    #[test]
+    fn warns_once_and_refuses_turns_until_overrun_allowed() {
+        let tracker = tracker(BudgetConfig {
+            max_session_usd: Some(10.0),
+            ..BudgetConfig::default()
+        });
+        let session_id = Uuid::new_v4();
+        // $8 reaches the default 80% threshold.
+        let warnings = tracker.record(session_id, Some(MODEL), &usage(0, 1_000_000));
+        assert_eq!(
+            warnings
+                .iter()
+                .map(|limit| (limit.scope, limit.spent_usd))
+                .collect::<Vec<_>>(),
+            vec![(BudgetScope::Session, 8.0)]
+        );
+        assert_eq!(tracker.check(session_id, Some(MODEL)), Ok(()));
+
+        let warnings = tracker.record(session_id, Some(MODEL), &usage(1_000_000, 0));
+        assert_eq!(warnings, Vec::new());
+        let limit = tracker
+            .check(session_id, Some(MODEL))
+            .expect_err("exceeded");
+        assert_eq!(limit.spent_usd, 10.0);
+        assert_eq!(tracker.check(Uuid::new_v4(), Some(MODEL)), Ok(()));
+
+        tracker.allow_overrun(session_id);
+        assert_eq!(tracker.check(session_id, Some(MODEL)), Ok(()));
+        assert_eq!(tracker.check(session_id, Some(MODEL)).is_err(), true);
+    }
+
+    #[test]
+    fn model_and_daily_limits_span_sessions() {
+        let mut config = BudgetConfig {
+            max_daily_usd: Some(100.0),
+            ..BudgetConfig::default()
+        };
+        config.models.insert(
+            MODEL.to_string(),
+            ModelBudgetConfig {
+                max_daily_usd: Some(4.0),
+                ..ModelBudgetConfig::default()
+            },
+        );
+        let tracker = tracker(config);
+        tracker.record(Uuid::new_v4(), Some(MODEL), &usage(1_000_000, 0));
+        tracker.record(Uuid::new_v4(), Some(MODEL), &usage(1_000_000, 0));
+        let session_id = Uuid::new_v4();
+        let limit = tracker
+            .check(session_id, Some(MODEL))
+            .expect_err("model budget");
+        assert_eq!(limit.scope, BudgetScope::Daily);
+        assert_eq!(limit.model.as_deref(), Some(MODEL));
+        assert_eq!(tracker.check(session_id, Some("openai/other")), Ok(()));
+
+        let tomorrow = Utc::now()
+            .date_naive()
+            .checked_add_days(Days::new(1))
+            .expect("date");
+        tracker.state.lock().roll_to(tomorrow);
+        assert_eq!(tracker.state.lock().daily.total, 0.0);
+    }
+
+    #[test]
+    fn spend_survives_restarts_and_is_shared() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let config = tracker(BudgetConfig::default()).config;
+        let session_id = Uuid::new_v4();
+        let first = BudgetTracker::load(config.clone(), temp.path());
+        first.record(session_id, Some(MODEL), &usage(1_000_000, 0));
+        assert_eq!(
+            temp.path().join(".odyssey").join("budget.json").exists(),
+            true
+        );
+
+        let second = BudgetTracker::load(config, temp.path());
+        assert_eq!(second.usage(session_id).session_usd, 2.0);
+        second.record(Uuid::new_v4(), Some(MODEL), &usage(0, 1_000_000));
+        assert_eq!(
+            first.usage(session_id),
+            BudgetUsage {
+                session_usd: 2.0,
+                daily_usd: 10.0,
+            }
+        );
+    }
+}
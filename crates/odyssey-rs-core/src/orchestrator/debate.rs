@@ -0,0 +1,376 @@
+//! Round-robin debates where several agents take turns on one task.
+
+use super::registry::AgentEntry;
+use super::runtime::{MemoryMode, ToolResultMode, TurnExecutor, TurnParams};
+use super::sequence::EventSequencer;
+use crate::error::OdysseyCoreError;
+use crate::types::SessionId;
+use autoagents_llm::LLMProvider;
+use log::{debug, info};
+use odyssey_rs_protocol::{EventMsg, EventSink};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::BroadcastStream;
+use uuid::Uuid;
+
+/// Default number of rounds when a request does not set one.
+pub const DEFAULT_DEBATE_ROUNDS: usize = 3;
+const DEBATE_STREAM_BUFFER: usize = 1024;
+/// First word a judge uses to end the debate.
+const JUDGE_DONE: &str = "DONE";
+
+/// Condition that can end a debate before `max_rounds`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DebateTermination {
+    /// Always run `max_rounds` rounds.
+    MaxRounds,
+    /// Stop once no agent changes its contribution between two rounds.
+    Convergence,
+    /// Ask a judge agent after each round; stop when it answers `DONE`.
+    Judge { agent_id: String },
+}
+
+/// Request to run registered agents in alternating turns on one task.
+#[derive(Debug, Clone)]
+pub struct DebateRequest {
+    /// Agents in speaking order; at least two distinct ids.
+    pub agents: Vec<String>,
+    /// Task every agent works on.
+    pub task: String,
+    /// LLM id used for every turn; defaults to the orchestrator default.
+    pub llm_id: Option<String>,
+    /// Maximum number of rounds; each round gives every agent one turn.
+    pub max_rounds: usize,
+    /// Early termination condition.
+    pub termination: DebateTermination,
+}
+
+impl DebateRequest {
+    /// Create a request that runs the default number of rounds.
+    pub fn new(agents: Vec<String>, task: impl Into<String>) -> Self {
+        Self {
+            agents,
+            task: task.into(),
+            llm_id: None,
+            max_rounds: DEFAULT_DEBATE_ROUNDS,
+            termination: DebateTermination::MaxRounds,
+        }
+    }
+}
+
+/// One agent's response in a debate round.
+#[derive(Debug, Clone, Serialize)]
+pub struct DebateContribution {
+    /// Round number, starting at 1.
+    pub round: usize,
+    /// Agent that produced the contribution.
+    pub agent_id: String,
+    /// Session holding the agent's side of the debate.
+    pub session_id: SessionId,
+    /// Assistant response text.
+    pub content: String,
+}
+
+/// Why a debate stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebateStopReason {
+    /// All rounds ran.
+    MaxRounds,
+    /// No agent changed its contribution.
+    Converged,
+    /// The judge agent ended the debate.
+    Judge,
+}
+
+/// Final outcome of a debate.
+#[derive(Debug, Clone, Serialize)]
+pub struct DebateResult {
+    /// Contributions in speaking order.
+    pub contributions: Vec<DebateContribution>,
+    /// Number of rounds that ran.
+    pub rounds: usize,
+    /// Why the debate stopped.
+    pub stop_reason: DebateStopReason,
+    /// Judge's final reply, when a judge ended the debate.
+    pub verdict: Option<String>,
+}
+
+/// Turn event tagged with the agent and round that produced it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DebateEvent {
+    /// Round number, starting at 1.
+    pub round: usize,
+    /// Agent whose turn emitted the event (the judge for judge turns).
+    pub agent_id: String,
+    /// Underlying turn event.
+    pub event: EventMsg,
+}
+
+/// Streaming handle for a debate.
+pub struct DebateStream {
+    /// Session used by each participating agent, including the judge.
+    pub sessions: HashMap<String, SessionId>,
+    /// Agent-tagged events from every turn in the debate.
+    pub events: BroadcastStream<DebateEvent>,
+    handle: JoinHandle<Result<DebateResult, OdysseyCoreError>>,
+}
+
+impl DebateStream {
+    /// Await the end of the debate and return its result.
+    pub async fn finish(self) -> Result<DebateResult, OdysseyCoreError> {
+        self.handle
+            .await
+            .map_err(|err| OdysseyCoreError::Executor(err.to_string()))?
+    }
+}
+
+/// Agent taking part in a debate, with its own session.
+#[derive(Clone)]
+pub(super) struct DebateAgent {
+    pub(super) entry: AgentEntry,
+    pub(super) session_id: SessionId,
+}
+
+/// Drives the rounds of a single debate.
+pub(super) struct DebateRunner {
+    pub(super) executor: Arc<TurnExecutor>,
+    pub(super) llm: Arc<dyn LLMProvider>,
+    pub(super) task: String,
+    pub(super) max_rounds: usize,
+    pub(super) agents: Vec<DebateAgent>,
+    pub(super) judge: Option<DebateAgent>,
+    pub(super) convergence: bool,
+    pub(super) event_sink: Option<Arc<dyn EventSink>>,
+    pub(super) sequencer: EventSequencer,
+}
+
+impl DebateRunner {
+    /// Start the debate on a background task.
+    pub(super) fn spawn(self) -> DebateStream {
+        let (sender, receiver) = broadcast::channel(DEBATE_STREAM_BUFFER);
+        let sessions = self
+            .agents
+            .iter()
+            .chain(self.judge.as_ref())
+            .map(|agent| (agent.entry.id.clone(), agent.session_id))
+            .collect();
+        let handle = tokio::spawn(async move { self.run(sender).await });
+        DebateStream {
+            sessions,
+            events: BroadcastStream::new(receiver),
+            handle,
+        }
+    }
+
+    async fn run(
+        self,
+        sender: broadcast::Sender<DebateEvent>,
+    ) -> Result<DebateResult, OdysseyCoreError> {
+        let mut contributions: Vec<DebateContribution> = Vec::new();
+        // Index into `contributions` up to which each agent has seen the debate.
+        let mut seen = vec![0; self.agents.len()];
+        let mut previous_round: Vec<String> = Vec::new();
+
+        for round in 1..=self.max_rounds {
+            let round_start = contributions.len();
+            for (idx, agent) in self.agents.iter().enumerate() {
+                let input = self.agent_input(agent, round, seen[idx], &contributions);
+                let content = self.run_turn(agent, round, input, &sender).await?;
+                contributions.push(DebateContribution {
+                    round,
+                    agent_id: agent.entry.id.clone(),
+                    session_id: agent.session_id,
+                    content,
+                });
+                seen[idx] = contributions.len();
+            }
+            let current_round = contributions[round_start..]
+                .iter()
+                .map(|contribution| normalize(&contribution.content))
+                .collect::<Vec<_>>();
+
+            if let Some(judge) = &self.judge {
+                let input = self.judge_input(round, &contributions[round_start..]);
+                let reply = self.run_turn(judge, round, input, &sender).await?;
+                if is_done(&reply) {
+                    info!("debate ended by judge (round={round})");
+                    return Ok(DebateResult {
+                        contributions,
+                        rounds: round,
+                        stop_reason: DebateStopReason::Judge,
+                        verdict: Some(reply),
+                    });
+                }
+            }
+            if self.convergence && current_round == previous_round {
+                info!("debate converged (round={round})");
+                return Ok(DebateResult {
+                    contributions,
+                    rounds: round,
+                    stop_reason: DebateStopReason::Converged,
+                    verdict: None,
+                });
+            }
+            previous_round = current_round;
+        }
+
+        Ok(DebateResult {
+            contributions,
+            rounds: self.max_rounds,
+            stop_reason: DebateStopReason::MaxRounds,
+            verdict: None,
+        })
+    }
+
+    /// Run one turn in the agent's session and return its response.
+    async fn run_turn(
+        &self,
+        agent: &DebateAgent,
+        round: usize,
+        input: String,
+        sender: &broadcast::Sender<DebateEvent>,
+    ) -> Result<String, OdysseyCoreError> {
+        debug!(
+            "debate turn (round={round}, agent_id={}, session_id={})",
+            agent.entry.id, agent.session_id
+        );
+        let sink = self.sequencer.wrap(Arc::new(DebateEventSink {
+            round,
+            agent_id: agent.entry.id.clone(),
+            sender: sender.clone(),
+            primary: self.event_sink.clone(),
+        }));
+        let _permit = self.executor.acquire_turn(agent.session_id).await?;
+        let result = self
+            .executor
+            .run_turn(TurnParams {
+                session_id: agent.session_id,
+                agent_id: agent.entry.id.clone(),
+                llm: self.llm.clone(),
+                input,
+                entry: agent.entry.clone(),
+                include_subagent_spawner: true,
+                tool_result_mode: ToolResultMode::SessionAndMemory,
+                memory_mode: MemoryMode::AgentProvider,
+                turn_id: Some(Uuid::new_v4()),
+                event_sink: Some(sink),
+                stream: true,
+                record_input: true,
+                user_id: None,
+            })
+            .await?;
+        Ok(result.response)
+    }
+
+    /// Prompt for a participant: the task on its first turn, then what others said since.
+    fn agent_input(
+        &self,
+        agent: &DebateAgent,
+        round: usize,
+        seen: usize,
+        contributions: &[DebateContribution],
+    ) -> String {
+        let mut input = String::new();
+        if round == 1 {
+            let _ = write!(input, "Task: {}\n\n", self.task);
+        }
+        let unseen = &contributions[seen..];
+        if !unseen.is_empty() {
+            input.push_str("Contributions since your last turn:\n\n");
+            input.push_str(&render_contributions(unseen));
+        }
+        let _ = write!(
+            input,
+            "You are {} in round {round} of {}. Build on or challenge the other contributions \
+and give your current answer.",
+            agent.entry.id, self.max_rounds
+        );
+        input
+    }
+
+    /// Prompt asking the judge whether the round settled the task.
+    fn judge_input(&self, round: usize, round_contributions: &[DebateContribution]) -> String {
+        format!(
+            "Task: {}\n\nRound {round} contributions:\n\n{}If the agents have reached a good answer, \
+reply with {JUDGE_DONE} on the first line followed by the final answer. Otherwise reply CONTINUE \
+and say what is still unresolved.",
+            self.task,
+            render_contributions(round_contributions)
+        )
+    }
+}
+
+/// Forwards turn events to the orchestrator sink and the debate stream with agent tags.
+struct DebateEventSink {
+    round: usize,
+    agent_id: String,
+    sender: broadcast::Sender<DebateEvent>,
+    primary: Option<Arc<dyn EventSink>>,
+}
+
+impl EventSink for DebateEventSink {
+    fn emit(&self, event: EventMsg) {
+        if let Some(primary) = &self.primary {
+            primary.emit(event.clone());
+        }
+        let _ = self.sender.send(DebateEvent {
+            round: self.round,
+            agent_id: self.agent_id.clone(),
+            event,
+        });
+    }
+}
+
+fn render_contributions(contributions: &[DebateContribution]) -> String {
+    let mut rendered = String::new();
+    for contribution in contributions {
+        let _ = write!(
+            rendered,
+            "[{} · round {}]\n{}\n\n",
+            contribution.agent_id,
+            contribution.round,
+            contribution.content.trim()
+        );
+    }
+    rendered
+}
+
+/// Whitespace- and case-insensitive form used for convergence checks.
+fn normalize(content: &str) -> String {
+    content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn is_done(reply: &str) -> bool {
+    reply
+        .split_whitespace()
+        .next()
+        .is_some_and(|word| word.trim_matches(|ch: char| !ch.is_alphanumeric()) == JUDGE_DONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_done, normalize};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn judge_reply_must_start_with_done() {
+        assert_eq!(is_done("DONE\nThe answer is 4."), true);
+        assert_eq!(is_done("**DONE**: ship it"), true);
+        assert_eq!(is_done("CONTINUE, not DONE yet"), false);
+        assert_eq!(is_done(""), false);
+    }
+
+    #[test]
+    fn normalize_ignores_case_and_spacing() {
+        assert_eq!(normalize("  The Answer\n is  4 "), "the answer is 4");
+    }
+}
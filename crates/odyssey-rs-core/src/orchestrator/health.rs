@@ -0,0 +1,273 @@
+//! Health snapshots and stuck-turn detection.
+//!
+//! A turn counts as active while its session emits events. A watchdog runs
+//! beside each turn and, once the session has been silent for the stall
+//! timeout, emits one `TurnStalled` event. It reports again only after the turn
+//! produced events and then went silent once more.
+
+use super::sequence::EventSequencer;
+use super::shutdown::{TurnStart, TurnTracker};
+use crate::types::SessionId;
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use odyssey_rs_protocol::{EventMsg, EventPayload, EventSink, TurnId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Seconds without events before a turn is stalled when not configured.
+pub(crate) const DEFAULT_STALL_TIMEOUT_SECS: u64 = 300;
+
+/// Snapshot returned by `Orchestrator::health`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrchestratorHealth {
+    /// Sessions with a running turn or queued submissions, ordered by id.
+    pub sessions: Vec<SessionHealth>,
+    /// Turns currently executing.
+    pub running_turns: usize,
+    /// Turns waiting for a session run lock or a global turn slot.
+    pub queued_turns: usize,
+    /// Running turns silent for longer than the stall timeout.
+    pub stalled_turns: Vec<TurnId>,
+    /// Latest turn failure since the orchestrator started.
+    pub last_error: Option<HealthError>,
+}
+
+/// Activity of one session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionHealth {
+    /// Session id.
+    pub session_id: SessionId,
+    /// Turns running in the session.
+    pub turns: Vec<TurnHealth>,
+    /// Submissions waiting for the session worker.
+    pub queued_submissions: usize,
+}
+
+/// Activity of one running turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnHealth {
+    /// Turn id.
+    pub turn_id: TurnId,
+    /// Seconds since the turn started.
+    pub running_secs: u64,
+    /// Seconds since the session last emitted an event, or since the turn started.
+    pub idle_secs: u64,
+    /// Whether `idle_secs` passed the stall timeout.
+    pub stalled: bool,
+}
+
+/// A turn failure kept for health reports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthError {
+    /// Session of the failed turn.
+    pub session_id: SessionId,
+    /// Failed turn.
+    pub turn_id: TurnId,
+    /// Error message.
+    pub message: String,
+    /// When the turn failed.
+    pub at: DateTime<Utc>,
+}
+
+/// Resolve `orchestrator.stall_timeout_secs`; 0 turns detection off.
+pub(crate) fn stall_timeout(configured: Option<u64>) -> Option<Duration> {
+    match configured.unwrap_or(DEFAULT_STALL_TIMEOUT_SECS) {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+/// How long the session of a turn has been silent at `now`.
+fn idle_for(sequencer: &EventSequencer, start: TurnStart, now: Instant) -> Duration {
+    let last = sequencer
+        .last_activity(start.session_id)
+        .map_or(start.started, |at| at.max(start.started));
+    now.saturating_duration_since(last)
+}
+
+/// Build a health snapshot from running turns and queued submissions.
+pub(crate) fn collect_health(
+    turns: &TurnTracker,
+    sequencer: &EventSequencer,
+    queued_submissions: HashMap<SessionId, usize>,
+    stall_timeout: Option<Duration>,
+) -> OrchestratorHealth {
+    let now = Instant::now();
+    let mut sessions: BTreeMap<SessionId, SessionHealth> = BTreeMap::new();
+    let mut stalled_turns = Vec::new();
+    for (session_id, queued) in queued_submissions {
+        session_entry(&mut sessions, session_id).queued_submissions = queued;
+    }
+    for (turn_id, start) in turns.running_turns() {
+        let idle = idle_for(sequencer, start, now);
+        let stalled = stall_timeout.is_some_and(|timeout| idle >= timeout);
+        if stalled {
+            stalled_turns.push(turn_id);
+        }
+        session_entry(&mut sessions, start.session_id)
+            .turns
+            .push(TurnHealth {
+                turn_id,
+                running_secs: now.saturating_duration_since(start.started).as_secs(),
+                idle_secs: idle.as_secs(),
+                stalled,
+            });
+    }
+    let metrics = turns.metrics();
+    OrchestratorHealth {
+        sessions: sessions.into_values().collect(),
+        running_turns: metrics.running_turns,
+        queued_turns: metrics.queued_turns,
+        stalled_turns,
+        last_error: turns.last_error(),
+    }
+}
+
+fn session_entry(
+    sessions: &mut BTreeMap<SessionId, SessionHealth>,
+    session_id: SessionId,
+) -> &mut SessionHealth {
+    sessions.entry(session_id).or_insert_with(|| SessionHealth {
+        session_id,
+        turns: Vec::new(),
+        queued_submissions: 0,
+    })
+}
+
+/// Watches one turn for stalls until dropped.
+pub(crate) struct StallWatchdog {
+    handle: JoinHandle<()>,
+}
+
+impl StallWatchdog {
+    /// Start watching `turn_id`, reporting stalls through `event_sink`.
+    pub(crate) fn spawn(
+        sequencer: EventSequencer,
+        event_sink: Option<Arc<dyn EventSink>>,
+        session_id: SessionId,
+        turn_id: TurnId,
+        timeout: Duration,
+    ) -> Self {
+        let start = TurnStart {
+            session_id,
+            started: Instant::now(),
+        };
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(timeout / 4);
+            let mut stalled = false;
+            loop {
+                interval.tick().await;
+                let idle = idle_for(&sequencer, start, Instant::now());
+                if idle < timeout {
+                    if stalled {
+                        info!("stalled turn resumed (session_id={session_id}, turn_id={turn_id})");
+                    }
+                    stalled = false;
+                    continue;
+                }
+                if stalled {
+                    continue;
+                }
+                stalled = true;
+                let idle_secs = idle.as_secs();
+                warn!(
+                    "turn stalled (session_id={session_id}, turn_id={turn_id}, idle_secs={idle_secs})"
+                );
+                if let Some(sink) = &event_sink {
+                    sink.emit(EventMsg {
+                        id: Uuid::new_v4(),
+                        session_id,
+                        seq: 0,
+                        user_id: None,
+                        created_at: Utc::now(),
+                        payload: EventPayload::TurnStalled { turn_id, idle_secs },
+                    });
+                }
+            }
+        });
+        Self { handle }
+    }
+}
+
+impl Drop for StallWatchdog {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StallWatchdog, collect_health, stall_timeout};
+    use crate::orchestrator::sequence::EventSequencer;
+    use crate::orchestrator::shutdown::TurnTracker;
+    use odyssey_rs_protocol::{EventMsg, EventPayload, EventSink};
+    use parking_lot::Mutex;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    #[derive(Default)]
+    struct Recorder {
+        stalls: Mutex<Vec<u64>>,
+    }
+
+    impl EventSink for Recorder {
+        fn emit(&self, event: EventMsg) {
+            if let EventPayload::TurnStalled { idle_secs, .. } = event.payload {
+                self.stalls.lock().push(idle_secs);
+            }
+        }
+    }
+
+    #[test]
+    fn resolves_stall_timeout() {
+        assert_eq!(stall_timeout(None), Some(Duration::from_secs(300)));
+        assert_eq!(stall_timeout(Some(5)), Some(Duration::from_secs(5)));
+        assert_eq!(stall_timeout(Some(0)), None);
+    }
+
+    #[tokio::test]
+    async fn reports_a_silent_turn_once() {
+        let sequencer = EventSequencer::default();
+        let recorder = Arc::new(Recorder::default());
+        let sink = sequencer.wrap(recorder.clone());
+        let tracker = Arc::new(TurnTracker::default());
+        let session_id = Uuid::new_v4();
+        let turn_id = Uuid::new_v4();
+        let _running = tracker.register(turn_id, session_id);
+        let timeout = Duration::from_millis(200);
+        let _watchdog =
+            StallWatchdog::spawn(sequencer.clone(), Some(sink), session_id, turn_id, timeout);
+
+        let health = collect_health(&tracker, &sequencer, HashMap::new(), Some(timeout));
+        assert_eq!(health.stalled_turns, Vec::<Uuid>::new());
+        assert_eq!(health.sessions[0].turns[0].stalled, false);
+
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert_eq!(recorder.stalls.lock().len(), 1);
+        let health = collect_health(
+            &tracker,
+            &sequencer,
+            HashMap::from([(session_id, 2)]),
+            Some(timeout),
+        );
+        assert_eq!(health.running_turns, 1);
+        assert_eq!(health.stalled_turns, vec![turn_id]);
+        assert_eq!(health.sessions.len(), 1);
+        assert_eq!(health.sessions[0].queued_submissions, 2);
+
+        tracker.record_error(session_id, turn_id, "boom".to_string());
+        let health = collect_health(&tracker, &sequencer, HashMap::new(), None);
+        assert_eq!(health.stalled_turns, Vec::<Uuid>::new());
+        assert_eq!(
+            health.last_error.map(|error| error.message),
+            Some("boom".to_string())
+        );
+    }
+}
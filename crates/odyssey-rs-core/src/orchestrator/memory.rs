@@ -3,7 +3,12 @@
 use odyssey_rs_config::MemoryRecallMode;
 use odyssey_rs_memory::{
     MemoryCapturePolicy, MemoryCompactionPolicy, MemoryRecallOptions, MemoryRecord,
+    ScoredMemoryRecord,
 };
+use serde_json::json;
+
+/// Characters of a record kept in recall debug metadata.
+const RECALL_PREVIEW_CHARS: usize = 80;
 
 /// Translate memory capture policy from config into runtime policy.
 pub(crate) fn capture_policy_from_config(
@@ -41,6 +46,8 @@ pub(crate) fn recall_options_from_config(
     MemoryRecallOptions {
         mode: recall_mode_from_config(config.mode),
         min_score: config.min_score,
+        text_weight: config.text_weight,
+        vector_weight: config.vector_weight,
     }
 }
 
@@ -76,6 +83,31 @@ pub(crate) fn format_memory_records(records: &[MemoryRecord]) -> String {
     lines.join("\n")
 }
 
+/// Describe recalled records for turn metadata when `memory.recall.debug` is set.
+pub(crate) fn recall_debug_metadata(records: &[ScoredMemoryRecord]) -> serde_json::Value {
+    records
+        .iter()
+        .map(|scored| {
+            let preview: String = scored
+                .record
+                .content
+                .chars()
+                .take(RECALL_PREVIEW_CHARS)
+                .collect();
+            json!({
+                "record_id": scored.record.id,
+                "role": scored.record.role,
+                "preview": preview,
+                "score": scored.explanation.score,
+                "matched_terms": scored.explanation.matched_terms,
+                "text_score": scored.explanation.text_score,
+                "vector_similarity": scored.explanation.vector_similarity,
+                "recency_boost": scored.explanation.recency_boost,
+            })
+        })
+        .collect()
+}
+
 /// Map memory recall mode from config to runtime enum.
 fn recall_mode_from_config(mode: MemoryRecallMode) -> odyssey_rs_memory::MemoryRecallMode {
     match mode {
@@ -0,0 +1,131 @@
+//! Memory compaction with summaries written by the agent's LLM.
+
+use autoagents_llm::LLMProvider;
+use autoagents_llm::chat::{ChatMessage, ChatRole, MessageType};
+use log::{info, warn};
+use odyssey_rs_memory::{
+    MemoryCompactionPolicy, MemoryError, MemoryProvider, MemoryRecord, MemorySummarizer,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Summarizes compacted records with one LLM call per cluster.
+pub(crate) struct LlmSummarizer {
+    llm: Arc<dyn LLMProvider>,
+}
+
+impl LlmSummarizer {
+    /// Summarize with `llm`.
+    pub(crate) fn new(llm: Arc<dyn LLMProvider>) -> Self {
+        Self { llm }
+    }
+}
+
+#[async_trait::async_trait]
+impl MemorySummarizer for LlmSummarizer {
+    async fn summarize(
+        &self,
+        records: &[MemoryRecord],
+        max_chars: usize,
+    ) -> Result<String, MemoryError> {
+        let messages = vec![
+            ChatMessage {
+                role: ChatRole::System,
+                message_type: MessageType::Text,
+                content: summary_instructions(max_chars),
+            },
+            ChatMessage {
+                role: ChatRole::User,
+                message_type: MessageType::Text,
+                content: render_excerpt(records),
+            },
+        ];
+        let response = self
+            .llm
+            .chat_with_tools(&messages, None, None)
+            .await
+            .map_err(|err| MemoryError::Summarizer(err.to_string()))?;
+        response
+            .text()
+            .filter(|text| !text.trim().is_empty())
+            .ok_or_else(|| MemoryError::Summarizer("empty summary".to_string()))
+    }
+}
+
+/// Compact a session in the background after a turn; failures are logged.
+pub(crate) fn spawn_memory_compaction(
+    provider: Arc<dyn MemoryProvider>,
+    llm: Arc<dyn LLMProvider>,
+    policy: MemoryCompactionPolicy,
+    session_id: Uuid,
+) {
+    tokio::spawn(async move {
+        let summarizer = LlmSummarizer::new(llm);
+        match provider
+            .compact_with_summarizer(session_id, &policy, &summarizer, false)
+            .await
+        {
+            Ok(Some(compaction)) => info!(
+                "background memory compaction finished (session_id={}, compaction_id={}, replaced={})",
+                session_id,
+                compaction.id,
+                compaction.replaced.len()
+            ),
+            Ok(None) => {}
+            Err(err) => warn!(
+                "background memory compaction failed (session_id={}): {}",
+                session_id, err
+            ),
+        }
+    });
+}
+
+fn summary_instructions(max_chars: usize) -> String {
+    format!(
+        "You compress conversation history for long-term memory. Summarize the excerpt \
+in at most {max_chars} characters. Keep decisions, facts, file paths, and open tasks; \
+drop greetings and repetition. Reply with the summary only."
+    )
+}
+
+fn render_excerpt(records: &[MemoryRecord]) -> String {
+    records
+        .iter()
+        .map(|record| format!("{}: {}", record.role, record.content))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LlmSummarizer;
+    use chrono::Utc;
+    use odyssey_rs_memory::{MemoryRecord, MemorySummarizer};
+    use odyssey_rs_test_utils::RecordingChatLLM;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn summarizes_records_as_transcript() {
+        let llm = Arc::new(RecordingChatLLM::new("User set up CI."));
+        let record = MemoryRecord {
+            id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            role: "user".to_string(),
+            content: "please add CI".to_string(),
+            metadata: serde_json::json!({}),
+            created_at: Utc::now(),
+        };
+        let summary = LlmSummarizer::new(llm.clone())
+            .summarize(&[record], 200)
+            .await
+            .expect("summary");
+        assert_eq!(summary, "User set up CI.");
+
+        let messages = llm.last_messages.lock().clone();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content.contains("at most 200 characters"), true);
+        assert_eq!(messages[1].content, "user: please add CI");
+    }
+}
@@ -1,50 +1,114 @@
 //! Orchestrator Core
 
 mod agent_factory;
+mod batch_confirm;
+mod budget;
+mod debate;
+mod health;
 mod memory;
+mod memory_compaction;
+mod project_memory;
 pub mod prompt;
+pub(crate) mod prompt_cache;
 mod registry;
 mod runtime;
+mod scheduler;
+mod sequence;
 mod sessions;
+mod shutdown;
+mod stream_stats;
+mod structured;
+mod submissions;
 mod tool_context;
+mod triggers;
+mod turn_guard;
+pub use budget::BudgetUsage;
+pub use debate::{
+    DEFAULT_DEBATE_ROUNDS, DebateContribution, DebateEvent, DebateRequest, DebateResult,
+    DebateStopReason, DebateStream, DebateTermination,
+};
+pub use health::{HealthError, OrchestratorHealth, SessionHealth, TurnHealth};
 pub use registry::LLMEntry;
+pub use scheduler::{ScheduledJobInfo, ScheduledRun, Scheduler};
+pub use shutdown::{RunMetrics, ShutdownReport};
+pub use structured::{DEFAULT_OUTPUT_SCHEMA_RETRIES, OutputSchema};
+pub use submissions::SubmissionReceipt;
+pub use triggers::{TriggerEvent, TriggerInfo, TriggerRouter, TriggeredRun};
 
 use crate::AgentBuilder;
+use crate::OdysseyAgent;
 use crate::agent::AgentInstance;
+use crate::artifacts::ArtifactStore;
+use crate::attachments::render_with_attachments;
 use crate::error::OdysseyCoreError;
+use crate::guardrails::GuardrailHook;
+use crate::hooks::{LifecycleHook, LifecycleHooks};
+use crate::import::{SessionImportFormat, parse_transcript};
+use crate::model_catalog::ModelCatalogEntry;
+use crate::model_manager::{
+    CachedModel, ModelDownloadProgress, ModelDownloadRequest, ModelManager,
+};
+use crate::orchestrator::prompt::EffectivePrompt;
 use crate::orchestrator::registry::LLMRegistry;
 use crate::permissions::{ApprovalHandler, ApprovalRequest, PermissionEngine, PermissionHook};
+use crate::prompts::{PromptPreset, presets_from_config};
+use crate::replay::ReplayMode;
+use crate::share::{Redactor, ShareOptions, SharedSession, render_session_html};
 use crate::skills::SkillStore;
 use crate::state::{JsonlStateStore, StateStore};
+use crate::templates::agent_template;
 use crate::tools::ToolRouter;
-use crate::types::{AgentInfo, OdysseyAgentRuntime, Session, SessionId, SessionSummary};
+use crate::types::{
+    AgentInfo, OdysseyAgentRuntime, Role, Session, SessionId, SessionPage, SessionSummary,
+};
+use crate::workspace_lock::WorkspaceLock;
+use autoagents_core::agent::prebuilt::executor::ReActAgent;
 use autoagents_core::agent::{AgentDeriveT, AgentExecutor};
 use autoagents_llm::LLMProvider;
 use directories::BaseDirs;
+use futures_util::FutureExt;
 use log::{debug, info, warn};
-use odyssey_rs_config::{OdysseyConfig, SessionsConfig};
-use odyssey_rs_protocol::{EventMsg, EventSink, SkillProvider, SkillSummary, TurnId};
+use odyssey_rs_config::{ApprovalWebhookConfig, ArtifactsConfig, OdysseyConfig, SessionsConfig};
+use odyssey_rs_memory::{
+    MemoryCompaction, MemoryCompactionPolicy, MemoryRecord, ScoredMemoryRecord,
+};
+use odyssey_rs_protocol::{
+    ArtifactProvider, ArtifactPublish, ArtifactSummary, Attachment, EventMsg, EventSink,
+    SkillProvider, SkillSummary, SubmissionEnvelope, TurnContextOverride, TurnId,
+};
 #[cfg(target_os = "linux")]
 use odyssey_rs_sandbox::BubblewrapProvider;
-use odyssey_rs_sandbox::{LocalSandboxProvider, SandboxProvider, default_provider_name};
-use odyssey_rs_tools::{QuestionHandler, ToolRegistry};
+use odyssey_rs_sandbox::{
+    LocalSandboxProvider, SandboxPool, SandboxPoolStats, SandboxProvider, default_provider_name,
+};
+use odyssey_rs_tools::{EnvironmentReport, QuestionHandler, ToolRegistry, ToolSpec};
 use parking_lot::RwLock;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
+use tokio_stream::StreamExt;
 use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 use uuid::Uuid;
 
 use agent_factory::AutoAgentsExecutor;
 use registry::{AgentEntry, AgentRegistry};
 use runtime::{ToolResultMode, TurnExecutor};
+use sequence::EventSequencer;
 use sessions::SessionStore;
+use submissions::SubmissionQueue;
 use tool_context::ToolContextFactory;
 
 pub const DEFAULT_AGENT_ID: &str = "odyssey-orchestrator";
 pub const DEFAULT_LLM_ID: &str = "odyssey-default-llm";
 const RUN_STREAM_BUFFER: usize = 512;
+/// Seconds submission idempotency keys are remembered when not configured.
+const DEFAULT_IDEMPOTENCY_WINDOW_SECS: u64 = 600;
+/// How long aborted turns get to unwind during shutdown.
+const SHUTDOWN_ABORT_GRACE: Duration = Duration::from_secs(5);
 
 /// Result payload for a single run invocation.
 pub struct RunResult {
@@ -52,6 +116,16 @@ pub struct RunResult {
     pub session_id: SessionId,
     /// Assistant response content.
     pub response: String,
+    /// Response parsed and validated against the requested output schema, if any.
+    pub structured: Option<serde_json::Value>,
+}
+
+/// Result of re-running an edited turn with `Orchestrator::retry_turn`.
+pub struct RetriedTurn {
+    /// Result of the re-run turn.
+    pub result: RunResult,
+    /// Fork holding the transcript as it was before the retry.
+    pub fork_session_id: SessionId,
 }
 
 /// Streaming handle for a single run invocation.
@@ -72,6 +146,55 @@ impl RunStream {
             .await
             .map_err(|err| OdysseyCoreError::Executor(err.to_string()))?
     }
+
+    /// Write every event as one JSON line until the run completes, then return its result.
+    ///
+    /// Each line is a serialized `EventMsg`, flushed as it arrives, so the output can be
+    /// piped into tools such as `jq`. Events dropped because the writer fell behind are
+    /// logged and skipped.
+    pub async fn into_jsonl_writer<W>(self, mut writer: W) -> Result<RunResult, OdysseyCoreError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let Self {
+            session_id,
+            mut events,
+            mut handle,
+            ..
+        } = self;
+        let result = loop {
+            tokio::select! {
+                biased;
+                event = events.next() => match event {
+                    Some(Ok(event)) => write_jsonl_event(&mut writer, &event).await?,
+                    Some(Err(BroadcastStreamRecvError::Lagged(skipped))) => {
+                        warn!("jsonl writer lagged (session_id={session_id}, skipped={skipped})");
+                    }
+                    None => break (&mut handle).await,
+                },
+                result = &mut handle => break result,
+            }
+        };
+        // Events emitted right before the turn finished may still be buffered.
+        while let Some(Some(event)) = events.next().now_or_never() {
+            if let Ok(event) = event {
+                write_jsonl_event(&mut writer, &event).await?;
+            }
+        }
+        result.map_err(|err| OdysseyCoreError::Executor(err.to_string()))?
+    }
+}
+
+/// Serialize an event as a single JSON line and flush it.
+async fn write_jsonl_event<W>(writer: &mut W, event: &EventMsg) -> Result<(), OdysseyCoreError>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut line = serde_json::to_vec(event).map_err(std::io::Error::other)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    writer.flush().await?;
+    Ok(())
 }
 
 /// Control how the base system prompt is resolved for an agent.
@@ -127,8 +250,18 @@ pub struct Orchestrator {
     agent_registry: AgentRegistry,
     session_store: SessionStore,
     executor: Arc<TurnExecutor>,
+    submissions: SubmissionQueue,
     skill_store: Option<Arc<dyn SkillProvider>>,
+    artifact_store: Option<Arc<ArtifactStore>>,
+    sandbox_pool: Option<Arc<SandboxPool>>,
+    hooks: LifecycleHooks,
     event_sink: Option<Arc<dyn EventSink>>,
+    /// Numbers events per session on every emission path.
+    sequencer: EventSequencer,
+    /// Cache of GGUF models for local LLM providers.
+    model_manager: ModelManager,
+    /// Keeps other processes off this workspace while sessions persist.
+    _workspace_lock: Option<WorkspaceLock>,
 }
 
 impl Orchestrator {
@@ -149,15 +282,25 @@ impl Orchestrator {
         let skill_store: Option<Arc<dyn SkillProvider>> = if skill_store.is_some() {
             skill_store
         } else {
-            let cwd = std::env::current_dir().map_err(OdysseyCoreError::Io)?;
+            let cwd = std::env::current_dir()?;
             debug!("loading skills (cwd={})", cwd.display());
 
-            Some(Arc::new(
+            let store = if config.offline {
+                SkillStore::load_cached(&config.skills, &cwd)
+            } else {
                 SkillStore::load(&config.skills, &cwd)
-                    .map_err(|err| OdysseyCoreError::Parse(err.to_string()))?,
+            };
+            Some(Arc::new(
+                store.map_err(|err| OdysseyCoreError::Parse(err.to_string()))?,
             ))
         };
 
+        let workspace_lock = if config.sessions.enabled {
+            let cwd = std::env::current_dir()?;
+            Some(WorkspaceLock::acquire(&cwd, config.sessions.force_lock)?)
+        } else {
+            None
+        };
         let state_store = if config.sessions.enabled {
             match state_store {
                 Some(store) => Some(store),
@@ -166,28 +309,67 @@ impl Orchestrator {
         } else {
             None
         };
-        let event_sink = event_sink.clone();
+        let artifact_store = if config.artifacts.enabled {
+            Some(build_default_artifact_store(&config.artifacts)?)
+        } else {
+            None
+        };
+        let sequencer = EventSequencer::new(state_store.clone());
+        let event_sink = event_sink.map(|sink| sequencer.wrap(sink));
         let permission_engine = Arc::new(PermissionEngine::new(config.permissions.clone())?);
         permission_engine.set_event_sink(event_sink.clone());
+        if let Some(webhook) = &config.permissions.webhook {
+            install_approval_webhook(&permission_engine, webhook)?;
+        }
         let sandbox_provider = if sandbox_provider.is_none() && sandbox_required(&config) {
             Some(build_default_sandbox_provider(&config.sandbox)?)
         } else {
             sandbox_provider
         };
+        let sandbox_pool = sandbox_provider
+            .clone()
+            .filter(|_| config.sandbox.pool_idle_secs > 0)
+            .map(|provider| {
+                Arc::new(SandboxPool::new(
+                    provider,
+                    Duration::from_secs(config.sandbox.pool_idle_secs),
+                ))
+            });
+        if config.orchestrator.probe_environment
+            && let Ok(runtime) = tokio::runtime::Handle::try_current()
+        {
+            debug!("probing host environment in the background");
+            runtime.spawn(EnvironmentReport::probe(None));
+        }
         let config = Arc::new(config);
         let question_handler = Arc::new(RwLock::new(None));
         let agent_registry = AgentRegistry::new(DEFAULT_AGENT_ID.into());
         let session_store = SessionStore::new(state_store.clone());
+        let hooks = LifecycleHooks::default();
+        if config.guardrails.enabled {
+            info!(
+                "content guardrails enabled (rules={})",
+                config.guardrails.rules.len()
+            );
+            hooks.add(Arc::new(GuardrailHook::from_config(&config.guardrails)?));
+        }
+        permission_engine.register_tool_risks(tools.risks());
+        let tool_router = ToolRouter::new(tools);
+        debug!("tool registry wired (tools={})", tool_router.list().len());
         let tool_context_factory = ToolContextFactory::new(
             config.clone(),
             sandbox_provider.clone(),
+            sandbox_pool.clone(),
             permission_engine.clone(),
             question_handler.clone(),
             skill_store.clone(),
+            artifact_store
+                .clone()
+                .map(|store| store as Arc<dyn ArtifactProvider>),
             event_sink.clone(),
+            hooks.clone(),
+            tool_router.scheduler(),
         );
-        let tool_router = ToolRouter::new(tools);
-        debug!("tool registry wired (tools={})", tool_router.list().len());
 
         let executor = Arc::new(TurnExecutor::new(
             config.clone(),
@@ -195,9 +377,28 @@ impl Orchestrator {
             tool_context_factory.clone(),
             tool_router.clone(),
             event_sink.clone(),
+            sequencer.clone(),
+            hooks.clone(),
         ));
 
         let llm_registry = LLMRegistry::new("default_LLM".into());
+        let model_manager = ModelManager::new(resolve_default_root(
+            config.models.cache_path.as_ref(),
+            "models",
+        )?);
+        let submissions = SubmissionQueue::new(
+            executor.clone(),
+            agent_registry.clone(),
+            llm_registry.clone(),
+            session_store.clone(),
+            event_sink.clone(),
+            Duration::from_secs(
+                config
+                    .orchestrator
+                    .idempotency_window_secs
+                    .unwrap_or(DEFAULT_IDEMPOTENCY_WINDOW_SECS),
+            ),
+        );
 
         let orchestrator = Self {
             config,
@@ -207,9 +408,16 @@ impl Orchestrator {
             agent_registry,
             session_store,
             executor,
+            submissions,
             skill_store,
+            artifact_store,
+            sandbox_pool,
+            hooks,
             llm_registry,
             event_sink,
+            sequencer,
+            model_manager,
+            _workspace_lock: workspace_lock,
         };
 
         if orchestrator.config.sandbox.enabled && sandbox_provider.is_none() {
@@ -218,6 +426,9 @@ impl Orchestrator {
                 "sandbox enabled but no provider configured".to_string(),
             ));
         }
+        if orchestrator.config.offline {
+            orchestrator.set_offline(true);
+        }
 
         info!("orchestrator initialized");
         Ok(orchestrator)
@@ -228,6 +439,40 @@ impl Orchestrator {
         &self.config
     }
 
+    /// Force read-only operation regardless of config.
+    ///
+    /// Tools run in a read-only sandbox and writes, edits, and artifact publishing are denied.
+    /// Commands are denied when no sandbox provider is available to contain them.
+    pub fn set_read_only(&self, read_only: bool) {
+        self.permission_engine.set_read_only(read_only);
+    }
+
+    /// Return true when read-only mode is active.
+    pub fn is_read_only(&self) -> bool {
+        self.permission_engine.is_read_only()
+    }
+
+    /// Run without network access regardless of config.
+    ///
+    /// Network tools (`WebSearch`, `WebFetch`, `HttpRequest`, `Download`) are hidden from
+    /// agents, sandboxed commands run without network access, network commands and git pushes
+    /// are denied, session uploads and model downloads fail, and turns fail with
+    /// `OfflineProvider` unless their LLM was registered with `register_local_llm_provider`.
+    /// Without a sandbox that isolates the network, only the named network commands are
+    /// blocked; other programs can still reach the network. Remote skill sources are only
+    /// read from their cache when `offline` is set in the config the orchestrator is built with.
+    pub fn set_offline(&self, offline: bool) {
+        info!("offline mode (enabled={offline})");
+        self.permission_engine.set_offline(offline);
+        self.tool_router.set_offline(offline);
+        self.llm_registry.set_offline(offline);
+    }
+
+    /// Return true when offline mode is active.
+    pub fn is_offline(&self) -> bool {
+        self.permission_engine.is_offline()
+    }
+
     /// Set an approval handler to resolve permission requests.
     pub fn set_approval_handler(&self, handler: Arc<dyn ApprovalHandler>) {
         self.permission_engine.set_approval_handler(Some(handler));
@@ -238,6 +483,11 @@ impl Orchestrator {
         self.permission_engine.add_hook(hook);
     }
 
+    /// Add a lifecycle hook that runs around every turn and tool call.
+    pub fn add_lifecycle_hook(&self, hook: Arc<dyn LifecycleHook>) {
+        self.hooks.add(hook);
+    }
+
     /// Resolve a pending permission request by id.
     pub fn resolve_approval(
         &self,
@@ -288,6 +538,28 @@ impl Orchestrator {
         })
     }
 
+    /// System prompt an agent runs with, split into the sections it was built from.
+    ///
+    /// Falls back to the default agent when `agent_id` is `None`. Sections are
+    /// only reported when they still render to the registered prompt.
+    pub fn effective_prompt(
+        &self,
+        agent_id: Option<&str>,
+    ) -> Result<EffectivePrompt, OdysseyCoreError> {
+        let agent_id = self.agent_registry.resolve_agent_id(agent_id)?;
+        let entry = self.agent_registry.get_entry(&agent_id)?;
+        let sections = entry
+            .prompt_sections
+            .filter(|built| built.render() == entry.prompt)
+            .map(|built| built.sections().to_vec())
+            .unwrap_or_default();
+        Ok(EffectivePrompt {
+            agent_id,
+            sections,
+            text: entry.prompt,
+        })
+    }
+
     /// Register a question handler for interactive tool queries.
     pub fn set_question_handler(&self, handler: Arc<dyn QuestionHandler>) {
         *self.question_handler.write() = Some(handler);
@@ -302,10 +574,47 @@ impl Orchestrator {
         Ok(())
     }
 
+    /// Register an LLM that runs on this machine; it stays usable in offline mode.
+    pub fn register_local_llm_provider(&self, entry: LLMEntry) -> Result<(), OdysseyCoreError> {
+        info!("registering local LLM (llm_id={})", entry.id);
+        self.llm_registry.insert_local_entry(entry);
+        Ok(())
+    }
+
     pub fn register_agent<T>(&self, agent: AgentBuilder<T>) -> Result<(), OdysseyCoreError>
     where
         T: OdysseyAgentRuntime,
         String: From<<T as AgentExecutor>::Output>, //TODO: Instead of String directly Add AgentOutput for orchestrator agent
+    {
+        self.insert_agent(agent, None)
+    }
+
+    /// Register one of the built-in [`AGENT_TEMPLATES`](crate::AGENT_TEMPLATES) under its template name.
+    ///
+    /// The template agent shares the default agent's memory provider, so register
+    /// a regular agent first.
+    pub fn register_template_agent(&self, name: &str) -> Result<(), OdysseyCoreError> {
+        let template =
+            agent_template(name).ok_or_else(|| OdysseyCoreError::UnknownAgent(name.to_string()))?;
+        let default_id = self.agent_registry.default_agent_id();
+        let memory_provider = self.agent_registry.get_entry(&default_id)?.memory_provider;
+        let agent = AgentBuilder::new(
+            template.name.to_string(),
+            ReActAgent::new(OdysseyAgent::new(template.prompt.to_string(), Vec::new())),
+            memory_provider,
+        )
+        .with_tool_policy(template.tool_policy());
+        self.insert_agent(agent, Some(template.description.to_string()))
+    }
+
+    fn insert_agent<T>(
+        &self,
+        agent: AgentBuilder<T>,
+        description: Option<String>,
+    ) -> Result<(), OdysseyCoreError>
+    where
+        T: OdysseyAgentRuntime,
+        String: From<<T as AgentExecutor>::Output>,
     {
         let id = agent.id().to_string();
         self.ensure_non_default_agent_id(&id)?;
@@ -315,7 +624,10 @@ impl Orchestrator {
             )));
         }
         info!("registering agent (agent_id={})", id);
-        let entry = self.build_entry_from_agent(agent)?;
+        let mut entry = self.build_entry_from_agent(agent)?;
+        if description.is_some() {
+            entry.description = description;
+        }
         let set_default = self.agent_registry.list_agents().is_empty();
         self.permission_engine
             .register_agent_mode(id.clone(), entry.permission_mode);
@@ -344,10 +656,11 @@ impl Orchestrator {
         let prompt = agent.description().to_string();
         let tool_policy = agent.tool_policy();
         let memory_provider = agent.memory_provider();
+        let prompt_sections = agent.prompt_sections();
         let executor: Arc<dyn agent_factory::AgentExecutorRunner> =
             Arc::new(AutoAgentsExecutor::new(agent));
 
-        Ok(AgentEntry::new(
+        let mut entry = AgentEntry::new(
             id,
             description,
             prompt,
@@ -358,7 +671,9 @@ impl Orchestrator {
             None,
             memory_provider,
             executor,
-        ))
+        );
+        entry.prompt_sections = prompt_sections;
+        Ok(entry)
     }
 
     /// Override the default agent id used for new sessions.
@@ -384,11 +699,84 @@ impl Orchestrator {
         self.llm_registry.list_llm_ids()
     }
 
+    /// Cache of GGUF models for local LLM providers.
+    pub fn model_manager(&self) -> &ModelManager {
+        &self.model_manager
+    }
+
+    /// List GGUF models in the local model cache.
+    pub fn list_cached_models(&self) -> Result<Vec<CachedModel>, OdysseyCoreError> {
+        self.model_manager.list()
+    }
+
+    /// Download a GGUF model into the cache, reporting progress to `on_progress`.
+    ///
+    /// Fails in offline mode and when the file does not match its expected SHA-256.
+    pub async fn download_model(
+        &self,
+        request: &ModelDownloadRequest,
+        on_progress: impl FnMut(ModelDownloadProgress) + Send,
+    ) -> Result<CachedModel, OdysseyCoreError> {
+        if self.is_offline() {
+            return Err(OdysseyCoreError::ModelManager(format!(
+                "cannot download {}/{} in offline mode",
+                request.repo_id, request.filename
+            )));
+        }
+        self.model_manager.download(request, on_progress).await
+    }
+
+    /// Re-hash a cached model and check it against `expected` or its recorded SHA-256.
+    pub async fn verify_model(
+        &self,
+        repo_id: &str,
+        filename: &str,
+        expected: Option<&str>,
+    ) -> Result<CachedModel, OdysseyCoreError> {
+        self.model_manager.verify(repo_id, filename, expected).await
+    }
+
+    /// Delete a cached model; returns false when it was not cached.
+    pub fn delete_model(&self, repo_id: &str, filename: &str) -> Result<bool, OdysseyCoreError> {
+        self.model_manager.delete(repo_id, filename)
+    }
+
     /// List registered tool names.
     pub fn list_tools(&self) -> Vec<String> {
         self.tool_router.list()
     }
 
+    /// Specs of the tools an agent may call, or the default agent's when unset.
+    pub fn tool_specs(&self, agent_id: Option<&str>) -> Result<Vec<ToolSpec>, OdysseyCoreError> {
+        let agent_id = self.agent_registry.resolve_agent_id(agent_id)?;
+        let entry = self.agent_registry.get_entry(&agent_id)?;
+        Ok(self.tool_router.specs_for_agent(&entry.tool_policy))
+    }
+
+    /// Call one of the session agent's tools directly, without an LLM turn.
+    ///
+    /// Permission rules, approvals, tool hooks, and the sandbox apply as they
+    /// would for the agent. The call waits for any running turn in the session,
+    /// and its result is not added to the transcript. Unknown tools and tool
+    /// failures are returned as [`OdysseyCoreError::Tool`].
+    pub async fn call_tool(
+        &self,
+        session_id: SessionId,
+        tool_name: &str,
+        args: serde_json::Value,
+    ) -> Result<serde_json::Value, OdysseyCoreError> {
+        let _permit = self.executor.acquire_turn(session_id).await?;
+        let session = self.resume_session(session_id)?;
+        let entry = self.agent_registry.get_entry(&session.agent_id)?;
+        info!(
+            "calling tool directly (session_id={}, tool_name={})",
+            session_id, tool_name
+        );
+        self.executor
+            .call_tool(session_id, &session.agent_id, &entry, tool_name, args)
+            .await
+    }
+
     /// Return summaries of loaded skills.
     pub fn list_skill_summaries(&self) -> Vec<SkillSummary> {
         self.skill_store
@@ -397,11 +785,122 @@ impl Orchestrator {
             .unwrap_or_default()
     }
 
+    /// Return the prompt presets configured under `prompts`, sorted by name.
+    pub fn list_prompt_presets(&self) -> Vec<PromptPreset> {
+        presets_from_config(&self.config.prompts)
+    }
+
+    /// Expand a prompt preset with the text typed after its command.
+    pub fn expand_prompt_preset(
+        &self,
+        name: &str,
+        input: &str,
+    ) -> Result<String, OdysseyCoreError> {
+        let config = self
+            .config
+            .prompts
+            .get(name)
+            .ok_or_else(|| OdysseyCoreError::UnknownPromptPreset(name.to_string()))?;
+        PromptPreset::from_config(name, config).expand(input)
+    }
+
+    /// Look up catalog capabilities for a provider/model pair.
+    pub fn model_info(&self, provider: &str, name: &str) -> Option<ModelCatalogEntry> {
+        self.executor.model_catalog().get(provider, name).cloned()
+    }
+
+    /// Publish an artifact to the shared store, replacing any artifact with the same name.
+    pub fn publish_artifact(
+        &self,
+        request: ArtifactPublish,
+    ) -> Result<ArtifactSummary, OdysseyCoreError> {
+        self.require_artifact_store()?.publish(request)
+    }
+
+    /// List artifacts in the shared store.
+    pub fn list_artifacts(&self) -> Result<Vec<ArtifactSummary>, OdysseyCoreError> {
+        self.require_artifact_store()?.list()
+    }
+
+    /// Fetch an artifact by name or `artifact://` reference.
+    pub fn get_artifact(&self, name: &str) -> Result<(ArtifactSummary, Vec<u8>), OdysseyCoreError> {
+        self.require_artifact_store()?.get(name)
+    }
+
+    fn require_artifact_store(&self) -> Result<&ArtifactStore, OdysseyCoreError> {
+        self.artifact_store
+            .as_deref()
+            .ok_or_else(|| OdysseyCoreError::Artifact("artifacts are not enabled".to_string()))
+    }
+
     /// Create a new session for the specified agent (or default).
     pub fn create_session(&self, agent_id: Option<String>) -> Result<SessionId, OdysseyCoreError> {
         let agent_id = self.agent_registry.resolve_agent_id(agent_id.as_deref())?;
         info!("creating session (agent_id={})", agent_id);
-        self.session_store.create_session(agent_id)
+        self.session_store.create_session(agent_id, None)
+    }
+
+    /// Create a new session owned by `user_id` for the specified agent (or default).
+    ///
+    /// Turns in the session are attributed to the owner unless a submission names
+    /// another user, and [`Orchestrator::list_sessions_for_user`] scopes listings to it.
+    pub fn create_session_for_user(
+        &self,
+        agent_id: Option<String>,
+        user_id: impl Into<String>,
+    ) -> Result<SessionId, OdysseyCoreError> {
+        let agent_id = self.agent_registry.resolve_agent_id(agent_id.as_deref())?;
+        let user_id = user_id.into();
+        info!("creating session (agent_id={agent_id}, user_id={user_id})");
+        self.session_store.create_session(agent_id, Some(user_id))
+    }
+
+    /// Import a transcript from another agent CLI into a new session for the default agent.
+    ///
+    /// Messages are appended to the session transcript, and user and assistant text is
+    /// also stored in the agent's memory so later turns can recall the conversation.
+    pub async fn import_session(
+        &self,
+        path: impl AsRef<Path>,
+        format: SessionImportFormat,
+    ) -> Result<SessionId, OdysseyCoreError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        let messages = parse_transcript(&text, format)?;
+        let agent_id = self.agent_registry.resolve_agent_id(None)?;
+        let entry = self.agent_registry.get_entry(&agent_id)?;
+        let session_id = self.create_session(Some(agent_id.clone()))?;
+        for message in &messages {
+            self.session_store.append_message(session_id, message)?;
+            if message.role == Role::System {
+                continue;
+            }
+            entry
+                .memory_provider
+                .store(MemoryRecord {
+                    id: Uuid::new_v4(),
+                    session_id,
+                    role: message.role.as_str().to_string(),
+                    content: message.content.clone(),
+                    metadata: serde_json::json!({
+                        "agent_id": agent_id,
+                        "kind": "message",
+                        "message_type": "text",
+                        "imported": true,
+                    }),
+                    created_at: message.created_at,
+                })
+                .await
+                .map_err(|err| OdysseyCoreError::Memory(err.to_string()))?;
+        }
+        info!(
+            "imported session (session_id={}, format={:?}, path={}, messages={})",
+            session_id,
+            format,
+            path.display(),
+            messages.len()
+        );
+        Ok(session_id)
     }
 
     /// Resume a session and return its state.
@@ -409,17 +908,358 @@ impl Orchestrator {
         self.session_store.resume_session(session_id)
     }
 
+    /// Load up to `limit` messages of a session ending before message index `cursor`,
+    /// or the newest messages when `cursor` is `None`.
+    ///
+    /// Unlike [`Orchestrator::resume_session`], this does not load the whole
+    /// transcript of a persisted session into memory.
+    pub fn get_session_page(
+        &self,
+        session_id: SessionId,
+        cursor: Option<usize>,
+        limit: usize,
+    ) -> Result<SessionPage, OdysseyCoreError> {
+        self.session_store.session_page(session_id, cursor, limit)
+    }
+
+    /// Copy a session into a new session for the same agent and return its id.
+    ///
+    /// The transcript and the agent's memory records for the session are both
+    /// copied, so the fork can continue the conversation independently.
+    pub async fn fork_session(&self, session_id: SessionId) -> Result<SessionId, OdysseyCoreError> {
+        let fork_id = self.session_store.fork_session(session_id)?;
+        let agent_id = self.session_store.resume_session(fork_id)?.agent_id;
+        let entry = self.agent_registry.get_entry(&agent_id)?;
+        let records = entry
+            .memory_provider
+            .recall(session_id, None, usize::MAX)
+            .await
+            .map_err(|err| OdysseyCoreError::Memory(err.to_string()))?;
+        for record in records {
+            entry
+                .memory_provider
+                .store(MemoryRecord {
+                    id: Uuid::new_v4(),
+                    session_id: fork_id,
+                    ..record
+                })
+                .await
+                .map_err(|err| OdysseyCoreError::Memory(err.to_string()))?;
+        }
+        Ok(fork_id)
+    }
+
+    /// Compact a session's memory now, summarizing old records with an LLM.
+    ///
+    /// Runs even when `memory.compaction.enabled` is off. Past the configured limits,
+    /// records are compacted until the limits hold; otherwise the session is compacted
+    /// down to half of `memory.compaction.max_messages`. Returns the audit entry, or
+    /// `None` when there was nothing to compact or the provider does not support it.
+    pub async fn compact_memory(
+        &self,
+        session_id: SessionId,
+        llm_id: Option<&str>,
+    ) -> Result<Option<MemoryCompaction>, OdysseyCoreError> {
+        let llm_id = self.llm_registry.resolve_llm_id(llm_id)?;
+        let llm = self.resovle_llm(&llm_id)?;
+        let _permit = self.executor.acquire_turn(session_id).await?;
+        let agent_id = self.session_store.resume_session(session_id)?.agent_id;
+        let entry = self.agent_registry.get_entry(&agent_id)?;
+        let memory_config = self.executor.resolve_memory_config(&entry);
+        let policy = MemoryCompactionPolicy {
+            enabled: true,
+            ..memory::compaction_policy_from_config(&memory_config.compaction)
+        };
+        entry
+            .memory_provider
+            .compact_with_summarizer(
+                session_id,
+                &policy,
+                &memory_compaction::LlmSummarizer::new(llm),
+                true,
+            )
+            .await
+            .map_err(|err| OdysseyCoreError::Memory(err.to_string()))
+    }
+
+    /// Score the memory records recalled for a session's next turn against `query`.
+    ///
+    /// Returns the same records the agent's context gets, each with its score,
+    /// matched query terms, vector similarity, and recency boost.
+    pub async fn explain_memory_recall(
+        &self,
+        session_id: SessionId,
+        query: Option<&str>,
+    ) -> Result<Vec<ScoredMemoryRecord>, OdysseyCoreError> {
+        let agent_id = self.session_store.resume_session(session_id)?.agent_id;
+        let entry = self.agent_registry.get_entry(&agent_id)?;
+        let memory_config = self.executor.resolve_memory_config(&entry);
+        entry
+            .memory_provider
+            .recall_explained(
+                session_id,
+                query,
+                memory_config.recall_k,
+                memory::recall_options_from_config(&memory_config.recall),
+            )
+            .await
+            .map_err(|err| OdysseyCoreError::Memory(err.to_string()))
+    }
+
+    /// Render a session as a self-contained HTML bundle with secrets redacted.
+    ///
+    /// With `options.upload` set, the bundle is POSTed to `sharing.upload` and the
+    /// returned URL is included; this requires the `share-upload` feature.
+    pub async fn share_session(
+        &self,
+        session_id: SessionId,
+        options: ShareOptions,
+    ) -> Result<SharedSession, OdysseyCoreError> {
+        let session = self.resume_session(session_id)?;
+        let mut redactor = Redactor::from_config(&self.config, &options.redact_patterns)?;
+        let html = render_session_html(&session, &options, &mut redactor);
+        if options.upload && self.is_offline() {
+            return Err(OdysseyCoreError::Share(
+                "cannot upload shared sessions in offline mode".to_string(),
+            ));
+        }
+        let url = if options.upload {
+            let upload = self.config.sharing.upload.as_ref().ok_or_else(|| {
+                OdysseyCoreError::Share("no sharing.upload endpoint configured".to_string())
+            })?;
+            Some(crate::share::upload(upload, &html).await?)
+        } else {
+            None
+        };
+        info!(
+            "shared session (session_id={}, bytes={}, redactions={}, uploaded={})",
+            session_id,
+            html.len(),
+            redactor.redactions(),
+            url.is_some()
+        );
+        Ok(SharedSession {
+            html,
+            url,
+            redactions: redactor.redactions(),
+        })
+    }
+
     /// List all persisted sessions.
     pub fn list_sessions(&self) -> Result<Vec<SessionSummary>, OdysseyCoreError> {
         self.session_store.list_sessions()
     }
 
+    /// List the sessions `user_id` created, plus sessions not attributed to any user.
+    pub fn list_sessions_for_user(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<SessionSummary>, OdysseyCoreError> {
+        let mut sessions = self.session_store.list_sessions()?;
+        sessions.retain(|session| {
+            session
+                .user_id
+                .as_deref()
+                .is_none_or(|owner| owner == user_id)
+        });
+        Ok(sessions)
+    }
+
     /// Delete a session and any associated overrides.
     pub fn delete_session(&self, session_id: SessionId) -> Result<bool, OdysseyCoreError> {
         info!("deleting session (session_id={})", session_id);
+        self.executor.replay_sessions().remove(session_id);
+        self.submissions.remove_session(session_id);
+        self.sequencer.set_actor(session_id, None);
+        if let Some(pool) = self.sandbox_pool.clone()
+            && let Ok(runtime) = tokio::runtime::Handle::try_current()
+        {
+            runtime.spawn(async move {
+                pool.reset_session(session_id).await;
+            });
+        }
         self.session_store.delete_session(session_id)
     }
 
+    /// Shut down pooled sandboxes for a session so the next turn prepares a fresh one.
+    pub async fn reset_sandboxes(&self, session_id: SessionId) -> usize {
+        match &self.sandbox_pool {
+            Some(pool) => pool.reset_session(session_id).await,
+            None => 0,
+        }
+    }
+
+    /// Reuse counters for pooled sandboxes, if pooling is enabled.
+    pub fn sandbox_pool_stats(&self) -> Option<SandboxPoolStats> {
+        self.sandbox_pool.as_ref().map(|pool| pool.stats())
+    }
+
+    /// Override turn context defaults (e.g. simulation) for subsequent turns in a session.
+    pub fn override_turn_context(
+        &self,
+        session_id: SessionId,
+        context: TurnContextOverride,
+    ) -> Result<(), OdysseyCoreError> {
+        info!("overriding turn context (session_id={})", session_id);
+        self.session_store.set_context_override(session_id, context)
+    }
+
+    /// Override the turn context for the next turn in a session only.
+    ///
+    /// Applied on top of any session-wide override, then discarded, so a single
+    /// message can run with, e.g., a different temperature.
+    pub fn override_next_turn_context(
+        &self,
+        session_id: SessionId,
+        context: TurnContextOverride,
+    ) -> Result<(), OdysseyCoreError> {
+        info!("overriding next turn context (session_id={})", session_id);
+        self.session_store
+            .set_next_turn_override(session_id, context)
+    }
+
+    /// Clear any turn context override for a session.
+    pub fn clear_turn_context_override(&self, session_id: SessionId) -> bool {
+        self.session_store.clear_context_override(session_id)
+    }
+
+    /// Effective working directory of a session, once it has run a turn.
+    ///
+    /// Starts at the workspace root and follows `cd`/`SetCwd` tool calls.
+    pub fn session_cwd(&self, session_id: SessionId) -> Option<PathBuf> {
+        self.session_store.current_cwd(session_id)
+    }
+
+    /// Record or replay LLM responses and tool results for subsequent turns in a session.
+    pub fn set_replay_mode(
+        &self,
+        session_id: SessionId,
+        mode: ReplayMode,
+    ) -> Result<(), OdysseyCoreError> {
+        self.session_store.resume_session(session_id)?;
+        info!("setting replay mode (session_id={})", session_id);
+        self.executor.replay_sessions().set(session_id, mode);
+        Ok(())
+    }
+
+    /// Stop recording or replaying a session.
+    pub fn clear_replay_mode(&self, session_id: SessionId) -> bool {
+        self.executor.replay_sessions().remove(session_id)
+    }
+
+    /// Queue a protocol submission for processing.
+    ///
+    /// Submissions for the same session are processed in order by a background
+    /// worker; user messages run on the session's agent and the default LLM, using
+    /// the submission id as the turn id. Results are reported through the event
+    /// sink. `CancelTurn` takes effect immediately for running or queued turns.
+    /// A repeated `idempotency_key` within `orchestrator.idempotency_window_secs`
+    /// is not run again; the receipt points at the original submission instead.
+    /// Must be called from within a Tokio runtime.
+    pub fn submit(
+        &self,
+        envelope: SubmissionEnvelope,
+    ) -> Result<SubmissionReceipt, OdysseyCoreError> {
+        self.executor.ensure_accepting()?;
+        self.submissions.submit(envelope)
+    }
+
+    /// Number of running and queued turns across all sessions.
+    pub fn run_metrics(&self) -> RunMetrics {
+        self.executor.turns().metrics()
+    }
+
+    /// Snapshot of active sessions, stalled turns, queue depths, and the last turn error.
+    ///
+    /// A turn is stalled once its session has emitted no events for
+    /// `orchestrator.stall_timeout_secs`; the watchdog also reports it with a
+    /// `TurnStalled` event.
+    pub fn health(&self) -> OrchestratorHealth {
+        health::collect_health(
+            self.executor.turns(),
+            &self.sequencer,
+            self.submissions.queue_depths(),
+            self.executor.stall_timeout(),
+        )
+    }
+
+    /// USD spent by `session_id` and by all sessions today, priced with `budget.models`.
+    pub fn budget_usage(&self, session_id: SessionId) -> BudgetUsage {
+        self.executor.budget().usage(session_id)
+    }
+
+    /// Let the next turn of `session_id` run even though a `budget` limit is reached.
+    ///
+    /// Turns refused with `BudgetExceeded` need this explicit continuation; the
+    /// grant covers one turn and is kept until a turn needs it.
+    pub fn allow_budget_overrun(&self, session_id: SessionId) {
+        info!("budget overrun allowed for next turn (session_id={session_id})");
+        self.executor.budget().allow_overrun(session_id);
+    }
+
+    /// Stop accepting turns and wind down the orchestrator.
+    ///
+    /// In-flight turns get `timeout` to finish; any still running afterwards are
+    /// aborted with a `TurnCancelled` event. Pooled sandbox handles are then shut
+    /// down and state and memory stores flushed. New turns fail with
+    /// `OdysseyCoreError::ShuttingDown` once this is called; calling it again
+    /// returns an empty report.
+    pub async fn shutdown(&self, timeout: Duration) -> ShutdownReport {
+        let turns = self.executor.turns();
+        if !turns.stop_accepting() {
+            debug!("orchestrator shutdown already started");
+            return ShutdownReport::default();
+        }
+        let in_flight = turns.running().len();
+        info!("shutting down orchestrator (in_flight_turns={in_flight})");
+
+        let mut report = ShutdownReport::default();
+        if tokio::time::timeout(timeout, turns.wait_idle())
+            .await
+            .is_err()
+        {
+            report.aborted_turns = turns.running();
+            warn!(
+                "aborting turns after shutdown timeout (aborted={})",
+                report.aborted_turns.len()
+            );
+            turns.abort_all();
+            if tokio::time::timeout(SHUTDOWN_ABORT_GRACE, turns.wait_idle())
+                .await
+                .is_err()
+            {
+                warn!("aborted turns did not unwind within the grace period");
+            }
+        }
+        report.completed_turns = in_flight.saturating_sub(report.aborted_turns.len());
+
+        if let Some(pool) = &self.sandbox_pool {
+            report.sandboxes_shut_down = pool.reset_all().await;
+        }
+        if let Some(store) = self.session_store.state_store()
+            && let Err(err) = store.flush()
+        {
+            report.flush_errors.push(format!("state store: {err}"));
+        }
+        for (agent_id, provider) in self.agent_registry.memory_providers() {
+            if let Err(err) = provider.flush().await {
+                report
+                    .flush_errors
+                    .push(format!("memory ({agent_id}): {err}"));
+            }
+        }
+        for error in &report.flush_errors {
+            warn!("shutdown flush failed: {error}");
+        }
+        info!(
+            "orchestrator shut down (completed_turns={}, aborted_turns={}, sandboxes_shut_down={})",
+            report.completed_turns,
+            report.aborted_turns.len(),
+            report.sandboxes_shut_down
+        );
+        report
+    }
+
     /// Run a single turn, creating a fresh session.
     pub async fn run(
         &self,
@@ -457,6 +1297,7 @@ impl Orchestrator {
         );
         let entry = self.agent_registry.get_entry(agent_id)?;
         let llm = self.resovle_llm(llm_id)?;
+        let _permit = self.executor.acquire_turn(session_id).await?;
         self.executor
             .run_turn(runtime::TurnParams {
                 session_id,
@@ -470,10 +1311,189 @@ impl Orchestrator {
                 turn_id: None,
                 event_sink: None,
                 stream: false,
+                record_input: true,
+                user_id: None,
+            })
+            .await
+    }
+
+    /// Re-run a turn with edited input, discarding it and every later message.
+    ///
+    /// The session is first copied with `fork_session`, so the discarded branch
+    /// stays available under `RetriedTurn::fork_session_id`. The transcript is then
+    /// truncated before the turn's user message, the agent's memory for the session
+    /// forgets records from that turn onward, and `input` runs as a new turn on the
+    /// session's agent. Only turns recorded with a turn id can be retried.
+    pub async fn retry_turn(
+        &self,
+        session_id: SessionId,
+        turn_id: TurnId,
+        llm_id: Option<&str>,
+        input: impl Into<String>,
+    ) -> Result<RetriedTurn, OdysseyCoreError> {
+        let input = input.into();
+        let llm_id = self.llm_registry.resolve_llm_id(llm_id)?;
+        let llm = self.resovle_llm(&llm_id)?;
+        let _permit = self.executor.acquire_turn(session_id).await?;
+        let session = self.resume_session(session_id)?;
+        let entry = self.agent_registry.get_entry(&session.agent_id)?;
+        let (index, message) = session
+            .messages
+            .iter()
+            .enumerate()
+            .find(|(_, message)| message.role == Role::User && message.turn_id == Some(turn_id))
+            .ok_or(OdysseyCoreError::UnknownTurn(turn_id))?;
+
+        let fork_session_id = self.fork_session(session_id).await?;
+        self.session_store.truncate_messages(session_id, index)?;
+        let forgotten = entry
+            .memory_provider
+            .forget_since(session_id, message.created_at)
+            .await
+            .map_err(|err| OdysseyCoreError::Memory(err.to_string()))?;
+        info!(
+            "retrying turn (session_id={}, turn_id={}, fork_session_id={}, discarded_messages={}, forgotten_records={})",
+            session_id,
+            turn_id,
+            fork_session_id,
+            session.messages.len() - index,
+            forgotten
+        );
+        let result = self
+            .executor
+            .run_turn(runtime::TurnParams {
+                session_id,
+                agent_id: session.agent_id.clone(),
+                llm,
+                input,
+                entry,
+                include_subagent_spawner: true,
+                tool_result_mode: ToolResultMode::SessionAndMemory,
+                memory_mode: runtime::MemoryMode::AgentProvider,
+                turn_id: None,
+                event_sink: None,
+                stream: false,
+                record_input: true,
+                user_id: None,
+            })
+            .await?;
+        Ok(RetriedTurn {
+            result,
+            fork_session_id,
+        })
+    }
+
+    /// Re-run the last user turn of a session, typically on a different model.
+    ///
+    /// The user message is kept as is. The turn's assistant and tool messages are
+    /// marked `superseded` rather than removed, and the agent's memory forgets them,
+    /// so the model sees the history up to the user message. The new response is
+    /// appended under the same turn id.
+    pub async fn regenerate_turn(
+        &self,
+        session_id: SessionId,
+        llm_id: Option<&str>,
+    ) -> Result<RunResult, OdysseyCoreError> {
+        let llm_id = self.llm_registry.resolve_llm_id(llm_id)?;
+        let llm = self.resovle_llm(&llm_id)?;
+        let _permit = self.executor.acquire_turn(session_id).await?;
+        let session = self.resume_session(session_id)?;
+        let entry = self.agent_registry.get_entry(&session.agent_id)?;
+        let (turn_id, message) = session
+            .messages
+            .iter()
+            .rev()
+            .find(|message| message.role == Role::User)
+            .and_then(|message| message.turn_id.map(|turn_id| (turn_id, message)))
+            .ok_or(OdysseyCoreError::NothingToRegenerate(session_id))?;
+
+        let superseded = self.session_store.mark_superseded(session_id, turn_id)?;
+        let forgotten = entry
+            .memory_provider
+            .forget_since(session_id, message.created_at)
+            .await
+            .map_err(|err| OdysseyCoreError::Memory(err.to_string()))?;
+        info!(
+            "regenerating turn (session_id={}, turn_id={}, llm_id={}, superseded_messages={}, forgotten_records={})",
+            session_id, turn_id, llm_id, superseded, forgotten
+        );
+        self.executor
+            .run_turn(runtime::TurnParams {
+                session_id,
+                agent_id: session.agent_id.clone(),
+                llm,
+                input: message.content.clone(),
+                entry,
+                include_subagent_spawner: true,
+                tool_result_mode: ToolResultMode::SessionAndMemory,
+                memory_mode: runtime::MemoryMode::AgentProvider,
+                turn_id: Some(turn_id),
+                event_sink: None,
+                stream: false,
+                record_input: false,
+                user_id: None,
             })
             .await
     }
 
+    /// Run a turn whose final answer must match a JSON Schema, creating a fresh session.
+    pub async fn run_with_schema(
+        &self,
+        agent_id: Option<&str>,
+        llm_id: Option<&str>,
+        input: impl Into<String>,
+        schema: OutputSchema,
+    ) -> Result<RunResult, OdysseyCoreError> {
+        let agent_id = self.agent_registry.resolve_agent_id(agent_id)?;
+        let llm_id = self.llm_registry.resolve_llm_id(llm_id)?;
+        let session_id = self.create_session(Some(agent_id.clone()))?;
+        self.run_in_session_with_schema(session_id, &agent_id, &llm_id, input.into(), schema)
+            .await
+    }
+
+    /// Run a turn in an existing session whose final answer must match a JSON Schema.
+    ///
+    /// Format instructions are appended to the input. When the answer does not
+    /// validate, follow-up turns in the same session describe the errors, up to
+    /// `schema.max_retries` times. The parsed value is returned in
+    /// `RunResult::structured`.
+    pub async fn run_in_session_with_schema(
+        &self,
+        session_id: SessionId,
+        agent_id: &str,
+        llm_id: &str,
+        input: String,
+        schema: OutputSchema,
+    ) -> Result<RunResult, OdysseyCoreError> {
+        let output = structured::StructuredOutput::compile(&schema)?;
+        let mut prompt = output.instruct(&input);
+        let attempts = schema.max_retries + 1;
+        let mut attempt = 1;
+        loop {
+            let mut result = self
+                .run_in_session(session_id, agent_id, llm_id, prompt)
+                .await?;
+            match output.parse(&result.response) {
+                Ok(value) => {
+                    result.structured = Some(value);
+                    return Ok(result);
+                }
+                Err(errors) if attempt < attempts => {
+                    warn!(
+                        "structured output rejected (session_id={session_id}, attempt={attempt}): {errors}"
+                    );
+                    prompt = structured::StructuredOutput::retry_prompt(&errors);
+                    attempt += 1;
+                }
+                Err(errors) => {
+                    return Err(OdysseyCoreError::OutputSchema(format!(
+                        "response did not match after {attempts} attempts: {errors}"
+                    )));
+                }
+            }
+        }
+    }
+
     /// Run a single turn and stream events, creating a fresh session.
     pub async fn run_stream(
         &self,
@@ -514,13 +1534,15 @@ impl Orchestrator {
         let turn_id = Uuid::new_v4();
         let (run_bus, receiver) = RunEventBus::new(RUN_STREAM_BUFFER);
         let run_bus = Arc::new(run_bus);
-        let fanout: Arc<dyn EventSink> = Arc::new(FanoutEventSink {
+        let fanout = self.sequencer.wrap(Arc::new(FanoutEventSink {
             primary: self.event_sink.clone(),
             secondary: run_bus,
-        });
+        }));
+        let permit = self.executor.acquire_turn(session_id).await?;
         let executor = self.executor.clone();
         let agent_id = agent_id.to_string();
         let handle = tokio::spawn(async move {
+            let _permit = permit;
             executor
                 .run_turn(runtime::TurnParams {
                     session_id,
@@ -534,6 +1556,8 @@ impl Orchestrator {
                     turn_id: Some(turn_id),
                     event_sink: Some(fanout),
                     stream: true,
+                    record_input: true,
+                    user_id: None,
                 })
                 .await
         });
@@ -546,6 +1570,95 @@ impl Orchestrator {
         })
     }
 
+    /// Like [`Orchestrator::run_stream_in_session`], with files attached to the input.
+    ///
+    /// Attachments are appended to the prompt as tagged blocks, see
+    /// [`render_with_attachments`].
+    pub async fn run_stream_with_attachments(
+        &self,
+        session_id: SessionId,
+        agent_id: &str,
+        llm_id: &str,
+        input: String,
+        attachments: &[Attachment],
+    ) -> Result<RunStream, OdysseyCoreError> {
+        debug!(
+            "attaching files to turn (session_id={}, attachments={})",
+            session_id,
+            attachments.len()
+        );
+        let input = render_with_attachments(&input, attachments);
+        self.run_stream_in_session(session_id, agent_id, llm_id, input)
+            .await
+    }
+
+    /// Run registered agents in alternating turns on one task and stream their events.
+    ///
+    /// Each agent (and the judge, if any) gets a fresh session. Every round gives each
+    /// agent one turn with the contributions it has not seen yet; the debate stops after
+    /// `max_rounds` or earlier when the termination condition is met.
+    pub async fn run_debate(
+        &self,
+        request: DebateRequest,
+    ) -> Result<DebateStream, OdysseyCoreError> {
+        let mut distinct = request.agents.clone();
+        distinct.sort();
+        distinct.dedup();
+        if request.agents.len() < 2 || distinct.len() != request.agents.len() {
+            return Err(OdysseyCoreError::Executor(
+                "debate needs at least two distinct agents".to_string(),
+            ));
+        }
+        if request.max_rounds == 0 {
+            return Err(OdysseyCoreError::Executor(
+                "debate max_rounds must be at least 1".to_string(),
+            ));
+        }
+        let llm_id = self
+            .llm_registry
+            .resolve_llm_id(request.llm_id.as_deref())?;
+        let llm = self.resovle_llm(&llm_id)?;
+        let mut agents = Vec::with_capacity(request.agents.len());
+        for agent_id in &request.agents {
+            agents.push(self.debate_agent(agent_id)?);
+        }
+        let judge = match &request.termination {
+            DebateTermination::Judge { agent_id } if request.agents.contains(agent_id) => {
+                return Err(OdysseyCoreError::Executor(
+                    "debate judge must not also be a participant".to_string(),
+                ));
+            }
+            DebateTermination::Judge { agent_id } => Some(self.debate_agent(agent_id)?),
+            DebateTermination::MaxRounds | DebateTermination::Convergence => None,
+        };
+        info!(
+            "starting debate (agents={}, max_rounds={}, termination={:?})",
+            request.agents.join(","),
+            request.max_rounds,
+            request.termination
+        );
+        Ok(debate::DebateRunner {
+            executor: self.executor.clone(),
+            llm,
+            task: request.task,
+            max_rounds: request.max_rounds,
+            agents,
+            judge,
+            convergence: request.termination == DebateTermination::Convergence,
+            event_sink: self.event_sink.clone(),
+            sequencer: self.sequencer.clone(),
+        }
+        .spawn())
+    }
+
+    fn debate_agent(&self, agent_id: &str) -> Result<debate::DebateAgent, OdysseyCoreError> {
+        let entry = self.agent_registry.get_entry(agent_id)?;
+        let session_id = self
+            .session_store
+            .create_session(agent_id.to_string(), None)?;
+        Ok(debate::DebateAgent { entry, session_id })
+    }
+
     fn resovle_llm(&self, llm_id: &str) -> Result<Arc<dyn LLMProvider>, OdysseyCoreError> {
         Ok(self.llm_registry.get_entry(llm_id)?.provider)
     }
@@ -573,6 +1686,43 @@ fn build_default_state_store(
     Ok(Arc::new(store))
 }
 
+/// Route approvals for `ask` decisions to the configured HTTP webhook.
+#[cfg(feature = "webhook-approvals")]
+fn install_approval_webhook(
+    permission_engine: &PermissionEngine,
+    webhook: &ApprovalWebhookConfig,
+) -> Result<(), OdysseyCoreError> {
+    let handler = crate::approval_webhook::WebhookApprovalHandler::from_config(webhook)?;
+    info!("approval webhook enabled (url={})", webhook.url);
+    permission_engine.set_approval_handler(Some(Arc::new(handler)));
+    Ok(())
+}
+
+/// Warn that a webhook is configured but this build cannot call it.
+#[cfg(not(feature = "webhook-approvals"))]
+fn install_approval_webhook(
+    _permission_engine: &PermissionEngine,
+    webhook: &ApprovalWebhookConfig,
+) -> Result<(), OdysseyCoreError> {
+    warn!(
+        "approval webhook configured but the webhook-approvals feature is disabled (url={})",
+        webhook.url
+    );
+    Ok(())
+}
+
+/// Build the workspace-scoped artifact store from config.
+fn build_default_artifact_store(
+    config: &ArtifactsConfig,
+) -> Result<Arc<ArtifactStore>, OdysseyCoreError> {
+    let cwd = std::env::current_dir()?;
+    let root = match config.path.as_ref() {
+        Some(path) => cwd.join(path),
+        None => cwd.join(".odyssey").join("artifacts"),
+    };
+    Ok(Arc::new(ArtifactStore::new(root)?))
+}
+
 /// Build the default sandbox provider from config and platform defaults.
 fn build_default_sandbox_provider(
     config: &odyssey_rs_config::SandboxConfig,
@@ -612,7 +1762,7 @@ fn resolve_default_root(
     path: Option<&String>,
     fallback_dir: &str,
 ) -> Result<PathBuf, OdysseyCoreError> {
-    let cwd = std::env::current_dir().map_err(OdysseyCoreError::Io)?;
+    let cwd = std::env::current_dir()?;
     if let Some(path) = path {
         let path = PathBuf::from(path);
         if path.is_absolute() {
@@ -0,0 +1,231 @@
+//! Project facts shared between sessions of one workspace.
+//!
+//! After each turn the assistant response is scanned for sentences that record
+//! a decision or describe where something lives; those are stored under the
+//! project namespace and listed in the memory section of later prompts.
+
+use odyssey_rs_config::ProjectMemoryConfig;
+use odyssey_rs_memory::{MemoryCapturePolicy, MemoryError, MemoryProvider, MemoryRecord};
+use serde_json::json;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Shortest sentence kept as a fact.
+const MIN_FACT_CHARS: usize = 20;
+/// Longest sentence kept as a fact.
+const MAX_FACT_CHARS: usize = 300;
+
+/// Phrases that mark a decision.
+const DECISION_MARKERS: &[&str] = &[
+    "decided",
+    "decision",
+    "we will ",
+    "we'll ",
+    "going forward",
+    "from now on",
+    "convention",
+    "agreed",
+    "chose ",
+];
+
+/// Phrases that describe the project layout; only kept when a path is named.
+const LAYOUT_MARKERS: &[&str] = &[
+    " lives in ",
+    " live in ",
+    " lives under ",
+    " located in ",
+    " defined in ",
+    " is in ",
+    " are in ",
+];
+
+/// Namespace of the project rooted at `workspace_root`.
+pub(crate) fn project_namespace(config: &ProjectMemoryConfig, workspace_root: &Path) -> String {
+    config
+        .namespace
+        .clone()
+        .or_else(|| {
+            workspace_root
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "default".to_string())
+}
+
+/// Pick up to `max` fact sentences from an assistant response.
+pub(crate) fn distill_facts(response: &str, max: usize) -> Vec<String> {
+    let mut facts: Vec<String> = Vec::new();
+    let mut in_code = false;
+    for line in response.lines() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || line.starts_with('#') {
+            continue;
+        }
+        for sentence in split_sentences(strip_list_marker(line)) {
+            if facts.len() >= max {
+                return facts;
+            }
+            if is_fact(&sentence) && !facts.contains(&sentence) {
+                facts.push(sentence);
+            }
+        }
+    }
+    facts
+}
+
+/// Store the facts of a response; returns how many were new.
+pub(crate) async fn capture_project_facts(
+    provider: &dyn MemoryProvider,
+    config: &ProjectMemoryConfig,
+    namespace: &str,
+    session_id: Uuid,
+    turn_id: Uuid,
+    response: &str,
+    policy: &MemoryCapturePolicy,
+) -> Result<usize, MemoryError> {
+    let mut stored = 0;
+    for fact in distill_facts(response, config.max_facts_per_turn) {
+        let record = MemoryRecord {
+            id: Uuid::new_v4(),
+            session_id,
+            role: "assistant".to_string(),
+            content: fact,
+            metadata: json!({
+                "kind": "project_fact",
+                "namespace": namespace,
+                "turn_id": turn_id,
+            }),
+            created_at: chrono::Utc::now(),
+        };
+        if provider.remember_project(namespace, record, policy).await? {
+            stored += 1;
+        }
+    }
+    Ok(stored)
+}
+
+/// Render recalled project facts as a prompt subsection.
+pub(crate) fn format_project_facts(records: &[MemoryRecord]) -> String {
+    if records.is_empty() {
+        return String::new();
+    }
+    let facts: Vec<String> = records
+        .iter()
+        .map(|record| format!("- {}", record.content))
+        .collect();
+    format!("## Project Memory\n\n{}", facts.join("\n"))
+}
+
+fn strip_list_marker(line: &str) -> &str {
+    let line = line.trim_start_matches(['-', '*', '+', '>']).trim_start();
+    match line.split_once(". ") {
+        Some((number, rest)) if number.chars().all(|ch| ch.is_ascii_digit()) => rest,
+        _ => line,
+    }
+}
+
+fn split_sentences(line: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        current.push(ch);
+        if matches!(ch, '.' | '!' | '?') && chars.peek().is_none_or(|next| next.is_whitespace()) {
+            sentences.push(current.trim().to_string());
+            current.clear();
+        }
+    }
+    if !current.trim().is_empty() {
+        sentences.push(current.trim().to_string());
+    }
+    sentences
+}
+
+fn is_fact(sentence: &str) -> bool {
+    let length = sentence.chars().count();
+    if !(MIN_FACT_CHARS..=MAX_FACT_CHARS).contains(&length) || sentence.ends_with('?') {
+        return false;
+    }
+    let lower = sentence.to_lowercase();
+    DECISION_MARKERS.iter().any(|marker| lower.contains(marker))
+        || (LAYOUT_MARKERS.iter().any(|marker| lower.contains(marker)) && names_path(sentence))
+}
+
+fn names_path(sentence: &str) -> bool {
+    sentence
+        .split_whitespace()
+        .map(|word| word.trim_matches(|ch: char| "`'\"(),.:;".contains(ch)))
+        .any(|word| word.contains('/') || word.rsplit_once('.').is_some_and(|(_, ext)| is_ext(ext)))
+}
+
+fn is_ext(ext: &str) -> bool {
+    (1..=4).contains(&ext.len()) && ext.chars().all(|ch| ch.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{distill_facts, format_project_facts, project_namespace};
+    use chrono::Utc;
+    use odyssey_rs_config::ProjectMemoryConfig;
+    use odyssey_rs_memory::MemoryRecord;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+    use uuid::Uuid;
+
+    #[test]
+    fn distills_decisions_and_layout_facts() {
+        let response = "\
+I looked at the code. We decided to keep errors in a single enum per crate.
+- The CLI entry point lives in `crates/cli/src/main.rs`.
+- Tests are in a good state.
+```
+// We decided nothing here, this is code.
+```
+Should we agree on a convention for logging?
+Going forward, migrations run before the server starts. Thanks!";
+        assert_eq!(
+            distill_facts(response, 5),
+            vec![
+                "We decided to keep errors in a single enum per crate.".to_string(),
+                "The CLI entry point lives in `crates/cli/src/main.rs`.".to_string(),
+                "Going forward, migrations run before the server starts.".to_string(),
+            ]
+        );
+        assert_eq!(distill_facts(response, 1).len(), 1);
+    }
+
+    #[test]
+    fn namespace_defaults_to_workspace_name() {
+        let mut config = ProjectMemoryConfig::default();
+        assert_eq!(
+            project_namespace(&config, Path::new("/work/odyssey")),
+            "odyssey"
+        );
+        config.namespace = Some("shared".to_string());
+        assert_eq!(
+            project_namespace(&config, Path::new("/work/odyssey")),
+            "shared"
+        );
+    }
+
+    #[test]
+    fn formats_facts_as_list() {
+        let record = MemoryRecord {
+            id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            role: "assistant".to_string(),
+            content: "We chose sqlite for the cache.".to_string(),
+            metadata: serde_json::json!({}),
+            created_at: Utc::now(),
+        };
+        assert_eq!(format_project_facts(&[]), "");
+        assert_eq!(
+            format_project_facts(&[record]),
+            "## Project Memory\n\n- We chose sqlite for the cache."
+        );
+    }
+}
@@ -1,22 +1,339 @@
 //! System prompt assembly for orchestrator and subagent turns.
 
 use super::memory::{format_memory_records, recall_options_from_config};
+use super::project_memory::{format_project_facts, project_namespace};
 use crate::error::OdysseyCoreError;
 use crate::instructions::resolve_instruction_roots;
-use odyssey_rs_config::MemoryConfig;
+use crate::locale::LocaleContext;
+use crate::model_catalog::estimate_tokens;
+use odyssey_rs_config::{
+    LocaleConfig, MemoryConfig, OdysseyConfig, PromptProfileConfig, PromptSectionName,
+};
 use odyssey_rs_memory::MemoryProvider;
 use odyssey_rs_protocol::SkillProvider;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 
-/// Prompt profile controls small formatting differences between agent types.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Prompt profile selects the sections and guidance of a system prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PromptProfile {
     /// Full system prompt for the orchestrator.
     OrchestratorDefault,
     /// Subagent prompt with reduced orchestration context.
     SubagentFocused,
+    /// Identity, caller instructions, and environment only.
+    Minimal,
+    /// Full prompt with guidance for editing and verifying code.
+    CodeAgent,
+    /// Prompt without skills, with guidance for planning before acting.
+    PlanningAgent,
+    /// Profile defined under `prompt_profiles` in config.
+    Custom(CustomPromptProfile),
+}
+
+/// Sections and guidance of a user-defined prompt profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomPromptProfile {
+    /// Profile name in config.
+    pub name: String,
+    /// Sections to include, in render order.
+    pub sections: Vec<PromptSectionKind>,
+    /// Whether workspace bootstrap files are included with the instructions.
+    pub bootstrap_files: bool,
+    /// Guidance appended to the identity section.
+    pub guidance: Option<String>,
+}
+
+const ALL_SECTIONS: [PromptSectionKind; 5] = [
+    PromptSectionKind::Identity,
+    PromptSectionKind::Instructions,
+    PromptSectionKind::Skills,
+    PromptSectionKind::Environment,
+    PromptSectionKind::Memory,
+];
+
+const CODE_AGENT_GUIDANCE: &str = "## Coding Guidelines\n\
+- Read the relevant code before changing it and follow the conventions you find.\n\
+- Keep changes focused on the task; do not reformat or refactor unrelated code.\n\
+- After editing, build and run the tests that cover the change, and report the results.";
+
+const PLANNING_AGENT_GUIDANCE: &str = "## Planning Guidelines\n\
+- Before acting, break the task into numbered steps and share the plan.\n\
+- Call out assumptions, open questions, and risks for each step.\n\
+- Prefer reading and investigating over changing files until the plan is agreed.";
+
+impl PromptProfile {
+    /// Resolve a built-in profile name or a user-defined profile from config.
+    pub fn resolve(
+        name: &str,
+        profiles: &BTreeMap<String, PromptProfileConfig>,
+    ) -> Result<Self, OdysseyCoreError> {
+        let profile = match name {
+            "orchestrator_default" => Self::OrchestratorDefault,
+            "subagent_focused" => Self::SubagentFocused,
+            "minimal" => Self::Minimal,
+            "code_agent" => Self::CodeAgent,
+            "planning_agent" => Self::PlanningAgent,
+            _ => {
+                let config = profiles
+                    .get(name)
+                    .ok_or_else(|| OdysseyCoreError::UnknownPromptProfile(name.to_string()))?;
+                Self::Custom(CustomPromptProfile {
+                    name: name.to_string(),
+                    sections: config.sections.iter().copied().map(Into::into).collect(),
+                    bootstrap_files: config.bootstrap_files,
+                    guidance: config.guidance.clone(),
+                })
+            }
+        };
+        Ok(profile)
+    }
+
+    /// Profile of the default agent, from `orchestrator.prompt_profile`.
+    pub fn from_config(config: &OdysseyConfig) -> Result<Self, OdysseyCoreError> {
+        match config.orchestrator.prompt_profile.as_deref() {
+            Some(name) => Self::resolve(name, &config.prompt_profiles),
+            None => Ok(Self::OrchestratorDefault),
+        }
+    }
+
+    /// Sections the profile includes, in render order.
+    pub fn sections(&self) -> Vec<PromptSectionKind> {
+        match self {
+            Self::OrchestratorDefault | Self::SubagentFocused | Self::CodeAgent => {
+                ALL_SECTIONS.to_vec()
+            }
+            Self::Minimal => vec![
+                PromptSectionKind::Identity,
+                PromptSectionKind::Instructions,
+                PromptSectionKind::Environment,
+            ],
+            Self::PlanningAgent => vec![
+                PromptSectionKind::Identity,
+                PromptSectionKind::Instructions,
+                PromptSectionKind::Environment,
+                PromptSectionKind::Memory,
+            ],
+            Self::Custom(custom) => custom.sections.clone(),
+        }
+    }
+
+    /// Whether workspace bootstrap files are included with the instructions.
+    fn bootstrap_files(&self) -> bool {
+        match self {
+            Self::OrchestratorDefault | Self::CodeAgent | Self::PlanningAgent => true,
+            Self::SubagentFocused | Self::Minimal => false,
+            Self::Custom(custom) => custom.bootstrap_files,
+        }
+    }
+
+    /// Guidance appended to the identity section.
+    fn guidance(&self) -> Option<&str> {
+        match self {
+            Self::CodeAgent => Some(CODE_AGENT_GUIDANCE),
+            Self::PlanningAgent => Some(PLANNING_AGENT_GUIDANCE),
+            Self::OrchestratorDefault | Self::SubagentFocused | Self::Minimal => None,
+            Self::Custom(custom) => custom.guidance.as_deref(),
+        }
+    }
+}
+
+const SECTION_SEPARATOR: &str = "\n\n---\n\n";
+
+/// System prompt split into a stable prefix and content that changes between builds.
+///
+/// The cacheable prefix is rendered first so providers with prompt caching can reuse it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemPrompt {
+    /// Identity, bootstrap files, and skills; stable across turns.
+    pub cacheable: String,
+    /// Current time and memory recall.
+    pub dynamic: String,
+}
+
+impl SystemPrompt {
+    /// Render the full prompt with the cacheable prefix first.
+    pub fn render(&self) -> String {
+        [self.cacheable.as_str(), self.dynamic.as_str()]
+            .into_iter()
+            .filter(|part| !part.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(SECTION_SEPARATOR)
+    }
+}
+
+/// Named section of a built system prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PromptSectionKind {
+    /// Who the agent is and how it should behave.
+    Identity,
+    /// Caller instructions and bootstrap files.
+    Instructions,
+    /// Always-loaded skills and skill summaries.
+    Skills,
+    /// Current time, runtime, and workspace paths.
+    Environment,
+    /// Recalled memory records.
+    Memory,
+}
+
+impl From<PromptSectionName> for PromptSectionKind {
+    fn from(name: PromptSectionName) -> Self {
+        match name {
+            PromptSectionName::Identity => Self::Identity,
+            PromptSectionName::Instructions => Self::Instructions,
+            PromptSectionName::Skills => Self::Skills,
+            PromptSectionName::Environment => Self::Environment,
+            PromptSectionName::Memory => Self::Memory,
+        }
+    }
+}
+
+impl PromptSectionKind {
+    /// Stable lowercase name of the section.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Identity => "identity",
+            Self::Instructions => "instructions",
+            Self::Skills => "skills",
+            Self::Environment => "environment",
+            Self::Memory => "memory",
+        }
+    }
+}
+
+/// One section of a built system prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptSection {
+    /// Section name.
+    pub kind: PromptSectionKind,
+    /// Rendered section text.
+    pub content: String,
+    /// Whether the section belongs to the stable, cacheable prefix.
+    pub cacheable: bool,
+    /// Estimated token count of `content`.
+    pub tokens: usize,
+}
+
+impl PromptSection {
+    fn new(kind: PromptSectionKind, content: String, cacheable: bool) -> Self {
+        let tokens = estimate_tokens(&content);
+        Self {
+            kind,
+            content,
+            cacheable,
+            tokens,
+        }
+    }
+}
+
+/// System prompt as an ordered list of named sections.
+///
+/// Sections can be inspected, reordered, overridden, or disabled before rendering.
+/// Rendering keeps cacheable sections ahead of dynamic ones regardless of order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuiltPrompt {
+    sections: Vec<PromptSection>,
+}
+
+impl BuiltPrompt {
+    /// Sections in render order.
+    pub fn sections(&self) -> &[PromptSection] {
+        &self.sections
+    }
+
+    /// Look up a section by kind.
+    pub fn section(&self, kind: PromptSectionKind) -> Option<&PromptSection> {
+        self.sections.iter().find(|section| section.kind == kind)
+    }
+
+    /// Replace a section's content, appending the section if it is missing.
+    pub fn override_section(&mut self, kind: PromptSectionKind, content: impl Into<String>) {
+        let content = content.into();
+        if let Some(section) = self
+            .sections
+            .iter_mut()
+            .find(|section| section.kind == kind)
+        {
+            section.tokens = estimate_tokens(&content);
+            section.content = content;
+            return;
+        }
+        let cacheable = !matches!(
+            kind,
+            PromptSectionKind::Environment | PromptSectionKind::Memory
+        );
+        self.sections
+            .push(PromptSection::new(kind, content, cacheable));
+    }
+
+    /// Remove a section; returns false when it was not present.
+    pub fn disable(&mut self, kind: PromptSectionKind) -> bool {
+        let before = self.sections.len();
+        self.sections.retain(|section| section.kind != kind);
+        self.sections.len() != before
+    }
+
+    /// Move the listed sections to the front in the given order.
+    ///
+    /// Sections not listed keep their relative order after the listed ones.
+    pub fn reorder(&mut self, order: &[PromptSectionKind]) {
+        self.sections.sort_by_key(|section| {
+            order
+                .iter()
+                .position(|kind| *kind == section.kind)
+                .unwrap_or(order.len())
+        });
+    }
+
+    /// Estimated tokens for each section, in render order.
+    pub fn token_estimates(&self) -> Vec<(PromptSectionKind, usize)> {
+        self.sections
+            .iter()
+            .map(|section| (section.kind, section.tokens))
+            .collect()
+    }
+
+    /// Estimated tokens across all sections.
+    pub fn total_tokens(&self) -> usize {
+        self.sections.iter().map(|section| section.tokens).sum()
+    }
+
+    /// Split the sections into cacheable and dynamic parts.
+    pub fn system_prompt(&self) -> SystemPrompt {
+        let join = |cacheable: bool| {
+            self.sections
+                .iter()
+                .filter(|section| section.cacheable == cacheable)
+                .map(|section| section.content.as_str())
+                .filter(|content| !content.trim().is_empty())
+                .collect::<Vec<_>>()
+                .join(SECTION_SEPARATOR)
+        };
+        SystemPrompt {
+            cacheable: join(true),
+            dynamic: join(false),
+        }
+    }
+
+    /// Render the full prompt with the cacheable prefix first.
+    pub fn render(&self) -> String {
+        self.system_prompt().render()
+    }
+}
+
+/// System prompt an agent runs with, attributed to the sections it was built from.
+///
+/// Per-turn notes, such as files changed outside tools, are not included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectivePrompt {
+    /// Agent the prompt belongs to.
+    pub agent_id: String,
+    /// Sections in render order; empty when the agent was registered with a plain prompt.
+    pub sections: Vec<PromptSection>,
+    /// Full prompt text sent to the model.
+    pub text: String,
 }
 
 /// Builds system prompts from base prompt, instructions, memory recall, and skills.
@@ -26,6 +343,8 @@ pub struct PromptBuilder {
     memory_provider: Arc<dyn MemoryProvider>,
     /// Optional skill store for skill summaries.
     skill_store: Option<Arc<dyn SkillProvider>>,
+    /// Time zone, locale, and units for the environment section; UTC only when unset.
+    locale: Option<LocaleContext>,
 }
 
 impl PromptBuilder {
@@ -37,9 +356,16 @@ impl PromptBuilder {
         Self {
             memory_provider,
             skill_store,
+            locale: None,
         }
     }
 
+    /// Render the current time in the configured time zone, with locale and units.
+    pub fn with_locale(mut self, config: &LocaleConfig) -> Self {
+        self.locale = LocaleContext::from_config(config);
+        self
+    }
+
     /// Build the system prompt for a single turn.
     pub async fn build_system_prompt(
         &self,
@@ -47,63 +373,147 @@ impl PromptBuilder {
         memory_config: &MemoryConfig,
         profile: PromptProfile,
     ) -> Result<String, OdysseyCoreError> {
+        Ok(self
+            .build_system_prompt_parts(additional_instructions, memory_config, profile)
+            .await?
+            .render())
+    }
+
+    /// Build the system prompt split into cacheable and dynamic sections.
+    pub async fn build_system_prompt_parts(
+        &self,
+        additional_instructions: &str,
+        memory_config: &MemoryConfig,
+        profile: PromptProfile,
+    ) -> Result<SystemPrompt, OdysseyCoreError> {
+        Ok(self
+            .build_sections(additional_instructions, memory_config, profile)
+            .await?
+            .system_prompt())
+    }
+
+    /// Build the system prompt as named sections with token estimates.
+    ///
+    /// Only the sections selected by `profile` are built, in its order.
+    pub async fn build_sections(
+        &self,
+        additional_instructions: &str,
+        memory_config: &MemoryConfig,
+        profile: PromptProfile,
+    ) -> Result<BuiltPrompt, OdysseyCoreError> {
         let cwd = std::env::current_dir().map_err(OdysseyCoreError::Io)?;
-        let instruction_roots = resolve_instruction_roots(&memory_config.instruction_roots, &cwd);
-        let bootstrap_sections = load_bootstrap_sections(&instruction_roots)?;
+        let kinds = profile.sections();
+        let mut sections = Vec::new();
+        for kind in &kinds {
+            let section = match kind {
+                PromptSectionKind::Identity => {
+                    let mut parts = vec![build_identity_section(&cwd), build_footer_section()];
+                    if let Some(guidance) = profile.guidance() {
+                        parts.push(guidance.to_string());
+                    }
+                    Some(PromptSection::new(
+                        *kind,
+                        parts.join(SECTION_SEPARATOR),
+                        true,
+                    ))
+                }
+                PromptSectionKind::Instructions => {
+                    build_instructions(additional_instructions, memory_config, &profile, &cwd)?
+                        .map(|content| PromptSection::new(*kind, content, true))
+                }
+                PromptSectionKind::Skills => {
+                    let skills = [
+                        "## Active Skills\n\nNo always-loaded skills.".to_string(),
+                        render_skill_section(self.skill_store.as_ref()),
+                    ];
+                    Some(PromptSection::new(
+                        *kind,
+                        skills.join(SECTION_SEPARATOR),
+                        true,
+                    ))
+                }
+                PromptSectionKind::Environment => Some(PromptSection::new(
+                    *kind,
+                    build_environment_section(&cwd, self.locale.as_ref()),
+                    false,
+                )),
+                PromptSectionKind::Memory => Some(PromptSection::new(
+                    *kind,
+                    self.build_memory(memory_config, &cwd).await?,
+                    false,
+                )),
+            };
+            sections.extend(section);
+        }
+        Ok(BuiltPrompt { sections })
+    }
+
+    /// Recalled memory records and project facts.
+    async fn build_memory(
+        &self,
+        memory_config: &MemoryConfig,
+        cwd: &std::path::Path,
+    ) -> Result<String, OdysseyCoreError> {
         let recall_options = recall_options_from_config(&memory_config.recall);
         let recall_records = self
             .memory_provider
             .recall_initial(None, memory_config.recall_k, recall_options)
             .await
             .map_err(|err| OdysseyCoreError::Memory(err.to_string()))?;
-
-        let mut sections = Vec::new();
-        let trimmed_additional_instructions = additional_instructions.trim();
-        let header = build_header_section(trimmed_additional_instructions, &cwd);
-        if !header.trim().is_empty() {
-            sections.push(header);
-        }
-        if profile == PromptProfile::OrchestratorDefault {
-            sections.extend(bootstrap_sections);
-        }
-        let recall_content = if let Some(records) = recall_records {
+        let mut recall_content = if let Some(records) = recall_records {
             format_memory_records(&records)
         } else {
             String::new()
         };
-        if recall_content.trim().is_empty() {
-            sections.push("## Memory\n\n".to_string());
-        } else {
-            sections.push(format!("## Memory\n\n{recall_content}"));
+        if memory_config.project.enabled {
+            let namespace = project_namespace(&memory_config.project, cwd);
+            let facts = self
+                .memory_provider
+                .recall_project(&namespace, memory_config.project.recall_k)
+                .await
+                .map_err(|err| OdysseyCoreError::Memory(err.to_string()))?;
+            let facts = format_project_facts(&facts);
+            if !facts.is_empty() {
+                if !recall_content.trim().is_empty() {
+                    recall_content.push_str("\n\n");
+                }
+                recall_content.push_str(&facts);
+            }
         }
-
-        sections.push("## Active Skills\n\nNo always-loaded skills.".to_string());
-        sections.push(render_skill_section(self.skill_store.as_ref()));
-        sections.push(build_footer_section());
-
-        if sections.is_empty() {
-            Ok(String::new())
+        Ok(if recall_content.trim().is_empty() {
+            "## Memory\n\n".to_string()
         } else {
-            Ok(sections.join("\n\n---\n\n"))
-        }
+            format!("## Memory\n\n{recall_content}")
+        })
+    }
+}
+
+/// Caller instructions and, when the profile includes them, bootstrap files.
+fn build_instructions(
+    additional_instructions: &str,
+    memory_config: &MemoryConfig,
+    profile: &PromptProfile,
+    cwd: &std::path::Path,
+) -> Result<Option<String>, OdysseyCoreError> {
+    let mut instructions = Vec::new();
+    let trimmed_additional_instructions = additional_instructions.trim();
+    if !trimmed_additional_instructions.is_empty() {
+        instructions.push(format!(
+            "## Additional Instructions\n{trimmed_additional_instructions}"
+        ));
+    }
+    if profile.bootstrap_files() {
+        let instruction_roots = resolve_instruction_roots(&memory_config.instruction_roots, cwd);
+        instructions.extend(load_bootstrap_sections(&instruction_roots)?);
     }
+    Ok((!instructions.is_empty()).then(|| instructions.join(SECTION_SEPARATOR)))
 }
 
 const BOOTSTRAP_FILES: [&str; 5] = ["AGENTS.md", "SOUL.md", "USER.md", "TOOLS.md", "IDENTITY.md"];
 
-fn build_header_section(additional_instructions: &str, cwd: &std::path::Path) -> String {
-    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M (%A)");
-    let runtime = format!("{} {}", std::env::consts::OS, std::env::consts::ARCH);
+fn build_identity_section(cwd: &std::path::Path) -> String {
     let workspace = cwd.display();
-    let date = chrono::Utc::now().format("%Y-%m-%d");
-    let memory_path = cwd.join("memory").join("MEMORY.md");
-    let daily_notes = cwd.join("memory").join(format!("{date}.md"));
-    let skills_path = cwd.join("skills");
-    let memory_path_display = memory_path.display();
-    let daily_notes_display = daily_notes.display();
-    let skills_path_display = skills_path.display();
-
-    let mut header = format!(
+    format!(
         "# Odyssey 🛠️ (built by liquidOS)\n\n\
 You are Odyssey, an assistant built by liquidOS. You have access to tools that let you:\n\
 - Read, write, and edit files in the workspace\n\
@@ -111,15 +521,6 @@ You are Odyssey, an assistant built by liquidOS. You have access to tools that l
 - Use web search and fetch web pages\n\
 - Send messages to specific chat channels\n\
 - Spawn subagents for background tasks\n\n\
-## Current Time\n\
-{now}\n\n\
-## Runtime\n\
-{runtime}\n\n\
-## Workspace\n\
-Your workspace: {workspace}\n\
-- Memory files: {memory_path_display}\n\
-- Daily notes: {daily_notes_display}\n\
-- Custom skills: {skills_path_display}/{{skill-name}}/SKILL.md\n\n\
 IMPORTANT BEHAVIOR RULES:\n\
 - For direct user conversation replies: respond with normal text only (do **not** call the message tool).\n\
 - Use the `message` tool **only** to send messages to external chat channels (WhatsApp, Telegram, Feishu) when explicitly required.\n\
@@ -128,14 +529,41 @@ IMPORTANT BEHAVIOR RULES:\n\
   2. Why you call it,\n\
   3. How you will use the result.\n\
 - When you store something to memory, append or write to {workspace}/memory/MEMORY.md and explain what you stored."
-    );
+    )
+}
 
-    if !additional_instructions.is_empty() {
-        header.push_str("\n\n## Additional Instructions\n");
-        header.push_str(additional_instructions);
-    }
+/// Current time, runtime, and workspace paths; the daily notes path changes with the date.
+fn build_environment_section(cwd: &std::path::Path, locale: Option<&LocaleContext>) -> String {
+    let now = chrono::Utc::now();
+    let (time, date) = match locale {
+        Some(locale) => (
+            locale.describe(now),
+            locale.local_time(now).format("%Y-%m-%d").to_string(),
+        ),
+        None => (
+            now.format("%Y-%m-%d %H:%M (%A)").to_string(),
+            now.format("%Y-%m-%d").to_string(),
+        ),
+    };
+    let runtime = format!("{} {}", std::env::consts::OS, std::env::consts::ARCH);
+    let workspace = cwd.display();
+    let memory_path = cwd.join("memory").join("MEMORY.md");
+    let daily_notes = cwd.join("memory").join(format!("{date}.md"));
+    let skills_path = cwd.join("skills");
+    let memory_path_display = memory_path.display();
+    let daily_notes_display = daily_notes.display();
+    let skills_path_display = skills_path.display();
 
-    header
+    format!(
+        "## Current Time\n{time}\n\n\
+## Runtime\n\
+{runtime}\n\n\
+## Workspace\n\
+Your workspace: {workspace}\n\
+- Memory files: {memory_path_display}\n\
+- Daily notes: {daily_notes_display}\n\
+- Custom skills: {skills_path_display}/{{skill-name}}/SKILL.md"
+    )
 }
 
 fn build_footer_section() -> String {
@@ -199,8 +627,11 @@ fn render_skill_section(store: Option<&Arc<dyn SkillProvider>>) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{PromptBuilder, PromptProfile};
-    use odyssey_rs_config::MemoryConfig;
+    use super::{PromptBuilder, PromptProfile, PromptSectionKind};
+    use crate::error::OdysseyCoreError;
+    use odyssey_rs_config::{
+        LocaleConfig, MeasurementUnits, MemoryConfig, PromptProfileConfig, PromptSectionName,
+    };
     use odyssey_rs_memory::MemoryRecord;
     use odyssey_rs_protocol::SkillSummary;
     use odyssey_rs_test_utils::{StubMemory, StubSkillProvider};
@@ -260,4 +691,172 @@ mod tests {
         assert!(prompt.contains("No skills available."));
         assert_eq!(prompt.contains("Additional Instructions"), false);
     }
+
+    #[tokio::test]
+    async fn environment_section_includes_locale_unless_disabled() {
+        let memory = Arc::new(StubMemory::with_initial(Vec::new()));
+        let builder = PromptBuilder::new(memory, None).with_locale(&LocaleConfig {
+            timezone: Some("-03:00".to_string()),
+            locale: Some("pt-BR".to_string()),
+            units: Some(MeasurementUnits::Metric),
+            ..LocaleConfig::default()
+        });
+        let parts = builder
+            .build_system_prompt_parts("", &MemoryConfig::default(), PromptProfile::Minimal)
+            .await
+            .expect("prompt");
+        assert!(parts.dynamic.contains("-03:00 (UTC-03:00)"));
+        assert!(parts.dynamic.contains("Locale: pt-BR; units: metric"));
+
+        let disabled = builder.with_locale(&LocaleConfig {
+            enabled: false,
+            ..LocaleConfig::default()
+        });
+        let parts = disabled
+            .build_system_prompt_parts("", &MemoryConfig::default(), PromptProfile::Minimal)
+            .await
+            .expect("prompt");
+        assert!(parts.dynamic.starts_with("## Current Time"));
+        assert_eq!(parts.dynamic.contains("Locale:"), false);
+    }
+
+    #[tokio::test]
+    async fn system_prompt_parts_keep_dynamic_sections_last() {
+        let memory = Arc::new(StubMemory::with_initial(Vec::new()));
+        let builder = PromptBuilder::new(memory, None);
+        let parts = builder
+            .build_system_prompt_parts(
+                "",
+                &MemoryConfig::default(),
+                PromptProfile::OrchestratorDefault,
+            )
+            .await
+            .expect("prompt");
+        assert!(parts.cacheable.contains("## Skills"));
+        assert_eq!(parts.cacheable.contains("## Current Time"), false);
+        assert_eq!(parts.cacheable.contains("## Memory"), false);
+        assert!(parts.dynamic.starts_with("## Current Time"));
+
+        let rendered = parts.render();
+        assert!(rendered.starts_with(&parts.cacheable));
+        assert!(rendered.ends_with(&parts.dynamic));
+    }
+
+    #[tokio::test]
+    async fn built_prompt_sections_can_be_edited() {
+        let memory = Arc::new(StubMemory::with_initial(Vec::new()));
+        let builder = PromptBuilder::new(memory, None);
+        let mut prompt = builder
+            .build_sections(
+                "Be brief.",
+                &MemoryConfig::default(),
+                PromptProfile::SubagentFocused,
+            )
+            .await
+            .expect("prompt");
+        let kinds = prompt
+            .sections()
+            .iter()
+            .map(|section| section.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                PromptSectionKind::Identity,
+                PromptSectionKind::Instructions,
+                PromptSectionKind::Skills,
+                PromptSectionKind::Environment,
+                PromptSectionKind::Memory,
+            ]
+        );
+        let total = prompt
+            .token_estimates()
+            .iter()
+            .map(|(_, tokens)| tokens)
+            .sum::<usize>();
+        assert_eq!(prompt.total_tokens(), total);
+
+        prompt.override_section(PromptSectionKind::Identity, "# Custom agent");
+        assert_eq!(
+            prompt
+                .section(PromptSectionKind::Identity)
+                .map(|section| section.tokens),
+            Some(4)
+        );
+        assert_eq!(prompt.disable(PromptSectionKind::Skills), true);
+        assert_eq!(prompt.disable(PromptSectionKind::Skills), false);
+        prompt.reorder(&[PromptSectionKind::Memory, PromptSectionKind::Instructions]);
+        assert_eq!(prompt.sections()[0].kind, PromptSectionKind::Memory);
+        assert_eq!(prompt.sections()[2].kind, PromptSectionKind::Identity);
+
+        let parts = prompt.system_prompt();
+        assert!(
+            parts
+                .cacheable
+                .starts_with("## Additional Instructions\nBe brief.")
+        );
+        assert!(parts.cacheable.ends_with("# Custom agent"));
+        assert_eq!(parts.cacheable.contains("## Skills"), false);
+        assert!(parts.dynamic.starts_with("## Memory"));
+    }
+
+    #[tokio::test]
+    async fn profiles_select_sections_and_guidance() {
+        let memory = Arc::new(StubMemory::with_initial(Vec::new()));
+        let builder = PromptBuilder::new(memory, None);
+        let kinds = |prompt: &super::BuiltPrompt| {
+            prompt
+                .sections()
+                .iter()
+                .map(|section| section.kind)
+                .collect::<Vec<_>>()
+        };
+
+        let minimal = builder
+            .build_sections("", &MemoryConfig::default(), PromptProfile::Minimal)
+            .await
+            .expect("prompt");
+        assert_eq!(
+            kinds(&minimal),
+            vec![PromptSectionKind::Identity, PromptSectionKind::Environment]
+        );
+
+        let code = builder
+            .build_sections("", &MemoryConfig::default(), PromptProfile::CodeAgent)
+            .await
+            .expect("prompt");
+        assert!(
+            code.section(PromptSectionKind::Identity)
+                .is_some_and(|section| section.content.ends_with("report the results."))
+        );
+
+        let profiles = std::collections::BTreeMap::from([(
+            "terse".to_string(),
+            PromptProfileConfig {
+                sections: vec![PromptSectionName::Memory, PromptSectionName::Identity],
+                bootstrap_files: false,
+                guidance: Some("Be terse.".to_string()),
+            },
+        )]);
+        let custom = PromptProfile::resolve("terse", &profiles).expect("custom");
+        let prompt = builder
+            .build_sections("Extra.", &MemoryConfig::default(), custom)
+            .await
+            .expect("prompt");
+        assert_eq!(
+            kinds(&prompt),
+            vec![PromptSectionKind::Memory, PromptSectionKind::Identity]
+        );
+        assert!(prompt.system_prompt().cacheable.ends_with("Be terse."));
+        assert_eq!(prompt.render().contains("Extra."), false);
+
+        assert_eq!(
+            PromptProfile::resolve("planning_agent", &profiles).expect("built in"),
+            PromptProfile::PlanningAgent
+        );
+        assert!(matches!(
+            PromptProfile::resolve("missing", &profiles),
+            Err(OdysseyCoreError::UnknownPromptProfile(name)) if name == "missing"
+        ));
+    }
 }
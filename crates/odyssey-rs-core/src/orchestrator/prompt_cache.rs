@@ -0,0 +1,278 @@
+//! Prompt cache hints and provider usage reporting.
+//!
+//! Each agent's system prompt is static for the lifetime of the agent, so it is
+//! the natural cacheable prefix. A stable cache key derived from the agent and
+//! prompt is attached to the turn context, and token usage reported by the
+//! provider is charged to the budget and emitted as `TokenUsage` events with
+//! the cache outcome.
+//!
+//! The key only goes into turn metadata: `LLMProvider` calls carry no
+//! per-request hints, so caching relies on providers that cache byte-stable
+//! prefixes on their own.
+
+use super::budget::BudgetTracker;
+use async_trait::async_trait;
+use autoagents_llm::LLMProvider;
+use autoagents_llm::chat::{
+    ChatMessage, ChatProvider, ChatResponse, StreamChunk, StreamResponse, StructuredOutputFormat,
+    Tool as LLMTool, Usage,
+};
+use autoagents_llm::completion::{CompletionProvider, CompletionRequest, CompletionResponse};
+use autoagents_llm::embedding::EmbeddingProvider;
+use autoagents_llm::error::LLMError;
+use autoagents_llm::models::ModelsProvider;
+use futures_util::{Stream, StreamExt};
+use log::{debug, warn};
+use odyssey_rs_config::OrchestratorConfig;
+use odyssey_rs_protocol::{
+    EventMsg, EventPayload, EventSink, PromptCacheStatus, SessionId, TokenUsage, TurnId,
+};
+use std::pin::Pin;
+use std::sync::Arc;
+use uuid::Uuid;
+
+type ChunkStream = Pin<Box<dyn Stream<Item = Result<StreamChunk, LLMError>> + Send>>;
+type StructStream = Pin<Box<dyn Stream<Item = Result<StreamResponse, LLMError>> + Send>>;
+
+const DEFAULT_KEY_PREFIX: &str = "odyssey";
+
+/// Derive the prompt cache key for an agent, or `None` when caching is disabled.
+///
+/// The key changes whenever the system prompt changes so stale prefixes are not reused.
+pub(crate) fn prompt_cache_key(
+    config: &OrchestratorConfig,
+    agent_id: &str,
+    system_prompt: &str,
+) -> Option<String> {
+    if !config.prompt_cache {
+        return None;
+    }
+    let prefix = config
+        .prompt_cache_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .unwrap_or(DEFAULT_KEY_PREFIX);
+    let digest = fnv1a(system_prompt.as_bytes());
+    Some(format!("{prefix}-{agent_id}-{digest:016x}"))
+}
+
+/// Stable 64-bit FNV-1a hash, independent of the std hasher seed.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Convert provider usage into a protocol usage record.
+fn token_usage(usage: &Usage, prompt_cache: bool) -> TokenUsage {
+    let cached_prompt_tokens = usage
+        .prompt_tokens_details
+        .as_ref()
+        .and_then(|details| details.cached_tokens)
+        .map(u64::from);
+    let status = prompt_cache.then(|| match cached_prompt_tokens {
+        Some(tokens) if tokens > 0 => PromptCacheStatus::Hit,
+        Some(_) | None => PromptCacheStatus::Miss,
+    });
+    TokenUsage {
+        prompt_tokens: u64::from(usage.prompt_tokens),
+        completion_tokens: u64::from(usage.completion_tokens),
+        cached_prompt_tokens,
+        prompt_cache: status,
+    }
+}
+
+/// Shared state for reporting the usage of a turn.
+#[derive(Clone)]
+pub(crate) struct UsageReporter {
+    pub(crate) sink: Option<Arc<dyn EventSink>>,
+    pub(crate) session_id: SessionId,
+    pub(crate) turn_id: TurnId,
+    pub(crate) prompt_cache_key: Option<String>,
+    /// Spend tracker charged for every call.
+    pub(crate) budget: Arc<BudgetTracker>,
+    /// Model of the turn as `provider/name`, used to price its usage.
+    pub(crate) model: Option<String>,
+}
+
+impl UsageReporter {
+    fn report(&self, usage: &Usage) {
+        let usage = token_usage(usage, self.prompt_cache_key.is_some());
+        debug!(
+            "llm usage (turn_id={}, prompt_tokens={}, cached_prompt_tokens={:?}, cache_key={:?})",
+            self.turn_id, usage.prompt_tokens, usage.cached_prompt_tokens, self.prompt_cache_key
+        );
+        let warnings = self
+            .budget
+            .record(self.session_id, self.model.as_deref(), &usage);
+        for limit in &warnings {
+            warn!(
+                "budget warning (session_id={}, turn_id={}): {limit}",
+                self.session_id, self.turn_id
+            );
+        }
+        let Some(sink) = &self.sink else {
+            return;
+        };
+        let turn_id = self.turn_id;
+        let payloads = std::iter::once(EventPayload::TokenUsage { turn_id, usage }).chain(
+            warnings
+                .into_iter()
+                .map(|limit| EventPayload::BudgetWarning { turn_id, limit }),
+        );
+        for payload in payloads {
+            sink.emit(EventMsg {
+                id: Uuid::new_v4(),
+                session_id: self.session_id,
+                seq: 0,
+                user_id: None,
+                created_at: chrono::Utc::now(),
+                payload,
+            });
+        }
+    }
+}
+
+/// LLM provider that reports token usage and prompt cache outcomes as events.
+struct UsageReportingLLM {
+    inner: Arc<dyn LLMProvider>,
+    reporter: UsageReporter,
+}
+
+/// Wrap an LLM so provider-reported usage is charged to the budget and emitted.
+pub(crate) fn report_usage(
+    llm: Arc<dyn LLMProvider>,
+    reporter: UsageReporter,
+) -> Arc<dyn LLMProvider> {
+    Arc::new(UsageReportingLLM {
+        inner: llm,
+        reporter,
+    })
+}
+
+#[async_trait]
+impl ChatProvider for UsageReportingLLM {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let response = self
+            .inner
+            .chat_with_tools(messages, tools, json_schema)
+            .await?;
+        if let Some(usage) = response.usage() {
+            self.reporter.report(&usage);
+        }
+        Ok(response)
+    }
+
+    async fn chat_with_web_search(&self, input: String) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let response = self.inner.chat_with_web_search(input).await?;
+        if let Some(usage) = response.usage() {
+            self.reporter.report(&usage);
+        }
+        Ok(response)
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, LLMError>> + Send>>, LLMError> {
+        self.inner.chat_stream(messages, json_schema).await
+    }
+
+    async fn chat_stream_struct(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<StructStream, LLMError> {
+        let stream = self
+            .inner
+            .chat_stream_struct(messages, tools, json_schema)
+            .await?;
+        let reporter = self.reporter.clone();
+        Ok(Box::pin(stream.inspect(move |item| {
+            if let Ok(response) = item
+                && let Some(usage) = &response.usage
+            {
+                reporter.report(usage);
+            }
+        })))
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<ChunkStream, LLMError> {
+        let stream = self
+            .inner
+            .chat_stream_with_tools(messages, tools, json_schema)
+            .await?;
+        let reporter = self.reporter.clone();
+        Ok(Box::pin(stream.inspect(move |chunk| {
+            if let Ok(StreamChunk::Usage(usage)) = chunk {
+                reporter.report(usage);
+            }
+        })))
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for UsageReportingLLM {
+    async fn complete(
+        &self,
+        req: &CompletionRequest,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req, json_schema).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for UsageReportingLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
+
+#[async_trait]
+impl ModelsProvider for UsageReportingLLM {}
+
+impl LLMProvider for UsageReportingLLM {}
+
+#[cfg(test)]
+mod tests {
+    use super::prompt_cache_key;
+    use odyssey_rs_config::OrchestratorConfig;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn cache_key_requires_opt_in() {
+        let config = OrchestratorConfig::default();
+        assert_eq!(prompt_cache_key(&config, "agent", "prompt"), None);
+    }
+
+    #[test]
+    fn cache_key_is_stable_and_tracks_prompt_changes() {
+        let config = OrchestratorConfig {
+            prompt_cache: true,
+            prompt_cache_key: Some("repo".to_string()),
+            ..OrchestratorConfig::default()
+        };
+        let first = prompt_cache_key(&config, "agent", "prompt").expect("key");
+        let second = prompt_cache_key(&config, "agent", "prompt").expect("key");
+        let changed = prompt_cache_key(&config, "agent", "prompt v2").expect("key");
+        assert_eq!(first, second);
+        assert_eq!(first.starts_with("repo-agent-"), true);
+        assert_eq!(first == changed, false);
+    }
+}
@@ -1,6 +1,7 @@
 //! Agent registry and default agent resolution.
 
 use super::agent_factory::AgentExecutorRunner;
+use super::prompt::BuiltPrompt;
 use crate::error::OdysseyCoreError;
 use crate::types::{AgentID, LLMProviderID};
 use autoagents_llm::LLMProvider;
@@ -8,8 +9,9 @@ use log::{debug, info};
 use odyssey_rs_config::{AgentSandboxConfig, MemoryConfig, PermissionMode, ToolPolicy};
 use odyssey_rs_memory::MemoryProvider;
 use parking_lot::RwLock;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Stored configuration and runtime for a registered agent.
 #[derive(Clone)]
@@ -21,6 +23,8 @@ pub(crate) struct AgentEntry {
     /// Base prompt for the agent.
     #[allow(dead_code)]
     pub(crate) prompt: String,
+    /// Sections `prompt` was built from, when registered with them.
+    pub(crate) prompt_sections: Option<BuiltPrompt>,
     /// Optional model configuration.
     pub(crate) model: Option<odyssey_rs_config::ModelConfig>,
     /// Tool allow/deny policy.
@@ -56,6 +60,7 @@ impl AgentEntry {
             id,
             description,
             prompt,
+            prompt_sections: None,
             model,
             tool_policy,
             permission_mode,
@@ -122,6 +127,15 @@ impl AgentRegistry {
         self.agents.read().keys().cloned().collect()
     }
 
+    /// Memory providers of every registered agent, keyed by agent id.
+    pub(crate) fn memory_providers(&self) -> Vec<(String, Arc<dyn MemoryProvider>)> {
+        self.agents
+            .read()
+            .values()
+            .map(|entry| (entry.id.clone(), entry.memory_provider.clone()))
+            .collect()
+    }
+
     /// Set the default agent id to an existing registered agent.
     pub(crate) fn set_default_agent_id(
         &self,
@@ -143,10 +157,14 @@ pub struct LLMEntry {
     pub provider: Arc<dyn LLMProvider>,
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub(crate) struct LLMRegistry {
     providers: Arc<RwLock<HashMap<LLMProviderID, LLMEntry>>>,
     default_provider: Arc<RwLock<LLMProviderID>>,
+    /// Providers running on this machine, usable in offline mode.
+    local: Arc<RwLock<HashSet<LLMProviderID>>>,
+    /// Reject providers not in `local`.
+    offline: Arc<AtomicBool>,
 }
 
 impl LLMRegistry {
@@ -154,19 +172,37 @@ impl LLMRegistry {
         Self {
             providers: Arc::new(RwLock::new(HashMap::default())),
             default_provider: Arc::new(RwLock::new(default_id)),
+            local: Arc::new(RwLock::new(HashSet::new())),
+            offline: Arc::new(AtomicBool::new(false)),
         }
     }
 
     pub(crate) fn insert_entry(&self, entry: LLMEntry) {
+        self.local.write().remove(&entry.id);
         let mut providers = self.providers.write();
         providers.insert(entry.id.clone(), entry);
     }
 
+    /// Insert a provider that runs on this machine and stays usable offline.
+    pub(crate) fn insert_local_entry(&self, entry: LLMEntry) {
+        let id = entry.id.clone();
+        self.insert_entry(entry);
+        self.local.write().insert(id);
+    }
+
+    pub(crate) fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
     pub(crate) fn list_llm_ids(&self) -> Vec<String> {
         self.providers.read().keys().cloned().collect()
     }
 
+    /// Look up a provider; offline, only local providers are returned.
     pub(crate) fn get_entry(&self, llm_id: &str) -> Result<LLMEntry, OdysseyCoreError> {
+        if self.offline.load(Ordering::SeqCst) && !self.local.read().contains(llm_id) {
+            return Err(OdysseyCoreError::OfflineProvider(llm_id.to_string()));
+        }
         self.providers
             .read()
             .get(llm_id)
@@ -322,4 +358,28 @@ mod tests {
         assert_eq!(registry.resolve_llm_id(None).unwrap(), "primary");
         assert_eq!(registry.get_entry("primary").unwrap().id, "primary");
     }
+
+    #[test]
+    fn llm_registry_offline_returns_only_local_entries() {
+        let registry = LLMRegistry::new("primary".to_string());
+        registry.insert_entry(LLMEntry {
+            id: "primary".to_string(),
+            provider: Arc::new(FailingLLM::new("remote")),
+        });
+        registry.insert_local_entry(LLMEntry {
+            id: "local".to_string(),
+            provider: Arc::new(FailingLLM::new("local")),
+        });
+        registry.set_offline(true);
+
+        assert_eq!(registry.get_entry("local").unwrap().id, "local");
+        match registry.get_entry("primary") {
+            Err(OdysseyCoreError::OfflineProvider(id)) => assert_eq!(id, "primary".to_string()),
+            Err(other) => panic!("unexpected error: {other:?}"),
+            Ok(_) => panic!("remote provider returned offline"),
+        }
+
+        registry.set_offline(false);
+        assert_eq!(registry.get_entry("primary").unwrap().id, "primary");
+    }
 }
@@ -1,29 +1,55 @@
 //! Turn execution flow for orchestrator and subagents.
 
 use super::agent_factory::AgentInput;
+use super::batch_confirm::{BatchGate, confirm_batches};
+use super::budget::BudgetTracker;
+use super::health::{StallWatchdog, stall_timeout};
 use super::memory::{
-    capture_policy_from_config, compaction_policy_from_config, recall_options_from_config,
+    capture_policy_from_config, compaction_policy_from_config, recall_debug_metadata,
+    recall_options_from_config,
 };
+use super::memory_compaction::spawn_memory_compaction;
+use super::project_memory::{capture_project_facts, project_namespace};
+use super::prompt_cache::{UsageReporter, prompt_cache_key, report_usage};
 use super::registry::AgentEntry;
+use super::sequence::EventSequencer;
 use super::sessions::SessionStore;
+use super::shutdown::TurnTracker;
+use super::stream_stats::StreamMeter;
 use super::tool_context::ToolContextFactory;
+use super::turn_guard::{TurnGuard, guard_turn};
 use crate::agent::memory::OdysseyMemoryAdapter;
 use crate::error::OdysseyCoreError;
+use crate::hooks::{LifecycleHooks, TurnHookContext};
+use crate::locale::LocaleContext;
+use crate::model_catalog::{
+    ModelCatalog, check_prompt_tokens, compaction_char_budget, estimate_tokens,
+};
+use crate::replay::{
+    RecordingLLMProvider, RecordingTool, ReplayLLM, ReplayMode, ReplaySessions, ReplayToolRouter,
+};
 use crate::tools::ToolRouter;
 use crate::types::{Message, Role, SessionId};
+use crate::workspace_watch::{WorkspaceWatcher, changed_files_note};
 use autoagents_core::agent::memory::{MemoryProvider, SlidingWindowMemory};
 use autoagents_llm::LLMProvider;
 use futures_util::StreamExt;
-use log::{debug, error, info};
-use odyssey_rs_config::MemoryConfig;
+use log::{debug, error, info, warn};
+use odyssey_rs_config::{MemoryConfig, MemorySummarizerKind};
+use odyssey_rs_memory::MemoryCompactionPolicy;
 use odyssey_rs_protocol::EventSink;
 use odyssey_rs_protocol::ToolError;
 use odyssey_rs_protocol::{EventMsg, EventPayload, ModelSpec, TurnContext, TurnId};
-use odyssey_rs_tools::{ToolContext, ToolResultHandler};
+use odyssey_rs_tools::{
+    EnvironmentReport, PermissionContext, Tool, ToolContext, ToolResultHandler, scheduled_adaptors,
+};
 use parking_lot::RwLock;
 use serde_json::json;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedMutexGuard, OwnedSemaphorePermit, Semaphore};
 use uuid::Uuid;
 
 /// Selects how tool results are captured during a turn.
@@ -60,6 +86,16 @@ pub(crate) struct TurnParams {
     pub(crate) turn_id: Option<TurnId>,
     pub(crate) event_sink: Option<Arc<dyn EventSink>>,
     pub(crate) stream: bool,
+    /// Append the input to the session as a user message; off when regenerating.
+    pub(crate) record_input: bool,
+    /// User prompting the turn; defaults to the session owner.
+    pub(crate) user_id: Option<String>,
+}
+
+/// Session lock and global slot held for the duration of a turn.
+pub(crate) struct TurnPermit {
+    _session: OwnedMutexGuard<()>,
+    _slot: Option<OwnedSemaphorePermit>,
 }
 
 /// Executes a single turn with prompt assembly and tool wiring.
@@ -74,6 +110,26 @@ pub(crate) struct TurnExecutor {
     tool_router: ToolRouter,
     /// Optional event sink for turn lifecycle events.
     event_sink: Option<Arc<dyn EventSink>>,
+    /// Shared event sequencer, told which user each session's turn belongs to.
+    sequencer: EventSequencer,
+    /// Per-session record/replay modes.
+    replay: ReplaySessions,
+    /// Global limit on concurrently running turns.
+    turn_slots: Option<Arc<Semaphore>>,
+    /// Model capabilities used to budget prompts.
+    model_catalog: ModelCatalog,
+    /// Lifecycle hooks run around each turn.
+    hooks: LifecycleHooks,
+    /// Running turns, drained or aborted on shutdown.
+    turns: Arc<TurnTracker>,
+    /// Watcher reporting files changed outside the agent's tools.
+    workspace_watcher: Option<WorkspaceWatcher>,
+    /// Silence after which a running turn is reported as stalled.
+    stall_timeout: Option<Duration>,
+    /// Spend tracker enforcing `budget` limits.
+    budget: Arc<BudgetTracker>,
+    /// Time zone, locale, and units added to turn metadata.
+    locale: Option<LocaleContext>,
 }
 
 impl TurnExecutor {
@@ -84,20 +140,211 @@ impl TurnExecutor {
         tool_context_factory: ToolContextFactory,
         tool_router: ToolRouter,
         event_sink: Option<Arc<dyn EventSink>>,
+        sequencer: EventSequencer,
+        hooks: LifecycleHooks,
     ) -> Self {
+        let turn_slots = config
+            .orchestrator
+            .max_concurrent_turns
+            .filter(|limit| *limit > 0)
+            .map(|limit| Arc::new(Semaphore::new(limit)));
+        let model_catalog = ModelCatalog::from_config(&config.models);
+        let workspace_watcher = if config.orchestrator.watch_workspace {
+            std::env::current_dir()
+                .map_err(OdysseyCoreError::Io)
+                .and_then(WorkspaceWatcher::start)
+                .inspect_err(|err| warn!("workspace watcher disabled: {err}"))
+                .ok()
+        } else {
+            None
+        };
+        let budget = match std::env::current_dir() {
+            Ok(cwd) => BudgetTracker::load(config.budget.clone(), &cwd),
+            Err(err) => {
+                warn!("budget totals kept in memory: {err}");
+                BudgetTracker::new(config.budget.clone())
+            }
+        };
         Self {
             config,
             session_store,
             tool_context_factory,
             tool_router,
             event_sink,
+            sequencer,
+            replay: ReplaySessions::default(),
+            turn_slots,
+            model_catalog,
+            hooks,
+            turns: Arc::new(TurnTracker::default()),
+            workspace_watcher,
+            stall_timeout: stall_timeout(config.orchestrator.stall_timeout_secs),
+            budget: Arc::new(budget),
+            locale: LocaleContext::from_config(&config.locale),
         }
     }
 
-    /// Execute a single agent turn end-to-end.
+    /// Wait for the session run lock and a global turn slot.
+    pub(crate) async fn acquire_turn(
+        &self,
+        session_id: SessionId,
+    ) -> Result<TurnPermit, OdysseyCoreError> {
+        self.ensure_accepting()?;
+        let queued = self.turns.enqueue();
+        let session = self
+            .session_store
+            .acquire_run_lock(session_id, self.config.orchestrator.session_busy)
+            .await?;
+        let slot = match &self.turn_slots {
+            Some(slots) => Some(
+                slots
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .map_err(|err| OdysseyCoreError::Executor(err.to_string()))?,
+            ),
+            None => None,
+        };
+        drop(queued);
+        // Shutdown may have started while waiting for the lock or a slot.
+        self.ensure_accepting()?;
+        Ok(TurnPermit {
+            _session: session,
+            _slot: slot,
+        })
+    }
+
+    /// Fail with `ShuttingDown` once shutdown has started.
+    pub(crate) fn ensure_accepting(&self) -> Result<(), OdysseyCoreError> {
+        if self.turns.is_accepting() {
+            Ok(())
+        } else {
+            Err(OdysseyCoreError::ShuttingDown)
+        }
+    }
+
+    /// Return the running-turn tracker used by shutdown.
+    pub(crate) fn turns(&self) -> &Arc<TurnTracker> {
+        &self.turns
+    }
+
+    /// Silence after which a running turn is reported as stalled.
+    pub(crate) fn stall_timeout(&self) -> Option<Duration> {
+        self.stall_timeout
+    }
+
+    /// Return the spend tracker enforcing `budget` limits.
+    pub(crate) fn budget(&self) -> &BudgetTracker {
+        &self.budget
+    }
+
+    /// Return the model capability catalog.
+    pub(crate) fn model_catalog(&self) -> &ModelCatalog {
+        &self.model_catalog
+    }
+
+    /// Return the per-session record/replay modes.
+    pub(crate) fn replay_sessions(&self) -> &ReplaySessions {
+        &self.replay
+    }
+
+    /// Execute a single agent turn end-to-end, wrapped in lifecycle hooks.
+    ///
+    /// The turn is tracked until it finishes and watched for stalls. When
+    /// shutdown aborts it, a `TurnCancelled` event is emitted and the turn fails
+    /// with `TurnAborted`. Failures are kept as the last error for health reports.
     pub(crate) async fn run_turn(
+        &self,
+        mut params: TurnParams,
+    ) -> Result<crate::orchestrator::RunResult, OdysseyCoreError> {
+        let session_id = params.session_id;
+        let turn_id = *params.turn_id.get_or_insert_with(Uuid::new_v4);
+        let event_sink = params
+            .event_sink
+            .clone()
+            .or_else(|| self.event_sink.clone());
+        let _running = self.turns.register(turn_id, session_id);
+        let _watchdog = self.stall_timeout.map(|timeout| {
+            StallWatchdog::spawn(
+                self.sequencer.clone(),
+                event_sink.clone(),
+                session_id,
+                turn_id,
+                timeout,
+            )
+        });
+        let result = tokio::select! {
+            result = self.run_hooked_turn(params) => result,
+            () = self.turns.aborted() => {
+                warn!(
+                    "turn aborted by shutdown (session_id={}, turn_id={})",
+                    session_id, turn_id
+                );
+                self.emit_event(event_sink, session_id, EventPayload::TurnCancelled { turn_id });
+                Err(OdysseyCoreError::TurnAborted(
+                    "orchestrator shut down".to_string(),
+                ))
+            }
+        };
+        if let Err(err) = &result {
+            self.turns
+                .record_error(session_id, turn_id, err.to_string());
+        }
+        result
+    }
+
+    /// Run lifecycle hooks around the turn body.
+    async fn run_hooked_turn(
+        &self,
+        mut params: TurnParams,
+    ) -> Result<crate::orchestrator::RunResult, OdysseyCoreError> {
+        if self.hooks.is_empty() {
+            return self.execute_turn(params, None).await;
+        }
+        let turn_id = *params.turn_id.get_or_insert_with(Uuid::new_v4);
+        let mut hook_context = TurnHookContext {
+            session_id: params.session_id,
+            agent_id: params.agent_id.clone(),
+            turn_id,
+            input: params.input.clone(),
+            metadata: json!({}),
+            event_sink: params
+                .event_sink
+                .clone()
+                .or_else(|| self.event_sink.clone()),
+        };
+        if let Err(err) = self.hooks.before_turn(&mut hook_context).await {
+            warn!(
+                "turn rejected by lifecycle hook (session_id={}, turn_id={}): {}",
+                params.session_id, turn_id, err
+            );
+            self.emit_event(
+                hook_context.event_sink.clone(),
+                params.session_id,
+                EventPayload::Error {
+                    turn_id: Some(turn_id),
+                    message: err.to_string(),
+                },
+            );
+            self.hooks.after_turn(&hook_context, Err(&err)).await;
+            return Err(err);
+        }
+        params.input = hook_context.input.clone();
+        let result = self.execute_turn(params, Some(&hook_context)).await;
+        self.hooks
+            .after_turn(
+                &hook_context,
+                result.as_ref().map(|result| result.response.as_str()),
+            )
+            .await;
+        result
+    }
+
+    /// Execute a single agent turn, applying hook metadata and response review when hooked.
+    async fn execute_turn(
         &self,
         params: TurnParams,
+        hook_context: Option<&TurnHookContext>,
     ) -> Result<crate::orchestrator::RunResult, OdysseyCoreError> {
         let TurnParams {
             session_id,
@@ -111,23 +358,184 @@ impl TurnExecutor {
             turn_id,
             event_sink,
             stream,
+            record_input,
+            user_id,
         } = params;
 
         let event_sink = event_sink.or_else(|| self.event_sink.clone());
         let turn_id = turn_id.unwrap_or_else(Uuid::new_v4);
+        let user_id = user_id.or_else(|| self.session_store.session_user(session_id));
+        // The session run lock is held, so no other turn of this session is attributed meanwhile.
+        self.sequencer.set_actor(session_id, user_id.clone());
+        // The user message is stamped with the turn start so it precedes the
+        // turn's memory records; retrying a turn forgets memory from this time.
+        let started_at = chrono::Utc::now();
         info!(
-            "starting turn (session_id={}, agent_id={}, prompt_len={}, subagents={})",
+            "starting turn (session_id={}, agent_id={}, user_id={}, prompt_len={}, subagents={})",
             session_id,
             agent_id,
+            user_id.as_deref().unwrap_or("-"),
             input.len(),
             include_subagent_spawner,
         );
         let memory_config = self.resolve_memory_config(&entry);
         let capture_policy = capture_policy_from_config(&memory_config.capture);
-        let compaction_policy = compaction_policy_from_config(&memory_config.compaction);
+        let mut compaction_policy = compaction_policy_from_config(&memory_config.compaction);
         let recall_options = recall_options_from_config(&memory_config.recall);
-        let system_prompt = entry.prompt.clone();
-        let turn_context = self.build_turn_context(&entry)?;
+        let mut system_prompt = entry.prompt.clone();
+        let workspace_root = std::env::current_dir().map_err(OdysseyCoreError::Io)?;
+        let session_cwd = self.session_store.session_cwd(session_id, &workspace_root);
+        let mut turn_context = self.build_turn_context(&entry, &session_cwd.get())?;
+        if let Some(context_override) = self.session_store.context_override(session_id) {
+            turn_context.apply_override(&context_override);
+        }
+        if let Some(next_turn) = self.session_store.take_next_turn_override(session_id) {
+            turn_context.apply_override(&next_turn);
+        }
+        let simulation = turn_context.simulation.unwrap_or(false);
+        self.tool_context_factory
+            .permission_engine()
+            .set_session_approval_policy(session_id, turn_context.approval_policy);
+        let model = turn_context
+            .model
+            .as_ref()
+            .and_then(|model| self.model_catalog.get(&model.provider, &model.name))
+            .cloned();
+        let model_key = turn_context
+            .model
+            .as_ref()
+            .map(|model| format!("{}/{}", model.provider, model.name));
+        if let Err(limit) = self.budget.check(session_id, model_key.as_deref()) {
+            let err = OdysseyCoreError::BudgetExceeded(limit);
+            self.emit_event(
+                event_sink.clone(),
+                session_id,
+                EventPayload::Error {
+                    turn_id: Some(turn_id),
+                    message: err.to_string(),
+                },
+            );
+            return Err(err);
+        }
+        if let Some(model) = &model
+            && compaction_policy.max_total_chars.is_none()
+        {
+            compaction_policy.max_total_chars = Some(compaction_char_budget(model));
+        }
+        if let (Some(serde_json::Value::Object(extra)), Some(target)) = (
+            hook_context.map(|ctx| &ctx.metadata),
+            turn_context.metadata.as_object_mut(),
+        ) {
+            target.extend(extra.clone());
+        }
+        if memory_config.recall.debug && matches!(memory_mode, MemoryMode::AgentProvider) {
+            match entry
+                .memory_provider
+                .recall_explained(
+                    session_id,
+                    Some(input.as_str()),
+                    memory_config.recall_k,
+                    recall_options,
+                )
+                .await
+            {
+                Ok(records) => {
+                    if let Some(metadata) = turn_context.metadata.as_object_mut() {
+                        metadata
+                            .insert("memory_recall".to_string(), recall_debug_metadata(&records));
+                    }
+                }
+                Err(err) => warn!(
+                    "failed to explain memory recall (session_id={}, turn_id={}): {}",
+                    session_id, turn_id, err
+                ),
+            }
+        }
+        let cache_key = prompt_cache_key(&self.config.orchestrator, &agent_id, &system_prompt);
+        if let (Some(key), Some(metadata)) = (&cache_key, turn_context.metadata.as_object_mut()) {
+            metadata.insert("prompt_cache_key".to_string(), json!(key));
+        }
+        if let (Some(locale), Some(metadata)) =
+            (&self.locale, turn_context.metadata.as_object_mut())
+        {
+            metadata.insert("locale".to_string(), locale.metadata(chrono::Utc::now()));
+        }
+        if self.config.orchestrator.probe_environment
+            && let Some(metadata) = turn_context.metadata.as_object_mut()
+        {
+            let report =
+                EnvironmentReport::probe(self.tool_context_factory.sandbox_provider()).await;
+            metadata.insert("environment".to_string(), json!(report));
+        }
+
+        let file_versions = self.session_store.file_versions(session_id);
+        if let Some(watcher) = &self.workspace_watcher {
+            let stale = watcher.take_stale(session_id, &file_versions);
+            if let Some(note) = changed_files_note(watcher.root(), &stale) {
+                info!(
+                    "files changed outside tools since last turn (session_id={}, files={})",
+                    session_id,
+                    stale.len()
+                );
+                system_prompt.push_str("\n\n");
+                system_prompt.push_str(&note);
+            }
+        }
+
+        // LLM summaries run after the turn, so the adapter skips its inline compaction.
+        let summarize_with_llm = compaction_policy.enabled
+            && memory_config.compaction.summarizer == MemorySummarizerKind::Llm;
+        // The adapter keeps its recall, so the estimate below matches the prompt the agent sends.
+        let (memory, history_tokens): (Option<Box<dyn MemoryProvider>>, usize) = match memory_mode {
+            MemoryMode::AgentProvider => {
+                let mut adapter = OdysseyMemoryAdapter::new(
+                    session_id,
+                    agent_id.clone(),
+                    entry.memory_provider.clone(),
+                    capture_policy.clone(),
+                    MemoryCompactionPolicy {
+                        enabled: compaction_policy.enabled && !summarize_with_llm,
+                        ..compaction_policy.clone()
+                    },
+                    recall_options,
+                    Some(memory_config.recall_k),
+                );
+                let history = adapter.prefetch().await.unwrap_or_else(|err| {
+                    warn!(
+                        "failed to recall memory for the prompt estimate (session_id={}): {}",
+                        session_id, err
+                    );
+                    Vec::new()
+                });
+                let history_tokens = history
+                    .iter()
+                    .map(|message| estimate_tokens(&message.content))
+                    .sum();
+                (Some(Box::new(adapter)), history_tokens)
+            }
+            MemoryMode::SubagentWindow { window_size } => {
+                (Some(Box::new(SlidingWindowMemory::new(window_size))), 0)
+            }
+        };
+        if let Some(model) = &model {
+            let estimated_tokens =
+                estimate_tokens(&system_prompt) + estimate_tokens(&input) + history_tokens;
+            if let Err(err) = check_prompt_tokens(model, estimated_tokens) {
+                warn!(
+                    "rejecting oversized prompt (session_id={}, turn_id={}): {}",
+                    session_id, turn_id, err
+                );
+                self.emit_event(
+                    event_sink.clone(),
+                    session_id,
+                    EventPayload::Error {
+                        turn_id: Some(turn_id),
+                        message: err.to_string(),
+                    },
+                );
+                return Err(err);
+            }
+        }
 
         let tool_result_handler = self.build_tool_result_handler(tool_result_mode);
         let (sandbox_enabled, sandbox_mode) = self.resolve_sandbox(&entry);
@@ -139,29 +547,102 @@ impl TurnExecutor {
                 turn_id,
                 sandbox_enabled,
                 sandbox_mode,
+                simulation,
+                model.as_ref(),
+                session_cwd,
+                file_versions,
                 tool_result_handler,
                 event_sink.clone(),
             )
             .await?;
         let tool_context = Arc::new(RwLock::new(tool_context));
-        let tools = self
-            .tool_router
-            .tools_for_agent(&entry.tool_policy, tool_context.clone());
-        let executor = entry.executor.clone();
-        let memory: Option<Box<dyn MemoryProvider>> = match memory_mode {
-            MemoryMode::AgentProvider => Some(Box::new(OdysseyMemoryAdapter::new(
-                session_id,
-                agent_id.clone(),
-                entry.memory_provider.clone(),
-                capture_policy.clone(),
-                compaction_policy.clone(),
-                recall_options,
-                Some(memory_config.recall_k),
-            ))),
-            MemoryMode::SubagentWindow { window_size } => {
-                Some(Box::new(SlidingWindowMemory::new(window_size)))
+        let replay = self.replay.get(session_id);
+        let compaction_llm = (summarize_with_llm
+            && replay.is_none()
+            && !simulation
+            && matches!(memory_mode, MemoryMode::AgentProvider))
+        .then(|| llm.clone());
+        let (llm, tools) = match &replay {
+            None => (
+                llm,
+                self.tool_router
+                    .tools_for_agent(&entry.tool_policy, tool_context.clone()),
+            ),
+            Some(ReplayMode::Record(recorder)) => {
+                recorder.begin_turn(turn_id, &input);
+                let tools = self
+                    .tool_router
+                    .select_tools(&entry.tool_policy)
+                    .into_iter()
+                    .map(|tool| {
+                        Arc::new(RecordingTool::new(tool, recorder.clone())) as Arc<dyn Tool>
+                    })
+                    .collect::<Vec<_>>();
+                let llm: Arc<dyn LLMProvider> =
+                    Arc::new(RecordingLLMProvider::new(llm, recorder.clone()));
+                let tools =
+                    scheduled_adaptors(tools, tool_context.clone(), self.tool_router.scheduler());
+                (llm, tools)
             }
+            Some(ReplayMode::Replay(player)) => {
+                let turn = player.next_turn(&input)?;
+                let tools = ReplayToolRouter::new(self.tool_router.clone(), turn.clone())
+                    .tools_for_agent(&entry.tool_policy, tool_context.clone());
+                let llm: Arc<dyn LLMProvider> = Arc::new(ReplayLLM::new(turn));
+                (llm, tools)
+            }
+        };
+        let tools = match &model {
+            Some(model) if !model.supports_tools => {
+                warn!(
+                    "model does not support tools; running without them (model={})",
+                    model.name
+                );
+                Vec::new()
+            }
+            Some(_) | None => tools,
+        };
+        let permissions = self.tool_context_factory.permission_engine();
+        let (llm, tools) = if !simulation && permissions.confirms_batches(&agent_id) {
+            let mutating = self
+                .tool_router
+                .select_tools(&entry.tool_policy)
+                .into_iter()
+                .filter(|tool| tool.is_mutating())
+                .map(|tool| tool.name().to_string())
+                .collect();
+            let gate = BatchGate::new(
+                permissions.clone(),
+                PermissionContext {
+                    session_id,
+                    agent_id: agent_id.clone(),
+                    tool_name: None,
+                    turn_id: Some(turn_id),
+                },
+                event_sink.clone(),
+                mutating,
+            );
+            confirm_batches(&gate, llm, tools)
+        } else {
+            (llm, tools)
+        };
+        let guard = TurnGuard::from_config(&self.config.orchestrator);
+        let (llm, tools) = match &guard {
+            Some(guard) => guard_turn(guard, llm, tools),
+            None => (llm, tools),
         };
+        let llm = report_usage(
+            llm,
+            UsageReporter {
+                sink: event_sink.clone(),
+                session_id,
+                turn_id,
+                prompt_cache_key: cache_key,
+                budget: self.budget.clone(),
+                model: model_key,
+            },
+        );
+        let executor = entry.executor.clone();
 
         let agent_input = AgentInput {
             session_id,
@@ -171,65 +652,90 @@ impl TurnExecutor {
 
         let event_sink_clone = event_sink.clone();
         let response = if stream {
-            let stream_sink = event_sink.clone().ok_or_else(|| {
-                OdysseyCoreError::Executor("streaming requires event sink".into())
-            })?;
-            let mut stream = executor
-                .run_stream(
-                    agent_input,
-                    turn_id,
-                    turn_context.clone(),
-                    tools,
-                    llm,
-                    memory,
-                    stream_sink.clone(),
-                )
-                .await?;
-            stream_sink.emit(EventMsg {
-                id: Uuid::new_v4(),
-                session_id,
-                created_at: chrono::Utc::now(),
-                payload: EventPayload::TurnStarted {
-                    turn_id,
-                    context: turn_context,
-                },
-            });
-            let mut response = String::new();
-            while let Some(chunk) = stream.next().await {
-                let chunk = chunk?;
-                if chunk.is_empty() {
-                    continue;
-                }
+            async {
+                let stream_sink = event_sink.clone().ok_or_else(|| {
+                    OdysseyCoreError::Executor("streaming requires event sink".into())
+                })?;
+                let mut meter = StreamMeter::new(Instant::now());
+                let mut stream = executor
+                    .run_stream(
+                        agent_input,
+                        turn_id,
+                        turn_context.clone(),
+                        tools,
+                        llm,
+                        memory,
+                        stream_sink.clone(),
+                    )
+                    .await?;
+                stream_sink.emit(EventMsg {
+                    id: Uuid::new_v4(),
+                    session_id,
+                    seq: 0,
+                    user_id: None,
+                    created_at: chrono::Utc::now(),
+                    payload: EventPayload::TurnStarted {
+                        turn_id,
+                        context: turn_context,
+                    },
+                });
+                let mut response = String::new();
+                while let Some(chunk) = stream.next().await {
+                    let chunk = chunk?;
+                    if chunk.is_empty() {
+                        continue;
+                    }
 
-                let (delta, next_response) = if chunk.starts_with(&response) {
-                    let delta = chunk[response.len()..].to_string();
-                    (delta, chunk)
-                } else {
-                    let mut next_response = response.clone();
-                    next_response.push_str(&chunk);
-                    (chunk, next_response)
-                };
-
-                response = next_response;
-                if !delta.is_empty() {
-                    stream_sink.emit(EventMsg {
-                        id: Uuid::new_v4(),
+                    let (delta, next_response) = if chunk.starts_with(&response) {
+                        let delta = chunk[response.len()..].to_string();
+                        (delta, chunk)
+                    } else {
+                        let mut next_response = response.clone();
+                        next_response.push_str(&chunk);
+                        (chunk, next_response)
+                    };
+
+                    response = next_response;
+                    let stats = meter.record(&delta, Instant::now());
+                    if !delta.is_empty() {
+                        stream_sink.emit(EventMsg {
+                            id: Uuid::new_v4(),
+                            session_id,
+                            seq: 0,
+                            user_id: None,
+                            created_at: chrono::Utc::now(),
+                            payload: EventPayload::AgentMessageDelta { turn_id, delta },
+                        });
+                    }
+                    if let Some(stats) = stats {
+                        self.emit_event(
+                            Some(stream_sink.clone()),
+                            session_id,
+                            EventPayload::StreamStats { turn_id, stats },
+                        );
+                    }
+                }
+                if let Some(stats) = meter.finish(Instant::now()) {
+                    self.emit_event(
+                        Some(stream_sink.clone()),
                         session_id,
-                        created_at: chrono::Utc::now(),
-                        payload: EventPayload::AgentMessageDelta { turn_id, delta },
-                    });
+                        EventPayload::StreamStats { turn_id, stats },
+                    );
                 }
+                stream_sink.emit(EventMsg {
+                    id: Uuid::new_v4(),
+                    session_id,
+                    seq: 0,
+                    user_id: None,
+                    created_at: chrono::Utc::now(),
+                    payload: EventPayload::TurnCompleted {
+                        turn_id,
+                        message: response.clone(),
+                    },
+                });
+                Ok::<_, OdysseyCoreError>(response)
             }
-            stream_sink.emit(EventMsg {
-                id: Uuid::new_v4(),
-                session_id,
-                created_at: chrono::Utc::now(),
-                payload: EventPayload::TurnCompleted {
-                    turn_id,
-                    message: response.clone(),
-                },
-            });
-            Ok(response)
+            .await
         } else {
             executor
                 .run(
@@ -243,6 +749,13 @@ impl TurnExecutor {
                 )
                 .await
         };
+        if let Some(ReplayMode::Record(recorder)) = &replay {
+            recorder.persist()?;
+        }
+        let response = match (response, guard.and_then(|guard| guard.tripped())) {
+            (Err(_), Some(reason)) => Err(OdysseyCoreError::TurnAborted(reason)),
+            (response, _) => response,
+        };
         let response = match response {
             Ok(response) => response,
             Err(err) => {
@@ -261,22 +774,73 @@ impl TurnExecutor {
                 return Err(err);
             }
         };
+        if let Some(ctx) = hook_context
+            && let Err(err) = self.hooks.review_response(ctx, &response).await
+        {
+            warn!(
+                "response rejected by lifecycle hook (session_id={}, turn_id={}): {}",
+                session_id, turn_id, err
+            );
+            self.emit_event(
+                event_sink_clone,
+                session_id,
+                EventPayload::Error {
+                    turn_id: Some(turn_id),
+                    message: err.to_string(),
+                },
+            );
+            return Err(err);
+        }
 
-        let user_message = Message {
-            role: Role::User,
-            content: input,
-            created_at: chrono::Utc::now(),
-        };
+        if record_input {
+            let user_message = Message {
+                role: Role::User,
+                content: input,
+                created_at: started_at,
+                turn_id: Some(turn_id),
+                superseded: false,
+                user_id,
+            };
+            self.session_store
+                .append_message(session_id, &user_message)?;
+        }
         let assistant_message = Message {
             role: Role::Assistant,
             content: response.clone(),
             created_at: chrono::Utc::now(),
+            turn_id: Some(turn_id),
+            superseded: false,
+            user_id: None,
         };
-
-        self.session_store
-            .append_message(session_id, &user_message)?;
         self.session_store
             .append_message(session_id, &assistant_message)?;
+        if let Some(llm) = compaction_llm {
+            spawn_memory_compaction(
+                entry.memory_provider.clone(),
+                llm,
+                compaction_policy,
+                session_id,
+            );
+        }
+        if memory_config.project.enabled && !simulation {
+            let namespace = project_namespace(&memory_config.project, &workspace_root);
+            if let Err(err) = capture_project_facts(
+                entry.memory_provider.as_ref(),
+                &memory_config.project,
+                &namespace,
+                session_id,
+                turn_id,
+                &response,
+                &capture_policy,
+            )
+            .await
+            {
+                warn!(
+                    "failed to store project facts (session_id={}, turn_id={}): {}",
+                    session_id, turn_id, err
+                );
+            }
+        }
 
         info!(
             "completed turn (session_id={}, agent_id={}, turn_id={}, response_len={})",
@@ -288,18 +852,17 @@ impl TurnExecutor {
         Ok(crate::orchestrator::RunResult {
             session_id,
             response,
+            structured: None,
         })
     }
 
-    /// Build a turn context populated from config and agent entry.
+    /// Build a turn context populated from config, agent entry, and session cwd.
     pub(crate) fn build_turn_context(
         &self,
         entry: &AgentEntry,
+        cwd: &Path,
     ) -> Result<TurnContext, OdysseyCoreError> {
-        let cwd = std::env::current_dir()
-            .map_err(OdysseyCoreError::Io)?
-            .display()
-            .to_string();
+        let cwd = cwd.display().to_string();
         let model = entry.model.as_ref().map(model_spec_from_config);
         let (sandbox_enabled, sandbox_mode) = self.resolve_sandbox(entry);
         let sandbox_mode = if sandbox_enabled {
@@ -313,10 +876,71 @@ impl TurnExecutor {
             model,
             sandbox_mode,
             approval_policy: None,
+            simulation: self.config.orchestrator.simulation.then_some(true),
+            temperature: None,
             metadata: json!({}),
         })
     }
 
+    /// Run one registered tool outside an agent turn, e.g. for an MCP client.
+    ///
+    /// The call goes through the same tool context as a turn: permission checks,
+    /// tool hooks, sandbox, session cwd, and output policy all apply. Results are
+    /// not recorded in the session transcript. The caller holds the turn permit.
+    pub(crate) async fn call_tool(
+        &self,
+        session_id: SessionId,
+        agent_id: &str,
+        entry: &AgentEntry,
+        tool_name: &str,
+        args: serde_json::Value,
+    ) -> Result<serde_json::Value, OdysseyCoreError> {
+        let tool = self
+            .tool_router
+            .select_tools(&entry.tool_policy)
+            .into_iter()
+            .find(|tool| tool.name() == tool_name)
+            .ok_or_else(|| {
+                OdysseyCoreError::Tool(ToolError::ToolNotFound(tool_name.to_string()))
+            })?;
+        let workspace_root = std::env::current_dir().map_err(OdysseyCoreError::Io)?;
+        let session_cwd = self.session_store.session_cwd(session_id, &workspace_root);
+        let mut turn_context = self.build_turn_context(entry, &session_cwd.get())?;
+        if let Some(context_override) = self.session_store.context_override(session_id) {
+            turn_context.apply_override(&context_override);
+        }
+        self.tool_context_factory
+            .permission_engine()
+            .set_session_approval_policy(session_id, turn_context.approval_policy);
+        let (sandbox_enabled, sandbox_mode) = self.resolve_sandbox(entry);
+        let turn_id = Uuid::new_v4();
+        debug!(
+            "calling tool outside a turn (session_id={}, tool_name={}, turn_id={})",
+            session_id, tool_name, turn_id
+        );
+        let mut tool_context = self
+            .tool_context_factory
+            .build_turn_context(
+                session_id,
+                agent_id,
+                turn_id,
+                sandbox_enabled,
+                sandbox_mode,
+                turn_context.simulation.unwrap_or(false),
+                None,
+                session_cwd,
+                self.session_store.file_versions(session_id),
+                None,
+                None,
+            )
+            .await?;
+        let _permit = self.tool_router.scheduler().acquire(tool.as_ref()).await;
+        tool_context
+            .execute_tool(tool.as_ref(), args)
+            .await
+            .map_err(OdysseyCoreError::Tool)
+    }
+
     /// Resolve memory configuration for an agent entry.
     pub(crate) fn resolve_memory_config(&self, entry: &AgentEntry) -> MemoryConfig {
         entry
@@ -370,6 +994,8 @@ impl TurnExecutor {
         let event = EventMsg {
             id: Uuid::new_v4(),
             session_id,
+            seq: 0,
+            user_id: None,
             created_at: chrono::Utc::now(),
             payload,
         };
@@ -419,6 +1045,9 @@ impl ToolResultHandler for SessionToolResultHandler {
             role: Role::System,
             content,
             created_at: chrono::Utc::now(),
+            turn_id: ctx.turn_id,
+            superseded: false,
+            user_id: None,
         };
 
         if let Some(session) = self.sessions.write().get_mut(&ctx.session_id) {
@@ -430,6 +1059,9 @@ impl ToolResultHandler for SessionToolResultHandler {
                 role: message.role.as_str().to_string(),
                 content: message.content.clone(),
                 created_at: message.created_at,
+                turn_id: message.turn_id,
+                superseded: message.superseded,
+                user_id: message.user_id.clone(),
             };
             store
                 .append_message(ctx.session_id, &record)
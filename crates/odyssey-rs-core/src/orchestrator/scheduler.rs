@@ -0,0 +1,579 @@
+//! Cron scheduler for recurring headless runs.
+//!
+//! Jobs come from `schedules.jobs` in config or are registered at runtime;
+//! runtime jobs are saved to `schedules.path` when it is set. Each run creates
+//! a fresh session for the job's agent, so its transcript lands in the session
+//! store next to interactive sessions.
+
+use super::Orchestrator;
+use crate::error::OdysseyCoreError;
+use crate::types::SessionId;
+use chrono::{DateTime, Local, Utc};
+use croner::Cron;
+use log::{debug, info, warn};
+use odyssey_rs_config::ScheduledJobConfig;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+/// Longest the loop sleeps before re-reading the clock, so suspends and clock changes are noticed.
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// Outcome of one scheduled run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledRun {
+    pub job_id: String,
+    /// Session holding the run's transcript, once it was created.
+    pub session_id: Option<SessionId>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// Final assistant response when the run succeeded.
+    pub response: Option<String>,
+    /// Error message when the run failed.
+    pub error: Option<String>,
+}
+
+/// Snapshot of a registered job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJobInfo {
+    pub job: ScheduledJobConfig,
+    /// True for jobs from `schedules.jobs`, which cannot be replaced or removed at runtime.
+    pub from_config: bool,
+    /// Next time the job is due; `None` while it is disabled.
+    pub next_run: Option<DateTime<Utc>>,
+    pub last_run: Option<ScheduledRun>,
+    /// True while a run of the job is in progress.
+    pub running: bool,
+}
+
+/// Runs registered jobs on their cron schedules until stopped.
+pub struct Scheduler {
+    orchestrator: Arc<Orchestrator>,
+    state: Arc<SchedulerState>,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Scheduler {
+    /// Load jobs from config and, when `schedules.path` is set, jobs saved by earlier runs.
+    ///
+    /// Saved jobs whose id matches a config job are ignored.
+    pub fn new(orchestrator: Arc<Orchestrator>) -> Result<Self, OdysseyCoreError> {
+        let config = &orchestrator.config().schedules;
+        let store_path = config.path.as_deref().map(resolve_store_path).transpose()?;
+        let now = Utc::now();
+        let mut jobs = BTreeMap::new();
+        for job in &config.jobs {
+            jobs.insert(job.id.clone(), JobEntry::new(job.clone(), true, now)?);
+        }
+        if let Some(path) = &store_path {
+            for job in load_jobs(path)? {
+                if jobs.contains_key(&job.id) {
+                    warn!(
+                        "ignoring saved scheduled job shadowed by config (id={})",
+                        job.id
+                    );
+                    continue;
+                }
+                jobs.insert(job.id.clone(), JobEntry::new(job, false, now)?);
+            }
+        }
+        info!("scheduler initialized (jobs={})", jobs.len());
+        Ok(Self {
+            orchestrator,
+            state: Arc::new(SchedulerState::new(jobs, store_path)),
+            task: Mutex::new(None),
+        })
+    }
+
+    /// Add a job, or replace one registered earlier at runtime, and save runtime jobs.
+    pub fn register(&self, job: ScheduledJobConfig) -> Result<(), OdysseyCoreError> {
+        self.state.register(JobEntry::new(job, false, Utc::now())?)
+    }
+
+    /// Remove a runtime job; returns false when no job has that id.
+    pub fn remove(&self, job_id: &str) -> Result<bool, OdysseyCoreError> {
+        self.state.remove(job_id)
+    }
+
+    /// Registered jobs sorted by id.
+    pub fn list(&self) -> Vec<ScheduledJobInfo> {
+        self.state.list()
+    }
+
+    /// Run a job immediately, outside its schedule, and wait for the result.
+    ///
+    /// Fails when the job is unknown or already running.
+    pub async fn run_now(&self, job_id: &str) -> Result<ScheduledRun, OdysseyCoreError> {
+        let job = self.state.begin(job_id)?;
+        let run = tokio::spawn(execute(self.orchestrator.clone(), self.state.clone(), job));
+        run.await
+            .map_err(|err| OdysseyCoreError::Executor(err.to_string()))
+    }
+
+    /// Start running jobs on their schedules; does nothing if already started.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn start(&self) {
+        let mut task = self.task.lock();
+        if task.as_ref().is_some_and(|handle| !handle.is_finished()) {
+            return;
+        }
+        info!("scheduler started");
+        *task = Some(tokio::spawn(run_loop(
+            self.orchestrator.clone(),
+            self.state.clone(),
+        )));
+    }
+
+    /// Stop scheduling new runs; runs already in progress finish on their own.
+    pub async fn stop(&self) {
+        let Some(handle) = self.task.lock().take() else {
+            return;
+        };
+        handle.abort();
+        let _ = handle.await;
+        info!("scheduler stopped");
+    }
+
+    /// Return true while the scheduling loop is running.
+    pub fn is_running(&self) -> bool {
+        self.task
+            .lock()
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        if let Some(handle) = self.task.get_mut().take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Registered job with its parsed schedule and run state.
+struct JobEntry {
+    job: ScheduledJobConfig,
+    cron: Cron,
+    from_config: bool,
+    next_run: Option<DateTime<Utc>>,
+    last_run: Option<ScheduledRun>,
+    running: bool,
+}
+
+impl JobEntry {
+    fn new(
+        job: ScheduledJobConfig,
+        from_config: bool,
+        now: DateTime<Utc>,
+    ) -> Result<Self, OdysseyCoreError> {
+        let cron = parse_job(&job)?;
+        let mut entry = Self {
+            job,
+            cron,
+            from_config,
+            next_run: None,
+            last_run: None,
+            running: false,
+        };
+        entry.schedule_after(now);
+        Ok(entry)
+    }
+
+    /// Set the next run to the first occurrence after `now`, evaluated in local time.
+    fn schedule_after(&mut self, now: DateTime<Utc>) {
+        if !self.job.enabled {
+            self.next_run = None;
+            return;
+        }
+        self.next_run = match self
+            .cron
+            .find_next_occurrence(&now.with_timezone(&Local), false)
+        {
+            Ok(next) => Some(next.with_timezone(&Utc)),
+            Err(err) => {
+                warn!(
+                    "scheduled job has no next occurrence (id={}, error={err})",
+                    self.job.id
+                );
+                None
+            }
+        };
+    }
+
+    fn info(&self) -> ScheduledJobInfo {
+        ScheduledJobInfo {
+            job: self.job.clone(),
+            from_config: self.from_config,
+            next_run: self.next_run,
+            last_run: self.last_run.clone(),
+            running: self.running,
+        }
+    }
+}
+
+/// Jobs shared between the scheduler handle, its loop, and in-flight runs.
+struct SchedulerState {
+    jobs: Mutex<BTreeMap<String, JobEntry>>,
+    store_path: Option<PathBuf>,
+    changed: Notify,
+}
+
+impl SchedulerState {
+    fn new(jobs: BTreeMap<String, JobEntry>, store_path: Option<PathBuf>) -> Self {
+        Self {
+            jobs: Mutex::new(jobs),
+            store_path,
+            changed: Notify::new(),
+        }
+    }
+
+    fn register(&self, mut entry: JobEntry) -> Result<(), OdysseyCoreError> {
+        {
+            let mut jobs = self.jobs.lock();
+            if let Some(existing) = jobs.get(&entry.job.id) {
+                if existing.from_config {
+                    return Err(OdysseyCoreError::Schedule(format!(
+                        "scheduled job {} is defined in config",
+                        entry.job.id
+                    )));
+                }
+                entry.last_run = existing.last_run.clone();
+                entry.running = existing.running;
+            }
+            let id = entry.job.id.clone();
+            let previous = jobs.insert(id.clone(), entry);
+            if let Err(err) = self.save(&jobs) {
+                match previous {
+                    Some(previous) => jobs.insert(id, previous),
+                    None => jobs.remove(&id),
+                };
+                return Err(err);
+            }
+            info!("scheduled job registered (id={id})");
+        }
+        self.changed.notify_one();
+        Ok(())
+    }
+
+    fn remove(&self, job_id: &str) -> Result<bool, OdysseyCoreError> {
+        {
+            let mut jobs = self.jobs.lock();
+            let Some(existing) = jobs.get(job_id) else {
+                return Ok(false);
+            };
+            if existing.from_config {
+                return Err(OdysseyCoreError::Schedule(format!(
+                    "scheduled job {job_id} is defined in config"
+                )));
+            }
+            let Some(removed) = jobs.remove(job_id) else {
+                return Ok(false);
+            };
+            if let Err(err) = self.save(&jobs) {
+                jobs.insert(job_id.to_string(), removed);
+                return Err(err);
+            }
+            info!("scheduled job removed (id={job_id})");
+        }
+        self.changed.notify_one();
+        Ok(true)
+    }
+
+    fn list(&self) -> Vec<ScheduledJobInfo> {
+        self.jobs.lock().values().map(JobEntry::info).collect()
+    }
+
+    /// Mark a job as running for an on-demand run.
+    fn begin(&self, job_id: &str) -> Result<ScheduledJobConfig, OdysseyCoreError> {
+        let mut jobs = self.jobs.lock();
+        let entry = jobs.get_mut(job_id).ok_or_else(|| {
+            OdysseyCoreError::Schedule(format!("unknown scheduled job: {job_id}"))
+        })?;
+        if entry.running {
+            return Err(OdysseyCoreError::Schedule(format!(
+                "scheduled job {job_id} is already running"
+            )));
+        }
+        entry.running = true;
+        Ok(entry.job.clone())
+    }
+
+    /// Mark due jobs as running and advance their schedules.
+    ///
+    /// A job still running from its previous occurrence skips this one.
+    fn take_due(&self, now: DateTime<Utc>) -> Vec<ScheduledJobConfig> {
+        let mut jobs = self.jobs.lock();
+        let mut due = Vec::new();
+        for entry in jobs.values_mut() {
+            if entry.next_run.is_none_or(|next| next > now) {
+                continue;
+            }
+            entry.schedule_after(now);
+            if entry.running {
+                warn!(
+                    "skipping scheduled run; previous run still in progress (id={})",
+                    entry.job.id
+                );
+                continue;
+            }
+            entry.running = true;
+            due.push(entry.job.clone());
+        }
+        due
+    }
+
+    /// Time until the earliest next run, capped at `MAX_SLEEP`.
+    fn wait_time(&self, now: DateTime<Utc>) -> Duration {
+        self.jobs
+            .lock()
+            .values()
+            .filter_map(|entry| entry.next_run)
+            .min()
+            .map_or(MAX_SLEEP, |next| {
+                (next - now).to_std().unwrap_or_default().min(MAX_SLEEP)
+            })
+    }
+
+    fn finish(&self, run: ScheduledRun) {
+        if let Some(entry) = self.jobs.lock().get_mut(&run.job_id) {
+            entry.running = false;
+            entry.last_run = Some(run);
+        }
+    }
+
+    /// Write runtime jobs to the store file, if one is configured.
+    fn save(&self, jobs: &BTreeMap<String, JobEntry>) -> Result<(), OdysseyCoreError> {
+        let Some(path) = &self.store_path else {
+            return Ok(());
+        };
+        let stored = StoredJobs {
+            jobs: jobs
+                .values()
+                .filter(|entry| !entry.from_config)
+                .map(|entry| entry.job.clone())
+                .collect(),
+        };
+        let payload = serde_json::to_vec_pretty(&stored)
+            .map_err(|err| OdysseyCoreError::Parse(err.to_string()))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        fs::write(&temp, payload)?;
+        fs::rename(&temp, path)?;
+        debug!(
+            "saved scheduled jobs (path={}, jobs={})",
+            path.display(),
+            stored.jobs.len()
+        );
+        Ok(())
+    }
+}
+
+/// On-disk format of runtime-registered jobs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredJobs {
+    #[serde(default)]
+    jobs: Vec<ScheduledJobConfig>,
+}
+
+async fn run_loop(orchestrator: Arc<Orchestrator>, state: Arc<SchedulerState>) {
+    loop {
+        let now = Utc::now();
+        for job in state.take_due(now) {
+            tokio::spawn(execute(orchestrator.clone(), state.clone(), job));
+        }
+        let wait = state.wait_time(now);
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = state.changed.notified() => {}
+        }
+    }
+}
+
+/// Run a job in a new session and record the outcome.
+async fn execute(
+    orchestrator: Arc<Orchestrator>,
+    state: Arc<SchedulerState>,
+    job: ScheduledJobConfig,
+) -> ScheduledRun {
+    info!("starting scheduled run (id={})", job.id);
+    let started_at = Utc::now();
+    let mut session_id = None;
+    let result = run_job(&orchestrator, &job, &mut session_id).await;
+    let run = ScheduledRun {
+        job_id: job.id,
+        session_id,
+        started_at,
+        finished_at: Utc::now(),
+        response: result.as_ref().ok().cloned(),
+        error: result.err().map(|err| err.to_string()),
+    };
+    match &run.error {
+        Some(err) => warn!("scheduled run failed (id={}, error={err})", run.job_id),
+        None => info!(
+            "scheduled run finished (id={}, session_id={:?})",
+            run.job_id, run.session_id
+        ),
+    }
+    state.finish(run.clone());
+    run
+}
+
+async fn run_job(
+    orchestrator: &Orchestrator,
+    job: &ScheduledJobConfig,
+    session_id: &mut Option<SessionId>,
+) -> Result<String, OdysseyCoreError> {
+    let agent_id = orchestrator
+        .agent_registry
+        .resolve_agent_id(job.agent.as_deref())?;
+    let llm_id = orchestrator
+        .llm_registry
+        .resolve_llm_id(job.llm.as_deref())?;
+    let id = orchestrator.create_session(Some(agent_id.clone()))?;
+    *session_id = Some(id);
+    let result = orchestrator
+        .run_in_session(id, &agent_id, &llm_id, job.prompt.clone())
+        .await?;
+    Ok(result.response)
+}
+
+/// Check a job's required fields and parse its cron expression.
+fn parse_job(job: &ScheduledJobConfig) -> Result<Cron, OdysseyCoreError> {
+    if job.id.trim().is_empty() {
+        return Err(OdysseyCoreError::Schedule(
+            "scheduled jobs require a non-empty id".to_string(),
+        ));
+    }
+    if job.prompt.trim().is_empty() {
+        return Err(OdysseyCoreError::Schedule(format!(
+            "scheduled job {} requires a non-empty prompt",
+            job.id
+        )));
+    }
+    Cron::new(&job.cron).parse().map_err(|err| {
+        OdysseyCoreError::Schedule(format!(
+            "scheduled job {} has an invalid cron expression: {err}",
+            job.id
+        ))
+    })
+}
+
+fn resolve_store_path(path: &str) -> Result<PathBuf, OdysseyCoreError> {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    Ok(std::env::current_dir()?.join(path))
+}
+
+fn load_jobs(path: &Path) -> Result<Vec<ScheduledJobConfig>, OdysseyCoreError> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let stored: StoredJobs = serde_json::from_str(&raw)
+        .map_err(|err| OdysseyCoreError::Parse(format!("{}: {err}", path.display())))?;
+    Ok(stored.jobs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JobEntry, SchedulerState};
+    use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+    use odyssey_rs_config::ScheduledJobConfig;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    fn job(id: &str, cron: &str) -> ScheduledJobConfig {
+        ScheduledJobConfig {
+            id: id.to_string(),
+            cron: cron.to_string(),
+            prompt: "report".to_string(),
+            agent: None,
+            llm: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_jobs() {
+        let now = Utc::now();
+        assert_eq!(JobEntry::new(job("", "@daily"), false, now).is_err(), true);
+        assert_eq!(
+            JobEntry::new(job("a", "nightly"), false, now).is_err(),
+            true
+        );
+        let mut disabled = job("a", "@daily");
+        disabled.enabled = false;
+        let entry = JobEntry::new(disabled, false, now).expect("entry");
+        assert_eq!(entry.next_run, None);
+    }
+
+    /// Local noon, so hourly and daily schedules never coincide within the test.
+    fn noon() -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(2026, 3, 10, 12, 0, 0)
+            .single()
+            .expect("local time")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn takes_due_jobs_and_skips_overlapping_runs() {
+        let now = noon();
+        let mut jobs = BTreeMap::new();
+        for id in ["hourly", "daily"] {
+            let cron = if id == "hourly" { "@hourly" } else { "@daily" };
+            jobs.insert(
+                id.to_string(),
+                JobEntry::new(job(id, cron), false, now).expect("entry"),
+            );
+        }
+        let state = SchedulerState::new(jobs, None);
+        assert_eq!(state.take_due(now), Vec::new());
+
+        let later = now + Duration::hours(1);
+        let due = state.take_due(later);
+        assert_eq!(
+            due.iter().map(|job| job.id.as_str()).collect::<Vec<_>>(),
+            vec!["hourly"]
+        );
+        let hourly = state
+            .list()
+            .into_iter()
+            .find(|info| info.job.id == "hourly")
+            .expect("hourly");
+        assert_eq!(hourly.running, true);
+        assert_eq!(hourly.next_run.is_some_and(|next| next > later), true);
+
+        // Still running an hour later, so that occurrence is skipped.
+        assert_eq!(state.take_due(later + Duration::hours(1)), Vec::new());
+    }
+
+    #[test]
+    fn config_jobs_cannot_be_replaced_or_removed() {
+        let now = Utc::now();
+        let mut jobs = BTreeMap::new();
+        jobs.insert(
+            "nightly".to_string(),
+            JobEntry::new(job("nightly", "0 2 * * *"), true, now).expect("entry"),
+        );
+        let state = SchedulerState::new(jobs, None);
+        let replacement = JobEntry::new(job("nightly", "@hourly"), false, now).expect("entry");
+        assert_eq!(state.register(replacement).is_err(), true);
+        assert_eq!(state.remove("nightly").is_err(), true);
+        assert_eq!(state.remove("missing").expect("remove"), false);
+    }
+}
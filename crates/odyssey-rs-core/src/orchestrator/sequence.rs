@@ -0,0 +1,338 @@
+//! Per-session event sequence numbers and user attribution.
+//!
+//! Every sink the orchestrator emits through is wrapped by one shared
+//! [`EventSequencer`], which stamps `EventMsg::seq` and, for sessions whose
+//! running turn belongs to a user, `EventMsg::user_id`. Fanout sinks for run
+//! streams and debates are wrapped as well; events that already carry a number
+//! pass through untouched, so each event is numbered exactly once and every
+//! listener sees the same number. The sequencer also remembers when each
+//! session last emitted an event, which the stall watchdog reads.
+//!
+//! Events are queued per session in `seq` order and forwarded outside the
+//! session's lock, so a listener may emit again from within `emit`; the nested
+//! event is delivered after the one being handled.
+//!
+//! Numbers are reserved in the state store [`SEQ_RESERVATION`] at a time, and a
+//! session's counter starts after its last reservation, so numbering stays
+//! monotonic across restarts at the cost of a gap.
+
+use crate::state::StateStore;
+use log::warn;
+use odyssey_rs_protocol::{EventMsg, EventPayload, EventSink, SessionId};
+use parking_lot::{Mutex, MutexGuard};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Sequence numbers reserved in the state store at a time.
+const SEQ_RESERVATION: u64 = 1024;
+
+/// Hands out consecutive sequence numbers per session.
+#[derive(Clone, Default)]
+pub(crate) struct EventSequencer {
+    /// Counter and delivery queue of each session.
+    counters: Arc<Mutex<HashMap<SessionId, Arc<Mutex<SessionCounter>>>>>,
+    /// Store that persists reservations; `None` numbers events in memory only.
+    store: Option<Arc<dyn StateStore>>,
+    /// User whose turn is running in each session.
+    actors: Arc<Mutex<HashMap<SessionId, String>>>,
+    /// When each session last emitted an event other than `TurnStalled`.
+    activity: Arc<Mutex<HashMap<SessionId, Instant>>>,
+}
+
+/// Sequence numbers handed out for one session, and events waiting to be forwarded.
+#[derive(Default)]
+struct SessionCounter {
+    /// Whether `last` was seeded from the state store.
+    seeded: bool,
+    /// Last number handed out.
+    last: u64,
+    /// Highest number reserved in the state store.
+    reserved: u64,
+    /// Numbered events not yet forwarded, in `seq` order, with the sink each is for.
+    pending: VecDeque<(Arc<dyn EventSink>, EventMsg)>,
+    /// Set while an emitter is forwarding `pending`.
+    draining: bool,
+}
+
+/// Holds a session's counter while draining and clears `draining` when done, even by panic.
+struct Drain<'a> {
+    counter: MutexGuard<'a, SessionCounter>,
+}
+
+impl Drop for Drain<'_> {
+    fn drop(&mut self) {
+        self.counter.draining = false;
+    }
+}
+
+impl EventSequencer {
+    /// Sequencer whose counters continue from the reservations in `store`.
+    pub(crate) fn new(store: Option<Arc<dyn StateStore>>) -> Self {
+        Self {
+            store,
+            ..Self::default()
+        }
+    }
+
+    /// Attribute later events of `session_id` to `user_id`, or to nobody.
+    pub(crate) fn set_actor(&self, session_id: SessionId, user_id: Option<String>) {
+        let mut actors = self.actors.lock();
+        match user_id {
+            Some(user_id) => actors.insert(session_id, user_id),
+            None => actors.remove(&session_id),
+        };
+    }
+
+    /// When `session_id` last emitted an event, ignoring stall reports.
+    pub(crate) fn last_activity(&self, session_id: SessionId) -> Option<Instant> {
+        self.activity.lock().get(&session_id).copied()
+    }
+
+    /// Wrap `inner` so events reaching it are numbered.
+    pub(crate) fn wrap(&self, inner: Arc<dyn EventSink>) -> Arc<dyn EventSink> {
+        Arc::new(SequencedEventSink {
+            sequencer: self.clone(),
+            inner,
+        })
+    }
+
+    fn counter(&self, session_id: SessionId) -> Arc<Mutex<SessionCounter>> {
+        self.counters.lock().entry(session_id).or_default().clone()
+    }
+
+    /// Next number for `session_id`, reserving a further block when needed.
+    fn next_seq(&self, session_id: SessionId, counter: &mut SessionCounter) -> u64 {
+        let Some(store) = &self.store else {
+            counter.last += 1;
+            return counter.last;
+        };
+        if !counter.seeded {
+            counter.seeded = true;
+            match store.event_seq(session_id) {
+                Ok(reserved) => {
+                    counter.last = counter.last.max(reserved);
+                    counter.reserved = counter.last;
+                }
+                Err(err) => warn!(
+                    "failed to read event sequence (session_id={}): {}",
+                    session_id, err
+                ),
+            }
+        }
+        counter.last += 1;
+        if counter.last > counter.reserved {
+            let through = counter.last + SEQ_RESERVATION - 1;
+            if let Err(err) = store.reserve_event_seq(session_id, through) {
+                warn!(
+                    "failed to reserve event sequence (session_id={}): {}",
+                    session_id, err
+                );
+            }
+            // Not retried on failure, so a broken store is not written once per event.
+            counter.reserved = through;
+        }
+        counter.last
+    }
+}
+
+struct SequencedEventSink {
+    sequencer: EventSequencer,
+    inner: Arc<dyn EventSink>,
+}
+
+impl EventSink for SequencedEventSink {
+    fn emit(&self, mut event: EventMsg) {
+        if event.seq != 0 {
+            self.inner.emit(event);
+            return;
+        }
+        if !matches!(event.payload, EventPayload::TurnStalled { .. }) {
+            self.sequencer
+                .activity
+                .lock()
+                .insert(event.session_id, Instant::now());
+        }
+        if event.user_id.is_none() {
+            event.user_id = self.sequencer.actors.lock().get(&event.session_id).cloned();
+        }
+        let counter = self.sequencer.counter(event.session_id);
+        let mut counter = counter.lock();
+        event.seq = self.sequencer.next_seq(event.session_id, &mut counter);
+        counter.pending.push_back((self.inner.clone(), event));
+        // Whoever is already draining, possibly an outer `emit` on this thread, forwards it.
+        if counter.draining {
+            return;
+        }
+        counter.draining = true;
+        let mut drain = Drain { counter };
+        while let Some((inner, event)) = drain.counter.pending.pop_front() {
+            MutexGuard::unlocked(&mut drain.counter, || inner.emit(event));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventSequencer, SEQ_RESERVATION};
+    use crate::state::{JsonlStateStore, StateStore};
+    use chrono::Utc;
+    use odyssey_rs_protocol::{EventMsg, EventPayload, EventSink};
+    use parking_lot::Mutex;
+    use pretty_assertions::assert_eq;
+    use std::sync::{Arc, OnceLock};
+    use uuid::Uuid;
+
+    #[derive(Default)]
+    struct Recorder {
+        seen: Mutex<Vec<(Uuid, u64)>>,
+        users: Mutex<Vec<Option<String>>>,
+    }
+
+    impl EventSink for Recorder {
+        fn emit(&self, event: EventMsg) {
+            self.seen.lock().push((event.session_id, event.seq));
+            self.users.lock().push(event.user_id);
+        }
+    }
+
+    fn event(session_id: Uuid) -> EventMsg {
+        EventMsg {
+            id: Uuid::new_v4(),
+            session_id,
+            seq: 0,
+            user_id: None,
+            created_at: Utc::now(),
+            payload: EventPayload::TurnCancelled {
+                turn_id: Uuid::nil(),
+            },
+        }
+    }
+
+    #[test]
+    fn numbers_events_per_session_once_across_wrappers() {
+        let sequencer = EventSequencer::default();
+        let recorder = Arc::new(Recorder::default());
+        let primary = sequencer.wrap(recorder.clone());
+        // A fanout path wraps a sink that is already sequenced.
+        let fanout = sequencer.wrap(primary.clone());
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+
+        primary.emit(event(first));
+        fanout.emit(event(first));
+        primary.emit(event(second));
+        fanout.emit(event(first));
+
+        assert_eq!(
+            *recorder.seen.lock(),
+            vec![(first, 1), (first, 2), (second, 1), (first, 3)]
+        );
+    }
+
+    /// Sink that emits one more event through `sink` while handling the first.
+    #[derive(Default)]
+    struct Reentrant {
+        sink: OnceLock<Arc<dyn EventSink>>,
+        seen: Mutex<Vec<u64>>,
+    }
+
+    impl EventSink for Reentrant {
+        fn emit(&self, received: EventMsg) {
+            let first = self.seen.lock().is_empty();
+            self.seen.lock().push(received.seq);
+            if first && let Some(sink) = self.sink.get() {
+                sink.emit(event(received.session_id));
+            }
+        }
+    }
+
+    #[test]
+    fn listeners_may_emit_while_handling_an_event() {
+        let sequencer = EventSequencer::default();
+        let listener = Arc::new(Reentrant::default());
+        let sink = sequencer.wrap(listener.clone());
+        let _ = listener.sink.set(sink.clone());
+        let session_id = Uuid::new_v4();
+
+        sink.emit(event(session_id));
+        sink.emit(event(session_id));
+
+        assert_eq!(*listener.seen.lock(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn continues_numbering_after_a_restart() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let store: Arc<dyn StateStore> =
+            Arc::new(JsonlStateStore::new(temp.path()).expect("store"));
+        let session_id = Uuid::new_v4();
+        store
+            .record_session(session_id, "agent", None, Utc::now())
+            .expect("record session");
+
+        let recorder = Arc::new(Recorder::default());
+        let sink = EventSequencer::new(Some(store.clone())).wrap(recorder.clone());
+        sink.emit(event(session_id));
+        sink.emit(event(session_id));
+        let restarted = EventSequencer::new(Some(store)).wrap(recorder.clone());
+        restarted.emit(event(session_id));
+
+        assert_eq!(
+            *recorder.seen.lock(),
+            vec![
+                (session_id, 1),
+                (session_id, 2),
+                (session_id, SEQ_RESERVATION + 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes_events_to_the_session_actor() {
+        let sequencer = EventSequencer::default();
+        let recorder = Arc::new(Recorder::default());
+        let sink = sequencer.wrap(recorder.clone());
+        let session_id = Uuid::new_v4();
+
+        sink.emit(event(session_id));
+        sequencer.set_actor(session_id, Some("alice".to_string()));
+        sink.emit(event(session_id));
+        let mut explicit = event(session_id);
+        explicit.user_id = Some("bob".to_string());
+        sink.emit(explicit);
+        sink.emit(event(Uuid::new_v4()));
+        sequencer.set_actor(session_id, None);
+        sink.emit(event(session_id));
+
+        assert_eq!(
+            *recorder.users.lock(),
+            vec![
+                None,
+                Some("alice".to_string()),
+                Some("bob".to_string()),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn tracks_activity_but_not_stall_reports() {
+        let sequencer = EventSequencer::default();
+        let sink = sequencer.wrap(Arc::new(Recorder::default()));
+        let session_id = Uuid::new_v4();
+        assert_eq!(sequencer.last_activity(session_id), None);
+
+        let mut stalled = event(session_id);
+        stalled.payload = EventPayload::TurnStalled {
+            turn_id: Uuid::nil(),
+            idle_secs: 1,
+        };
+        sink.emit(stalled);
+        assert_eq!(sequencer.last_activity(session_id), None);
+
+        sink.emit(event(session_id));
+        assert_eq!(sequencer.last_activity(session_id).is_some(), true);
+    }
+}
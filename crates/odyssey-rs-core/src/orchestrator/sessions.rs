@@ -1,12 +1,17 @@
 //! In-memory session store with optional persistence via StateStore.
 
 use crate::error::OdysseyCoreError;
-use crate::state::{MessageRecord, StateStore};
-use crate::types::{Message, Session, SessionId, SessionSummary};
+use crate::state::{MessageRecord, StateStore, page_bounds};
+use crate::types::{Message, Role, Session, SessionId, SessionPage, SessionSummary};
 use log::{debug, info};
-use parking_lot::RwLock;
+use odyssey_rs_config::SessionBusyPolicy;
+use odyssey_rs_protocol::{TurnContextOverride, TurnId};
+use odyssey_rs_tools::{FileVersions, SessionCwd};
+use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tokio::sync::OwnedMutexGuard;
 use uuid::Uuid;
 
 /// Session storage facade used by orchestrator and subagents.
@@ -16,6 +21,16 @@ pub(crate) struct SessionStore {
     sessions: Arc<RwLock<HashMap<SessionId, Session>>>,
     /// Optional persistent store for sessions.
     state_store: Option<Arc<dyn StateStore>>,
+    /// Turn context overrides applied to every turn in a session.
+    context_overrides: Arc<RwLock<HashMap<SessionId, TurnContextOverride>>>,
+    /// Turn context overrides applied to the next turn in a session only.
+    next_turn_overrides: Arc<Mutex<HashMap<SessionId, TurnContextOverride>>>,
+    /// Per-session locks serializing turns within a session.
+    run_locks: Arc<Mutex<HashMap<SessionId, Arc<tokio::sync::Mutex<()>>>>>,
+    /// Effective working directory per session, carried across turns.
+    cwds: Arc<Mutex<HashMap<SessionId, SessionCwd>>>,
+    /// File hashes each session's agent last saw, used for edit conflict detection.
+    file_versions: Arc<Mutex<HashMap<SessionId, FileVersions>>>,
 }
 
 impl SessionStore {
@@ -24,6 +39,11 @@ impl SessionStore {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             state_store,
+            context_overrides: Arc::new(RwLock::new(HashMap::new())),
+            next_turn_overrides: Arc::new(Mutex::new(HashMap::new())),
+            run_locks: Arc::new(Mutex::new(HashMap::new())),
+            cwds: Arc::new(Mutex::new(HashMap::new())),
+            file_versions: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -37,22 +57,34 @@ impl SessionStore {
         self.state_store.clone()
     }
 
-    /// Create a new session and persist it if configured.
-    pub(crate) fn create_session(&self, agent_id: String) -> Result<SessionId, OdysseyCoreError> {
+    /// Create a new session owned by `user_id` (if any) and persist it if configured.
+    pub(crate) fn create_session(
+        &self,
+        agent_id: String,
+        user_id: Option<String>,
+    ) -> Result<SessionId, OdysseyCoreError> {
         let session = Session {
             id: Uuid::new_v4(),
             agent_id: agent_id.clone(),
+            user_id,
             messages: Vec::new(),
             created_at: chrono::Utc::now(),
         };
         info!(
-            "created session (session_id={}, agent_id={})",
-            session.id, agent_id
+            "created session (session_id={}, agent_id={}, user_id={})",
+            session.id,
+            agent_id,
+            session.user_id.as_deref().unwrap_or("-")
         );
 
         if let Some(store) = &self.state_store {
             store
-                .record_session(session.id, &session.agent_id, session.created_at)
+                .record_session(
+                    session.id,
+                    &session.agent_id,
+                    session.user_id.as_deref(),
+                    session.created_at,
+                )
                 .map_err(|err| OdysseyCoreError::State(err.to_string()))?;
         }
 
@@ -84,6 +116,50 @@ impl SessionStore {
         Err(OdysseyCoreError::UnknownSession(session_id))
     }
 
+    /// Load a window of a session's messages without caching the full transcript.
+    pub(crate) fn session_page(
+        &self,
+        session_id: SessionId,
+        cursor: Option<usize>,
+        limit: usize,
+    ) -> Result<SessionPage, OdysseyCoreError> {
+        if let Some(session) = self.sessions.read().get(&session_id) {
+            let total = session.messages.len();
+            let (start, end) = page_bounds(total, cursor, limit);
+            return Ok(SessionPage {
+                id: session.id,
+                agent_id: session.agent_id.clone(),
+                user_id: session.user_id.clone(),
+                messages: session.messages[start..end].to_vec(),
+                start,
+                total,
+                created_at: session.created_at,
+            });
+        }
+
+        if let Some(store) = &self.state_store
+            && let Some(record) = store
+                .load_session_page(session_id, cursor, limit)
+                .map_err(|err| OdysseyCoreError::State(err.to_string()))?
+        {
+            debug!(
+                "loaded session page from store (session_id={}, start={}, total={})",
+                session_id, record.start, record.total
+            );
+            return Ok(SessionPage::from(record));
+        }
+
+        Err(OdysseyCoreError::UnknownSession(session_id))
+    }
+
+    /// Return the user who created a cached session.
+    pub(crate) fn session_user(&self, session_id: SessionId) -> Option<String> {
+        self.sessions
+            .read()
+            .get(&session_id)
+            .and_then(|session| session.user_id.clone())
+    }
+
     /// List all session summaries, using persistence when configured.
     pub(crate) fn list_sessions(&self) -> Result<Vec<SessionSummary>, OdysseyCoreError> {
         if let Some(store) = &self.state_store {
@@ -100,6 +176,7 @@ impl SessionStore {
             .map(|session| SessionSummary {
                 id: session.id,
                 agent_id: session.agent_id.clone(),
+                user_id: session.user_id.clone(),
                 message_count: session.messages.len(),
                 created_at: session.created_at,
             })
@@ -112,6 +189,11 @@ impl SessionStore {
     pub(crate) fn delete_session(&self, session_id: SessionId) -> Result<bool, OdysseyCoreError> {
         info!("deleting session (session_id={})", session_id);
         let mut removed = self.sessions.write().remove(&session_id).is_some();
+        self.context_overrides.write().remove(&session_id);
+        self.next_turn_overrides.lock().remove(&session_id);
+        self.run_locks.lock().remove(&session_id);
+        self.cwds.lock().remove(&session_id);
+        self.file_versions.lock().remove(&session_id);
         if let Some(store) = &self.state_store {
             let deleted = store
                 .delete_session(session_id)
@@ -121,6 +203,104 @@ impl SessionStore {
         Ok(removed)
     }
 
+    /// Store a turn context override for a session, replacing any previous one.
+    pub(crate) fn set_context_override(
+        &self,
+        session_id: SessionId,
+        context: TurnContextOverride,
+    ) -> Result<(), OdysseyCoreError> {
+        self.resume_session(session_id)?;
+        debug!("setting turn context override (session_id={})", session_id);
+        self.context_overrides.write().insert(session_id, context);
+        Ok(())
+    }
+
+    /// Remove the turn context override for a session.
+    pub(crate) fn clear_context_override(&self, session_id: SessionId) -> bool {
+        self.context_overrides.write().remove(&session_id).is_some()
+    }
+
+    /// Store a turn context override for the next turn of a session only,
+    /// replacing any previous one.
+    pub(crate) fn set_next_turn_override(
+        &self,
+        session_id: SessionId,
+        context: TurnContextOverride,
+    ) -> Result<(), OdysseyCoreError> {
+        self.resume_session(session_id)?;
+        debug!(
+            "setting next turn context override (session_id={})",
+            session_id
+        );
+        self.next_turn_overrides.lock().insert(session_id, context);
+        Ok(())
+    }
+
+    /// Remove and return the next turn's context override for a session, if any.
+    pub(crate) fn take_next_turn_override(
+        &self,
+        session_id: SessionId,
+    ) -> Option<TurnContextOverride> {
+        self.next_turn_overrides.lock().remove(&session_id)
+    }
+
+    /// Return the turn context override for a session, if any.
+    pub(crate) fn context_override(&self, session_id: SessionId) -> Option<TurnContextOverride> {
+        self.context_overrides.read().get(&session_id).cloned()
+    }
+
+    /// Return the tracked working directory for a session, starting at `workspace_root`.
+    pub(crate) fn session_cwd(&self, session_id: SessionId, workspace_root: &Path) -> SessionCwd {
+        self.cwds
+            .lock()
+            .entry(session_id)
+            .or_insert_with(|| SessionCwd::new(workspace_root))
+            .clone()
+    }
+
+    /// Return the current working directory of a session, if it has run a turn.
+    pub(crate) fn current_cwd(&self, session_id: SessionId) -> Option<PathBuf> {
+        self.cwds.lock().get(&session_id).map(SessionCwd::get)
+    }
+
+    /// Return the file hashes tracked for a session.
+    pub(crate) fn file_versions(&self, session_id: SessionId) -> FileVersions {
+        self.file_versions
+            .lock()
+            .entry(session_id)
+            .or_default()
+            .clone()
+    }
+
+    /// Acquire the run lock for a session, queueing or rejecting when busy.
+    pub(crate) async fn acquire_run_lock(
+        &self,
+        session_id: SessionId,
+        policy: SessionBusyPolicy,
+    ) -> Result<OwnedMutexGuard<()>, OdysseyCoreError> {
+        let lock = self
+            .run_locks
+            .lock()
+            .entry(session_id)
+            .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(())))
+            .clone();
+        match policy {
+            SessionBusyPolicy::Queue => {
+                if let Ok(guard) = lock.clone().try_lock_owned() {
+                    return Ok(guard);
+                }
+                debug!(
+                    "queueing turn behind running turn (session_id={})",
+                    session_id
+                );
+                Ok(lock.lock_owned().await)
+            }
+            SessionBusyPolicy::Reject => lock
+                .try_lock_owned()
+                .map_err(|_| OdysseyCoreError::SessionBusy(session_id)),
+        }
+    }
+
     /// Append a message to a session and persist it if configured.
     pub(crate) fn append_message(
         &self,
@@ -144,6 +324,9 @@ impl SessionStore {
                 role: message.role.as_str().to_string(),
                 content: message.content.clone(),
                 created_at: message.created_at,
+                turn_id: message.turn_id,
+                superseded: message.superseded,
+                user_id: message.user_id.clone(),
             };
             store
                 .append_message(session_id, &record)
@@ -151,6 +334,84 @@ impl SessionStore {
         }
         Ok(())
     }
+
+    /// Keep the first `message_count` messages of a session and drop the rest.
+    pub(crate) fn truncate_messages(
+        &self,
+        session_id: SessionId,
+        message_count: usize,
+    ) -> Result<(), OdysseyCoreError> {
+        self.resume_session(session_id)?;
+        let mut sessions = self.sessions.write();
+        let session = sessions
+            .get_mut(&session_id)
+            .ok_or(OdysseyCoreError::UnknownSession(session_id))?;
+        debug!(
+            "truncating session (session_id={}, from={}, to={})",
+            session_id,
+            session.messages.len(),
+            message_count
+        );
+        session.messages.truncate(message_count);
+        if let Some(store) = &self.state_store {
+            store
+                .truncate_messages(session_id, message_count)
+                .map_err(|err| OdysseyCoreError::State(err.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Mark the assistant and tool messages of a turn as superseded.
+    ///
+    /// Returns how many messages were newly marked.
+    pub(crate) fn mark_superseded(
+        &self,
+        session_id: SessionId,
+        turn_id: TurnId,
+    ) -> Result<usize, OdysseyCoreError> {
+        self.resume_session(session_id)?;
+        let mut sessions = self.sessions.write();
+        let session = sessions
+            .get_mut(&session_id)
+            .ok_or(OdysseyCoreError::UnknownSession(session_id))?;
+        let mut marked = 0;
+        for message in &mut session.messages {
+            if message.turn_id == Some(turn_id) && message.role != Role::User && !message.superseded
+            {
+                message.superseded = true;
+                marked += 1;
+            }
+        }
+        debug!(
+            "marked turn superseded (session_id={}, turn_id={}, messages={})",
+            session_id, turn_id, marked
+        );
+        if let Some(store) = &self.state_store {
+            store
+                .mark_superseded(session_id, turn_id)
+                .map_err(|err| OdysseyCoreError::State(err.to_string()))?;
+        }
+        Ok(marked)
+    }
+
+    /// Copy a session's transcript into a new session for the same agent and user.
+    pub(crate) fn fork_session(
+        &self,
+        session_id: SessionId,
+    ) -> Result<SessionId, OdysseyCoreError> {
+        let source = self.resume_session(session_id)?;
+        let fork_id = self.create_session(source.agent_id, source.user_id)?;
+        for message in &source.messages {
+            self.append_message(fork_id, message)?;
+        }
+        info!(
+            "forked session (session_id={}, fork_id={}, messages={})",
+            session_id,
+            fork_id,
+            source.messages.len()
+        );
+        Ok(fork_id)
+    }
 }
 
 #[cfg(test)]
@@ -158,14 +419,60 @@ mod tests {
     use super::SessionStore;
     use crate::state::JsonlStateStore;
     use crate::types::{Message, Role, Session};
+    use odyssey_rs_config::SessionBusyPolicy;
+    use odyssey_rs_protocol::TurnContextOverride;
     use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
     use std::sync::Arc;
+    use std::time::Duration;
     use tempfile::tempdir;
 
+    #[test]
+    fn session_store_truncates_and_forks_sessions() {
+        let root = tempdir().expect("root");
+        let state = JsonlStateStore::new(root.path()).expect("state");
+        let store = SessionStore::new(Some(Arc::new(state)));
+        let session_id = store
+            .create_session("agent".to_string(), None)
+            .expect("create");
+        for content in ["one", "two", "three"] {
+            let message = Message {
+                role: Role::User,
+                content: content.to_string(),
+                created_at: chrono::Utc::now(),
+                turn_id: None,
+                superseded: false,
+                user_id: None,
+            };
+            store.append_message(session_id, &message).expect("append");
+        }
+
+        let fork_id = store.fork_session(session_id).expect("fork");
+        store.truncate_messages(session_id, 1).expect("truncate");
+
+        let reloaded = SessionStore::new(Some(Arc::new(
+            JsonlStateStore::new(root.path()).expect("state"),
+        )));
+        let session = reloaded.resume_session(session_id).expect("session");
+        assert_eq!(session.messages.len(), 1);
+        assert_eq!(session.messages[0].content, "one");
+        let fork = reloaded.resume_session(fork_id).expect("fork");
+        assert_eq!(fork.agent_id, "agent");
+        assert_eq!(
+            fork.messages
+                .iter()
+                .map(|message| message.content.as_str())
+                .collect::<Vec<_>>(),
+            vec!["one", "two", "three"]
+        );
+    }
+
     #[test]
     fn session_store_in_memory_lists_sessions() {
         let store = SessionStore::new(None);
-        let session_id = store.create_session("agent".to_string()).expect("create");
+        let session_id = store
+            .create_session("agent".to_string(), None)
+            .expect("create");
         let summaries = store.list_sessions().expect("list");
         assert_eq!(summaries.len(), 1);
         assert_eq!(summaries[0].id, session_id);
@@ -178,11 +485,16 @@ mod tests {
         let state = JsonlStateStore::new(root.path()).expect("state");
         let store = SessionStore::new(Some(Arc::new(state)));
 
-        let session_id = store.create_session("agent".to_string()).expect("create");
+        let session_id = store
+            .create_session("agent".to_string(), None)
+            .expect("create");
         let message = Message {
             role: Role::User,
             content: "hello".to_string(),
             created_at: chrono::Utc::now(),
+            turn_id: None,
+            superseded: false,
+            user_id: None,
         };
         store.append_message(session_id, &message).expect("append");
 
@@ -195,6 +507,7 @@ mod tests {
             Session {
                 id: session_id,
                 agent_id: "agent".to_string(),
+                user_id: None,
                 created_at: session.created_at,
                 messages: vec![message],
             }
@@ -211,4 +524,159 @@ mod tests {
             other => panic!("unexpected error: {other:?}"),
         }
     }
+
+    #[test]
+    fn session_store_tracks_context_overrides() {
+        let store = SessionStore::new(None);
+        let session_id = store
+            .create_session("agent".to_string(), None)
+            .expect("create");
+        store
+            .set_context_override(
+                session_id,
+                TurnContextOverride {
+                    simulation: Some(true),
+                    ..TurnContextOverride::default()
+                },
+            )
+            .expect("override");
+        let context = store.context_override(session_id).expect("stored override");
+        assert_eq!(context.simulation, Some(true));
+        store
+            .set_next_turn_override(
+                session_id,
+                TurnContextOverride {
+                    temperature: Some(0.2),
+                    ..TurnContextOverride::default()
+                },
+            )
+            .expect("next turn override");
+        let next_turn = store
+            .take_next_turn_override(session_id)
+            .expect("next turn override");
+        assert_eq!(next_turn.temperature, Some(0.2));
+        assert_eq!(store.take_next_turn_override(session_id).is_none(), true);
+
+        assert_eq!(store.delete_session(session_id).expect("delete"), true);
+        assert_eq!(store.context_override(session_id).is_none(), true);
+
+        let err = store
+            .set_context_override(session_id, TurnContextOverride::default())
+            .expect_err("unknown session");
+        match err {
+            crate::error::OdysseyCoreError::UnknownSession(id) => assert_eq!(id, session_id),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn session_store_tracks_cwd_until_deleted() {
+        let store = SessionStore::new(None);
+        let session_id = store
+            .create_session("agent".to_string(), None)
+            .expect("create");
+        let root = PathBuf::from("/workspace");
+        assert_eq!(store.current_cwd(session_id), None);
+
+        let cwd = store.session_cwd(session_id, &root);
+        cwd.set(root.join("src"));
+        let again = store.session_cwd(session_id, &root);
+        assert_eq!(again.get(), root.join("src"));
+        assert_eq!(store.current_cwd(session_id), Some(root.join("src")));
+
+        store.delete_session(session_id).expect("delete");
+        assert_eq!(store.current_cwd(session_id), None);
+    }
+
+    #[tokio::test]
+    async fn run_lock_rejects_or_queues_busy_sessions() {
+        let store = SessionStore::new(None);
+        let session_id = store
+            .create_session("agent".to_string(), None)
+            .expect("create");
+        let guard = store
+            .acquire_run_lock(session_id, SessionBusyPolicy::Reject)
+            .await
+            .expect("first lock");
+
+        let err = store
+            .acquire_run_lock(session_id, SessionBusyPolicy::Reject)
+            .await
+            .expect_err("busy");
+        match err {
+            crate::error::OdysseyCoreError::SessionBusy(id) => assert_eq!(id, session_id),
+            other => panic!("unexpected error: {other:?}"),
+        }
+
+        let other_session = store
+            .create_session("agent".to_string(), None)
+            .expect("create");
+        store
+            .acquire_run_lock(other_session, SessionBusyPolicy::Reject)
+            .await
+            .expect("other session is independent");
+
+        let queued_store = store.clone();
+        let queued = tokio::spawn(async move {
+            queued_store
+                .acquire_run_lock(session_id, SessionBusyPolicy::Queue)
+                .await
+                .map(|_| ())
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(queued.is_finished(), false);
+        drop(guard);
+        queued.await.expect("join").expect("queued lock");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn session_store_handles_parallel_appends() {
+        let root = tempdir().expect("root");
+        let state = JsonlStateStore::new(root.path()).expect("state");
+        let store = SessionStore::new(Some(Arc::new(state)));
+        let sessions = (0..4)
+            .map(|_| {
+                store
+                    .create_session("agent".to_string(), None)
+                    .expect("create")
+            })
+            .collect::<Vec<_>>();
+
+        let mut handles = Vec::new();
+        for worker in 0..32 {
+            let store = store.clone();
+            let session_id = sessions[worker % sessions.len()];
+            handles.push(tokio::spawn(async move {
+                let _guard = store
+                    .acquire_run_lock(session_id, SessionBusyPolicy::Queue)
+                    .await
+                    .expect("lock");
+                for turn in 0..8 {
+                    let message = Message {
+                        role: Role::User,
+                        content: format!("worker {worker} turn {turn}"),
+                        created_at: chrono::Utc::now(),
+                        turn_id: None,
+                        superseded: false,
+                        user_id: None,
+                    };
+                    store.append_message(session_id, &message).expect("append");
+                    tokio::task::yield_now().await;
+                }
+            }));
+        }
+        for handle in handles {
+            handle.await.expect("join");
+        }
+
+        let reloaded = SessionStore::new(Some(Arc::new(
+            JsonlStateStore::new(root.path()).expect("state"),
+        )));
+        for session_id in sessions {
+            let cached = store.resume_session(session_id).expect("cached");
+            assert_eq!(cached.messages.len(), 64);
+            let persisted = reloaded.resume_session(session_id).expect("persisted");
+            assert_eq!(persisted.messages, cached.messages);
+        }
+    }
 }
@@ -0,0 +1,189 @@
+//! In-flight turn tracking for graceful shutdown, run metrics, and health.
+
+use super::health::HealthError;
+use crate::types::SessionId;
+use chrono::Utc;
+use odyssey_rs_protocol::TurnId;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
+use tokio::sync::{Notify, watch};
+
+/// Outcome of `Orchestrator::shutdown`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    /// Turns that finished on their own before the timeout.
+    pub completed_turns: usize,
+    /// Turns aborted after the timeout, each reported with a `TurnCancelled` event.
+    pub aborted_turns: Vec<TurnId>,
+    /// Pooled sandbox handles shut down.
+    pub sandboxes_shut_down: usize,
+    /// Errors from flushing state and memory stores.
+    pub flush_errors: Vec<String>,
+}
+
+/// Snapshot of orchestrator activity returned by `Orchestrator::run_metrics`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunMetrics {
+    /// Turns currently executing.
+    pub running_turns: usize,
+    /// Turns waiting for a session run lock or a global turn slot.
+    pub queued_turns: usize,
+}
+
+/// Session and start time of a running turn.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TurnStart {
+    pub(crate) session_id: SessionId,
+    pub(crate) started: Instant,
+}
+
+/// Tracks running turns so shutdown can drain or abort them.
+pub(crate) struct TurnTracker {
+    accepting: AtomicBool,
+    running: Mutex<HashMap<TurnId, TurnStart>>,
+    queued: AtomicUsize,
+    idle: Notify,
+    abort: watch::Sender<bool>,
+    last_error: Mutex<Option<HealthError>>,
+}
+
+impl Default for TurnTracker {
+    fn default() -> Self {
+        Self {
+            accepting: AtomicBool::new(true),
+            running: Mutex::new(HashMap::new()),
+            queued: AtomicUsize::new(0),
+            idle: Notify::new(),
+            abort: watch::Sender::new(false),
+            last_error: Mutex::new(None),
+        }
+    }
+}
+
+impl TurnTracker {
+    /// Return true until shutdown starts.
+    pub(crate) fn is_accepting(&self) -> bool {
+        self.accepting.load(Ordering::SeqCst)
+    }
+
+    /// Reject new turns; returns false when shutdown already started.
+    pub(crate) fn stop_accepting(&self) -> bool {
+        self.accepting.swap(false, Ordering::SeqCst)
+    }
+
+    /// Register a running turn until the returned guard drops.
+    pub(crate) fn register(
+        self: &Arc<Self>,
+        turn_id: TurnId,
+        session_id: SessionId,
+    ) -> RunningTurn {
+        self.running.lock().insert(
+            turn_id,
+            TurnStart {
+                session_id,
+                started: Instant::now(),
+            },
+        );
+        RunningTurn {
+            tracker: self.clone(),
+            turn_id,
+        }
+    }
+
+    /// Ids of the turns still running.
+    pub(crate) fn running(&self) -> Vec<TurnId> {
+        self.running.lock().keys().copied().collect()
+    }
+
+    /// Running turns with their session and start time.
+    pub(crate) fn running_turns(&self) -> Vec<(TurnId, TurnStart)> {
+        self.running
+            .lock()
+            .iter()
+            .map(|(turn_id, start)| (*turn_id, *start))
+            .collect()
+    }
+
+    /// Remember the latest turn failure for health reports.
+    pub(crate) fn record_error(&self, session_id: SessionId, turn_id: TurnId, message: String) {
+        *self.last_error.lock() = Some(HealthError {
+            session_id,
+            turn_id,
+            message,
+            at: Utc::now(),
+        });
+    }
+
+    /// Latest turn failure, if any.
+    pub(crate) fn last_error(&self) -> Option<HealthError> {
+        self.last_error.lock().clone()
+    }
+
+    /// Count a turn as queued until the returned guard drops.
+    pub(crate) fn enqueue(&self) -> QueuedTurn<'_> {
+        self.queued.fetch_add(1, Ordering::SeqCst);
+        QueuedTurn { tracker: self }
+    }
+
+    /// Current running and queued turn counts.
+    pub(crate) fn metrics(&self) -> RunMetrics {
+        RunMetrics {
+            running_turns: self.running.lock().len(),
+            queued_turns: self.queued.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Wait until no turns are running.
+    pub(crate) async fn wait_idle(&self) {
+        loop {
+            let notified = self.idle.notified();
+            if self.running.lock().is_empty() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Signal every running turn to stop.
+    pub(crate) fn abort_all(&self) {
+        self.abort.send_replace(true);
+    }
+
+    /// Resolve once `abort_all` has been called.
+    pub(crate) async fn aborted(&self) {
+        let mut receiver = self.abort.subscribe();
+        // The sender lives as long as the tracker, so this only returns once aborted.
+        let _ = receiver.wait_for(|aborted| *aborted).await;
+    }
+}
+
+/// Registration of a running turn; dropping it marks the turn finished.
+pub(crate) struct RunningTurn {
+    tracker: Arc<TurnTracker>,
+    turn_id: TurnId,
+}
+
+impl Drop for RunningTurn {
+    fn drop(&mut self) {
+        let mut running = self.tracker.running.lock();
+        running.remove(&self.turn_id);
+        if running.is_empty() {
+            self.tracker.idle.notify_waiters();
+        }
+    }
+}
+
+/// A turn waiting to start; dropping it removes the turn from the queue.
+pub(crate) struct QueuedTurn<'a> {
+    tracker: &'a TurnTracker,
+}
+
+impl Drop for QueuedTurn<'_> {
+    fn drop(&mut self) {
+        self.tracker.queued.fetch_sub(1, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,108 @@
+//! Streaming throughput reported as `StreamStats` events.
+//!
+//! Deltas arrive far more often than anyone can read a number, so stats are
+//! emitted at most once per [`STREAM_STATS_INTERVAL`] plus once when the
+//! stream ends. Tokens are estimated from the streamed text.
+
+use crate::model_catalog::estimate_tokens;
+use odyssey_rs_protocol::StreamStats;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two `StreamStats` events of one turn.
+pub(crate) const STREAM_STATS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Tracks streamed output of one turn.
+#[derive(Debug)]
+pub(crate) struct StreamMeter {
+    started: Instant,
+    first_token: Option<Instant>,
+    tokens: u64,
+    last_emit: Option<Instant>,
+}
+
+impl StreamMeter {
+    /// Start measuring a turn that began at `started`.
+    pub(crate) fn new(started: Instant) -> Self {
+        Self {
+            started,
+            first_token: None,
+            tokens: 0,
+            last_emit: None,
+        }
+    }
+
+    /// Record a delta received at `now`; returns stats when an update is due.
+    pub(crate) fn record(&mut self, delta: &str, now: Instant) -> Option<StreamStats> {
+        if delta.is_empty() {
+            return None;
+        }
+        let first_token = *self.first_token.get_or_insert(now);
+        self.tokens += estimate_tokens(delta) as u64;
+        let due = self
+            .last_emit
+            .is_none_or(|last| now.duration_since(last) >= STREAM_STATS_INTERVAL);
+        // The first delta alone gives no rate, so wait for a full interval.
+        if !due || now.duration_since(first_token) < STREAM_STATS_INTERVAL {
+            return None;
+        }
+        self.last_emit = Some(now);
+        Some(self.stats(now))
+    }
+
+    /// Final stats at `now`, or `None` when nothing was streamed.
+    pub(crate) fn finish(&self, now: Instant) -> Option<StreamStats> {
+        self.first_token.map(|_| self.stats(now))
+    }
+
+    fn stats(&self, now: Instant) -> StreamStats {
+        let first_token = self.first_token.unwrap_or(now);
+        let streaming = now.duration_since(first_token).as_secs_f64();
+        let tokens_per_sec = if streaming > 0.0 {
+            self.tokens as f64 / streaming
+        } else {
+            0.0
+        };
+        StreamStats {
+            tokens: self.tokens,
+            tokens_per_sec,
+            time_to_first_token_ms: first_token.duration_since(self.started).as_millis() as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamMeter;
+    use odyssey_rs_protocol::StreamStats;
+    use pretty_assertions::assert_eq;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn emits_throttled_stats_and_final_totals() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut meter = StreamMeter::new(start);
+        assert_eq!(meter.finish(at(100)), None);
+
+        assert_eq!(meter.record("", at(200)), None);
+        assert_eq!(meter.record("abcdabcd", at(400)), None);
+        assert_eq!(meter.record("abcd", at(600)), None);
+        assert_eq!(
+            meter.record("abcd", at(900)),
+            Some(StreamStats {
+                tokens: 4,
+                tokens_per_sec: 8.0,
+                time_to_first_token_ms: 400,
+            })
+        );
+        assert_eq!(meter.record("abcd", at(1000)), None);
+        assert_eq!(
+            meter.finish(at(1400)),
+            Some(StreamStats {
+                tokens: 5,
+                tokens_per_sec: 5.0,
+                time_to_first_token_ms: 400,
+            })
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Structured output: JSON Schema instructions, extraction, and validation.
+
+use crate::error::OdysseyCoreError;
+use jsonschema::Validator;
+use serde_json::Value;
+
+/// Default number of corrective retries after the first attempt.
+pub const DEFAULT_OUTPUT_SCHEMA_RETRIES: usize = 2;
+
+/// JSON Schema the final answer of a run must satisfy.
+#[derive(Debug, Clone)]
+pub struct OutputSchema {
+    /// JSON Schema for the answer.
+    pub schema: Value,
+    /// Retries with validation feedback after the first attempt fails.
+    pub max_retries: usize,
+}
+
+impl OutputSchema {
+    /// Create an output schema with the default retry budget.
+    pub fn new(schema: Value) -> Self {
+        Self {
+            schema,
+            max_retries: DEFAULT_OUTPUT_SCHEMA_RETRIES,
+        }
+    }
+}
+
+/// Compiled output schema used while a run is retried.
+pub(super) struct StructuredOutput {
+    schema: Value,
+    validator: Validator,
+}
+
+impl StructuredOutput {
+    /// Compile the schema, rejecting invalid schemas before any turn runs.
+    pub(super) fn compile(schema: &OutputSchema) -> Result<Self, OdysseyCoreError> {
+        let validator = jsonschema::validator_for(&schema.schema)
+            .map_err(|err| OdysseyCoreError::OutputSchema(format!("invalid schema: {err}")))?;
+        Ok(Self {
+            schema: schema.schema.clone(),
+            validator,
+        })
+    }
+
+    /// Append format instructions to the user input.
+    pub(super) fn instruct(&self, input: &str) -> String {
+        let schema = serde_json::to_string_pretty(&self.schema).unwrap_or_default();
+        format!(
+            "{input}\n\nRespond with only a JSON value that matches this JSON Schema, \
+without any other text:\n```json\n{schema}\n```"
+        )
+    }
+
+    /// Follow-up prompt describing why the previous answer was rejected.
+    pub(super) fn retry_prompt(errors: &str) -> String {
+        format!(
+            "Your previous answer did not match the required JSON Schema: {errors}\n\n\
+Reply again with only the corrected JSON value."
+        )
+    }
+
+    /// Extract the JSON value from a response and validate it.
+    ///
+    /// Returns a description of every problem when the response does not match.
+    pub(super) fn parse(&self, response: &str) -> Result<Value, String> {
+        let value = extract_json(response)
+            .ok_or_else(|| "response does not contain valid JSON".to_string())?;
+        let errors = self
+            .validator
+            .iter_errors(&value)
+            .map(|err| {
+                let path = err.instance_path.to_string();
+                if path.is_empty() {
+                    err.to_string()
+                } else {
+                    format!("{path}: {err}")
+                }
+            })
+            .collect::<Vec<_>>();
+        if errors.is_empty() {
+            Ok(value)
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+}
+
+/// Parse the whole response, a fenced code block, or the outermost object or array.
+fn extract_json(response: &str) -> Option<Value> {
+    let trimmed = response.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+    if let Some(start) = trimmed.find("```") {
+        let block = &trimmed[start + 3..];
+        let block = block.strip_prefix("json").unwrap_or(block);
+        if let Some(end) = block.find("```")
+            && let Ok(value) = serde_json::from_str(block[..end].trim())
+        {
+            return Some(value);
+        }
+    }
+    ['{', '[']
+        .into_iter()
+        .zip(['}', ']'])
+        .filter_map(|(open, close)| {
+            let start = trimmed.find(open)?;
+            let end = trimmed.rfind(close)?;
+            (start < end)
+                .then(|| serde_json::from_str(&trimmed[start..=end]).ok())
+                .flatten()
+        })
+        .next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutputSchema, StructuredOutput};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn output() -> StructuredOutput {
+        StructuredOutput::compile(&OutputSchema::new(json!({
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+        })))
+        .expect("schema")
+    }
+
+    #[test]
+    fn parse_extracts_json_from_fences_and_prose() {
+        let output = output();
+        assert_eq!(output.parse(r#"{"name": "a"}"#), Ok(json!({ "name": "a" })));
+        assert_eq!(
+            output.parse("```json\n{\"name\": \"b\"}\n```"),
+            Ok(json!({ "name": "b" }))
+        );
+        assert_eq!(
+            output.parse("Here you go: {\"name\": \"c\"} hope it helps"),
+            Ok(json!({ "name": "c" }))
+        );
+    }
+
+    #[test]
+    fn parse_reports_schema_violations() {
+        let output = output();
+        let err = output.parse(r#"{"name": 3}"#).expect_err("wrong type");
+        assert!(err.starts_with("/name: "));
+        assert!(output.parse("no json here").is_err());
+    }
+
+    #[test]
+    fn compile_rejects_invalid_schemas() {
+        assert!(StructuredOutput::compile(&OutputSchema::new(json!({ "type": 5 }))).is_err());
+    }
+}
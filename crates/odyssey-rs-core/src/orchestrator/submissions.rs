@@ -0,0 +1,429 @@
+//! Per-session submission queue for protocol envelopes.
+
+use crate::attachments::render_with_attachments;
+use crate::error::OdysseyCoreError;
+use crate::orchestrator::registry::{AgentRegistry, LLMRegistry};
+use crate::orchestrator::runtime::{self, ToolResultMode, TurnExecutor};
+use crate::orchestrator::sessions::SessionStore;
+use crate::types::SessionId;
+use log::{debug, info, warn};
+use odyssey_rs_protocol::{
+    EventMsg, EventPayload, EventSink, SubmissionEnvelope, SubmissionPayload, TurnId,
+};
+use parking_lot::Mutex;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::AbortHandle;
+use uuid::Uuid;
+
+/// Outcome of [`Orchestrator::submit`](crate::Orchestrator::submit).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionReceipt {
+    /// Submission that handles the request: the envelope's own id, or the original
+    /// submission's id when its idempotency key was seen before. User messages use it
+    /// as their turn id.
+    pub submission_id: Uuid,
+    /// True when an earlier submission had the same idempotency key; this one was not queued.
+    pub duplicate: bool,
+    /// Final response of the original turn, for duplicates of a turn that already completed.
+    pub response: Option<String>,
+}
+
+/// Routes submissions to one worker per session so they are processed in order.
+#[derive(Clone)]
+pub(crate) struct SubmissionQueue {
+    /// Shared state used by session workers.
+    inner: Arc<SubmissionWorkerState>,
+    /// Sender for each session worker.
+    workers: Arc<Mutex<HashMap<SessionId, mpsc::UnboundedSender<SubmissionEnvelope>>>>,
+}
+
+struct SubmissionWorkerState {
+    executor: Arc<TurnExecutor>,
+    agent_registry: AgentRegistry,
+    llm_registry: LLMRegistry,
+    session_store: SessionStore,
+    event_sink: Option<Arc<dyn EventSink>>,
+    /// Abort handles for turns currently running.
+    running: Mutex<HashMap<TurnId, AbortHandle>>,
+    /// Turns cancelled before their submission was dequeued.
+    cancelled: Mutex<HashSet<TurnId>>,
+    /// Submissions sent to each session worker and not yet dequeued.
+    pending: Mutex<HashMap<SessionId, usize>>,
+    idempotency: IdempotencyCache,
+}
+
+/// Submissions remembered by session and idempotency key.
+struct IdempotencyCache {
+    /// How long keys are remembered; zero disables deduplication.
+    window: Duration,
+    entries: Mutex<HashMap<(SessionId, String), KeyedSubmission>>,
+}
+
+struct KeyedSubmission {
+    submission_id: Uuid,
+    recorded_at: Instant,
+    response: Option<String>,
+}
+
+impl SubmissionQueue {
+    /// Create a queue that runs turns through the shared executor.
+    pub(crate) fn new(
+        executor: Arc<TurnExecutor>,
+        agent_registry: AgentRegistry,
+        llm_registry: LLMRegistry,
+        session_store: SessionStore,
+        event_sink: Option<Arc<dyn EventSink>>,
+        idempotency_window: Duration,
+    ) -> Self {
+        Self {
+            inner: Arc::new(SubmissionWorkerState {
+                executor,
+                agent_registry,
+                llm_registry,
+                session_store,
+                event_sink,
+                running: Mutex::new(HashMap::new()),
+                cancelled: Mutex::new(HashSet::new()),
+                pending: Mutex::new(HashMap::new()),
+                idempotency: IdempotencyCache {
+                    window: idempotency_window,
+                    entries: Mutex::new(HashMap::new()),
+                },
+            }),
+            workers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Enqueue a submission for its session.
+    ///
+    /// Cancellations take effect immediately; everything else is processed in order.
+    /// A submission whose idempotency key was seen within the window is not queued.
+    pub(crate) fn submit(
+        &self,
+        envelope: SubmissionEnvelope,
+    ) -> Result<SubmissionReceipt, OdysseyCoreError> {
+        let session_id = envelope.session_id;
+        self.inner.session_store.resume_session(session_id)?;
+        debug!(
+            "submission received (session_id={}, submission_id={})",
+            session_id, envelope.id
+        );
+        let receipt = SubmissionReceipt {
+            submission_id: envelope.id,
+            duplicate: false,
+            response: None,
+        };
+        if let SubmissionPayload::CancelTurn { turn_id } = &envelope.payload {
+            self.cancel(*turn_id);
+            return Ok(receipt);
+        }
+        if let Some(key) = &envelope.idempotency_key
+            && let Some(original) = self.inner.idempotency.check(session_id, key, envelope.id)
+        {
+            info!(
+                "skipping duplicate submission (session_id={}, submission_id={}, original_id={})",
+                session_id, envelope.id, original.submission_id
+            );
+            return Ok(original);
+        }
+        let runtime = match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => runtime,
+            Err(err) => {
+                self.inner.forget_key(&envelope);
+                return Err(OdysseyCoreError::Executor(err.to_string()));
+            }
+        };
+        let mut workers = self.workers.lock();
+        // Counted before sending so the worker never dequeues an uncounted submission.
+        *self.inner.pending.lock().entry(session_id).or_default() += 1;
+        let sender = workers.entry(session_id).or_insert_with(|| {
+            let (sender, receiver) = mpsc::unbounded_channel();
+            runtime.spawn(run_worker(self.inner.clone(), receiver));
+            sender
+        });
+        if let Err(err) = sender.send(envelope) {
+            // Worker exited; start a fresh one for the pending submission.
+            let (sender, receiver) = mpsc::unbounded_channel();
+            runtime.spawn(run_worker(self.inner.clone(), receiver));
+            if let Err(err) = sender.send(err.0) {
+                self.inner.dequeued(session_id);
+                self.inner.forget_key(&err.0);
+                return Err(OdysseyCoreError::Executor(err.to_string()));
+            }
+            workers.insert(session_id, sender);
+        }
+        Ok(receipt)
+    }
+
+    /// Submissions waiting for each session worker.
+    pub(crate) fn queue_depths(&self) -> HashMap<SessionId, usize> {
+        self.inner.pending.lock().clone()
+    }
+
+    /// Stop the worker for a session once its queued submissions drain.
+    pub(crate) fn remove_session(&self, session_id: SessionId) -> bool {
+        self.workers.lock().remove(&session_id).is_some()
+    }
+
+    fn cancel(&self, turn_id: TurnId) {
+        let mut running = self.inner.running.lock();
+        if let Some(handle) = running.remove(&turn_id) {
+            info!("cancelling running turn (turn_id={})", turn_id);
+            handle.abort();
+            return;
+        }
+        info!("cancelling queued turn (turn_id={})", turn_id);
+        self.inner.cancelled.lock().insert(turn_id);
+    }
+}
+
+/// Drain submissions for a single session in arrival order.
+async fn run_worker(
+    state: Arc<SubmissionWorkerState>,
+    mut receiver: mpsc::UnboundedReceiver<SubmissionEnvelope>,
+) {
+    while let Some(envelope) = receiver.recv().await {
+        let session_id = envelope.session_id;
+        state.dequeued(session_id);
+        match envelope.payload {
+            SubmissionPayload::UserMessage {
+                content,
+                attachments,
+            } => {
+                let input = render_with_attachments(&content, &attachments);
+                let outcome = run_user_message(
+                    &state,
+                    session_id,
+                    envelope.id,
+                    input,
+                    envelope.user_id.clone(),
+                )
+                .await;
+                match outcome {
+                    Ok(response) => {
+                        if let Some(key) = &envelope.idempotency_key {
+                            state.idempotency.complete(session_id, key, response);
+                        }
+                    }
+                    Err(payload) => {
+                        // Forgotten before the event so a retry prompted by it runs again.
+                        state.forget_key(&envelope);
+                        if let Some(payload) = payload {
+                            state.emit(session_id, payload);
+                        }
+                    }
+                }
+            }
+            SubmissionPayload::OverrideTurnContext { context } => {
+                if let Err(err) = state
+                    .session_store
+                    .set_context_override(session_id, context)
+                {
+                    warn!(
+                        "failed to apply context override (session_id={}): {}",
+                        session_id, err
+                    );
+                    state.emit(
+                        session_id,
+                        EventPayload::Error {
+                            turn_id: None,
+                            message: err.to_string(),
+                        },
+                    );
+                }
+            }
+            SubmissionPayload::CancelTurn { turn_id } => {
+                // Cancellations are handled at submit time; nothing is queued.
+                debug!("ignoring queued cancellation (turn_id={})", turn_id);
+            }
+        }
+    }
+    debug!("submission worker stopped");
+}
+
+/// Run a user message as a turn whose id matches the submission id.
+///
+/// Returns the final response when the turn completed, or else the event reporting why
+/// it did not, if one is still due.
+async fn run_user_message(
+    state: &Arc<SubmissionWorkerState>,
+    session_id: SessionId,
+    turn_id: TurnId,
+    input: String,
+    user_id: Option<String>,
+) -> Result<String, Option<EventPayload>> {
+    if state.cancelled.lock().remove(&turn_id) {
+        return Err(Some(EventPayload::TurnCancelled { turn_id }));
+    }
+    let params = state
+        .turn_params(session_id, turn_id, input, user_id)
+        .map_err(|err| {
+            Some(EventPayload::Error {
+                turn_id: Some(turn_id),
+                message: err.to_string(),
+            })
+        })?;
+    let permit = state
+        .executor
+        .acquire_turn(session_id)
+        .await
+        .map_err(|err| {
+            Some(EventPayload::Error {
+                turn_id: Some(turn_id),
+                message: err.to_string(),
+            })
+        })?;
+    let executor = state.executor.clone();
+    let handle = tokio::spawn(async move { executor.run_turn(params).await });
+    {
+        let mut running = state.running.lock();
+        // A cancel may have arrived while waiting for the permit.
+        if state.cancelled.lock().remove(&turn_id) {
+            handle.abort();
+        } else {
+            running.insert(turn_id, handle.abort_handle());
+        }
+    }
+    let outcome = handle.await;
+    state.running.lock().remove(&turn_id);
+    drop(permit);
+    match outcome {
+        Ok(Ok(result)) => Ok(result.response),
+        Ok(Err(err)) => {
+            // run_turn already emitted an error event for execution failures.
+            warn!(
+                "submitted turn failed (session_id={}, turn_id={}): {}",
+                session_id, turn_id, err
+            );
+            Err(None)
+        }
+        Err(err) if err.is_cancelled() => {
+            info!(
+                "submitted turn cancelled (session_id={}, turn_id={})",
+                session_id, turn_id
+            );
+            Err(Some(EventPayload::TurnCancelled { turn_id }))
+        }
+        Err(err) => Err(Some(EventPayload::Error {
+            turn_id: Some(turn_id),
+            message: err.to_string(),
+        })),
+    }
+}
+
+impl IdempotencyCache {
+    /// Receipt of an earlier submission with `key`, or `None` after remembering `submission_id`.
+    fn check(
+        &self,
+        session_id: SessionId,
+        key: &str,
+        submission_id: Uuid,
+    ) -> Option<SubmissionReceipt> {
+        if self.window.is_zero() {
+            return None;
+        }
+        let mut entries = self.entries.lock();
+        entries.retain(|_, entry| entry.recorded_at.elapsed() < self.window);
+        match entries.entry((session_id, key.to_string())) {
+            Entry::Occupied(entry) => {
+                let original = entry.get();
+                Some(SubmissionReceipt {
+                    submission_id: original.submission_id,
+                    duplicate: true,
+                    response: original.response.clone(),
+                })
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(KeyedSubmission {
+                    submission_id,
+                    recorded_at: Instant::now(),
+                    response: None,
+                });
+                None
+            }
+        }
+    }
+
+    /// Drop `key` when it still belongs to `submission_id`, so a retry runs again.
+    fn forget(&self, session_id: SessionId, key: &str, submission_id: Uuid) {
+        let mut entries = self.entries.lock();
+        if let Entry::Occupied(entry) = entries.entry((session_id, key.to_string()))
+            && entry.get().submission_id == submission_id
+        {
+            entry.remove();
+        }
+    }
+
+    /// Record the final response of the turn submitted with `key`.
+    fn complete(&self, session_id: SessionId, key: &str, response: String) {
+        if let Some(entry) = self.entries.lock().get_mut(&(session_id, key.to_string())) {
+            entry.response = Some(response);
+        }
+    }
+}
+
+impl SubmissionWorkerState {
+    /// Stop counting a submission that left the queue of `session_id`.
+    fn dequeued(&self, session_id: SessionId) {
+        let mut pending = self.pending.lock();
+        if let Some(count) = pending.get_mut(&session_id) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                pending.remove(&session_id);
+            }
+        }
+    }
+
+    /// Forget the idempotency key of a submission whose turn did not complete.
+    fn forget_key(&self, envelope: &SubmissionEnvelope) {
+        if let Some(key) = &envelope.idempotency_key {
+            self.idempotency
+                .forget(envelope.session_id, key, envelope.id);
+        }
+    }
+
+    fn turn_params(
+        &self,
+        session_id: SessionId,
+        turn_id: TurnId,
+        input: String,
+        user_id: Option<String>,
+    ) -> Result<runtime::TurnParams, OdysseyCoreError> {
+        let session = self.session_store.resume_session(session_id)?;
+        let entry = self.agent_registry.get_entry(&session.agent_id)?;
+        let llm_id = self.llm_registry.resolve_llm_id(None)?;
+        let llm = self.llm_registry.get_entry(&llm_id)?.provider;
+        Ok(runtime::TurnParams {
+            session_id,
+            agent_id: session.agent_id,
+            llm,
+            input,
+            entry,
+            include_subagent_spawner: true,
+            tool_result_mode: ToolResultMode::SessionAndMemory,
+            memory_mode: runtime::MemoryMode::AgentProvider,
+            turn_id: Some(turn_id),
+            event_sink: None,
+            stream: self.event_sink.is_some(),
+            record_input: true,
+            user_id: user_id.or(session.user_id),
+        })
+    }
+
+    fn emit(&self, session_id: SessionId, payload: EventPayload) {
+        if let Some(sink) = &self.event_sink {
+            sink.emit(EventMsg {
+                id: Uuid::new_v4(),
+                session_id,
+                seq: 0,
+                user_id: None,
+                created_at: chrono::Utc::now(),
+                payload,
+            });
+        }
+    }
+}
@@ -1,16 +1,19 @@
 //! Tool context construction for orchestrator and subagents.
 
 use crate::error::OdysseyCoreError;
+use crate::hooks::LifecycleHooks;
 use crate::permissions::PermissionEngine;
+use crate::secrets::secret_resolver;
 use log::debug;
-use odyssey_rs_protocol::{EventSink, SkillProvider};
+use odyssey_rs_protocol::{ArtifactProvider, EventSink, SkillProvider};
 use odyssey_rs_sandbox::{
     LocalSandboxProvider, SandboxContext, SandboxEnvPolicy, SandboxFilesystemPolicy, SandboxLimits,
-    SandboxNetworkPolicy, SandboxPolicy, SandboxProvider,
+    SandboxNetworkPolicy, SandboxPolicy, SandboxPool, SandboxProvider,
 };
 use odyssey_rs_tools::{
-    PermissionChecker, QuestionHandler, ToolContext, ToolOutputPolicy, ToolResultHandler,
-    ToolSandbox, TurnServices,
+    ApproxTokenEstimator, FileVersions, PermissionChecker, QuestionHandler, SecretResolver,
+    SessionCwd, ToolCallHook, ToolContext, ToolOutputPolicy, ToolResultHandler, ToolSandbox,
+    ToolScheduler, TurnServices,
 };
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -23,20 +26,32 @@ pub(crate) struct ToolContextFactory {
     config: Arc<odyssey_rs_config::OdysseyConfig>,
     /// Optional sandbox provider for tool execution.
     sandbox_provider: Option<Arc<dyn SandboxProvider>>,
+    /// Optional pool reusing prepared sandbox handles across turns.
+    sandbox_pool: Option<Arc<SandboxPool>>,
     /// Permission engine for tool approvals.
     permission_engine: Arc<PermissionEngine>,
     /// Optional question handler for interactive prompts.
     question_handler: Arc<RwLock<Option<Arc<dyn QuestionHandler>>>>,
     /// Optional skill store for skill metadata.
     skill_store: Option<Arc<dyn SkillProvider>>,
+    /// Optional shared artifact store.
+    artifact_store: Option<Arc<dyn ArtifactProvider>>,
     /// Optional tool event sink for streaming events.
     tool_event_sink: Option<Arc<dyn EventSink>>,
+    /// Lifecycle hooks run around tool calls.
+    hooks: LifecycleHooks,
+    /// Resolver for secret references, built from `config.secrets`.
+    secrets: Arc<SecretResolver>,
+    /// Scheduler shared with the tool router for exclusion groups.
+    scheduler: Arc<ToolScheduler>,
 }
 
 #[derive(Clone)]
 struct ScopedPermissionChecker {
     engine: Arc<PermissionEngine>,
     event_sink: Option<Arc<dyn EventSink>>,
+    /// Deny commands because read-only mode has no provider that enforces it.
+    deny_commands: bool,
 }
 
 #[async_trait::async_trait]
@@ -46,6 +61,20 @@ impl PermissionChecker for ScopedPermissionChecker {
         ctx: &odyssey_rs_tools::PermissionContext,
         request: odyssey_rs_protocol::PermissionRequest,
     ) -> Result<odyssey_rs_tools::PermissionOutcome, odyssey_rs_protocol::ToolError> {
+        if self.deny_commands
+            && matches!(
+                request,
+                odyssey_rs_protocol::PermissionRequest::Command { .. }
+            )
+        {
+            return Ok(odyssey_rs_tools::PermissionOutcome {
+                allowed: false,
+                reason: Some(
+                    "read-only mode requires a sandbox that enforces it to run commands"
+                        .to_string(),
+                ),
+            });
+        }
         self.engine
             .authorize_with_sink(ctx, request, self.event_sink.clone())
             .await
@@ -54,24 +83,45 @@ impl PermissionChecker for ScopedPermissionChecker {
 
 impl ToolContextFactory {
     /// Create a new factory with shared dependencies.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         config: Arc<odyssey_rs_config::OdysseyConfig>,
         sandbox_provider: Option<Arc<dyn SandboxProvider>>,
+        sandbox_pool: Option<Arc<SandboxPool>>,
         permission_engine: Arc<PermissionEngine>,
         question_handler: Arc<RwLock<Option<Arc<dyn QuestionHandler>>>>,
         skill_store: Option<Arc<dyn SkillProvider>>,
+        artifact_store: Option<Arc<dyn ArtifactProvider>>,
         tool_event_sink: Option<Arc<dyn EventSink>>,
+        hooks: LifecycleHooks,
+        scheduler: Arc<ToolScheduler>,
     ) -> Self {
+        let secrets = Arc::new(secret_resolver(&config.secrets));
         Self {
             config,
             sandbox_provider,
+            sandbox_pool,
             permission_engine,
             question_handler,
             skill_store,
+            artifact_store,
             tool_event_sink,
+            hooks,
+            secrets,
+            scheduler,
         }
     }
 
+    /// Permission engine shared by every turn.
+    pub(crate) fn permission_engine(&self) -> &Arc<PermissionEngine> {
+        &self.permission_engine
+    }
+
+    /// Sandbox provider used for tool execution, if any.
+    pub(crate) fn sandbox_provider(&self) -> Option<&dyn SandboxProvider> {
+        self.sandbox_provider.as_deref()
+    }
+
     /// Build a per-turn tool context with sandbox and tool result handling.
     #[allow(clippy::too_many_arguments)]
     pub(crate) async fn build_turn_context(
@@ -81,51 +131,109 @@ impl ToolContextFactory {
         turn_id: Uuid,
         sandbox_enabled: bool,
         sandbox_mode: odyssey_rs_protocol::SandboxMode,
+        simulation: bool,
+        model: Option<&odyssey_rs_config::ModelCatalogEntry>,
+        cwd: SessionCwd,
+        file_versions: FileVersions,
         tool_result_handler: Option<Arc<dyn ToolResultHandler>>,
         event_sink_override: Option<Arc<dyn EventSink>>,
     ) -> Result<ToolContext, OdysseyCoreError> {
         debug!(
-            "building turn tool context (session_id={}, agent_id={}, turn_id={}, sandbox_enabled={})",
-            session_id, agent_id, turn_id, sandbox_enabled
+            "building turn tool context (session_id={}, agent_id={}, turn_id={}, sandbox_enabled={}, simulation={})",
+            session_id, agent_id, turn_id, sandbox_enabled, simulation
         );
-        let cwd = std::env::current_dir().map_err(OdysseyCoreError::Io)?;
-        let output_policy = Some(output_policy_from_config(&self.config.tools.output_policy));
-        let sandbox_policy = sandbox_policy_from_config(&self.config.sandbox);
-        let provider = if sandbox_enabled {
-            self.sandbox_provider.clone().ok_or_else(|| {
-                OdysseyCoreError::Sandbox("sandbox enabled but no provider configured".to_string())
-            })?
+        let read_only = self.permission_engine.is_read_only();
+        let (sandbox_enabled, sandbox_mode) = if read_only {
+            debug!("read-only mode forces a read-only sandbox");
+            (
+                sandbox_enabled || self.sandbox_provider.is_some(),
+                odyssey_rs_protocol::SandboxMode::ReadOnly,
+            )
         } else {
-            Arc::new(LocalSandboxProvider::default())
+            (sandbox_enabled, sandbox_mode)
+        };
+        let workspace_root = std::env::current_dir().map_err(OdysseyCoreError::Io)?;
+        let output_policy = Some(output_policy_from_config(
+            &self.config.tools.output_policy,
+            model,
+        ));
+        let mut sandbox_policy = sandbox_policy_from_config(&self.config.sandbox);
+        if self.permission_engine.is_offline() {
+            debug!("offline mode denies sandbox network access");
+            sandbox_policy.network = SandboxNetworkPolicy::deny_all();
+        }
+        let sandbox_context = SandboxContext {
+            workspace_root: workspace_root.clone(),
+            mode: sandbox_mode,
+            policy: sandbox_policy,
+        };
+        let sandbox = match (&self.sandbox_pool, sandbox_enabled) {
+            (Some(pool), true) => {
+                let lease = pool
+                    .acquire(session_id, &sandbox_context)
+                    .await
+                    .map_err(|err| OdysseyCoreError::Sandbox(err.to_string()))?;
+                ToolSandbox {
+                    provider: pool.provider().clone(),
+                    handle: lease.handle().clone(),
+                    lease: Some(lease),
+                }
+            }
+            (None, true) | (_, false) => {
+                let provider = if sandbox_enabled {
+                    self.sandbox_provider.clone().ok_or_else(|| {
+                        OdysseyCoreError::Sandbox(
+                            "sandbox enabled but no provider configured".to_string(),
+                        )
+                    })?
+                } else {
+                    Arc::new(LocalSandboxProvider::default())
+                };
+                let handle = provider
+                    .prepare(&sandbox_context)
+                    .await
+                    .map_err(|err| OdysseyCoreError::Sandbox(err.to_string()))?;
+                ToolSandbox {
+                    provider,
+                    handle,
+                    lease: None,
+                }
+            }
         };
-        let handle = provider
-            .prepare(&SandboxContext {
-                workspace_root: cwd.clone(),
-                mode: sandbox_mode,
-                policy: sandbox_policy,
-            })
-            .await
-            .map_err(|err| OdysseyCoreError::Sandbox(err.to_string()))?;
 
+        let deny_commands = read_only && !sandbox.provider.enforces_read_only();
+        if deny_commands {
+            debug!("read-only mode denies commands; the sandbox provider does not enforce it");
+        }
         let event_sink = event_sink_override.or_else(|| self.tool_event_sink.clone());
         let permission_checker = ScopedPermissionChecker {
             engine: self.permission_engine.clone(),
             event_sink: event_sink.clone(),
+            deny_commands,
         };
         let services = Arc::new(TurnServices {
-            cwd: cwd.clone(),
-            workspace_root: cwd,
+            cwd,
+            file_versions,
+            workspace_root,
             output_policy,
-            sandbox: Some(ToolSandbox { provider, handle }),
+            sandbox: Some(sandbox),
             web: None,
             event_sink,
             skill_provider: self
                 .skill_store
                 .clone()
                 .map(|store| store as Arc<dyn SkillProvider>),
+            artifact_provider: self.artifact_store.clone(),
             question_handler: self.question_handler.read().clone(),
             permission_checker: Some(Arc::new(permission_checker)),
             tool_result_handler,
+            tool_hook: (!self.hooks.is_empty())
+                .then(|| Arc::new(self.hooks.clone()) as Arc<dyn ToolCallHook>),
+            simulation,
+            follow_symlinks: self.config.sandbox.filesystem.follow_symlinks,
+            secrets: Some(self.secrets.clone()),
+            web_api_key_ref: self.config.tools.web.api_key_ref.clone(),
+            scheduler: Some(self.scheduler.clone()),
         });
 
         Ok(ToolContext {
@@ -140,11 +248,25 @@ impl ToolContextFactory {
 }
 
 /// Translate tool output policy config into runtime policy.
+///
+/// Token budgets are estimated with the turn model's `chars_per_token` when
+/// its catalog entry sets one, and images are only returned inline when the
+/// model supports vision.
 fn output_policy_from_config(
     config: &odyssey_rs_config::ToolOutputPolicyConfig,
+    model: Option<&odyssey_rs_config::ModelCatalogEntry>,
 ) -> ToolOutputPolicy {
+    let estimator = match model.and_then(|model| model.chars_per_token) {
+        Some(chars_per_token) => ApproxTokenEstimator { chars_per_token },
+        None => ApproxTokenEstimator::default(),
+    };
     ToolOutputPolicy {
         max_string_bytes: config.max_string_bytes,
+        max_string_tokens: config.max_string_tokens,
+        token_estimator: Arc::new(estimator),
+        max_image_bytes: model
+            .filter(|model| model.supports_vision && config.max_image_bytes > 0)
+            .map(|_| config.max_image_bytes),
         max_array_len: config.max_array_len,
         max_object_entries: config.max_object_entries,
         redact_keys: config.redact_keys.clone(),
@@ -163,6 +285,7 @@ fn sandbox_policy_from_config(config: &odyssey_rs_config::SandboxConfig) -> Sand
             deny_write: config.filesystem.deny_write.clone(),
             allow_exec: config.filesystem.allow_exec.clone(),
             deny_exec: config.filesystem.deny_exec.clone(),
+            follow_symlinks: config.filesystem.follow_symlinks,
         },
         env: SandboxEnvPolicy {
             allow: config.env.allow.clone(),
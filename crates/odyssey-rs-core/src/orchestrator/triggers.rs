@@ -0,0 +1,287 @@
+//! Webhook triggers that start runs from inbound events.
+//!
+//! Triggers are configured under `triggers` and keyed by id. A transport (such
+//! as the server) authenticates the request, then hands the event name and JSON
+//! payload to [`TriggerRouter::dispatch`], which renders the trigger's prompt and
+//! runs it in a new session under the trigger's approval profile.
+
+use super::{Orchestrator, RunResult};
+use crate::error::OdysseyCoreError;
+use crate::types::SessionId;
+use log::{debug, info, warn};
+use odyssey_rs_config::{ApprovalPolicyProfile, TriggerConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+/// Inbound event delivered to a trigger.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TriggerEvent {
+    /// Event name, e.g. `issues.opened`.
+    pub event: Option<String>,
+    /// Event body.
+    pub payload: Value,
+}
+
+/// Configured trigger and its current load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerInfo {
+    pub id: String,
+    pub events: Vec<String>,
+    pub profile: ApprovalPolicyProfile,
+    pub max_concurrent: usize,
+    /// Runs of this trigger in progress.
+    pub running: usize,
+}
+
+/// Run started by a trigger.
+pub struct TriggeredRun {
+    pub trigger_id: String,
+    /// Session the run executes in.
+    pub session_id: SessionId,
+    handle: JoinHandle<Result<RunResult, OdysseyCoreError>>,
+}
+
+impl TriggeredRun {
+    /// Await completion of the run and return the final result.
+    pub async fn finish(self) -> Result<RunResult, OdysseyCoreError> {
+        self.handle
+            .await
+            .map_err(|err| OdysseyCoreError::Executor(err.to_string()))?
+    }
+}
+
+/// Routes inbound events to configured triggers.
+pub struct TriggerRouter {
+    orchestrator: Arc<Orchestrator>,
+    triggers: BTreeMap<String, Trigger>,
+}
+
+/// Trigger config with its concurrency slots.
+struct Trigger {
+    config: TriggerConfig,
+    slots: Arc<Semaphore>,
+}
+
+impl TriggerRouter {
+    /// Build a router for the triggers in the orchestrator's config.
+    pub fn new(orchestrator: Arc<Orchestrator>) -> Self {
+        let triggers = orchestrator
+            .config()
+            .triggers
+            .iter()
+            .map(|(id, config)| {
+                let trigger = Trigger {
+                    config: config.clone(),
+                    slots: Arc::new(Semaphore::new(config.max_concurrent.max(1))),
+                };
+                (id.clone(), trigger)
+            })
+            .collect::<BTreeMap<_, _>>();
+        info!("trigger router initialized (triggers={})", triggers.len());
+        Self {
+            orchestrator,
+            triggers,
+        }
+    }
+
+    /// Configured triggers sorted by id.
+    pub fn list(&self) -> Vec<TriggerInfo> {
+        self.triggers
+            .iter()
+            .map(|(id, trigger)| TriggerInfo {
+                id: id.clone(),
+                events: trigger.config.events.clone(),
+                profile: trigger.config.profile,
+                max_concurrent: trigger.config.max_concurrent,
+                running: trigger
+                    .config
+                    .max_concurrent
+                    .max(1)
+                    .saturating_sub(trigger.slots.available_permits()),
+            })
+            .collect()
+    }
+
+    /// Start a run for an event; returns `None` when the trigger ignores the event.
+    ///
+    /// Fails with `UnknownTrigger` for an unconfigured id and `TriggerBusy` when the
+    /// trigger already runs `max_concurrent` events. Must be called from within a
+    /// tokio runtime.
+    pub fn dispatch(
+        &self,
+        trigger_id: &str,
+        event: TriggerEvent,
+    ) -> Result<Option<TriggeredRun>, OdysseyCoreError> {
+        let trigger = self
+            .triggers
+            .get(trigger_id)
+            .ok_or_else(|| OdysseyCoreError::UnknownTrigger(trigger_id.to_string()))?;
+        if !accepts_event(&trigger.config.events, event.event.as_deref()) {
+            debug!(
+                "trigger ignored event (trigger_id={trigger_id}, event={:?})",
+                event.event
+            );
+            return Ok(None);
+        }
+        let permit = trigger
+            .slots
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| OdysseyCoreError::TriggerBusy(trigger_id.to_string()))?;
+
+        let orchestrator = self.orchestrator.clone();
+        let agent_id = orchestrator
+            .agent_registry
+            .resolve_agent_id(trigger.config.agent.as_deref())?;
+        let llm_id = orchestrator
+            .llm_registry
+            .resolve_llm_id(trigger.config.llm.as_deref())?;
+        let prompt = render_prompt(&trigger.config.prompt, &event);
+        let session_id = orchestrator.create_session(Some(agent_id.clone()))?;
+        orchestrator
+            .permission_engine
+            .set_session_profile(session_id, Some(trigger.config.profile));
+        info!(
+            "trigger fired (trigger_id={trigger_id}, event={:?}, session_id={session_id}, profile={})",
+            event.event, trigger.config.profile
+        );
+
+        let id = trigger_id.to_string();
+        let handle = tokio::spawn(async move {
+            let _permit = permit;
+            let result = orchestrator
+                .run_in_session(session_id, &agent_id, &llm_id, prompt)
+                .await;
+            orchestrator
+                .permission_engine
+                .set_session_profile(session_id, None);
+            if let Err(err) = &result {
+                warn!("triggered run failed (trigger_id={id}, error={err})");
+            }
+            result
+        });
+        Ok(Some(TriggeredRun {
+            trigger_id: trigger_id.to_string(),
+            session_id,
+            handle,
+        }))
+    }
+}
+
+/// Return true when an event passes the trigger's event filter.
+///
+/// An entry matches the same event name, and an entry without a `.` also
+/// matches its sub-events (`issues` matches `issues.opened`).
+fn accepts_event(filters: &[String], event: Option<&str>) -> bool {
+    if filters.is_empty() {
+        return true;
+    }
+    let Some(event) = event else {
+        return false;
+    };
+    filters.iter().any(|filter| {
+        event == filter
+            || (!filter.contains('.')
+                && event
+                    .strip_prefix(filter.as_str())
+                    .is_some_and(|rest| rest.starts_with('.')))
+    })
+}
+
+/// Fill `{{event}}`, `{{payload}}`, and `{{payload.field.path}}` placeholders.
+///
+/// Missing payload fields render as empty text; unknown placeholders are kept.
+fn render_prompt(template: &str, event: &TriggerEvent) -> String {
+    let mut prompt = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        prompt.push_str(&rest[..start]);
+        let inner = &rest[start + 2..];
+        let Some(end) = inner.find("}}") else {
+            prompt.push_str(&rest[start..]);
+            return prompt;
+        };
+        match placeholder_value(inner[..end].trim(), event) {
+            Some(value) => prompt.push_str(&value),
+            None => prompt.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &inner[end + 2..];
+    }
+    prompt.push_str(rest);
+    prompt
+}
+
+fn placeholder_value(key: &str, event: &TriggerEvent) -> Option<String> {
+    match key {
+        "event" => Some(event.event.clone().unwrap_or_default()),
+        "payload" => Some(serde_json::to_string_pretty(&event.payload).unwrap_or_default()),
+        _ => {
+            let path = key.strip_prefix("payload.")?;
+            let value = path
+                .split('.')
+                .try_fold(&event.payload, |value, segment| match value {
+                    Value::Array(items) => {
+                        segment.parse::<usize>().ok().and_then(|idx| items.get(idx))
+                    }
+                    _ => value.get(segment),
+                });
+            Some(match value {
+                Some(Value::String(text)) => text.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(other) => other.to_string(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TriggerEvent, accepts_event, render_prompt};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn renders_payload_placeholders() {
+        let event = TriggerEvent {
+            event: Some("issues.opened".to_string()),
+            payload: json!({
+                "issue": { "title": "Crash on start", "number": 42, "labels": [{ "name": "bug" }] },
+            }),
+        };
+        assert_eq!(
+            render_prompt(
+                "{{event}} #{{payload.issue.number}}: {{ payload.issue.title }} \
+                 [{{payload.issue.labels.0.name}}]{{payload.issue.body}} {{other}}",
+                &event
+            ),
+            "issues.opened #42: Crash on start [bug] {{other}}"
+        );
+        assert_eq!(
+            render_prompt("Payload:\n{{payload}}", &event),
+            format!(
+                "Payload:\n{}",
+                serde_json::to_string_pretty(&event.payload).expect("json")
+            )
+        );
+        assert_eq!(
+            render_prompt("unclosed {{event", &event),
+            "unclosed {{event"
+        );
+    }
+
+    #[test]
+    fn filters_events() {
+        let filters = vec!["issues".to_string(), "pull_request.opened".to_string()];
+        assert_eq!(accepts_event(&[], None), true);
+        assert_eq!(accepts_event(&filters, Some("issues")), true);
+        assert_eq!(accepts_event(&filters, Some("issues.opened")), true);
+        assert_eq!(accepts_event(&filters, Some("issues_comment")), false);
+        assert_eq!(accepts_event(&filters, Some("pull_request.opened")), true);
+        assert_eq!(accepts_event(&filters, Some("pull_request.closed")), false);
+        assert_eq!(accepts_event(&filters, None), false);
+    }
+}
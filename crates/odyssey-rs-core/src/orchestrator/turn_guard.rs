@@ -0,0 +1,310 @@
+//! Stop conditions enforced around the AutoAgents ReAct loop.
+//!
+//! Each LLM call counts as one ReAct iteration. Repeating the same tool call
+//! with identical arguments too many times is treated as a loop. Once a limit
+//! trips, further LLM and tool calls fail so the executor ends the turn.
+
+use async_trait::async_trait;
+use autoagents_core::tool::{ToolCallError, ToolRuntime, ToolT};
+use autoagents_llm::LLMProvider;
+use autoagents_llm::chat::{
+    ChatMessage, ChatProvider, ChatResponse, StreamChunk, StreamResponse, StructuredOutputFormat,
+    Tool as LLMTool,
+};
+use autoagents_llm::completion::{CompletionProvider, CompletionRequest, CompletionResponse};
+use autoagents_llm::embedding::EmbeddingProvider;
+use autoagents_llm::error::LLMError;
+use autoagents_llm::models::ModelsProvider;
+use futures_util::Stream;
+use log::warn;
+use odyssey_rs_config::OrchestratorConfig;
+use odyssey_rs_protocol::ToolError;
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+
+type ChunkStream = Pin<Box<dyn Stream<Item = Result<StreamChunk, LLMError>> + Send>>;
+
+/// Per-turn iteration and loop-detection state.
+#[derive(Debug)]
+pub(crate) struct TurnGuard {
+    max_iterations: Option<usize>,
+    max_repeated_tool_calls: Option<usize>,
+    state: Mutex<GuardState>,
+}
+
+#[derive(Debug, Default)]
+struct GuardState {
+    iterations: usize,
+    tool_calls: HashMap<(String, String), usize>,
+    tripped: Option<String>,
+}
+
+impl TurnGuard {
+    /// Build a guard from orchestrator config, or `None` when no limits are set.
+    pub(crate) fn from_config(config: &OrchestratorConfig) -> Option<Arc<Self>> {
+        let max_iterations = config.max_react_iterations.filter(|limit| *limit > 0);
+        let max_repeated_tool_calls = config.max_repeated_tool_calls.filter(|limit| *limit > 0);
+        if max_iterations.is_none() && max_repeated_tool_calls.is_none() {
+            return None;
+        }
+        Some(Arc::new(Self {
+            max_iterations,
+            max_repeated_tool_calls,
+            state: Mutex::new(GuardState::default()),
+        }))
+    }
+
+    /// Reason the guard stopped the turn, if it tripped.
+    pub(crate) fn tripped(&self) -> Option<String> {
+        self.state.lock().tripped.clone()
+    }
+
+    /// Record an LLM call, failing once the iteration limit is exceeded.
+    fn on_llm_call(&self) -> Result<(), String> {
+        let mut state = self.state.lock();
+        if let Some(reason) = &state.tripped {
+            return Err(reason.clone());
+        }
+        state.iterations += 1;
+        if let Some(limit) = self.max_iterations
+            && state.iterations > limit
+        {
+            let reason = format!("reached max_react_iterations ({limit})");
+            warn!("stopping turn: {reason}");
+            state.tripped = Some(reason.clone());
+            return Err(reason);
+        }
+        Ok(())
+    }
+
+    /// Record a tool call, failing once the same call repeats too often.
+    fn on_tool_call(&self, name: &str, args: &Value) -> Result<(), String> {
+        let mut state = self.state.lock();
+        if let Some(reason) = &state.tripped {
+            return Err(reason.clone());
+        }
+        let Some(limit) = self.max_repeated_tool_calls else {
+            return Ok(());
+        };
+        let count = state
+            .tool_calls
+            .entry((name.to_string(), args.to_string()))
+            .or_default();
+        *count += 1;
+        if *count > limit {
+            let reason =
+                format!("loop detected: {name} called {count} times with identical arguments");
+            warn!("stopping turn: {reason}");
+            state.tripped = Some(reason.clone());
+            return Err(reason);
+        }
+        Ok(())
+    }
+}
+
+/// LLM provider that counts ReAct iterations against a turn guard.
+struct GuardedLLM {
+    inner: Arc<dyn LLMProvider>,
+    guard: Arc<TurnGuard>,
+}
+
+impl GuardedLLM {
+    fn new(inner: Arc<dyn LLMProvider>, guard: Arc<TurnGuard>) -> Self {
+        Self { inner, guard }
+    }
+
+    fn check(&self) -> Result<(), LLMError> {
+        self.guard.on_llm_call().map_err(LLMError::ProviderError)
+    }
+}
+
+#[async_trait]
+impl ChatProvider for GuardedLLM {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.check()?;
+        self.inner
+            .chat_with_tools(messages, tools, json_schema)
+            .await
+    }
+
+    async fn chat_with_web_search(&self, input: String) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.check()?;
+        self.inner.chat_with_web_search(input).await
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, LLMError>> + Send>>, LLMError> {
+        self.check()?;
+        self.inner.chat_stream(messages, json_schema).await
+    }
+
+    async fn chat_stream_struct(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamResponse, LLMError>> + Send>>, LLMError>
+    {
+        self.check()?;
+        self.inner
+            .chat_stream_struct(messages, tools, json_schema)
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<ChunkStream, LLMError> {
+        self.check()?;
+        self.inner
+            .chat_stream_with_tools(messages, tools, json_schema)
+            .await
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for GuardedLLM {
+    async fn complete(
+        &self,
+        req: &CompletionRequest,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req, json_schema).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for GuardedLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
+
+#[async_trait]
+impl ModelsProvider for GuardedLLM {}
+
+impl LLMProvider for GuardedLLM {}
+
+/// Tool wrapper that reports calls to a turn guard before executing.
+struct GuardedTool {
+    inner: Arc<dyn ToolT>,
+    guard: Arc<TurnGuard>,
+}
+
+impl GuardedTool {
+    fn new(inner: Arc<dyn ToolT>, guard: Arc<TurnGuard>) -> Self {
+        Self { inner, guard }
+    }
+}
+
+impl fmt::Debug for GuardedTool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GuardedTool")
+            .field("name", &self.inner.name())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ToolRuntime for GuardedTool {
+    async fn execute(&self, args: Value) -> Result<Value, ToolCallError> {
+        if let Err(reason) = self.guard.on_tool_call(self.inner.name(), &args) {
+            return Err(ToolCallError::RuntimeError(Box::new(
+                ToolError::ExecutionFailed(reason),
+            )));
+        }
+        self.inner.execute(args).await
+    }
+}
+
+impl ToolT for GuardedTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn args_schema(&self) -> Value {
+        self.inner.args_schema()
+    }
+}
+
+/// Wrap an LLM and tools so they report to the guard.
+pub(crate) fn guard_turn(
+    guard: &Arc<TurnGuard>,
+    llm: Arc<dyn LLMProvider>,
+    tools: Vec<Arc<dyn ToolT>>,
+) -> (Arc<dyn LLMProvider>, Vec<Arc<dyn ToolT>>) {
+    let llm: Arc<dyn LLMProvider> = Arc::new(GuardedLLM::new(llm, guard.clone()));
+    let tools = tools
+        .into_iter()
+        .map(|tool| Arc::new(GuardedTool::new(tool, guard.clone())) as Arc<dyn ToolT>)
+        .collect();
+    (llm, tools)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TurnGuard;
+    use odyssey_rs_config::OrchestratorConfig;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn guard_is_disabled_without_limits() {
+        let config = OrchestratorConfig::default();
+        assert_eq!(TurnGuard::from_config(&config).is_none(), true);
+    }
+
+    #[test]
+    fn guard_trips_after_max_iterations() {
+        let config = OrchestratorConfig {
+            max_react_iterations: Some(2),
+            ..OrchestratorConfig::default()
+        };
+        let guard = TurnGuard::from_config(&config).expect("guard");
+        assert_eq!(guard.on_llm_call(), Ok(()));
+        assert_eq!(guard.on_llm_call(), Ok(()));
+        let reason = "reached max_react_iterations (2)".to_string();
+        assert_eq!(guard.on_llm_call(), Err(reason.clone()));
+        assert_eq!(guard.tripped(), Some(reason));
+    }
+
+    #[test]
+    fn guard_detects_repeated_tool_calls() {
+        let config = OrchestratorConfig {
+            max_repeated_tool_calls: Some(2),
+            ..OrchestratorConfig::default()
+        };
+        let guard = TurnGuard::from_config(&config).expect("guard");
+        let args = json!({ "path": "a.txt" });
+        assert_eq!(guard.on_tool_call("Read", &args), Ok(()));
+        assert_eq!(
+            guard.on_tool_call("Read", &json!({ "path": "b.txt" })),
+            Ok(())
+        );
+        assert_eq!(guard.on_tool_call("Read", &args), Ok(()));
+        let err = guard.on_tool_call("Read", &args).expect_err("loop");
+        assert_eq!(
+            err,
+            "loop detected: Read called 3 times with identical arguments"
+        );
+        assert_eq!(guard.on_llm_call(), Err(err));
+    }
+}
@@ -1,15 +1,27 @@
 //! Persistent storage for approval decisions.
+//!
+//! `AllowAlways` grants live in one JSONL file that several processes (a
+//! server and a TUI, or two workspaces) share. Writers hold the file's
+//! `.lock` sibling, reload the file, and replace it atomically; readers reload
+//! whenever the file changed since they last read it. Conflicts resolve as:
+//!
+//! - Grants are a union: a grant recorded by any process applies in all.
+//! - A grant already in the file is not written again.
+//! - The file is authoritative, so removing a line revokes the grant in every
+//!   process on its next lookup.
 
+use crate::atomic_file::{lock_exclusive, quarantine_lines, split_complete, write_atomic};
 use crate::error::OdysseyCoreError;
 use chrono::{DateTime, Utc};
 use directories::BaseDirs;
-use log::warn;
+use log::{debug, warn};
 use odyssey_rs_protocol::ApprovalDecision;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs::{File, OpenOptions, create_dir_all};
-use std::io::{BufRead, BufReader, Write};
+use std::fs::{self, create_dir_all};
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 const PERMISSION_FILENAME: &str = "permission.jsonl";
 
@@ -21,11 +33,20 @@ struct ApprovalRecord {
     created_at: DateTime<Utc>,
 }
 
+/// Size and modification time that tell whether the file changed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
 #[derive(Debug)]
 pub(crate) struct ApprovalStore {
     workspace_root: String,
     path: PathBuf,
     cache: HashMap<String, ApprovalDecision>,
+    /// Stamp of the file the cache was read from; `None` when it was missing.
+    stamp: Option<FileStamp>,
 }
 
 impl ApprovalStore {
@@ -41,6 +62,7 @@ impl ApprovalStore {
                     workspace_root,
                     path,
                     cache: HashMap::new(),
+                    stamp: None,
                 })
             }
         }
@@ -48,19 +70,23 @@ impl ApprovalStore {
 
     pub(crate) fn load(workspace_root: &Path, path: PathBuf) -> Result<Self, OdysseyCoreError> {
         let workspace_root = canonical_workspace_root(workspace_root)?;
-        let cache = load_cached_approvals(&path, &workspace_root)?;
+        let (cache, stamp) = load_cached_approvals(&path, &workspace_root)?;
         Ok(Self {
             workspace_root,
             path,
             cache,
+            stamp,
         })
     }
 
-    pub(crate) fn lookup(&self, key: &str) -> Option<ApprovalDecision> {
+    /// Look up a grant, first reloading the file if another process changed it.
+    pub(crate) fn lookup(&mut self, key: &str) -> Option<ApprovalDecision> {
+        self.refresh();
         self.cache.get(key).copied()
     }
 
     pub(crate) fn record_allow_always(&mut self, key: String) -> Result<(), OdysseyCoreError> {
+        self.refresh();
         if self.cache.contains_key(&key) {
             return Ok(());
         }
@@ -75,14 +101,40 @@ impl ApprovalStore {
         if let Some(parent) = self.path.parent() {
             create_dir_all(parent)?;
         }
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.path)?;
-        writeln!(file, "{serialized}")?;
-        self.cache.insert(key, ApprovalDecision::AllowAlways);
+        let _lock = lock_exclusive(&self.path)?;
+        // Reload under the lock so grants written since our last read are kept.
+        let (cache, mut contents) = read_approvals(&self.path, &self.workspace_root)?;
+        self.cache = cache;
+        if self.cache.contains_key(&key) {
+            debug!("approval already recorded by another process (key={key})");
+        } else {
+            // Rewrite rather than append so a crash never leaves a torn record.
+            contents.extend_from_slice(serialized.as_bytes());
+            contents.push(b'\n');
+            write_atomic(&self.path, &contents)?;
+            self.cache.insert(key, ApprovalDecision::AllowAlways);
+        }
+        self.stamp = file_stamp(&self.path);
         Ok(())
     }
+
+    /// Reload the cache when the file changed since it was last read.
+    fn refresh(&mut self) {
+        if file_stamp(&self.path) == self.stamp {
+            return;
+        }
+        match load_cached_approvals(&self.path, &self.workspace_root) {
+            Ok((cache, stamp)) => {
+                debug!("reloaded approval store (records={})", cache.len());
+                self.cache = cache;
+                self.stamp = stamp;
+            }
+            Err(err) => {
+                let path = self.path.display();
+                warn!("failed to reload approval store (path={path}): {err}");
+            }
+        }
+    }
 }
 
 fn canonical_workspace_root(root: &Path) -> Result<String, OdysseyCoreError> {
@@ -98,28 +150,51 @@ fn default_permission_path() -> Result<PathBuf, OdysseyCoreError> {
     Ok(cwd.join(".odyssey").join(PERMISSION_FILENAME))
 }
 
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        len: metadata.len(),
+        modified: metadata.modified().ok(),
+    })
+}
+
+/// Load this workspace's approvals under the store lock, with the stamp of
+/// the file they were read from.
 fn load_cached_approvals(
     path: &Path,
     workspace_root: &str,
-) -> Result<HashMap<String, ApprovalDecision>, OdysseyCoreError> {
-    let file = match File::open(path) {
-        Ok(file) => file,
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
-            return Ok(HashMap::new());
+) -> Result<(HashMap<String, ApprovalDecision>, Option<FileStamp>), OdysseyCoreError> {
+    if file_stamp(path).is_none() {
+        return Ok((HashMap::new(), None));
+    }
+    let _lock = lock_exclusive(path)?;
+    let (cache, _) = read_approvals(path, workspace_root)?;
+    Ok((cache, file_stamp(path)))
+}
+
+/// Read this workspace's approvals and the file's valid lines, quarantining
+/// lines that fail to parse. Callers hold the store lock.
+fn read_approvals(
+    path: &Path,
+    workspace_root: &str,
+) -> Result<(HashMap<String, ApprovalDecision>, Vec<u8>), OdysseyCoreError> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            return Ok((HashMap::new(), Vec::new()));
         }
         Err(err) => return Err(OdysseyCoreError::Io(err)),
     };
 
-    let reader = BufReader::new(file);
+    let (lines, tail) = split_complete(&contents);
     let mut cache = HashMap::new();
-    for line in reader.lines() {
-        let line = line.map_err(OdysseyCoreError::Io)?;
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        match serde_json::from_str::<ApprovalRecord>(trimmed) {
+    let mut kept = Vec::with_capacity(contents.len());
+    let mut corrupt = Vec::new();
+    for line in lines.chain(Some(tail).filter(|tail| !tail.trim_ascii().is_empty())) {
+        match serde_json::from_slice::<ApprovalRecord>(line.trim_ascii()) {
             Ok(record) => {
+                kept.extend_from_slice(line);
+                kept.push(b'\n');
                 if record.workspace_root != workspace_root {
                     continue;
                 }
@@ -129,10 +204,27 @@ fn load_cached_approvals(
             }
             Err(err) => {
                 warn!("invalid approval record ignored: {err}");
+                corrupt.push(line);
             }
         }
     }
-    Ok(cache)
+    if !corrupt.is_empty() {
+        match quarantine_lines(path, &corrupt).and_then(|quarantine| {
+            write_atomic(path, &kept)?;
+            Ok(quarantine)
+        }) {
+            Ok(quarantine) => {
+                let quarantine = quarantine.display();
+                let count = corrupt.len();
+                warn!("quarantined {count} corrupt approval records (path={quarantine})");
+            }
+            Err(err) => {
+                let path = path.display();
+                warn!("failed to quarantine corrupt approval records (path={path}): {err}");
+            }
+        }
+    }
+    Ok((cache, kept))
 }
 
 #[cfg(test)]
@@ -153,7 +245,7 @@ mod tests {
             .record_allow_always("tool:Read".to_string())
             .expect("record");
 
-        let store = ApprovalStore::load(workspace.path(), store_path).expect("store reload");
+        let mut store = ApprovalStore::load(workspace.path(), store_path).expect("store reload");
         assert_eq!(
             store.lookup("tool:Read"),
             Some(ApprovalDecision::AllowAlways)
@@ -175,7 +267,77 @@ mod tests {
         let serialized = serde_json::to_string(&record).expect("serialize");
         std::fs::write(&store_path, format!("not-json\n{serialized}\n")).expect("write file");
 
-        let store = ApprovalStore::load(workspace_b.path(), store_path).expect("store");
+        let mut store = ApprovalStore::load(workspace_b.path(), store_path).expect("store");
         assert_eq!(store.lookup("tool:Read"), None);
     }
+
+    #[test]
+    fn quarantines_corrupt_records_and_keeps_valid_ones() {
+        let workspace = tempdir().expect("workspace");
+        let file_dir = tempdir().expect("file_dir");
+        let store_path = file_dir.path().join("permission.jsonl");
+        let mut store = ApprovalStore::load(workspace.path(), store_path.clone()).expect("store");
+        store
+            .record_allow_always("tool:Read".to_string())
+            .expect("record");
+        let valid = std::fs::read_to_string(&store_path).expect("read store");
+        std::fs::write(&store_path, format!("{valid}\0\0garbage\n{{\"torn\"")).expect("corrupt");
+
+        let mut store = ApprovalStore::load(workspace.path(), store_path.clone()).expect("reload");
+        assert_eq!(
+            store.lookup("tool:Read"),
+            Some(ApprovalDecision::AllowAlways)
+        );
+        assert_eq!(std::fs::read_to_string(&store_path).expect("read"), valid);
+        assert_eq!(
+            std::fs::read_to_string(file_dir.path().join("permission.jsonl.corrupt"))
+                .expect("quarantine"),
+            "\0\0garbage\n{\"torn\"\n"
+        );
+
+        store
+            .record_allow_always("tool:Write".to_string())
+            .expect("record after quarantine");
+        let mut store = ApprovalStore::load(workspace.path(), store_path).expect("reload");
+        assert_eq!(
+            store.lookup("tool:Write"),
+            Some(ApprovalDecision::AllowAlways)
+        );
+    }
+
+    #[test]
+    fn grants_are_shared_between_stores_of_one_file() {
+        let workspace = tempdir().expect("workspace");
+        let file_dir = tempdir().expect("file_dir");
+        let store_path = file_dir.path().join("permission.jsonl");
+        let mut server = ApprovalStore::load(workspace.path(), store_path.clone()).expect("server");
+        let mut tui = ApprovalStore::load(workspace.path(), store_path.clone()).expect("tui");
+
+        server
+            .record_allow_always("tool:Read".to_string())
+            .expect("server grant");
+        assert_eq!(tui.lookup("tool:Read"), Some(ApprovalDecision::AllowAlways));
+
+        tui.record_allow_always("tool:Write".to_string())
+            .expect("tui grant");
+        server
+            .record_allow_always("tool:Write".to_string())
+            .expect("duplicate grant");
+        assert_eq!(
+            server.lookup("tool:Read"),
+            Some(ApprovalDecision::AllowAlways)
+        );
+        let contents = std::fs::read_to_string(&store_path).expect("read");
+        assert_eq!(contents.lines().count(), 2);
+
+        // The file is authoritative: removing a line revokes the grant everywhere.
+        let kept: String = contents
+            .lines()
+            .filter(|line| !line.contains("tool:Write"))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        std::fs::write(&store_path, kept).expect("revoke");
+        assert_eq!(server.lookup("tool:Write"), None);
+        assert_eq!(tui.lookup("tool:Write"), None);
+    }
 }
@@ -7,17 +7,23 @@ use async_trait::async_trait;
 use chrono::Utc;
 use globset::Glob;
 use log::{debug, info, warn};
-use odyssey_rs_config::{PermissionMode, PermissionRule, PermissionsConfig};
+use odyssey_rs_config::{
+    ApprovalPolicyProfile, ApprovalTimeoutDecision, GitPushAccess, PermissionMode, PermissionRule,
+    PermissionsConfig,
+};
 use odyssey_rs_protocol::EventSink;
 use odyssey_rs_protocol::ToolError;
 use odyssey_rs_protocol::{
-    ApprovalDecision, EventMsg, EventPayload, PathAccess, PermissionAction, PermissionRequest,
+    ApprovalDecision, ApprovalPolicy, EventMsg, EventPayload, PathAccess, PermissionAction,
+    PermissionRequest, ProposedToolCall, ToolRisk,
 };
 use odyssey_rs_tools::{PermissionChecker, PermissionContext, PermissionOutcome};
 use parking_lot::{Mutex, RwLock};
 use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tokio::sync::oneshot;
 use uuid::Uuid;
 
@@ -58,6 +64,9 @@ pub struct ApprovalRequest {
     pub action: PermissionAction,
     /// Original permission request.
     pub request: PermissionRequest,
+    /// Risk class of the tool behind the request, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk: Option<ToolRisk>,
 }
 
 /// Approval handler interface for interactive permission resolution.
@@ -74,6 +83,34 @@ struct PendingApproval {
     request: ApprovalRequest,
 }
 
+/// Removes a pending approval when its waiting future is dropped.
+///
+/// Resolved approvals are already gone from the pending map, so this only
+/// fires when the turn stops waiting (for example on cancellation).
+struct PendingApprovalGuard<'a> {
+    engine: &'a PermissionEngine,
+    ctx: &'a PermissionContext,
+    request_id: Uuid,
+    event_sink: Option<Arc<dyn EventSink>>,
+}
+
+impl Drop for PendingApprovalGuard<'_> {
+    fn drop(&mut self) {
+        if self
+            .engine
+            .pending
+            .lock()
+            .remove(&self.request_id)
+            .is_none()
+        {
+            return;
+        }
+        info!("approval expired (request_id={})", self.request_id);
+        self.engine
+            .emit_approval_expired(self.ctx, self.request_id, self.event_sink.take());
+    }
+}
+
 /// Compiled matcher for a permission rule.
 #[derive(Debug)]
 struct RuleMatcher {
@@ -83,6 +120,40 @@ struct RuleMatcher {
     path_raw: Option<String>,
     command: Option<Vec<String>>,
     access: Option<PathAccess>,
+    git_push: Option<GitPushAccess>,
+    /// Config file the rule came from, if known.
+    source: Option<String>,
+}
+
+impl RuleMatcher {
+    /// Human-readable rule description used in denial reasons.
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(tool) = &self.tool {
+            parts.push(format!("tool={tool}"));
+        }
+        if let Some(path) = &self.path_raw {
+            parts.push(format!("path={path}"));
+        }
+        if let Some(command) = &self.command {
+            parts.push(format!("command={}", command.join(" ")));
+        }
+        if let Some(access) = self.access {
+            parts.push(format!("access={access:?}").to_ascii_lowercase());
+        }
+        if let Some(git_push) = self.git_push {
+            parts.push(format!("git_push={git_push:?}").to_ascii_lowercase());
+        }
+        parts.join(", ")
+    }
+
+    /// Reason reported when this rule denies a request.
+    fn deny_reason(&self) -> String {
+        match &self.source {
+            Some(source) => format!("denied by rule ({}) from {source}", self.describe()),
+            None => "denied by rule".to_string(),
+        }
+    }
 }
 
 /// Permission engine implementing approval rules and hooks.
@@ -95,8 +166,41 @@ pub struct PermissionEngine {
     pending: Mutex<HashMap<Uuid, PendingApproval>>,
     approval_handler: RwLock<Option<Arc<dyn ApprovalHandler>>>,
     event_sink: RwLock<Option<Arc<dyn EventSink>>>,
+    /// Deny side-effecting requests regardless of rules and mode.
+    read_only: AtomicBool,
+    /// Deny network tools, network commands, and git pushes regardless of rules and mode.
+    offline: AtomicBool,
+    /// How long to wait for an approval before applying `timeout_decision`.
+    approval_timeout: Option<Duration>,
+    timeout_decision: ApprovalDecision,
+    /// Preset that denies risky requests and answers approvals without waiting.
+    profile: ApprovalPolicyProfile,
+    /// Per-session profile overrides, such as headless webhook runs.
+    session_profiles: RwLock<HashMap<Uuid, ApprovalPolicyProfile>>,
+    /// Approval policy of each session's current turn.
+    session_policies: RwLock<HashMap<Uuid, ApprovalPolicy>>,
+    /// Risk class of each registered tool.
+    tool_risks: RwLock<HashMap<String, ToolRisk>>,
 }
 
+/// Tools blocked outright while read-only mode is active.
+const READ_ONLY_BLOCKED_TOOLS: &[&str] = &[
+    "Write",
+    "Edit",
+    "NotebookEdit",
+    "GitCommit",
+    "Download",
+    "PublishArtifact",
+];
+/// Tools that reach the network, denied by non-interactive profiles and hidden offline.
+pub(crate) const NETWORK_TOOLS: &[&str] = &["WebFetch", "WebSearch", "HttpRequest", "Download"];
+/// Commands that reach the network, denied by non-interactive profiles.
+const NETWORK_COMMANDS: &[&str] = &[
+    "curl", "wget", "ssh", "scp", "sftp", "rsync", "nc", "ncat", "netcat", "telnet", "ftp",
+];
+/// Git subcommands that talk to remotes.
+const NETWORK_GIT_SUBCOMMANDS: &[&str] = &["clone", "fetch", "pull", "push", "ls-remote"];
+
 impl PermissionEngine {
     /// Create a new permission engine from config.
     pub fn new(config: PermissionsConfig) -> Result<Self, OdysseyCoreError> {
@@ -119,9 +223,42 @@ impl PermissionEngine {
             pending: Mutex::new(HashMap::new()),
             approval_handler: RwLock::new(None),
             event_sink: RwLock::new(None),
+            read_only: AtomicBool::new(false),
+            offline: AtomicBool::new(false),
+            approval_timeout: config.approval_timeout_secs.map(Duration::from_secs),
+            timeout_decision: match config.timeout_decision {
+                ApprovalTimeoutDecision::Deny => ApprovalDecision::Deny,
+                ApprovalTimeoutDecision::Allow => ApprovalDecision::AllowOnce,
+            },
+            profile: config.profile,
+            session_profiles: RwLock::new(HashMap::new()),
+            session_policies: RwLock::new(HashMap::new()),
+            tool_risks: RwLock::new(HashMap::new()),
         })
     }
 
+    /// Enable or disable read-only mode.
+    pub fn set_read_only(&self, read_only: bool) {
+        info!("permission read-only mode (enabled={read_only})");
+        self.read_only.store(read_only, Ordering::SeqCst);
+    }
+
+    /// Return true when read-only mode is active.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::SeqCst)
+    }
+
+    /// Enable or disable offline mode.
+    pub fn set_offline(&self, offline: bool) {
+        info!("permission offline mode (enabled={offline})");
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
+    /// Return true when offline mode is active.
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::SeqCst)
+    }
+
     /// Attach an event sink for permission events.
     pub fn set_event_sink(&self, sink: Option<Arc<dyn EventSink>>) {
         *self.event_sink.write() = sink;
@@ -154,6 +291,39 @@ impl PermissionEngine {
         }
     }
 
+    /// Apply an approval profile to one session instead of the configured profile.
+    pub fn set_session_profile(&self, session_id: Uuid, profile: Option<ApprovalPolicyProfile>) {
+        let mut session_profiles = self.session_profiles.write();
+        if let Some(profile) = profile {
+            session_profiles.insert(session_id, profile);
+        } else {
+            session_profiles.remove(&session_id);
+        }
+    }
+
+    /// Apply a turn's approval policy to one session.
+    ///
+    /// Under `on_request`, read-only tools run without a prompt in default mode.
+    pub fn set_session_approval_policy(&self, session_id: Uuid, policy: Option<ApprovalPolicy>) {
+        let mut session_policies = self.session_policies.write();
+        if let Some(policy) = policy {
+            session_policies.insert(session_id, policy);
+        } else {
+            session_policies.remove(&session_id);
+        }
+    }
+
+    /// Cache the risk class of each registered tool.
+    pub fn register_tool_risks(&self, risks: HashMap<String, ToolRisk>) {
+        debug!("registering tool risks (tools={})", risks.len());
+        self.tool_risks.write().extend(risks);
+    }
+
+    /// Risk class of a registered tool.
+    pub fn tool_risk(&self, name: &str) -> Option<ToolRisk> {
+        self.tool_risks.read().get(name).copied()
+    }
+
     /// Resolve a pending approval by request id.
     pub fn resolve_approval(&self, request_id: Uuid, decision: ApprovalDecision) -> bool {
         if let Some(pending) = self.pending.lock().remove(&request_id) {
@@ -176,6 +346,28 @@ impl PermissionEngine {
             .collect()
     }
 
+    /// Whether an agent's mutating tool calls are confirmed in batches.
+    pub fn confirms_batches(&self, agent_id: &str) -> bool {
+        self.mode_for_agent(agent_id) == PermissionMode::ConfirmBatch
+    }
+
+    /// Ask once for the mutating tool calls proposed in one model response.
+    ///
+    /// Hooks may decide the batch; rules never match it. An "allow always"
+    /// answer only applies to this batch.
+    pub async fn confirm_batch(
+        &self,
+        ctx: &PermissionContext,
+        calls: Vec<ProposedToolCall>,
+        event_sink: Option<Arc<dyn EventSink>>,
+    ) -> Result<PermissionOutcome, ToolError> {
+        let request = PermissionRequest::ToolBatch { calls };
+        if let Some(outcome) = self.apply_hook_decisions(ctx, &request).await? {
+            return Ok(outcome);
+        }
+        self.ask_for_approval(ctx, request, event_sink).await
+    }
+
     /// Determine the permission mode for a given agent.
     fn mode_for_agent(&self, agent_id: &str) -> PermissionMode {
         self.agent_modes
@@ -185,14 +377,67 @@ impl PermissionEngine {
             .unwrap_or(self.default_mode)
     }
 
+    /// Determine the approval profile for a given session.
+    fn profile_for_session(&self, session_id: Uuid) -> ApprovalPolicyProfile {
+        self.session_profiles
+            .read()
+            .get(&session_id)
+            .copied()
+            .unwrap_or(self.profile)
+    }
+
+    /// Return true when a session's turn runs under the `on_request` policy.
+    fn session_on_request(&self, session_id: Uuid) -> bool {
+        self.session_policies.read().get(&session_id) == Some(&ApprovalPolicy::OnRequest)
+    }
+
+    /// Risk class shown for a request: the requested tool, the riskiest
+    /// tool of a batch, or the tool that raised a path or command request.
+    fn request_risk(
+        &self,
+        ctx: &PermissionContext,
+        request: &PermissionRequest,
+    ) -> Option<ToolRisk> {
+        match request {
+            PermissionRequest::Tool { name } => self.tool_risk(name),
+            PermissionRequest::ToolBatch { calls } => calls
+                .iter()
+                .filter_map(|call| self.tool_risk(&call.tool_name))
+                .max(),
+            PermissionRequest::Path { .. }
+            | PermissionRequest::ExternalPath { .. }
+            | PermissionRequest::Command { .. }
+            | PermissionRequest::GitPush { .. } => ctx
+                .tool_name
+                .as_deref()
+                .and_then(|name| self.tool_risk(name)),
+        }
+    }
+
+    /// Return true when a request comes from a read-only tool and only reads
+    /// inside the workspace.
+    fn read_only_tool_allows(&self, ctx: &PermissionContext, request: &PermissionRequest) -> bool {
+        let read_only = |name: &str| self.tool_risk(name) == Some(ToolRisk::ReadOnly);
+        match request {
+            PermissionRequest::Tool { name } => read_only(name),
+            PermissionRequest::Path {
+                mode: PathAccess::Read,
+                ..
+            } => ctx.tool_name.as_deref().is_some_and(read_only),
+            PermissionRequest::Path { .. }
+            | PermissionRequest::ExternalPath { .. }
+            | PermissionRequest::Command { .. }
+            | PermissionRequest::GitPush { .. }
+            | PermissionRequest::ToolBatch { .. } => false,
+        }
+    }
+
     /// Check whether a tool is explicitly allowed by rules.
     fn tool_allowed_by_rules(&self, tool_name: &str) -> bool {
-        matches!(
-            self.rule_action_for_request(&PermissionRequest::Tool {
-                name: tool_name.to_string(),
-            }),
-            Some(PermissionAction::Allow)
-        )
+        self.rule_for_request(&PermissionRequest::Tool {
+            name: tool_name.to_string(),
+        })
+        .is_some_and(|rule| rule.action == PermissionAction::Allow)
     }
 
     /// Emit a permission requested event.
@@ -202,6 +447,7 @@ impl PermissionEngine {
         request_id: Uuid,
         action: PermissionAction,
         request: PermissionRequest,
+        risk: Option<ToolRisk>,
         event_sink: Option<Arc<dyn EventSink>>,
     ) {
         let Some(sink) = self.resolve_event_sink(event_sink) else {
@@ -217,12 +463,15 @@ impl PermissionEngine {
         let event = EventMsg {
             id: Uuid::new_v4(),
             session_id: ctx.session_id,
+            seq: 0,
+            user_id: None,
             created_at: Utc::now(),
             payload: EventPayload::PermissionRequested {
                 turn_id,
                 request_id,
                 action,
                 request,
+                risk,
             },
         };
         sink.emit(event);
@@ -234,6 +483,7 @@ impl PermissionEngine {
         ctx: &PermissionContext,
         request_id: Uuid,
         decision: ApprovalDecision,
+        timed_out: bool,
         event_sink: Option<Arc<dyn EventSink>>,
     ) {
         let Some(sink) = self.resolve_event_sink(event_sink) else {
@@ -243,31 +493,65 @@ impl PermissionEngine {
             return;
         };
         debug!(
-            "approval event resolved (request_id={}, session_id={}, decision={:?})",
-            request_id, ctx.session_id, decision
+            "approval event resolved (request_id={}, session_id={}, decision={:?}, timed_out={})",
+            request_id, ctx.session_id, decision, timed_out
         );
         let event = EventMsg {
             id: Uuid::new_v4(),
             session_id: ctx.session_id,
+            seq: 0,
+            user_id: None,
             created_at: Utc::now(),
             payload: EventPayload::ApprovalResolved {
                 turn_id,
                 request_id,
                 decision,
+                timed_out,
             },
         };
         sink.emit(event);
     }
 
+    /// Emit an approval expired event.
+    fn emit_approval_expired(
+        &self,
+        ctx: &PermissionContext,
+        request_id: Uuid,
+        event_sink: Option<Arc<dyn EventSink>>,
+    ) {
+        let Some(sink) = self.resolve_event_sink(event_sink) else {
+            return;
+        };
+        let Some(turn_id) = ctx.turn_id else {
+            return;
+        };
+        sink.emit(EventMsg {
+            id: Uuid::new_v4(),
+            session_id: ctx.session_id,
+            seq: 0,
+            user_id: None,
+            created_at: Utc::now(),
+            payload: EventPayload::ApprovalExpired {
+                turn_id,
+                request_id,
+            },
+        });
+    }
+
     /// Retrieve a cached approval decision for repeated requests.
     fn lookup_cached_approval(&self, request: &PermissionRequest) -> Option<ApprovalDecision> {
+        if matches!(request, PermissionRequest::ToolBatch { .. }) {
+            return None;
+        }
         let key = request_key(request);
         self.approval_store.lock().lookup(&key)
     }
 
     /// Cache approval decisions that allow repeated execution.
     fn cache_approval(&self, request: &PermissionRequest, decision: ApprovalDecision) {
-        if decision != ApprovalDecision::AllowAlways {
+        if decision != ApprovalDecision::AllowAlways
+            || matches!(request, PermissionRequest::ToolBatch { .. })
+        {
             return;
         }
         let key = request_key(request);
@@ -303,20 +587,19 @@ impl PermissionEngine {
         Ok(None)
     }
 
-    /// Determine the action that matches a request based on rules.
-    fn rule_action_for_request(&self, request: &PermissionRequest) -> Option<PermissionAction> {
-        for action in [
+    /// Find the rule that decides a request (deny, then allow, then ask).
+    fn rule_for_request(&self, request: &PermissionRequest) -> Option<&RuleMatcher> {
+        [
             PermissionAction::Deny,
             PermissionAction::Allow,
             PermissionAction::Ask,
-        ] {
-            for rule in &self.rules {
-                if rule.action == action && rule_matches(rule, request) {
-                    return Some(action);
-                }
-            }
-        }
-        None
+        ]
+        .into_iter()
+        .find_map(|action| {
+            self.rules
+                .iter()
+                .find(|rule| rule.action == action && rule_matches(rule, request))
+        })
     }
 
     /// Ask the approval handler or wait for a manual decision.
@@ -330,36 +613,57 @@ impl PermissionEngine {
         if let Some(decision) = self.lookup_cached_approval(&request) {
             return Ok(outcome_from_decision(decision));
         }
+        let profile = self.profile_for_session(ctx.session_id);
+        if !profile.is_interactive() {
+            if profile_allows(profile, &request) {
+                return Ok(PermissionOutcome {
+                    allowed: true,
+                    reason: None,
+                });
+            }
+            let reason = format!("{profile} profile denies requests that need approval");
+            debug!("permission denied: {reason}");
+            return Ok(PermissionOutcome {
+                allowed: false,
+                reason: Some(reason),
+            });
+        }
 
         let request_id = Uuid::new_v4();
         let action = PermissionAction::Ask;
+        let risk = self.request_risk(ctx, &request);
         self.emit_permission_requested(
             ctx,
             request_id,
             action,
             request.clone(),
+            risk,
             event_sink.clone(),
         );
 
         let handler = self.approval_handler.read().clone();
         if let Some(handler) = handler {
-            let decision = handler
-                .request_approval(ApprovalRequest {
-                    request_id,
-                    session_id: ctx.session_id,
-                    agent_id: ctx.agent_id.clone(),
-                    turn_id: ctx.turn_id,
-                    action,
-                    request: request.clone(),
-                })
-                .await;
+            let pending = handler.request_approval(ApprovalRequest {
+                request_id,
+                session_id: ctx.session_id,
+                agent_id: ctx.agent_id.clone(),
+                turn_id: ctx.turn_id,
+                action,
+                request: request.clone(),
+                risk,
+            });
+            let Some(decision) = self.wait_for_decision(pending).await else {
+                return Ok(self.time_out_approval(ctx, request_id, event_sink));
+            };
             self.cache_approval(&request, decision);
-            self.emit_approval_resolved(ctx, request_id, decision, event_sink);
+            self.emit_approval_resolved(ctx, request_id, decision, false, event_sink);
             return Ok(outcome_from_decision(decision));
         }
 
         if resolved_sink.is_none() {
-            if matches!(self.mode_for_agent(&ctx.agent_id), PermissionMode::Default) {
+            if matches!(self.mode_for_agent(&ctx.agent_id), PermissionMode::Default)
+                && !matches!(request, PermissionRequest::GitPush { .. })
+            {
                 info!(
                     "permission requested without approval handler or event sink; defaulting to allow"
                 );
@@ -383,6 +687,7 @@ impl PermissionEngine {
             turn_id: ctx.turn_id,
             action,
             request: request.clone(),
+            risk,
         };
         self.pending.lock().insert(
             request_id,
@@ -391,12 +696,49 @@ impl PermissionEngine {
                 request: approval_request,
             },
         );
-        let decision = receiver.await.unwrap_or(ApprovalDecision::Deny);
+        let guard = PendingApprovalGuard {
+            engine: self,
+            ctx,
+            request_id,
+            event_sink: event_sink.clone(),
+        };
+        let Some(decision) = self.wait_for_decision(receiver).await else {
+            self.pending.lock().remove(&request_id);
+            drop(guard);
+            return Ok(self.time_out_approval(ctx, request_id, event_sink));
+        };
+        drop(guard);
+        let decision = decision.unwrap_or(ApprovalDecision::Deny);
         self.cache_approval(&request, decision);
-        self.emit_approval_resolved(ctx, request_id, decision, event_sink);
+        self.emit_approval_resolved(ctx, request_id, decision, false, event_sink);
         Ok(outcome_from_decision(decision))
     }
 
+    /// Await an approval decision, returning `None` if the approval timeout elapses first.
+    async fn wait_for_decision<F: Future>(&self, decision: F) -> Option<F::Output> {
+        let Some(timeout) = self.approval_timeout else {
+            return Some(decision.await);
+        };
+        tokio::time::timeout(timeout, decision).await.ok()
+    }
+
+    /// Apply the configured timeout decision to an unanswered approval.
+    fn time_out_approval(
+        &self,
+        ctx: &PermissionContext,
+        request_id: Uuid,
+        event_sink: Option<Arc<dyn EventSink>>,
+    ) -> PermissionOutcome {
+        let decision = self.timeout_decision;
+        warn!("approval timed out (request_id={request_id}, decision={decision:?})");
+        self.emit_approval_resolved(ctx, request_id, decision, true, event_sink);
+        let mut outcome = outcome_from_decision(decision);
+        if !outcome.allowed {
+            outcome.reason = Some("approval timed out".to_string());
+        }
+        outcome
+    }
+
     /// Authorize a permission request based on hooks, rules, and mode.
     pub async fn authorize_with_sink(
         &self,
@@ -404,21 +746,42 @@ impl PermissionEngine {
         request: PermissionRequest,
         event_sink: Option<Arc<dyn EventSink>>,
     ) -> Result<PermissionOutcome, ToolError> {
+        if self.is_read_only()
+            && let Some(reason) = read_only_denial(&request)
+        {
+            debug!("permission denied: {reason}");
+            return Ok(PermissionOutcome {
+                allowed: false,
+                reason: Some(reason),
+            });
+        }
+        if self.is_offline()
+            && let Some(reason) = offline_denial(&request)
+        {
+            debug!("permission denied: {reason}");
+            return Ok(PermissionOutcome {
+                allowed: false,
+                reason: Some(reason),
+            });
+        }
         if let Some(outcome) = self.apply_hook_decisions(ctx, &request).await? {
             return Ok(outcome);
         }
 
-        let action = self.rule_action_for_request(&request);
-        if let Some(action) = action {
-            return match action {
+        if let Some(rule) = self.rule_for_request(&request) {
+            return match rule.action {
                 PermissionAction::Allow => Ok(PermissionOutcome {
                     allowed: true,
                     reason: None,
                 }),
-                PermissionAction::Deny => Ok(PermissionOutcome {
-                    allowed: false,
-                    reason: Some("denied by rule".to_string()),
-                }),
+                PermissionAction::Deny => {
+                    let reason = rule.deny_reason();
+                    debug!("permission denied: {reason}");
+                    Ok(PermissionOutcome {
+                        allowed: false,
+                        reason: Some(reason),
+                    })
+                }
                 PermissionAction::Ask => self.ask_for_approval(ctx, request, event_sink).await,
             };
         }
@@ -437,7 +800,21 @@ impl PermissionEngine {
             });
         }
 
+        if let Some(reason) = profile_denial(self.profile_for_session(ctx.session_id), &request) {
+            debug!("permission denied: {reason}");
+            return Ok(PermissionOutcome {
+                allowed: false,
+                reason: Some(reason),
+            });
+        }
+
         match self.mode_for_agent(&ctx.agent_id) {
+            // Pushes leave the machine, so only an explicit rule skips the prompt.
+            PermissionMode::BypassPermissions
+                if matches!(request, PermissionRequest::GitPush { .. }) =>
+            {
+                self.ask_for_approval(ctx, request, event_sink).await
+            }
             PermissionMode::BypassPermissions => Ok(PermissionOutcome {
                 allowed: true,
                 reason: None,
@@ -447,7 +824,18 @@ impl PermissionEngine {
                 reason: Some("plan mode blocks tool execution".to_string()),
             }),
             PermissionMode::AcceptEdits => {
-                if accept_edits_allows(&request) {
+                if accept_edits_allows(&request) || self.read_only_tool_allows(ctx, &request) {
+                    Ok(PermissionOutcome {
+                        allowed: true,
+                        reason: None,
+                    })
+                } else {
+                    self.ask_for_approval(ctx, request, event_sink).await
+                }
+            }
+            // Mutating calls were confirmed as a batch before they ran.
+            PermissionMode::ConfirmBatch => {
+                if confirm_batch_allows(&request) {
                     Ok(PermissionOutcome {
                         allowed: true,
                         reason: None,
@@ -456,6 +844,15 @@ impl PermissionEngine {
                     self.ask_for_approval(ctx, request, event_sink).await
                 }
             }
+            PermissionMode::Default
+                if self.session_on_request(ctx.session_id)
+                    && self.read_only_tool_allows(ctx, &request) =>
+            {
+                Ok(PermissionOutcome {
+                    allowed: true,
+                    reason: None,
+                })
+            }
             PermissionMode::Default => self.ask_for_approval(ctx, request, event_sink).await,
         }
     }
@@ -473,6 +870,138 @@ impl PermissionChecker for PermissionEngine {
     }
 }
 
+/// Reason a request is blocked in read-only mode, if it is.
+fn read_only_denial(request: &PermissionRequest) -> Option<String> {
+    match request {
+        PermissionRequest::Tool { name } => READ_ONLY_BLOCKED_TOOLS
+            .contains(&name.as_str())
+            .then(|| format!("read-only mode blocks {name}")),
+        PermissionRequest::Path { path, mode } | PermissionRequest::ExternalPath { path, mode } => {
+            (*mode == PathAccess::Write).then(|| format!("read-only mode blocks writes to {path}"))
+        }
+        PermissionRequest::Command { .. } | PermissionRequest::ToolBatch { .. } => None,
+        PermissionRequest::GitPush { remote, .. } => {
+            Some(format!("read-only mode blocks git push to {remote}"))
+        }
+    }
+}
+
+/// Reason a request is blocked in offline mode, if it is.
+fn offline_denial(request: &PermissionRequest) -> Option<String> {
+    match request {
+        PermissionRequest::Tool { name } if NETWORK_TOOLS.contains(&name.as_str()) => {
+            Some(format!("offline mode blocks network tool {name}"))
+        }
+        PermissionRequest::Command { argv } if is_network_command(argv) => Some(format!(
+            "offline mode blocks network command {}",
+            argv.join(" ")
+        )),
+        PermissionRequest::GitPush { remote, .. } => {
+            Some(format!("offline mode blocks git push to {remote}"))
+        }
+        PermissionRequest::Tool { .. }
+        | PermissionRequest::Path { .. }
+        | PermissionRequest::ExternalPath { .. }
+        | PermissionRequest::Command { .. }
+        | PermissionRequest::ToolBatch { .. } => None,
+    }
+}
+
+/// Reason a non-interactive profile denies a request outright, if it does.
+fn profile_denial(profile: ApprovalPolicyProfile, request: &PermissionRequest) -> Option<String> {
+    if profile.is_interactive() {
+        return None;
+    }
+    match request {
+        PermissionRequest::ExternalPath { path, .. } => Some(format!(
+            "{profile} profile denies access outside the workspace: {path}"
+        )),
+        PermissionRequest::Tool { name } if NETWORK_TOOLS.contains(&name.as_str()) => {
+            Some(format!("{profile} profile denies network tool {name}"))
+        }
+        PermissionRequest::Command { argv } if is_network_command(argv) => Some(format!(
+            "{profile} profile denies network command {}",
+            argv.join(" ")
+        )),
+        PermissionRequest::GitPush { remote, .. } => {
+            Some(format!("{profile} profile denies git push to {remote}"))
+        }
+        PermissionRequest::Tool { .. }
+        | PermissionRequest::Path { .. }
+        | PermissionRequest::Command { .. }
+        | PermissionRequest::ToolBatch { .. } => None,
+    }
+}
+
+/// Return true when a profile answers an approval request with allow.
+fn profile_allows(profile: ApprovalPolicyProfile, request: &PermissionRequest) -> bool {
+    let trusted = profile == ApprovalPolicyProfile::CiTrusted;
+    match request {
+        PermissionRequest::Tool { name } => profile_allowed_tools(profile).contains(&name.as_str()),
+        PermissionRequest::Path { mode, .. } => *mode == PathAccess::Read || trusted,
+        PermissionRequest::Command { .. } | PermissionRequest::ToolBatch { .. } => trusted,
+        PermissionRequest::ExternalPath { .. } | PermissionRequest::GitPush { .. } => false,
+    }
+}
+
+/// Tools a profile allows without asking.
+fn profile_allowed_tools(profile: ApprovalPolicyProfile) -> &'static [&'static str] {
+    match profile {
+        ApprovalPolicyProfile::Interactive => &[],
+        ApprovalPolicyProfile::CiSafe => &[
+            "Read",
+            "Glob",
+            "Grep",
+            "Outline",
+            "EnvInfo",
+            "NotebookRead",
+            "GitStatus",
+            "GitDiff",
+        ],
+        ApprovalPolicyProfile::CiTrusted => &[
+            "Read",
+            "Write",
+            "Edit",
+            "Glob",
+            "Grep",
+            "Outline",
+            "EnvInfo",
+            "NotebookRead",
+            "NotebookEdit",
+            "GitStatus",
+            "GitDiff",
+            "GitCommit",
+            "Bash",
+        ],
+    }
+}
+
+/// Return true when a command line reaches the network.
+fn is_network_command(argv: &[String]) -> bool {
+    let Some(program) = argv.first() else {
+        return false;
+    };
+    let program = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    if NETWORK_COMMANDS.contains(&program) {
+        return true;
+    }
+    if program != "git" {
+        return false;
+    }
+    let mut args = argv.iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // Global options that take a separate value.
+            "-C" | "-c" => {
+                args.next();
+            }
+            option if option.starts_with('-') => {}
+            subcommand => return NETWORK_GIT_SUBCOMMANDS.contains(&subcommand),
+        }
+    }
+    false
+}
+
 /// Compile configured permission rules into matchers.
 fn compile_rules(rules: Vec<PermissionRule>) -> Result<Vec<RuleMatcher>, OdysseyCoreError> {
     rules
@@ -494,6 +1023,8 @@ fn compile_rules(rules: Vec<PermissionRule>) -> Result<Vec<RuleMatcher>, Odyssey
                 path_raw: rule.path,
                 command: rule.command,
                 access,
+                git_push: rule.git_push,
+                source: rule.source,
             })
         })
         .collect()
@@ -501,6 +1032,23 @@ fn compile_rules(rules: Vec<PermissionRule>) -> Result<Vec<RuleMatcher>, Odyssey
 
 /// Determine whether a rule matches a permission request.
 fn rule_matches(rule: &RuleMatcher, request: &PermissionRequest) -> bool {
+    // Push rules and push requests only ever match each other, so catch-all
+    // rules never allow a push.
+    if let PermissionRequest::GitPush { force, .. } = request {
+        let kind = if *force {
+            GitPushAccess::Force
+        } else {
+            GitPushAccess::Push
+        };
+        return rule.git_push == Some(kind)
+            && rule.tool.is_none()
+            && rule.path.is_none()
+            && rule.command.is_none()
+            && rule.access.is_none();
+    }
+    if rule.git_push.is_some() || matches!(request, PermissionRequest::ToolBatch { .. }) {
+        return false;
+    }
     let has_filters = rule.tool.is_some()
         || rule.path.is_some()
         || rule.command.is_some()
@@ -540,6 +1088,7 @@ fn rule_matches(rule: &RuleMatcher, request: &PermissionRequest) -> bool {
             };
             argv.starts_with(prefix)
         }
+        PermissionRequest::GitPush { .. } | PermissionRequest::ToolBatch { .. } => false,
     }
 }
 
@@ -547,11 +1096,35 @@ fn rule_matches(rule: &RuleMatcher, request: &PermissionRequest) -> bool {
 fn accept_edits_allows(request: &PermissionRequest) -> bool {
     match request {
         PermissionRequest::Tool { name } => {
-            matches!(name.as_str(), "Read" | "Write" | "Edit" | "Glob" | "Grep")
+            matches!(
+                name.as_str(),
+                "Read"
+                    | "Write"
+                    | "Edit"
+                    | "Glob"
+                    | "Grep"
+                    | "Outline"
+                    | "NotebookRead"
+                    | "NotebookEdit"
+            )
         }
         PermissionRequest::Path { .. } => true,
         PermissionRequest::ExternalPath { .. } => false,
         PermissionRequest::Command { .. } => false,
+        PermissionRequest::GitPush { .. } => false,
+        PermissionRequest::ToolBatch { .. } => false,
+    }
+}
+
+/// Determine if confirm-batch mode allows the request of a confirmed call.
+fn confirm_batch_allows(request: &PermissionRequest) -> bool {
+    match request {
+        PermissionRequest::Tool { .. }
+        | PermissionRequest::Path { .. }
+        | PermissionRequest::Command { .. } => true,
+        PermissionRequest::ExternalPath { .. }
+        | PermissionRequest::GitPush { .. }
+        | PermissionRequest::ToolBatch { .. } => false,
     }
 }
 
@@ -562,6 +1135,18 @@ fn request_key(request: &PermissionRequest) -> String {
         PermissionRequest::Path { path, mode } => format!("path:{mode:?}:{path}"),
         PermissionRequest::ExternalPath { path, mode } => format!("external:{mode:?}:{path}"),
         PermissionRequest::Command { argv } => format!("command:{}", argv.join(" ")),
+        PermissionRequest::GitPush {
+            remote,
+            branch,
+            force,
+        } => format!("git_push:{remote}:{branch}:{force}"),
+        PermissionRequest::ToolBatch { calls } => {
+            let names = calls
+                .iter()
+                .map(|call| call.tool_name.as_str())
+                .collect::<Vec<_>>();
+            format!("tool_batch:{}", names.join(","))
+        }
     }
 }
 
@@ -624,6 +1209,8 @@ mod tests {
                     path: None,
                     command: None,
                     access: None,
+                    git_push: None,
+                    source: None,
                 },
                 PermissionRule {
                     action: PermissionAction::Ask,
@@ -631,8 +1218,14 @@ mod tests {
                     path: Some("odyssey_test/ask_override.txt".to_string()),
                     command: None,
                     access: Some(PathAccess::Write),
+                    git_push: None,
+                    source: None,
                 },
             ],
+            webhook: None,
+            approval_timeout_secs: None,
+            timeout_decision: ApprovalTimeoutDecision::Deny,
+            profile: ApprovalPolicyProfile::Interactive,
         };
         let engine = engine_with_store(config, workspace.path(), store_path);
         engine.set_approval_handler(Some(Arc::new(StaticApprovalHandler {
@@ -672,7 +1265,13 @@ mod tests {
                 path: None,
                 command: None,
                 access: None,
+                git_push: None,
+                source: None,
             }],
+            webhook: None,
+            approval_timeout_secs: None,
+            timeout_decision: ApprovalTimeoutDecision::Deny,
+            profile: ApprovalPolicyProfile::Interactive,
         };
 
         let engine = engine_with_store(config.clone(), workspace.path(), store_path.clone());
@@ -712,4 +1311,136 @@ mod tests {
         assert_eq!(outcome.allowed, true);
         assert_eq!(outcome.reason, None);
     }
+
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<EventMsg>>,
+    }
+
+    impl EventSink for RecordingSink {
+        fn emit(&self, event: EventMsg) {
+            self.events.lock().push(event);
+        }
+    }
+
+    #[tokio::test]
+    async fn dropped_approval_is_removed_and_reported_expired() {
+        let workspace = temp_workspace();
+        let store_path = workspace.path().join("permission.jsonl");
+        let config = PermissionsConfig {
+            mode: PermissionMode::Default,
+            rules: vec![PermissionRule {
+                action: PermissionAction::Ask,
+                tool: Some("Bash".to_string()),
+                path: None,
+                command: None,
+                access: None,
+                git_push: None,
+                source: None,
+            }],
+            webhook: None,
+            approval_timeout_secs: None,
+            timeout_decision: ApprovalTimeoutDecision::Deny,
+            profile: ApprovalPolicyProfile::Interactive,
+        };
+        let engine = engine_with_store(config, workspace.path(), store_path);
+        let sink = Arc::new(RecordingSink::default());
+        engine.set_event_sink(Some(sink.clone()));
+        let ctx = PermissionContext {
+            session_id: Uuid::new_v4(),
+            agent_id: "agent".to_string(),
+            tool_name: Some("Bash".to_string()),
+            turn_id: Some(Uuid::new_v4()),
+        };
+
+        let request = PermissionRequest::Tool {
+            name: "Bash".to_string(),
+        };
+        let pending = tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            engine.authorize(&ctx, request),
+        )
+        .await;
+        assert!(pending.is_err());
+        assert_eq!(engine.list_pending_approvals().len(), 0);
+
+        let events = sink.events.lock();
+        let requested = events
+            .iter()
+            .find_map(|event| match &event.payload {
+                EventPayload::PermissionRequested { request_id, .. } => Some(*request_id),
+                _ => None,
+            })
+            .expect("requested event");
+        let expired = events
+            .iter()
+            .find_map(|event| match &event.payload {
+                EventPayload::ApprovalExpired { request_id, .. } => Some(*request_id),
+                _ => None,
+            })
+            .expect("expired event");
+        assert_eq!(expired, requested);
+    }
+
+    #[tokio::test]
+    async fn unanswered_approval_applies_timeout_decision() {
+        let workspace = temp_workspace();
+        let store_path = workspace.path().join("permission.jsonl");
+        let config = PermissionsConfig {
+            mode: PermissionMode::Default,
+            rules: vec![PermissionRule {
+                action: PermissionAction::Ask,
+                tool: Some("Bash".to_string()),
+                path: None,
+                command: None,
+                access: None,
+                git_push: None,
+                source: None,
+            }],
+            webhook: None,
+            approval_timeout_secs: Some(0),
+            timeout_decision: ApprovalTimeoutDecision::Allow,
+            profile: ApprovalPolicyProfile::Interactive,
+        };
+        let engine = engine_with_store(config, workspace.path(), store_path);
+        let sink = Arc::new(RecordingSink::default());
+        engine.set_event_sink(Some(sink.clone()));
+        let ctx = PermissionContext {
+            session_id: Uuid::new_v4(),
+            agent_id: "agent".to_string(),
+            tool_name: Some("Bash".to_string()),
+            turn_id: Some(Uuid::new_v4()),
+        };
+
+        let outcome = engine
+            .authorize(
+                &ctx,
+                PermissionRequest::Tool {
+                    name: "Bash".to_string(),
+                },
+            )
+            .await
+            .expect("outcome");
+        assert_eq!(outcome.allowed, true);
+        assert_eq!(engine.list_pending_approvals().len(), 0);
+
+        let events = sink.events.lock();
+        let resolved = events
+            .iter()
+            .find_map(|event| match &event.payload {
+                EventPayload::ApprovalResolved {
+                    decision,
+                    timed_out,
+                    ..
+                } => Some((*decision, *timed_out)),
+                _ => None,
+            })
+            .expect("resolved event");
+        assert_eq!(resolved, (ApprovalDecision::AllowOnce, true));
+        assert!(
+            !events
+                .iter()
+                .any(|event| matches!(event.payload, EventPayload::ApprovalExpired { .. }))
+        );
+    }
 }
@@ -0,0 +1,132 @@
+//! Named prompt presets expanded from slash commands.
+
+use crate::error::OdysseyCoreError;
+use odyssey_rs_config::PromptPresetConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Placeholder replaced with the full argument string.
+const ALL_ARGS_PLACEHOLDER: &str = "{{args}}";
+
+/// Reusable prompt configured under `prompts`, such as `/review <file>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptPreset {
+    /// Command name without the leading slash.
+    pub name: String,
+    /// Short description shown by frontends.
+    pub description: Option<String>,
+    /// Positional argument names.
+    pub args: Vec<String>,
+    /// Prompt text with `{{arg}}` placeholders.
+    pub template: String,
+}
+
+impl PromptPreset {
+    /// Build a preset from its config entry.
+    pub fn from_config(name: &str, config: &PromptPresetConfig) -> Self {
+        Self {
+            name: name.to_string(),
+            description: config.description.clone(),
+            args: config.args.clone(),
+            template: config.template.clone(),
+        }
+    }
+
+    /// Command usage, e.g. `/review <file>`.
+    pub fn usage(&self) -> String {
+        let mut usage = format!("/{}", self.name);
+        for arg in &self.args {
+            usage.push_str(&format!(" <{arg}>"));
+        }
+        usage
+    }
+
+    /// Fill the template from the text typed after the command.
+    ///
+    /// Arguments are split on whitespace and the last declared argument takes
+    /// the remaining text. Every declared argument is required.
+    pub fn expand(&self, input: &str) -> Result<String, OdysseyCoreError> {
+        let input = input.trim();
+        let mut rest = input;
+        let mut values = Vec::with_capacity(self.args.len());
+        for (idx, arg) in self.args.iter().enumerate() {
+            let value = if idx + 1 == self.args.len() {
+                std::mem::take(&mut rest)
+            } else {
+                let (value, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                rest = tail.trim_start();
+                value
+            };
+            if value.is_empty() {
+                return Err(OdysseyCoreError::Parse(format!(
+                    "missing argument {arg}; usage: {}",
+                    self.usage()
+                )));
+            }
+            values.push((arg, value));
+        }
+
+        let mut prompt = self.template.replace(ALL_ARGS_PLACEHOLDER, input);
+        for (arg, value) in values {
+            prompt = prompt.replace(&format!("{{{{{arg}}}}}"), value);
+        }
+        Ok(prompt)
+    }
+}
+
+/// Presets from config, sorted by name.
+pub(crate) fn presets_from_config(
+    prompts: &BTreeMap<String, PromptPresetConfig>,
+) -> Vec<PromptPreset> {
+    prompts
+        .iter()
+        .map(|(name, config)| PromptPreset::from_config(name, config))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PromptPreset;
+    use pretty_assertions::assert_eq;
+
+    fn preset(args: &[&str], template: &str) -> PromptPreset {
+        PromptPreset {
+            name: "review".to_string(),
+            description: None,
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            template: template.to_string(),
+        }
+    }
+
+    #[test]
+    fn expands_named_arguments_with_trailing_remainder() {
+        let preset = preset(&["file", "focus"], "Review {{file}} focusing on {{focus}}.");
+        assert_eq!(preset.usage(), "/review <file> <focus>");
+        assert_eq!(
+            preset
+                .expand("  src/lib.rs   error handling and logging ")
+                .expect("expand"),
+            "Review src/lib.rs focusing on error handling and logging."
+        );
+    }
+
+    #[test]
+    fn expands_all_args_placeholder_without_declared_args() {
+        let preset = preset(&[], "Explain: {{args}}");
+        assert_eq!(
+            preset.expand("why is this slow?").expect("expand"),
+            "Explain: why is this slow?"
+        );
+        assert_eq!(preset.expand("").expect("expand"), "Explain: ");
+    }
+
+    #[test]
+    fn rejects_missing_arguments() {
+        let preset = preset(&["file", "focus"], "{{file}} {{focus}}");
+        let err = preset.expand("src/lib.rs").expect_err("missing focus");
+        assert_eq!(
+            err.to_string(),
+            "parse error: missing argument focus; usage: /review <file> <focus>"
+        );
+    }
+}
@@ -0,0 +1,890 @@
+//! Deterministic record/replay of LLM responses and tool results per turn.
+//!
+//! When recording, every LLM response and tool result produced during a turn is
+//! captured in a [`ReplayLog`]. When replaying, [`ReplayLLM`] and
+//! [`ReplayToolRouter`] serve the recorded data so a session can be re-executed
+//! without calling a model or touching the workspace.
+
+use crate::error::OdysseyCoreError;
+use crate::tools::ToolRouter;
+use crate::types::SessionId;
+use async_trait::async_trait;
+use autoagents_core::tool::ToolT;
+use autoagents_llm::chat::{
+    ChatMessage, ChatProvider, ChatResponse, StreamChunk, StreamResponse, StructuredOutputFormat,
+    Tool as LLMTool,
+};
+use autoagents_llm::completion::{CompletionProvider, CompletionRequest, CompletionResponse};
+use autoagents_llm::embedding::EmbeddingProvider;
+use autoagents_llm::error::LLMError;
+use autoagents_llm::models::ModelsProvider;
+use autoagents_llm::{LLMProvider, ToolCall};
+use futures_util::stream::{self, Stream, StreamExt};
+use log::{debug, info, warn};
+use odyssey_rs_config::ToolPolicy;
+use odyssey_rs_protocol::{ToolError, ToolErrorKind, TurnId};
+use odyssey_rs_tools::{Tool, ToolContext, ToolDeltaSink, tools_to_adaptors};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Current replay log format version.
+const REPLAY_LOG_VERSION: u32 = 1;
+
+type ChunkStream = Pin<Box<dyn Stream<Item = Result<StreamChunk, LLMError>> + Send>>;
+
+/// Recorded LLM response for a single model call.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecordedLLMResponse {
+    /// Text content returned by the model.
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Tool calls requested by the model.
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+/// Recorded result of a single tool invocation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecordedToolResult {
+    /// Tool name.
+    pub tool_name: String,
+    /// Arguments passed to the tool.
+    pub args: Value,
+    /// Tool output (or error message when unsuccessful).
+    pub output: Value,
+    /// Whether the tool call succeeded.
+    pub success: bool,
+    /// Error category when unsuccessful; absent in recordings made before
+    /// errors were categorized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<ToolErrorKind>,
+}
+
+/// Recorded data for a single turn.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TurnRecording {
+    /// Turn id at recording time.
+    pub turn_id: TurnId,
+    /// User input for the turn.
+    pub input: String,
+    /// LLM responses in call order.
+    #[serde(default)]
+    pub llm_responses: Vec<RecordedLLMResponse>,
+    /// Tool results in call order.
+    #[serde(default)]
+    pub tool_results: Vec<RecordedToolResult>,
+}
+
+/// Ordered recording of all turns in a session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReplayLog {
+    /// Replay log format version.
+    pub version: u32,
+    /// Recorded turns in execution order.
+    #[serde(default)]
+    pub turns: Vec<TurnRecording>,
+}
+
+impl Default for ReplayLog {
+    fn default() -> Self {
+        Self {
+            version: REPLAY_LOG_VERSION,
+            turns: Vec::new(),
+        }
+    }
+}
+
+impl ReplayLog {
+    /// Load a replay log from a JSON file.
+    pub fn load(path: &Path) -> Result<Self, OdysseyCoreError> {
+        let contents = fs::read_to_string(path)?;
+        let log: ReplayLog = serde_json::from_str(&contents)
+            .map_err(|err| OdysseyCoreError::Replay(format!("invalid replay log: {err}")))?;
+        if log.version != REPLAY_LOG_VERSION {
+            return Err(OdysseyCoreError::Replay(format!(
+                "unsupported replay log version: {}",
+                log.version
+            )));
+        }
+        Ok(log)
+    }
+
+    /// Write the replay log to a JSON file, creating parent directories.
+    pub fn save(&self, path: &Path) -> Result<(), OdysseyCoreError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|err| OdysseyCoreError::Replay(err.to_string()))?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+/// Captures LLM responses and tool results while turns execute.
+#[derive(Clone, Default)]
+pub struct ReplayRecorder {
+    /// Recorded turns.
+    log: Arc<Mutex<ReplayLog>>,
+    /// Optional file the log is persisted to after each turn.
+    path: Option<PathBuf>,
+}
+
+impl ReplayRecorder {
+    /// Create an in-memory recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a recorder that persists the log to `path` after every turn.
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self {
+            log: Arc::new(Mutex::new(ReplayLog::default())),
+            path: Some(path.into()),
+        }
+    }
+
+    /// Return a snapshot of the recorded log.
+    pub fn log(&self) -> ReplayLog {
+        self.log.lock().clone()
+    }
+
+    /// Start recording a new turn.
+    pub(crate) fn begin_turn(&self, turn_id: TurnId, input: &str) {
+        debug!("recording turn (turn_id={})", turn_id);
+        self.log.lock().turns.push(TurnRecording {
+            turn_id,
+            input: input.to_string(),
+            llm_responses: Vec::new(),
+            tool_results: Vec::new(),
+        });
+    }
+
+    /// Persist the log if a path is configured.
+    pub(crate) fn persist(&self) -> Result<(), OdysseyCoreError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let log = self.log();
+        log.save(path)
+    }
+
+    fn record_llm_response(&self, response: RecordedLLMResponse) {
+        let mut log = self.log.lock();
+        match log.turns.last_mut() {
+            Some(turn) => turn.llm_responses.push(response),
+            None => warn!("dropping LLM response recorded outside of a turn"),
+        }
+    }
+
+    fn record_tool_result(&self, result: RecordedToolResult) {
+        let mut log = self.log.lock();
+        match log.turns.last_mut() {
+            Some(turn) => turn.tool_results.push(result),
+            None => warn!("dropping tool result recorded outside of a turn"),
+        }
+    }
+}
+
+/// Serves recorded turns in order.
+#[derive(Clone)]
+pub struct ReplayPlayer {
+    /// Turns that have not been replayed yet.
+    turns: Arc<Mutex<VecDeque<TurnRecording>>>,
+}
+
+impl ReplayPlayer {
+    /// Create a player from a recorded log.
+    pub fn new(log: ReplayLog) -> Self {
+        Self {
+            turns: Arc::new(Mutex::new(log.turns.into())),
+        }
+    }
+
+    /// Load a player from a replay log file.
+    pub fn from_path(path: &Path) -> Result<Self, OdysseyCoreError> {
+        Ok(Self::new(ReplayLog::load(path)?))
+    }
+
+    /// Number of recorded turns left to replay.
+    pub fn remaining_turns(&self) -> usize {
+        self.turns.lock().len()
+    }
+
+    /// Take the next recorded turn.
+    pub(crate) fn next_turn(&self, input: &str) -> Result<ReplayTurn, OdysseyCoreError> {
+        let turn = self
+            .turns
+            .lock()
+            .pop_front()
+            .ok_or_else(|| OdysseyCoreError::Replay("no recorded turns left".to_string()))?;
+        if turn.input != input {
+            warn!(
+                "replayed turn input differs from recording (turn_id={})",
+                turn.turn_id
+            );
+        }
+        Ok(ReplayTurn::new(turn))
+    }
+}
+
+/// Recorded data for the turn currently being replayed.
+#[derive(Clone)]
+pub struct ReplayTurn {
+    /// Remaining LLM responses.
+    llm_responses: Arc<Mutex<VecDeque<RecordedLLMResponse>>>,
+    /// Remaining tool results.
+    tool_results: Arc<Mutex<Vec<RecordedToolResult>>>,
+}
+
+impl ReplayTurn {
+    fn new(turn: TurnRecording) -> Self {
+        Self {
+            llm_responses: Arc::new(Mutex::new(turn.llm_responses.into())),
+            tool_results: Arc::new(Mutex::new(turn.tool_results)),
+        }
+    }
+
+    fn next_llm_response(&self) -> Result<RecordedLLMResponse, LLMError> {
+        self.llm_responses.lock().pop_front().ok_or_else(|| {
+            LLMError::ProviderError("replay has no recorded LLM response left".to_string())
+        })
+    }
+
+    /// Take the first recorded result matching the tool name and arguments.
+    fn take_tool_result(&self, name: &str, args: &Value) -> Option<RecordedToolResult> {
+        let mut results = self.tool_results.lock();
+        let index = results
+            .iter()
+            .position(|result| result.tool_name == name && &result.args == args)?;
+        Some(results.remove(index))
+    }
+}
+
+/// Record or replay mode for a session.
+#[derive(Clone)]
+pub enum ReplayMode {
+    /// Record LLM responses and tool results.
+    Record(ReplayRecorder),
+    /// Serve recorded LLM responses and tool results.
+    Replay(ReplayPlayer),
+}
+
+/// Per-session replay modes tracked by the turn executor.
+#[derive(Clone, Default)]
+pub(crate) struct ReplaySessions {
+    modes: Arc<RwLock<HashMap<SessionId, ReplayMode>>>,
+}
+
+impl ReplaySessions {
+    pub(crate) fn set(&self, session_id: SessionId, mode: ReplayMode) {
+        self.modes.write().insert(session_id, mode);
+    }
+
+    pub(crate) fn get(&self, session_id: SessionId) -> Option<ReplayMode> {
+        self.modes.read().get(&session_id).cloned()
+    }
+
+    pub(crate) fn remove(&self, session_id: SessionId) -> bool {
+        self.modes.write().remove(&session_id).is_some()
+    }
+}
+
+/// Chat response rebuilt from a recording.
+#[derive(Debug, Clone)]
+struct ReplayChatResponse {
+    response: RecordedLLMResponse,
+}
+
+impl std::fmt::Display for ReplayChatResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.response.text.as_deref().unwrap_or_default())
+    }
+}
+
+impl ChatResponse for ReplayChatResponse {
+    fn text(&self) -> Option<String> {
+        self.response.text.clone()
+    }
+
+    fn tool_calls(&self) -> Option<Vec<ToolCall>> {
+        self.response.tool_calls.clone()
+    }
+}
+
+/// LLM provider that records responses from an inner provider.
+#[derive(Clone)]
+pub struct RecordingLLMProvider {
+    inner: Arc<dyn LLMProvider>,
+    recorder: ReplayRecorder,
+}
+
+impl RecordingLLMProvider {
+    /// Wrap an LLM provider so its responses are recorded.
+    pub fn new(inner: Arc<dyn LLMProvider>, recorder: ReplayRecorder) -> Self {
+        Self { inner, recorder }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for RecordingLLMProvider {
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let response = self
+            .inner
+            .chat_with_tools(messages, tools, json_schema)
+            .await?;
+        self.recorder.record_llm_response(RecordedLLMResponse {
+            text: response.text(),
+            tool_calls: response.tool_calls(),
+        });
+        Ok(response)
+    }
+
+    async fn chat_with_web_search(&self, input: String) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.inner.chat_with_web_search(input).await
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, LLMError>> + Send>>, LLMError> {
+        self.inner.chat_stream(messages, json_schema).await
+    }
+
+    async fn chat_stream_struct(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamResponse, LLMError>> + Send>>, LLMError>
+    {
+        self.inner
+            .chat_stream_struct(messages, tools, json_schema)
+            .await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<&[LLMTool]>,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<ChunkStream, LLMError> {
+        let inner = self
+            .inner
+            .chat_stream_with_tools(messages, tools, json_schema)
+            .await?;
+        let captured = Arc::new(Mutex::new(RecordedLLMResponse {
+            text: Some(String::new()),
+            tool_calls: None,
+        }));
+        let capture = captured.clone();
+        let recorder = self.recorder.clone();
+        let finish = stream::once(async move {
+            let response = std::mem::replace(
+                &mut *captured.lock(),
+                RecordedLLMResponse {
+                    text: None,
+                    tool_calls: None,
+                },
+            );
+            recorder.record_llm_response(response);
+        })
+        .filter_map(|()| async { None });
+        let recorded = inner
+            .inspect(move |chunk| {
+                let mut captured = capture.lock();
+                if let Ok(StreamChunk::Text(delta)) = chunk
+                    && let Some(text) = &mut captured.text
+                {
+                    text.push_str(delta);
+                }
+                if let Ok(StreamChunk::ToolUseComplete { tool_call, .. }) = chunk {
+                    captured
+                        .tool_calls
+                        .get_or_insert_default()
+                        .push(tool_call.clone());
+                }
+            })
+            .chain(finish);
+        Ok(Box::pin(recorded))
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for RecordingLLMProvider {
+    async fn complete(
+        &self,
+        req: &CompletionRequest,
+        json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<CompletionResponse, LLMError> {
+        self.inner.complete(req, json_schema).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for RecordingLLMProvider {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        self.inner.embed(input).await
+    }
+}
+
+#[async_trait]
+impl ModelsProvider for RecordingLLMProvider {}
+
+impl LLMProvider for RecordingLLMProvider {}
+
+/// LLM provider that serves recorded responses for a replayed turn.
+#[derive(Clone)]
+pub struct ReplayLLM {
+    turn: ReplayTurn,
+}
+
+impl ReplayLLM {
+    /// Create a replay provider for a recorded turn.
+    pub fn new(turn: ReplayTurn) -> Self {
+        Self { turn }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for ReplayLLM {
+    async fn chat_with_tools(
+        &self,
+        _messages: &[ChatMessage],
+        _tools: Option<&[LLMTool]>,
+        _json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        let response = self.turn.next_llm_response()?;
+        Ok(Box::new(ReplayChatResponse { response }))
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        _messages: &[ChatMessage],
+        _tools: Option<&[LLMTool]>,
+        _json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<ChunkStream, LLMError> {
+        let response = self.turn.next_llm_response()?;
+        let text = response
+            .text
+            .filter(|text| !text.is_empty())
+            .map(StreamChunk::Text);
+        let tool_calls = response
+            .tool_calls
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(index, tool_call)| StreamChunk::ToolUseComplete { index, tool_call });
+        let chunks = text
+            .into_iter()
+            .chain(tool_calls)
+            .map(Ok)
+            .collect::<Vec<_>>();
+        Ok(Box::pin(stream::iter(chunks)))
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for ReplayLLM {
+    async fn complete(
+        &self,
+        _req: &CompletionRequest,
+        _json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<CompletionResponse, LLMError> {
+        Err(LLMError::ProviderError(
+            "replay does not support completions".to_string(),
+        ))
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for ReplayLLM {
+    async fn embed(&self, _input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        Err(LLMError::ProviderError(
+            "replay does not support embeddings".to_string(),
+        ))
+    }
+}
+
+#[async_trait]
+impl ModelsProvider for ReplayLLM {}
+
+impl LLMProvider for ReplayLLM {}
+
+/// Tool wrapper that records results from the inner tool.
+#[derive(Debug)]
+pub(crate) struct RecordingTool {
+    inner: Arc<dyn Tool>,
+    recorder: ReplayRecorder,
+}
+
+impl RecordingTool {
+    pub(crate) fn new(inner: Arc<dyn Tool>, recorder: ReplayRecorder) -> Self {
+        Self { inner, recorder }
+    }
+
+    /// Record a tool invocation outcome.
+    fn record(&self, args: Value, result: &Result<Value, ToolError>) {
+        let (output, success, error_kind) = match result {
+            Ok(output) => (output.clone(), true, None),
+            Err(err) => (
+                Value::String(err.message().to_string()),
+                false,
+                Some(err.kind()),
+            ),
+        };
+        self.recorder.record_tool_result(RecordedToolResult {
+            tool_name: self.inner.name().to_string(),
+            args,
+            output,
+            success,
+            error_kind,
+        });
+    }
+}
+
+impl std::fmt::Debug for ReplayRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplayRecorder")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Tool for RecordingTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn args_schema(&self) -> Value {
+        self.inner.args_schema()
+    }
+
+    fn supports_parallel(&self) -> bool {
+        self.inner.supports_parallel()
+    }
+
+    fn is_mutating(&self) -> bool {
+        self.inner.is_mutating()
+    }
+
+    fn max_concurrency(&self) -> Option<usize> {
+        self.inner.max_concurrency()
+    }
+
+    fn exclusion_group(&self) -> Option<&str> {
+        self.inner.exclusion_group()
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let result = self.inner.call(ctx, args.clone()).await;
+        self.record(args, &result);
+        result
+    }
+
+    async fn call_streaming(
+        &self,
+        ctx: &ToolContext,
+        args: Value,
+        sink: &dyn ToolDeltaSink,
+    ) -> Result<Value, ToolError> {
+        let result = self.inner.call_streaming(ctx, args.clone(), sink).await;
+        self.record(args, &result);
+        result
+    }
+}
+
+/// Tool wrapper that serves recorded results instead of executing.
+#[derive(Debug)]
+struct ReplayTool {
+    inner: Arc<dyn Tool>,
+    turn: ReplayTurn,
+}
+
+impl std::fmt::Debug for ReplayTurn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplayTurn")
+            .field("llm_responses", &self.llm_responses.lock().len())
+            .field("tool_results", &self.tool_results.lock().len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Tool for ReplayTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn args_schema(&self) -> Value {
+        self.inner.args_schema()
+    }
+
+    fn supports_parallel(&self) -> bool {
+        self.inner.supports_parallel()
+    }
+
+    fn is_mutating(&self) -> bool {
+        self.inner.is_mutating()
+    }
+
+    fn max_concurrency(&self) -> Option<usize> {
+        self.inner.max_concurrency()
+    }
+
+    fn exclusion_group(&self) -> Option<&str> {
+        self.inner.exclusion_group()
+    }
+
+    async fn call(&self, _ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let name = self.inner.name();
+        let recorded = self.turn.take_tool_result(name, &args).ok_or_else(|| {
+            ToolError::ExecutionFailed(format!("no recorded result for tool {name}"))
+        })?;
+        if recorded.success {
+            return Ok(recorded.output);
+        }
+        let message = match recorded.output {
+            Value::String(message) => message,
+            other => other.to_string(),
+        };
+        Err(match recorded.error_kind {
+            Some(kind) => ToolError::from_kind(kind, message),
+            None => ToolError::ExecutionFailed(message),
+        })
+    }
+}
+
+/// Tool router that serves recorded tool results for a replayed turn.
+pub struct ReplayToolRouter {
+    router: ToolRouter,
+    turn: ReplayTurn,
+}
+
+impl ReplayToolRouter {
+    /// Create a replay router for a recorded turn.
+    pub fn new(router: ToolRouter, turn: ReplayTurn) -> Self {
+        Self { router, turn }
+    }
+
+    /// Build adapted replay tools filtered by policy.
+    pub fn tools_for_agent(
+        &self,
+        policy: &ToolPolicy,
+        ctx: Arc<RwLock<ToolContext>>,
+    ) -> Vec<Arc<dyn ToolT>> {
+        let tools = self
+            .router
+            .select_tools(policy)
+            .into_iter()
+            .map(|inner| {
+                Arc::new(ReplayTool {
+                    inner,
+                    turn: self.turn.clone(),
+                }) as Arc<dyn Tool>
+            })
+            .collect::<Vec<_>>();
+        info!("serving recorded tool results (tools={})", tools.len());
+        tools_to_adaptors(tools, ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        RecordedLLMResponse, RecordedToolResult, RecordingLLMProvider, RecordingTool, ReplayLLM,
+        ReplayLog, ReplayPlayer, ReplayRecorder, ReplayTool, TurnRecording,
+    };
+    use autoagents_llm::chat::{ChatMessage, ChatProvider, ChatRole, MessageType, StreamChunk};
+    use autoagents_llm::{FunctionCall, LLMProvider, ToolCall};
+    use futures_util::StreamExt;
+    use odyssey_rs_protocol::ToolError;
+    use odyssey_rs_test_utils::{DummyTool, FixedLLM, base_tool_context};
+    use odyssey_rs_tools::Tool;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+    use uuid::Uuid;
+
+    fn user_message(content: &str) -> ChatMessage {
+        ChatMessage {
+            role: ChatRole::User,
+            message_type: MessageType::Text,
+            content: content.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn recorded_turn_replays_llm_and_tool_results() {
+        let recorder = ReplayRecorder::new();
+        recorder.begin_turn(Uuid::new_v4(), "hello");
+
+        let llm = RecordingLLMProvider::new(Arc::new(FixedLLM::new("recorded")), recorder.clone());
+        let response = llm
+            .chat_with_tools(&[user_message("hello")], None, None)
+            .await
+            .expect("chat");
+        assert_eq!(response.text(), Some("recorded".to_string()));
+
+        let tool = RecordingTool::new(Arc::new(DummyTool::new("Read")), recorder.clone());
+        let ctx = base_tool_context();
+        let output = tool
+            .call(&ctx, json!({ "path": "a.txt" }))
+            .await
+            .expect("tool");
+
+        let log = recorder.log();
+        assert_eq!(log.turns.len(), 1);
+        assert_eq!(log.turns[0].llm_responses.len(), 1);
+        assert_eq!(
+            log.turns[0].tool_results,
+            vec![RecordedToolResult {
+                tool_name: "Read".to_string(),
+                args: json!({ "path": "a.txt" }),
+                output: output.clone(),
+                success: true,
+                error_kind: None,
+            }]
+        );
+
+        let player = ReplayPlayer::new(log);
+        let turn = player.next_turn("hello").expect("turn");
+        assert_eq!(player.remaining_turns(), 0);
+
+        let replay_llm: Arc<dyn LLMProvider> = Arc::new(ReplayLLM::new(turn.clone()));
+        let replayed = replay_llm
+            .chat_with_tools(&[user_message("hello")], None, None)
+            .await
+            .expect("replay chat");
+        assert_eq!(replayed.text(), Some("recorded".to_string()));
+        replay_llm
+            .chat_with_tools(&[user_message("hello")], None, None)
+            .await
+            .expect_err("exhausted");
+
+        let replay_tool = ReplayTool {
+            inner: Arc::new(DummyTool::new("Read")),
+            turn,
+        };
+        let replayed_output = replay_tool
+            .call(&ctx, json!({ "path": "a.txt" }))
+            .await
+            .expect("replay tool");
+        assert_eq!(replayed_output, output);
+        let err = replay_tool
+            .call(&ctx, json!({ "path": "a.txt" }))
+            .await
+            .expect_err("no result left");
+        let ToolError::ExecutionFailed(message) = err else {
+            panic!("expected execution failed");
+        };
+        assert_eq!(message, "no recorded result for tool Read");
+    }
+
+    #[tokio::test]
+    async fn streamed_tool_calls_are_recorded_and_replayed() {
+        let response = RecordedLLMResponse {
+            text: Some("checking".to_string()),
+            tool_calls: Some(vec![ToolCall {
+                id: "call_1".to_string(),
+                call_type: "function".to_string(),
+                function: FunctionCall {
+                    name: "Read".to_string(),
+                    arguments: r#"{"path":"a.txt"}"#.to_string(),
+                },
+            }]),
+        };
+        let log = ReplayLog {
+            turns: vec![TurnRecording {
+                turn_id: Uuid::new_v4(),
+                input: "hello".to_string(),
+                llm_responses: vec![response.clone()],
+                tool_results: Vec::new(),
+            }],
+            ..ReplayLog::default()
+        };
+        let turn = ReplayPlayer::new(log).next_turn("hello").expect("turn");
+        let recorder = ReplayRecorder::new();
+        recorder.begin_turn(Uuid::new_v4(), "hello");
+        let llm = RecordingLLMProvider::new(Arc::new(ReplayLLM::new(turn)), recorder.clone());
+
+        let chunks = llm
+            .chat_stream_with_tools(&[user_message("hello")], None, None)
+            .await
+            .expect("stream")
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(chunks.len(), 2);
+        let Ok(StreamChunk::ToolUseComplete { index, tool_call }) = &chunks[1] else {
+            panic!("expected a tool call chunk");
+        };
+        assert_eq!((*index, tool_call.id.as_str()), (0, "call_1"));
+        assert_eq!(recorder.log().turns[0].llm_responses, vec![response]);
+    }
+
+    #[tokio::test]
+    async fn replayed_tools_require_matching_arguments() {
+        let recorder = ReplayRecorder::new();
+        recorder.begin_turn(Uuid::new_v4(), "hello");
+        let tool = RecordingTool::new(Arc::new(DummyTool::new("Read")), recorder.clone());
+        let ctx = base_tool_context();
+        tool.call(&ctx, json!({ "path": "a.txt" }))
+            .await
+            .expect("tool");
+
+        let turn = ReplayPlayer::new(recorder.log())
+            .next_turn("hello")
+            .expect("turn");
+        let replay_tool = ReplayTool {
+            inner: Arc::new(DummyTool::new("Read")),
+            turn,
+        };
+        replay_tool
+            .call(&ctx, json!({ "path": "b.txt" }))
+            .await
+            .expect_err("different arguments");
+        replay_tool
+            .call(&ctx, json!({ "path": "a.txt" }))
+            .await
+            .expect("recorded arguments");
+    }
+
+    #[test]
+    fn replay_log_round_trips_through_file() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("replay").join("session.json");
+        let recorder = ReplayRecorder::with_path(&path);
+        recorder.begin_turn(Uuid::new_v4(), "hello");
+        recorder.persist().expect("persist");
+
+        let loaded = ReplayLog::load(&path).expect("load");
+        assert_eq!(loaded, recorder.log());
+
+        let player = ReplayPlayer::from_path(&path).expect("player");
+        assert_eq!(player.remaining_turns(), 1);
+        player.next_turn("different input").expect("turn");
+        player.next_turn("hello").expect_err("no turns left");
+    }
+}
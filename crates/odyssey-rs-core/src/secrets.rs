@@ -0,0 +1,68 @@
+//! Secret resolution for `api_key_ref` entries in config.
+//!
+//! LLM factories call [`provider_api_key`] for `providers.<name>.api_key_ref`,
+//! and the orchestrator hands tools a resolver for `tools.web.api_key_ref`, so
+//! API keys can live in the environment, a secrets file, or the OS keyring.
+
+use crate::error::OdysseyCoreError;
+use odyssey_rs_config::{OdysseyConfig, SecretsConfig};
+use odyssey_rs_tools::SecretResolver;
+use std::path::PathBuf;
+
+/// Resolver for the backends configured in `secrets`.
+pub fn secret_resolver(config: &SecretsConfig) -> SecretResolver {
+    SecretResolver::new(
+        config.file.as_ref().map(PathBuf::from),
+        config.keyring_service.clone(),
+    )
+}
+
+/// API key for `provider` from its `api_key_ref`, or `None` when none is configured.
+pub fn provider_api_key(
+    config: &OdysseyConfig,
+    provider: &str,
+) -> Result<Option<String>, OdysseyCoreError> {
+    let Some(reference) = config
+        .providers
+        .get(provider)
+        .and_then(|provider| provider.api_key_ref.as_deref())
+    else {
+        return Ok(None);
+    };
+    Ok(Some(secret_resolver(&config.secrets).resolve(reference)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::provider_api_key;
+    use odyssey_rs_config::{OdysseyConfig, ProviderConfig, SecretsConfig};
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn resolves_provider_api_key_refs() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("secrets.json");
+        std::fs::write(&path, r#"{ "openai": "sk-file" }"#).expect("write secrets");
+        let config = OdysseyConfig::builder()
+            .provider(
+                "openai",
+                ProviderConfig {
+                    api_key_ref: Some("file:openai".to_string()),
+                },
+            )
+            .provider("local", ProviderConfig::default())
+            .secrets(SecretsConfig {
+                file: Some(path.to_string_lossy().to_string()),
+                ..SecretsConfig::default()
+            })
+            .build();
+
+        assert_eq!(
+            provider_api_key(&config, "openai").expect("openai"),
+            Some("sk-file".to_string())
+        );
+        assert_eq!(provider_api_key(&config, "local").expect("local"), None);
+        assert_eq!(provider_api_key(&config, "anthropic").expect("unset"), None);
+    }
+}
@@ -1,9 +1,12 @@
 //! Skill discovery and loading for Odyssey.
 
+mod remote;
+
 use async_trait::async_trait;
 use log::{debug, info};
 use odyssey_rs_config::{SettingSource, SkillsConfig};
 use odyssey_rs_protocol::{SkillProvider, SkillSummary, ToolError};
+use remote::SyncMode;
 use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -22,6 +25,8 @@ pub enum SkillError {
     NotFound { name: String },
     #[error("duplicate skill name: {name}")]
     DuplicateName { name: String },
+    #[error("remote skill source {url}: {message}")]
+    Remote { url: String, message: String },
     #[error("yaml error: {0}")]
     Yaml(#[from] serde_yaml::Error),
 }
@@ -47,8 +52,33 @@ struct SkillFrontmatter {
 
 impl SkillStore {
     /// Load skills from configured locations.
+    ///
+    /// Remote sources use their cached checkout and are only fetched when missing.
     pub fn load(config: &SkillsConfig, cwd: &Path) -> Result<Self, SkillError> {
+        Self::load_with_mode(config, cwd, SyncMode::CacheFirst)
+    }
+
+    /// Fetch every remote skill source, then load skills from all locations.
+    ///
+    /// Remotes that cannot be reached fall back to their cached checkout.
+    pub fn refresh(config: &SkillsConfig, cwd: &Path) -> Result<Self, SkillError> {
+        Self::load_with_mode(config, cwd, SyncMode::Refresh)
+    }
+
+    /// Load skills without fetching; remote sources without a cached checkout are skipped.
+    ///
+    /// Used in offline mode.
+    pub fn load_cached(config: &SkillsConfig, cwd: &Path) -> Result<Self, SkillError> {
+        Self::load_with_mode(config, cwd, SyncMode::CacheOnly)
+    }
+
+    fn load_with_mode(
+        config: &SkillsConfig,
+        cwd: &Path,
+        mode: SyncMode,
+    ) -> Result<Self, SkillError> {
         let mut roots = SkillLocator::new(config).roots(cwd);
+        roots.extend(remote::remote_roots(config, cwd, mode)?);
         roots.retain(|root| root.exists());
         roots.sort();
         roots.dedup();
@@ -305,6 +335,10 @@ mod tests {
             paths: vec![root.to_string_lossy().to_string()],
             allow: vec!["*".to_string()],
             deny: Vec::new(),
+            remotes: Vec::new(),
+            cache_dir: None,
+            remote_allow: vec!["*".to_string()],
+            remote_deny: Vec::new(),
         }
     }
 
@@ -391,6 +425,10 @@ Beta description.
             paths: vec![temp.path().to_string_lossy().to_string()],
             allow: vec!["Alpha".to_string()],
             deny: vec!["beta".to_string()],
+            remotes: Vec::new(),
+            cache_dir: None,
+            remote_allow: vec!["*".to_string()],
+            remote_deny: Vec::new(),
         };
         let store = SkillStore::load(&config, temp.path()).expect("store");
         let list = store.list();
@@ -0,0 +1,304 @@
+//! Remote skill sources fetched from git repositories into a local cache.
+//!
+//! Each remote is checked out under `<cache_dir>/<name>-<hash>` using the `git`
+//! CLI, so any transport git supports (https, ssh, local paths) works. Cached
+//! checkouts are reused on load and updated on refresh; when a fetch fails the
+//! cached copy is used instead, and remotes that were never fetched are skipped.
+
+use super::SkillError;
+use crate::orchestrator::prompt_cache::fnv1a;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use log::{debug, info, warn};
+use odyssey_rs_config::{SkillRemote, SkillsConfig};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+/// Whether cached checkouts are reused or updated from the remote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SyncMode {
+    /// Use cached checkouts and only fetch remotes that are missing.
+    CacheFirst,
+    /// Fetch every remote, falling back to the cache when offline.
+    Refresh,
+    /// Never fetch; remotes without a cached checkout are skipped (offline mode).
+    CacheOnly,
+}
+
+/// Fetch configured remotes as needed and return the skill roots they provide.
+pub(super) fn remote_roots(
+    config: &SkillsConfig,
+    cwd: &Path,
+    mode: SyncMode,
+) -> Result<Vec<PathBuf>, SkillError> {
+    if config.remotes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let allow = build_globset(&config.remote_allow)?;
+    let deny = build_globset(&config.remote_deny)?;
+    let cache_dir = cache_dir(config, cwd);
+    let mut roots = Vec::new();
+    for remote in &config.remotes {
+        let allowed = config.remote_allow.is_empty() || allow.is_match(&remote.url);
+        if deny.is_match(&remote.url) || !allowed {
+            warn!(
+                "skipping remote skill source not allowed by config (url={})",
+                remote.url
+            );
+            continue;
+        }
+        reject_option_like(remote)?;
+        let subdir = remote_subdir(remote)?;
+        let checkout = cache_dir.join(cache_key(remote));
+        if !sync_remote(remote, &checkout, mode) {
+            continue;
+        }
+        roots.push(checkout.join(subdir));
+    }
+    Ok(roots)
+}
+
+/// Resolve the skill cache directory.
+fn cache_dir(config: &SkillsConfig, cwd: &Path) -> PathBuf {
+    match config.cache_dir.as_deref() {
+        Some(path) if Path::new(path).is_absolute() => PathBuf::from(path),
+        Some(path) => cwd.join(path),
+        None => directories::UserDirs::new()
+            .map(|dirs| dirs.home_dir().join(".odyssey"))
+            .unwrap_or_else(|| cwd.join(".odyssey"))
+            .join("cache")
+            .join("skills"),
+    }
+}
+
+/// Bring a checkout up to date; returns false when no usable copy exists.
+fn sync_remote(remote: &SkillRemote, checkout: &Path, mode: SyncMode) -> bool {
+    let cached = checkout.join(".git").exists();
+    if !cached && mode == SyncMode::CacheOnly {
+        warn!(
+            "skipping remote skills without a cached checkout in offline mode (url={})",
+            remote.url
+        );
+        return false;
+    }
+    if cached && mode != SyncMode::Refresh {
+        debug!(
+            "using cached remote skills (url={}, path={})",
+            remote.url,
+            checkout.display()
+        );
+        return true;
+    }
+    match fetch_remote(remote, checkout, cached) {
+        Ok(()) => {
+            info!(
+                "fetched remote skills (url={}, ref={})",
+                remote.url,
+                remote.reference.as_deref().unwrap_or("HEAD")
+            );
+            true
+        }
+        Err(err) if cached => {
+            warn!(
+                "failed to refresh remote skills; using cached copy (url={}): {}",
+                remote.url, err
+            );
+            true
+        }
+        Err(err) => {
+            warn!(
+                "failed to fetch remote skills; skipping (url={}): {}",
+                remote.url, err
+            );
+            if let Err(err) = std::fs::remove_dir_all(checkout) {
+                debug!("failed to remove partial checkout: {err}");
+            }
+            false
+        }
+    }
+}
+
+/// Initialize (if needed) and fetch the configured ref into a checkout.
+fn fetch_remote(remote: &SkillRemote, checkout: &Path, cached: bool) -> Result<(), SkillError> {
+    if !cached {
+        std::fs::create_dir_all(checkout)?;
+        run_git(remote, checkout, &["init", "--quiet"])?;
+        run_git(
+            remote,
+            checkout,
+            &["remote", "add", "--end-of-options", "origin", &remote.url],
+        )?;
+    }
+    let reference = remote.reference.as_deref().unwrap_or("HEAD");
+    run_git(
+        remote,
+        checkout,
+        &[
+            "fetch",
+            "--quiet",
+            "--depth",
+            "1",
+            "--end-of-options",
+            "origin",
+            reference,
+        ],
+    )?;
+    run_git(
+        remote,
+        checkout,
+        &["checkout", "--quiet", "--force", "FETCH_HEAD"],
+    )
+}
+
+/// Run a git command inside a checkout.
+fn run_git(remote: &SkillRemote, checkout: &Path, args: &[&str]) -> Result<(), SkillError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(checkout)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|err| SkillError::Remote {
+            url: remote.url.clone(),
+            message: format!("failed to run git: {err}"),
+        })?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(SkillError::Remote {
+        url: remote.url.clone(),
+        message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+/// Reject URLs and refs that git would parse as options.
+fn reject_option_like(remote: &SkillRemote) -> Result<(), SkillError> {
+    let values = [Some(remote.url.as_str()), remote.reference.as_deref()];
+    if let Some(value) = values
+        .into_iter()
+        .flatten()
+        .find(|value| value.starts_with('-'))
+    {
+        return Err(SkillError::Remote {
+            url: remote.url.clone(),
+            message: format!("url and ref must not start with '-': {value}"),
+        });
+    }
+    Ok(())
+}
+
+/// Validate the optional subdirectory so it stays inside the checkout.
+fn remote_subdir(remote: &SkillRemote) -> Result<PathBuf, SkillError> {
+    let subdir = PathBuf::from(remote.subdir.as_deref().unwrap_or_default());
+    let escapes = subdir
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir));
+    if escapes {
+        return Err(SkillError::Remote {
+            url: remote.url.clone(),
+            message: format!("invalid subdir: {}", subdir.display()),
+        });
+    }
+    Ok(subdir)
+}
+
+/// Stable cache directory name for a remote and ref.
+fn cache_key(remote: &SkillRemote) -> String {
+    let name = remote
+        .url
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    let identity = format!(
+        "{}#{}",
+        remote.url,
+        remote.reference.as_deref().unwrap_or_default()
+    );
+    let digest = fnv1a(identity.as_bytes());
+    format!("{name}-{digest:016x}")
+}
+
+/// Compile URL glob patterns.
+fn build_globset(patterns: &[String]) -> Result<GlobSet, SkillError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|err| SkillError::Remote {
+            url: pattern.clone(),
+            message: format!("invalid remote pattern: {err}"),
+        })?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|err| SkillError::Remote {
+        url: String::new(),
+        message: format!("invalid remote patterns: {err}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SyncMode, cache_key, reject_option_like, remote_subdir, sync_remote};
+    use odyssey_rs_config::SkillRemote;
+    use pretty_assertions::assert_eq;
+
+    fn remote(url: &str, subdir: Option<&str>) -> SkillRemote {
+        SkillRemote {
+            url: url.to_string(),
+            reference: Some("main".to_string()),
+            subdir: subdir.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn cache_key_uses_repo_name_and_is_stable() {
+        let first = cache_key(&remote("https://example.com/org/team-skills.git", None));
+        let second = cache_key(&remote("https://example.com/org/team-skills.git", None));
+        let other = cache_key(&remote("git@example.com:org/team-skills.git", None));
+        assert_eq!(first, second);
+        assert_eq!(first.starts_with("team-skills-"), true);
+        assert_eq!(first == other, false);
+    }
+
+    #[test]
+    fn subdir_must_stay_inside_checkout() {
+        assert_eq!(
+            remote_subdir(&remote("https://example.com/skills", Some("skills/public")))
+                .expect("subdir")
+                .to_string_lossy(),
+            "skills/public"
+        );
+        assert!(remote_subdir(&remote("https://example.com/skills", Some("../etc"))).is_err());
+        assert!(remote_subdir(&remote("https://example.com/skills", Some("/etc"))).is_err());
+    }
+
+    #[test]
+    fn option_like_url_and_ref_are_rejected() {
+        let mut source = remote("https://example.com/skills", None);
+        assert!(reject_option_like(&source).is_ok());
+        source.reference = Some("--upload-pack=touch pwned".to_string());
+        assert!(reject_option_like(&source).is_err());
+        let source = remote("--upload-pack=touch pwned", None);
+        assert!(reject_option_like(&source).is_err());
+    }
+
+    #[test]
+    fn cache_only_mode_never_fetches() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let checkout = temp.path().join("skills");
+        let source = remote("https://example.invalid/skills.git", None);
+        assert_eq!(sync_remote(&source, &checkout, SyncMode::CacheOnly), false);
+        assert_eq!(checkout.exists(), false);
+
+        std::fs::create_dir_all(checkout.join(".git")).expect("cached checkout");
+        assert_eq!(sync_remote(&source, &checkout, SyncMode::CacheOnly), true);
+    }
+}
@@ -1,12 +1,16 @@
 //! Session persistence for Odyssey using JSONL rollouts.
+//!
+//! See [`JsonlStateStore`] for the on-disk layout and crash-recovery semantics.
 
+use crate::atomic_file::{quarantine_lines, split_complete, write_atomic};
 use crate::types::SessionId;
 use chrono::{DateTime, Utc};
 use log::{debug, info, warn};
+use odyssey_rs_protocol::TurnId;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use std::fs::{self, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 use uuid::Uuid;
@@ -20,6 +24,15 @@ pub struct MessageRecord {
     pub content: String,
     /// Timestamp for the message.
     pub created_at: DateTime<Utc>,
+    /// Turn that produced the message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<TurnId>,
+    /// Whether a regenerated response replaced this message.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub superseded: bool,
+    /// User who prompted the message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
 }
 
 /// Persisted session record.
@@ -29,12 +42,61 @@ pub struct SessionRecord {
     pub id: SessionId,
     /// Agent id for the session.
     pub agent_id: String,
+    /// User who created the session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
     /// Session creation timestamp.
     pub created_at: DateTime<Utc>,
     /// All messages in the session.
     pub messages: Vec<MessageRecord>,
 }
 
+/// A window of a session's messages, used to page through long transcripts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionPageRecord {
+    /// Session identifier.
+    pub id: SessionId,
+    /// Agent id for the session.
+    pub agent_id: String,
+    /// User who created the session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// Session creation timestamp.
+    pub created_at: DateTime<Utc>,
+    /// Messages in the window, oldest first.
+    pub messages: Vec<MessageRecord>,
+    /// Index of the first message in the window.
+    pub start: usize,
+    /// Total number of messages in the session.
+    pub total: usize,
+}
+
+impl SessionPageRecord {
+    /// Cut the page ending before `cursor` (or at the newest message) out of a full record.
+    pub fn from_record(record: SessionRecord, cursor: Option<usize>, limit: usize) -> Self {
+        let total = record.messages.len();
+        let (start, end) = page_bounds(total, cursor, limit);
+        let mut messages = record.messages;
+        messages.truncate(end);
+        messages.drain(..start);
+        Self {
+            id: record.id,
+            agent_id: record.agent_id,
+            user_id: record.user_id,
+            created_at: record.created_at,
+            messages,
+            start,
+            total,
+        }
+    }
+}
+
+/// Message range `[start, end)` for a page of `limit` messages ending before `cursor`.
+pub(crate) fn page_bounds(total: usize, cursor: Option<usize>, limit: usize) -> (usize, usize) {
+    let end = cursor.unwrap_or(total).min(total);
+    (end.saturating_sub(limit), end)
+}
+
 /// Summary record used for listing sessions.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SessionSummaryRecord {
@@ -42,6 +104,9 @@ pub struct SessionSummaryRecord {
     pub id: SessionId,
     /// Agent id for the session.
     pub agent_id: String,
+    /// User who created the session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
     /// Total number of messages.
     pub message_count: usize,
     /// Session creation timestamp.
@@ -52,11 +117,12 @@ pub struct SessionSummaryRecord {
 
 /// Persistent store abstraction for sessions and messages.
 pub trait StateStore: Send + Sync {
-    /// Record a new session creation.
+    /// Record a new session creation, attributed to `user_id` when known.
     fn record_session(
         &self,
         session_id: SessionId,
         agent_id: &str,
+        user_id: Option<&str>,
         created_at: DateTime<Utc>,
     ) -> Result<(), StateError>;
     /// Append a message to a session.
@@ -65,12 +131,50 @@ pub trait StateStore: Send + Sync {
         session_id: SessionId,
         message: &MessageRecord,
     ) -> Result<(), StateError>;
+    /// Drop every message after the first `message_count`.
+    fn truncate_messages(
+        &self,
+        session_id: SessionId,
+        message_count: usize,
+    ) -> Result<(), StateError>;
+    /// Mark the non-user messages of a turn as superseded.
+    fn mark_superseded(&self, session_id: SessionId, turn_id: TurnId) -> Result<(), StateError>;
     /// Load a session record by id.
     fn load_session(&self, session_id: SessionId) -> Result<Option<SessionRecord>, StateError>;
+    /// Load up to `limit` messages ending before message index `cursor`, or the
+    /// newest messages when `cursor` is `None`.
+    ///
+    /// The default loads the whole session; stores with large transcripts should
+    /// override it to read only the requested window.
+    fn load_session_page(
+        &self,
+        session_id: SessionId,
+        cursor: Option<usize>,
+        limit: usize,
+    ) -> Result<Option<SessionPageRecord>, StateError> {
+        Ok(self
+            .load_session(session_id)?
+            .map(|record| SessionPageRecord::from_record(record, cursor, limit)))
+    }
     /// List all session summaries.
     fn list_sessions(&self) -> Result<Vec<SessionSummaryRecord>, StateError>;
     /// Delete a session and its backing storage.
     fn delete_session(&self, session_id: SessionId) -> Result<bool, StateError>;
+    /// Highest event sequence number reserved for a session, or 0 when none was.
+    fn event_seq(&self, session_id: SessionId) -> Result<u64, StateError> {
+        let _ = session_id;
+        Ok(0)
+    }
+    /// Reserve event sequence numbers up to `through`, so numbering after a restart
+    /// continues past them; stores that keep no sessions across restarts need not override this.
+    fn reserve_event_seq(&self, session_id: SessionId, through: u64) -> Result<(), StateError> {
+        let _ = (session_id, through);
+        Ok(())
+    }
+    /// Persist buffered writes; stores that write through need not override this.
+    fn flush(&self) -> Result<(), StateError> {
+        Ok(())
+    }
 }
 
 /// Errors returned by the state store.
@@ -99,6 +203,8 @@ enum RolloutEvent {
     SessionCreated {
         session_id: SessionId,
         agent_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        user_id: Option<String>,
         created_at: DateTime<Utc>,
     },
     Message {
@@ -106,6 +212,27 @@ enum RolloutEvent {
         role: String,
         content: String,
         created_at: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        turn_id: Option<TurnId>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        superseded: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        user_id: Option<String>,
+    },
+    /// Messages after the first `message_count` were discarded.
+    Truncated {
+        session_id: SessionId,
+        message_count: usize,
+    },
+    /// Non-user messages of `turn_id` were replaced by a regenerated response.
+    Superseded {
+        session_id: SessionId,
+        turn_id: TurnId,
+    },
+    /// Event sequence numbers up to `through` were handed out.
+    EventSeq {
+        session_id: SessionId,
+        through: u64,
     },
 }
 
@@ -113,8 +240,13 @@ enum RolloutEvent {
 struct RolloutState {
     version: Option<u32>,
     agent_id: Option<String>,
+    user_id: Option<String>,
     created_at: Option<DateTime<Utc>>,
     messages: Vec<MessageRecord>,
+    /// Highest reserved event sequence number.
+    event_seq: u64,
+    /// Lines that compaction would drop: discarded messages and bookkeeping events.
+    stale_lines: usize,
 }
 
 impl RolloutState {
@@ -128,24 +260,48 @@ impl RolloutState {
             }
             RolloutEvent::SessionCreated {
                 agent_id,
+                user_id,
                 created_at,
                 ..
             } => {
                 self.agent_id = Some(agent_id);
+                self.user_id = user_id;
                 self.created_at = Some(created_at);
             }
             RolloutEvent::Message {
                 role,
                 content,
                 created_at,
+                turn_id,
+                superseded,
+                user_id,
                 ..
             } => {
                 self.messages.push(MessageRecord {
                     role,
                     content,
                     created_at,
+                    turn_id,
+                    superseded,
+                    user_id,
                 });
             }
+            RolloutEvent::Truncated { message_count, .. } => {
+                self.stale_lines += 1 + self.messages.len().saturating_sub(message_count);
+                self.messages.truncate(message_count);
+            }
+            RolloutEvent::Superseded { turn_id, .. } => {
+                self.stale_lines += 1;
+                for message in &mut self.messages {
+                    if message.turn_id == Some(turn_id) && message.role != "user" {
+                        message.superseded = true;
+                    }
+                }
+            }
+            RolloutEvent::EventSeq { through, .. } => {
+                self.stale_lines += 1;
+                self.event_seq = self.event_seq.max(through);
+            }
         }
         Ok(())
     }
@@ -157,18 +313,102 @@ impl RolloutState {
         Ok(SessionRecord {
             id: session_id,
             agent_id,
+            user_id: self.user_id,
             created_at,
             messages: self.messages,
         })
     }
 }
 
+/// Stale journal lines that trigger compaction once they also outnumber live messages.
+const DEFAULT_COMPACTION_THRESHOLD: usize = 256;
+
+/// Per-session summary stored beside the journal so listing sessions does not replay it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct SessionIndex {
+    agent_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user_id: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    message_count: usize,
+    /// Journal lines that compaction would drop.
+    stale_lines: usize,
+    /// Journal length in bytes when the index was written.
+    journal_len: u64,
+}
+
+impl SessionIndex {
+    fn new(record: &SessionRecord, stale_lines: usize, journal_len: u64) -> Self {
+        Self {
+            agent_id: record.agent_id.clone(),
+            user_id: record.user_id.clone(),
+            created_at: record.created_at,
+            updated_at: record
+                .messages
+                .last()
+                .map(|msg| msg.created_at)
+                .unwrap_or(record.created_at),
+            message_count: record.messages.len(),
+            stale_lines,
+            journal_len,
+        }
+    }
+
+    fn summary(self, id: SessionId) -> SessionSummaryRecord {
+        SessionSummaryRecord {
+            id,
+            agent_id: self.agent_id,
+            user_id: self.user_id,
+            message_count: self.message_count,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// Journal position and replayed flags of a message, without its content.
+struct MessageSlot {
+    offset: u64,
+    turn_id: Option<TurnId>,
+    is_user: bool,
+    superseded: bool,
+}
+
+/// Replayed journal and the number of bytes that hold complete lines.
+struct Journal {
+    rollout: RolloutState,
+    len: u64,
+    /// The last event parsed but lacks its trailing newline.
+    unterminated: bool,
+    /// Complete lines that failed to parse and were skipped.
+    corrupt: usize,
+}
+
 /// JSONL-backed state store implementation.
+///
+/// Each session is an append-only journal (`<id>.jsonl`) plus a small index
+/// (`<id>.index.json`) used by [`StateStore::list_sessions`]. Crash recovery:
+///
+/// - Every event is appended with a single write, and the journal is the source
+///   of truth. A crash mid-append leaves at most one partial last line, which
+///   readers ignore and the next write cuts off. Appends are not fsynced, so a
+///   power loss can drop the latest events but not corrupt earlier ones.
+/// - A complete line that fails to parse (for example after a power loss left
+///   garbage in the file) is skipped instead of failing the load. It is moved to
+///   `<id>.jsonl.corrupt` and the journal is rewritten without it, with a warning
+///   that names the quarantine file.
+/// - The index records the journal length it describes and is replaced
+///   atomically. A missing, unreadable, or stale index is rebuilt from the journal.
+/// - Compaction and quarantine rewrite the journal into a temp file, sync it, and
+///   rename it into place, so a crash leaves either the old or the new journal.
 pub struct JsonlStateStore {
     /// Root directory for session rollouts.
     root: PathBuf,
     /// Serialize write access to rollout files.
     write_lock: Mutex<()>,
+    /// Stale lines that trigger compaction; `None` keeps every line.
+    compaction_threshold: Option<usize>,
 }
 
 impl JsonlStateStore {
@@ -180,22 +420,58 @@ impl JsonlStateStore {
         Ok(Self {
             root,
             write_lock: Mutex::new(()),
+            compaction_threshold: Some(DEFAULT_COMPACTION_THRESHOLD),
         })
     }
 
+    /// Compact a journal once this many lines are stale (discarded messages and
+    /// bookkeeping events) and they outnumber live messages; `None` disables
+    /// compaction and keeps the full history for auditing.
+    pub fn with_compaction_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.compaction_threshold = threshold;
+        self
+    }
+
     /// Build the rollout file path for a session.
     fn rollout_path(&self, session_id: SessionId) -> PathBuf {
         self.root.join(format!("{session_id}.jsonl"))
     }
 
-    /// Append an event to an existing rollout file.
+    /// Build the index file path for a session.
+    fn index_path(&self, session_id: SessionId) -> PathBuf {
+        self.root.join(format!("{session_id}.index.json"))
+    }
+
+    /// Append an event to an existing rollout file and update its index.
     fn write_event(&self, session_id: SessionId, event: &RolloutEvent) -> Result<(), StateError> {
         let _guard = self.write_lock.lock();
+        let mut index = self
+            .current_index(session_id)?
+            .ok_or(StateError::MissingMetadata)?;
         let path = self.rollout_path(session_id);
-        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
-        let line = serde_json::to_string(event)?;
-        writeln!(file, "{line}")?;
-        Ok(())
+        let mut file = OpenOptions::new().append(true).open(&path)?;
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+
+        let index = match event {
+            RolloutEvent::Message { created_at, .. } => {
+                index.message_count += 1;
+                index.updated_at = *created_at;
+                index.journal_len += line.len() as u64;
+                index
+            }
+            _ => {
+                let journal = self
+                    .read_journal(session_id)?
+                    .ok_or(StateError::MissingMetadata)?;
+                let stale_lines = journal.rollout.stale_lines;
+                let record = journal.rollout.finish(session_id)?;
+                SessionIndex::new(&record, stale_lines, journal.len)
+            }
+        };
+        self.write_index(session_id, &index)?;
+        self.compact_if_needed(session_id, &index)
     }
 
     /// Create a new rollout file and write the initial event.
@@ -214,31 +490,350 @@ impl JsonlStateStore {
             .write(true)
             .open(&path)?;
         let header = serde_json::to_string(&RolloutEvent::SchemaVersion { version: 1 })?;
-        writeln!(file, "{header}")?;
         let line = serde_json::to_string(event)?;
-        writeln!(file, "{line}")?;
+        let contents = format!("{header}\n{line}\n");
+        file.write_all(contents.as_bytes())?;
+
+        let mut rollout = RolloutState::default();
+        rollout.apply(RolloutEvent::SchemaVersion { version: 1 })?;
+        rollout.apply(event.clone())?;
+        let record = rollout.finish(session_id)?;
+        self.write_index(
+            session_id,
+            &SessionIndex::new(&record, 0, contents.len() as u64),
+        )
+    }
+
+    /// Replay a journal, ignoring a partial last line left by an interrupted write
+    /// and skipping corrupt complete lines, which are counted for quarantine.
+    fn read_journal(&self, session_id: SessionId) -> Result<Option<Journal>, StateError> {
+        let contents = match fs::read(self.rollout_path(session_id)) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let (lines, tail) = split_complete(&contents);
+        let mut rollout = RolloutState::default();
+        let mut corrupt = 0;
+        for line in lines {
+            match serde_json::from_slice::<RolloutEvent>(line) {
+                Ok(event) => rollout.apply(event)?,
+                Err(err) => {
+                    warn!(
+                        "skipping corrupt journal line (session_id={}, bytes={}): {}",
+                        session_id,
+                        line.len(),
+                        err
+                    );
+                    corrupt += 1;
+                }
+            }
+        }
+
+        let mut journal = Journal {
+            rollout,
+            len: (contents.len() - tail.len()) as u64,
+            unterminated: false,
+            corrupt,
+        };
+        let tail = tail.trim_ascii();
+        if tail.is_empty() {
+            return Ok(Some(journal));
+        }
+        match serde_json::from_slice::<RolloutEvent>(tail) {
+            Ok(event) => {
+                journal.rollout.apply(event)?;
+                journal.len = contents.len() as u64;
+                journal.unterminated = true;
+            }
+            Err(err) => {
+                warn!(
+                    "ignoring partial journal line (session_id={}, bytes={}): {}",
+                    session_id,
+                    tail.len(),
+                    err
+                );
+            }
+        }
+        Ok(Some(journal))
+    }
+
+    /// Move corrupt complete lines to `<id>.jsonl.corrupt` and atomically rewrite
+    /// the journal without them; a partial last line is left for [`Self::current_index`].
+    ///
+    /// Callers hold `write_lock`.
+    fn quarantine_corrupt_lines(&self, session_id: SessionId) -> Result<(), StateError> {
+        let path = self.rollout_path(session_id);
+        let contents = match fs::read(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        let (lines, tail) = split_complete(&contents);
+        let mut kept = Vec::with_capacity(contents.len());
+        let mut corrupt = Vec::new();
+        for line in lines {
+            if serde_json::from_slice::<RolloutEvent>(line).is_ok() {
+                kept.extend_from_slice(line);
+                kept.push(b'\n');
+            } else {
+                corrupt.push(line);
+            }
+        }
+        if corrupt.is_empty() {
+            return Ok(());
+        }
+        kept.extend_from_slice(tail);
+        let quarantine = quarantine_lines(&path, &corrupt)?;
+        write_atomic(&path, &kept)?;
+        warn!(
+            "quarantined corrupt journal lines (session_id={}, lines={}, path={})",
+            session_id,
+            corrupt.len(),
+            quarantine.display()
+        );
         Ok(())
     }
 
-    /// Read and reconstruct a session from its rollout file.
-    fn read_rollout(&self, session_id: SessionId) -> Result<Option<SessionRecord>, StateError> {
+    /// Read one page of a session, keeping only message offsets for the rest of the journal.
+    fn read_page(
+        &self,
+        session_id: SessionId,
+        cursor: Option<usize>,
+        limit: usize,
+    ) -> Result<Option<SessionPageRecord>, StateError> {
+        let file = match File::open(self.rollout_path(session_id)) {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let mut reader = BufReader::new(file);
+        let mut header = RolloutState::default();
+        let mut slots: Vec<MessageSlot> = Vec::new();
+        let mut line = Vec::new();
+        let mut offset = 0u64;
+        let mut corrupt = 0;
+        loop {
+            line.clear();
+            let read = reader.read_until(b'\n', &mut line)?;
+            if read == 0 {
+                break;
+            }
+            let line_offset = offset;
+            offset += read as u64;
+            if line.trim_ascii().is_empty() {
+                continue;
+            }
+            let event: RolloutEvent = match serde_json::from_slice(&line) {
+                Ok(event) => event,
+                // A partial last line from an interrupted write; see `JsonlStateStore`.
+                Err(_) if !line.ends_with(b"\n") => break,
+                Err(err) => {
+                    warn!(
+                        "skipping corrupt journal line (session_id={}, bytes={}): {}",
+                        session_id, read, err
+                    );
+                    corrupt += 1;
+                    continue;
+                }
+            };
+            match event {
+                RolloutEvent::Message {
+                    role,
+                    turn_id,
+                    superseded,
+                    ..
+                } => slots.push(MessageSlot {
+                    offset: line_offset,
+                    turn_id,
+                    is_user: role == "user",
+                    superseded,
+                }),
+                RolloutEvent::Truncated { message_count, .. } => slots.truncate(message_count),
+                RolloutEvent::Superseded { turn_id, .. } => {
+                    for slot in &mut slots {
+                        if slot.turn_id == Some(turn_id) && !slot.is_user {
+                            slot.superseded = true;
+                        }
+                    }
+                }
+                event @ (RolloutEvent::SchemaVersion { .. }
+                | RolloutEvent::SessionCreated { .. }
+                | RolloutEvent::EventSeq { .. }) => header.apply(event)?,
+            }
+        }
+
+        let record = header.finish(session_id)?;
+        let total = slots.len();
+        let (start, end) = page_bounds(total, cursor, limit);
+        let mut messages = Vec::with_capacity(end - start);
+        for slot in &slots[start..end] {
+            reader.seek(SeekFrom::Start(slot.offset))?;
+            line.clear();
+            reader.read_until(b'\n', &mut line)?;
+            if let RolloutEvent::Message {
+                role,
+                content,
+                created_at,
+                turn_id,
+                user_id,
+                ..
+            } = serde_json::from_slice(&line)?
+            {
+                messages.push(MessageRecord {
+                    role,
+                    content,
+                    created_at,
+                    turn_id,
+                    superseded: slot.superseded,
+                    user_id,
+                });
+            }
+        }
+        if corrupt > 0 {
+            let _guard = self.write_lock.lock();
+            self.quarantine_corrupt_lines(session_id)?;
+        }
+        Ok(Some(SessionPageRecord {
+            id: session_id,
+            agent_id: record.agent_id,
+            user_id: record.user_id,
+            created_at: record.created_at,
+            messages,
+            start,
+            total,
+        }))
+    }
+
+    /// Load the session index, rebuilding it from the journal when it is stale.
+    ///
+    /// Callers hold `write_lock`; a rebuild also cuts off a partial last line.
+    fn current_index(&self, session_id: SessionId) -> Result<Option<SessionIndex>, StateError> {
         let path = self.rollout_path(session_id);
-        if !path.exists() {
+        let mut journal_len = match fs::metadata(&path) {
+            Ok(metadata) => metadata.len(),
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(index) = self.read_index(session_id)
+            && index.journal_len == journal_len
+        {
+            return Ok(Some(index));
+        }
+
+        debug!("rebuilding session index (session_id={})", session_id);
+        let Some(mut journal) = self.read_journal(session_id)? else {
             return Ok(None);
+        };
+        if journal.corrupt > 0 {
+            self.quarantine_corrupt_lines(session_id)?;
+            journal_len = fs::metadata(&path)?.len();
+            journal = self
+                .read_journal(session_id)?
+                .ok_or(StateError::MissingMetadata)?;
         }
-        let file = OpenOptions::new().read(true).open(&path)?;
-        let reader = BufReader::new(file);
-        let mut rollout = RolloutState::default();
+        let mut len = journal.len;
+        if len < journal_len {
+            warn!(
+                "dropping partial journal line (session_id={}, bytes={})",
+                session_id,
+                journal_len - len
+            );
+            OpenOptions::new().write(true).open(&path)?.set_len(len)?;
+        }
+        if journal.unterminated {
+            OpenOptions::new()
+                .append(true)
+                .open(&path)?
+                .write_all(b"\n")?;
+            len += 1;
+        }
+        let stale_lines = journal.rollout.stale_lines;
+        let record = journal.rollout.finish(session_id)?;
+        let index = SessionIndex::new(&record, stale_lines, len);
+        self.write_index(session_id, &index)?;
+        Ok(Some(index))
+    }
 
-        for line in reader.lines() {
-            let line = line?;
-            if line.trim().is_empty() {
-                continue;
+    /// Read the stored index, treating an unreadable one as missing.
+    fn read_index(&self, session_id: SessionId) -> Option<SessionIndex> {
+        let contents = fs::read_to_string(self.index_path(session_id)).ok()?;
+        match serde_json::from_str(&contents) {
+            Ok(index) => Some(index),
+            Err(err) => {
+                warn!(
+                    "ignoring unreadable session index (session_id={}): {}",
+                    session_id, err
+                );
+                None
             }
-            let event: RolloutEvent = serde_json::from_str(&line)?;
-            rollout.apply(event)?;
         }
-        Ok(Some(rollout.finish(session_id)?))
+    }
+
+    /// Replace the index atomically.
+    fn write_index(&self, session_id: SessionId, index: &SessionIndex) -> Result<(), StateError> {
+        write_atomic(&self.index_path(session_id), &serde_json::to_vec(index)?)?;
+        Ok(())
+    }
+
+    /// Compact the journal when stale lines pass the threshold and outnumber live messages.
+    fn compact_if_needed(
+        &self,
+        session_id: SessionId,
+        index: &SessionIndex,
+    ) -> Result<(), StateError> {
+        let Some(threshold) = self.compaction_threshold else {
+            return Ok(());
+        };
+        if index.stale_lines < threshold.max(1) || index.stale_lines < index.message_count {
+            return Ok(());
+        }
+        let rollout = self
+            .read_journal(session_id)?
+            .ok_or(StateError::MissingMetadata)?
+            .rollout;
+        let event_seq = rollout.event_seq;
+        let record = rollout.finish(session_id)?;
+        let mut contents = String::new();
+        let events = [
+            RolloutEvent::SchemaVersion { version: 1 },
+            RolloutEvent::SessionCreated {
+                session_id,
+                agent_id: record.agent_id.clone(),
+                user_id: record.user_id.clone(),
+                created_at: record.created_at,
+            },
+        ]
+        .into_iter()
+        .chain((event_seq > 0).then_some(RolloutEvent::EventSeq {
+            session_id,
+            through: event_seq,
+        }))
+        .chain(record.messages.iter().map(|message| RolloutEvent::Message {
+            session_id,
+            role: message.role.clone(),
+            content: message.content.clone(),
+            created_at: message.created_at,
+            turn_id: message.turn_id,
+            superseded: message.superseded,
+            user_id: message.user_id.clone(),
+        }));
+        for event in events {
+            contents.push_str(&serde_json::to_string(&event)?);
+            contents.push('\n');
+        }
+
+        write_atomic(&self.rollout_path(session_id), contents.as_bytes())?;
+        info!(
+            "compacted session journal (session_id={}, messages={}, dropped_lines={})",
+            session_id,
+            record.messages.len(),
+            index.stale_lines
+        );
+        self.write_index(
+            session_id,
+            &SessionIndex::new(&record, 0, contents.len() as u64),
+        )
     }
 }
 
@@ -248,15 +843,19 @@ impl StateStore for JsonlStateStore {
         &self,
         session_id: SessionId,
         agent_id: &str,
+        user_id: Option<&str>,
         created_at: DateTime<Utc>,
     ) -> Result<(), StateError> {
         info!(
-            "recording session creation (session_id={}, agent_id={})",
-            session_id, agent_id
+            "recording session creation (session_id={}, agent_id={}, user_id={})",
+            session_id,
+            agent_id,
+            user_id.unwrap_or("-")
         );
         let event = RolloutEvent::SessionCreated {
             session_id,
             agent_id: agent_id.to_string(),
+            user_id: user_id.map(str::to_string),
             created_at,
         };
         self.write_new_rollout(session_id, &event)
@@ -279,16 +878,92 @@ impl StateStore for JsonlStateStore {
             role: message.role.clone(),
             content: message.content.clone(),
             created_at: message.created_at,
+            turn_id: message.turn_id,
+            superseded: message.superseded,
+            user_id: message.user_id.clone(),
+        };
+        self.write_event(session_id, &event)
+    }
+
+    /// Append a truncation event; discarded message events are kept until the
+    /// journal is compacted.
+    fn truncate_messages(
+        &self,
+        session_id: SessionId,
+        message_count: usize,
+    ) -> Result<(), StateError> {
+        info!(
+            "truncating session rollout (session_id={}, message_count={})",
+            session_id, message_count
+        );
+        let event = RolloutEvent::Truncated {
+            session_id,
+            message_count,
+        };
+        self.write_event(session_id, &event)
+    }
+
+    /// Append a superseded event for a regenerated turn.
+    fn mark_superseded(&self, session_id: SessionId, turn_id: TurnId) -> Result<(), StateError> {
+        info!(
+            "marking turn superseded (session_id={}, turn_id={})",
+            session_id, turn_id
+        );
+        let event = RolloutEvent::Superseded {
+            session_id,
+            turn_id,
         };
         self.write_event(session_id, &event)
     }
 
-    /// Load a session from the rollout file.
+    /// Replay the journal for its last event sequence reservation.
+    fn event_seq(&self, session_id: SessionId) -> Result<u64, StateError> {
+        Ok(self
+            .read_journal(session_id)?
+            .map_or(0, |journal| journal.rollout.event_seq))
+    }
+
+    /// Append a reservation line; sessions without a journal are skipped.
+    fn reserve_event_seq(&self, session_id: SessionId, through: u64) -> Result<(), StateError> {
+        if !self.rollout_path(session_id).exists() {
+            return Ok(());
+        }
+        debug!(
+            "reserving event sequence numbers (session_id={}, through={})",
+            session_id, through
+        );
+        self.write_event(
+            session_id,
+            &RolloutEvent::EventSeq {
+                session_id,
+                through,
+            },
+        )
+    }
+
+    /// Load a session from the rollout file, quarantining corrupt lines.
     fn load_session(&self, session_id: SessionId) -> Result<Option<SessionRecord>, StateError> {
-        self.read_rollout(session_id)
+        let Some(journal) = self.read_journal(session_id)? else {
+            return Ok(None);
+        };
+        if journal.corrupt > 0 {
+            let _guard = self.write_lock.lock();
+            self.quarantine_corrupt_lines(session_id)?;
+        }
+        Ok(Some(journal.rollout.finish(session_id)?))
     }
 
-    /// List all sessions by scanning rollout files.
+    /// Load a page by scanning the journal and reading only the requested messages.
+    fn load_session_page(
+        &self,
+        session_id: SessionId,
+        cursor: Option<usize>,
+        limit: usize,
+    ) -> Result<Option<SessionPageRecord>, StateError> {
+        self.read_page(session_id, cursor, limit)
+    }
+
+    /// List all sessions from their indexes, rebuilding stale ones.
     fn list_sessions(&self) -> Result<Vec<SessionSummaryRecord>, StateError> {
         let mut summaries = Vec::new();
         for entry in fs::read_dir(&self.root)? {
@@ -305,28 +980,24 @@ impl StateStore for JsonlStateStore {
                 Ok(id) => id,
                 Err(_) => continue,
             };
-            if let Some(record) = self.read_rollout(session_id)? {
-                let updated_at = record
-                    .messages
-                    .last()
-                    .map(|msg| msg.created_at)
-                    .unwrap_or(record.created_at);
-                summaries.push(SessionSummaryRecord {
-                    id: record.id,
-                    agent_id: record.agent_id,
-                    message_count: record.messages.len(),
-                    created_at: record.created_at,
-                    updated_at,
-                });
+            let _guard = self.write_lock.lock();
+            if let Some(index) = self.current_index(session_id)? {
+                summaries.push(index.summary(session_id));
             }
         }
         summaries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
         Ok(summaries)
     }
 
-    /// Delete the rollout file for a session.
+    /// Delete the rollout file and index for a session.
     fn delete_session(&self, session_id: SessionId) -> Result<bool, StateError> {
+        let _guard = self.write_lock.lock();
         let path = self.rollout_path(session_id);
+        if let Err(err) = fs::remove_file(self.index_path(session_id))
+            && err.kind() != ErrorKind::NotFound
+        {
+            return Err(err.into());
+        }
         if path.exists() {
             info!("deleting session rollout (session_id={})", session_id);
             fs::remove_file(path)?;
@@ -340,9 +1011,14 @@ impl StateStore for JsonlStateStore {
 
 #[cfg(test)]
 mod tests {
-    use super::{JsonlStateStore, MessageRecord, SessionRecord, SessionSummaryRecord, StateStore};
+    use super::{
+        JsonlStateStore, MessageRecord, SessionPageRecord, SessionRecord, SessionSummaryRecord,
+        StateStore,
+    };
     use chrono::Utc;
     use pretty_assertions::assert_eq;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
     use tempfile::tempdir;
     use uuid::Uuid;
 
@@ -353,13 +1029,16 @@ mod tests {
         let session_id = Uuid::new_v4();
         let created_at = Utc::now();
         store
-            .record_session(session_id, "agent", created_at)
+            .record_session(session_id, "agent", None, created_at)
             .expect("record session");
 
         let message = MessageRecord {
             role: "user".to_string(),
             content: "hello".to_string(),
             created_at,
+            turn_id: None,
+            superseded: false,
+            user_id: None,
         };
         store
             .append_message(session_id, &message)
@@ -372,6 +1051,7 @@ mod tests {
         let expected = SessionRecord {
             id: session_id,
             agent_id: "agent".to_string(),
+            user_id: None,
             created_at,
             messages: vec![message.clone()],
         };
@@ -381,6 +1061,7 @@ mod tests {
         let expected_summary = SessionSummaryRecord {
             id: session_id,
             agent_id: "agent".to_string(),
+            user_id: None,
             message_count: 1,
             created_at,
             updated_at: created_at,
@@ -393,4 +1074,426 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn jsonl_state_store_keeps_user_attribution() {
+        let temp = tempdir().expect("tempdir");
+        let store = JsonlStateStore::new(temp.path()).expect("store");
+        let session_id = Uuid::new_v4();
+        let created_at = Utc::now();
+        store
+            .record_session(session_id, "agent", Some("alice"), created_at)
+            .expect("record session");
+        for (role, user_id) in [("user", Some("bob")), ("assistant", None)] {
+            let message = MessageRecord {
+                role: role.to_string(),
+                content: "text".to_string(),
+                created_at,
+                turn_id: None,
+                superseded: false,
+                user_id: user_id.map(str::to_string),
+            };
+            store
+                .append_message(session_id, &message)
+                .expect("append message");
+        }
+
+        let record = store
+            .load_session(session_id)
+            .expect("load")
+            .expect("record");
+        assert_eq!(record.user_id.as_deref(), Some("alice"));
+        assert_eq!(
+            record
+                .messages
+                .iter()
+                .map(|message| message.user_id.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("bob"), None]
+        );
+        assert_eq!(
+            store.list_sessions().expect("summaries")[0]
+                .user_id
+                .as_deref(),
+            Some("alice")
+        );
+    }
+
+    #[test]
+    fn jsonl_state_store_replays_truncation() {
+        let temp = tempdir().expect("tempdir");
+        let store = JsonlStateStore::new(temp.path()).expect("store");
+        let session_id = Uuid::new_v4();
+        let created_at = Utc::now();
+        store
+            .record_session(session_id, "agent", None, created_at)
+            .expect("record session");
+        let turn_id = Uuid::new_v4();
+        for content in ["first", "second", "third"] {
+            let message = MessageRecord {
+                role: "user".to_string(),
+                content: content.to_string(),
+                created_at,
+                turn_id: Some(turn_id),
+                superseded: false,
+                user_id: None,
+            };
+            store
+                .append_message(session_id, &message)
+                .expect("append message");
+        }
+        store.truncate_messages(session_id, 1).expect("truncate");
+
+        let record = store
+            .load_session(session_id)
+            .expect("load")
+            .expect("record");
+        assert_eq!(record.messages.len(), 1);
+        assert_eq!(record.messages[0].content, "first");
+        assert_eq!(record.messages[0].turn_id, Some(turn_id));
+        assert_eq!(
+            store.list_sessions().expect("summaries")[0].message_count,
+            1
+        );
+    }
+
+    #[test]
+    fn jsonl_state_store_replays_superseded_turns() {
+        let temp = tempdir().expect("tempdir");
+        let store = JsonlStateStore::new(temp.path()).expect("store");
+        let session_id = Uuid::new_v4();
+        let created_at = Utc::now();
+        store
+            .record_session(session_id, "agent", None, created_at)
+            .expect("record session");
+        let turn_id = Uuid::new_v4();
+        for (role, content) in [("user", "question"), ("assistant", "first answer")] {
+            let message = MessageRecord {
+                role: role.to_string(),
+                content: content.to_string(),
+                created_at,
+                turn_id: Some(turn_id),
+                superseded: false,
+                user_id: None,
+            };
+            store
+                .append_message(session_id, &message)
+                .expect("append message");
+        }
+        store
+            .mark_superseded(session_id, turn_id)
+            .expect("mark superseded");
+        let message = MessageRecord {
+            role: "assistant".to_string(),
+            content: "second answer".to_string(),
+            created_at,
+            turn_id: Some(turn_id),
+            superseded: false,
+            user_id: None,
+        };
+        store
+            .append_message(session_id, &message)
+            .expect("append message");
+
+        let record = store
+            .load_session(session_id)
+            .expect("load")
+            .expect("record");
+        let superseded = record
+            .messages
+            .iter()
+            .map(|message| (message.content.as_str(), message.superseded))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            superseded,
+            vec![
+                ("question", false),
+                ("first answer", true),
+                ("second answer", false)
+            ]
+        );
+    }
+
+    fn user_message(content: &str) -> MessageRecord {
+        MessageRecord {
+            role: "user".to_string(),
+            content: content.to_string(),
+            created_at: Utc::now(),
+            turn_id: None,
+            superseded: false,
+            user_id: None,
+        }
+    }
+
+    #[test]
+    fn jsonl_state_store_recovers_from_torn_writes() {
+        let temp = tempdir().expect("tempdir");
+        let store = JsonlStateStore::new(temp.path()).expect("store");
+        let session_id = Uuid::new_v4();
+        store
+            .record_session(session_id, "agent", None, Utc::now())
+            .expect("record session");
+        store
+            .append_message(session_id, &user_message("first"))
+            .expect("append message");
+
+        let journal = temp.path().join(format!("{session_id}.jsonl"));
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&journal)
+            .expect("open journal");
+        file.write_all(br#"{"type":"message","session_id""#)
+            .expect("torn write");
+        fs::remove_file(temp.path().join(format!("{session_id}.index.json")))
+            .expect("remove index");
+
+        let record = store
+            .load_session(session_id)
+            .expect("load")
+            .expect("record");
+        assert_eq!(record.messages.len(), 1);
+        assert_eq!(
+            store.list_sessions().expect("summaries")[0].message_count,
+            1
+        );
+
+        store
+            .append_message(session_id, &user_message("second"))
+            .expect("append after torn write");
+        let record = store
+            .load_session(session_id)
+            .expect("load")
+            .expect("record");
+        assert_eq!(
+            record
+                .messages
+                .iter()
+                .map(|message| message.content.as_str())
+                .collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+        assert_eq!(
+            store.list_sessions().expect("summaries")[0].message_count,
+            2
+        );
+    }
+
+    #[test]
+    fn jsonl_state_store_quarantines_corrupt_lines() {
+        let temp = tempdir().expect("tempdir");
+        let store = JsonlStateStore::new(temp.path()).expect("store");
+        let session_id = Uuid::new_v4();
+        store
+            .record_session(session_id, "agent", None, Utc::now())
+            .expect("record session");
+        store
+            .append_message(session_id, &user_message("first"))
+            .expect("append message");
+
+        let journal = temp.path().join(format!("{session_id}.jsonl"));
+        let corrupt = temp.path().join(format!("{session_id}.jsonl.corrupt"));
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&journal)
+            .expect("open journal");
+        file.write_all(b"\0\0\0garbage\n").expect("corrupt line");
+        drop(file);
+
+        let page = store
+            .load_session_page(session_id, None, 10)
+            .expect("load page")
+            .expect("page");
+        assert_eq!(page.total, 1);
+        assert_eq!(
+            fs::read(&corrupt).expect("quarantine"),
+            b"\0\0\0garbage\n".to_vec()
+        );
+        assert_eq!(
+            fs::read_to_string(&journal)
+                .expect("journal")
+                .contains("garbage"),
+            false
+        );
+
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&journal)
+            .expect("open journal");
+        file.write_all(b"{\"type\":\"unknown\"}\n")
+            .expect("corrupt line");
+        drop(file);
+        let record = store
+            .load_session(session_id)
+            .expect("load")
+            .expect("record");
+        assert_eq!(record.messages.len(), 1);
+
+        store
+            .append_message(session_id, &user_message("second"))
+            .expect("append after quarantine");
+        let record = store
+            .load_session(session_id)
+            .expect("load")
+            .expect("record");
+        assert_eq!(
+            record
+                .messages
+                .iter()
+                .map(|message| message.content.as_str())
+                .collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+        assert_eq!(
+            store.list_sessions().expect("summaries")[0].message_count,
+            2
+        );
+        assert_eq!(
+            fs::read_to_string(&corrupt)
+                .expect("quarantine")
+                .ends_with("{\"type\":\"unknown\"}\n"),
+            true
+        );
+    }
+
+    #[test]
+    fn jsonl_state_store_compacts_stale_lines() {
+        let temp = tempdir().expect("tempdir");
+        let store = JsonlStateStore::new(temp.path())
+            .expect("store")
+            .with_compaction_threshold(Some(2));
+        let session_id = Uuid::new_v4();
+        store
+            .record_session(session_id, "agent", None, Utc::now())
+            .expect("record session");
+        for content in ["first", "second", "third"] {
+            store
+                .append_message(session_id, &user_message(content))
+                .expect("append message");
+        }
+        store.truncate_messages(session_id, 1).expect("truncate");
+
+        let journal = temp.path().join(format!("{session_id}.jsonl"));
+        let lines = fs::read_to_string(&journal).expect("read journal");
+        assert_eq!(lines.lines().count(), 3);
+        store
+            .append_message(session_id, &user_message("fourth"))
+            .expect("append message");
+
+        let record = store
+            .load_session(session_id)
+            .expect("load")
+            .expect("record");
+        assert_eq!(
+            record
+                .messages
+                .iter()
+                .map(|message| message.content.as_str())
+                .collect::<Vec<_>>(),
+            vec!["first", "fourth"]
+        );
+        assert_eq!(
+            store.list_sessions().expect("summaries")[0].message_count,
+            2
+        );
+    }
+
+    #[test]
+    fn jsonl_state_store_keeps_event_seq_through_compaction() {
+        let temp = tempdir().expect("tempdir");
+        let store = JsonlStateStore::new(temp.path())
+            .expect("store")
+            .with_compaction_threshold(Some(2));
+        let session_id = Uuid::new_v4();
+        assert_eq!(store.event_seq(session_id).expect("missing session"), 0);
+        store
+            .reserve_event_seq(session_id, 10)
+            .expect("skip missing session");
+        store
+            .record_session(session_id, "agent", None, Utc::now())
+            .expect("record session");
+        store.reserve_event_seq(session_id, 1024).expect("reserve");
+        assert_eq!(store.event_seq(session_id).expect("event seq"), 1024);
+
+        // The second reservation line passes the threshold and compacts the journal.
+        store.reserve_event_seq(session_id, 2048).expect("reserve");
+        let journal = temp.path().join(format!("{session_id}.jsonl"));
+        let lines = fs::read_to_string(&journal).expect("read journal");
+        assert_eq!(lines.lines().count(), 3);
+        assert_eq!(store.event_seq(session_id).expect("event seq"), 2048);
+    }
+
+    #[test]
+    fn jsonl_state_store_pages_messages() {
+        let temp = tempdir().expect("tempdir");
+        let store = JsonlStateStore::new(temp.path()).expect("store");
+        let session_id = Uuid::new_v4();
+        store
+            .record_session(session_id, "agent", None, Utc::now())
+            .expect("record session");
+        for content in ["one", "two", "dropped"] {
+            store
+                .append_message(session_id, &user_message(content))
+                .expect("append message");
+        }
+        store.truncate_messages(session_id, 2).expect("truncate");
+        let turn_id = Uuid::new_v4();
+        for content in ["three", "four", "five"] {
+            let message = MessageRecord {
+                role: "assistant".to_string(),
+                turn_id: Some(turn_id),
+                ..user_message(content)
+            };
+            store
+                .append_message(session_id, &message)
+                .expect("append message");
+        }
+        store
+            .mark_superseded(session_id, turn_id)
+            .expect("mark superseded");
+
+        let contents = |page: &SessionPageRecord| {
+            page.messages
+                .iter()
+                .map(|message| (message.content.clone(), message.superseded))
+                .collect::<Vec<_>>()
+        };
+        let newest = store
+            .load_session_page(session_id, None, 2)
+            .expect("page")
+            .expect("record");
+        assert_eq!(newest.agent_id, "agent");
+        assert_eq!((newest.start, newest.total), (3, 5));
+        assert_eq!(
+            contents(&newest),
+            vec![("four".to_string(), true), ("five".to_string(), true)]
+        );
+
+        let older = store
+            .load_session_page(session_id, Some(newest.start), 2)
+            .expect("page")
+            .expect("record");
+        assert_eq!(older.start, 1);
+        assert_eq!(
+            contents(&older),
+            vec![("two".to_string(), false), ("three".to_string(), true)]
+        );
+
+        let full = store
+            .load_session(session_id)
+            .expect("load")
+            .expect("record");
+        let expected = SessionPageRecord::from_record(full, Some(1), 2);
+        let oldest = store
+            .load_session_page(session_id, Some(1), 2)
+            .expect("page")
+            .expect("record");
+        assert_eq!(oldest, expected);
+        assert_eq!(contents(&oldest), vec![("one".to_string(), false)]);
+        assert_eq!(
+            store
+                .load_session_page(Uuid::new_v4(), None, 2)
+                .expect("missing"),
+            None
+        );
+    }
 }
@@ -0,0 +1,117 @@
+//! Ready-made agent profiles shipped with the crate.
+//!
+//! Each template bundles a system prompt with a tool allow list, so
+//! `Orchestrator::register_template_agent("code-reviewer")` gives new users a
+//! useful specialist without writing prompts.
+
+use odyssey_rs_config::ToolPolicy;
+
+/// Built-in agent profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgentTemplate {
+    /// Template name, also used as the registered agent id.
+    pub name: &'static str,
+    /// One-line summary shown in agent listings.
+    pub description: &'static str,
+    /// System prompt for the agent.
+    pub prompt: &'static str,
+    /// Tools the agent may use; empty allows every tool.
+    pub tools: &'static [&'static str],
+}
+
+impl AgentTemplate {
+    /// Tool policy allowing the template's tools.
+    pub fn tool_policy(&self) -> ToolPolicy {
+        if self.tools.is_empty() {
+            return ToolPolicy::allow_all();
+        }
+        ToolPolicy {
+            allow: self.tools.iter().map(|tool| tool.to_string()).collect(),
+            deny: Vec::new(),
+        }
+    }
+}
+
+/// Read-only tools for exploring a workspace.
+const READ_TOOLS: [&str; 6] = ["Read", "Glob", "Grep", "Outline", "GitStatus", "GitDiff"];
+
+/// Every template shipped with the crate.
+pub const AGENT_TEMPLATES: &[AgentTemplate] = &[
+    AgentTemplate {
+        name: "code-reviewer",
+        description: "Reviews diffs for bugs, security issues, and missing tests without editing files.",
+        prompt: include_str!("code-reviewer.md"),
+        tools: &READ_TOOLS,
+    },
+    AgentTemplate {
+        name: "test-writer",
+        description: "Writes tests that follow the project's existing test style and runs them.",
+        prompt: include_str!("test-writer.md"),
+        tools: &[],
+    },
+    AgentTemplate {
+        name: "refactorer",
+        description: "Restructures code without changing behavior and verifies with the test suite.",
+        prompt: include_str!("refactorer.md"),
+        tools: &[],
+    },
+    AgentTemplate {
+        name: "doc-writer",
+        description: "Writes and updates documentation and doc comments from the code.",
+        prompt: include_str!("doc-writer.md"),
+        tools: &[
+            "Read",
+            "Glob",
+            "Grep",
+            "Outline",
+            "GitStatus",
+            "GitDiff",
+            "Write",
+            "Edit",
+        ],
+    },
+];
+
+/// Look up a built-in template by name.
+pub fn agent_template(name: &str) -> Option<&'static AgentTemplate> {
+    AGENT_TEMPLATES
+        .iter()
+        .find(|template| template.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AGENT_TEMPLATES, agent_template};
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    #[test]
+    fn templates_are_complete_and_unique() {
+        let names = AGENT_TEMPLATES
+            .iter()
+            .map(|template| template.name)
+            .collect::<HashSet<_>>();
+        assert_eq!(names.len(), AGENT_TEMPLATES.len());
+        for template in AGENT_TEMPLATES {
+            assert_eq!(template.prompt.trim().is_empty(), false);
+            assert_eq!(template.description.is_empty(), false);
+        }
+    }
+
+    #[test]
+    fn reviewer_is_read_only() {
+        let policy = agent_template("code-reviewer")
+            .expect("template")
+            .tool_policy();
+        assert_eq!(policy.allow.contains(&"Read".to_string()), true);
+        assert_eq!(policy.allow.contains(&"Write".to_string()), false);
+        assert_eq!(
+            agent_template("test-writer")
+                .expect("template")
+                .tool_policy()
+                .allow,
+            vec!["*".to_string()]
+        );
+        assert_eq!(agent_template("architect"), None);
+    }
+}
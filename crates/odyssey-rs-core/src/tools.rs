@@ -1,23 +1,46 @@
 //! Tool routing and policy filtering for orchestrator usage.
 
+use crate::permissions::NETWORK_TOOLS;
 use autoagents_core::tool::ToolT;
-use log::debug;
+use log::{debug, info};
 use odyssey_rs_config::ToolPolicy;
-use odyssey_rs_tools::{ToolContext, ToolRegistry, ToolSpec, tools_to_adaptors};
+use odyssey_rs_tools::{
+    Tool, ToolContext, ToolRegistry, ToolScheduler, ToolSpec, scheduled_adaptors,
+};
 use parking_lot::RwLock;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Router that filters and adapts tools based on policy.
 #[derive(Clone)]
 pub struct ToolRouter {
     /// Registry of available tools.
     registry: ToolRegistry,
+    /// Scheduler shared by every turn so limits hold across sessions.
+    scheduler: Arc<ToolScheduler>,
+    /// Hide network tools from every agent; shared by clones.
+    offline: Arc<AtomicBool>,
 }
 
 impl ToolRouter {
     /// Create a new router around the given registry.
     pub fn new(registry: ToolRegistry) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            scheduler: Arc::new(ToolScheduler::new()),
+            offline: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Hide or expose network tools such as `WebFetch` and `HttpRequest`.
+    pub fn set_offline(&self, offline: bool) {
+        info!("tool router offline mode (enabled={offline})");
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
+    /// Return the scheduler enforcing tool concurrency limits.
+    pub fn scheduler(&self) -> Arc<ToolScheduler> {
+        self.scheduler.clone()
     }
 
     #[allow(dead_code)]
@@ -26,24 +49,12 @@ impl ToolRouter {
         self.registry.list()
     }
 
-    #[allow(dead_code)]
     /// Build tool specs for an agent policy without adaptation.
     pub fn specs_for_agent(&self, policy: &ToolPolicy) -> Vec<ToolSpec> {
-        let allow = &policy.allow;
-        let deny = &policy.deny;
         self.registry
             .all()
             .into_iter()
-            .filter(|tool| {
-                let name = tool.name();
-                if deny.iter().any(|entry| entry == name) {
-                    return false;
-                }
-                if allow.is_empty() || allow.iter().any(|entry| entry == "*") {
-                    return true;
-                }
-                allow.iter().any(|entry| entry == name)
-            })
+            .filter(|tool| self.allows(policy, tool.name()))
             .map(|tool| tool.spec())
             .collect()
     }
@@ -54,22 +65,18 @@ impl ToolRouter {
         policy: &ToolPolicy,
         ctx: Arc<RwLock<ToolContext>>,
     ) -> Vec<Arc<dyn ToolT>> {
+        scheduled_adaptors(self.select_tools(policy), ctx, self.scheduler.clone())
+    }
+
+    /// Select registry tools allowed by the agent policy.
+    pub fn select_tools(&self, policy: &ToolPolicy) -> Vec<Arc<dyn Tool>> {
         let allow = &policy.allow;
         let deny = &policy.deny;
         let tools = self
             .registry
             .all()
             .into_iter()
-            .filter(|tool| {
-                let name = tool.name();
-                if deny.iter().any(|entry| entry == name) {
-                    return false;
-                }
-                if allow.is_empty() || allow.iter().any(|entry| entry == "*") {
-                    return true;
-                }
-                allow.iter().any(|entry| entry == name)
-            })
+            .filter(|tool| self.allows(policy, tool.name()))
             .collect::<Vec<_>>();
         debug!(
             "tool selection resolved (allowed={}, denied={}, selected={})",
@@ -77,7 +84,21 @@ impl ToolRouter {
             deny.len(),
             tools.len()
         );
-        tools_to_adaptors(tools, ctx)
+        tools
+    }
+
+    /// Whether `policy` exposes the tool `name`; network tools are hidden offline.
+    fn allows(&self, policy: &ToolPolicy, name: &str) -> bool {
+        if self.offline.load(Ordering::SeqCst) && NETWORK_TOOLS.contains(&name) {
+            return false;
+        }
+        if policy.deny.iter().any(|entry| entry == name) {
+            return false;
+        }
+        if policy.allow.is_empty() || policy.allow.iter().any(|entry| entry == "*") {
+            return true;
+        }
+        policy.allow.iter().any(|entry| entry == name)
     }
 }
 
@@ -143,4 +164,31 @@ mod tests {
         assert_eq!(specs.len(), 1);
         assert_eq!(specs[0].name, "Read".to_string());
     }
+
+    #[test]
+    fn tool_router_hides_network_tools_offline() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(DummyTool::new("Read")));
+        registry.register(Arc::new(DummyTool::new("WebFetch")));
+        registry.register(Arc::new(DummyTool::new("HttpRequest")));
+        let router = ToolRouter::new(registry);
+        let clone = router.clone();
+        let names = |router: &ToolRouter| {
+            let mut names = router
+                .select_tools(&ToolPolicy::allow_all())
+                .iter()
+                .map(|tool| tool.name().to_string())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        assert_eq!(names(&clone).len(), 3);
+
+        router.set_offline(true);
+        assert_eq!(names(&clone), vec!["Read".to_string()]);
+        assert_eq!(router.specs_for_agent(&ToolPolicy::allow_all()).len(), 1);
+
+        router.set_offline(false);
+        assert_eq!(names(&clone).len(), 3);
+    }
 }
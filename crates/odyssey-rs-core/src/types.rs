@@ -3,6 +3,7 @@
 use autoagents_core::agent::{AgentDeriveT, AgentExecutor, AgentHooks};
 use chrono::{DateTime, Utc};
 use odyssey_rs_config::{ModelConfig, PermissionMode, ToolPolicy};
+use odyssey_rs_protocol::TurnId;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -20,6 +21,15 @@ pub struct Message {
     pub content: String,
     /// Timestamp for the message.
     pub created_at: DateTime<Utc>,
+    /// Turn that produced the message; unset for imported or older messages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<TurnId>,
+    /// Whether a regenerated response replaced this message; kept as an alternative.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub superseded: bool,
+    /// User who prompted the message; set on user messages in attributed sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
 }
 
 /// Speaker role for a message.
@@ -71,12 +81,42 @@ pub struct Session {
     pub id: SessionId,
     /// Agent responsible for this session.
     pub agent_id: String,
+    /// User who created the session; unset for unattributed sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
     /// Ordered list of messages in the session.
     pub messages: Vec<Message>,
     /// Creation timestamp.
     pub created_at: DateTime<Utc>,
 }
 
+/// A window of a session's messages for paging through long transcripts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionPage {
+    /// Session identifier.
+    pub id: SessionId,
+    /// Agent responsible for this session.
+    pub agent_id: String,
+    /// User who created the session; unset for unattributed sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// Messages in the window, oldest first.
+    pub messages: Vec<Message>,
+    /// Index of the first message in the window.
+    pub start: usize,
+    /// Total number of messages in the session.
+    pub total: usize,
+    /// Creation timestamp.
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionPage {
+    /// Cursor for the next older page, or `None` when this page starts the transcript.
+    pub fn older_cursor(&self) -> Option<usize> {
+        (self.start > 0).then_some(self.start)
+    }
+}
+
 /// Summary view of a session for listing.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SessionSummary {
@@ -84,6 +124,9 @@ pub struct SessionSummary {
     pub id: SessionId,
     /// Agent responsible for this session.
     pub agent_id: String,
+    /// User who created the session; unset for unattributed sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
     /// Count of messages stored.
     pub message_count: usize,
     /// Creation timestamp.
@@ -112,16 +155,36 @@ impl From<crate::state::SessionRecord> for Session {
         Self {
             id: record.id,
             agent_id: record.agent_id,
+            user_id: record.user_id,
             created_at: record.created_at,
-            messages: record
-                .messages
-                .into_iter()
-                .map(|message| Message {
-                    role: Role::parse(&message.role),
-                    content: message.content,
-                    created_at: message.created_at,
-                })
-                .collect(),
+            messages: record.messages.into_iter().map(Message::from).collect(),
+        }
+    }
+}
+
+impl From<crate::state::SessionPageRecord> for SessionPage {
+    fn from(record: crate::state::SessionPageRecord) -> Self {
+        Self {
+            id: record.id,
+            agent_id: record.agent_id,
+            user_id: record.user_id,
+            messages: record.messages.into_iter().map(Message::from).collect(),
+            start: record.start,
+            total: record.total,
+            created_at: record.created_at,
+        }
+    }
+}
+
+impl From<crate::state::MessageRecord> for Message {
+    fn from(message: crate::state::MessageRecord) -> Self {
+        Self {
+            role: Role::parse(&message.role),
+            content: message.content,
+            created_at: message.created_at,
+            turn_id: message.turn_id,
+            superseded: message.superseded,
+            user_id: message.user_id,
         }
     }
 }
@@ -131,6 +194,7 @@ impl From<crate::state::SessionSummaryRecord> for SessionSummary {
         Self {
             id: record.id,
             agent_id: record.agent_id,
+            user_id: record.user_id,
             message_count: record.message_count,
             created_at: record.created_at,
         }
@@ -189,17 +253,24 @@ mod tests {
         let record = SessionRecord {
             id: session_id,
             agent_id: "agent".to_string(),
+            user_id: None,
             created_at,
             messages: vec![
                 MessageRecord {
                     role: "system".to_string(),
                     content: "rules".to_string(),
                     created_at,
+                    turn_id: None,
+                    superseded: false,
+                    user_id: None,
                 },
                 MessageRecord {
                     role: "assistant".to_string(),
                     content: "hello".to_string(),
                     created_at,
+                    turn_id: None,
+                    superseded: false,
+                    user_id: None,
                 },
             ],
         };
@@ -208,17 +279,24 @@ mod tests {
         let expected = Session {
             id: session_id,
             agent_id: "agent".to_string(),
+            user_id: None,
             created_at,
             messages: vec![
                 Message {
                     role: Role::System,
                     content: "rules".to_string(),
                     created_at,
+                    turn_id: None,
+                    superseded: false,
+                    user_id: None,
                 },
                 Message {
                     role: Role::Assistant,
                     content: "hello".to_string(),
                     created_at,
+                    turn_id: None,
+                    superseded: false,
+                    user_id: None,
                 },
             ],
         };
@@ -0,0 +1,275 @@
+//! Lock file that keeps two Odyssey processes from sharing one workspace.
+//!
+//! Session journals and the approval store are appended to without
+//! coordination, so only one process may persist sessions for a workspace at a
+//! time. The lock is an exclusive advisory lock (`flock` on Unix) on
+//! `.odyssey/odyssey.lock`, whose contents name the owner. The operating
+//! system releases it when the holding process exits, so stale locks need no
+//! cleanup; the file is removed when the last holder in the process drops it.
+//! `force` replaces the file of a live holder with a new one.
+
+use crate::error::OdysseyCoreError;
+use chrono::{DateTime, Utc};
+use log::{debug, info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+/// File name of the lock under the workspace `.odyssey` directory.
+pub const LOCK_FILENAME: &str = "odyssey.lock";
+
+/// Locks held by this process, keyed by lock file path.
+static HELD: LazyLock<Mutex<HashMap<PathBuf, HeldLock>>> = LazyLock::new(Default::default);
+
+/// Owner recorded in the lock file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockOwner {
+    /// Process id of the holder.
+    pub pid: u32,
+    /// Distinguishes successive holders of the file.
+    pub token: Uuid,
+    /// When the lock was acquired.
+    pub acquired_at: DateTime<Utc>,
+}
+
+/// Locked file shared by every holder in this process.
+struct HeldLock {
+    /// Open file carrying the lock; closing it releases the lock.
+    _file: File,
+    /// Token written to the file by this process.
+    token: Uuid,
+    /// Number of live `WorkspaceLock` values for the file.
+    holders: usize,
+}
+
+/// Workspace lock held until dropped.
+#[derive(Debug)]
+pub struct WorkspaceLock {
+    path: PathBuf,
+}
+
+impl WorkspaceLock {
+    /// Acquire the lock of `workspace_root`.
+    ///
+    /// Locks held by the current process are shared and counted, so several
+    /// orchestrators in one process (as in tests) do not block each other.
+    pub fn acquire(workspace_root: &Path, force: bool) -> Result<Self, OdysseyCoreError> {
+        let dir = workspace_root.join(".odyssey");
+        fs::create_dir_all(&dir)?;
+        let path = fs::canonicalize(&dir)?.join(LOCK_FILENAME);
+        let mut held = HELD.lock();
+        if let Some(entry) = held.get_mut(&path) {
+            entry.holders += 1;
+            debug!(
+                "workspace lock already held by this process (holders={})",
+                entry.holders
+            );
+            return Ok(Self { path });
+        }
+        // A second attempt covers a file replaced by a forced takeover or a
+        // holder removing it between our open and lock.
+        for _ in 0..2 {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)?;
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => {
+                    let pid = read_owner(&path).map_or(0, |owner| owner.pid);
+                    if !force {
+                        return Err(OdysseyCoreError::WorkspaceLocked { pid, path });
+                    }
+                    warn!(
+                        "forcing workspace lock held by pid {pid} (path={})",
+                        path.display()
+                    );
+                    remove_lock_file(&path)?;
+                    continue;
+                }
+                Err(TryLockError::Error(err)) => return Err(err.into()),
+            }
+            if !is_same_file(&file, &path) {
+                debug!("workspace lock file was replaced while locking; retrying");
+                continue;
+            }
+            let owner = LockOwner {
+                pid: std::process::id(),
+                token: Uuid::new_v4(),
+                acquired_at: Utc::now(),
+            };
+            let line = serde_json::to_string(&owner)
+                .map_err(|err| OdysseyCoreError::State(err.to_string()))?;
+            file.set_len(0)?;
+            file.write_all(line.as_bytes())?;
+            file.sync_all()?;
+            info!("acquired workspace lock (path={})", path.display());
+            held.insert(
+                path.clone(),
+                HeldLock {
+                    _file: file,
+                    token: owner.token,
+                    holders: 1,
+                },
+            );
+            return Ok(Self { path });
+        }
+        Err(OdysseyCoreError::State(format!(
+            "could not lock workspace {}",
+            path.display()
+        )))
+    }
+
+    /// Path of the lock file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for WorkspaceLock {
+    fn drop(&mut self) {
+        let mut held = HELD.lock();
+        let Some(entry) = held.get_mut(&self.path) else {
+            return;
+        };
+        entry.holders = entry.holders.saturating_sub(1);
+        if entry.holders > 0 {
+            return;
+        }
+        let Some(entry) = held.remove(&self.path) else {
+            return;
+        };
+        // A forced takeover replaced our file; leave the new holder's lock alone.
+        // The file is removed before the lock is released with `entry`.
+        if read_owner(&self.path).is_some_and(|owner| owner.token == entry.token)
+            && let Err(err) = remove_lock_file(&self.path)
+        {
+            warn!(
+                "failed to remove workspace lock (path={}): {err}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Read the owner of a lock file; unreadable files have none.
+fn read_owner(path: &Path) -> Option<LockOwner> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(contents.trim()).ok()
+}
+
+/// Remove a lock file that may already be gone.
+fn remove_lock_file(path: &Path) -> std::io::Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Whether `path` still names the locked `file`.
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(locked), Ok(current)) => locked.dev() == current.dev() && locked.ino() == current.ino(),
+        (Err(_), _) | (_, Err(_)) => false,
+    }
+}
+
+/// Whether `path` still names the locked `file`; files cannot be removed
+/// while open on this platform, so it always does.
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, _path: &Path) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LOCK_FILENAME, LockOwner, WorkspaceLock, read_owner};
+    use crate::error::OdysseyCoreError;
+    use chrono::Utc;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+    use uuid::Uuid;
+
+    fn write_owner(root: &std::path::Path, pid: u32) {
+        let owner = LockOwner {
+            pid,
+            token: Uuid::new_v4(),
+            acquired_at: Utc::now(),
+        };
+        std::fs::create_dir_all(root.join(".odyssey")).expect("dir");
+        std::fs::write(
+            root.join(".odyssey").join(LOCK_FILENAME),
+            serde_json::to_string(&owner).expect("json"),
+        )
+        .expect("write");
+    }
+
+    #[test]
+    fn lock_is_removed_on_drop_and_shared_within_process() {
+        let temp = tempdir().expect("tempdir");
+        let lock = WorkspaceLock::acquire(temp.path(), false).expect("lock");
+        let path = lock.path().to_path_buf();
+        assert_eq!(
+            read_owner(&path).map(|owner| owner.pid),
+            Some(std::process::id())
+        );
+
+        let second = WorkspaceLock::acquire(temp.path(), false).expect("same process");
+        drop(lock);
+        assert_eq!(path.exists(), true);
+        drop(second);
+        assert_eq!(path.exists(), false);
+    }
+
+    #[test]
+    fn stale_locks_are_replaced() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::create_dir_all(temp.path().join(".odyssey")).expect("dir");
+        std::fs::write(temp.path().join(".odyssey").join(LOCK_FILENAME), "garbage").expect("write");
+        let lock = WorkspaceLock::acquire(temp.path(), false).expect("lock");
+        assert_eq!(
+            read_owner(lock.path()).map(|owner| owner.pid),
+            Some(std::process::id())
+        );
+    }
+
+    #[test]
+    fn live_locks_need_force() {
+        let temp = tempdir().expect("tempdir");
+        write_owner(temp.path(), u32::MAX);
+        drop(WorkspaceLock::acquire(temp.path(), false).expect("unlocked file"));
+
+        // Another holder: a separate open file description conflicts even in
+        // this process.
+        write_owner(temp.path(), 1);
+        let other = std::fs::OpenOptions::new()
+            .write(true)
+            .open(temp.path().join(".odyssey").join(LOCK_FILENAME))
+            .expect("open");
+        other.lock().expect("other holder");
+        let err = WorkspaceLock::acquire(temp.path(), false).expect_err("locked");
+        assert_eq!(
+            matches!(err, OdysseyCoreError::WorkspaceLocked { pid: 1, .. }),
+            true
+        );
+        let lock = WorkspaceLock::acquire(temp.path(), true).expect("forced");
+        assert_eq!(
+            read_owner(lock.path()).map(|owner| owner.pid),
+            Some(std::process::id())
+        );
+        drop(other);
+        let path = lock.path().to_path_buf();
+        drop(lock);
+        assert_eq!(path.exists(), false);
+    }
+}
@@ -0,0 +1,188 @@
+//! Workspace watcher that notices files changed outside the agent's tools.
+//!
+//! Only files a session has already seen (tracked in its [`FileVersions`]) are
+//! recorded, and a recorded file is reported only if its content no longer
+//! matches what the agent saw. The agent's own Write and Edit calls update
+//! those hashes, so they are not reported back as external changes.
+
+use crate::error::OdysseyCoreError;
+use crate::types::SessionId;
+use log::{debug, warn};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use odyssey_rs_tools::FileVersions;
+use parking_lot::Mutex;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Files listed in a changed-files note before the rest are summarized.
+const MAX_NOTE_FILES: usize = 20;
+/// Directories whose changes are never reported.
+const IGNORED_DIRS: &[&str] = &[".git"];
+
+/// Recursive watcher over the workspace root.
+pub struct WorkspaceWatcher {
+    root: PathBuf,
+    sessions: Arc<Mutex<HashMap<SessionId, WatchedSession>>>,
+    _watcher: RecommendedWatcher,
+}
+
+/// Files a session has seen, and the ones changed since its last turn.
+struct WatchedSession {
+    file_versions: FileVersions,
+    changed: BTreeSet<PathBuf>,
+}
+
+impl WorkspaceWatcher {
+    /// Start watching `root` recursively.
+    pub fn start(root: impl AsRef<Path>) -> Result<Self, OdysseyCoreError> {
+        let root = root.as_ref().to_path_buf();
+        let sessions = Arc::new(Mutex::new(HashMap::<SessionId, WatchedSession>::new()));
+        let handler_sessions = sessions.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+                Ok(event) => record_event(&handler_sessions, &event),
+                Err(err) => warn!("workspace watcher error: {err}"),
+            })
+            .map_err(|err| OdysseyCoreError::Executor(format!("workspace watcher: {err}")))?;
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .map_err(|err| OdysseyCoreError::Executor(format!("workspace watcher: {err}")))?;
+        debug!("watching workspace (root={})", root.display());
+        Ok(Self {
+            root,
+            sessions,
+            _watcher: watcher,
+        })
+    }
+
+    /// Workspace root being watched.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Files the session has seen that changed since the last call, sorted.
+    ///
+    /// The first call for a session starts tracking it and returns nothing.
+    pub fn take_stale(&self, session_id: SessionId, file_versions: &FileVersions) -> Vec<PathBuf> {
+        let changed = {
+            let mut sessions = self.sessions.lock();
+            let session = sessions
+                .entry(session_id)
+                .or_insert_with(|| WatchedSession {
+                    file_versions: file_versions.clone(),
+                    changed: BTreeSet::new(),
+                });
+            std::mem::take(&mut session.changed)
+        };
+        changed
+            .into_iter()
+            .filter(|path| file_versions.is_stale(path))
+            .collect()
+    }
+}
+
+/// Record changed paths for every session that has seen them.
+fn record_event(sessions: &Mutex<HashMap<SessionId, WatchedSession>>, event: &Event) {
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) | EventKind::Any
+    ) {
+        return;
+    }
+    let mut sessions = sessions.lock();
+    for path in event.paths.iter().filter(|path| !is_ignored(path)) {
+        for session in sessions.values_mut() {
+            if session.file_versions.contains(path) {
+                session.changed.insert(path.clone());
+            }
+        }
+    }
+}
+
+fn is_ignored(path: &Path) -> bool {
+    path.components().any(|component| {
+        matches!(component, Component::Normal(name) if IGNORED_DIRS.iter().any(|dir| name == *dir))
+    })
+}
+
+/// Note telling the agent which files changed since it last read them.
+pub fn changed_files_note(root: &Path, paths: &[PathBuf]) -> Option<String> {
+    if paths.is_empty() {
+        return None;
+    }
+    let mut note = String::from(
+        "<workspace_changes>\nThese files changed outside your tools since you last read them. \
+         Read them again before relying on their contents or editing them:\n",
+    );
+    for path in paths.iter().take(MAX_NOTE_FILES) {
+        let display = path.strip_prefix(root).unwrap_or(path).display();
+        if path.exists() {
+            let _ = writeln!(note, "- {display}");
+        } else {
+            let _ = writeln!(note, "- {display} (deleted)");
+        }
+    }
+    if paths.len() > MAX_NOTE_FILES {
+        let _ = writeln!(note, "- ... and {} more", paths.len() - MAX_NOTE_FILES);
+    }
+    note.push_str("</workspace_changes>");
+    Some(note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WorkspaceWatcher, changed_files_note};
+    use odyssey_rs_tools::FileVersions;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+    use tempfile::tempdir;
+    use uuid::Uuid;
+
+    #[test]
+    fn formats_changed_files_note() {
+        let root = PathBuf::from("/repo");
+        assert_eq!(changed_files_note(&root, &[]), None);
+        let paths = (0..22)
+            .map(|idx| root.join(format!("src/file{idx:02}.rs")))
+            .collect::<Vec<_>>();
+        let note = changed_files_note(&root, &paths).expect("note");
+        assert_eq!(note.lines().nth(2), Some("- src/file00.rs (deleted)"));
+        assert_eq!(note.lines().count(), 2 + 20 + 2);
+        assert!(note.ends_with("- ... and 2 more\n</workspace_changes>"));
+    }
+
+    #[test]
+    fn reports_seen_files_changed_externally() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path().canonicalize().expect("root");
+        let seen = root.join("seen.txt");
+        let unseen = root.join("unseen.txt");
+        std::fs::write(&seen, "before").expect("write");
+        let file_versions = FileVersions::default();
+        file_versions.record(seen.clone(), "stale-hash".to_string());
+
+        let watcher = WorkspaceWatcher::start(&root).expect("watcher");
+        let session_id = Uuid::new_v4();
+        assert_eq!(
+            watcher.take_stale(session_id, &file_versions),
+            Vec::<PathBuf>::new()
+        );
+
+        std::fs::write(&seen, "after").expect("write");
+        std::fs::write(&unseen, "new").expect("write");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut stale = Vec::new();
+        while stale.is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+            stale = watcher.take_stale(session_id, &file_versions);
+        }
+        assert_eq!(stale, vec![seen]);
+        assert_eq!(
+            watcher.take_stale(session_id, &file_versions),
+            Vec::<PathBuf>::new()
+        );
+    }
+}
@@ -1,12 +1,26 @@
 //! Tests for permission engine behavior.
 
 use odyssey_rs_config::{
-    PathAccess, PermissionAction, PermissionMode, PermissionRule, PermissionsConfig,
+    ApprovalPolicyProfile, ApprovalTimeoutDecision, GitPushAccess, PathAccess, PermissionAction,
+    PermissionMode, PermissionRule, PermissionsConfig,
 };
 use odyssey_rs_core::PermissionEngine;
-use odyssey_rs_protocol::PermissionRequest;
-use odyssey_rs_tools::{PermissionChecker, PermissionContext};
+use odyssey_rs_protocol::{
+    ApprovalDecision, ApprovalPolicy, EventPayload, PermissionRequest, ProposedToolCall, ToolError,
+    ToolRisk,
+};
+use odyssey_rs_sandbox::SandboxHandle;
+use odyssey_rs_test_utils::{CollectingEventSink, MockSandboxProvider, ScriptedApprovalHandler};
+use odyssey_rs_tools::builtins::BashTool;
+use odyssey_rs_tools::{
+    PermissionChecker, PermissionContext, PermissionOutcome, Tool, ToolContext, ToolSandbox,
+    builtin_tool_registry,
+};
 use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tempfile::tempdir;
 use uuid::Uuid;
 
 /// Plan mode should block tool execution by default.
@@ -15,6 +29,10 @@ async fn plan_mode_blocks_tools() {
     let config = PermissionsConfig {
         mode: PermissionMode::Plan,
         rules: Vec::new(),
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
     };
     let engine = PermissionEngine::new(config).expect("engine");
     let ctx = PermissionContext {
@@ -52,7 +70,13 @@ async fn allow_rule_overrides_default() {
             path: None,
             command: None,
             access: None,
+            git_push: None,
+            source: None,
         }],
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
     };
     let engine = PermissionEngine::new(config).expect("engine");
     let ctx = PermissionContext {
@@ -87,7 +111,13 @@ async fn tool_allow_implies_path_allow() {
             path: None,
             command: None,
             access: None,
+            git_push: None,
+            source: None,
         }],
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
     };
     let engine = PermissionEngine::new(config).expect("engine");
     let ctx = PermissionContext {
@@ -123,6 +153,8 @@ async fn deny_path_overrides_tool_allow() {
                 path: None,
                 command: None,
                 access: None,
+                git_push: None,
+                source: None,
             },
             PermissionRule {
                 action: PermissionAction::Deny,
@@ -130,8 +162,14 @@ async fn deny_path_overrides_tool_allow() {
                 path: Some("secret.txt".to_string()),
                 command: None,
                 access: Some(PathAccess::Write),
+                git_push: None,
+                source: None,
             },
         ],
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
     };
     let engine = PermissionEngine::new(config).expect("engine");
     let ctx = PermissionContext {
@@ -155,3 +193,563 @@ async fn deny_path_overrides_tool_allow() {
     assert_eq!(outcome.allowed, false);
     assert_eq!(outcome.reason.as_deref(), Some("denied by rule"));
 }
+
+/// Deny reasons name the rule and the file it was loaded from.
+#[tokio::test]
+async fn deny_reason_reports_rule_source() {
+    let config = PermissionsConfig {
+        mode: PermissionMode::Default,
+        rules: vec![PermissionRule {
+            action: PermissionAction::Deny,
+            tool: Some("Bash".to_string()),
+            path: None,
+            command: None,
+            access: None,
+            git_push: None,
+            source: Some("policies/ci.json5".to_string()),
+        }],
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
+    };
+    let engine = PermissionEngine::new(config).expect("engine");
+    let ctx = PermissionContext {
+        session_id: Uuid::nil(),
+        agent_id: "agent".to_string(),
+        tool_name: Some("Bash".to_string()),
+        turn_id: None,
+    };
+
+    let outcome = engine
+        .authorize(
+            &ctx,
+            PermissionRequest::Tool {
+                name: "Bash".to_string(),
+            },
+        )
+        .await
+        .expect("outcome");
+
+    assert_eq!(outcome.allowed, false);
+    assert_eq!(
+        outcome.reason.as_deref(),
+        Some("denied by rule (tool=Bash) from policies/ci.json5")
+    );
+}
+
+/// Read-only mode denies writes even when rules allow them.
+#[tokio::test]
+async fn read_only_mode_overrides_allow_rules() {
+    let config = PermissionsConfig {
+        mode: PermissionMode::BypassPermissions,
+        rules: vec![PermissionRule {
+            action: PermissionAction::Allow,
+            tool: Some("Write".to_string()),
+            path: None,
+            command: None,
+            access: None,
+            git_push: None,
+            source: None,
+        }],
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
+    };
+    let engine = PermissionEngine::new(config).expect("engine");
+    engine.set_read_only(true);
+    let ctx = PermissionContext {
+        session_id: Uuid::nil(),
+        agent_id: "agent".to_string(),
+        tool_name: Some("Write".to_string()),
+        turn_id: None,
+    };
+
+    let tool = engine
+        .authorize(
+            &ctx,
+            PermissionRequest::Tool {
+                name: "Write".to_string(),
+            },
+        )
+        .await
+        .expect("outcome");
+    assert_eq!(tool.allowed, false);
+    assert_eq!(tool.reason.as_deref(), Some("read-only mode blocks Write"));
+
+    let write = engine
+        .authorize(
+            &ctx,
+            PermissionRequest::Path {
+                path: "src/lib.rs".to_string(),
+                mode: PathAccess::Write,
+            },
+        )
+        .await
+        .expect("outcome");
+    assert_eq!(write.allowed, false);
+
+    let read = engine
+        .authorize(
+            &ctx,
+            PermissionRequest::Path {
+                path: "src/lib.rs".to_string(),
+                mode: PathAccess::Read,
+            },
+        )
+        .await
+        .expect("outcome");
+    assert_eq!(read.allowed, true);
+
+    engine.set_read_only(false);
+    let tool = engine
+        .authorize(
+            &ctx,
+            PermissionRequest::Tool {
+                name: "Write".to_string(),
+            },
+        )
+        .await
+        .expect("outcome");
+    assert_eq!(tool.allowed, true);
+}
+
+/// Offline mode denies network tools, network commands, and git pushes even in bypass mode.
+#[tokio::test]
+async fn offline_mode_denies_network_access() {
+    let config = PermissionsConfig {
+        mode: PermissionMode::BypassPermissions,
+        rules: Vec::new(),
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
+    };
+    let engine = PermissionEngine::new(config).expect("engine");
+    engine.set_offline(true);
+    assert_eq!(engine.is_offline(), true);
+    let ctx = PermissionContext {
+        session_id: Uuid::nil(),
+        agent_id: "agent".to_string(),
+        tool_name: Some("Bash".to_string()),
+        turn_id: None,
+    };
+
+    let fetch = engine
+        .authorize(
+            &ctx,
+            PermissionRequest::Tool {
+                name: "WebFetch".to_string(),
+            },
+        )
+        .await
+        .expect("outcome");
+    assert_eq!(
+        fetch.reason.as_deref(),
+        Some("offline mode blocks network tool WebFetch")
+    );
+    let curl = engine
+        .authorize(
+            &ctx,
+            PermissionRequest::Command {
+                argv: vec!["curl".to_string(), "https://example.com".to_string()],
+            },
+        )
+        .await
+        .expect("outcome");
+    assert_eq!(curl.allowed, false);
+    let push = engine
+        .authorize(
+            &ctx,
+            PermissionRequest::GitPush {
+                remote: "origin".to_string(),
+                branch: "main".to_string(),
+                force: false,
+            },
+        )
+        .await
+        .expect("outcome");
+    assert_eq!(push.allowed, false);
+    let build = engine
+        .authorize(
+            &ctx,
+            PermissionRequest::Command {
+                argv: vec!["cargo".to_string(), "build".to_string()],
+            },
+        )
+        .await
+        .expect("outcome");
+    assert_eq!(build.allowed, true);
+
+    engine.set_offline(false);
+    let fetch = engine
+        .authorize(
+            &ctx,
+            PermissionRequest::Tool {
+                name: "WebFetch".to_string(),
+            },
+        )
+        .await
+        .expect("outcome");
+    assert_eq!(fetch.allowed, true);
+}
+
+/// CI profiles answer approvals without waiting and deny external paths and network access.
+#[tokio::test]
+async fn ci_profiles_never_wait_for_approval() {
+    let config = PermissionsConfig {
+        mode: PermissionMode::Default,
+        rules: vec![PermissionRule {
+            action: PermissionAction::Allow,
+            tool: None,
+            path: None,
+            command: Some(vec!["curl".to_string(), "localhost".to_string()]),
+            access: None,
+            git_push: None,
+            source: None,
+        }],
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::CiSafe,
+    };
+    let engine = PermissionEngine::new(config.clone()).expect("engine");
+    let tool = |name: &str| PermissionRequest::Tool {
+        name: name.to_string(),
+    };
+    let command = |argv: &[&str]| PermissionRequest::Command {
+        argv: argv.iter().map(ToString::to_string).collect(),
+    };
+
+    assert_eq!(decide(&engine, tool("Read")).await.allowed, true);
+    let read = PermissionRequest::Path {
+        path: "src/lib.rs".to_string(),
+        mode: PathAccess::Read,
+    };
+    assert_eq!(decide(&engine, read).await.allowed, true);
+    let bash = decide(&engine, tool("Bash")).await;
+    assert_eq!(bash.allowed, false);
+    assert_eq!(
+        bash.reason.as_deref(),
+        Some("ci-safe profile denies requests that need approval")
+    );
+    let external = PermissionRequest::ExternalPath {
+        path: "/etc/passwd".to_string(),
+        mode: PathAccess::Read,
+    };
+    assert_eq!(
+        decide(&engine, external).await.reason.as_deref(),
+        Some("ci-safe profile denies access outside the workspace: /etc/passwd")
+    );
+    assert_eq!(decide(&engine, tool("WebFetch")).await.allowed, false);
+    assert_eq!(
+        decide(&engine, command(&["git", "-C", "repo", "push"]))
+            .await
+            .reason
+            .as_deref(),
+        Some("ci-safe profile denies network command git -C repo push")
+    );
+    assert_eq!(
+        decide(&engine, command(&["curl", "localhost"]))
+            .await
+            .allowed,
+        true
+    );
+
+    let engine = PermissionEngine::new(PermissionsConfig {
+        profile: ApprovalPolicyProfile::CiTrusted,
+        ..config
+    })
+    .expect("engine");
+    assert_eq!(decide(&engine, tool("Bash")).await.allowed, true);
+    assert_eq!(
+        decide(&engine, command(&["cargo", "test"])).await.allowed,
+        true
+    );
+    assert_eq!(
+        decide(&engine, command(&["/usr/bin/wget", "example.com"]))
+            .await
+            .allowed,
+        false
+    );
+    assert_eq!(
+        decide(&engine, tool("PublishArtifact")).await.allowed,
+        false
+    );
+}
+
+/// A session profile overrides the configured profile for that session only.
+#[tokio::test]
+async fn session_profile_overrides_configured_profile() {
+    let engine = PermissionEngine::new(PermissionsConfig::default()).expect("engine");
+    let bash = || PermissionRequest::Tool {
+        name: "Bash".to_string(),
+    };
+    assert_eq!(decide(&engine, bash()).await.allowed, true);
+
+    engine.set_session_profile(Uuid::nil(), Some(ApprovalPolicyProfile::CiSafe));
+    assert_eq!(
+        decide(&engine, bash()).await.reason.as_deref(),
+        Some("ci-safe profile denies requests that need approval")
+    );
+    let other = PermissionContext {
+        session_id: Uuid::new_v4(),
+        agent_id: "agent".to_string(),
+        tool_name: None,
+        turn_id: None,
+    };
+    assert_eq!(
+        engine
+            .authorize(&other, bash())
+            .await
+            .expect("outcome")
+            .allowed,
+        true
+    );
+
+    engine.set_session_profile(Uuid::nil(), None);
+    assert_eq!(decide(&engine, bash()).await.allowed, true);
+}
+
+/// Read-only tools skip the prompt under accept_edits and the on_request
+/// policy; approval requests carry the tool's risk class.
+#[tokio::test]
+async fn read_only_tools_skip_approval() {
+    let config = PermissionsConfig {
+        mode: PermissionMode::AcceptEdits,
+        rules: Vec::new(),
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
+    };
+    let engine = PermissionEngine::new(config).expect("engine");
+    engine.register_tool_risks(builtin_tool_registry().risks());
+    let approvals = Arc::new(ScriptedApprovalHandler::always(ApprovalDecision::Deny));
+    engine.set_approval_handler(Some(approvals.clone()));
+    let tool = |name: &str| PermissionRequest::Tool {
+        name: name.to_string(),
+    };
+
+    assert_eq!(engine.tool_risk("GitStatus"), Some(ToolRisk::ReadOnly));
+    assert_eq!(decide(&engine, tool("GitStatus")).await.allowed, true);
+    assert_eq!(decide(&engine, tool("Bash")).await.allowed, false);
+
+    engine.register_agent_mode("agent".to_string(), Some(PermissionMode::Default));
+    assert_eq!(decide(&engine, tool("GitStatus")).await.allowed, false);
+    engine.set_session_approval_policy(Uuid::nil(), Some(ApprovalPolicy::OnRequest));
+    assert_eq!(decide(&engine, tool("GitStatus")).await.allowed, true);
+    assert_eq!(decide(&engine, tool("WebFetch")).await.allowed, false);
+
+    let read_ctx = PermissionContext {
+        session_id: Uuid::nil(),
+        agent_id: "agent".to_string(),
+        tool_name: Some("Read".to_string()),
+        turn_id: None,
+    };
+    let path = |mode| PermissionRequest::Path {
+        path: "src/lib.rs".to_string(),
+        mode,
+    };
+    let read = engine
+        .authorize(&read_ctx, path(PathAccess::Read))
+        .await
+        .expect("outcome");
+    assert_eq!(read.allowed, true);
+    let write = engine
+        .authorize(&read_ctx, path(PathAccess::Write))
+        .await
+        .expect("outcome");
+    assert_eq!(write.allowed, false);
+
+    let risks: Vec<_> = approvals
+        .requests()
+        .into_iter()
+        .map(|request| request.risk)
+        .collect();
+    assert_eq!(
+        risks,
+        vec![
+            Some(ToolRisk::Destructive),
+            Some(ToolRisk::ReadOnly),
+            Some(ToolRisk::ExternalNetwork),
+            Some(ToolRisk::ReadOnly),
+        ]
+    );
+}
+
+/// Authorize a request outside any tool call.
+async fn decide(engine: &PermissionEngine, request: PermissionRequest) -> PermissionOutcome {
+    let ctx = PermissionContext {
+        session_id: Uuid::nil(),
+        agent_id: "agent".to_string(),
+        tool_name: None,
+        turn_id: None,
+    };
+    engine.authorize(&ctx, request).await.expect("outcome")
+}
+
+/// Git pushes need a matching git_push rule, even in bypass mode or under a tool allow.
+#[tokio::test]
+async fn git_push_requires_push_rule() {
+    let config = PermissionsConfig {
+        mode: PermissionMode::BypassPermissions,
+        rules: vec![
+            PermissionRule {
+                action: PermissionAction::Allow,
+                tool: Some("GitCommit".to_string()),
+                path: None,
+                command: None,
+                access: None,
+                git_push: None,
+                source: None,
+            },
+            PermissionRule {
+                action: PermissionAction::Allow,
+                tool: None,
+                path: None,
+                command: None,
+                access: None,
+                git_push: Some(GitPushAccess::Push),
+                source: None,
+            },
+        ],
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
+    };
+    let engine = PermissionEngine::new(config).expect("engine");
+    let ctx = PermissionContext {
+        session_id: Uuid::nil(),
+        agent_id: "agent".to_string(),
+        tool_name: Some("GitCommit".to_string()),
+        turn_id: None,
+    };
+    let push = |force| PermissionRequest::GitPush {
+        remote: "origin".to_string(),
+        branch: "main".to_string(),
+        force,
+    };
+
+    let outcome = engine.authorize(&ctx, push(false)).await.expect("outcome");
+    assert_eq!(outcome.allowed, true);
+
+    let outcome = engine.authorize(&ctx, push(true)).await.expect("outcome");
+    assert_eq!(outcome.allowed, false);
+    assert_eq!(
+        outcome.reason.as_deref(),
+        Some("no approval handler configured")
+    );
+}
+
+/// Approval decisions should gate commands before they reach the sandbox.
+#[tokio::test]
+async fn approvals_gate_sandboxed_commands() {
+    let config = PermissionsConfig {
+        mode: PermissionMode::Default,
+        rules: Vec::new(),
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
+    };
+    let engine = Arc::new(PermissionEngine::new(config).expect("engine"));
+    let approvals = Arc::new(ScriptedApprovalHandler::new([ApprovalDecision::AllowOnce]));
+    let events = Arc::new(CollectingEventSink::new());
+    engine.set_approval_handler(Some(approvals.clone()));
+    engine.set_event_sink(Some(events.clone()));
+    let sandbox = Arc::new(MockSandboxProvider::new().with_stdout("hello\n"));
+    let workspace = tempdir().expect("workspace");
+    let ctx = ToolContext::builder()
+        .with_workspace_root(workspace.path())
+        .with_turn_id(Uuid::new_v4())
+        .with_permission_checker(engine)
+        .with_event_sink(events.clone())
+        .with_sandbox(ToolSandbox {
+            provider: sandbox.clone(),
+            handle: SandboxHandle { id: Uuid::nil() },
+            lease: None,
+        })
+        .build();
+
+    let result = BashTool::default()
+        .call(&ctx, json!({ "command": "echo hello" }))
+        .await
+        .expect("approved");
+    let err = BashTool::default()
+        .call(&ctx, json!({ "command": "echo again" }))
+        .await
+        .expect_err("denied");
+
+    assert_eq!(result["stdout"], "hello\n");
+    assert!(matches!(err, ToolError::PermissionDenied(_)));
+    let specs = sandbox.specs();
+    assert_eq!(specs.len(), 1);
+    assert_eq!(specs[0].command, PathBuf::from("echo"));
+    assert_eq!(specs[0].args, vec!["hello".to_string()]);
+    assert_eq!(approvals.requests().len(), 2);
+    let requested = events
+        .payloads()
+        .into_iter()
+        .filter(|payload| matches!(payload, EventPayload::PermissionRequested { .. }))
+        .count();
+    assert_eq!(requested, 2);
+}
+
+/// Confirm-batch mode allows single calls and asks once per batch, never remembering it.
+#[tokio::test]
+async fn confirm_batch_mode_asks_per_batch() {
+    let config = PermissionsConfig {
+        mode: PermissionMode::ConfirmBatch,
+        rules: Vec::new(),
+        webhook: None,
+        approval_timeout_secs: None,
+        timeout_decision: ApprovalTimeoutDecision::Deny,
+        profile: ApprovalPolicyProfile::Interactive,
+    };
+    let engine = PermissionEngine::new(config).expect("engine");
+    let approvals = Arc::new(ScriptedApprovalHandler::new([
+        ApprovalDecision::AllowAlways,
+        ApprovalDecision::AllowAlways,
+        ApprovalDecision::Deny,
+    ]));
+    engine.set_approval_handler(Some(approvals.clone()));
+    let ctx = PermissionContext {
+        session_id: Uuid::nil(),
+        agent_id: "agent".to_string(),
+        tool_name: None,
+        turn_id: None,
+    };
+    let batch = vec![ProposedToolCall {
+        tool_name: "Bash".to_string(),
+        arguments: json!({ "command": "cargo fmt" }),
+    }];
+
+    assert_eq!(engine.confirms_batches("agent"), true);
+    assert_eq!(
+        decide(
+            &engine,
+            PermissionRequest::Tool {
+                name: "Bash".to_string()
+            }
+        )
+        .await
+        .allowed,
+        true
+    );
+    for _ in 0..2 {
+        let outcome = engine
+            .confirm_batch(&ctx, batch.clone(), None)
+            .await
+            .expect("outcome");
+        assert_eq!(outcome.allowed, true);
+    }
+    let external = PermissionRequest::ExternalPath {
+        path: "/etc/hosts".to_string(),
+        mode: PathAccess::Write,
+    };
+    assert_eq!(decide(&engine, external).await.allowed, false);
+    assert_eq!(approvals.requests().len(), 3);
+}
@@ -1,16 +1,103 @@
 //! Session persistence integration tests.
 
+use async_trait::async_trait;
 use autoagents_core::agent::prebuilt::executor::ReActAgent;
 use autoagents_llm::LLMProvider;
-use odyssey_rs_config::OdysseyConfig;
-use odyssey_rs_core::{AgentBuilder, DEFAULT_AGENT_ID, LLMEntry, OdysseyAgent, Orchestrator};
+use autoagents_llm::chat::{ChatMessage, ChatProvider, ChatResponse, StructuredOutputFormat, Tool};
+use autoagents_llm::completion::{CompletionProvider, CompletionRequest, CompletionResponse};
+use autoagents_llm::embedding::EmbeddingProvider;
+use autoagents_llm::error::LLMError;
+use autoagents_llm::models::ModelsProvider;
+use odyssey_rs_config::{OdysseyConfig, SessionBusyPolicy};
+use odyssey_rs_core::error::OdysseyCoreError;
+use odyssey_rs_core::{
+    AgentBuilder, DEFAULT_AGENT_ID, LLMEntry, OdysseyAgent, Orchestrator, RunMetrics,
+    SessionImportFormat,
+};
 use odyssey_rs_memory::FileMemoryProvider;
-use odyssey_rs_test_utils::FixedLLM;
+use odyssey_rs_test_utils::{FixedChatResponse, FixedLLM};
 use odyssey_rs_tools::builtin_tool_registry;
 use pretty_assertions::assert_eq;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tempfile::tempdir;
+use tempfile::{TempDir, tempdir};
+use tokio::sync::Notify;
+
+/// LLM that blocks each chat call until released.
+#[derive(Debug, Default)]
+struct GatedLLM {
+    started: Arc<Notify>,
+    release: Arc<Notify>,
+}
+
+#[async_trait]
+impl ChatProvider for GatedLLM {
+    async fn chat_with_tools(
+        &self,
+        _messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+        _json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        self.started.notify_one();
+        self.release.notified().await;
+        Ok(Box::new(FixedChatResponse::new("gated response")))
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for GatedLLM {
+    async fn complete(
+        &self,
+        _req: &CompletionRequest,
+        _json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<CompletionResponse, LLMError> {
+        Err(LLMError::ProviderError("gated".to_string()))
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for GatedLLM {
+    async fn embed(&self, _input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        Err(LLMError::ProviderError("gated".to_string()))
+    }
+}
+
+#[async_trait]
+impl ModelsProvider for GatedLLM {}
+
+impl LLMProvider for GatedLLM {}
+
+/// Build an orchestrator with the default agent and the given LLM.
+fn build_orchestrator(
+    mut config: OdysseyConfig,
+    temp: &TempDir,
+    llm: Arc<dyn LLMProvider>,
+) -> Orchestrator {
+    config.memory.path = Some(temp.path().join("memory").to_string_lossy().to_string());
+    let memory = Arc::new(
+        FileMemoryProvider::new(PathBuf::from(
+            config.memory.path.clone().expect("memory path"),
+        ))
+        .expect("memory provider"),
+    );
+    let default_agent = AgentBuilder::new(
+        DEFAULT_AGENT_ID.to_string(),
+        ReActAgent::new(OdysseyAgent::new("Test agent".to_string(), Vec::new())),
+        memory,
+    );
+    let orchestrator = Orchestrator::new(config, builtin_tool_registry(), None, None, None, None)
+        .expect("build orchestrator");
+    orchestrator
+        .register_llm_provider(LLMEntry {
+            id: "default_LLM".to_string(),
+            provider: llm,
+        })
+        .expect("register llm");
+    orchestrator
+        .register_agent(default_agent)
+        .expect("register agent");
+    orchestrator
+}
 
 /// Sessions should resume from the configured state store.
 #[tokio::test]
@@ -71,3 +158,345 @@ async fn resumes_session_from_state_store() {
     assert_eq!(session.id, result.session_id);
     assert!(session.messages.len() >= 2);
 }
+
+/// Session pages should walk a persisted transcript from the newest messages back.
+#[tokio::test]
+async fn pages_persisted_session_messages() {
+    let temp = tempdir().expect("tempdir");
+    let mut config = OdysseyConfig::default();
+    config.sessions.enabled = true;
+    config.sessions.path = Some(temp.path().join("sessions").to_string_lossy().to_string());
+    let orchestrator = build_orchestrator(config.clone(), &temp, Arc::new(FixedLLM::new("reply")));
+    let session_id = orchestrator.create_session(None).expect("session");
+    for turn in 0..3 {
+        orchestrator
+            .run_in_session(
+                session_id,
+                DEFAULT_AGENT_ID,
+                "default_LLM",
+                format!("turn {turn}"),
+            )
+            .await
+            .expect("turn");
+    }
+    let cached = orchestrator
+        .get_session_page(session_id, None, 4)
+        .expect("cached page");
+
+    let orchestrator = build_orchestrator(config, &temp, Arc::new(FixedLLM::new("reply")));
+    let newest = orchestrator
+        .get_session_page(session_id, None, 4)
+        .expect("newest page");
+    assert_eq!(newest, cached);
+    assert_eq!((newest.start, newest.total), (2, 6));
+    assert_eq!(newest.messages[0].content, "turn 1");
+    assert_eq!(newest.older_cursor(), Some(2));
+
+    let oldest = orchestrator
+        .get_session_page(session_id, newest.older_cursor(), 4)
+        .expect("oldest page");
+    assert_eq!(
+        oldest
+            .messages
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect::<Vec<_>>(),
+        vec!["turn 0", "reply"]
+    );
+    assert_eq!(oldest.older_cursor(), None);
+
+    let err = orchestrator
+        .get_session_page(uuid::Uuid::new_v4(), None, 4)
+        .expect_err("unknown session");
+    assert!(matches!(err, OdysseyCoreError::UnknownSession(_)));
+}
+
+/// A second turn on a busy session should be rejected with a typed error.
+#[tokio::test]
+async fn rejects_concurrent_turn_in_same_session() {
+    let temp = tempdir().expect("tempdir");
+    let mut config = OdysseyConfig::default();
+    config.orchestrator.session_busy = SessionBusyPolicy::Reject;
+    let llm = Arc::new(GatedLLM::default());
+    let started = llm.started.clone();
+    let release = llm.release.clone();
+    let orchestrator = build_orchestrator(config, &temp, llm);
+    let session_id = orchestrator.create_session(None).expect("session");
+
+    let first = orchestrator.run_in_session(
+        session_id,
+        DEFAULT_AGENT_ID,
+        "default_LLM",
+        "first".to_string(),
+    );
+    let second = async {
+        started.notified().await;
+        let result = orchestrator
+            .run_in_session(
+                session_id,
+                DEFAULT_AGENT_ID,
+                "default_LLM",
+                "second".to_string(),
+            )
+            .await;
+        release.notify_one();
+        result
+    };
+    let (first, second) = tokio::join!(first, second);
+
+    assert_eq!(first.expect("first turn").response, "gated response");
+    match second {
+        Err(OdysseyCoreError::SessionBusy(id)) => assert_eq!(id, session_id),
+        Err(other) => panic!("unexpected error: {other:?}"),
+        Ok(_) => panic!("second turn should be rejected"),
+    }
+}
+
+/// Run metrics should count a turn waiting on a busy session as queued.
+#[tokio::test]
+async fn reports_queued_turns_in_run_metrics() {
+    let temp = tempdir().expect("tempdir");
+    let mut config = OdysseyConfig::default();
+    config.orchestrator.session_busy = SessionBusyPolicy::Queue;
+    let llm = Arc::new(GatedLLM::default());
+    let started = llm.started.clone();
+    let release = llm.release.clone();
+    let orchestrator = build_orchestrator(config, &temp, llm);
+    let session_id = orchestrator.create_session(None).expect("session");
+    assert_eq!(orchestrator.run_metrics(), RunMetrics::default());
+
+    let first = orchestrator.run_in_session(
+        session_id,
+        DEFAULT_AGENT_ID,
+        "default_LLM",
+        "first".to_string(),
+    );
+    let second = orchestrator.run_in_session(
+        session_id,
+        DEFAULT_AGENT_ID,
+        "default_LLM",
+        "second".to_string(),
+    );
+    let observe = async {
+        started.notified().await;
+        while orchestrator.run_metrics().queued_turns == 0 {
+            tokio::task::yield_now().await;
+        }
+        let metrics = orchestrator.run_metrics();
+        release.notify_one();
+        started.notified().await;
+        release.notify_one();
+        metrics
+    };
+    let (first, second, metrics) = tokio::join!(first, second, observe);
+
+    first.expect("first turn");
+    second.expect("second turn");
+    assert_eq!(
+        metrics,
+        RunMetrics {
+            running_turns: 1,
+            queued_turns: 1,
+        }
+    );
+    assert_eq!(orchestrator.run_metrics(), RunMetrics::default());
+}
+
+/// Health should flag a turn that emits no events past the stall timeout.
+#[tokio::test]
+async fn health_reports_stalled_turns() {
+    let temp = tempdir().expect("tempdir");
+    let mut config = OdysseyConfig::default();
+    config.orchestrator.stall_timeout_secs = Some(1);
+    let llm = Arc::new(GatedLLM::default());
+    let started = llm.started.clone();
+    let release = llm.release.clone();
+    let orchestrator = build_orchestrator(config, &temp, llm);
+    let session_id = orchestrator.create_session(None).expect("session");
+    assert_eq!(orchestrator.health().sessions.is_empty(), true);
+
+    let turn = orchestrator.run_in_session(
+        session_id,
+        DEFAULT_AGENT_ID,
+        "default_LLM",
+        "hang".to_string(),
+    );
+    let observe = async {
+        started.notified().await;
+        while orchestrator.health().stalled_turns.is_empty() {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        let health = orchestrator.health();
+        release.notify_one();
+        health
+    };
+    let (turn, health) = tokio::join!(turn, observe);
+
+    turn.expect("turn");
+    assert_eq!(health.running_turns, 1);
+    assert_eq!(health.sessions.len(), 1);
+    assert_eq!(health.sessions[0].session_id, session_id);
+    assert_eq!(health.sessions[0].turns[0].stalled, true);
+    assert_eq!(health.sessions[0].turns[0].idle_secs >= 1, true);
+    let health = orchestrator.health();
+    assert_eq!(health.sessions.is_empty(), true);
+    assert_eq!(health.last_error, None);
+}
+
+/// A reached budget should refuse turns until an overrun is allowed for one turn.
+#[tokio::test]
+async fn budget_refuses_turns_until_overrun_allowed() {
+    let temp = tempdir().expect("tempdir");
+    let mut config = OdysseyConfig::default();
+    config.budget.max_session_usd = Some(0.0);
+    let orchestrator = build_orchestrator(config, &temp, Arc::new(FixedLLM::new("paid")));
+    let session_id = orchestrator.create_session(None).expect("session");
+    let run = || {
+        orchestrator.run_in_session(
+            session_id,
+            DEFAULT_AGENT_ID,
+            "default_LLM",
+            "hello".to_string(),
+        )
+    };
+
+    let err = run().await.expect_err("over budget");
+    assert_eq!(matches!(err, OdysseyCoreError::BudgetExceeded(_)), true);
+    orchestrator.allow_budget_overrun(session_id);
+    assert_eq!(run().await.expect("overrun").response, "paid");
+    assert_eq!(run().await.is_err(), true);
+    assert_eq!(orchestrator.budget_usage(session_id).session_usd, 0.0);
+}
+
+/// Turns in different sessions should run concurrently under the global limit.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn runs_parallel_turns_across_sessions() {
+    let temp = tempdir().expect("tempdir");
+    let mut config = OdysseyConfig::default();
+    config.orchestrator.max_concurrent_turns = Some(2);
+    let orchestrator = build_orchestrator(config, &temp, Arc::new(FixedLLM::new("parallel")));
+    let sessions = (0..6)
+        .map(|_| orchestrator.create_session(None).expect("session"))
+        .collect::<Vec<_>>();
+
+    let turns = sessions.iter().flat_map(|session_id| {
+        (0..2).map(|turn| {
+            orchestrator.run_in_session(
+                *session_id,
+                DEFAULT_AGENT_ID,
+                "default_LLM",
+                format!("turn {turn}"),
+            )
+        })
+    });
+    let results = futures_util::future::join_all(turns).await;
+    for result in results {
+        assert_eq!(result.expect("turn").response, "parallel");
+    }
+    for session_id in sessions {
+        let session = orchestrator.resume_session(session_id).expect("session");
+        assert_eq!(session.messages.len(), 4);
+    }
+}
+
+/// Imported transcripts should become sessions that later turns can continue.
+#[tokio::test]
+async fn imports_claude_code_transcript_into_session() {
+    let temp = tempdir().expect("tempdir");
+    let orchestrator = build_orchestrator(
+        OdysseyConfig::default(),
+        &temp,
+        Arc::new(FixedLLM::new("continued")),
+    );
+    let path = temp.path().join("transcript.jsonl");
+    std::fs::write(
+        &path,
+        r#"{"type":"user","timestamp":"2025-06-01T10:00:00Z","message":{"role":"user","content":"list files"}}
+{"type":"assistant","timestamp":"2025-06-01T10:00:01Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"ls"}}]}}
+{"type":"user","timestamp":"2025-06-01T10:00:02Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"Cargo.toml"}]}}
+{"type":"assistant","timestamp":"2025-06-01T10:00:03Z","message":{"role":"assistant","content":[{"type":"text","text":"One file."}]}}
+"#,
+    )
+    .expect("write transcript");
+
+    let session_id = orchestrator
+        .import_session(&path, SessionImportFormat::ClaudeCode)
+        .await
+        .expect("import");
+    let session = orchestrator.resume_session(session_id).expect("session");
+    assert_eq!(session.agent_id, DEFAULT_AGENT_ID);
+    assert_eq!(
+        session
+            .messages
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "list files",
+            "tool Bash\nargs: {\"command\":\"ls\"}\nresult: Cargo.toml",
+            "One file.",
+        ]
+    );
+
+    orchestrator
+        .run_in_session(
+            session_id,
+            DEFAULT_AGENT_ID,
+            "default_LLM",
+            "and now?".to_string(),
+        )
+        .await
+        .expect("turn");
+    let session = orchestrator.resume_session(session_id).expect("session");
+    assert_eq!(session.messages.len(), 5);
+
+    let err = orchestrator
+        .import_session(
+            temp.path().join("missing.jsonl"),
+            SessionImportFormat::Codex,
+        )
+        .await
+        .err()
+        .expect("missing file");
+    assert!(matches!(err, OdysseyCoreError::Io(_)));
+}
+
+/// Manual compaction should replace old memory records with an LLM summary and log the originals.
+#[tokio::test]
+async fn compacts_memory_with_llm_summary() {
+    let temp = tempdir().expect("tempdir");
+    let mut config = OdysseyConfig::default();
+    config.memory.compaction.max_messages = 4;
+    let orchestrator = build_orchestrator(config, &temp, Arc::new(FixedLLM::new("summary")));
+    let session_id = orchestrator
+        .create_session(Some(DEFAULT_AGENT_ID.to_string()))
+        .expect("session");
+    for prompt in ["one", "two", "three"] {
+        orchestrator
+            .run_in_session(
+                session_id,
+                DEFAULT_AGENT_ID,
+                "default_LLM",
+                prompt.to_string(),
+            )
+            .await
+            .expect("turn");
+    }
+
+    let compaction = orchestrator
+        .compact_memory(session_id, None)
+        .await
+        .expect("compact")
+        .expect("compaction");
+    assert_eq!(compaction.session_id, session_id);
+    assert_eq!(compaction.replaced.is_empty(), false);
+    assert_eq!(compaction.summaries.len(), 1);
+    assert_eq!(compaction.summaries[0].content, "summary");
+
+    let audit = temp
+        .path()
+        .join("memory")
+        .join("compactions")
+        .join(format!("{session_id}.jsonl"));
+    assert_eq!(audit.exists(), true);
+}
@@ -1,10 +1,12 @@
 //! Skill store tests for discovery and filtering.
 
-use odyssey_rs_config::{SettingSource, SkillsConfig};
+use odyssey_rs_config::{SettingSource, SkillRemote, SkillsConfig};
 use odyssey_rs_core::skills::SkillStore;
 use odyssey_rs_protocol::SkillProvider;
 use pretty_assertions::assert_eq;
 use std::fs;
+use std::path::Path;
+use std::process::Command;
 use tempfile::tempdir;
 
 /// Load skills from a project-scoped skills directory.
@@ -33,6 +35,10 @@ Use this skill for demos.
         paths: Vec::new(),
         allow: vec!["*".to_string()],
         deny: Vec::new(),
+        remotes: Vec::new(),
+        cache_dir: None,
+        remote_allow: vec!["*".to_string()],
+        remote_deny: Vec::new(),
     };
     let store = SkillStore::load(&config, temp.path()).expect("load store");
     let summaries = store.summaries();
@@ -56,9 +62,80 @@ fn allowlist_and_denylist_filter_skills() {
         paths: Vec::new(),
         allow: vec!["Allowed".to_string()],
         deny: vec!["Blocked".to_string()],
+        remotes: Vec::new(),
+        cache_dir: None,
+        remote_allow: vec!["*".to_string()],
+        remote_deny: Vec::new(),
     };
     let store = SkillStore::load(&config, temp.path()).expect("load store");
     let summaries = store.summaries();
     assert_eq!(summaries.len(), 1);
     assert_eq!(summaries[0].name, "Allowed");
 }
+
+/// Run git in a directory with a fixed identity.
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args([
+            "-c",
+            "user.name=Odyssey",
+            "-c",
+            "user.email=odyssey@example.com",
+        ])
+        .args(args)
+        .status()
+        .expect("run git");
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// Remote skills load from the cache and survive the remote going away.
+#[test]
+fn remote_skills_are_cached_and_refresh_falls_back_offline() {
+    let temp = tempdir().expect("tempdir");
+    let origin = temp.path().join("origin");
+    fs::create_dir_all(origin.join("skills").join("review")).expect("origin dir");
+    fs::write(
+        origin.join("skills").join("review").join("SKILL.md"),
+        "# Review\n\nReview pull requests.",
+    )
+    .expect("write skill");
+    git(&origin, &["init", "--quiet"]);
+    git(&origin, &["add", "."]);
+    git(&origin, &["commit", "--quiet", "-m", "skills"]);
+
+    let origin_url = origin.to_string_lossy().to_string();
+    let config = SkillsConfig {
+        setting_sources: Vec::new(),
+        paths: Vec::new(),
+        allow: vec!["*".to_string()],
+        deny: Vec::new(),
+        remotes: vec![SkillRemote {
+            url: origin_url.clone(),
+            reference: None,
+            subdir: Some("skills".to_string()),
+        }],
+        cache_dir: Some(temp.path().join("cache").to_string_lossy().to_string()),
+        remote_allow: vec!["*".to_string()],
+        remote_deny: Vec::new(),
+    };
+    let store = SkillStore::load(&config, temp.path()).expect("load store");
+    let names = store
+        .summaries()
+        .into_iter()
+        .map(|skill| skill.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Review".to_string()]);
+
+    fs::remove_dir_all(&origin).expect("remove origin");
+    let refreshed = SkillStore::refresh(&config, temp.path()).expect("refresh store");
+    assert_eq!(refreshed.summaries().len(), 1);
+
+    let denied = SkillsConfig {
+        remote_deny: vec![origin_url],
+        ..config
+    };
+    let store = SkillStore::load(&denied, temp.path()).expect("load store");
+    assert_eq!(store.summaries().is_empty(), true);
+}
@@ -0,0 +1,465 @@
+//! Submission queue integration tests.
+
+use autoagents_core::agent::prebuilt::executor::ReActAgent;
+use autoagents_llm::LLMProvider;
+use odyssey_rs_config::OdysseyConfig;
+use odyssey_rs_core::{AgentBuilder, DEFAULT_AGENT_ID, LLMEntry, OdysseyAgent, Orchestrator};
+use odyssey_rs_memory::FileMemoryProvider;
+use odyssey_rs_protocol::{
+    EventMsg, EventPayload, EventSink, SubmissionEnvelope, SubmissionPayload, TurnContextOverride,
+};
+use odyssey_rs_test_utils::StreamingLLM;
+use odyssey_rs_tools::builtin_tool_registry;
+use pretty_assertions::assert_eq;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::{TempDir, tempdir};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Event sink forwarding events into a channel.
+struct ChannelSink {
+    sender: mpsc::UnboundedSender<EventMsg>,
+}
+
+impl EventSink for ChannelSink {
+    fn emit(&self, event: EventMsg) {
+        let _ = self.sender.send(event);
+    }
+}
+
+/// Build an orchestrator wired to a channel event sink.
+fn build_orchestrator(temp: &TempDir) -> (Orchestrator, mpsc::UnboundedReceiver<EventMsg>) {
+    let mut config = OdysseyConfig::default();
+    config.memory.path = Some(temp.path().join("memory").to_string_lossy().to_string());
+    let memory = Arc::new(
+        FileMemoryProvider::new(PathBuf::from(
+            config.memory.path.clone().expect("memory path"),
+        ))
+        .expect("memory provider"),
+    );
+    let default_agent = AgentBuilder::new(
+        DEFAULT_AGENT_ID.to_string(),
+        ReActAgent::new(OdysseyAgent::new("Test agent".to_string(), Vec::new())),
+        memory,
+    );
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let orchestrator = Orchestrator::new(
+        config,
+        builtin_tool_registry(),
+        None,
+        None,
+        None,
+        Some(Arc::new(ChannelSink { sender })),
+    )
+    .expect("build orchestrator");
+    let llm: Arc<dyn LLMProvider> = Arc::new(StreamingLLM::new(vec![
+        "queued ".to_string(),
+        "response".to_string(),
+    ]));
+    orchestrator
+        .register_llm_provider(LLMEntry {
+            id: "default_LLM".to_string(),
+            provider: llm,
+        })
+        .expect("register llm");
+    orchestrator
+        .register_agent(default_agent)
+        .expect("register agent");
+    (orchestrator, receiver)
+}
+
+fn envelope(session_id: Uuid, payload: SubmissionPayload) -> SubmissionEnvelope {
+    SubmissionEnvelope {
+        id: Uuid::new_v4(),
+        session_id,
+        created_at: chrono::Utc::now(),
+        idempotency_key: None,
+        user_id: None,
+        payload,
+    }
+}
+
+/// Wait for the next event matching the predicate.
+async fn next_matching(
+    receiver: &mut mpsc::UnboundedReceiver<EventMsg>,
+    mut predicate: impl FnMut(&EventPayload) -> bool,
+) -> EventPayload {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let event = receiver.recv().await.expect("event");
+            if predicate(&event.payload) {
+                return event.payload;
+            }
+        }
+    })
+    .await
+    .expect("timed out waiting for event")
+}
+
+/// Context overrides and user messages should be applied in submission order.
+#[tokio::test]
+async fn submissions_apply_overrides_before_turns() {
+    let temp = tempdir().expect("tempdir");
+    let (orchestrator, mut events) = build_orchestrator(&temp);
+    let session_id = orchestrator.create_session(None).expect("session");
+
+    orchestrator
+        .submit(envelope(
+            session_id,
+            SubmissionPayload::OverrideTurnContext {
+                context: TurnContextOverride {
+                    simulation: Some(true),
+                    ..TurnContextOverride::default()
+                },
+            },
+        ))
+        .expect("submit override");
+    let message = envelope(
+        session_id,
+        SubmissionPayload::UserMessage {
+            content: "hello".to_string(),
+            attachments: Vec::new(),
+        },
+    );
+    let turn_id = message.id;
+    orchestrator.submit(message).expect("submit message");
+
+    let started = next_matching(&mut events, |payload| {
+        matches!(payload, EventPayload::TurnStarted { .. })
+    })
+    .await;
+    match started {
+        EventPayload::TurnStarted {
+            turn_id: started_id,
+            context,
+        } => {
+            assert_eq!(started_id, turn_id);
+            assert_eq!(context.simulation, Some(true));
+        }
+        other => panic!("unexpected event: {other:?}"),
+    }
+    let completed = next_matching(&mut events, |payload| {
+        matches!(payload, EventPayload::TurnCompleted { .. })
+    })
+    .await;
+    match completed {
+        EventPayload::TurnCompleted {
+            turn_id: completed_id,
+            message,
+        } => {
+            assert_eq!(completed_id, turn_id);
+            assert_eq!(message, "queued response");
+        }
+        other => panic!("unexpected event: {other:?}"),
+    }
+}
+
+/// A next-turn override should apply to the following turn only.
+#[tokio::test]
+async fn next_turn_override_applies_once() {
+    let temp = tempdir().expect("tempdir");
+    let (orchestrator, mut events) = build_orchestrator(&temp);
+    let session_id = orchestrator.create_session(None).expect("session");
+
+    orchestrator
+        .override_next_turn_context(
+            session_id,
+            TurnContextOverride {
+                temperature: Some(0.2),
+                ..TurnContextOverride::default()
+            },
+        )
+        .expect("override next turn");
+    let mut temperatures = Vec::new();
+    for content in ["first", "second"] {
+        orchestrator
+            .submit(envelope(
+                session_id,
+                SubmissionPayload::UserMessage {
+                    content: content.to_string(),
+                    attachments: Vec::new(),
+                },
+            ))
+            .expect("submit message");
+        match next_matching(&mut events, |payload| {
+            matches!(payload, EventPayload::TurnStarted { .. })
+        })
+        .await
+        {
+            EventPayload::TurnStarted { context, .. } => temperatures.push(context.temperature),
+            other => panic!("unexpected event: {other:?}"),
+        }
+        next_matching(&mut events, |payload| {
+            matches!(payload, EventPayload::TurnCompleted { .. })
+        })
+        .await;
+    }
+    assert_eq!(temperatures, vec![Some(0.2), None]);
+}
+
+/// Cancelling a queued turn should skip it and emit a cancellation event.
+#[tokio::test]
+async fn submissions_cancel_queued_turn() {
+    let temp = tempdir().expect("tempdir");
+    let (orchestrator, mut events) = build_orchestrator(&temp);
+    let session_id = orchestrator.create_session(None).expect("session");
+    let message = envelope(
+        session_id,
+        SubmissionPayload::UserMessage {
+            content: "never runs".to_string(),
+            attachments: Vec::new(),
+        },
+    );
+    let turn_id = message.id;
+
+    orchestrator
+        .submit(envelope(
+            session_id,
+            SubmissionPayload::CancelTurn { turn_id },
+        ))
+        .expect("submit cancel");
+    orchestrator.submit(message).expect("submit message");
+
+    let payload = next_matching(&mut events, |payload| {
+        matches!(
+            payload,
+            EventPayload::TurnCancelled { .. } | EventPayload::TurnStarted { .. }
+        )
+    })
+    .await;
+    match payload {
+        EventPayload::TurnCancelled {
+            turn_id: cancelled_id,
+        } => assert_eq!(cancelled_id, turn_id),
+        other => panic!("unexpected event: {other:?}"),
+    }
+    let session = orchestrator.resume_session(session_id).expect("session");
+    assert_eq!(session.messages.len(), 0);
+}
+
+/// Submissions for unknown sessions should be rejected up front.
+#[tokio::test]
+async fn submissions_reject_unknown_session() {
+    let temp = tempdir().expect("tempdir");
+    let (orchestrator, _events) = build_orchestrator(&temp);
+    let result = orchestrator.submit(envelope(
+        Uuid::new_v4(),
+        SubmissionPayload::UserMessage {
+            content: "hello".to_string(),
+            attachments: Vec::new(),
+        },
+    ));
+    assert!(result.is_err());
+}
+
+/// Events of a session should carry consecutive sequence numbers starting at 1.
+#[tokio::test]
+async fn session_events_are_numbered_in_order() {
+    let temp = tempdir().expect("tempdir");
+    let (orchestrator, mut events) = build_orchestrator(&temp);
+    let session_id = orchestrator.create_session(None).expect("session");
+    orchestrator
+        .submit(envelope(
+            session_id,
+            SubmissionPayload::UserMessage {
+                content: "hello".to_string(),
+                attachments: Vec::new(),
+            },
+        ))
+        .expect("submit message");
+
+    let seqs = tokio::time::timeout(Duration::from_secs(10), async {
+        let mut seqs = Vec::new();
+        loop {
+            let event = events.recv().await.expect("event");
+            assert_eq!(event.session_id, session_id);
+            seqs.push(event.seq);
+            if matches!(event.payload, EventPayload::TurnCompleted { .. }) {
+                return seqs;
+            }
+        }
+    })
+    .await
+    .expect("timed out waiting for turn");
+    assert_eq!(seqs, (1..=seqs.len() as u64).collect::<Vec<_>>());
+}
+
+/// Retried submissions with the same idempotency key should run once and report the original.
+#[tokio::test]
+async fn submissions_dedupe_idempotency_keys() {
+    let temp = tempdir().expect("tempdir");
+    let (orchestrator, mut events) = build_orchestrator(&temp);
+    let session_id = orchestrator.create_session(None).expect("session");
+    let keyed = || {
+        let mut message = envelope(
+            session_id,
+            SubmissionPayload::UserMessage {
+                content: "hello".to_string(),
+                attachments: Vec::new(),
+            },
+        );
+        message.idempotency_key = Some("retry-1".to_string());
+        message
+    };
+
+    let original = keyed();
+    let original_id = original.id;
+    let receipt = orchestrator.submit(original).expect("submit original");
+    assert_eq!(receipt.submission_id, original_id);
+    assert_eq!(receipt.duplicate, false);
+    let retry = orchestrator.submit(keyed()).expect("submit retry");
+    assert_eq!(retry.submission_id, original_id);
+    assert_eq!(retry.duplicate, true);
+
+    // Submissions run in order, so the next turn after the original is the unkeyed one.
+    let follow_up = envelope(
+        session_id,
+        SubmissionPayload::UserMessage {
+            content: "again".to_string(),
+            attachments: Vec::new(),
+        },
+    );
+    let follow_up_id = follow_up.id;
+    orchestrator.submit(follow_up).expect("submit follow-up");
+    for expected in [original_id, follow_up_id] {
+        let started = next_matching(&mut events, |payload| {
+            matches!(payload, EventPayload::TurnStarted { .. })
+        })
+        .await;
+        match started {
+            EventPayload::TurnStarted { turn_id, .. } => assert_eq!(turn_id, expected),
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    let late_retry = orchestrator.submit(keyed()).expect("submit late retry");
+    assert_eq!(late_retry.submission_id, original_id);
+    assert_eq!(late_retry.response.as_deref(), Some("queued response"));
+}
+
+/// A keyed submission whose turn did not complete should run again when retried.
+#[tokio::test]
+async fn cancelled_submissions_forget_idempotency_keys() {
+    let temp = tempdir().expect("tempdir");
+    let (orchestrator, mut events) = build_orchestrator(&temp);
+    let session_id = orchestrator.create_session(None).expect("session");
+    let keyed = || {
+        let mut message = envelope(
+            session_id,
+            SubmissionPayload::UserMessage {
+                content: "hello".to_string(),
+                attachments: Vec::new(),
+            },
+        );
+        message.idempotency_key = Some("retry-1".to_string());
+        message
+    };
+
+    let original = keyed();
+    let turn_id = original.id;
+    orchestrator
+        .submit(envelope(
+            session_id,
+            SubmissionPayload::CancelTurn { turn_id },
+        ))
+        .expect("submit cancel");
+    orchestrator.submit(original).expect("submit original");
+    next_matching(&mut events, |payload| {
+        matches!(payload, EventPayload::TurnCancelled { .. })
+    })
+    .await;
+
+    let retry = keyed();
+    let retry_id = retry.id;
+    let receipt = orchestrator.submit(retry).expect("submit retry");
+    assert_eq!(receipt.submission_id, retry_id);
+    assert_eq!(receipt.duplicate, false);
+    let started = next_matching(&mut events, |payload| {
+        matches!(payload, EventPayload::TurnStarted { .. })
+    })
+    .await;
+    match started {
+        EventPayload::TurnStarted { turn_id, .. } => assert_eq!(turn_id, retry_id),
+        other => panic!("unexpected event: {other:?}"),
+    }
+}
+
+/// Turns should be attributed to the submitting user, or else to the session owner.
+#[tokio::test]
+async fn submissions_attribute_turns_to_users() {
+    let temp = tempdir().expect("tempdir");
+    let (orchestrator, mut events) = build_orchestrator(&temp);
+    let session_id = orchestrator
+        .create_session_for_user(None, "alice")
+        .expect("session");
+    let shared = orchestrator.create_session(None).expect("shared session");
+    let bobs = orchestrator
+        .create_session_for_user(None, "bob")
+        .expect("bob's session");
+
+    let message = |content: &str| {
+        envelope(
+            session_id,
+            SubmissionPayload::UserMessage {
+                content: content.to_string(),
+                attachments: Vec::new(),
+            },
+        )
+    };
+    orchestrator.submit(message("hello")).expect("submit");
+    let mut delegated = message("again");
+    delegated.user_id = Some("bob".to_string());
+    orchestrator.submit(delegated).expect("submit as bob");
+
+    let mut completed = Vec::new();
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while completed.len() < 2 {
+            let event = events.recv().await.expect("event");
+            if matches!(event.payload, EventPayload::TurnCompleted { .. }) {
+                completed.push(event.user_id);
+            }
+        }
+    })
+    .await
+    .expect("timed out waiting for turns");
+    assert_eq!(
+        completed,
+        vec![Some("alice".to_string()), Some("bob".to_string())]
+    );
+
+    // The transcript is written after the completion event, so wait for both replies.
+    let session = tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let session = orchestrator.resume_session(session_id).expect("session");
+            if session.messages.len() == 4 {
+                return session;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for transcript");
+    assert_eq!(session.user_id.as_deref(), Some("alice"));
+    assert_eq!(
+        session
+            .messages
+            .iter()
+            .map(|message| message.user_id.as_deref())
+            .collect::<Vec<_>>(),
+        vec![Some("alice"), None, Some("bob"), None]
+    );
+
+    let mut visible = orchestrator
+        .list_sessions_for_user("alice")
+        .expect("list")
+        .into_iter()
+        .map(|summary| summary.id)
+        .collect::<Vec<_>>();
+    visible.sort();
+    let mut expected = vec![session_id, shared];
+    expected.sort();
+    assert_eq!(visible, expected);
+    assert_eq!(visible.contains(&bobs), false);
+}
@@ -0,0 +1,185 @@
+//! Summarized compaction of old memory records.
+//!
+//! Records older than the policy keeps are grouped into clusters of adjacent
+//! records, each cluster is replaced by one summary produced by a
+//! [`MemorySummarizer`], and the replaced originals are kept in a
+//! [`MemoryCompaction`] audit entry.
+
+use crate::error::MemoryError;
+use crate::model::MemoryRecord;
+use crate::policy::MemoryCompactionPolicy;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Most record characters summarized together in one cluster.
+pub const CLUSTER_MAX_CHARS: usize = 12_000;
+
+#[async_trait]
+/// Produces the summary that replaces a cluster of compacted records.
+pub trait MemorySummarizer: Send + Sync {
+    /// Summarize `records` in at most `max_chars` characters.
+    async fn summarize(
+        &self,
+        records: &[MemoryRecord],
+        max_chars: usize,
+    ) -> Result<String, MemoryError>;
+}
+
+/// Audit entry of one compaction run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MemoryCompaction {
+    /// Compaction identifier, also stored in each summary's metadata.
+    pub id: Uuid,
+    /// Session that was compacted.
+    pub session_id: Uuid,
+    /// When the compaction ran.
+    pub created_at: DateTime<Utc>,
+    /// Summary records that replaced the originals, oldest first.
+    pub summaries: Vec<MemoryRecord>,
+    /// Original records removed from the session.
+    pub replaced: Vec<MemoryRecord>,
+}
+
+/// Number of leading records to compact, or 0 when nothing is due.
+///
+/// Past `max_messages` or `max_total_chars`, records are compacted until both
+/// limits hold again. With `force`, a session within its limits is compacted
+/// down to half of `max_messages`.
+pub fn compaction_split(
+    records: &[MemoryRecord],
+    policy: &MemoryCompactionPolicy,
+    force: bool,
+) -> usize {
+    let mut split = records.len().saturating_sub(policy.max_messages);
+    if let Some(max_total) = policy.max_total_chars {
+        let mut total_chars: usize = records[split..]
+            .iter()
+            .map(|record| record.content.chars().count())
+            .sum();
+        while total_chars > max_total && split < records.len() {
+            total_chars = total_chars.saturating_sub(records[split].content.chars().count());
+            split += 1;
+        }
+    }
+    if split == 0 && force {
+        split = records.len().saturating_sub(policy.max_messages / 2);
+    }
+    split
+}
+
+/// Group adjacent records into clusters of at most `max_chars` characters.
+///
+/// A record longer than `max_chars` forms a cluster of its own.
+pub fn cluster_records(records: &[MemoryRecord], max_chars: usize) -> Vec<&[MemoryRecord]> {
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    let mut chars = 0;
+    for (index, record) in records.iter().enumerate() {
+        let len = record.content.chars().count();
+        if index > start && chars + len > max_chars {
+            clusters.push(&records[start..index]);
+            start = index;
+            chars = 0;
+        }
+        chars += len;
+    }
+    if start < records.len() {
+        clusters.push(&records[start..]);
+    }
+    clusters
+}
+
+/// Summarize each cluster of `removed` into a summary record.
+///
+/// Summaries keep the timestamp of their cluster's last record, so they sort
+/// before the records that were kept.
+pub async fn summarize_clusters(
+    compaction_id: Uuid,
+    session_id: Uuid,
+    removed: &[MemoryRecord],
+    max_chars: usize,
+    summarizer: &dyn MemorySummarizer,
+) -> Result<Vec<MemoryRecord>, MemoryError> {
+    let mut summaries = Vec::new();
+    for cluster in cluster_records(removed, CLUSTER_MAX_CHARS) {
+        let Some(last) = cluster.last() else {
+            continue;
+        };
+        let summary = summarizer.summarize(cluster, max_chars).await?;
+        let summary: String = summary.trim().chars().take(max_chars).collect();
+        if summary.is_empty() {
+            continue;
+        }
+        summaries.push(MemoryRecord {
+            id: Uuid::new_v4(),
+            session_id,
+            role: "system".to_string(),
+            content: summary,
+            metadata: serde_json::json!({
+                "summary": true,
+                "count": cluster.len(),
+                "compaction_id": compaction_id,
+            }),
+            created_at: last.created_at,
+        });
+    }
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cluster_records, compaction_split};
+    use crate::model::MemoryRecord;
+    use crate::policy::MemoryCompactionPolicy;
+    use chrono::Utc;
+    use pretty_assertions::assert_eq;
+    use uuid::Uuid;
+
+    fn record(content: &str) -> MemoryRecord {
+        MemoryRecord {
+            id: Uuid::new_v4(),
+            session_id: Uuid::nil(),
+            role: "user".to_string(),
+            content: content.to_string(),
+            metadata: serde_json::json!({}),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn splits_on_thresholds_or_when_forced() {
+        let records: Vec<_> = ["aaaa", "bbbb", "cccc", "dddd", "eeee", "ffff"]
+            .into_iter()
+            .map(record)
+            .collect();
+        let mut policy = MemoryCompactionPolicy {
+            enabled: true,
+            max_messages: 4,
+            summary_max_chars: 100,
+            max_total_chars: None,
+        };
+        assert_eq!(compaction_split(&records, &policy, false), 2);
+        policy.max_total_chars = Some(10);
+        assert_eq!(compaction_split(&records, &policy, false), 4);
+
+        policy.max_messages = 10;
+        policy.max_total_chars = None;
+        assert_eq!(compaction_split(&records, &policy, false), 0);
+        assert_eq!(compaction_split(&records, &policy, true), 1);
+    }
+
+    #[test]
+    fn clusters_adjacent_records_by_size() {
+        let records: Vec<_> = ["aaaa", "bbbb", "cccccccccc", "dd", "ee"]
+            .into_iter()
+            .map(record)
+            .collect();
+        let sizes: Vec<usize> = cluster_records(&records, 8)
+            .iter()
+            .map(|cluster| cluster.len())
+            .collect();
+        assert_eq!(sizes, vec![2, 1, 2]);
+    }
+}
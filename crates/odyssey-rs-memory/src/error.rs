@@ -15,4 +15,7 @@ pub enum MemoryError {
     /// Invalid instruction root.
     #[error("invalid instruction root: {0}")]
     InvalidRoot(String),
+    /// Summarizing compacted records failed.
+    #[error("summarizer error: {0}")]
+    Summarizer(String),
 }
@@ -1,11 +1,14 @@
 //! Memory capture and recall support for Odyssey.
 
+pub mod compaction;
 pub mod error;
 pub mod model;
 pub mod policy;
 pub mod provider;
 pub mod recall;
 
+/// Summarized compaction with an audit trail.
+pub use compaction::{MemoryCompaction, MemorySummarizer};
 /// Memory error type.
 pub use error::MemoryError;
 /// Memory record model.
@@ -13,6 +16,8 @@ pub use model::MemoryRecord;
 /// Capture and compaction policies.
 pub use policy::{MemoryCapturePolicy, MemoryCompactionPolicy};
 /// Memory provider interface and default file implementation.
-pub use provider::{FileMemoryProvider, MemoryProvider};
-/// Recall modes and options.
-pub use recall::{MemoryRecallMode, MemoryRecallOptions};
+pub use provider::{FileMemoryProvider, MemoryProvider, shannon_entropy};
+/// Recall modes, options, and scoring.
+pub use recall::{
+    MemoryRecallMode, MemoryRecallOptions, RecallExplanation, ScoredMemoryRecord, explain_recall,
+};
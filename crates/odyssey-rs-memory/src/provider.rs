@@ -1,11 +1,12 @@
 //! Memory provider implementations and policy enforcement.
 
+use crate::compaction::{MemoryCompaction, MemorySummarizer, compaction_split, summarize_clusters};
 use crate::error::MemoryError;
 use crate::model::MemoryRecord;
 use crate::policy::{MemoryCapturePolicy, MemoryCompactionPolicy};
-use crate::recall::{MemoryRecallMode, MemoryRecallOptions};
+use crate::recall::{MemoryRecallMode, MemoryRecallOptions, ScoredMemoryRecord, explain_recall};
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use log::{debug, info};
 use regex::Regex;
 use std::fs::OpenOptions;
@@ -51,6 +52,34 @@ pub trait MemoryProvider: Send + Sync {
         self.recall(session_id, query, limit).await
     }
 
+    /// Recall a session's newest `limit` records with the score and match reasons of each.
+    ///
+    /// Records are selected as for the agent's context, regardless of `query`; the
+    /// query only drives the scores. Providers with embeddings can override this to
+    /// report vector similarity.
+    async fn recall_explained(
+        &self,
+        session_id: Uuid,
+        query: Option<&str>,
+        limit: usize,
+        options: MemoryRecallOptions,
+    ) -> Result<Vec<ScoredMemoryRecord>, MemoryError> {
+        let now = Utc::now();
+        let records = self
+            .recall_with_options(session_id, None, limit, options)
+            .await?;
+        Ok(records
+            .into_iter()
+            .map(|record| {
+                let explanation = explain_recall(&record, query, None, &options, now);
+                ScoredMemoryRecord {
+                    record,
+                    explanation,
+                }
+            })
+            .collect())
+    }
+
     /// Recall global memory records for system prompt assembly.
     async fn recall_initial(
         &self,
@@ -70,6 +99,59 @@ pub trait MemoryProvider: Send + Sync {
     ) -> Result<Option<MemoryRecord>, MemoryError> {
         Ok(None)
     }
+
+    /// Compact a session, replacing old records with summaries from `summarizer`.
+    ///
+    /// `force` compacts a session that is still within the policy limits. Returns the
+    /// audit entry, or `None` when nothing was compacted or the provider cannot compact.
+    async fn compact_with_summarizer(
+        &self,
+        _session_id: Uuid,
+        _policy: &MemoryCompactionPolicy,
+        _summarizer: &dyn MemorySummarizer,
+        _force: bool,
+    ) -> Result<Option<MemoryCompaction>, MemoryError> {
+        Ok(None)
+    }
+
+    /// Remove a session's records created at or after `since`, returning how many were removed.
+    ///
+    /// Used when a turn is retried so the discarded branch is not recalled. Providers that
+    /// cannot delete records keep them and return 0.
+    async fn forget_since(
+        &self,
+        _session_id: Uuid,
+        _since: DateTime<Utc>,
+    ) -> Result<usize, MemoryError> {
+        Ok(0)
+    }
+
+    /// Store a durable fact shared by every session of the project `namespace`.
+    ///
+    /// The capture policy applies as for messages. Returns false when the fact was
+    /// filtered, is already stored, or the provider has no project storage.
+    async fn remember_project(
+        &self,
+        _namespace: &str,
+        _record: MemoryRecord,
+        _policy: &MemoryCapturePolicy,
+    ) -> Result<bool, MemoryError> {
+        Ok(false)
+    }
+
+    /// Recall the newest `limit` facts of the project `namespace`, oldest first.
+    async fn recall_project(
+        &self,
+        _namespace: &str,
+        _limit: usize,
+    ) -> Result<Vec<MemoryRecord>, MemoryError> {
+        Ok(Vec::new())
+    }
+
+    /// Persist buffered records; providers that write through need not override this.
+    async fn flush(&self) -> Result<(), MemoryError> {
+        Ok(())
+    }
 }
 
 /// File-backed memory provider storing JSONL records per session.
@@ -98,24 +180,31 @@ impl FileMemoryProvider {
         self.root.join(format!("{session_id}.jsonl.tmp"))
     }
 
+    /// Path to the compaction audit log of a session.
+    fn audit_path(&self, session_id: Uuid) -> PathBuf {
+        self.root
+            .join("compactions")
+            .join(format!("{session_id}.jsonl"))
+    }
+
+    /// Path to the JSONL file of a project namespace; unsafe characters become `_`.
+    fn project_path(&self, namespace: &str) -> PathBuf {
+        let name: String = namespace
+            .chars()
+            .map(|ch| {
+                if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                    ch
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.root.join("projects").join(format!("{name}.jsonl"))
+    }
+
     /// Load all records for a session.
     fn load_records(&self, session_id: Uuid) -> Result<Vec<MemoryRecord>, MemoryError> {
-        let path = self.session_path(session_id);
-        if !path.exists() {
-            return Ok(Vec::new());
-        }
-        let file = OpenOptions::new().read(true).open(path)?;
-        let reader = BufReader::new(file);
-        let mut records = Vec::new();
-        for line in reader.lines() {
-            let line = line?;
-            if line.trim().is_empty() {
-                continue;
-            }
-            let record: MemoryRecord = serde_json::from_str(&line)?;
-            records.push(record);
-        }
-        Ok(records)
+        read_records(&self.session_path(session_id))
     }
 
     /// Rewrite a session's records atomically.
@@ -133,9 +222,6 @@ impl FileMemoryProvider {
                 writeln!(file, "{line}")?;
             }
         }
-        if path.exists() {
-            std::fs::remove_file(&path)?;
-        }
         std::fs::rename(temp_path, path)?;
         Ok(())
     }
@@ -201,41 +287,11 @@ impl MemoryProvider for FileMemoryProvider {
             return Ok(None);
         }
         let mut records = self.load_records(session_id)?;
-        if records.is_empty() {
-            return Ok(None);
-        }
-
-        let mut needs_compaction = records.len() > policy.max_messages;
-        if let Some(max_total) = policy.max_total_chars {
-            let total_chars: usize = records
-                .iter()
-                .map(|record| record.content.chars().count())
-                .sum();
-            needs_compaction = needs_compaction || total_chars > max_total;
-        }
-        if !needs_compaction {
+        let split = compaction_split(&records, policy, false);
+        if split == 0 {
             return Ok(None);
         }
-
-        let mut removed = Vec::new();
-        if policy.max_messages == 0 {
-            removed.append(&mut records);
-        } else if records.len() > policy.max_messages {
-            let split = records.len().saturating_sub(policy.max_messages);
-            removed.extend(records.drain(..split));
-        }
-
-        if let Some(max_total) = policy.max_total_chars {
-            let mut total_chars: usize = records
-                .iter()
-                .map(|record| record.content.chars().count())
-                .sum();
-            while total_chars > max_total && !records.is_empty() {
-                let record = records.remove(0);
-                total_chars = total_chars.saturating_sub(record.content.chars().count());
-                removed.push(record);
-            }
-        }
+        let removed: Vec<MemoryRecord> = records.drain(..split).collect();
 
         let summary = build_summary_record(session_id, &removed, policy.summary_max_chars);
         let mut next_records = Vec::new();
@@ -252,6 +308,156 @@ impl MemoryProvider for FileMemoryProvider {
         );
         Ok(summary)
     }
+
+    /// Replace old records with summaries and append the originals to the audit log.
+    ///
+    /// Summarizing can take a while, so the session is re-read afterwards; records
+    /// stored meanwhile are kept, and the run is abandoned if the compacted records
+    /// changed.
+    async fn compact_with_summarizer(
+        &self,
+        session_id: Uuid,
+        policy: &MemoryCompactionPolicy,
+        summarizer: &dyn MemorySummarizer,
+        force: bool,
+    ) -> Result<Option<MemoryCompaction>, MemoryError> {
+        let records = self.load_records(session_id)?;
+        let split = compaction_split(&records, policy, force);
+        if split == 0 {
+            return Ok(None);
+        }
+        let id = Uuid::new_v4();
+        let summaries = summarize_clusters(
+            id,
+            session_id,
+            &records[..split],
+            policy.summary_max_chars,
+            summarizer,
+        )
+        .await?;
+
+        let current = self.load_records(session_id)?;
+        let unchanged = current.len() >= split
+            && current[..split]
+                .iter()
+                .zip(&records[..split])
+                .all(|(current, original)| current.id == original.id);
+        if !unchanged {
+            info!("memory compaction abandoned, session changed (session_id={session_id})");
+            return Ok(None);
+        }
+        let compaction = MemoryCompaction {
+            id,
+            session_id,
+            created_at: Utc::now(),
+            summaries,
+            replaced: records[..split].to_vec(),
+        };
+        let audit_path = self.audit_path(session_id);
+        if let Some(parent) = audit_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut audit = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(audit_path)?;
+        writeln!(audit, "{}", serde_json::to_string(&compaction)?)?;
+
+        let mut next_records = compaction.summaries.clone();
+        next_records.extend_from_slice(&current[split..]);
+        self.write_records(session_id, &next_records)?;
+        info!(
+            "memory compacted with summaries (session_id={}, compaction_id={}, replaced={}, summaries={})",
+            session_id,
+            id,
+            compaction.replaced.len(),
+            compaction.summaries.len()
+        );
+        Ok(Some(compaction))
+    }
+
+    /// Append a project fact unless the same content is already stored.
+    async fn remember_project(
+        &self,
+        namespace: &str,
+        record: MemoryRecord,
+        policy: &MemoryCapturePolicy,
+    ) -> Result<bool, MemoryError> {
+        let Some(record) = apply_capture_policy(record, policy)? else {
+            return Ok(false);
+        };
+        let path = self.project_path(namespace);
+        if read_records(&path)?
+            .iter()
+            .any(|existing| existing.content == record.content)
+        {
+            return Ok(false);
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        let line = serde_json::to_string(&record)?;
+        writeln!(file, "{line}")?;
+        debug!(
+            "stored project memory (namespace={}, content_len={})",
+            namespace,
+            record.content.len()
+        );
+        Ok(true)
+    }
+
+    /// Recall the newest project facts.
+    async fn recall_project(
+        &self,
+        namespace: &str,
+        limit: usize,
+    ) -> Result<Vec<MemoryRecord>, MemoryError> {
+        let records = read_records(&self.project_path(namespace))?;
+        let start = records.len().saturating_sub(limit);
+        Ok(records[start..].to_vec())
+    }
+
+    /// Rewrite the session file without records created at or after `since`.
+    async fn forget_since(
+        &self,
+        session_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<usize, MemoryError> {
+        let mut records = self.load_records(session_id)?;
+        let before = records.len();
+        records.retain(|record| record.created_at < since);
+        let removed = before - records.len();
+        if removed > 0 {
+            self.write_records(session_id, &records)?;
+            info!(
+                "memory records forgotten (session_id={}, removed={}, remaining={})",
+                session_id,
+                removed,
+                records.len()
+            );
+        }
+        Ok(removed)
+    }
+}
+
+/// Read JSONL records from `path`; a missing file has none.
+fn read_records(path: &Path) -> Result<Vec<MemoryRecord>, MemoryError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = OpenOptions::new().read(true).open(path)?;
+    let reader = BufReader::new(file);
+    let mut records = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: MemoryRecord = serde_json::from_str(&line)?;
+        records.push(record);
+    }
+    Ok(records)
 }
 
 /// Apply capture policy to a record, returning None if filtered.
@@ -376,8 +582,8 @@ fn redact_high_entropy(content: &str, threshold: f32, replacement: &str) -> Stri
         .to_string()
 }
 
-/// Calculate Shannon entropy for a token string.
-fn shannon_entropy(token: &str) -> f32 {
+/// Calculate Shannon entropy, in bits per byte, for a token string.
+pub fn shannon_entropy(token: &str) -> f32 {
     let mut counts = [0usize; 256];
     let bytes = token.as_bytes();
     if bytes.is_empty() {
@@ -401,7 +607,10 @@ mod tests {
         FileMemoryProvider, MemoryProvider, apply_capture_policy, redact_high_entropy,
         truncate_chars,
     };
-    use crate::{MemoryCapturePolicy, MemoryCompactionPolicy, MemoryRecord};
+    use crate::{
+        MemoryCapturePolicy, MemoryCompaction, MemoryCompactionPolicy, MemoryError, MemoryRecord,
+        MemorySummarizer,
+    };
     use chrono::Utc;
     use pretty_assertions::assert_eq;
     use serde_json::json;
@@ -513,9 +722,169 @@ mod tests {
         assert_eq!(records[1], record_c);
     }
 
+    struct JoinSummarizer;
+
+    #[async_trait::async_trait]
+    impl MemorySummarizer for JoinSummarizer {
+        async fn summarize(
+            &self,
+            records: &[MemoryRecord],
+            _max_chars: usize,
+        ) -> Result<String, MemoryError> {
+            let contents: Vec<&str> = records
+                .iter()
+                .map(|record| record.content.as_str())
+                .collect();
+            Ok(format!("summary of {}", contents.join(", ")))
+        }
+    }
+
+    #[tokio::test]
+    async fn compact_with_summarizer_replaces_records_and_keeps_audit() {
+        let temp = tempdir().expect("tempdir");
+        let provider = FileMemoryProvider::new(temp.path()).expect("provider");
+        let session_id = Uuid::new_v4();
+        let mut kept = Vec::new();
+        for content in ["one", "two", "three", "four"] {
+            let record = MemoryRecord {
+                session_id,
+                ..base_record(content)
+            };
+            provider.store(record.clone()).await.expect("store");
+            kept.push(record);
+        }
+        let policy = MemoryCompactionPolicy {
+            enabled: true,
+            max_messages: 4,
+            summary_max_chars: 18,
+            max_total_chars: None,
+        };
+        let compaction = provider
+            .compact_with_summarizer(session_id, &policy, &JoinSummarizer, false)
+            .await
+            .expect("compact");
+        assert_eq!(compaction, None);
+
+        let compaction = provider
+            .compact_with_summarizer(session_id, &policy, &JoinSummarizer, true)
+            .await
+            .expect("compact")
+            .expect("compaction");
+        assert_eq!(compaction.replaced, kept[..2].to_vec());
+        assert_eq!(compaction.summaries.len(), 1);
+        let summary = &compaction.summaries[0];
+        assert_eq!(summary.content, "summary of one, tw");
+        assert_eq!(summary.metadata["compaction_id"], json!(compaction.id));
+        assert_eq!(summary.created_at, kept[1].created_at);
+
+        let records = provider.recall(session_id, None, 10).await.expect("recall");
+        assert_eq!(records.len(), 3);
+        assert_eq!(&records[0], summary);
+        assert_eq!(records[1..].to_vec(), kept[2..].to_vec());
+
+        let audit = std::fs::read_to_string(
+            temp.path()
+                .join("compactions")
+                .join(format!("{session_id}.jsonl")),
+        )
+        .expect("audit");
+        let logged: MemoryCompaction = serde_json::from_str(audit.trim()).expect("entry");
+        assert_eq!(logged, compaction);
+    }
+
     #[test]
     fn redact_high_entropy_uses_replacement() {
         let redacted = redact_high_entropy("ABCDEFGHIJKLMNOPQRSTUVWX", 0.1, "[X]");
         assert_eq!(redacted, "[X]");
     }
+
+    #[tokio::test]
+    async fn forget_since_drops_newer_records() {
+        let temp = tempdir().expect("tempdir");
+        let provider = FileMemoryProvider::new(temp.path()).expect("provider");
+        let session_id = Uuid::new_v4();
+        let cutoff = Utc::now();
+        let older = MemoryRecord {
+            session_id,
+            created_at: cutoff - chrono::Duration::seconds(5),
+            ..base_record("kept")
+        };
+        let newer = MemoryRecord {
+            session_id,
+            created_at: cutoff,
+            ..base_record("dropped")
+        };
+        provider.store(older.clone()).await.expect("store older");
+        provider.store(newer).await.expect("store newer");
+
+        let removed = provider
+            .forget_since(session_id, cutoff)
+            .await
+            .expect("forget");
+        assert_eq!(removed, 1);
+        let records = provider.recall(session_id, None, 10).await.expect("recall");
+        assert_eq!(records, vec![older]);
+        assert_eq!(
+            provider
+                .forget_since(session_id, cutoff)
+                .await
+                .expect("forget"),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn project_memory_is_shared_deduplicated_and_redacted() {
+        let temp = tempdir().expect("tempdir");
+        let provider = FileMemoryProvider::new(temp.path()).expect("provider");
+        let policy = MemoryCapturePolicy {
+            redact_patterns: vec!["hunter2".to_string()],
+            ..MemoryCapturePolicy::default()
+        };
+        let fact = |content: &str| base_record(content);
+
+        for content in [
+            "Tests live in tests/",
+            "Use thiserror",
+            "Tests live in tests/",
+        ] {
+            provider
+                .remember_project("acme/app", fact(content), &policy)
+                .await
+                .expect("remember");
+        }
+        provider
+            .remember_project("acme/app", fact("password is hunter2"), &policy)
+            .await
+            .expect("remember");
+        provider
+            .remember_project("other", fact("Unrelated"), &policy)
+            .await
+            .expect("remember");
+
+        let recalled = provider
+            .recall_project("acme/app", 2)
+            .await
+            .expect("recall");
+        let contents = recalled
+            .iter()
+            .map(|record| record.content.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(contents, vec!["Use thiserror", "password is [REDACTED]"]);
+        assert_eq!(
+            provider
+                .recall_project("acme/app", 10)
+                .await
+                .expect("recall")
+                .len(),
+            3
+        );
+        assert_eq!(
+            temp.path()
+                .join("projects")
+                .join("acme_app.jsonl")
+                .is_file(),
+            true
+        );
+    }
 }
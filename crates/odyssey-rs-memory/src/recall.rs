@@ -1,4 +1,13 @@
-//! Memory recall configuration.
+//! Memory recall configuration and scoring.
+
+use crate::model::MemoryRecord;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Largest score bonus a record gets for being recent.
+pub const RECENCY_MAX_BOOST: f32 = 0.1;
+/// Age in hours at which the recency bonus has halved.
+pub const RECENCY_HALF_LIFE_HOURS: f32 = 24.0;
 
 /// Recall modes supported by memory providers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,6 +27,10 @@ pub struct MemoryRecallOptions {
     pub mode: MemoryRecallMode,
     /// Optional minimum score filter.
     pub min_score: Option<f32>,
+    /// Weight of the text match score in hybrid mode.
+    pub text_weight: f32,
+    /// Weight of the vector similarity in hybrid mode.
+    pub vector_weight: f32,
 }
 
 impl Default for MemoryRecallOptions {
@@ -26,6 +39,141 @@ impl Default for MemoryRecallOptions {
         Self {
             mode: MemoryRecallMode::Text,
             min_score: None,
+            text_weight: 0.3,
+            vector_weight: 0.7,
+        }
+    }
+}
+
+/// Why a record was recalled, and how it scored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecallExplanation {
+    /// Combined score: the mode's match score plus the recency boost.
+    pub score: f32,
+    /// Query terms found in the record, in query order.
+    pub matched_terms: Vec<String>,
+    /// Share of query terms found in the record, from 0 to 1.
+    pub text_score: f32,
+    /// Embedding similarity, when the provider computed one.
+    pub vector_similarity: Option<f32>,
+    /// Bonus for recent records, up to [`RECENCY_MAX_BOOST`].
+    pub recency_boost: f32,
+}
+
+/// A recalled record with its explanation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoredMemoryRecord {
+    /// Recalled record.
+    pub record: MemoryRecord,
+    /// Score and match reasons.
+    pub explanation: RecallExplanation,
+}
+
+/// Score `record` against `query` the way `options` weighs recall signals.
+///
+/// Without a vector similarity, vector mode falls back to the text score and
+/// hybrid mode counts only the weighted text score.
+pub fn explain_recall(
+    record: &MemoryRecord,
+    query: Option<&str>,
+    vector_similarity: Option<f32>,
+    options: &MemoryRecallOptions,
+    now: DateTime<Utc>,
+) -> RecallExplanation {
+    let terms = query.map(query_terms).unwrap_or_default();
+    let content = record.content.to_lowercase();
+    let matched_terms: Vec<String> = terms
+        .iter()
+        .filter(|term| content.contains(term.as_str()))
+        .cloned()
+        .collect();
+    let text_score = if terms.is_empty() {
+        0.0
+    } else {
+        matched_terms.len() as f32 / terms.len() as f32
+    };
+    let match_score = match options.mode {
+        MemoryRecallMode::Text => text_score,
+        MemoryRecallMode::Vector => vector_similarity.unwrap_or(text_score),
+        MemoryRecallMode::Hybrid => {
+            options.text_weight * text_score
+                + options.vector_weight * vector_similarity.unwrap_or(0.0)
         }
+    };
+    let age_hours = (now - record.created_at).num_seconds().max(0) as f32 / 3600.0;
+    let recency_boost = RECENCY_MAX_BOOST * 0.5f32.powf(age_hours / RECENCY_HALF_LIFE_HOURS);
+    RecallExplanation {
+        score: match_score + recency_boost,
+        matched_terms,
+        text_score,
+        vector_similarity,
+        recency_boost,
+    }
+}
+
+/// Distinct lowercase words of a query, ignoring one-letter words.
+fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in query
+        .split(|ch: char| !ch.is_alphanumeric() && ch != '_')
+        .filter(|word| word.chars().count() > 1)
+    {
+        let word = word.to_lowercase();
+        if !terms.contains(&word) {
+            terms.push(word);
+        }
+    }
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        MemoryRecallMode, MemoryRecallOptions, RECENCY_MAX_BOOST, RecallExplanation, explain_recall,
+    };
+    use crate::model::MemoryRecord;
+    use chrono::{Duration, Utc};
+    use pretty_assertions::assert_eq;
+    use uuid::Uuid;
+
+    #[test]
+    fn explains_matched_terms_weights_and_recency() {
+        let now = Utc::now();
+        let record = MemoryRecord {
+            id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            role: "user".to_string(),
+            content: "Deploy the API with Docker".to_string(),
+            metadata: serde_json::json!({}),
+            created_at: now - Duration::hours(24),
+        };
+        let options = MemoryRecallOptions::default();
+        assert_eq!(
+            explain_recall(
+                &record,
+                Some("docker deploy? k8s docker"),
+                None,
+                &options,
+                now
+            ),
+            RecallExplanation {
+                score: 2.0 / 3.0 + RECENCY_MAX_BOOST / 2.0,
+                matched_terms: vec!["docker".to_string(), "deploy".to_string()],
+                text_score: 2.0 / 3.0,
+                vector_similarity: None,
+                recency_boost: RECENCY_MAX_BOOST / 2.0,
+            }
+        );
+
+        let hybrid = MemoryRecallOptions {
+            mode: MemoryRecallMode::Hybrid,
+            ..options
+        };
+        let explanation = explain_recall(&record, Some("docker"), Some(0.5), &hybrid, now);
+        assert_eq!(explanation.score, 0.3 + 0.7 * 0.5 + RECENCY_MAX_BOOST / 2.0);
+        assert_eq!(
+            explain_recall(&record, None, None, &options, now).matched_terms,
+            Vec::<String>::new()
+        );
     }
 }
@@ -0,0 +1,5 @@
+//! Emit the linker arguments Node.js addons need.
+
+fn main() {
+    napi_build::setup();
+}
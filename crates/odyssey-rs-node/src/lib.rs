@@ -0,0 +1,268 @@
+//! Node.js bindings for the Odyssey orchestrator.
+//!
+//! Built with napi-rs so the web UI and editor extensions can embed the engine
+//! in-process. The orchestrator is assembled like the `odyssey-rs-tui` binary
+//! (same config loading, sandbox, and default agent), and every event it emits
+//! is forwarded to JavaScript as a plain JSON object.
+
+use autoagents_core::agent::prebuilt::executor::ReActAgent;
+use log::{debug, info, warn};
+use napi::bindgen_prelude::spawn;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Error, Result, Status};
+use napi_derive::napi;
+use odyssey_rs_core::orchestrator::prompt::PromptProfile;
+use odyssey_rs_core::skills::SkillStore;
+use odyssey_rs_core::{
+    AgentBuilder, DEFAULT_AGENT_ID, LLMEntry, OdysseyAgent, Orchestrator, PromptBuilder,
+};
+use odyssey_rs_memory::FileMemoryProvider;
+use odyssey_rs_protocol::{ApprovalDecision, EventKind};
+use odyssey_rs_tools::builtin_tool_registry;
+use odyssey_rs_tui::bootstrap::{
+    DEFAULT_LLM_ID, ENV_OPENAI_API_KEY, build_openai_llm, default_sandbox_provider, load_config,
+    openai_api_key,
+};
+use odyssey_rs_tui::{EventBus, EventFilter};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Buffered events per subscriber before slow listeners start skipping.
+const EVENT_BUFFER: usize = 2048;
+const ENV_OPENAI_MODEL: &str = "OPENAI_MODEL";
+const DEFAULT_MODEL: &str = "gpt-5.2";
+
+/// Options for `Odyssey.create`.
+#[napi(object)]
+#[derive(Default)]
+pub struct OdysseyOptions {
+    /// Path to an odyssey.json5 file; the layered config for `cwd` is used otherwise.
+    pub config_path: Option<String>,
+    /// Workspace directory; defaults to the process working directory.
+    pub cwd: Option<String>,
+    /// OpenAI model name; defaults to `OPENAI_MODEL`, then `gpt-5.2`.
+    pub model: Option<String>,
+    /// OpenAI API key; defaults to `providers.openai.api_key_ref`, then `OPENAI_API_KEY`.
+    pub api_key: Option<String>,
+}
+
+/// Embedded orchestrator handle.
+#[napi]
+pub struct Odyssey {
+    orchestrator: Arc<Orchestrator>,
+    events: EventBus,
+}
+
+#[napi]
+impl Odyssey {
+    /// Build an orchestrator with the default agent and an OpenAI model.
+    #[napi]
+    pub async fn create(options: Option<OdysseyOptions>) -> Result<Odyssey> {
+        let options = options.unwrap_or_default();
+        let cwd = match options.cwd {
+            Some(cwd) => PathBuf::from(cwd),
+            None => std::env::current_dir().map_err(to_napi)?,
+        };
+        let config =
+            load_config(options.config_path.as_deref().map(Path::new), &cwd).map_err(to_napi)?;
+        let api_key = match options.api_key {
+            Some(api_key) => Some(api_key),
+            None => openai_api_key(&config).map_err(to_napi)?,
+        }
+        .ok_or_else(|| {
+            Error::new(
+                Status::InvalidArg,
+                format!(
+                    "apiKey, {ENV_OPENAI_API_KEY}, or providers.openai.api_key_ref is required"
+                ),
+            )
+        })?;
+        let model = options
+            .model
+            .or_else(|| std::env::var(ENV_OPENAI_MODEL).ok())
+            .unwrap_or_else(|| DEFAULT_MODEL.to_string());
+        let llm = build_openai_llm(api_key, &model).map_err(to_napi)?;
+
+        let memory_root = config
+            .memory
+            .path
+            .clone()
+            .unwrap_or_else(|| ".odyssey/memory".to_string());
+        let memory = Arc::new(FileMemoryProvider::new(cwd.join(memory_root)).map_err(to_napi)?);
+        let skill_store = Arc::new(SkillStore::load(&config.skills, &cwd).map_err(to_napi)?);
+        let system_prompt = PromptBuilder::new(memory.clone(), Some(skill_store.clone()))
+            .with_locale(&config.locale)
+            .build_system_prompt(
+                "",
+                &config.memory,
+                PromptProfile::from_config(&config).map_err(to_napi)?,
+            )
+            .await
+            .map_err(to_napi)?;
+        let events = EventBus::new(EVENT_BUFFER);
+        let orchestrator = Orchestrator::new(
+            config,
+            builtin_tool_registry(),
+            default_sandbox_provider().map_err(to_napi)?,
+            None,
+            Some(skill_store),
+            Some(Arc::new(events.clone())),
+        )
+        .map_err(to_napi)?;
+        orchestrator
+            .register_llm_provider(LLMEntry {
+                id: DEFAULT_LLM_ID.to_string(),
+                provider: llm,
+            })
+            .map_err(to_napi)?;
+        orchestrator
+            .register_agent(AgentBuilder::new(
+                DEFAULT_AGENT_ID.to_string(),
+                ReActAgent::new(OdysseyAgent::new(system_prompt, Vec::new())),
+                memory,
+            ))
+            .map_err(to_napi)?;
+        info!(
+            "node orchestrator created (cwd={}, model={model})",
+            cwd.display()
+        );
+        Ok(Self {
+            orchestrator: Arc::new(orchestrator),
+            events,
+        })
+    }
+
+    /// Create a session, optionally for a specific agent, and return its id.
+    #[napi]
+    pub fn create_session(&self, agent_id: Option<String>) -> Result<String> {
+        let session_id = self
+            .orchestrator
+            .create_session(agent_id)
+            .map_err(to_napi)?;
+        Ok(session_id.to_string())
+    }
+
+    /// Send a prompt to a session and resolve with the final response.
+    ///
+    /// The turn streams, so `streamEvents` listeners see deltas as they arrive.
+    #[napi]
+    pub async fn send_message(
+        &self,
+        session_id: String,
+        prompt: String,
+        llm_id: Option<String>,
+    ) -> Result<String> {
+        let session_id = parse_id(&session_id)?;
+        let session = self
+            .orchestrator
+            .resume_session(session_id)
+            .map_err(to_napi)?;
+        let llm_id = llm_id.unwrap_or_else(|| DEFAULT_LLM_ID.to_string());
+        debug!(
+            "sending message (session_id={session_id}, prompt_len={})",
+            prompt.len()
+        );
+        let stream = self
+            .orchestrator
+            .run_stream_in_session(session_id, &session.agent_id, &llm_id, prompt)
+            .await
+            .map_err(to_napi)?;
+        let result = stream.finish().await.map_err(to_napi)?;
+        Ok(result.response)
+    }
+
+    /// Call `callback` with every event of a session until the subscription is closed.
+    ///
+    /// Events are `EventMsg` objects as serialized by `odyssey-rs-protocol`. When
+    /// `kinds` is given (e.g. `["tool_call", "error"]`), only events of those kinds
+    /// are delivered.
+    #[napi(ts_args_type = "sessionId: string, callback: (event: any) => void, kinds?: string[]")]
+    pub fn stream_events(
+        &self,
+        session_id: String,
+        callback: ThreadsafeFunction<Value, ErrorStrategy::Fatal>,
+        kinds: Option<Vec<String>>,
+    ) -> Result<EventSubscription> {
+        let session_id = parse_id(&session_id)?;
+        let mut filter = EventFilter::default().session(session_id);
+        if let Some(kinds) = kinds {
+            filter = filter.kinds(
+                kinds
+                    .iter()
+                    .map(|kind| parse_kind(kind))
+                    .collect::<Result<Vec<_>>>()?,
+            );
+        }
+        let mut receiver = self.events.subscribe_filtered(filter);
+        debug!("subscribing to events (session_id={session_id})");
+        let handle = spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                match serde_json::to_value(&event) {
+                    Ok(value) => {
+                        callback.call(value, ThreadsafeFunctionCallMode::NonBlocking);
+                    }
+                    Err(err) => warn!("failed to serialize event: {err}"),
+                }
+            }
+            debug!("event subscription ended (session_id={session_id})");
+        });
+        Ok(EventSubscription {
+            handle: Some(handle),
+        })
+    }
+
+    /// Resolve a pending permission request with `allow_once`, `allow_always`, or `deny`.
+    ///
+    /// Returns false when the request is no longer pending.
+    #[napi]
+    pub fn resolve_permission(&self, request_id: String, decision: String) -> Result<bool> {
+        let request_id = parse_id(&request_id)?;
+        let decision = parse_decision(&decision)?;
+        Ok(self.orchestrator.resolve_approval(request_id, decision))
+    }
+}
+
+/// Handle returned by `streamEvents`.
+#[napi]
+pub struct EventSubscription {
+    handle: Option<JoinHandle<()>>,
+}
+
+#[napi]
+impl EventSubscription {
+    /// Stop delivering events.
+    #[napi]
+    pub fn close(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Parse a session or request id passed from JavaScript.
+fn parse_id(id: &str) -> Result<Uuid> {
+    Uuid::parse_str(id).map_err(|_| Error::new(Status::InvalidArg, format!("invalid id: {id}")))
+}
+
+/// Parse an approval decision in its snake_case wire form.
+fn parse_decision(decision: &str) -> Result<ApprovalDecision> {
+    serde_json::from_value(Value::String(decision.to_string())).map_err(|_| {
+        Error::new(
+            Status::InvalidArg,
+            format!("invalid decision: {decision} (expected allow_once, allow_always, or deny)"),
+        )
+    })
+}
+
+fn parse_kind(kind: &str) -> Result<EventKind> {
+    serde_json::from_value(Value::String(kind.to_string()))
+        .map_err(|_| Error::new(Status::InvalidArg, format!("invalid event kind: {kind}")))
+}
+
+/// Map any displayable error to a JavaScript exception.
+fn to_napi(err: impl std::fmt::Display) -> Error {
+    Error::from_reason(err.to_string())
+}
@@ -0,0 +1,97 @@
+use crate::SessionId;
+use crate::tool::ToolError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Scheme prefix for artifact references embedded in messages.
+pub const ARTIFACT_REFERENCE_SCHEME: &str = "artifact://";
+
+/// Metadata for a published artifact.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArtifactSummary {
+    /// Unique id of this artifact version.
+    pub id: Uuid,
+    /// Artifact name; publishing the same name replaces the previous version.
+    pub name: String,
+    /// Optional human-readable description.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Optional media type (e.g. `text/markdown`).
+    #[serde(default)]
+    pub media_type: Option<String>,
+    /// Content size in bytes.
+    pub size: u64,
+    /// Session that published the artifact, if any.
+    #[serde(default)]
+    pub session_id: Option<SessionId>,
+    /// Publish timestamp.
+    pub created_at: DateTime<Utc>,
+    /// Free-form metadata.
+    #[serde(default)]
+    pub metadata: Value,
+}
+
+impl ArtifactSummary {
+    /// Reference string that can be embedded in messages to point at this artifact.
+    pub fn reference(&self) -> String {
+        artifact_reference(&self.name)
+    }
+}
+
+/// Request to publish a named artifact.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactPublish {
+    /// Artifact name.
+    pub name: String,
+    /// Artifact content.
+    pub content: Vec<u8>,
+    /// Optional description.
+    pub description: Option<String>,
+    /// Optional media type.
+    pub media_type: Option<String>,
+    /// Publishing session, if any.
+    pub session_id: Option<SessionId>,
+    /// Free-form metadata.
+    pub metadata: Value,
+}
+
+/// Artifact provider interface used by tools.
+pub trait ArtifactProvider: Send + Sync {
+    /// List published artifacts sorted by name.
+    fn list(&self) -> Result<Vec<ArtifactSummary>, ToolError>;
+
+    /// Publish an artifact, replacing any previous artifact with the same name.
+    fn publish(&self, request: ArtifactPublish) -> Result<ArtifactSummary, ToolError>;
+
+    /// Fetch an artifact by name or `artifact://` reference.
+    fn get(&self, name: &str) -> Result<(ArtifactSummary, Vec<u8>), ToolError>;
+}
+
+/// Build an `artifact://` reference for a name.
+pub fn artifact_reference(name: &str) -> String {
+    format!("{ARTIFACT_REFERENCE_SCHEME}{name}")
+}
+
+/// Resolve an artifact name from either a plain name or an `artifact://` reference.
+pub fn artifact_name(reference: &str) -> &str {
+    let reference = reference.trim();
+    reference
+        .strip_prefix(ARTIFACT_REFERENCE_SCHEME)
+        .unwrap_or(reference)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{artifact_name, artifact_reference};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn artifact_references_round_trip() {
+        let reference = artifact_reference("build-log");
+        assert_eq!(reference, "artifact://build-log");
+        assert_eq!(artifact_name(&reference), "build-log");
+        assert_eq!(artifact_name(" report.md "), "report.md");
+    }
+}
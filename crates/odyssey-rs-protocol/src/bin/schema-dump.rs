@@ -0,0 +1,36 @@
+//! Print the wire protocol JSON Schema or canonical samples.
+//!
+//! ```bash
+//! cargo run -p odyssey-rs-protocol --bin schema-dump -- schema > docs/src/protocol.schema.json
+//! cargo run -p odyssey-rs-protocol --bin schema-dump -- samples > docs/src/protocol.samples.json
+//! ```
+
+use odyssey_rs_protocol::{protocol_schema, wire_samples};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: schema-dump [schema|samples]";
+
+fn main() -> ExitCode {
+    let target = std::env::args().nth(1);
+    let value = match target.as_deref() {
+        None | Some("schema") => protocol_schema(),
+        Some("samples") => wire_samples(),
+        Some(other) => {
+            eprintln!("unknown target: {other}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match serde_json::to_string_pretty(&value) {
+        Ok(rendered) => {
+            println!("{rendered}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!(
+                "failed to render {}: {err}",
+                target.as_deref().unwrap_or("schema")
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,12 +1,21 @@
 //! Wire protocol types for Odyssey events, Requests, and common types.
 
+mod artifact;
+mod schema;
 mod skill;
 mod tool;
 
+pub use artifact::{
+    ARTIFACT_REFERENCE_SCHEME, ArtifactProvider, ArtifactPublish, ArtifactSummary, artifact_name,
+    artifact_reference,
+};
+pub use schema::{event_samples, protocol_schema, submission_samples, wire_samples};
 pub use skill::{SkillProvider, SkillSummary};
-pub use tool::ToolError;
+pub use tool::{ToolError, ToolErrorKind};
 
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use uuid::Uuid;
@@ -21,7 +30,7 @@ pub type ToolCallId = Uuid;
 pub type ExecId = Uuid;
 
 /// Wrapper for client submissions into the submission queue.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SubmissionEnvelope {
     /// Unique id for the submission.
     pub id: Uuid,
@@ -29,29 +38,68 @@ pub struct SubmissionEnvelope {
     pub session_id: SessionId,
     /// Timestamp when the submission was created.
     pub created_at: DateTime<Utc>,
+    /// Client-chosen key identifying the request across retries.
+    ///
+    /// A repeated key in the same session is not processed again while the
+    /// orchestrator remembers it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// User the submission is made on behalf of, for shared deployments.
+    ///
+    /// When unset, turns are attributed to the user that created the session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
     /// Submission payload content.
     pub payload: SubmissionPayload,
 }
 
 /// All submission operations that a client can enqueue.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case", tag = "type", content = "payload")]
 pub enum SubmissionPayload {
     /// Submit a user message to start a turn.
-    UserMessage { content: String },
+    UserMessage {
+        content: String,
+        /// Files attached to the message, e.g. from `@path` mentions.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        attachments: Vec<Attachment>,
+    },
     /// Override turn context defaults without user input.
     OverrideTurnContext { context: TurnContextOverride },
-    /// Cancel an in-flight turn.
+    /// Cancel an in-flight or queued turn.
+    ///
+    /// Turns started from a `UserMessage` use the submission id as their turn id.
     CancelTurn { turn_id: TurnId },
 }
 
+/// File content attached to a user message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Attachment {
+    /// Path relative to the workspace root.
+    pub path: String,
+    /// File content, or a leading excerpt when the file is too large.
+    pub content: String,
+    /// True when `content` is an excerpt rather than the whole file.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
 /// Wrapper for events emitted by the event queue.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EventMsg {
     /// Unique id for the event.
     pub id: Uuid,
     /// Session id associated with the event.
     pub session_id: SessionId,
+    /// Position of the event in its session, starting at 1 and increasing by one per event.
+    ///
+    /// Assigned by the orchestrator when the event is emitted; 0 means unassigned.
+    /// A jump larger than one means events were missed.
+    #[serde(default)]
+    pub seq: u64,
+    /// User whose turn produced the event, when the session is attributed to one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
     /// Timestamp when the event was created.
     pub created_at: DateTime<Utc>,
     /// Event payload content.
@@ -59,7 +107,7 @@ pub struct EventMsg {
 }
 
 /// All events emitted during orchestration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case", tag = "type", content = "payload")]
 pub enum EventPayload {
     /// Turn lifecycle started.
@@ -69,6 +117,12 @@ pub enum EventPayload {
     },
     /// Turn lifecycle completed.
     TurnCompleted { turn_id: TurnId, message: String },
+    /// Turn was cancelled before completing.
+    TurnCancelled { turn_id: TurnId },
+    /// Turn has run `idle_secs` without emitting events and may be stuck.
+    ///
+    /// Emitted once per stall; the turn keeps running and may still complete.
+    TurnStalled { turn_id: TurnId, idle_secs: u64 },
     /// Streaming response delta from the agent.
     AgentMessageDelta { turn_id: TurnId, delta: String },
     /// Streaming reasoning delta from the agent.
@@ -115,21 +169,46 @@ pub enum EventPayload {
         exec_id: ExecId,
         exit_code: i32,
     },
+    /// Effective working directory of the session changed.
+    CwdChanged { turn_id: TurnId, cwd: String },
     /// Permission request emitted for approval.
     PermissionRequested {
         turn_id: TurnId,
         request_id: Uuid,
         action: PermissionAction,
         request: PermissionRequest,
+        /// Risk class of the tool behind the request, when known.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        risk: Option<ToolRisk>,
     },
     /// Permission decision resolved.
     ApprovalResolved {
         turn_id: TurnId,
         request_id: Uuid,
         decision: ApprovalDecision,
+        /// True when no one answered and the configured timeout decision was applied.
+        #[serde(default)]
+        timed_out: bool,
+    },
+    /// Pending approval dropped without a decision (e.g. the turn was cancelled).
+    ApprovalExpired { turn_id: TurnId, request_id: Uuid },
+    /// Content guardrail blocked agent output or a tool call.
+    GuardrailTriggered {
+        turn_id: TurnId,
+        rule: String,
+        target: GuardrailTarget,
+        tool_name: Option<String>,
     },
     /// Plan update broadcast.
     PlanUpdate { turn_id: TurnId, plan: Value },
+    /// Token usage reported by the provider for one LLM call.
+    TokenUsage { turn_id: TurnId, usage: TokenUsage },
+    /// Progress of a streaming response, emitted periodically while deltas arrive.
+    StreamStats { turn_id: TurnId, stats: StreamStats },
+    /// Spend crossed the warning threshold of a budget limit.
+    ///
+    /// Emitted once per limit; new turns fail once the limit itself is reached.
+    BudgetWarning { turn_id: TurnId, limit: BudgetLimit },
     /// Error event for the session or turn.
     Error {
         turn_id: Option<TurnId>,
@@ -137,8 +216,169 @@ pub enum EventPayload {
     },
 }
 
+impl EventMsg {
+    /// Category of the event payload.
+    pub fn kind(&self) -> EventKind {
+        self.payload.kind()
+    }
+}
+
+impl EventPayload {
+    /// Category of this payload, for counting or filtering events without matching variants.
+    pub fn kind(&self) -> EventKind {
+        match self {
+            EventPayload::TurnStarted { .. }
+            | EventPayload::TurnCompleted { .. }
+            | EventPayload::TurnCancelled { .. }
+            | EventPayload::TurnStalled { .. } => EventKind::TurnLifecycle,
+            EventPayload::AgentMessageDelta { .. }
+            | EventPayload::ReasoningDelta { .. }
+            | EventPayload::ReasoningSectionBreak { .. } => EventKind::Delta,
+            EventPayload::ToolCallStarted { .. }
+            | EventPayload::ToolCallDelta { .. }
+            | EventPayload::ToolCallFinished { .. } => EventKind::ToolCall,
+            EventPayload::ExecCommandBegin { .. }
+            | EventPayload::ExecCommandOutputDelta { .. }
+            | EventPayload::ExecCommandEnd { .. } => EventKind::Exec,
+            EventPayload::CwdChanged { .. } => EventKind::Cwd,
+            EventPayload::PermissionRequested { .. }
+            | EventPayload::ApprovalResolved { .. }
+            | EventPayload::ApprovalExpired { .. } => EventKind::Permission,
+            EventPayload::GuardrailTriggered { .. } => EventKind::Guardrail,
+            EventPayload::PlanUpdate { .. } => EventKind::Plan,
+            EventPayload::TokenUsage { .. }
+            | EventPayload::StreamStats { .. }
+            | EventPayload::BudgetWarning { .. } => EventKind::Usage,
+            EventPayload::Error { .. } => EventKind::Error,
+        }
+    }
+}
+
+/// Category of an [`EventPayload`].
+///
+/// Serialized names are stable; new payload variants join an existing kind
+/// where one fits.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// Turn started, completed, cancelled, or stalled.
+    TurnLifecycle,
+    /// Streamed agent message or reasoning text.
+    Delta,
+    /// Tool call started, streamed output, or finished.
+    ToolCall,
+    /// Command execution began, streamed output, or ended.
+    Exec,
+    /// Session working directory changed.
+    Cwd,
+    /// Permission requested, resolved, or expired.
+    Permission,
+    /// Guardrail blocked output or a tool call.
+    Guardrail,
+    /// Plan update.
+    Plan,
+    /// Token usage for an LLM call, streaming throughput, or a budget warning.
+    Usage,
+    /// Session or turn error.
+    Error,
+}
+
+impl EventKind {
+    /// Every kind, in declaration order.
+    pub const ALL: [EventKind; 10] = [
+        EventKind::TurnLifecycle,
+        EventKind::Delta,
+        EventKind::ToolCall,
+        EventKind::Exec,
+        EventKind::Cwd,
+        EventKind::Permission,
+        EventKind::Guardrail,
+        EventKind::Plan,
+        EventKind::Usage,
+        EventKind::Error,
+    ];
+}
+
+/// Throughput of a streaming response so far.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
+pub struct StreamStats {
+    /// Response tokens streamed so far, estimated from the text.
+    pub tokens: u64,
+    /// Tokens per second since the first token.
+    pub tokens_per_sec: f64,
+    /// Milliseconds from the start of the turn to the first token.
+    pub time_to_first_token_ms: u64,
+}
+
+/// Token counts for a single LLM call.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+pub struct TokenUsage {
+    /// Prompt (input) tokens billed for the call.
+    pub prompt_tokens: u64,
+    /// Completion (output) tokens billed for the call.
+    pub completion_tokens: u64,
+    /// Prompt tokens served from the provider's prompt cache, when reported.
+    #[serde(default)]
+    pub cached_prompt_tokens: Option<u64>,
+    /// Prompt cache outcome; `None` when prompt caching is disabled.
+    #[serde(default)]
+    pub prompt_cache: Option<PromptCacheStatus>,
+}
+
+/// Spend against one budget limit.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct BudgetLimit {
+    /// Whether the limit covers one session or one UTC day.
+    pub scope: BudgetScope,
+    /// Model (`provider/name`) the limit applies to; `None` for all models.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// USD spent so far within the scope.
+    pub spent_usd: f64,
+    /// Configured limit in USD.
+    pub limit_usd: f64,
+}
+
+impl std::fmt::Display for BudgetLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scope = match self.scope {
+            BudgetScope::Session => "session",
+            BudgetScope::Daily => "daily",
+        };
+        write!(
+            f,
+            "{scope} budget spent ${:.2} of ${:.2}",
+            self.spent_usd, self.limit_usd
+        )?;
+        if let Some(model) = &self.model {
+            write!(f, " for {model}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Period a budget limit covers.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetScope {
+    /// Everything spent in one session.
+    Session,
+    /// Everything spent across sessions in the current UTC day.
+    Daily,
+}
+
+/// Whether a call reused a cached prompt prefix.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptCacheStatus {
+    /// Part of the prompt was served from cache.
+    Hit,
+    /// No cached prefix was reused.
+    Miss,
+}
+
 /// Execution output stream selection.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecStream {
     /// Standard output stream.
@@ -148,7 +388,7 @@ pub enum ExecStream {
 }
 
 /// Turn-scoped execution context.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct TurnContext {
     /// Working directory for tool execution.
     #[serde(default)]
@@ -162,6 +402,12 @@ pub struct TurnContext {
     /// Approval policy override for tools.
     #[serde(default)]
     pub approval_policy: Option<ApprovalPolicy>,
+    /// Preview mutating tools instead of executing them.
+    #[serde(default)]
+    pub simulation: Option<bool>,
+    /// Requested sampling temperature for the turn.
+    #[serde(default)]
+    pub temperature: Option<f32>,
     /// Additional metadata for the turn.
     #[serde(default = "empty_json_object")]
     pub metadata: Value,
@@ -182,6 +428,12 @@ impl TurnContext {
         if override_ctx.approval_policy.is_some() {
             self.approval_policy = override_ctx.approval_policy;
         }
+        if override_ctx.simulation.is_some() {
+            self.simulation = override_ctx.simulation;
+        }
+        if override_ctx.temperature.is_some() {
+            self.temperature = override_ctx.temperature;
+        }
         let Some(override_map) = override_ctx.metadata.as_object() else {
             return;
         };
@@ -202,7 +454,7 @@ impl TurnContext {
 }
 
 /// Partial override of turn context fields.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct TurnContextOverride {
     /// Override working directory.
     #[serde(default)]
@@ -216,13 +468,19 @@ pub struct TurnContextOverride {
     /// Override approval policy.
     #[serde(default)]
     pub approval_policy: Option<ApprovalPolicy>,
+    /// Override simulation (dry-run) mode.
+    #[serde(default)]
+    pub simulation: Option<bool>,
+    /// Override the sampling temperature.
+    #[serde(default)]
+    pub temperature: Option<f32>,
     /// Override metadata fields.
     #[serde(default = "empty_json_object")]
     pub metadata: Value,
 }
 
 /// Model specification used for a turn.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ModelSpec {
     /// Provider identifier (e.g., openai).
     pub provider: String,
@@ -231,7 +489,7 @@ pub struct ModelSpec {
 }
 
 /// Approval policy for tool execution.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ApprovalPolicy {
     /// Treat all tools as untrusted and require approval.
@@ -245,7 +503,7 @@ pub enum ApprovalPolicy {
 }
 
 /// Sandbox policy presets.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SandboxMode {
     /// Read-only access to the workspace.
@@ -256,8 +514,18 @@ pub enum SandboxMode {
     DangerFullAccess,
 }
 
+/// Content scanned by guardrail rules.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardrailTarget {
+    /// Final assistant response of a turn.
+    AgentOutput,
+    /// String values in tool call arguments.
+    ToolArguments,
+}
+
 /// Request for a permission decision.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case", tag = "type", content = "payload")]
 pub enum PermissionRequest {
     /// Tool invocation permission.
@@ -268,10 +536,28 @@ pub enum PermissionRequest {
     ExternalPath { path: String, mode: PathAccess },
     /// Command execution request.
     Command { argv: Vec<String> },
+    /// Git push from the GitCommit tool; only matched by `git_push` rules.
+    GitPush {
+        remote: String,
+        branch: String,
+        force: bool,
+    },
+    /// Mutating tool calls of one model response, confirmed together in
+    /// `confirm_batch` permission mode; never matched by rules.
+    ToolBatch { calls: Vec<ProposedToolCall> },
+}
+
+/// Tool call proposed by the model, shown before a batch runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProposedToolCall {
+    /// Tool name.
+    pub tool_name: String,
+    /// Arguments as sent by the model.
+    pub arguments: Value,
 }
 
 /// Path access mode used in permission checks.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PathAccess {
     /// Read access.
@@ -282,8 +568,36 @@ pub enum PathAccess {
     Execute,
 }
 
+/// Static risk class of a tool, from least to most dangerous.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolRisk {
+    /// Only reads workspace state.
+    ReadOnly,
+    /// Changes files or repository state inside the workspace.
+    WorkspaceMutating,
+    /// Reaches hosts outside the machine.
+    ExternalNetwork,
+    /// Runs arbitrary commands whose effects cannot be bounded.
+    Destructive,
+}
+
+impl ToolRisk {
+    /// Short label shown in approval prompts.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read-only",
+            Self::WorkspaceMutating => "workspace-mutating",
+            Self::ExternalNetwork => "external-network",
+            Self::Destructive => "destructive",
+        }
+    }
+}
+
 /// Policy action resolved for a permission request.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PermissionAction {
     /// Allow the action.
@@ -296,7 +610,7 @@ pub enum PermissionAction {
 }
 
 /// Decision returned by a user or policy.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ApprovalDecision {
     /// Allow the action once.
@@ -313,6 +627,17 @@ pub trait EventSink: Send + Sync {
     fn emit(&self, event: EventMsg);
 }
 
+/// Sink that applies backpressure by awaiting each event.
+///
+/// The orchestrator emits through [`EventSink`]; wrap an async sink in the
+/// core crate's `BufferedEventSink` to deliver every event in order, e.g. to
+/// an audit log or a remote server.
+#[async_trait]
+pub trait AsyncEventSink: Send + Sync {
+    /// Deliver an event; the next one is not sent until this returns.
+    async fn emit(&self, event: EventMsg);
+}
+
 /// Default metadata value for empty JSON objects.
 fn empty_json_object() -> Value {
     Value::Object(Map::new())
@@ -334,11 +659,15 @@ mod tests {
             }),
             sandbox_mode: Some(SandboxMode::ReadOnly),
             approval_policy: Some(ApprovalPolicy::OnRequest),
+            simulation: None,
+            temperature: Some(0.7),
             metadata: json!({ "existing": 1 }),
         };
         let override_ctx = TurnContextOverride {
             cwd: Some("/override".to_string()),
             approval_policy: Some(ApprovalPolicy::Never),
+            simulation: Some(true),
+            temperature: Some(0.2),
             metadata: json!({ "extra": true }),
             ..TurnContextOverride::default()
         };
@@ -346,6 +675,8 @@ mod tests {
 
         assert_eq!(ctx.cwd, Some("/override".to_string()));
         assert_eq!(ctx.approval_policy, Some(ApprovalPolicy::Never));
+        assert_eq!(ctx.simulation, Some(true));
+        assert_eq!(ctx.temperature, Some(0.2));
         assert_eq!(ctx.metadata, json!({ "existing": 1, "extra": true }));
     }
 
@@ -354,6 +685,8 @@ mod tests {
         let event = EventMsg {
             id: Uuid::new_v4(),
             session_id: Uuid::new_v4(),
+            seq: 0,
+            user_id: None,
             created_at: Utc::now(),
             payload: EventPayload::ToolCallFinished {
                 turn_id: Uuid::new_v4(),
@@ -367,4 +700,142 @@ mod tests {
         let decoded_value = serde_json::to_value(decoded).expect("serialize decoded");
         assert_eq!(decoded_value, encoded);
     }
+
+    #[test]
+    fn event_kind_names_are_stable() {
+        assert_eq!(
+            serde_json::to_value(EventKind::ALL).expect("serialize"),
+            json!([
+                "turn_lifecycle",
+                "delta",
+                "tool_call",
+                "exec",
+                "cwd",
+                "permission",
+                "guardrail",
+                "plan",
+                "usage",
+                "error"
+            ])
+        );
+        for kind in EventKind::ALL {
+            let encoded = serde_json::to_value(kind).expect("serialize");
+            let decoded: EventKind = serde_json::from_value(encoded).expect("deserialize");
+            assert_eq!(decoded, kind);
+        }
+    }
+
+    #[test]
+    fn event_payloads_map_to_kinds() {
+        let turn_id = Uuid::new_v4();
+        let cases = [
+            (
+                EventPayload::TurnCancelled { turn_id },
+                EventKind::TurnLifecycle,
+            ),
+            (
+                EventPayload::TurnStalled {
+                    turn_id,
+                    idle_secs: 300,
+                },
+                EventKind::TurnLifecycle,
+            ),
+            (
+                EventPayload::ReasoningSectionBreak { turn_id },
+                EventKind::Delta,
+            ),
+            (
+                EventPayload::ToolCallDelta {
+                    turn_id,
+                    tool_call_id: Uuid::new_v4(),
+                    delta: json!("out"),
+                },
+                EventKind::ToolCall,
+            ),
+            (
+                EventPayload::ExecCommandEnd {
+                    turn_id,
+                    exec_id: Uuid::new_v4(),
+                    exit_code: 0,
+                },
+                EventKind::Exec,
+            ),
+            (
+                EventPayload::ApprovalExpired {
+                    turn_id,
+                    request_id: Uuid::new_v4(),
+                },
+                EventKind::Permission,
+            ),
+            (
+                EventPayload::TokenUsage {
+                    turn_id,
+                    usage: TokenUsage::default(),
+                },
+                EventKind::Usage,
+            ),
+            (
+                EventPayload::StreamStats {
+                    turn_id,
+                    stats: StreamStats::default(),
+                },
+                EventKind::Usage,
+            ),
+            (
+                EventPayload::BudgetWarning {
+                    turn_id,
+                    limit: BudgetLimit {
+                        scope: BudgetScope::Daily,
+                        model: None,
+                        spent_usd: 8.0,
+                        limit_usd: 10.0,
+                    },
+                },
+                EventKind::Usage,
+            ),
+            (
+                EventPayload::Error {
+                    turn_id: None,
+                    message: "boom".to_string(),
+                },
+                EventKind::Error,
+            ),
+        ];
+        for (payload, kind) in cases {
+            assert_eq!(payload.kind(), kind);
+        }
+    }
+
+    #[test]
+    fn budget_limits_describe_their_scope() {
+        let mut limit = BudgetLimit {
+            scope: BudgetScope::Session,
+            model: None,
+            spent_usd: 5.004,
+            limit_usd: 5.0,
+        };
+        assert_eq!(limit.to_string(), "session budget spent $5.00 of $5.00");
+        limit.scope = BudgetScope::Daily;
+        limit.model = Some("openai/gpt-4.1".to_string());
+        assert_eq!(
+            limit.to_string(),
+            "daily budget spent $5.00 of $5.00 for openai/gpt-4.1"
+        );
+    }
+
+    #[test]
+    fn user_message_attachments_are_optional() {
+        let payload: SubmissionPayload = serde_json::from_value(
+            json!({ "type": "user_message", "payload": { "content": "hi" } }),
+        )
+        .expect("deserialize");
+        let SubmissionPayload::UserMessage { attachments, .. } = &payload else {
+            panic!("expected user message");
+        };
+        assert_eq!(attachments, &Vec::<Attachment>::new());
+        assert_eq!(
+            serde_json::to_value(&payload).expect("serialize"),
+            json!({ "type": "user_message", "payload": { "content": "hi" } })
+        );
+    }
 }
@@ -0,0 +1,250 @@
+//! JSON Schema and canonical samples for the wire types.
+//!
+//! Non-Rust consumers generate types from [`protocol_schema`]; the samples
+//! from [`wire_samples`] hold one message per event and submission variant.
+//! Both are committed under `docs/src` and checked by the crate's tests, so
+//! wire changes show up in review.
+
+use crate::{
+    ApprovalDecision, ApprovalPolicy, Attachment, BudgetLimit, BudgetScope, EventKind, EventMsg,
+    EventPayload, ExecStream, GuardrailTarget, ModelSpec, PathAccess, PermissionAction,
+    PermissionRequest, PromptCacheStatus, SandboxMode, StreamStats, SubmissionEnvelope,
+    SubmissionPayload, TokenUsage, ToolRisk, TurnContext, TurnContextOverride,
+};
+use chrono::{DateTime, Utc};
+use schemars::generate::SchemaSettings;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+/// JSON Schema (draft 2020-12) for every message on the wire.
+///
+/// The root accepts an `EventMsg` or a `SubmissionEnvelope`; all types,
+/// including `EventKind`, are under `$defs`.
+pub fn protocol_schema() -> Value {
+    let mut generator = SchemaSettings::draft2020_12().into_generator();
+    let event = generator.subschema_for::<EventMsg>();
+    let submission = generator.subschema_for::<SubmissionEnvelope>();
+    generator.subschema_for::<EventKind>();
+    let definitions = generator.take_definitions(true);
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "OdysseyProtocol",
+        "description": "An event emitted by Odyssey or a submission sent to it.",
+        "oneOf": [event, submission],
+        "$defs": definitions,
+    })
+}
+
+/// Canonical samples: one `EventMsg` per event variant and one
+/// `SubmissionEnvelope` per submission variant, with fixed ids and timestamps.
+pub fn wire_samples() -> Value {
+    json!({
+        "events": event_samples(),
+        "submissions": submission_samples(),
+    })
+}
+
+/// One event per `EventPayload` variant, in declaration order.
+pub fn event_samples() -> Vec<EventMsg> {
+    let turn_id = sample_id(2);
+    let tool_call_id = sample_id(3);
+    let exec_id = sample_id(4);
+    let request_id = sample_id(5);
+    let payloads = vec![
+        EventPayload::TurnStarted {
+            turn_id,
+            context: TurnContext {
+                cwd: Some("/workspace".to_string()),
+                model: Some(sample_model()),
+                sandbox_mode: Some(SandboxMode::WorkspaceWrite),
+                approval_policy: Some(ApprovalPolicy::OnRequest),
+                simulation: Some(false),
+                temperature: None,
+                metadata: json!({ "source": "tui" }),
+            },
+        },
+        EventPayload::TurnCompleted {
+            turn_id,
+            message: "Done.".to_string(),
+        },
+        EventPayload::TurnCancelled { turn_id },
+        EventPayload::TurnStalled {
+            turn_id,
+            idle_secs: 300,
+        },
+        EventPayload::AgentMessageDelta {
+            turn_id,
+            delta: "Hello".to_string(),
+        },
+        EventPayload::ReasoningDelta {
+            turn_id,
+            delta: "Checking the workspace".to_string(),
+        },
+        EventPayload::ReasoningSectionBreak { turn_id },
+        EventPayload::ToolCallStarted {
+            turn_id,
+            tool_call_id,
+            tool_name: "Read".to_string(),
+            arguments: json!({ "path": "README.md" }),
+        },
+        EventPayload::ToolCallDelta {
+            turn_id,
+            tool_call_id,
+            delta: json!({ "stdout": "partial output\n" }),
+        },
+        EventPayload::ToolCallFinished {
+            turn_id,
+            tool_call_id,
+            result: json!({ "content": "# Odyssey" }),
+            success: true,
+        },
+        EventPayload::ExecCommandBegin {
+            turn_id,
+            exec_id,
+            command: vec!["cargo".to_string(), "test".to_string()],
+            cwd: Some("/workspace".to_string()),
+        },
+        EventPayload::ExecCommandOutputDelta {
+            turn_id,
+            exec_id,
+            stream: ExecStream::Stdout,
+            delta: "test result: ok\n".to_string(),
+        },
+        EventPayload::ExecCommandEnd {
+            turn_id,
+            exec_id,
+            exit_code: 0,
+        },
+        EventPayload::CwdChanged {
+            turn_id,
+            cwd: "/workspace/crates".to_string(),
+        },
+        EventPayload::PermissionRequested {
+            turn_id,
+            request_id,
+            action: PermissionAction::Ask,
+            request: PermissionRequest::Path {
+                path: "src/lib.rs".to_string(),
+                mode: PathAccess::Write,
+            },
+            risk: Some(ToolRisk::WorkspaceMutating),
+        },
+        EventPayload::ApprovalResolved {
+            turn_id,
+            request_id,
+            decision: ApprovalDecision::AllowOnce,
+            timed_out: false,
+        },
+        EventPayload::ApprovalExpired {
+            turn_id,
+            request_id,
+        },
+        EventPayload::GuardrailTriggered {
+            turn_id,
+            rule: "no-secrets".to_string(),
+            target: GuardrailTarget::ToolArguments,
+            tool_name: Some("Bash".to_string()),
+        },
+        EventPayload::PlanUpdate {
+            turn_id,
+            plan: json!([{ "step": "Run tests", "status": "in_progress" }]),
+        },
+        EventPayload::TokenUsage {
+            turn_id,
+            usage: TokenUsage {
+                prompt_tokens: 1200,
+                completion_tokens: 80,
+                cached_prompt_tokens: Some(1024),
+                prompt_cache: Some(PromptCacheStatus::Hit),
+            },
+        },
+        EventPayload::StreamStats {
+            turn_id,
+            stats: StreamStats {
+                tokens: 120,
+                tokens_per_sec: 48.5,
+                time_to_first_token_ms: 640,
+            },
+        },
+        EventPayload::BudgetWarning {
+            turn_id,
+            limit: BudgetLimit {
+                scope: BudgetScope::Session,
+                model: Some("openai/gpt-4.1".to_string()),
+                spent_usd: 4.1,
+                limit_usd: 5.0,
+            },
+        },
+        EventPayload::Error {
+            turn_id: Some(turn_id),
+            message: "provider request failed".to_string(),
+        },
+    ];
+    payloads
+        .into_iter()
+        .enumerate()
+        .map(|(index, payload)| EventMsg {
+            id: sample_id(100 + index as u128),
+            session_id: sample_id(1),
+            seq: index as u64 + 1,
+            user_id: Some("alice".to_string()),
+            created_at: sample_time(),
+            payload,
+        })
+        .collect()
+}
+
+/// One submission per `SubmissionPayload` variant, in declaration order.
+pub fn submission_samples() -> Vec<SubmissionEnvelope> {
+    let payloads = vec![
+        SubmissionPayload::UserMessage {
+            content: "Summarize @README.md".to_string(),
+            attachments: vec![Attachment {
+                path: "README.md".to_string(),
+                content: "# Odyssey".to_string(),
+                truncated: false,
+            }],
+        },
+        SubmissionPayload::OverrideTurnContext {
+            context: TurnContextOverride {
+                cwd: Some("/workspace".to_string()),
+                model: Some(sample_model()),
+                sandbox_mode: Some(SandboxMode::ReadOnly),
+                approval_policy: Some(ApprovalPolicy::Never),
+                simulation: Some(true),
+                temperature: Some(0.2),
+                metadata: json!({}),
+            },
+        },
+        SubmissionPayload::CancelTurn {
+            turn_id: sample_id(2),
+        },
+    ];
+    payloads
+        .into_iter()
+        .enumerate()
+        .map(|(index, payload)| SubmissionEnvelope {
+            id: sample_id(200 + index as u128),
+            session_id: sample_id(1),
+            created_at: sample_time(),
+            idempotency_key: (index == 0).then(|| "retry-1".to_string()),
+            user_id: (index == 0).then(|| "alice".to_string()),
+            payload,
+        })
+        .collect()
+}
+
+fn sample_id(value: u128) -> Uuid {
+    Uuid::from_u128(value)
+}
+
+fn sample_time() -> DateTime<Utc> {
+    DateTime::UNIX_EPOCH
+}
+
+fn sample_model() -> ModelSpec {
+    ModelSpec {
+        provider: "openai".to_string(),
+        name: "gpt-4.1-mini".to_string(),
+    }
+}
@@ -1,3 +1,6 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
 /// Errors returned by tools and tool adapters.
 #[derive(Debug, thiserror::Error)]
 pub enum ToolError {
@@ -13,4 +16,131 @@ pub enum ToolError {
     /// Tool execution was denied by permissions.
     #[error("permission denied: {0}")]
     PermissionDenied(String),
+    /// A file, artifact, or other resource the tool needs does not exist.
+    #[error("not found: {0}")]
+    NotFound(String),
+    /// The operation did not finish in time.
+    #[error("timed out: {0}")]
+    Timeout(String),
+    /// The target changed or already exists, so the operation was not applied.
+    #[error("conflict: {0}")]
+    Conflict(String),
+    /// A temporary failure, such as a dropped connection, that may succeed on retry.
+    #[error("transient failure: {0}")]
+    Transient(String),
+}
+
+/// Category of a tool error, reported in tool results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolErrorKind {
+    InvalidArguments,
+    NotFound,
+    PermissionDenied,
+    Timeout,
+    Conflict,
+    Transient,
+    /// Any other failure.
+    Failed,
+}
+
+impl ToolError {
+    /// Build an error of the given category.
+    pub fn from_kind(kind: ToolErrorKind, message: impl Into<String>) -> Self {
+        let message = message.into();
+        match kind {
+            ToolErrorKind::InvalidArguments => ToolError::InvalidArguments(message),
+            ToolErrorKind::NotFound => ToolError::NotFound(message),
+            ToolErrorKind::PermissionDenied => ToolError::PermissionDenied(message),
+            ToolErrorKind::Timeout => ToolError::Timeout(message),
+            ToolErrorKind::Conflict => ToolError::Conflict(message),
+            ToolErrorKind::Transient => ToolError::Transient(message),
+            ToolErrorKind::Failed => ToolError::ExecutionFailed(message),
+        }
+    }
+
+    /// Error message without the category prefix.
+    pub fn message(&self) -> &str {
+        match self {
+            ToolError::ToolNotFound(message)
+            | ToolError::InvalidArguments(message)
+            | ToolError::ExecutionFailed(message)
+            | ToolError::PermissionDenied(message)
+            | ToolError::NotFound(message)
+            | ToolError::Timeout(message)
+            | ToolError::Conflict(message)
+            | ToolError::Transient(message) => message,
+        }
+    }
+
+    /// Category of this error.
+    pub fn kind(&self) -> ToolErrorKind {
+        match self {
+            ToolError::InvalidArguments(_) => ToolErrorKind::InvalidArguments,
+            ToolError::ToolNotFound(_) | ToolError::NotFound(_) => ToolErrorKind::NotFound,
+            ToolError::PermissionDenied(_) => ToolErrorKind::PermissionDenied,
+            ToolError::Timeout(_) => ToolErrorKind::Timeout,
+            ToolError::Conflict(_) => ToolErrorKind::Conflict,
+            ToolError::Transient(_) => ToolErrorKind::Transient,
+            ToolError::ExecutionFailed(_) => ToolErrorKind::Failed,
+        }
+    }
+
+    /// Return true when repeating the same call may succeed.
+    pub fn retryable(&self) -> bool {
+        self.kind().retryable()
+    }
+
+    /// Tool result reported for this error, e.g. in `ToolCallFinished`.
+    pub fn to_result(&self) -> Value {
+        json!({
+            "error": self.to_string(),
+            "kind": self.kind(),
+            "retryable": self.retryable(),
+        })
+    }
+}
+
+impl ToolErrorKind {
+    /// Return true when repeating the same call may succeed.
+    pub fn retryable(self) -> bool {
+        match self {
+            ToolErrorKind::Timeout | ToolErrorKind::Transient => true,
+            ToolErrorKind::InvalidArguments
+            | ToolErrorKind::NotFound
+            | ToolErrorKind::PermissionDenied
+            | ToolErrorKind::Conflict
+            | ToolErrorKind::Failed => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ToolError, ToolErrorKind};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn reports_kind_and_retryability() {
+        assert_eq!(
+            ToolError::Transient("connection reset".to_string()).to_result(),
+            json!({
+                "error": "transient failure: connection reset",
+                "kind": "transient",
+                "retryable": true,
+            })
+        );
+        assert_eq!(
+            ToolError::ToolNotFound("Nope".to_string()).kind(),
+            ToolErrorKind::NotFound
+        );
+        assert_eq!(ToolError::Timeout(String::new()).retryable(), true);
+        assert_eq!(ToolError::Conflict(String::new()).retryable(), false);
+        assert_eq!(ToolError::ExecutionFailed(String::new()).retryable(), false);
+
+        let err = ToolError::from_kind(ToolErrorKind::Conflict, "file changed");
+        assert_eq!(err.to_string(), "conflict: file changed");
+        assert_eq!(err.message(), "file changed");
+    }
 }
@@ -0,0 +1,103 @@
+//! Wire stability tests for the published protocol schema and samples.
+
+use odyssey_rs_protocol::{
+    EventMsg, SubmissionEnvelope, event_samples, protocol_schema, submission_samples, wire_samples,
+};
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Variant tags listed by the schema for an adjacently tagged enum.
+fn schema_tags(schema: &Value, definition: &str) -> BTreeSet<String> {
+    schema
+        .pointer(&format!("/$defs/{definition}/oneOf"))
+        .and_then(Value::as_array)
+        .expect("variants")
+        .iter()
+        .filter_map(|variant| variant.pointer("/properties/type/const"))
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Variant tags present in serialized samples.
+fn sample_tags<T: serde::Serialize>(samples: &[T]) -> BTreeSet<String> {
+    samples
+        .iter()
+        .map(|sample| serde_json::to_value(sample).expect("serialize"))
+        .filter_map(|value| {
+            value
+                .pointer("/payload/type")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+/// The committed schema and samples should match what the code generates.
+#[test]
+fn published_schema_and_samples_are_current() {
+    let published: Value =
+        serde_json::from_str(include_str!("../../../docs/src/protocol.schema.json"))
+            .expect("published schema");
+    assert_eq!(
+        published,
+        protocol_schema(),
+        "regenerate with `cargo run -p odyssey-rs-protocol --bin schema-dump -- schema > docs/src/protocol.schema.json`"
+    );
+    let published: Value =
+        serde_json::from_str(include_str!("../../../docs/src/protocol.samples.json"))
+            .expect("published samples");
+    assert_eq!(
+        published,
+        wire_samples(),
+        "regenerate with `cargo run -p odyssey-rs-protocol --bin schema-dump -- samples > docs/src/protocol.samples.json`"
+    );
+}
+
+/// Every event and submission variant should have a sample.
+#[test]
+fn samples_cover_every_variant() {
+    let schema = protocol_schema();
+    let events = event_samples();
+    let submissions = submission_samples();
+    assert_eq!(sample_tags(&events), schema_tags(&schema, "EventPayload"));
+    assert_eq!(
+        sample_tags(&submissions),
+        schema_tags(&schema, "SubmissionPayload")
+    );
+    assert_eq!(sample_tags(&events).len(), events.len());
+    assert_eq!(sample_tags(&submissions).len(), submissions.len());
+}
+
+/// Samples should survive a JSON round trip unchanged.
+#[test]
+fn samples_round_trip_through_json() {
+    for event in event_samples() {
+        let encoded = serde_json::to_value(&event).expect("serialize");
+        let decoded: EventMsg = serde_json::from_value(encoded.clone()).expect("deserialize");
+        assert_eq!(serde_json::to_value(decoded).expect("reserialize"), encoded);
+    }
+    for submission in submission_samples() {
+        let encoded = serde_json::to_value(&submission).expect("serialize");
+        let decoded: SubmissionEnvelope =
+            serde_json::from_value(encoded.clone()).expect("deserialize");
+        assert_eq!(serde_json::to_value(decoded).expect("reserialize"), encoded);
+    }
+}
+
+/// Samples should validate against the schema consumers generate types from.
+#[test]
+fn samples_match_the_schema() {
+    let validator = jsonschema::validator_for(&protocol_schema()).expect("valid schema");
+    let samples = wire_samples();
+    for group in ["events", "submissions"] {
+        for sample in samples[group].as_array().expect("samples") {
+            let errors: Vec<String> = validator
+                .iter_errors(sample)
+                .map(|err| err.to_string())
+                .collect();
+            assert_eq!(errors, Vec::<String>::new(), "{sample}");
+        }
+    }
+}
@@ -1,14 +1,18 @@
 //! Sandbox provider interfaces and implementations.
 
 pub mod error;
+pub mod pool;
 pub mod provider;
 pub mod types;
 
 /// Sandbox error type.
 pub use error::SandboxError;
+/// Handle pooling across turns.
+pub use pool::{SandboxLease, SandboxPool, SandboxPoolStats};
 /// Provider traits and helpers.
 pub use provider::{
-    CommandOutputSink, DependencyReport, SandboxProvider, local::LocalSandboxProvider,
+    CommandOutputSink, DependencyReport, SandboxProvider, has_symlink_below,
+    local::LocalSandboxProvider,
 };
 /// Core sandbox types and policies.
 pub use types::{
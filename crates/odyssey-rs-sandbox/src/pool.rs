@@ -0,0 +1,369 @@
+//! Reuse of prepared sandbox handles across turns.
+//!
+//! Preparing a sandbox can be expensive (bubblewrap mount plans today,
+//! container providers later), so handles are pooled per session and sandbox
+//! context. A session that keeps requesting the same context gets the same
+//! handle back; handles idle past the TTL (counted from the last checkout or
+//! lease release) are shut down on the next acquisition, and sessions can be
+//! reset explicitly when they end.
+
+use crate::{SandboxContext, SandboxError, SandboxHandle, SandboxProvider};
+use log::{debug, info};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+/// Pool key: owning session plus a hash of the sandbox context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PoolKey {
+    session_id: Uuid,
+    context_hash: u64,
+}
+
+/// Pooled handle with its usage bookkeeping.
+#[derive(Debug)]
+struct PoolEntry {
+    handle: SandboxHandle,
+    /// When the handle was last checked out or released.
+    ///
+    /// Shared with every lease; a strong count above one means the handle is in use.
+    last_used: Arc<Mutex<Instant>>,
+}
+
+/// Handle checked out from a [`SandboxPool`].
+///
+/// Idle expiry skips handles while any lease for them is alive, and dropping a
+/// lease restarts the handle's idle time.
+#[derive(Debug, Clone)]
+pub struct SandboxLease {
+    handle: SandboxHandle,
+    last_used: Arc<Mutex<Instant>>,
+}
+
+impl SandboxLease {
+    /// Sandbox handle to pass to the provider.
+    pub fn handle(&self) -> &SandboxHandle {
+        &self.handle
+    }
+}
+
+impl Drop for SandboxLease {
+    fn drop(&mut self) {
+        *self.last_used.lock() = Instant::now();
+    }
+}
+
+/// Reuse counters for a sandbox pool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SandboxPoolStats {
+    /// Acquisitions served by an existing handle.
+    pub reused: u64,
+    /// Acquisitions that prepared a new handle.
+    pub prepared: u64,
+    /// Handles shut down after idling past the TTL.
+    pub expired: u64,
+    /// Handles shut down by an explicit reset.
+    pub reset: u64,
+    /// Handles currently pooled.
+    pub pooled: usize,
+}
+
+impl SandboxPoolStats {
+    /// Share of acquisitions that reused an existing handle, between 0 and 1.
+    pub fn reuse_rate(&self) -> f64 {
+        let total = self.reused + self.prepared;
+        if total == 0 {
+            return 0.0;
+        }
+        self.reused as f64 / total as f64
+    }
+}
+
+/// Pool of prepared sandbox handles keyed by session and context.
+pub struct SandboxPool {
+    provider: Arc<dyn SandboxProvider>,
+    idle_ttl: Duration,
+    entries: Mutex<HashMap<PoolKey, PoolEntry>>,
+    reused: AtomicU64,
+    prepared: AtomicU64,
+    expired: AtomicU64,
+    reset: AtomicU64,
+}
+
+impl SandboxPool {
+    /// Create a pool that shuts down handles unused for `idle_ttl`.
+    pub fn new(provider: Arc<dyn SandboxProvider>, idle_ttl: Duration) -> Self {
+        Self {
+            provider,
+            idle_ttl,
+            entries: Mutex::new(HashMap::new()),
+            reused: AtomicU64::new(0),
+            prepared: AtomicU64::new(0),
+            expired: AtomicU64::new(0),
+            reset: AtomicU64::new(0),
+        }
+    }
+
+    /// Provider that owns the pooled handles.
+    pub fn provider(&self) -> &Arc<dyn SandboxProvider> {
+        &self.provider
+    }
+
+    /// Return a pooled handle for the session and context, preparing one if needed.
+    pub async fn acquire(
+        &self,
+        session_id: Uuid,
+        ctx: &SandboxContext,
+    ) -> Result<SandboxLease, SandboxError> {
+        self.evict_idle().await;
+        let key = PoolKey {
+            session_id,
+            context_hash: context_hash(ctx),
+        };
+        if let Some(lease) = self.checkout(&key) {
+            self.reused.fetch_add(1, Ordering::Relaxed);
+            debug!(
+                "reusing pooled sandbox (session_id={}, handle_id={})",
+                session_id, lease.handle.id
+            );
+            return Ok(lease);
+        }
+
+        let handle = self.provider.prepare(ctx).await?;
+        self.prepared.fetch_add(1, Ordering::Relaxed);
+        let (lease, duplicate) = {
+            let mut entries = self.entries.lock();
+            match entries.get_mut(&key) {
+                // A concurrent acquisition prepared the same context first; keep that one.
+                Some(entry) => {
+                    *entry.last_used.lock() = Instant::now();
+                    (lease_for(entry), Some(handle))
+                }
+                None => {
+                    let entry = PoolEntry {
+                        handle,
+                        last_used: Arc::new(Mutex::new(Instant::now())),
+                    };
+                    let lease = lease_for(&entry);
+                    entries.insert(key, entry);
+                    (lease, None)
+                }
+            }
+        };
+        if let Some(handle) = duplicate {
+            self.provider.shutdown(handle).await;
+        }
+        debug!(
+            "pooled new sandbox (session_id={}, handle_id={})",
+            session_id, lease.handle.id
+        );
+        Ok(lease)
+    }
+
+    /// Shut down handles that have been idle past the TTL and are not leased.
+    pub async fn evict_idle(&self) -> usize {
+        let now = Instant::now();
+        let idle_ttl = self.idle_ttl;
+        let expired = self.remove_where(|_, entry| {
+            Arc::strong_count(&entry.last_used) == 1
+                && now.duration_since(*entry.last_used.lock()) >= idle_ttl
+        });
+        let count = expired.len();
+        self.shutdown_all(expired).await;
+        if count > 0 {
+            self.expired.fetch_add(count as u64, Ordering::Relaxed);
+            info!("expired idle sandboxes (count={count})");
+        }
+        count
+    }
+
+    /// Shut down every pooled handle for a session, including leased ones.
+    pub async fn reset_session(&self, session_id: Uuid) -> usize {
+        let removed = self.remove_where(|key, _| key.session_id == session_id);
+        self.finish_reset(removed).await
+    }
+
+    /// Shut down every pooled handle.
+    pub async fn reset_all(&self) -> usize {
+        let removed = self.remove_where(|_, _| true);
+        self.finish_reset(removed).await
+    }
+
+    /// Snapshot of reuse counters.
+    pub fn stats(&self) -> SandboxPoolStats {
+        SandboxPoolStats {
+            reused: self.reused.load(Ordering::Relaxed),
+            prepared: self.prepared.load(Ordering::Relaxed),
+            expired: self.expired.load(Ordering::Relaxed),
+            reset: self.reset.load(Ordering::Relaxed),
+            pooled: self.entries.lock().len(),
+        }
+    }
+
+    fn checkout(&self, key: &PoolKey) -> Option<SandboxLease> {
+        let entries = self.entries.lock();
+        let entry = entries.get(key)?;
+        *entry.last_used.lock() = Instant::now();
+        Some(lease_for(entry))
+    }
+
+    fn remove_where(&self, predicate: impl Fn(&PoolKey, &PoolEntry) -> bool) -> Vec<SandboxHandle> {
+        let mut entries = self.entries.lock();
+        let keys: Vec<PoolKey> = entries
+            .iter()
+            .filter(|(key, entry)| predicate(key, entry))
+            .map(|(key, _)| *key)
+            .collect();
+        keys.iter()
+            .filter_map(|key| entries.remove(key))
+            .map(|entry| entry.handle)
+            .collect()
+    }
+
+    async fn finish_reset(&self, handles: Vec<SandboxHandle>) -> usize {
+        let count = handles.len();
+        self.shutdown_all(handles).await;
+        self.reset.fetch_add(count as u64, Ordering::Relaxed);
+        if count > 0 {
+            info!("reset pooled sandboxes (count={count})");
+        }
+        count
+    }
+
+    async fn shutdown_all(&self, handles: Vec<SandboxHandle>) {
+        for handle in handles {
+            self.provider.shutdown(handle).await;
+        }
+    }
+}
+
+fn lease_for(entry: &PoolEntry) -> SandboxLease {
+    SandboxLease {
+        handle: entry.handle.clone(),
+        last_used: entry.last_used.clone(),
+    }
+}
+
+/// Hash everything that affects how a sandbox is prepared.
+fn context_hash(ctx: &SandboxContext) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    ctx.workspace_root.hash(&mut hasher);
+    ctx.mode.hash(&mut hasher);
+    ctx.policy.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SandboxPool;
+    use crate::{
+        AccessDecision, AccessMode, LocalSandboxProvider, SandboxContext, SandboxPolicy,
+        SandboxProvider,
+    };
+    use odyssey_rs_protocol::SandboxMode;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tempfile::tempdir;
+    use uuid::Uuid;
+
+    fn context(root: &std::path::Path, mode: SandboxMode) -> SandboxContext {
+        SandboxContext {
+            workspace_root: root.to_path_buf(),
+            mode,
+            policy: SandboxPolicy::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn reuses_handles_per_session_and_context() {
+        let workspace = tempdir().expect("workspace");
+        let pool = SandboxPool::new(
+            Arc::new(LocalSandboxProvider::new()),
+            Duration::from_secs(60),
+        );
+        let session = Uuid::new_v4();
+        let ctx = context(workspace.path(), SandboxMode::WorkspaceWrite);
+
+        let first = pool.acquire(session, &ctx).await.expect("acquire");
+        let second = pool.acquire(session, &ctx).await.expect("acquire");
+        assert_eq!(first.handle().id, second.handle().id);
+
+        let read_only = context(workspace.path(), SandboxMode::ReadOnly);
+        let other_mode = pool.acquire(session, &read_only).await.expect("acquire");
+        let other_session = pool.acquire(Uuid::new_v4(), &ctx).await.expect("acquire");
+        assert_eq!(other_mode.handle().id == first.handle().id, false);
+        assert_eq!(other_session.handle().id == first.handle().id, false);
+
+        let stats = pool.stats();
+        assert_eq!((stats.reused, stats.prepared, stats.pooled), (1, 3, 3));
+        assert_eq!(stats.reuse_rate(), 0.25);
+    }
+
+    #[tokio::test]
+    async fn idle_handles_expire_unless_leased() {
+        let workspace = tempdir().expect("workspace");
+        let provider = Arc::new(LocalSandboxProvider::new());
+        let pool = SandboxPool::new(provider.clone(), Duration::ZERO);
+        let ctx = context(workspace.path(), SandboxMode::WorkspaceWrite);
+        let inside = workspace.path().join("file.txt");
+
+        let lease = pool.acquire(Uuid::new_v4(), &ctx).await.expect("acquire");
+        assert_eq!(pool.evict_idle().await, 0);
+        let handle = lease.handle().clone();
+        drop(lease);
+        assert_eq!(pool.evict_idle().await, 1);
+        assert!(matches!(
+            provider.check_access(&handle, &inside, AccessMode::Read),
+            AccessDecision::Deny(_)
+        ));
+        assert_eq!(pool.stats().expired, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn releasing_a_lease_restarts_idle_time() {
+        let workspace = tempdir().expect("workspace");
+        let pool = SandboxPool::new(
+            Arc::new(LocalSandboxProvider::new()),
+            Duration::from_millis(100),
+        );
+        let ctx = context(workspace.path(), SandboxMode::WorkspaceWrite);
+
+        let lease = pool.acquire(Uuid::new_v4(), &ctx).await.expect("acquire");
+        tokio::time::advance(Duration::from_millis(150)).await;
+        drop(lease);
+        assert_eq!(pool.evict_idle().await, 0);
+        tokio::time::advance(Duration::from_millis(150)).await;
+        assert_eq!(pool.evict_idle().await, 1);
+    }
+
+    #[tokio::test]
+    async fn reset_session_shuts_down_its_handles() {
+        let workspace = tempdir().expect("workspace");
+        let provider = Arc::new(LocalSandboxProvider::new());
+        let pool = SandboxPool::new(provider.clone(), Duration::from_secs(60));
+        let ctx = context(workspace.path(), SandboxMode::WorkspaceWrite);
+        let session = Uuid::new_v4();
+        let kept = pool.acquire(Uuid::new_v4(), &ctx).await.expect("acquire");
+        let reset = pool.acquire(session, &ctx).await.expect("acquire");
+
+        assert_eq!(pool.reset_session(session).await, 1);
+        let inside = workspace.path().join("file.txt");
+        assert!(matches!(
+            provider.check_access(reset.handle(), &inside, AccessMode::Read),
+            AccessDecision::Deny(_)
+        ));
+        assert_eq!(
+            provider.check_access(kept.handle(), &inside, AccessMode::Read),
+            AccessDecision::Allow
+        );
+        let stats = pool.stats();
+        assert_eq!((stats.reset, stats.pooled), (1, 1));
+    }
+}
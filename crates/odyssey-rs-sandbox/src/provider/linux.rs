@@ -206,6 +206,24 @@ impl SandboxProvider for BubblewrapProvider {
         prepared.access.check(path, mode)
     }
 
+    /// Check a host against the prepared domain lists.
+    fn check_network(&self, handle: &SandboxHandle, host: &str) -> AccessDecision {
+        let state = self.state.read();
+        let Some(prepared) = state.get(&handle.id) else {
+            warn!(
+                "bubblewrap network check failed (unknown handle_id={})",
+                handle.id
+            );
+            return AccessDecision::Deny("unknown sandbox handle".to_string());
+        };
+        prepared.domains.check_host(host)
+    }
+
+    /// Bubblewrap mounts the filesystem read-only in `SandboxMode::ReadOnly`.
+    fn enforces_read_only(&self) -> bool {
+        true
+    }
+
     /// Return dependency report for the provider.
     fn dependency_report(&self) -> DependencyReport {
         Self::dependency_report_linux()
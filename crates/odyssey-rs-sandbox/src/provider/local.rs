@@ -87,6 +87,15 @@ impl SandboxProvider for LocalSandboxProvider {
         prepared.access.check(path, mode)
     }
 
+    /// Check a host against the prepared domain lists.
+    fn check_network(&self, handle: &SandboxHandle, host: &str) -> AccessDecision {
+        let state = self.state.read();
+        let Some(prepared) = state.get(&handle.id) else {
+            return AccessDecision::Deny("unknown sandbox handle".to_string());
+        };
+        prepared.domains.check_host(host)
+    }
+
     /// Shutdown and remove sandbox state.
     async fn shutdown(&self, handle: SandboxHandle) {
         info!("local sandbox shutdown (handle_id={})", handle.id);
@@ -134,6 +143,7 @@ mod tests {
         };
         let handle = provider.prepare(&ctx).await.expect("prepare");
         let handle_clone = handle.clone();
+        assert_eq!(provider.enforces_read_only(), false);
 
         let inside = workspace.path().join("file.txt");
         assert_eq!(
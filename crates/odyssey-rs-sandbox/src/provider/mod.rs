@@ -3,21 +3,24 @@
 use async_trait::async_trait;
 use log::{debug, info, warn};
 use std::collections::BTreeMap;
-use std::path::{Component, Path, PathBuf};
+use std::path::{Component, Path, PathBuf, Prefix};
 use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 
 use crate::error::SandboxError;
 use crate::types::{
     AccessDecision, AccessMode, CommandResult, CommandSpec, SandboxContext, SandboxHandle,
-    SandboxLimits, SandboxNetworkMode, SandboxPolicy,
+    SandboxLimits, SandboxNetworkMode, SandboxNetworkPolicy, SandboxPolicy,
 };
 use odyssey_rs_protocol::SandboxMode;
+pub use path::has_symlink_below;
+use path::{PathKey, PathStyle, resolve_symlinks};
 
 #[cfg(target_os = "linux")]
 pub mod linux;
 // pub mod noop;
 pub mod local;
+mod path;
 
 /// Report of missing dependencies for a sandbox provider.
 #[derive(Debug, Default)]
@@ -53,6 +56,21 @@ pub trait SandboxProvider: Send + Sync {
     fn check_access(&self, handle: &SandboxHandle, path: &Path, mode: AccessMode)
     -> AccessDecision;
 
+    /// Check whether in-process network clients may contact a host.
+    ///
+    /// Defaults to allowing every host for providers without a domain policy.
+    fn check_network(&self, _handle: &SandboxHandle, _host: &str) -> AccessDecision {
+        AccessDecision::Allow
+    }
+
+    /// Whether commands run under `SandboxMode::ReadOnly` cannot write outside the workspace
+    /// policy, i.e. the provider confines the process rather than only checking paths.
+    ///
+    /// Defaults to `false`; read-only mode denies commands on providers that do not enforce it.
+    fn enforces_read_only(&self) -> bool {
+        false
+    }
+
     /// Return a dependency report for the provider.
     fn dependency_report(&self) -> DependencyReport {
         DependencyReport::default()
@@ -95,6 +113,8 @@ pub struct PreparedSandbox {
     /// Network policy.
     #[allow(dead_code)]
     network: SandboxNetworkMode,
+    /// Domain lists checked for in-process network requests.
+    domains: SandboxNetworkPolicy,
     /// Default working directory.
     working_dir: PathBuf,
     /// Mount list for the sandbox.
@@ -113,15 +133,22 @@ enum DefaultScope {
 /// Allow/deny rules for a specific access mode.
 #[derive(Debug, Clone)]
 struct AccessRules {
-    allow: Vec<PathBuf>,
-    deny: Vec<PathBuf>,
+    allow: Vec<PathKey>,
+    deny: Vec<PathKey>,
     default_scope: DefaultScope,
 }
 
 /// Aggregated access policy for read/write/exec.
 #[derive(Debug, Clone)]
 struct AccessPolicy {
-    workspace_root: PathBuf,
+    /// Workspace root used to resolve relative paths.
+    workspace_root: PathKey,
+    /// Workspace root as given and with symlinks resolved.
+    workspace_scope: Vec<PathKey>,
+    /// Whether paths are resolved against the host filesystem.
+    resolve_links: bool,
+    /// Whether symlinks inside the workspace may be traversed.
+    follow_symlinks: bool,
     read: AccessRules,
     write: AccessRules,
     exec: AccessRules,
@@ -134,7 +161,19 @@ impl AccessPolicy {
         policy: &SandboxPolicy,
         workspace_root: &Path,
     ) -> Result<Self, SandboxError> {
-        let workspace_root = normalize_path(workspace_root);
+        Self::with_style(mode, policy, workspace_root, PathStyle::native())
+    }
+
+    /// Build access policy using the path rules of a specific platform.
+    fn with_style(
+        mode: SandboxMode,
+        policy: &SandboxPolicy,
+        workspace_root: &Path,
+        style: PathStyle,
+    ) -> Result<Self, SandboxError> {
+        let resolve_links = style == PathStyle::native();
+        let workspace_root = PathKey::new(workspace_root, style);
+        let workspace_scope = link_variants(&workspace_root, resolve_links);
         let default_read = match mode {
             SandboxMode::ReadOnly | SandboxMode::WorkspaceWrite => DefaultScope::WorkspaceOnly,
             SandboxMode::DangerFullAccess => DefaultScope::All,
@@ -149,23 +188,28 @@ impl AccessPolicy {
             SandboxMode::WorkspaceWrite => DefaultScope::WorkspaceOnly,
             SandboxMode::DangerFullAccess => DefaultScope::All,
         };
+        let filesystem = &policy.filesystem;
+        let keys = |patterns: &[String]| pattern_keys(&workspace_root, patterns, resolve_links);
         let read = AccessRules {
-            allow: normalize_patterns(&workspace_root, &policy.filesystem.allow_read)?,
-            deny: normalize_patterns(&workspace_root, &policy.filesystem.deny_read)?,
+            allow: keys(&filesystem.allow_read)?,
+            deny: keys(&filesystem.deny_read)?,
             default_scope: default_read,
         };
         let write = AccessRules {
-            allow: normalize_patterns(&workspace_root, &policy.filesystem.allow_write)?,
-            deny: normalize_patterns(&workspace_root, &policy.filesystem.deny_write)?,
+            allow: keys(&filesystem.allow_write)?,
+            deny: keys(&filesystem.deny_write)?,
             default_scope: default_write,
         };
         let exec = AccessRules {
-            allow: normalize_patterns(&workspace_root, &policy.filesystem.allow_exec)?,
-            deny: normalize_patterns(&workspace_root, &policy.filesystem.deny_exec)?,
+            allow: keys(&filesystem.allow_exec)?,
+            deny: keys(&filesystem.deny_exec)?,
             default_scope: default_exec,
         };
         Ok(Self {
             workspace_root,
+            workspace_scope,
+            resolve_links,
+            follow_symlinks: filesystem.follow_symlinks,
             read,
             write,
             exec,
@@ -173,45 +217,74 @@ impl AccessPolicy {
     }
 
     /// Check access against allow/deny rules.
+    ///
+    /// Both the requested path and its symlink-resolved target must pass, so a
+    /// link inside the workspace cannot grant access to what it points at.
     fn check(&self, path: &Path, mode: AccessMode) -> AccessDecision {
-        let path = if path.is_absolute() {
-            normalize_path(path)
-        } else {
-            normalize_path(&self.workspace_root.join(path))
-        };
+        let path = self.workspace_root.resolve(path);
         let rules = match mode {
             AccessMode::Read => &self.read,
             AccessMode::Write => &self.write,
             AccessMode::Execute => &self.exec,
         };
-        if matches_any(&path, &rules.deny) {
+        if !self.resolve_links {
+            return self.check_rules(&path, rules);
+        }
+        let host_path = path.to_path_buf();
+        if !self.follow_symlinks
+            && self
+                .workspace_scope
+                .iter()
+                .any(|root| has_symlink_below(&host_path, &root.to_path_buf()))
+        {
             return AccessDecision::Deny(format!(
-                "access denied by sandbox policy: {}",
-                path.display()
+                "symlinks are not followed by sandbox policy: {path}"
             ));
         }
+        let target = match resolve_symlinks(&host_path) {
+            Ok(target) => PathKey::new(&target, path.style()),
+            Err(err) => {
+                return AccessDecision::Deny(format!(
+                    "failed to resolve symlinks in {path}: {err}"
+                ));
+            }
+        };
+        match self.check_rules(&path, rules) {
+            AccessDecision::Allow if target != path => match self.check_rules(&target, rules) {
+                AccessDecision::Allow => AccessDecision::Allow,
+                AccessDecision::Deny(reason) => {
+                    AccessDecision::Deny(format!("{reason} (via symlink {path})"))
+                }
+            },
+            decision => decision,
+        }
+    }
+
+    /// Check a normalized path against allow/deny rules.
+    fn check_rules(&self, path: &PathKey, rules: &AccessRules) -> AccessDecision {
+        if matches_any(path, &rules.deny) {
+            return AccessDecision::Deny(format!("access denied by sandbox policy: {path}"));
+        }
         if !rules.allow.is_empty() {
-            if matches_any(&path, &rules.allow) {
+            if matches_any(path, &rules.allow) {
                 return AccessDecision::Allow;
             }
             return AccessDecision::Deny(format!(
-                "access not permitted by sandbox allowlist: {}",
-                path.display()
+                "access not permitted by sandbox allowlist: {path}"
             ));
         }
         match rules.default_scope {
             DefaultScope::All => AccessDecision::Allow,
             DefaultScope::WorkspaceOnly => {
-                if path.starts_with(&self.workspace_root) {
+                if matches_any(path, &self.workspace_scope) {
                     AccessDecision::Allow
                 } else {
-                    AccessDecision::Deny(format!("path outside workspace root: {}", path.display()))
+                    AccessDecision::Deny(format!("path outside workspace root: {path}"))
                 }
             }
-            DefaultScope::None => AccessDecision::Deny(format!(
-                "sandbox mode blocks this access: {}",
-                path.display()
-            )),
+            DefaultScope::None => {
+                AccessDecision::Deny(format!("sandbox mode blocks this access: {path}"))
+            }
         }
     }
 }
@@ -220,11 +293,7 @@ impl AccessPolicy {
 fn normalize_patterns(root: &Path, patterns: &[String]) -> Result<Vec<PathBuf>, SandboxError> {
     let mut resolved = Vec::new();
     for pattern in patterns {
-        if pattern.contains('*') || pattern.contains('?') || pattern.contains('[') {
-            return Err(SandboxError::InvalidConfig(format!(
-                "glob patterns are not supported in sandbox paths: {pattern}"
-            )));
-        }
+        reject_glob(pattern)?;
         let path = PathBuf::from(pattern);
         let joined = if path.is_absolute() {
             path
@@ -236,18 +305,74 @@ fn normalize_patterns(root: &Path, patterns: &[String]) -> Result<Vec<PathBuf>,
     Ok(resolved)
 }
 
+/// Normalize path patterns into comparison keys relative to the workspace root.
+fn pattern_keys(
+    root: &PathKey,
+    patterns: &[String],
+    resolve_links: bool,
+) -> Result<Vec<PathKey>, SandboxError> {
+    let mut resolved = Vec::new();
+    for pattern in patterns {
+        reject_glob(pattern)?;
+        resolved.extend(link_variants(
+            &root.resolve(Path::new(pattern)),
+            resolve_links,
+        ));
+    }
+    Ok(resolved)
+}
+
+/// A path key plus its symlink-resolved form when that differs.
+fn link_variants(key: &PathKey, resolve_links: bool) -> Vec<PathKey> {
+    let mut variants = vec![key.clone()];
+    if resolve_links && let Ok(target) = resolve_symlinks(&key.to_path_buf()) {
+        let target = PathKey::new(&target, key.style());
+        if target != *key {
+            variants.push(target);
+        }
+    }
+    variants
+}
+
+/// Reject glob syntax, which sandbox path rules do not support.
+fn reject_glob(pattern: &str) -> Result<(), SandboxError> {
+    if pattern.contains('*') || pattern.contains('?') || pattern.contains('[') {
+        return Err(SandboxError::InvalidConfig(format!(
+            "glob patterns are not supported in sandbox paths: {pattern}"
+        )));
+    }
+    Ok(())
+}
+
 /// Check whether a path matches any prefix pattern.
-fn matches_any(path: &Path, patterns: &[PathBuf]) -> bool {
+fn matches_any(path: &PathKey, patterns: &[PathKey]) -> bool {
     patterns.iter().any(|pattern| path.starts_with(pattern))
 }
 
 /// Normalize a path by resolving components.
+///
+/// Windows verbatim prefixes (`\\?\C:`, `\\?\UNC\server\share`) are rewritten
+/// to their plain forms so canonicalized and user-supplied paths agree.
 fn normalize_path(path: &Path) -> PathBuf {
     let mut normalized = PathBuf::new();
     for component in path.components() {
         match component {
-            Component::Prefix(prefix) => normalized.push(prefix.as_os_str()),
-            Component::RootDir => normalized.push(Path::new("/")),
+            Component::Prefix(prefix) => match prefix.kind() {
+                Prefix::VerbatimDisk(drive) => {
+                    normalized.push(format!("{}:", char::from(drive)));
+                }
+                Prefix::VerbatimUNC(server, share) => {
+                    let mut unc = std::ffi::OsString::from(r"\\");
+                    unc.push(server);
+                    unc.push(r"\");
+                    unc.push(share);
+                    normalized.push(unc);
+                }
+                Prefix::Verbatim(_) | Prefix::DeviceNS(_) | Prefix::UNC(_, _) | Prefix::Disk(_) => {
+                    normalized.push(prefix.as_os_str())
+                }
+            },
+            Component::RootDir => normalized.push(Path::new(std::path::MAIN_SEPARATOR_STR)),
             Component::CurDir => {}
             Component::ParentDir => {
                 normalized.pop();
@@ -261,9 +386,9 @@ fn normalize_path(path: &Path) -> PathBuf {
 #[cfg(test)]
 mod tests {
     use super::{
-        AccessPolicy, bind_if_exists, build_env, build_mounts, build_prepared_sandbox,
-        command_display, matches_any, network_mode, normalize_path, normalize_patterns,
-        run_local_process,
+        AccessPolicy, PathKey, PathStyle, bind_if_exists, build_env, build_mounts,
+        build_prepared_sandbox, command_display, matches_any, network_mode, normalize_path,
+        normalize_patterns, run_local_process,
     };
     use crate::{AccessDecision, AccessMode, CommandSpec, SandboxNetworkMode, SandboxPolicy};
     use odyssey_rs_protocol::SandboxMode;
@@ -353,11 +478,134 @@ mod tests {
 
     #[test]
     fn matches_any_checks_prefixes() {
-        let path = PathBuf::from("/tmp/data/file.txt");
-        let patterns = vec![PathBuf::from("/tmp/data")];
+        let path = PathKey::new(Path::new("/tmp/data/file.txt"), PathStyle::Unix);
+        let patterns = vec![PathKey::new(Path::new("/tmp/data"), PathStyle::Unix)];
         assert_eq!(matches_any(&path, &patterns), true);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_escaping_workspace_are_denied() {
+        let workspace = tempdir().expect("tempdir");
+        let outside = tempdir().expect("tempdir");
+        let root = workspace.path();
+        std::fs::write(outside.path().join("secret.txt"), "secret").expect("write");
+        std::fs::create_dir(root.join("src")).expect("mkdir");
+        std::os::unix::fs::symlink(outside.path(), root.join("escape")).expect("symlink");
+        std::os::unix::fs::symlink(outside.path().join("missing"), root.join("dangling"))
+            .expect("symlink");
+        std::os::unix::fs::symlink(root.join("src"), root.join("alias")).expect("symlink");
+        let access =
+            AccessPolicy::new(SandboxMode::WorkspaceWrite, &SandboxPolicy::default(), root)
+                .expect("access policy");
+
+        for path in ["escape/secret.txt", "escape/new.txt", "dangling"] {
+            assert!(
+                matches!(
+                    access.check(&root.join(path), AccessMode::Write),
+                    AccessDecision::Deny(_)
+                ),
+                "{path}"
+            );
+        }
+        assert_eq!(
+            access.check(&root.join("alias/main.rs"), AccessMode::Write),
+            AccessDecision::Allow
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follow_symlinks_disabled_denies_workspace_links() {
+        let workspace = tempdir().expect("tempdir");
+        let root = workspace.path();
+        std::fs::create_dir(root.join("src")).expect("mkdir");
+        std::os::unix::fs::symlink(root.join("src"), root.join("alias")).expect("symlink");
+        let mut policy = SandboxPolicy::default();
+        policy.filesystem.follow_symlinks = false;
+        let access =
+            AccessPolicy::new(SandboxMode::WorkspaceWrite, &policy, root).expect("access policy");
+
+        assert!(matches!(
+            access.check(&root.join("alias/main.rs"), AccessMode::Read),
+            AccessDecision::Deny(reason) if reason.contains("symlinks are not followed")
+        ));
+        assert_eq!(
+            access.check(&root.join("src/main.rs"), AccessMode::Read),
+            AccessDecision::Allow
+        );
+    }
+
+    fn windows_policy(mode: SandboxMode, policy: &SandboxPolicy) -> AccessPolicy {
+        AccessPolicy::with_style(
+            mode,
+            policy,
+            Path::new(r"\\?\C:\Users\Dev\Repo"),
+            PathStyle::Windows,
+        )
+        .expect("access policy")
+    }
+
+    #[test]
+    fn windows_workspace_matches_case_and_separators() {
+        let access = windows_policy(SandboxMode::WorkspaceWrite, &SandboxPolicy::default());
+        for path in [
+            r"C:\Users\Dev\Repo\src\main.rs",
+            r"c:\users\dev\repo\src\main.rs",
+            "C:/Users/Dev/Repo/src/main.rs",
+            r"src\main.rs",
+        ] {
+            assert_eq!(
+                access.check(Path::new(path), AccessMode::Write),
+                AccessDecision::Allow,
+                "{path}"
+            );
+        }
+        for path in [
+            r"C:\Users\Dev\Repository\file.txt",
+            r"D:\Users\Dev\Repo\file.txt",
+            r"C:\Users\Dev\Repo\..\Other\file.txt",
+            r"\\server\share\Users\Dev\Repo\file.txt",
+        ] {
+            assert!(
+                matches!(
+                    access.check(Path::new(path), AccessMode::Write),
+                    AccessDecision::Deny(_)
+                ),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn windows_rules_match_unc_and_drive_paths() {
+        let mut policy = SandboxPolicy::default();
+        policy.filesystem.allow_read =
+            vec![r"\\Server\Share\tools".to_string(), r"D:\Data".to_string()];
+        policy.filesystem.deny_read = vec![r"d:/data/secrets".to_string()];
+        let access = windows_policy(SandboxMode::WorkspaceWrite, &policy);
+
+        assert_eq!(
+            access.check(
+                Path::new(r"\\?\UNC\server\share\TOOLS\bin.exe"),
+                AccessMode::Read
+            ),
+            AccessDecision::Allow
+        );
+        assert_eq!(
+            access.check(Path::new(r"D:\data\report.csv"), AccessMode::Read),
+            AccessDecision::Allow
+        );
+        assert!(matches!(
+            access.check(Path::new(r"D:\Data\Secrets\key.pem"), AccessMode::Read),
+            AccessDecision::Deny(_)
+        ));
+        assert!(matches!(
+            access.check(Path::new(r"\\server\other\tools"), AccessMode::Read),
+            AccessDecision::Deny(_)
+        ));
+    }
+
     #[test]
     fn command_display_resolves_relative_paths() {
         let display = command_display(Path::new("bin/run"), Path::new("/tmp")).expect("display");
@@ -648,6 +896,7 @@ pub fn build_prepared_sandbox(ctx: &SandboxContext) -> Result<PreparedSandbox, S
         env,
         limits: ctx.policy.limits.clone(),
         network,
+        domains: ctx.policy.network.clone(),
         working_dir: normalize_path(&ctx.workspace_root),
         mounts,
     })
@@ -0,0 +1,330 @@
+//! Platform-aware path normalization for sandbox access checks.
+//!
+//! Access rules compare paths by prefix, which only works once both sides are
+//! in the same canonical form. Paths are parsed into a root (a drive letter or
+//! UNC share on Windows) plus components, with `.`/`..` resolved lexically.
+//! Windows paths additionally accept either separator, drop verbatim (`\\?\`)
+//! prefixes, and compare case-insensitively. Symlinks are resolved separately
+//! against the host filesystem so link targets can be checked as well.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Path syntax and comparison rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PathStyle {
+    /// `/`-separated, case-sensitive paths.
+    Unix,
+    /// Drive letter or UNC prefixed, case-insensitive paths.
+    Windows,
+}
+
+impl PathStyle {
+    /// Style of the platform the sandbox runs on.
+    pub(super) fn native() -> Self {
+        if cfg!(windows) {
+            Self::Windows
+        } else {
+            Self::Unix
+        }
+    }
+
+    fn is_separator(self, ch: char) -> bool {
+        match self {
+            Self::Unix => ch == '/',
+            Self::Windows => ch == '/' || ch == '\\',
+        }
+    }
+
+    fn same(self, left: &str, right: &str) -> bool {
+        match self {
+            Self::Unix => left == right,
+            Self::Windows => left.to_lowercase() == right.to_lowercase(),
+        }
+    }
+}
+
+/// Absolute, normalized path used for prefix comparisons.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct PathKey {
+    style: PathStyle,
+    /// Drive (`C:`) or UNC share (`\\server\share`); empty on Unix.
+    prefix: String,
+    /// Components below the root.
+    components: Vec<String>,
+}
+
+/// Result of splitting the prefix and root off a path string.
+struct ParsedPath<'a> {
+    prefix: Option<String>,
+    rooted: bool,
+    rest: &'a str,
+}
+
+impl PathKey {
+    /// Normalize a path, treating relative paths as relative to the filesystem root.
+    pub(super) fn new(path: &Path, style: PathStyle) -> Self {
+        let root = Self {
+            style,
+            prefix: String::new(),
+            components: Vec::new(),
+        };
+        root.resolve(path)
+    }
+
+    /// Normalize a path, resolving relative paths against `self`.
+    ///
+    /// On Windows, rooted paths without a drive (`\dir`) inherit the base
+    /// drive, and drive-relative paths (`D:dir`) resolve against the base only
+    /// when it is on the same drive.
+    pub(super) fn resolve(&self, path: &Path) -> Self {
+        let raw = path.to_string_lossy();
+        let parsed = parse(&raw, self.style);
+        let mut key = match (parsed.prefix, parsed.rooted) {
+            (Some(prefix), true) => self.with_root(prefix),
+            (None, true) => self.with_root(self.prefix.clone()),
+            (Some(prefix), false) if self.style.same(&prefix, &self.prefix) => self.clone(),
+            (Some(prefix), false) => self.with_root(prefix),
+            (None, false) => self.clone(),
+        };
+        for part in parsed.rest.split(|ch| self.style.is_separator(ch)) {
+            match part {
+                "" | "." => {}
+                ".." => {
+                    key.components.pop();
+                }
+                part => key.components.push(part.to_string()),
+            }
+        }
+        key
+    }
+
+    /// Path syntax of this key.
+    pub(super) fn style(&self) -> PathStyle {
+        self.style
+    }
+
+    /// Convert back to a host path; only meaningful for the native style.
+    pub(super) fn to_path_buf(&self) -> PathBuf {
+        PathBuf::from(self.to_string())
+    }
+
+    /// Whether `self` equals or is nested under `base`.
+    pub(super) fn starts_with(&self, base: &PathKey) -> bool {
+        self.style == base.style
+            && self.style.same(&self.prefix, &base.prefix)
+            && base.components.len() <= self.components.len()
+            && self
+                .components
+                .iter()
+                .zip(&base.components)
+                .all(|(left, right)| self.style.same(left, right))
+    }
+
+    fn with_root(&self, prefix: String) -> Self {
+        Self {
+            style: self.style,
+            prefix,
+            components: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for PathKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = match self.style {
+            PathStyle::Unix => "/",
+            PathStyle::Windows => "\\",
+        };
+        write!(
+            f,
+            "{}{separator}{}",
+            self.prefix,
+            self.components.join(separator)
+        )
+    }
+}
+
+/// Resolve symlinks along an absolute host path.
+///
+/// The deepest entry that exists (including dangling links) is canonicalized
+/// and the missing components are appended, so paths that do not exist yet
+/// still resolve through linked parent directories. Dangling links fail.
+pub(super) fn resolve_symlinks(path: &Path) -> std::io::Result<PathBuf> {
+    let mut missing = Vec::new();
+    let mut current = path;
+    loop {
+        if current.symlink_metadata().is_ok() {
+            let mut resolved = current.canonicalize()?;
+            resolved.extend(missing.iter().rev());
+            return Ok(resolved);
+        }
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                current = parent;
+            }
+            _ => return Ok(path.to_path_buf()),
+        }
+    }
+}
+
+/// Whether any existing component of `path` strictly below `root` is a symlink.
+///
+/// Shared by sandbox access checks and workspace path resolution in tools.
+pub fn has_symlink_below(path: &Path, root: &Path) -> bool {
+    path.ancestors()
+        .take_while(|ancestor| ancestor.starts_with(root) && *ancestor != root)
+        .any(|ancestor| {
+            ancestor
+                .symlink_metadata()
+                .is_ok_and(|meta| meta.file_type().is_symlink())
+        })
+}
+
+/// Split the prefix and root from a path string.
+fn parse(path: &str, style: PathStyle) -> ParsedPath<'_> {
+    match style {
+        PathStyle::Unix => ParsedPath {
+            prefix: None,
+            rooted: path.starts_with('/'),
+            rest: path,
+        },
+        PathStyle::Windows => parse_windows(path),
+    }
+}
+
+/// Split a Windows path into drive/UNC prefix, root flag, and remainder.
+fn parse_windows(path: &str) -> ParsedPath<'_> {
+    let is_sep = |ch: char| ch == '/' || ch == '\\';
+    let verbatim = ["\\\\?\\", "\\\\.\\", "//?/", "//./"]
+        .iter()
+        .find_map(|marker| path.strip_prefix(marker));
+    if let Some(rest) = verbatim {
+        let unc = rest.get(..4).is_some_and(|head| {
+            head.eq_ignore_ascii_case("UNC\\") || head.eq_ignore_ascii_case("UNC/")
+        });
+        if unc {
+            return parse_unc(&rest[4..]);
+        }
+        if let Some(parsed) = parse_drive(rest) {
+            return parsed;
+        }
+        // Device or volume paths (e.g. `\\?\Volume{...}`): the first component is the prefix.
+        let (device, rest) = rest.split_once(is_sep).unwrap_or((rest, ""));
+        return ParsedPath {
+            prefix: Some(format!("\\\\?\\{device}")),
+            rooted: true,
+            rest,
+        };
+    }
+    let mut chars = path.chars();
+    if chars.next().is_some_and(is_sep) && chars.next().is_some_and(is_sep) {
+        return parse_unc(&path[2..]);
+    }
+    parse_drive(path).unwrap_or(ParsedPath {
+        prefix: None,
+        rooted: path.starts_with(is_sep),
+        rest: path,
+    })
+}
+
+/// Parse `server\share\rest` following a UNC marker.
+fn parse_unc(path: &str) -> ParsedPath<'_> {
+    let is_sep = |ch: char| ch == '/' || ch == '\\';
+    let (server, rest) = path.split_once(is_sep).unwrap_or((path, ""));
+    let (share, rest) = rest.split_once(is_sep).unwrap_or((rest, ""));
+    ParsedPath {
+        prefix: Some(format!("\\\\{server}\\{share}")),
+        rooted: true,
+        rest,
+    }
+}
+
+/// Parse a leading drive letter (`C:`), if present.
+fn parse_drive(path: &str) -> Option<ParsedPath<'_>> {
+    let mut chars = path.chars();
+    let letter = chars.next().filter(char::is_ascii_alphabetic)?;
+    chars.next().filter(|ch| *ch == ':')?;
+    let rest = &path[2..];
+    Some(ParsedPath {
+        prefix: Some(format!("{}:", letter.to_ascii_uppercase())),
+        rooted: rest.starts_with(['/', '\\']),
+        rest,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PathKey, PathStyle};
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    fn windows(path: &str) -> PathKey {
+        PathKey::new(Path::new(path), PathStyle::Windows)
+    }
+
+    #[test]
+    fn unix_paths_resolve_dot_segments() {
+        let key = PathKey::new(Path::new("/tmp/a/./b/../c"), PathStyle::Unix);
+        assert_eq!(key.to_string(), "/tmp/a/c");
+        assert_eq!(
+            PathKey::new(Path::new("/../.."), PathStyle::Unix).to_string(),
+            "/"
+        );
+    }
+
+    #[test]
+    fn windows_paths_normalize_prefixes_and_separators() {
+        assert_eq!(
+            windows("c:/Users/dev/../Repo").to_string(),
+            "C:\\Users\\Repo"
+        );
+        assert_eq!(windows("\\\\?\\C:\\Repo").to_string(), "C:\\Repo");
+        assert_eq!(
+            windows("\\\\?\\UNC\\server\\share\\dir").to_string(),
+            "\\\\server\\share\\dir"
+        );
+        assert_eq!(
+            windows("//server/share/dir/..").to_string(),
+            "\\\\server\\share\\"
+        );
+        assert_eq!(windows("C:\\..\\..").to_string(), "C:\\");
+    }
+
+    #[test]
+    fn windows_comparisons_ignore_case_and_respect_roots() {
+        let root = windows("C:\\Users\\Dev\\Repo");
+        assert_eq!(
+            windows("c:\\users\\dev\\repo\\src").starts_with(&root),
+            true
+        );
+        assert_eq!(
+            windows("C:\\Users\\Dev\\Repository").starts_with(&root),
+            false
+        );
+        assert_eq!(windows("D:\\Users\\Dev\\Repo").starts_with(&root), false);
+
+        let share = windows("\\\\server\\share");
+        assert_eq!(windows("\\\\SERVER\\Share\\file").starts_with(&share), true);
+        assert_eq!(
+            windows("\\\\server\\other\\file").starts_with(&share),
+            false
+        );
+    }
+
+    #[test]
+    fn windows_relative_paths_follow_base_drive() {
+        let base = windows("C:\\repo");
+        assert_eq!(
+            base.resolve(Path::new("src\\main.rs")).to_string(),
+            "C:\\repo\\src\\main.rs"
+        );
+        assert_eq!(base.resolve(Path::new("\\tmp")).to_string(), "C:\\tmp");
+        assert_eq!(
+            base.resolve(Path::new("c:src")).to_string(),
+            "C:\\repo\\src"
+        );
+        assert_eq!(base.resolve(Path::new("D:src")).to_string(), "D:\\src");
+    }
+}
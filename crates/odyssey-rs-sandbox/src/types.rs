@@ -44,7 +44,7 @@ pub struct SandboxHandle {
 }
 
 /// Aggregated sandbox policy settings.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct SandboxPolicy {
     /// Filesystem access policy.
     pub filesystem: SandboxFilesystemPolicy,
@@ -57,7 +57,7 @@ pub struct SandboxPolicy {
 }
 
 /// Filesystem allow/deny lists.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SandboxFilesystemPolicy {
     /// Allowed read paths.
     pub allow_read: Vec<String>,
@@ -71,10 +71,28 @@ pub struct SandboxFilesystemPolicy {
     pub allow_exec: Vec<String>,
     /// Denied executable paths.
     pub deny_exec: Vec<String>,
+    /// Follow symlinks inside the workspace. Resolved targets are checked
+    /// against the policy either way; when disabled, paths that traverse a
+    /// workspace symlink are denied outright.
+    pub follow_symlinks: bool,
+}
+
+impl Default for SandboxFilesystemPolicy {
+    fn default() -> Self {
+        Self {
+            allow_read: Vec::new(),
+            deny_read: Vec::new(),
+            allow_write: Vec::new(),
+            deny_write: Vec::new(),
+            allow_exec: Vec::new(),
+            deny_exec: Vec::new(),
+            follow_symlinks: true,
+        }
+    }
 }
 
 /// Environment variable policy settings.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct SandboxEnvPolicy {
     /// Allowed environment variables.
     pub allow: Vec<String>,
@@ -85,7 +103,7 @@ pub struct SandboxEnvPolicy {
 }
 
 /// Network access policy settings.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct SandboxNetworkPolicy {
     /// Allowed domains.
     pub allow_domains: Vec<String>,
@@ -93,8 +111,60 @@ pub struct SandboxNetworkPolicy {
     pub deny_domains: Vec<String>,
 }
 
+impl SandboxNetworkPolicy {
+    /// Policy denying every host; commands run without network access.
+    pub fn deny_all() -> Self {
+        Self {
+            allow_domains: Vec::new(),
+            deny_domains: vec!["*".to_string()],
+        }
+    }
+
+    /// Check a host against the domain lists.
+    ///
+    /// `example.com` matches the domain and its subdomains, `*.example.com`
+    /// matches subdomains only, and `*` matches every host. Deny entries win;
+    /// when allow entries are set, hosts matching none of them are denied.
+    pub fn check_host(&self, host: &str) -> AccessDecision {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        if let Some(entry) = self
+            .deny_domains
+            .iter()
+            .find(|entry| domain_matches(entry, &host))
+        {
+            return AccessDecision::Deny(format!("host {host} is denied by {entry}"));
+        }
+        if !self.allow_domains.is_empty()
+            && !self
+                .allow_domains
+                .iter()
+                .any(|entry| domain_matches(entry, &host))
+        {
+            return AccessDecision::Deny(format!("host {host} is not in allow_domains"));
+        }
+        AccessDecision::Allow
+    }
+}
+
+/// Return true when a lowercase host matches a domain list entry.
+fn domain_matches(entry: &str, host: &str) -> bool {
+    let entry = entry.trim().trim_end_matches('.').to_ascii_lowercase();
+    if entry == "*" {
+        return true;
+    }
+    if let Some(suffix) = entry.strip_prefix("*.") {
+        return host
+            .strip_suffix(suffix)
+            .is_some_and(|prefix| prefix.ends_with('.'));
+    }
+    host == entry
+        || host
+            .strip_suffix(entry.as_str())
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
 /// Resource limits for sandboxed commands.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct SandboxLimits {
     /// CPU seconds limit.
     pub cpu_seconds: Option<u64>,
@@ -142,7 +212,7 @@ impl CommandSpec {
 
 #[cfg(test)]
 mod tests {
-    use super::CommandSpec;
+    use super::{AccessDecision, CommandSpec, SandboxNetworkPolicy};
     use pretty_assertions::assert_eq;
     use std::path::PathBuf;
 
@@ -154,6 +224,39 @@ mod tests {
         assert_eq!(spec.cwd, None);
         assert_eq!(spec.env.len(), 0);
     }
+
+    #[test]
+    fn network_policy_matches_domains() {
+        let policy = SandboxNetworkPolicy {
+            allow_domains: vec!["example.com".to_string(), "*.api.dev".to_string()],
+            deny_domains: vec!["internal.example.com".to_string()],
+        };
+        assert_eq!(policy.check_host("example.com"), AccessDecision::Allow);
+        assert_eq!(policy.check_host("WWW.Example.com."), AccessDecision::Allow);
+        assert_eq!(policy.check_host("v1.api.dev"), AccessDecision::Allow);
+        assert_eq!(
+            policy.check_host("api.dev"),
+            AccessDecision::Deny("host api.dev is not in allow_domains".to_string())
+        );
+        assert_eq!(
+            policy.check_host("badexample.com"),
+            AccessDecision::Deny("host badexample.com is not in allow_domains".to_string())
+        );
+        assert_eq!(
+            policy.check_host("db.internal.example.com"),
+            AccessDecision::Deny(
+                "host db.internal.example.com is denied by internal.example.com".to_string()
+            )
+        );
+        assert_eq!(
+            SandboxNetworkPolicy::default().check_host("anything.test"),
+            AccessDecision::Allow
+        );
+        assert_eq!(
+            SandboxNetworkPolicy::deny_all().check_host("localhost"),
+            AccessDecision::Deny("host localhost is denied by *".to_string())
+        );
+    }
 }
 
 /// Result of a sandboxed command execution.
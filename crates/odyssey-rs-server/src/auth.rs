@@ -0,0 +1,310 @@
+//! Token-based authorization for the server API.
+//!
+//! Each entry in `server.tokens` maps an API token to a user and a
+//! [`ServerRole`]. Roles are ordered: viewers list sessions and read events,
+//! operators also create sessions, submit turns, and resolve approvals, and
+//! admins also change configuration at runtime. With no tokens configured the
+//! API is closed unless `server.open` is set, in which case every request acts
+//! as an anonymous admin, which suits a server bound to localhost for a single
+//! user.
+
+use log::{debug, info, warn};
+use odyssey_rs_config::{ServerConfig, ServerRole};
+use odyssey_rs_tools::{SecretError, SecretResolver};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Operation a request asks to perform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// List sessions.
+    ListSessions,
+    /// Subscribe to a session's events.
+    ReadEvents,
+    /// Create a session.
+    CreateSession,
+    /// Enqueue a submission: a user message, a context override, or a cancel.
+    Submit,
+    /// Answer a pending approval request.
+    ResolveApproval,
+    /// Change configuration at runtime.
+    ChangeConfig,
+}
+
+impl Action {
+    /// Least role allowed to perform the action.
+    pub fn required_role(self) -> ServerRole {
+        match self {
+            Action::ListSessions | Action::ReadEvents => ServerRole::Viewer,
+            Action::CreateSession | Action::Submit | Action::ResolveApproval => {
+                ServerRole::Operator
+            }
+            Action::ChangeConfig => ServerRole::Admin,
+        }
+    }
+}
+
+/// Caller a request was authenticated as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    /// User the token belongs to; unset when the API is open.
+    ///
+    /// Servers pass it as `SubmissionEnvelope::user_id` and to
+    /// `Orchestrator::create_session_for_user` so work is attributed to the caller.
+    pub user_id: Option<String>,
+    /// Role granted to the caller.
+    pub role: ServerRole,
+}
+
+impl Principal {
+    /// Whether the caller's role permits `action`.
+    pub fn allows(&self, action: Action) -> bool {
+        self.role >= action.required_role()
+    }
+}
+
+/// Errors returned while authenticating or authorizing a request.
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("missing API token")]
+    MissingToken,
+    #[error("invalid API token")]
+    InvalidToken,
+    #[error("{user_id} ({role:?}) may not perform {action:?}")]
+    Forbidden {
+        user_id: String,
+        role: ServerRole,
+        action: Action,
+    },
+    #[error("API token for {user_id} could not be loaded: {source}")]
+    Secret {
+        user_id: String,
+        #[source]
+        source: SecretError,
+    },
+    #[error("users {0} and {1} share an API token")]
+    DuplicateToken(String, String),
+    #[error("no server API tokens configured; set server.open to allow unauthenticated access")]
+    NoTokens,
+}
+
+/// Maps API tokens to principals and checks their roles.
+#[derive(Debug, Clone, Default)]
+pub struct Authorizer {
+    tokens: HashMap<String, Principal>,
+    open: bool,
+}
+
+impl Authorizer {
+    /// Resolve every configured `token_ref` through `secrets`.
+    pub fn from_config(config: &ServerConfig, secrets: &SecretResolver) -> Result<Self, AuthError> {
+        let mut tokens = HashMap::new();
+        for entry in &config.tokens {
+            let token = secrets
+                .resolve(&entry.token_ref)
+                .map_err(|source| AuthError::Secret {
+                    user_id: entry.user_id.clone(),
+                    source,
+                })?;
+            let principal = Principal {
+                user_id: Some(entry.user_id.clone()),
+                role: entry.role,
+            };
+            if let Some(existing) = tokens.insert(token, principal) {
+                return Err(AuthError::DuplicateToken(
+                    existing.user_id.unwrap_or_default(),
+                    entry.user_id.clone(),
+                ));
+            }
+        }
+        if tokens.is_empty() {
+            if !config.open {
+                return Err(AuthError::NoTokens);
+            }
+            warn!("server.open is set; the API is open to anyone who can reach it");
+        } else {
+            if config.open {
+                warn!("ignoring server.open because API tokens are configured");
+            }
+            info!("loaded server API tokens (count={})", tokens.len());
+        }
+        Ok(Self {
+            tokens,
+            open: config.open,
+        })
+    }
+
+    /// Whether requests are accepted without a token.
+    pub fn is_open(&self) -> bool {
+        self.open && self.tokens.is_empty()
+    }
+
+    /// Identify the caller presenting `token`.
+    pub fn authenticate(&self, token: Option<&str>) -> Result<Principal, AuthError> {
+        if self.is_open() {
+            return Ok(Principal {
+                user_id: None,
+                role: ServerRole::Admin,
+            });
+        }
+        let token = token.ok_or(AuthError::MissingToken)?;
+        self.tokens
+            .get(token)
+            .cloned()
+            .ok_or(AuthError::InvalidToken)
+    }
+
+    /// Identify the caller presenting `token` and check that it may perform `action`.
+    pub fn authorize(&self, token: Option<&str>, action: Action) -> Result<Principal, AuthError> {
+        let principal = self.authenticate(token)?;
+        if !principal.allows(action) {
+            let user_id = principal.user_id.unwrap_or_default();
+            debug!("denied request (user_id={user_id}, action={action:?})");
+            return Err(AuthError::Forbidden {
+                user_id,
+                role: principal.role,
+                action,
+            });
+        }
+        Ok(principal)
+    }
+}
+
+/// Token from an `Authorization: Bearer <token>` header value.
+pub fn bearer_token(header: &str) -> Option<&str> {
+    let (scheme, token) = header.trim().split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, AuthError, Authorizer, Principal, bearer_token};
+    use odyssey_rs_config::{ApiTokenConfig, ServerConfig, ServerRole};
+    use odyssey_rs_tools::{SecretError, SecretResolver, SecretsProvider};
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    struct FixedSecrets;
+
+    impl SecretsProvider for FixedSecrets {
+        fn get(&self, name: &str) -> Result<String, SecretError> {
+            match name {
+                "ADMIN" => Ok("admin-token".to_string()),
+                "VIEWER" | "COPY" => Ok("viewer-token".to_string()),
+                _ => Err(SecretError::NotFound(name.to_string())),
+            }
+        }
+    }
+
+    fn token(token_ref: &str, user_id: &str, role: ServerRole) -> ApiTokenConfig {
+        ApiTokenConfig {
+            token_ref: token_ref.to_string(),
+            user_id: user_id.to_string(),
+            role,
+        }
+    }
+
+    fn authorizer(tokens: Vec<ApiTokenConfig>, open: bool) -> Result<Authorizer, AuthError> {
+        let secrets =
+            SecretResolver::new(None, "odyssey").with_provider("env", Arc::new(FixedSecrets));
+        Authorizer::from_config(&ServerConfig { tokens, open }, &secrets)
+    }
+
+    #[test]
+    fn roles_gate_actions() {
+        let auth = authorizer(
+            vec![
+                token("env:ADMIN", "alice", ServerRole::Admin),
+                token("env:VIEWER", "bob", ServerRole::Viewer),
+            ],
+            true,
+        )
+        .expect("authorizer");
+        assert_eq!(auth.is_open(), false);
+
+        let admin = auth
+            .authorize(Some("admin-token"), Action::ChangeConfig)
+            .expect("admin");
+        assert_eq!(
+            admin,
+            Principal {
+                user_id: Some("alice".to_string()),
+                role: ServerRole::Admin,
+            }
+        );
+        auth.authorize(Some("viewer-token"), Action::ReadEvents)
+            .expect("viewer reads");
+        for action in [
+            Action::CreateSession,
+            Action::Submit,
+            Action::ResolveApproval,
+        ] {
+            let err = auth
+                .authorize(Some("viewer-token"), action)
+                .expect_err("viewer writes");
+            assert_eq!(matches!(err, AuthError::Forbidden { .. }), true);
+        }
+        assert_eq!(
+            matches!(
+                auth.authorize(Some("guess"), Action::ListSessions),
+                Err(AuthError::InvalidToken)
+            ),
+            true
+        );
+        assert_eq!(
+            matches!(
+                auth.authorize(None, Action::ListSessions),
+                Err(AuthError::MissingToken)
+            ),
+            true
+        );
+    }
+
+    #[test]
+    fn open_only_when_configured_and_rejects_bad_config() {
+        let err = authorizer(Vec::new(), false).expect_err("closed without tokens");
+        assert_eq!(matches!(err, AuthError::NoTokens), true);
+        assert_eq!(
+            matches!(
+                Authorizer::default().authorize(None, Action::ListSessions),
+                Err(AuthError::MissingToken)
+            ),
+            true
+        );
+
+        let open = authorizer(Vec::new(), true).expect("authorizer");
+        assert_eq!(open.is_open(), true);
+        assert_eq!(
+            open.authorize(None, Action::ChangeConfig).expect("open"),
+            Principal {
+                user_id: None,
+                role: ServerRole::Admin,
+            }
+        );
+
+        let err = authorizer(
+            vec![token("env:MISSING", "carol", ServerRole::Operator)],
+            false,
+        )
+        .expect_err("missing secret");
+        assert_eq!(matches!(err, AuthError::Secret { .. }), true);
+        let err = authorizer(
+            vec![
+                token("env:VIEWER", "bob", ServerRole::Viewer),
+                token("env:COPY", "dave", ServerRole::Admin),
+            ],
+            false,
+        )
+        .expect_err("duplicate");
+        assert_eq!(err.to_string(), "users bob and dave share an API token");
+    }
+
+    #[test]
+    fn parses_bearer_headers() {
+        assert_eq!(bearer_token("Bearer abc"), Some("abc"));
+        assert_eq!(bearer_token("bearer  abc "), Some("abc"));
+        assert_eq!(bearer_token("Basic abc"), None);
+        assert_eq!(bearer_token("Bearer "), None);
+    }
+}
@@ -0,0 +1,8 @@
+//! Odyssey server library.
+//!
+//! Holds the pieces of the HTTP/WebSocket server that do not depend on the
+//! transport, starting with API token authorization.
+
+pub mod auth;
+
+pub use auth::{Action, AuthError, Authorizer, Principal, bearer_token};
@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use odyssey_rs_core::permissions::{ApprovalHandler, ApprovalRequest};
+use odyssey_rs_protocol::ApprovalDecision;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+
+/// Approval handler that answers with scripted decisions and records every request.
+///
+/// Decisions are used in order; once they run out the fallback decision (deny by
+/// default) answers the remaining requests.
+pub struct ScriptedApprovalHandler {
+    decisions: Mutex<VecDeque<ApprovalDecision>>,
+    fallback: ApprovalDecision,
+    requests: Mutex<Vec<ApprovalRequest>>,
+}
+
+impl ScriptedApprovalHandler {
+    pub fn new(decisions: impl IntoIterator<Item = ApprovalDecision>) -> Self {
+        Self {
+            decisions: Mutex::new(decisions.into_iter().collect()),
+            fallback: ApprovalDecision::Deny,
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Handler that always answers with `decision`.
+    pub fn always(decision: ApprovalDecision) -> Self {
+        Self::new([]).with_fallback(decision)
+    }
+
+    /// Decision used once the script is exhausted.
+    pub fn with_fallback(mut self, decision: ApprovalDecision) -> Self {
+        self.fallback = decision;
+        self
+    }
+
+    /// Requests received so far, in call order.
+    pub fn requests(&self) -> Vec<ApprovalRequest> {
+        self.requests.lock().clone()
+    }
+}
+
+#[async_trait]
+impl ApprovalHandler for ScriptedApprovalHandler {
+    async fn request_approval(&self, request: ApprovalRequest) -> ApprovalDecision {
+        self.requests.lock().push(request);
+        self.decisions.lock().pop_front().unwrap_or(self.fallback)
+    }
+}
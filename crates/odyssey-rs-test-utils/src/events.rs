@@ -0,0 +1,39 @@
+use odyssey_rs_protocol::{EventMsg, EventPayload, EventSink};
+use parking_lot::Mutex;
+
+/// Event sink that keeps every emitted event for later assertions.
+#[derive(Default)]
+pub struct CollectingEventSink {
+    events: Mutex<Vec<EventMsg>>,
+}
+
+impl CollectingEventSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events emitted so far, in emission order.
+    pub fn events(&self) -> Vec<EventMsg> {
+        self.events.lock().clone()
+    }
+
+    /// Payloads emitted so far, in emission order.
+    pub fn payloads(&self) -> Vec<EventPayload> {
+        self.events
+            .lock()
+            .iter()
+            .map(|event| event.payload.clone())
+            .collect()
+    }
+
+    /// Remove and return the events emitted so far.
+    pub fn take(&self) -> Vec<EventMsg> {
+        std::mem::take(&mut *self.events.lock())
+    }
+}
+
+impl EventSink for CollectingEventSink {
+    fn emit(&self, event: EventMsg) {
+        self.events.lock().push(event);
+    }
+}
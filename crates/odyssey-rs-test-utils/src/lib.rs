@@ -1,17 +1,23 @@
 //! Test helpers shared across Odyssey crates.
 
 pub mod agent;
+pub mod approval;
 pub mod context;
+pub mod events;
 pub mod llm;
 pub mod memory;
+pub mod sandbox;
 pub mod skills;
 pub mod tools;
 
 pub use agent::DummyAgent;
+pub use approval::ScriptedApprovalHandler;
 pub use context::base_tool_context;
+pub use events::CollectingEventSink;
 pub use llm::{
-    FailingLLM, FixedChatResponse, FixedLLM, RecordingChatLLM, RecordingLLM, StreamingLLM,
+    FailingLLM, FixedChatResponse, FixedLLM, RecordingChatLLM, RecordingLLM, SlowLLM, StreamingLLM,
 };
 pub use memory::StubMemory;
+pub use sandbox::MockSandboxProvider;
 pub use skills::StubSkillProvider;
 pub use tools::{DummyTool, DummyToolRuntime};
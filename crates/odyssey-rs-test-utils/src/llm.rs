@@ -12,6 +12,7 @@ use futures_util::stream;
 use parking_lot::Mutex;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct FixedChatResponse {
@@ -252,6 +253,73 @@ impl ModelsProvider for StreamingLLM {}
 
 impl LLMProvider for StreamingLLM {}
 
+/// LLM that waits before answering, for exercising in-flight turns.
+#[derive(Debug, Clone)]
+pub struct SlowLLM {
+    response: String,
+    delay: Duration,
+}
+
+impl SlowLLM {
+    pub fn new(response: impl Into<String>, delay: Duration) -> Self {
+        Self {
+            response: response.into(),
+            delay,
+        }
+    }
+}
+
+#[async_trait]
+impl ChatProvider for SlowLLM {
+    async fn chat_with_tools(
+        &self,
+        _messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+        _json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<Box<dyn ChatResponse>, LLMError> {
+        tokio::time::sleep(self.delay).await;
+        Ok(Box::new(FixedChatResponse::new(self.response.clone())))
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        _messages: &[ChatMessage],
+        _tools: Option<&[Tool]>,
+        _json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<LlmStream, LLMError> {
+        tokio::time::sleep(self.delay).await;
+        Ok(Box::pin(stream::iter(vec![Ok(StreamChunk::Text(
+            self.response.clone(),
+        ))])))
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for SlowLLM {
+    async fn complete(
+        &self,
+        _req: &CompletionRequest,
+        _json_schema: Option<StructuredOutputFormat>,
+    ) -> Result<CompletionResponse, LLMError> {
+        tokio::time::sleep(self.delay).await;
+        Ok(CompletionResponse {
+            text: self.response.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for SlowLLM {
+    async fn embed(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>, LLMError> {
+        Ok(input.into_iter().map(|_| vec![0.0, 0.0]).collect())
+    }
+}
+
+#[async_trait]
+impl ModelsProvider for SlowLLM {}
+
+impl LLMProvider for SlowLLM {}
+
 #[derive(Debug, Clone)]
 pub struct RecordingChatLLM {
     response: String,
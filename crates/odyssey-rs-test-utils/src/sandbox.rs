@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use odyssey_rs_sandbox::{
+    AccessDecision, AccessMode, CommandOutputSink, CommandResult, CommandSpec, SandboxContext,
+    SandboxError, SandboxHandle, SandboxProvider,
+};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Sandbox provider that returns scripted command results without spawning processes.
+///
+/// Results are returned in the order they were queued; once the queue is empty every
+/// command succeeds with no output. Every spec passed to `run_command` is recorded.
+#[derive(Default)]
+pub struct MockSandboxProvider {
+    results: Mutex<VecDeque<Result<CommandResult, SandboxError>>>,
+    specs: Mutex<Vec<CommandSpec>>,
+    denied_paths: Vec<PathBuf>,
+    denied_hosts: Vec<String>,
+}
+
+impl MockSandboxProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a command result.
+    pub fn with_result(self, result: CommandResult) -> Self {
+        self.results.lock().push_back(Ok(result));
+        self
+    }
+
+    /// Queue a successful command with the given stdout.
+    pub fn with_stdout(self, stdout: impl Into<String>) -> Self {
+        self.with_result(CommandResult {
+            status_code: Some(0),
+            stdout: stdout.into(),
+            stderr: String::new(),
+        })
+    }
+
+    /// Queue a provider error.
+    pub fn with_error(self, error: SandboxError) -> Self {
+        self.results.lock().push_back(Err(error));
+        self
+    }
+
+    /// Deny access to a path and everything below it.
+    pub fn deny_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.denied_paths.push(path.into());
+        self
+    }
+
+    /// Deny network access to a host.
+    pub fn deny_host(mut self, host: impl Into<String>) -> Self {
+        self.denied_hosts.push(host.into());
+        self
+    }
+
+    /// Command specs received so far, in call order.
+    pub fn specs(&self) -> Vec<CommandSpec> {
+        self.specs.lock().clone()
+    }
+
+    fn next_result(&self, spec: CommandSpec) -> Result<CommandResult, SandboxError> {
+        self.specs.lock().push(spec);
+        self.results.lock().pop_front().unwrap_or_else(|| {
+            Ok(CommandResult {
+                status_code: Some(0),
+                ..CommandResult::default()
+            })
+        })
+    }
+}
+
+#[async_trait]
+impl SandboxProvider for MockSandboxProvider {
+    async fn prepare(&self, _ctx: &SandboxContext) -> Result<SandboxHandle, SandboxError> {
+        Ok(SandboxHandle { id: Uuid::new_v4() })
+    }
+
+    async fn run_command(
+        &self,
+        _handle: &SandboxHandle,
+        spec: CommandSpec,
+    ) -> Result<CommandResult, SandboxError> {
+        self.next_result(spec)
+    }
+
+    async fn run_command_streaming(
+        &self,
+        _handle: &SandboxHandle,
+        spec: CommandSpec,
+        sink: &mut dyn CommandOutputSink,
+    ) -> Result<CommandResult, SandboxError> {
+        let result = self.next_result(spec)?;
+        if !result.stdout.is_empty() {
+            sink.stdout(&result.stdout);
+        }
+        if !result.stderr.is_empty() {
+            sink.stderr(&result.stderr);
+        }
+        Ok(result)
+    }
+
+    fn check_access(
+        &self,
+        _handle: &SandboxHandle,
+        path: &Path,
+        _mode: AccessMode,
+    ) -> AccessDecision {
+        match self
+            .denied_paths
+            .iter()
+            .find(|denied| path.starts_with(denied))
+        {
+            Some(denied) => AccessDecision::Deny(format!("denied by mock: {}", denied.display())),
+            None => AccessDecision::Allow,
+        }
+    }
+
+    fn check_network(&self, _handle: &SandboxHandle, host: &str) -> AccessDecision {
+        if self.denied_hosts.iter().any(|denied| denied == host) {
+            AccessDecision::Deny(format!("denied by mock: {host}"))
+        } else {
+            AccessDecision::Allow
+        }
+    }
+
+    async fn shutdown(&self, _handle: SandboxHandle) {}
+}
@@ -0,0 +1,51 @@
+use criterion::{BatchSize, Criterion, Throughput, criterion_group, criterion_main};
+use odyssey_rs_tools::ToolOutputPolicy;
+use serde_json::{Value, json};
+
+const PAYLOAD_BYTES: usize = 4 * 1024 * 1024;
+
+/// Bash-style result with a multi-megabyte stdout and a long line listing.
+fn large_payload() -> Value {
+    let line = "warning: unused variable `x` --> src/lib.rs:10:5\n";
+    let stdout = line.repeat(PAYLOAD_BYTES / line.len());
+    let lines = stdout.lines().map(Value::from).collect::<Vec<_>>();
+    json!({
+        "status_code": 0,
+        "stdout": stdout,
+        "stderr": "",
+        "lines": lines,
+    })
+}
+
+fn bench_output_policy(criterion: &mut Criterion) {
+    let payload = large_payload();
+    let byte_policy = ToolOutputPolicy::default();
+    let token_policy = ToolOutputPolicy {
+        max_string_tokens: Some(8_000),
+        ..ToolOutputPolicy::default()
+    };
+    let redacting_policy = ToolOutputPolicy {
+        redact_values: vec!["sk-live".to_string(), "unused".to_string()],
+        ..ToolOutputPolicy::default()
+    };
+
+    let mut group = criterion.benchmark_group("output_policy");
+    group.throughput(Throughput::Bytes(PAYLOAD_BYTES as u64));
+    for (name, policy) in [
+        ("truncate_bytes_4mb", &byte_policy),
+        ("truncate_tokens_4mb", &token_policy),
+        ("redact_values_4mb", &redacting_policy),
+    ] {
+        group.bench_function(name, |bencher| {
+            bencher.iter_batched(
+                || payload.clone(),
+                |payload| policy.apply(payload),
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(output_policy_benches, bench_output_policy);
+criterion_main!(output_policy_benches);
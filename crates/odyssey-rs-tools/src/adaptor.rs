@@ -1,6 +1,6 @@
 //! Adaptor for autoagents tool trait.
 
-use crate::{Tool, ToolContext};
+use crate::{Tool, ToolContext, ToolScheduler};
 use async_trait::async_trait;
 use autoagents_core::tool::{ToolCallError, ToolRuntime, ToolT};
 use parking_lot::RwLock;
@@ -15,12 +15,24 @@ pub struct ToolAdaptor {
     tool: Arc<dyn Tool>,
     /// Shared tool context.
     ctx: Arc<RwLock<ToolContext>>,
+    /// Optional scheduler enforcing concurrency limits and exclusion groups.
+    scheduler: Option<Arc<ToolScheduler>>,
 }
 
 impl ToolAdaptor {
     /// Create a new tool adaptor.
     pub fn new(tool: Arc<dyn Tool>, ctx: Arc<RwLock<ToolContext>>) -> Self {
-        Self { tool, ctx }
+        Self {
+            tool,
+            ctx,
+            scheduler: None,
+        }
+    }
+
+    /// Run calls through a scheduler before executing.
+    pub fn with_scheduler(mut self, scheduler: Arc<ToolScheduler>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
     }
 }
 
@@ -36,6 +48,10 @@ impl fmt::Debug for ToolAdaptor {
 impl ToolRuntime for ToolAdaptor {
     /// Execute a tool call, delegating the full pipeline to ToolContext.
     async fn execute(&self, args: Value) -> Result<Value, ToolCallError> {
+        let _permit = match &self.scheduler {
+            Some(scheduler) => Some(scheduler.acquire(self.tool.as_ref()).await),
+            None => None,
+        };
         let mut ctx = self.ctx.read().clone();
         ctx.execute_tool(self.tool.as_ref(), args)
             .await
@@ -76,10 +92,25 @@ pub fn tools_to_adaptors(
         .collect()
 }
 
+/// Wrap multiple tools with adaptors that share a scheduler.
+pub fn scheduled_adaptors(
+    tools: Vec<Arc<dyn Tool>>,
+    ctx: Arc<RwLock<ToolContext>>,
+    scheduler: Arc<ToolScheduler>,
+) -> Vec<Arc<dyn ToolT>> {
+    tools
+        .into_iter()
+        .map(|tool| {
+            Arc::new(ToolAdaptor::new(tool, ctx.clone()).with_scheduler(scheduler.clone()))
+                as Arc<dyn ToolT>
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::{ToolAdaptor, tool_to_adaptor, tools_to_adaptors};
-    use crate::{Tool, ToolContext, TurnServices};
+    use crate::{Tool, ToolContext};
     use async_trait::async_trait;
     use autoagents_core::tool::ToolRuntime;
     use odyssey_rs_protocol::ToolError;
@@ -87,7 +118,6 @@ mod tests {
     use pretty_assertions::assert_eq;
     use serde_json::json;
     use std::sync::Arc;
-    use uuid::Uuid;
 
     #[derive(Debug)]
     struct DummyTool;
@@ -116,25 +146,7 @@ mod tests {
     }
 
     fn base_context() -> ToolContext {
-        ToolContext {
-            session_id: Uuid::nil(),
-            agent_id: "agent".to_string(),
-            turn_id: None,
-            tool_call_id: None,
-            tool_name: None,
-            services: Arc::new(TurnServices {
-                cwd: ".".into(),
-                workspace_root: ".".into(),
-                output_policy: None,
-                sandbox: None,
-                web: None,
-                event_sink: None,
-                skill_provider: None,
-                question_handler: None,
-                permission_checker: None,
-                tool_result_handler: None,
-            }),
-        }
+        ToolContext::builder().with_workspace_root(".").build()
     }
 
     #[tokio::test]
@@ -0,0 +1,284 @@
+//! Built-in tools for publishing and retrieving shared artifacts.
+
+use crate::builtins::utils::parse_args;
+use crate::{Tool, ToolContext};
+use async_trait::async_trait;
+use autoagents_core::tool::ToolInputT;
+use autoagents_derive::ToolInput;
+use log::info;
+use odyssey_rs_protocol::{
+    ArtifactProvider, ArtifactPublish, ArtifactSummary, ToolError, ToolRisk,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Tool that publishes a named artifact to the shared store.
+#[derive(Debug, Default)]
+pub struct PublishArtifactTool;
+
+/// Arguments for PublishArtifactTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+struct PublishArtifactArgs {
+    #[input(description = "Artifact name; publishing an existing name replaces it")]
+    name: String,
+    #[input(description = "Artifact content")]
+    content: String,
+    #[input(description = "Optional description of the artifact")]
+    #[serde(default)]
+    description: Option<String>,
+    #[input(description = "Optional media type, e.g. text/markdown")]
+    #[serde(default)]
+    media_type: Option<String>,
+}
+
+#[async_trait]
+impl Tool for PublishArtifactTool {
+    fn name(&self) -> &str {
+        "PublishArtifact"
+    }
+
+    fn description(&self) -> &str {
+        "Publish a named artifact (report, log, dataset) that later sessions can retrieve"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = PublishArtifactArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::WorkspaceMutating
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let provider = artifact_provider(ctx)?;
+        let input: PublishArtifactArgs = parse_args(args)?;
+        if ctx.services.simulation {
+            info!(
+                "simulated artifact publish (name={}, bytes={})",
+                input.name,
+                input.content.len()
+            );
+            return Ok(json!({
+                "simulated": true,
+                "action": "publish_artifact",
+                "name": input.name,
+                "bytes": input.content.len(),
+            }));
+        }
+        let summary = provider.publish(ArtifactPublish {
+            name: input.name,
+            content: input.content.into_bytes(),
+            description: input.description,
+            media_type: input.media_type,
+            session_id: Some(ctx.session_id),
+            metadata: json!({
+                "agent_id": ctx.agent_id,
+                "turn_id": ctx.turn_id,
+            }),
+        })?;
+        info!("published artifact (name={})", summary.name);
+        Ok(summary_json(&summary))
+    }
+}
+
+/// Tool that lists artifacts or reads one by name or reference.
+#[derive(Debug, Default)]
+pub struct ArtifactTool;
+
+/// Arguments for ArtifactTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+struct ArtifactArgs {
+    #[input(
+        description = "Artifact name or artifact:// reference to read. Omit to list artifacts."
+    )]
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[async_trait]
+impl Tool for ArtifactTool {
+    fn name(&self) -> &str {
+        "Artifact"
+    }
+
+    fn description(&self) -> &str {
+        "List shared artifacts or read one by name or artifact:// reference"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = ArtifactArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let provider = artifact_provider(ctx)?;
+        let input: ArtifactArgs = parse_args(args)?;
+        if let Some(name) = input.name.as_deref() {
+            info!("reading artifact (name={})", name);
+            let (summary, content) = provider.get(name)?;
+            let mut result = summary_json(&summary);
+            result["content"] = Value::String(String::from_utf8_lossy(&content).to_string());
+            return Ok(result);
+        }
+        info!("listing artifacts");
+        let artifacts = provider
+            .list()?
+            .iter()
+            .map(summary_json)
+            .collect::<Vec<_>>();
+        Ok(json!({ "artifacts": artifacts }))
+    }
+}
+
+fn artifact_provider(ctx: &ToolContext) -> Result<Arc<dyn ArtifactProvider>, ToolError> {
+    ctx.services
+        .artifact_provider
+        .clone()
+        .ok_or_else(|| ToolError::ExecutionFailed("artifacts are not enabled".to_string()))
+}
+
+fn summary_json(summary: &ArtifactSummary) -> Value {
+    json!({
+        "name": summary.name,
+        "reference": summary.reference(),
+        "description": summary.description,
+        "media_type": summary.media_type,
+        "size": summary.size,
+        "session_id": summary.session_id,
+        "created_at": summary.created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArtifactTool, PublishArtifactTool};
+    use crate::{Tool, ToolContext};
+    use chrono::Utc;
+    use odyssey_rs_protocol::{
+        ArtifactProvider, ArtifactPublish, ArtifactSummary, ToolError, artifact_name,
+    };
+    use parking_lot::Mutex;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+    use uuid::Uuid;
+
+    #[derive(Default)]
+    struct MemoryArtifacts {
+        entries: Mutex<Vec<(ArtifactSummary, Vec<u8>)>>,
+    }
+
+    impl ArtifactProvider for MemoryArtifacts {
+        fn list(&self) -> Result<Vec<ArtifactSummary>, ToolError> {
+            Ok(self
+                .entries
+                .lock()
+                .iter()
+                .map(|(summary, _)| summary.clone())
+                .collect())
+        }
+
+        fn publish(&self, request: ArtifactPublish) -> Result<ArtifactSummary, ToolError> {
+            let summary = ArtifactSummary {
+                id: Uuid::new_v4(),
+                name: request.name,
+                description: request.description,
+                media_type: request.media_type,
+                size: request.content.len() as u64,
+                session_id: request.session_id,
+                created_at: Utc::now(),
+                metadata: request.metadata,
+            };
+            self.entries.lock().push((summary.clone(), request.content));
+            Ok(summary)
+        }
+
+        fn get(&self, name: &str) -> Result<(ArtifactSummary, Vec<u8>), ToolError> {
+            let name = artifact_name(name);
+            self.entries
+                .lock()
+                .iter()
+                .find(|(summary, _)| summary.name == name)
+                .cloned()
+                .ok_or_else(|| ToolError::ExecutionFailed(format!("unknown artifact: {name}")))
+        }
+    }
+
+    fn context(
+        root: &std::path::Path,
+        provider: Option<Arc<dyn ArtifactProvider>>,
+        simulation: bool,
+    ) -> ToolContext {
+        let builder = ToolContext::builder()
+            .with_workspace_root(root)
+            .with_simulation(simulation);
+        match provider {
+            Some(provider) => builder.with_artifact_provider(provider).build(),
+            None => builder.build(),
+        }
+    }
+
+    #[tokio::test]
+    async fn artifact_tools_require_provider() {
+        let temp = tempdir().expect("tempdir");
+        let ctx = context(temp.path(), None, false);
+        let err = ArtifactTool
+            .call(&ctx, json!({}))
+            .await
+            .expect_err("no provider");
+        let ToolError::ExecutionFailed(message) = err else {
+            panic!("expected execution failed");
+        };
+        assert_eq!(message, "artifacts are not enabled");
+    }
+
+    #[tokio::test]
+    async fn publish_then_read_by_reference() {
+        let temp = tempdir().expect("tempdir");
+        let provider = Arc::new(MemoryArtifacts::default());
+        let ctx = context(temp.path(), Some(provider.clone()), false);
+        let published = PublishArtifactTool
+            .call(
+                &ctx,
+                json!({ "name": "report", "content": "# Findings", "media_type": "text/markdown" }),
+            )
+            .await
+            .expect("publish");
+        assert_eq!(published["reference"], "artifact://report");
+        assert_eq!(published["size"], 10);
+
+        let listed = ArtifactTool.call(&ctx, json!({})).await.expect("list");
+        assert_eq!(listed["artifacts"][0]["name"], "report");
+
+        let read = ArtifactTool
+            .call(&ctx, json!({ "name": "artifact://report" }))
+            .await
+            .expect("read");
+        assert_eq!(read["content"], "# Findings");
+        assert_eq!(read["media_type"], "text/markdown");
+    }
+
+    #[tokio::test]
+    async fn publish_is_simulated_without_writing() {
+        let temp = tempdir().expect("tempdir");
+        let provider = Arc::new(MemoryArtifacts::default());
+        let ctx = context(temp.path(), Some(provider.clone()), true);
+        let result = PublishArtifactTool
+            .call(&ctx, json!({ "name": "report", "content": "data" }))
+            .await
+            .expect("simulate");
+        assert_eq!(result["simulated"], true);
+        assert_eq!(provider.list().expect("list").is_empty(), true);
+    }
+}
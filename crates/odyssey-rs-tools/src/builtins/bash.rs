@@ -1,14 +1,15 @@
 //! Built-in tool for running shell commands in the workspace.
 
+use crate::builtins::cwd::change_cwd;
 use crate::builtins::utils::{ResolveMode, resolve_workspace_path};
-use crate::{Tool, ToolContext};
+use crate::{Tool, ToolContext, ToolDeltaSink};
 use async_trait::async_trait;
 use autoagents_core::tool::ToolInputT;
 use autoagents_derive::ToolInput;
 use chrono::Utc;
 use log::{debug, info, warn};
 use odyssey_rs_protocol::ToolError;
-use odyssey_rs_protocol::{EventMsg, EventPayload, ExecStream};
+use odyssey_rs_protocol::{EventMsg, EventPayload, ExecStream, ToolRisk};
 use odyssey_rs_sandbox::{AccessMode, CommandOutputSink, CommandSpec};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
@@ -22,7 +23,7 @@ struct BashArgs {
     #[input(description = "The shell command to execute")]
     command: String,
     #[input(
-        description = "Optional working directory for the command, If none the current directory will be used"
+        description = "Optional working directory for the command, If none the session working directory will be used"
     )]
     #[serde(default)]
     cwd: Option<String>,
@@ -46,86 +47,153 @@ impl Tool for BashTool {
         serde_json::from_str(params_str).expect("Error parsing tool paramters")
     }
 
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::Destructive
+    }
+
     async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
-        let input = parse_bash_args(args)?;
-        let (command_str, command_args) = parse_command_line(&input.command)?;
-        info!(
-            "executing command (args_len={}, has_cwd={})",
-            command_args.len(),
-            input.cwd.is_some(),
-        );
+        run_bash(ctx, args, None).await
+    }
+
+    async fn call_streaming(
+        &self,
+        ctx: &ToolContext,
+        args: Value,
+        sink: &dyn ToolDeltaSink,
+    ) -> Result<Value, ToolError> {
+        run_bash(ctx, args, Some(sink)).await
+    }
+}
+
+/// Run a command, optionally pushing output chunks to a tool delta sink.
+async fn run_bash(
+    ctx: &ToolContext,
+    args: Value,
+    deltas: Option<&dyn ToolDeltaSink>,
+) -> Result<Value, ToolError> {
+    let input = parse_bash_args(args)?;
+    let (command_str, command_args) = parse_command_line(&input.command)?;
+    info!(
+        "executing command (args_len={}, has_cwd={})",
+        command_args.len(),
+        input.cwd.is_some(),
+    );
+
+    // Commands do not run in a persistent shell, so `cd` updates the session cwd instead.
+    if command_str == "cd" {
+        return change_directory(ctx, &command_args, input.cwd.is_some());
+    }
 
-        let cwd = match input.cwd.as_deref() {
-            Some(cwd) => resolve_workspace_path(ctx, cwd, ResolveMode::Existing)?,
-            None => ctx.services.cwd.clone(),
+    let cwd = match input.cwd.as_deref() {
+        Some(cwd) => resolve_workspace_path(ctx, cwd, ResolveMode::Existing)?,
+        None => ctx.cwd(),
+    };
+
+    let raw_command = PathBuf::from(&command_str);
+    let (command, check_execute) =
+        if raw_command.components().count() > 1 || raw_command.is_absolute() {
+            (
+                resolve_workspace_path(ctx, &command_str, ResolveMode::Existing)?,
+                true,
+            )
+        } else {
+            (raw_command, false)
         };
 
-        let raw_command = PathBuf::from(&command_str);
-        let (command, check_execute) =
-            if raw_command.components().count() > 1 || raw_command.is_absolute() {
-                (
-                    resolve_workspace_path(ctx, &command_str, ResolveMode::Existing)?,
-                    true,
-                )
-            } else {
-                (raw_command, false)
-            };
-
-        if check_execute {
-            ctx.check_access(&command, AccessMode::Execute)?;
-        }
+    if check_execute {
+        ctx.check_access(&command, AccessMode::Execute)?;
+    }
 
-        let mut argv = Vec::with_capacity(1 + command_args.len());
-        argv.push(command_str.clone());
-        argv.extend(command_args.iter().cloned());
-        ctx.authorize_command(argv).await?;
+    let mut argv = Vec::with_capacity(1 + command_args.len());
+    argv.push(command_str.clone());
+    argv.extend(command_args.iter().cloned());
+    if ctx.services.simulation {
+        info!("simulated command (args_len={})", command_args.len());
+        return Ok(json!({
+            "simulated": true,
+            "action": "exec",
+            "argv": argv,
+            "cwd": cwd.display().to_string(),
+        }));
+    }
+    ctx.authorize_command(argv).await?;
 
-        let mut spec = CommandSpec::new(command);
-        spec.args = command_args;
-        spec.cwd = Some(cwd);
-        spec.env = BTreeMap::new(); //TODO: Replace with actual env later
+    let mut spec = CommandSpec::new(command);
+    spec.args = command_args;
+    spec.cwd = Some(cwd);
+    spec.env = BTreeMap::new(); //TODO: Replace with actual env later
 
-        let sandbox = ctx.services.sandbox.as_ref().ok_or_else(|| {
+    let sandbox =
+        ctx.services.sandbox.as_ref().ok_or_else(|| {
             ToolError::ExecutionFailed("sandbox provider not configured".to_string())
         })?;
-        let result = if let (Some(turn_id), Some(sink)) = (
-            ctx.turn_id,
-            ctx.services.event_sink.as_ref().map(|sink| sink.as_ref()),
-        ) {
-            debug!("streaming command output");
-            let exec_id = Uuid::new_v4();
-            emit_exec_begin(ctx, sink, turn_id, exec_id, &command_str, &spec);
-            let mut output_sink = ExecOutputSink {
-                ctx,
-                sink,
-                turn_id,
-                exec_id,
-            };
-            let result = sandbox
-                .provider
-                .run_command_streaming(&sandbox.handle, spec, &mut output_sink)
-                .await
-                .map_err(|err| ToolError::ExecutionFailed(err.to_string()))?;
-            emit_exec_end(ctx, sink, turn_id, exec_id, result.status_code);
-            result
-        } else {
-            debug!("running command without streaming");
-            sandbox
-                .provider
-                .run_command(&sandbox.handle, spec)
-                .await
-                .map_err(|err| ToolError::ExecutionFailed(err.to_string()))?
+    let result = if let (Some(turn_id), Some(sink)) = (
+        ctx.turn_id,
+        ctx.services.event_sink.as_ref().map(|sink| sink.as_ref()),
+    ) {
+        debug!("streaming command output");
+        let exec_id = Uuid::new_v4();
+        emit_exec_begin(ctx, sink, turn_id, exec_id, &command_str, &spec);
+        let mut output_sink = ExecOutputSink {
+            ctx,
+            sink,
+            turn_id,
+            exec_id,
+            deltas,
         };
+        let result = sandbox
+            .provider
+            .run_command_streaming(&sandbox.handle, spec, &mut output_sink)
+            .await
+            .map_err(|err| ToolError::ExecutionFailed(err.to_string()))?;
+        emit_exec_end(ctx, sink, turn_id, exec_id, result.status_code);
+        result
+    } else {
+        debug!("running command without streaming");
+        sandbox
+            .provider
+            .run_command(&sandbox.handle, spec)
+            .await
+            .map_err(|err| ToolError::ExecutionFailed(err.to_string()))?
+    };
 
-        if result.status_code.unwrap_or(-1) != 0 {
-            warn!("command finished with non-zero status");
-        }
-        Ok(json!({
-            "status_code": result.status_code,
-            "stdout": result.stdout,
-            "stderr": result.stderr,
-        }))
+    if result.status_code.unwrap_or(-1) != 0 {
+        warn!("command finished with non-zero status");
     }
+    Ok(json!({
+        "status_code": result.status_code,
+        "stdout": result.stdout,
+        "stderr": result.stderr,
+    }))
+}
+
+/// Handle a bare `cd` by changing the session working directory.
+fn change_directory(ctx: &ToolContext, args: &[String], has_cwd: bool) -> Result<Value, ToolError> {
+    if has_cwd {
+        return Err(ToolError::InvalidArguments(
+            "cd cannot be combined with cwd".to_string(),
+        ));
+    }
+    let target = match args {
+        [] => None,
+        [path] => Some(path.as_str()),
+        _ => {
+            return Err(ToolError::InvalidArguments(
+                "cd takes a single directory".to_string(),
+            ));
+        }
+    };
+    let cwd = change_cwd(ctx, target)?;
+    Ok(json!({
+        "status_code": 0,
+        "stdout": "",
+        "stderr": "",
+        "cwd": cwd.display().to_string(),
+    }))
 }
 
 fn parse_bash_args(args: Value) -> Result<BashArgs, ToolError> {
@@ -164,11 +232,24 @@ struct ExecOutputSink<'a> {
     sink: &'a dyn crate::EventSink,
     turn_id: Uuid,
     exec_id: Uuid,
+    deltas: Option<&'a dyn ToolDeltaSink>,
+}
+
+impl ExecOutputSink<'_> {
+    /// Push a chunk as a tool call delta when streaming is requested.
+    fn push_delta(&self, stream: &str, chunk: &str) {
+        if let Some(deltas) = self.deltas
+            && !chunk.is_empty()
+        {
+            deltas.push(json!({ "stream": stream, "text": chunk }));
+        }
+    }
 }
 
 impl CommandOutputSink for ExecOutputSink<'_> {
     /// Emit stdout chunks as streaming events.
     fn stdout(&mut self, chunk: &str) {
+        self.push_delta("stdout", chunk);
         emit_exec_delta(
             self.ctx,
             self.sink,
@@ -181,6 +262,7 @@ impl CommandOutputSink for ExecOutputSink<'_> {
 
     /// Emit stderr chunks as streaming events.
     fn stderr(&mut self, chunk: &str) {
+        self.push_delta("stderr", chunk);
         emit_exec_delta(
             self.ctx,
             self.sink,
@@ -211,6 +293,8 @@ fn emit_exec_begin(
     let event = EventMsg {
         id: Uuid::new_v4(),
         session_id: ctx.session_id,
+        seq: 0,
+        user_id: None,
         created_at: Utc::now(),
         payload: EventPayload::ExecCommandBegin {
             turn_id,
@@ -237,6 +321,8 @@ fn emit_exec_delta(
     let event = EventMsg {
         id: Uuid::new_v4(),
         session_id: ctx.session_id,
+        seq: 0,
+        user_id: None,
         created_at: Utc::now(),
         payload: EventPayload::ExecCommandOutputDelta {
             turn_id,
@@ -259,6 +345,8 @@ fn emit_exec_end(
     let event = EventMsg {
         id: Uuid::new_v4(),
         session_id: ctx.session_id,
+        seq: 0,
+        user_id: None,
         created_at: Utc::now(),
         payload: EventPayload::ExecCommandEnd {
             turn_id,
@@ -273,8 +361,8 @@ fn emit_exec_end(
 mod tests {
     use super::{BashTool, parse_bash_args, parse_command_line};
     use crate::{
-        PermissionChecker, PermissionContext, PermissionOutcome, Tool, ToolContext, ToolSandbox,
-        TurnServices,
+        PermissionChecker, PermissionContext, PermissionOutcome, Tool, ToolContext,
+        ToolContextBuilder, ToolDeltaSink, ToolSandbox,
     };
     use async_trait::async_trait;
     use odyssey_rs_protocol::{EventMsg, PermissionRequest, ToolError};
@@ -364,33 +452,17 @@ mod tests {
         }
     }
 
-    fn base_services(root: &std::path::Path) -> TurnServices {
-        TurnServices {
-            cwd: root.to_path_buf(),
-            workspace_root: root.to_path_buf(),
-            output_policy: None,
-            sandbox: None,
-            web: None,
-            event_sink: None,
-            skill_provider: None,
-            question_handler: None,
-            permission_checker: Some(Arc::new(AllowAllPermissions)),
-            tool_result_handler: None,
-        }
+    fn base_builder(root: &std::path::Path) -> ToolContextBuilder {
+        ToolContext::builder()
+            .with_session_id(Uuid::new_v4())
+            .with_workspace_root(root)
+            .with_permission_checker(Arc::new(AllowAllPermissions))
     }
 
     #[tokio::test]
     async fn bash_tool_errors_without_sandbox() {
         let workspace = tempdir().expect("workspace");
-        let services = base_services(workspace.path());
-        let ctx = ToolContext {
-            session_id: Uuid::new_v4(),
-            agent_id: "agent".to_string(),
-            turn_id: None,
-            tool_call_id: None,
-            tool_name: None,
-            services: Arc::new(services),
-        };
+        let ctx = base_builder(workspace.path()).build();
         let tool = BashTool::default();
         let err = tool
             .call(&ctx, json!({ "command": "echo hello" }))
@@ -414,21 +486,15 @@ mod tests {
         let handle = provider.prepare(&sandbox_ctx).await.expect("prepare");
 
         let sink = Arc::new(RecordingSink::default());
-        let mut services = base_services(workspace.path());
-        services.sandbox = Some(ToolSandbox {
-            provider: Arc::new(provider),
-            handle,
-        });
-        services.event_sink = Some(sink.clone());
-
-        let ctx = ToolContext {
-            session_id: Uuid::new_v4(),
-            agent_id: "agent".to_string(),
-            turn_id: Some(Uuid::new_v4()),
-            tool_call_id: None,
-            tool_name: None,
-            services: Arc::new(services),
-        };
+        let ctx = base_builder(workspace.path())
+            .with_turn_id(Uuid::new_v4())
+            .with_sandbox(ToolSandbox {
+                provider: Arc::new(provider),
+                handle,
+                lease: None,
+            })
+            .with_event_sink(sink.clone())
+            .build();
 
         let tool = BashTool::default();
         let result = tool
@@ -440,4 +506,105 @@ mod tests {
         let events = sink.events.lock();
         assert_eq!(events.is_empty(), false);
     }
+
+    #[derive(Default)]
+    struct CollectingDeltas {
+        deltas: parking_lot::Mutex<Vec<serde_json::Value>>,
+    }
+
+    impl ToolDeltaSink for CollectingDeltas {
+        fn push(&self, delta: serde_json::Value) {
+            self.deltas.lock().push(delta);
+        }
+    }
+
+    #[tokio::test]
+    async fn bash_tool_streams_output_deltas() {
+        let workspace = tempdir().expect("workspace");
+        let provider = LocalSandboxProvider::new();
+        let sandbox_ctx = SandboxContext {
+            workspace_root: workspace.path().to_path_buf(),
+            mode: odyssey_rs_protocol::SandboxMode::WorkspaceWrite,
+            policy: SandboxPolicy::default(),
+        };
+        let handle = provider.prepare(&sandbox_ctx).await.expect("prepare");
+
+        let ctx = base_builder(workspace.path())
+            .with_turn_id(Uuid::new_v4())
+            .with_sandbox(ToolSandbox {
+                provider: Arc::new(provider),
+                handle,
+                lease: None,
+            })
+            .with_event_sink(Arc::new(RecordingSink::default()))
+            .build();
+
+        let deltas = CollectingDeltas::default();
+        let result = BashTool::default()
+            .call_streaming(&ctx, json!({ "command": "printf hello" }), &deltas)
+            .await
+            .expect("call");
+        assert_eq!(result["stdout"], "hello");
+
+        let text = deltas
+            .deltas
+            .lock()
+            .iter()
+            .filter(|delta| delta["stream"] == "stdout")
+            .filter_map(|delta| delta["text"].as_str().map(str::to_string))
+            .collect::<String>();
+        assert_eq!(text, "hello");
+    }
+
+    #[tokio::test]
+    async fn bash_tool_cd_updates_session_cwd() {
+        let workspace = tempdir().expect("workspace");
+        std::fs::create_dir(workspace.path().join("sub")).expect("mkdir");
+        let ctx = base_builder(workspace.path()).with_simulation(true).build();
+
+        let tool = BashTool::default();
+        let result = tool
+            .call(&ctx, json!({ "command": "cd sub" }))
+            .await
+            .expect("cd");
+        let sub = workspace.path().join("sub");
+        assert_eq!(result["cwd"], sub.display().to_string());
+        let result = tool
+            .call(&ctx, json!({ "command": "touch created.txt" }))
+            .await
+            .expect("simulated call");
+        assert_eq!(result["cwd"], sub.display().to_string());
+
+        let err = tool
+            .call(&ctx, json!({ "command": "cd ../.." }))
+            .await
+            .expect_err("escape");
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+        tool.call(&ctx, json!({ "command": "cd" }))
+            .await
+            .expect("cd root");
+        assert_eq!(ctx.cwd(), workspace.path().to_path_buf());
+    }
+
+    #[tokio::test]
+    async fn bash_tool_simulation_skips_execution() {
+        let workspace = tempdir().expect("workspace");
+        let ctx = base_builder(workspace.path()).with_simulation(true).build();
+
+        let tool = BashTool::default();
+        let result = tool
+            .call(&ctx, json!({ "command": "touch created.txt" }))
+            .await
+            .expect("simulated call");
+        assert_eq!(
+            result,
+            json!({
+                "simulated": true,
+                "action": "exec",
+                "argv": ["touch", "created.txt"],
+                "cwd": workspace.path().display().to_string(),
+            })
+        );
+        assert_eq!(workspace.path().join("created.txt").exists(), false);
+    }
 }
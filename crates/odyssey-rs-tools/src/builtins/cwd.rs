@@ -0,0 +1,162 @@
+//! Built-in tool for changing the session working directory.
+
+use crate::builtins::utils::{ResolveMode, parse_args, relative_display, resolve_workspace_path};
+use crate::{Tool, ToolContext};
+use async_trait::async_trait;
+use autoagents_core::tool::ToolInputT;
+use autoagents_derive::ToolInput;
+use odyssey_rs_protocol::{ToolError, ToolRisk};
+use odyssey_rs_sandbox::AccessMode;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+/// Tool that sets the working directory used by later tool calls.
+#[derive(Debug, Default)]
+pub struct SetCwdTool;
+
+/// Arguments for SetCwdTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+#[serde(deny_unknown_fields)]
+struct SetCwdArgs {
+    #[input(
+        description = "Directory relative to the current working directory. Omit to return to the workspace root."
+    )]
+    #[serde(default)]
+    path: Option<String>,
+}
+
+#[async_trait]
+impl Tool for SetCwdTool {
+    fn name(&self) -> &str {
+        "SetCwd"
+    }
+
+    fn description(&self) -> &str {
+        "Change the working directory used to resolve relative paths in later tool calls"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = SetCwdArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let input: SetCwdArgs = parse_args(args)?;
+        let cwd = change_cwd(ctx, input.path.as_deref())?;
+        Ok(json!({
+            "cwd": cwd.display().to_string(),
+            "relative": relative_display(&ctx.services.workspace_root, &cwd),
+        }))
+    }
+}
+
+/// Validate a target directory and make it the session cwd.
+///
+/// `None` returns to the workspace root. Shared with the Bash tool so `cd`
+/// behaves the same as `SetCwd`.
+pub(super) fn change_cwd(ctx: &ToolContext, path: Option<&str>) -> Result<PathBuf, ToolError> {
+    let target = match path {
+        Some(path) => resolve_workspace_path(ctx, path, ResolveMode::Existing)?,
+        None => ctx.services.workspace_root.clone(),
+    };
+    if !target.is_dir() {
+        return Err(ToolError::InvalidArguments(format!(
+            "not a directory: {}",
+            relative_display(&ctx.services.workspace_root, &target)
+        )));
+    }
+    ctx.check_access(&target, AccessMode::Read)?;
+    ctx.set_cwd(target.clone());
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SetCwdTool;
+    use crate::{Tool, ToolContext};
+    use odyssey_rs_protocol::{EventMsg, EventPayload, ToolError};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::path::Path;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+    use uuid::Uuid;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        events: parking_lot::Mutex<Vec<EventMsg>>,
+    }
+
+    impl odyssey_rs_protocol::EventSink for RecordingSink {
+        fn emit(&self, event: EventMsg) {
+            self.events.lock().push(event);
+        }
+    }
+
+    fn context(root: &Path, sink: Arc<RecordingSink>) -> ToolContext {
+        ToolContext::builder()
+            .with_session_id(Uuid::new_v4())
+            .with_turn_id(Uuid::new_v4())
+            .with_workspace_root(root)
+            .with_event_sink(sink)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn set_cwd_updates_context_and_emits_event() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::create_dir_all(temp.path().join("src/nested")).expect("mkdir");
+        let sink = Arc::new(RecordingSink::default());
+        let ctx = context(temp.path(), sink.clone());
+
+        let result = SetCwdTool
+            .call(&ctx, json!({ "path": "src" }))
+            .await
+            .expect("cd src");
+        assert_eq!(result["relative"], "src");
+        SetCwdTool
+            .call(&ctx, json!({ "path": "nested" }))
+            .await
+            .expect("cd nested");
+        assert_eq!(ctx.cwd(), temp.path().join("src/nested"));
+
+        SetCwdTool.call(&ctx, json!({})).await.expect("cd root");
+        assert_eq!(ctx.cwd(), temp.path().to_path_buf());
+
+        let changes = sink
+            .events
+            .lock()
+            .iter()
+            .filter_map(|event| match &event.payload {
+                EventPayload::CwdChanged { cwd, .. } => Some(cwd.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[2], temp.path().display().to_string());
+    }
+
+    #[tokio::test]
+    async fn set_cwd_rejects_files_and_escapes() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("file.txt"), "data").expect("write");
+        let ctx = context(temp.path(), Arc::new(RecordingSink::default()));
+
+        let err = SetCwdTool
+            .call(&ctx, json!({ "path": "file.txt" }))
+            .await
+            .expect_err("file");
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+        let err = SetCwdTool
+            .call(&ctx, json!({ "path": ".." }))
+            .await
+            .expect_err("escape");
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+        assert_eq!(ctx.cwd(), temp.path().to_path_buf());
+    }
+}
@@ -0,0 +1,619 @@
+//! Built-in tool for downloading files into the workspace.
+
+use crate::builtins::filesystem::WORKSPACE_WRITE_GROUP;
+use crate::builtins::utils::{
+    ContentHasher, ResolveMode, parse_args, relative_display, resolve_workspace_path,
+};
+use crate::builtins::web::url_host;
+use crate::{Tool, ToolContext};
+use async_trait::async_trait;
+use autoagents_core::tool::ToolInputT;
+use autoagents_derive::ToolInput;
+use log::{info, warn};
+use odyssey_rs_protocol::{ArtifactPublish, PathAccess, ToolError, ToolRisk};
+use odyssey_rs_sandbox::AccessMode;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Default cap on downloaded bytes (100 MiB).
+const DEFAULT_MAX_DOWNLOAD_BYTES: u64 = 100 * 1024 * 1024;
+/// Largest `max_bytes` a caller may ask for (4 GiB).
+const MAX_DOWNLOAD_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+/// Largest download also published as an artifact, which holds its content in memory (16 MiB).
+const MAX_ARTIFACT_BYTES: u64 = 16 * 1024 * 1024;
+/// Redirects followed before giving up.
+const MAX_REDIRECTS: usize = 5;
+
+/// Tool for downloading a URL to a workspace file.
+#[derive(Debug, Default)]
+pub struct DownloadTool;
+
+#[async_trait]
+impl Tool for DownloadTool {
+    fn name(&self) -> &str {
+        "Download"
+    }
+
+    fn description(&self) -> &str {
+        "Download a URL to a workspace file with a size cap and optional sha256 verification, and record it as an artifact"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = DownloadArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn exclusion_group(&self) -> Option<&str> {
+        Some(WORKSPACE_WRITE_GROUP)
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ExternalNetwork
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let input: DownloadArgs = parse_args(args)?;
+        let expected_sha256 = input
+            .expected_sha256
+            .as_deref()
+            .map(parse_sha256)
+            .transpose()?;
+        let max_bytes = input.max_bytes.unwrap_or(DEFAULT_MAX_DOWNLOAD_BYTES);
+        if max_bytes == 0 || max_bytes > MAX_DOWNLOAD_BYTES {
+            return Err(ToolError::InvalidArguments(format!(
+                "max_bytes must be between 1 and {MAX_DOWNLOAD_BYTES}"
+            )));
+        }
+        let path = resolve_workspace_path(ctx, &input.path, ResolveMode::AllowMissing)?;
+        if !ctx.services.simulation {
+            ctx.authorize_path(&path, PathAccess::Write).await?;
+        }
+        ctx.check_access(&path, AccessMode::Write)?;
+        if path.is_dir() {
+            return Err(ToolError::ExecutionFailed(
+                "path is a directory".to_string(),
+            ));
+        }
+        let existed = path.exists();
+        if existed && !input.overwrite {
+            return Err(ToolError::Conflict(
+                "file exists; set overwrite to true to replace".to_string(),
+            ));
+        }
+        let host = url_host(&input.url)?;
+        ctx.check_network(&host)?;
+        let display_path = relative_display(&ctx.services.workspace_root, &path);
+
+        if ctx.services.simulation {
+            info!("simulated download (max_bytes={max_bytes}, overwrite={existed})");
+            return Ok(json!({
+                "simulated": true,
+                "action": "download",
+                "url": input.url,
+                "path": display_path,
+                "max_bytes": max_bytes,
+                "overwrite": existed,
+            }));
+        }
+
+        let provider =
+            ctx.services.web.as_ref().ok_or_else(|| {
+                ToolError::ExecutionFailed("web provider not configured".to_string())
+            })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| {
+                ToolError::ExecutionFailed(format!("failed to create directories: {err}"))
+            })?;
+        }
+        let mut sink = DownloadSink::create(&path, max_bytes)?;
+        let mut url = input.url.clone();
+        let mut redirects = 0;
+        info!("downloading (url_len={}, max_bytes={max_bytes})", url.len());
+        let response = loop {
+            let response = provider
+                .download(&url, &mut |chunk| sink.write(chunk))
+                .await?;
+            if !(300..400).contains(&response.status) {
+                break response;
+            }
+            let Some(location) = response.location else {
+                break response;
+            };
+            if redirects == MAX_REDIRECTS {
+                return Err(ToolError::ExecutionFailed(format!(
+                    "too many redirects (max {MAX_REDIRECTS})"
+                )));
+            }
+            redirects += 1;
+            url = resolve_location(&url, &location)?;
+            ctx.check_network(&url_host(&url)?)?;
+            sink.reset()?;
+        };
+        if !(200..300).contains(&response.status) {
+            return Err(ToolError::ExecutionFailed(format!(
+                "download failed with status {}",
+                response.status
+            )));
+        }
+        // Only moving the finished file into place excludes other writes.
+        let _exclusive = ctx.exclusive(self).await;
+        let Downloaded {
+            size,
+            sha256,
+            content_hash,
+        } = sink.finish(&path, expected_sha256.as_deref())?;
+        ctx.services
+            .file_versions
+            .record(path.clone(), content_hash);
+        info!("downloaded file (bytes={size}, redirects={redirects})");
+
+        let artifact = match ctx.services.artifact_provider.as_ref() {
+            Some(_) if size > MAX_ARTIFACT_BYTES => {
+                warn!(
+                    "download too large to publish as an artifact (bytes={size}, max={MAX_ARTIFACT_BYTES})"
+                );
+                None
+            }
+            Some(artifacts) => {
+                let content = fs::read(&path).map_err(|err| {
+                    ToolError::ExecutionFailed(format!("failed to read downloaded file: {err}"))
+                })?;
+                let name = input.artifact.unwrap_or_else(|| file_name(&path));
+                let summary = artifacts.publish(ArtifactPublish {
+                    name,
+                    content,
+                    description: Some(format!("Downloaded from {}", input.url)),
+                    media_type: response.content_type.clone(),
+                    session_id: Some(ctx.session_id),
+                    metadata: json!({
+                        "agent_id": ctx.agent_id,
+                        "turn_id": ctx.turn_id,
+                        "url": input.url,
+                        "path": display_path,
+                        "sha256": sha256,
+                    }),
+                })?;
+                Some(summary.reference())
+            }
+            None => {
+                if input.artifact.is_some() {
+                    warn!("artifacts are not enabled; download not recorded");
+                }
+                None
+            }
+        };
+
+        Ok(json!({
+            "url": input.url,
+            "final_url": url,
+            "path": display_path,
+            "size": size,
+            "sha256": sha256,
+            "content_type": response.content_type,
+            "overwritten": existed,
+            "artifact": artifact,
+        }))
+    }
+}
+
+/// Size and digests of a finished download.
+struct Downloaded {
+    size: u64,
+    sha256: String,
+    /// Hash recorded in `file_versions`, as Read would report it.
+    content_hash: String,
+}
+
+/// Temp file next to the target that receives the body while it is hashed.
+///
+/// The temp file is removed on drop unless `finish` moved it into place.
+struct DownloadSink {
+    temp_path: PathBuf,
+    file: File,
+    hasher: Sha256,
+    content_hasher: ContentHasher,
+    size: u64,
+    max_bytes: u64,
+    persisted: bool,
+}
+
+impl DownloadSink {
+    fn create(target: &Path, max_bytes: u64) -> Result<Self, ToolError> {
+        let temp_path = target.with_file_name(format!(
+            ".{}.download-{}",
+            file_name(target),
+            Uuid::new_v4().simple()
+        ));
+        let file = File::create_new(&temp_path).map_err(|err| {
+            ToolError::ExecutionFailed(format!("failed to create download file: {err}"))
+        })?;
+        Ok(Self {
+            temp_path,
+            file,
+            hasher: Sha256::new(),
+            content_hasher: ContentHasher::default(),
+            size: 0,
+            max_bytes,
+            persisted: false,
+        })
+    }
+
+    fn write(&mut self, chunk: &[u8]) -> Result<(), ToolError> {
+        self.size += chunk.len() as u64;
+        if self.size > self.max_bytes {
+            return Err(ToolError::ExecutionFailed(format!(
+                "download exceeds max_bytes ({})",
+                self.max_bytes
+            )));
+        }
+        self.hasher.update(chunk);
+        self.content_hasher.update(chunk);
+        self.file.write_all(chunk).map_err(|err| {
+            ToolError::ExecutionFailed(format!("failed to write download file: {err}"))
+        })
+    }
+
+    /// Discard a redirect response body before the next hop.
+    fn reset(&mut self) -> Result<(), ToolError> {
+        self.file
+            .set_len(0)
+            .and_then(|()| self.file.rewind())
+            .map_err(|err| {
+                ToolError::ExecutionFailed(format!("failed to reset download file: {err}"))
+            })?;
+        self.hasher = Sha256::new();
+        self.content_hasher = ContentHasher::default();
+        self.size = 0;
+        Ok(())
+    }
+
+    /// Verify the digest and move the download onto `target`.
+    fn finish(
+        mut self,
+        target: &Path,
+        expected_sha256: Option<&str>,
+    ) -> Result<Downloaded, ToolError> {
+        let sha256 = format!("{:x}", std::mem::take(&mut self.hasher).finalize());
+        if let Some(expected) = expected_sha256
+            && expected != sha256
+        {
+            return Err(ToolError::ExecutionFailed(format!(
+                "sha256 mismatch: expected {expected}, got {sha256}"
+            )));
+        }
+        self.file.sync_all().map_err(|err| {
+            ToolError::ExecutionFailed(format!("failed to flush download file: {err}"))
+        })?;
+        fs::rename(&self.temp_path, target).map_err(|err| {
+            ToolError::ExecutionFailed(format!("failed to move download into place: {err}"))
+        })?;
+        self.persisted = true;
+        Ok(Downloaded {
+            size: self.size,
+            sha256,
+            content_hash: self.content_hasher.finish(),
+        })
+    }
+}
+
+impl Drop for DownloadSink {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// Normalize an expected sha256 digest to lowercase hex.
+fn parse_sha256(value: &str) -> Result<String, ToolError> {
+    let value = value.trim();
+    let value = value.strip_prefix("sha256:").unwrap_or(value);
+    if value.len() != 64 || !value.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(ToolError::InvalidArguments(
+            "expected_sha256 must be 64 hex characters".to_string(),
+        ));
+    }
+    Ok(value.to_ascii_lowercase())
+}
+
+/// Resolve a redirect `Location` against the URL that returned it.
+fn resolve_location(base: &str, location: &str) -> Result<String, ToolError> {
+    let location = location.trim();
+    if location.contains("://") {
+        return Ok(location.to_string());
+    }
+    let (scheme, rest) = base
+        .split_once("://")
+        .ok_or_else(|| ToolError::ExecutionFailed(format!("invalid url: {base}")))?;
+    if let Some(network_path) = location.strip_prefix("//") {
+        return Ok(format!("{scheme}://{network_path}"));
+    }
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(authority_end);
+    if location.starts_with('/') {
+        return Ok(format!("{scheme}://{authority}{location}"));
+    }
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let directory = path.rsplit_once('/').map_or("", |(directory, _)| directory);
+    Ok(format!("{scheme}://{authority}{directory}/{location}"))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "download".to_string())
+}
+
+/// Arguments for DownloadTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+#[serde(deny_unknown_fields)]
+struct DownloadArgs {
+    #[input(description = "http or https URL to download.")]
+    url: String,
+    #[input(description = "Workspace path to write the file to.")]
+    path: String,
+    #[input(description = "Expected sha256 digest (hex); the download is discarded on mismatch.")]
+    #[serde(default)]
+    expected_sha256: Option<String>,
+    #[input(description = "Maximum bytes to download (defaults to 100 MiB, at most 4 GiB).")]
+    #[serde(default)]
+    max_bytes: Option<u64>,
+    #[input(description = "Replace the file if it already exists.")]
+    #[serde(default)]
+    overwrite: bool,
+    #[input(
+        description = "Artifact name to record the download under (defaults to the file name)."
+    )]
+    #[serde(default)]
+    artifact: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DownloadTool, resolve_location};
+    use crate::builtins::utils::content_hash;
+    use crate::{
+        DownloadResponse, Tool, ToolContext, WebFetchResult, WebProvider, WebSearchResult,
+    };
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use odyssey_rs_protocol::{ArtifactProvider, ArtifactPublish, ArtifactSummary, ToolError};
+    use parking_lot::Mutex;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+    use uuid::Uuid;
+
+    /// Serves `/latest` as a redirect to `/tool.tar.gz`, which streams in two chunks.
+    #[derive(Default)]
+    struct RedirectingProvider {
+        requested: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl WebProvider for RedirectingProvider {
+        async fn search(
+            &self,
+            _query: &str,
+            _limit: usize,
+        ) -> Result<Vec<WebSearchResult>, ToolError> {
+            Ok(Vec::new())
+        }
+
+        async fn fetch(&self, _url: &str, _max_bytes: usize) -> Result<WebFetchResult, ToolError> {
+            Err(ToolError::ExecutionFailed("unused".to_string()))
+        }
+
+        async fn download(
+            &self,
+            url: &str,
+            on_chunk: &mut (dyn FnMut(&[u8]) -> Result<(), ToolError> + Send),
+        ) -> Result<DownloadResponse, ToolError> {
+            self.requested.lock().push(url.to_string());
+            if url.ends_with("/latest") {
+                on_chunk(b"moved")?;
+                return Ok(DownloadResponse {
+                    status: 302,
+                    content_type: None,
+                    location: Some("/tool.tar.gz".to_string()),
+                });
+            }
+            on_chunk(b"hello ")?;
+            on_chunk(b"world")?;
+            Ok(DownloadResponse {
+                status: 200,
+                content_type: Some("application/gzip".to_string()),
+                location: None,
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingArtifacts {
+        published: Mutex<Vec<ArtifactPublish>>,
+    }
+
+    impl ArtifactProvider for RecordingArtifacts {
+        fn list(&self) -> Result<Vec<ArtifactSummary>, ToolError> {
+            Ok(Vec::new())
+        }
+
+        fn publish(&self, request: ArtifactPublish) -> Result<ArtifactSummary, ToolError> {
+            let summary = ArtifactSummary {
+                id: Uuid::new_v4(),
+                name: request.name.clone(),
+                description: request.description.clone(),
+                media_type: request.media_type.clone(),
+                size: request.content.len() as u64,
+                session_id: request.session_id,
+                created_at: Utc::now(),
+                metadata: request.metadata.clone(),
+            };
+            self.published.lock().push(request);
+            Ok(summary)
+        }
+
+        fn get(&self, name: &str) -> Result<(ArtifactSummary, Vec<u8>), ToolError> {
+            Err(ToolError::NotFound(name.to_string()))
+        }
+    }
+
+    fn context(
+        root: &std::path::Path,
+        web: Arc<RedirectingProvider>,
+        artifacts: Option<Arc<RecordingArtifacts>>,
+    ) -> ToolContext {
+        let builder = ToolContext::builder()
+            .with_workspace_root(root)
+            .with_web(web);
+        match artifacts {
+            Some(artifacts) => builder.with_artifact_provider(artifacts).build(),
+            None => builder.build(),
+        }
+    }
+
+    /// sha256 of "hello world".
+    const HELLO_SHA256: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+    #[tokio::test]
+    async fn download_follows_redirects_verifies_and_records_artifact() {
+        let temp = tempdir().expect("tempdir");
+        let web = Arc::new(RedirectingProvider::default());
+        let artifacts = Arc::new(RecordingArtifacts::default());
+        let ctx = context(temp.path(), web.clone(), Some(artifacts.clone()));
+
+        let result = DownloadTool
+            .call(
+                &ctx,
+                json!({
+                    "url": "https://example.com/releases/latest",
+                    "path": "vendor/tool.tar.gz",
+                    "expected_sha256": HELLO_SHA256.to_ascii_uppercase(),
+                }),
+            )
+            .await
+            .expect("download");
+
+        assert_eq!(result["final_url"], "https://example.com/tool.tar.gz");
+        assert_eq!(result["size"], 11);
+        assert_eq!(result["sha256"], HELLO_SHA256);
+        assert_eq!(result["artifact"], "artifact://tool.tar.gz");
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("vendor/tool.tar.gz")).expect("read"),
+            "hello world"
+        );
+        assert_eq!(web.requested.lock().len(), 2);
+        let published = artifacts.published.lock();
+        assert_eq!(published[0].content, b"hello world".to_vec());
+        assert_eq!(published[0].metadata["sha256"], HELLO_SHA256);
+        assert_eq!(
+            ctx.services
+                .file_versions
+                .get(&temp.path().join("vendor/tool.tar.gz")),
+            Some(content_hash(b"hello world"))
+        );
+    }
+
+    #[tokio::test]
+    async fn download_discards_mismatched_or_oversized_files() {
+        let temp = tempdir().expect("tempdir");
+        let ctx = context(temp.path(), Arc::new(RedirectingProvider::default()), None);
+
+        let err = DownloadTool
+            .call(
+                &ctx,
+                json!({
+                    "url": "https://example.com/tool.tar.gz",
+                    "path": "tool.tar.gz",
+                    "expected_sha256": "0".repeat(64),
+                }),
+            )
+            .await
+            .expect_err("checksum mismatch");
+        let ToolError::ExecutionFailed(message) = err else {
+            panic!("expected execution failed");
+        };
+        assert_eq!(message.starts_with("sha256 mismatch"), true);
+
+        let err = DownloadTool
+            .call(
+                &ctx,
+                json!({
+                    "url": "https://example.com/tool.tar.gz",
+                    "path": "tool.tar.gz",
+                    "max_bytes": 8,
+                }),
+            )
+            .await
+            .expect_err("too large");
+        let ToolError::ExecutionFailed(message) = err else {
+            panic!("expected execution failed");
+        };
+        assert_eq!(message, "download exceeds max_bytes (8)");
+        assert_eq!(std::fs::read_dir(temp.path()).expect("read dir").count(), 0);
+
+        let err = DownloadTool
+            .call(
+                &ctx,
+                json!({
+                    "url": "https://example.com/tool.tar.gz",
+                    "path": "tool.tar.gz",
+                    "max_bytes": super::MAX_DOWNLOAD_BYTES + 1,
+                }),
+            )
+            .await
+            .expect_err("max_bytes over the cap");
+        assert_eq!(matches!(err, ToolError::InvalidArguments(_)), true);
+    }
+
+    #[tokio::test]
+    async fn download_refuses_to_overwrite_without_flag() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("tool.tar.gz"), "old").expect("write");
+        let ctx = context(temp.path(), Arc::new(RedirectingProvider::default()), None);
+
+        let err = DownloadTool
+            .call(
+                &ctx,
+                json!({ "url": "https://example.com/tool.tar.gz", "path": "tool.tar.gz" }),
+            )
+            .await
+            .expect_err("exists");
+        assert_eq!(matches!(err, ToolError::Conflict(_)), true);
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("tool.tar.gz")).expect("read"),
+            "old"
+        );
+    }
+
+    #[test]
+    fn resolve_location_handles_relative_targets() {
+        let base = "https://example.com/a/b?x=1";
+        assert_eq!(
+            resolve_location(base, "https://cdn.test/f").expect("absolute"),
+            "https://cdn.test/f"
+        );
+        assert_eq!(
+            resolve_location(base, "//cdn.test/f").expect("network path"),
+            "https://cdn.test/f"
+        );
+        assert_eq!(
+            resolve_location(base, "/f").expect("absolute path"),
+            "https://example.com/f"
+        );
+        assert_eq!(
+            resolve_location(base, "c").expect("relative path"),
+            "https://example.com/a/c"
+        );
+    }
+}
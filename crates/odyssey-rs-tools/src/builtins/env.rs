@@ -0,0 +1,129 @@
+//! Built-in tool reporting the host environment.
+
+use crate::builtins::utils::parse_args;
+use crate::environment::EnvironmentReport;
+use crate::{Tool, ToolContext};
+use async_trait::async_trait;
+use autoagents_core::tool::ToolInputT;
+use autoagents_derive::ToolInput;
+use log::info;
+use odyssey_rs_protocol::{ToolError, ToolRisk};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Tool that reports the OS, hardware, installed toolchains, and sandbox dependencies.
+#[derive(Debug, Default)]
+pub struct EnvInfoTool;
+
+#[async_trait]
+impl Tool for EnvInfoTool {
+    fn name(&self) -> &str {
+        "EnvInfo"
+    }
+
+    fn description(&self) -> &str {
+        "Report the OS, architecture, CPU and memory, installed interpreters/compilers with versions, and sandbox dependency status"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = EnvInfoArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn supports_parallel(&self) -> bool {
+        true
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let input: EnvInfoArgs = parse_args(args)?;
+        let sandbox = ctx.services.sandbox.as_ref();
+        let mut report =
+            EnvironmentReport::probe(sandbox.map(|sandbox| sandbox.provider.as_ref())).await;
+        if !input.toolchains.is_empty() {
+            report
+                .toolchains
+                .retain(|toolchain| input.toolchains.contains(&toolchain.name));
+            report
+                .missing
+                .retain(|name| input.toolchains.contains(name));
+        }
+        info!(
+            "reported environment (toolchains={})",
+            report.toolchains.len()
+        );
+        let mut result = json!(report);
+        if let Some(map) = result.as_object_mut() {
+            map.insert("sandbox_enabled".to_string(), json!(sandbox.is_some()));
+            map.insert(
+                "workspace_root".to_string(),
+                json!(ctx.services.workspace_root.display().to_string()),
+            );
+            map.insert("cwd".to_string(), json!(ctx.cwd().display().to_string()));
+        }
+        Ok(result)
+    }
+}
+
+/// Arguments for EnvInfoTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+#[serde(deny_unknown_fields)]
+struct EnvInfoArgs {
+    #[input(
+        description = "Only report these toolchains (e.g. [\"python3\", \"node\"]). Omit to report all probed ones."
+    )]
+    #[serde(default)]
+    toolchains: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EnvInfoTool;
+    use crate::{Tool, ToolContext, ToolSandbox};
+    use odyssey_rs_sandbox::{
+        LocalSandboxProvider, SandboxContext, SandboxPolicy, SandboxProvider,
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn env_info_reports_host_and_sandbox() {
+        let temp = tempdir().expect("tempdir");
+        let provider = LocalSandboxProvider::new();
+        let handle = provider
+            .prepare(&SandboxContext {
+                workspace_root: temp.path().to_path_buf(),
+                mode: odyssey_rs_protocol::SandboxMode::WorkspaceWrite,
+                policy: SandboxPolicy::default(),
+            })
+            .await
+            .expect("prepare");
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .with_sandbox(ToolSandbox {
+                provider: Arc::new(provider),
+                handle,
+                lease: None,
+            })
+            .build();
+
+        let result = EnvInfoTool
+            .call(&ctx, json!({ "toolchains": ["git", "no-such-toolchain"] }))
+            .await
+            .expect("env info");
+
+        assert_eq!(result["os"], std::env::consts::OS);
+        assert_eq!(result["arch"], std::env::consts::ARCH);
+        assert_eq!(result["cpus"].as_u64().unwrap_or_default() >= 1, true);
+        assert_eq!(result["sandbox_enabled"], true);
+        assert_eq!(result["sandbox"], json!({ "errors": [], "warnings": [] }));
+        let reported = result["toolchains"].as_array().expect("toolchains").len()
+            + result["missing"].as_array().expect("missing").len();
+        assert_eq!(reported, 1);
+    }
+}
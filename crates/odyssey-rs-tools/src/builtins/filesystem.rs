@@ -1,26 +1,35 @@
 //! Built-in filesystem tools (read/write/edit/glob/grep).
 
-use crate::builtins::utils::{ResolveMode, parse_args, relative_display, resolve_workspace_path};
-use crate::{Tool, ToolContext};
+use crate::builtins::media::{SNIFF_BYTES, binary_mime, image_attachment, is_inline_image};
+use crate::builtins::utils::{
+    ContentHasher, ResolveMode, content_hash, ensure_unchanged, io_error, parse_args,
+    relative_display, resolve_workspace_path, unified_diff,
+};
+use crate::builtins::walk::walk_files;
+use crate::{Tool, ToolContext, ToolDeltaSink};
 use async_trait::async_trait;
 use autoagents_core::tool::ToolInputT;
 use autoagents_derive::ToolInput;
 use log::{debug, info};
 use odyssey_rs_protocol::PathAccess;
-use odyssey_rs_protocol::ToolError;
+use odyssey_rs_protocol::{ToolError, ToolRisk};
 use odyssey_rs_sandbox::AccessMode;
 use regex::RegexBuilder;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::collections::VecDeque;
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
-use walkdir::WalkDir;
 
 /// Default maximum number of bytes to read from a file.
 const DEFAULT_MAX_READ_BYTES: usize = 200_000;
+/// Buffer size for streaming file reads.
+const READ_CHUNK_BYTES: usize = 64 * 1024;
 /// Default maximum number of results for glob/grep.
 const DEFAULT_MAX_RESULTS: usize = 200;
+/// Exclusion group shared by tools that modify workspace files.
+pub(super) const WORKSPACE_WRITE_GROUP: &str = "workspace-write";
 
 /// Tool for reading workspace files.
 #[derive(Debug, Default)]
@@ -33,7 +42,7 @@ impl Tool for ReadTool {
     }
 
     fn description(&self) -> &str {
-        "Read a text file from the workspace"
+        "Read a text file from the workspace, up to max_bytes or max_lines; binary files return their type and size instead"
     }
 
     fn args_schema(&self) -> Value {
@@ -45,6 +54,10 @@ impl Tool for ReadTool {
         true
     }
 
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
+
     async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
         let input: ReadArgs = parse_args(args)?;
         let path = resolve_workspace_path(ctx, &input.path, ResolveMode::Existing)?;
@@ -52,7 +65,7 @@ impl Tool for ReadTool {
         ctx.check_access(&path, AccessMode::Read)?;
 
         let metadata = fs::metadata(&path).map_err(|err| {
-            ToolError::ExecutionFailed(format!("failed to read metadata for {path:?}: {err}"))
+            io_error(&err, format!("failed to read metadata for {path:?}: {err}"))
         })?;
         if metadata.is_dir() {
             return Err(ToolError::ExecutionFailed(
@@ -60,8 +73,47 @@ impl Tool for ReadTool {
             ));
         }
 
-        let bytes = fs::read(&path)
-            .map_err(|err| ToolError::ExecutionFailed(format!("failed to read {path:?}: {err}")))?;
+        let read_error =
+            |err: std::io::Error| io_error(&err, format!("failed to read {path:?}: {err}"));
+        let mut file = File::open(&path).map_err(read_error)?;
+        let mut head = Vec::with_capacity(SNIFF_BYTES);
+        file.by_ref()
+            .take(SNIFF_BYTES as u64)
+            .read_to_end(&mut head)
+            .map_err(read_error)?;
+        let display_path = relative_display(&ctx.services.workspace_root, &path);
+        if let Some(mime) = binary_mime(&head) {
+            let size = metadata.len();
+            let max_image_bytes = ctx
+                .services
+                .output_policy
+                .as_ref()
+                .and_then(|policy| policy.max_image_bytes);
+            let inline = is_inline_image(mime)
+                && max_image_bytes.is_some_and(|max_bytes| size <= max_bytes as u64);
+            info!(
+                "read binary file (mime={}, size={}, inline={})",
+                mime, size, inline
+            );
+            let mut result = json!({
+                "path": display_path,
+                "binary": true,
+                "mime_type": mime,
+                "size": size,
+            });
+            if inline {
+                let mut bytes = head;
+                file.read_to_end(&mut bytes).map_err(read_error)?;
+                let hash = content_hash(&bytes);
+                ctx.services
+                    .file_versions
+                    .record(path.clone(), hash.clone());
+                result["hash"] = json!(hash);
+                result["image"] = image_attachment(mime, &bytes);
+            }
+            return Ok(result);
+        }
+
         let max_bytes = input.max_bytes.unwrap_or_else(|| {
             ctx.services
                 .output_policy
@@ -69,28 +121,105 @@ impl Tool for ReadTool {
                 .map(|policy| policy.max_string_bytes)
                 .unwrap_or(DEFAULT_MAX_READ_BYTES)
         });
-        let truncated = bytes.len() > max_bytes;
-        let slice = if truncated {
-            &bytes[..max_bytes]
-        } else {
-            &bytes
-        };
-        let content = String::from_utf8_lossy(slice).to_string();
+        let mut budget = ReadBudget::new(max_bytes, input.max_lines);
+        let mut hasher = ContentHasher::default();
+        let mut filled = head.len();
+        let mut chunk = head;
+        chunk.resize(READ_CHUNK_BYTES, 0);
+        // Stop at the budget; one chunk past it tells whether anything was left.
+        loop {
+            let kept = budget.content.len();
+            budget.push(&chunk[..filled]);
+            hasher.update(&budget.content[kept..]);
+            if budget.truncated {
+                break;
+            }
+            filled = file.read(&mut chunk).map_err(read_error)?;
+            if filled == 0 {
+                break;
+            }
+        }
+        // A truncated read only vouches for the prefix, so edits need a full read first.
+        let hash = (!budget.truncated).then(|| hasher.finish());
+        match &hash {
+            Some(hash) => ctx
+                .services
+                .file_versions
+                .record(path.clone(), hash.clone()),
+            None => ctx.services.file_versions.record_partial(
+                path.clone(),
+                budget.content.len(),
+                hasher.finish(),
+            ),
+        }
+        let content = String::from_utf8_lossy(&budget.content).to_string();
         info!(
             "read file (bytes_read={}, truncated={})",
-            slice.len(),
-            truncated
+            budget.content.len(),
+            budget.truncated
         );
 
         Ok(json!({
-            "path": relative_display(&ctx.services.workspace_root, &path),
+            "path": display_path,
             "content": content,
-            "truncated": truncated,
-            "bytes_read": slice.len(),
+            "truncated": budget.truncated,
+            "bytes_read": budget.content.len(),
+            "hash": hash,
         }))
     }
 }
 
+/// Leading bytes and lines of a file that fit the Read budget.
+struct ReadBudget {
+    max_bytes: usize,
+    max_lines: Option<usize>,
+    lines: usize,
+    content: Vec<u8>,
+    /// Content past the budget was dropped.
+    truncated: bool,
+}
+
+impl ReadBudget {
+    fn new(max_bytes: usize, max_lines: Option<usize>) -> Self {
+        Self {
+            max_bytes,
+            max_lines,
+            lines: 0,
+            content: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    /// Keep as much of the next chunk as the budget allows.
+    fn push(&mut self, chunk: &[u8]) {
+        if chunk.is_empty() {
+            return;
+        }
+        let full = self.content.len() >= self.max_bytes
+            || self.max_lines.is_some_and(|max| self.lines >= max);
+        if full {
+            self.truncated = true;
+            return;
+        }
+        let mut end = chunk.len().min(self.max_bytes - self.content.len());
+        if let Some(max_lines) = self.max_lines {
+            for (pos, _) in chunk[..end]
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| **b == b'\n')
+            {
+                self.lines += 1;
+                if self.lines == max_lines {
+                    end = pos + 1;
+                    break;
+                }
+            }
+        }
+        self.content.extend_from_slice(&chunk[..end]);
+        self.truncated = end < chunk.len();
+    }
+}
+
 /// Tool for writing or overwriting workspace files.
 #[derive(Debug, Default)]
 pub struct WriteTool;
@@ -110,15 +239,30 @@ impl Tool for WriteTool {
         serde_json::from_str(params_str).expect("Error parsing tool parameters")
     }
 
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn exclusion_group(&self) -> Option<&str> {
+        Some(WORKSPACE_WRITE_GROUP)
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::WorkspaceMutating
+    }
+
     async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
         let input: WriteArgs = parse_args(args)?;
         let path = resolve_workspace_path(ctx, &input.path, ResolveMode::AllowMissing)?;
-        ctx.authorize_path(&path, PathAccess::Write).await?;
+        if !ctx.services.simulation {
+            ctx.authorize_path(&path, PathAccess::Write).await?;
+        }
         ctx.check_access(&path, AccessMode::Write)?;
+        let _exclusive = ctx.exclusive(self).await;
 
         let existed = path.exists();
         if existed && !input.overwrite {
-            return Err(ToolError::ExecutionFailed(
+            return Err(ToolError::Conflict(
                 "file exists; set overwrite to true to replace".to_string(),
             ));
         }
@@ -127,6 +271,31 @@ impl Tool for WriteTool {
                 "path is a directory".to_string(),
             ));
         }
+        let previous = if existed {
+            let bytes = fs::read(&path).map_err(|err| {
+                ToolError::ExecutionFailed(format!("failed to read existing file: {err}"))
+            })?;
+            let hash = content_hash(&bytes);
+            ensure_unchanged(ctx, &path, &hash, input.expected_hash.as_deref())?;
+            Some((String::from_utf8_lossy(&bytes).into_owned(), hash))
+        } else {
+            None
+        };
+
+        if ctx.services.simulation {
+            info!(
+                "simulated write (bytes={}, overwrite={})",
+                input.content.len(),
+                existed
+            );
+            return Ok(json!({
+                "simulated": true,
+                "action": "write",
+                "path": relative_display(&ctx.services.workspace_root, &path),
+                "bytes": input.content.len(),
+                "overwrite": existed,
+            }));
+        }
 
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).map_err(|err| {
@@ -136,16 +305,25 @@ impl Tool for WriteTool {
 
         fs::write(&path, input.content.as_bytes())
             .map_err(|err| ToolError::ExecutionFailed(format!("failed to write file: {err}")))?;
+        let hash = content_hash(input.content.as_bytes());
+        ctx.services
+            .file_versions
+            .record(path.clone(), hash.clone());
         info!(
             "wrote file (bytes_written={}, overwritten={})",
             input.content.len(),
             existed
         );
 
+        let display_path = relative_display(&ctx.services.workspace_root, &path);
+        let (before, previous_hash) = previous.unzip();
         Ok(json!({
-            "path": relative_display(&ctx.services.workspace_root, &path),
+            "diff": unified_diff(&display_path, before.as_deref().unwrap_or_default(), &input.content),
+            "path": display_path,
             "bytes_written": input.content.len(),
             "overwritten": existed,
+            "previous_hash": previous_hash,
+            "hash": hash,
         }))
     }
 }
@@ -169,6 +347,18 @@ impl Tool for EditTool {
         serde_json::from_str(params_str).expect("Error parsing tool parameters")
     }
 
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn exclusion_group(&self) -> Option<&str> {
+        Some(WORKSPACE_WRITE_GROUP)
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::WorkspaceMutating
+    }
+
     async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
         let input: EditArgs = parse_args(args)?;
         if input.old_text.is_empty() {
@@ -178,12 +368,17 @@ impl Tool for EditTool {
         }
         let path = resolve_workspace_path(ctx, &input.path, ResolveMode::Existing)?;
         ctx.authorize_path(&path, PathAccess::Read).await?;
-        ctx.authorize_path(&path, PathAccess::Write).await?;
+        if !ctx.services.simulation {
+            ctx.authorize_path(&path, PathAccess::Write).await?;
+        }
         ctx.check_access(&path, AccessMode::Read)?;
         ctx.check_access(&path, AccessMode::Write)?;
+        let _exclusive = ctx.exclusive(self).await;
 
         let content = fs::read_to_string(&path)
-            .map_err(|err| ToolError::ExecutionFailed(format!("failed to read file: {err}")))?;
+            .map_err(|err| io_error(&err, format!("failed to read file: {err}")))?;
+        let previous_hash = content_hash(content.as_bytes());
+        ensure_unchanged(ctx, &path, &previous_hash, input.expected_hash.as_deref())?;
         let occurrences = content.match_indices(&input.old_text).count();
         if occurrences == 0 {
             return Err(ToolError::ExecutionFailed(
@@ -196,6 +391,17 @@ impl Tool for EditTool {
                     .to_string(),
             ));
         }
+        let replaced = if input.replace_all { occurrences } else { 1 };
+
+        if ctx.services.simulation {
+            info!("simulated edit (replacements={})", replaced);
+            return Ok(json!({
+                "simulated": true,
+                "action": "edit",
+                "path": relative_display(&ctx.services.workspace_root, &path),
+                "replacements": replaced,
+            }));
+        }
 
         let updated = if input.replace_all {
             content.replace(&input.old_text, &input.new_text)
@@ -204,14 +410,19 @@ impl Tool for EditTool {
         };
         fs::write(&path, updated.as_bytes())
             .map_err(|err| ToolError::ExecutionFailed(format!("failed to write file: {err}")))?;
-        info!(
-            "edited file (replacements={})",
-            if input.replace_all { occurrences } else { 1 }
-        );
+        let hash = content_hash(updated.as_bytes());
+        ctx.services
+            .file_versions
+            .record(path.clone(), hash.clone());
+        info!("edited file (replacements={})", replaced);
 
+        let display_path = relative_display(&ctx.services.workspace_root, &path);
         Ok(json!({
-            "path": relative_display(&ctx.services.workspace_root, &path),
-            "replaced": if input.replace_all { occurrences } else { 1 },
+            "diff": unified_diff(&display_path, &content, &updated),
+            "path": display_path,
+            "replaced": replaced,
+            "previous_hash": previous_hash,
+            "hash": hash,
         }))
     }
 }
@@ -227,7 +438,7 @@ impl Tool for GlobTool {
     }
 
     fn description(&self) -> &str {
-        "Find files by glob pattern within the workspace"
+        "Find files by glob pattern within the workspace, skipping gitignored and hidden paths"
     }
 
     fn args_schema(&self) -> Value {
@@ -239,6 +450,10 @@ impl Tool for GlobTool {
         true
     }
 
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
+
     async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
         let input: GlobArgs = parse_args(args)?;
         let root = match input.root.as_deref() {
@@ -271,24 +486,23 @@ impl Tool for GlobTool {
 
         let mut matches = Vec::new();
         let mut truncated = false;
-        for entry in WalkDir::new(&root) {
-            let entry = entry.map_err(|err| {
-                ToolError::ExecutionFailed(format!("failed to walk directory: {err}"))
-            })?;
-            if !entry.file_type().is_file() {
-                continue;
-            }
-            let path = entry.path();
-            let relative = path.strip_prefix(&root).unwrap_or(path);
-            if set.is_match(relative) {
-                ctx.check_access(path, AccessMode::Read)?;
-                matches.push(relative_display(&ctx.services.workspace_root, path));
-                if matches.len() >= max_results {
-                    truncated = true;
-                    break;
+        walk_files(
+            &root,
+            |path| {
+                let relative = path.strip_prefix(&root).unwrap_or(path);
+                if !set.is_match(relative) {
+                    return Ok(None);
                 }
-            }
-        }
+                ctx.check_access(path, AccessMode::Read)?;
+                Ok(Some(relative_display(&ctx.services.workspace_root, path)))
+            },
+            |path| {
+                matches.push(path);
+                truncated = matches.len() >= max_results;
+                !truncated
+            },
+        )?;
+        matches.sort();
         info!(
             "glob completed (matches={}, truncated={})",
             matches.len(),
@@ -313,7 +527,7 @@ impl Tool for GrepTool {
     }
 
     fn description(&self) -> &str {
-        "Search file contents with a regular expression"
+        "Search text file contents with a regular expression, skipping gitignored, hidden, and binary files"
     }
 
     fn args_schema(&self) -> Value {
@@ -325,144 +539,205 @@ impl Tool for GrepTool {
         true
     }
 
-    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
-        let input: GrepArgs = parse_args(args)?;
-        let root = match input.path.as_deref() {
-            Some(path) => resolve_workspace_path(ctx, path, ResolveMode::Existing)?,
-            None => ctx.services.workspace_root.clone(),
-        };
-        ctx.authorize_path(&root, PathAccess::Read).await?;
-        let case_sensitive = input.case_sensitive.unwrap_or(true);
-        debug!(
-            "grep starting (case_sensitive={}, has_glob={})",
-            case_sensitive,
-            input.glob.is_some()
-        );
-        let regex = RegexBuilder::new(&input.pattern)
-            .case_insensitive(!case_sensitive)
-            .build()
-            .map_err(|err| ToolError::InvalidArguments(err.to_string()))?;
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
 
-        let mut glob = None;
-        if let Some(pattern) = input.glob.as_ref() {
-            let mut builder = globset::GlobSetBuilder::new();
-            let glob_pattern = globset::Glob::new(pattern)
-                .map_err(|err| ToolError::InvalidArguments(err.to_string()))?;
-            builder.add(glob_pattern);
-            let set = builder
-                .build()
-                .map_err(|err| ToolError::InvalidArguments(err.to_string()))?;
-            glob = Some(set);
-        }
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        run_grep(ctx, args, None).await
+    }
 
-        let max_results = input.max_results.unwrap_or_else(|| {
-            ctx.services
-                .output_policy
-                .as_ref()
-                .map(|policy| policy.max_array_len)
-                .unwrap_or(DEFAULT_MAX_RESULTS)
-        });
+    async fn call_streaming(
+        &self,
+        ctx: &ToolContext,
+        args: Value,
+        sink: &dyn ToolDeltaSink,
+    ) -> Result<Value, ToolError> {
+        run_grep(ctx, args, Some(sink)).await
+    }
+}
 
-        let mut matches = Vec::new();
-        let mut truncated = false;
+/// Search files, optionally pushing matches to a tool delta sink per file.
+async fn run_grep(
+    ctx: &ToolContext,
+    args: Value,
+    deltas: Option<&dyn ToolDeltaSink>,
+) -> Result<Value, ToolError> {
+    let input: GrepArgs = parse_args(args)?;
+    let root = match input.path.as_deref() {
+        Some(path) => resolve_workspace_path(ctx, path, ResolveMode::Existing)?,
+        None => ctx.services.workspace_root.clone(),
+    };
+    ctx.authorize_path(&root, PathAccess::Read).await?;
+    let case_sensitive = input.case_sensitive.unwrap_or(true);
+    debug!(
+        "grep starting (case_sensitive={}, has_glob={})",
+        case_sensitive,
+        input.glob.is_some()
+    );
+    let regex = RegexBuilder::new(&input.pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|err| ToolError::InvalidArguments(err.to_string()))?;
+
+    let mut glob = None;
+    if let Some(pattern) = input.glob.as_ref() {
+        let mut builder = globset::GlobSetBuilder::new();
+        let glob_pattern = globset::Glob::new(pattern)
+            .map_err(|err| ToolError::InvalidArguments(err.to_string()))?;
+        builder.add(glob_pattern);
+        let set = builder
+            .build()
+            .map_err(|err| ToolError::InvalidArguments(err.to_string()))?;
+        glob = Some(set);
+    }
 
-        if root.is_file() {
-            search_file(
-                ctx,
-                &regex,
-                glob.as_ref(),
-                &root,
-                &mut matches,
-                max_results,
-                &mut truncated,
-            )?;
-        } else {
-            for entry in WalkDir::new(&root) {
-                let entry = entry.map_err(|err| {
-                    ToolError::ExecutionFailed(format!("failed to walk directory: {err}"))
-                })?;
-                if !entry.file_type().is_file() {
-                    continue;
-                }
-                if truncated {
-                    break;
-                }
-                let path = entry.path();
-                search_file(
-                    ctx,
-                    &regex,
-                    glob.as_ref(),
-                    path,
-                    &mut matches,
-                    max_results,
-                    &mut truncated,
-                )?;
-            }
+    let max_results = input.max_results.unwrap_or_else(|| {
+        ctx.services
+            .output_policy
+            .as_ref()
+            .map(|policy| policy.max_array_len)
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+    });
+
+    let search = FileSearch {
+        ctx,
+        regex: &regex,
+        glob: glob.as_ref(),
+        context: input.context.unwrap_or(0),
+        max_results,
+    };
+    let mut matches = Vec::new();
+    let mut truncated = false;
+    let mut collect = |found: Vec<Value>| {
+        let keep = found.len().min(max_results - matches.len());
+        push_grep_matches(deltas, &found[..keep]);
+        matches.extend(found.into_iter().take(keep));
+        truncated = matches.len() >= max_results;
+        !truncated
+    };
+    if root.is_file() {
+        if let Some(found) = search.search_file(&root)? {
+            collect(found);
         }
-        info!(
-            "grep completed (matches={}, truncated={})",
-            matches.len(),
-            truncated
-        );
+    } else {
+        walk_files(&root, |path| search.search_file(path), collect)?;
+    }
+    matches.sort_by(|a, b| {
+        (a["path"].as_str(), a["line"].as_u64()).cmp(&(b["path"].as_str(), b["line"].as_u64()))
+    });
+    info!(
+        "grep completed (matches={}, truncated={})",
+        matches.len(),
+        truncated
+    );
+
+    Ok(json!({
+        "pattern": input.pattern,
+        "matches": matches,
+        "truncated": truncated,
+    }))
+}
 
-        Ok(json!({
-            "pattern": input.pattern,
-            "matches": matches,
-            "truncated": truncated,
-        }))
+/// Push newly found grep matches as a single tool delta.
+fn push_grep_matches(deltas: Option<&dyn ToolDeltaSink>, matches: &[Value]) {
+    if let Some(deltas) = deltas
+        && !matches.is_empty()
+    {
+        deltas.push(json!({ "matches": matches }));
     }
 }
 
-/// Search a file and append matching lines into the results vector.
-fn search_file(
-    ctx: &ToolContext,
-    regex: &regex::Regex,
-    glob: Option<&globset::GlobSet>,
-    path: &Path,
-    matches: &mut Vec<Value>,
+/// Per-call Grep settings shared by the walker threads.
+struct FileSearch<'a> {
+    ctx: &'a ToolContext,
+    regex: &'a regex::Regex,
+    glob: Option<&'a globset::GlobSet>,
+    /// Lines of context kept before and after each match.
+    context: usize,
     max_results: usize,
-    truncated: &mut bool,
-) -> Result<(), ToolError> {
-    if let Some(set) = glob {
-        let relative = path
-            .strip_prefix(&ctx.services.workspace_root)
-            .unwrap_or(path);
-        if !set.is_match(relative) {
-            return Ok(());
+}
+
+impl FileSearch<'_> {
+    /// Matching lines of a text file, or `None` when the glob filter or binary
+    /// sniffing skips it.
+    fn search_file(&self, path: &Path) -> Result<Option<Vec<Value>>, ToolError> {
+        let ctx = self.ctx;
+        if let Some(set) = self.glob {
+            let relative = path
+                .strip_prefix(&ctx.services.workspace_root)
+                .unwrap_or(path);
+            if !set.is_match(relative) {
+                return Ok(None);
+            }
         }
-    }
-    ctx.check_access(path, AccessMode::Read)?;
+        ctx.check_access(path, AccessMode::Read)?;
 
-    let file = File::open(path)
-        .map_err(|err| ToolError::ExecutionFailed(format!("failed to open file: {err}")))?;
-    let reader = BufReader::new(file);
+        let file = File::open(path)
+            .map_err(|err| ToolError::ExecutionFailed(format!("failed to open file: {err}")))?;
+        let mut reader = BufReader::with_capacity(SNIFF_BYTES, file);
+        let head = reader
+            .fill_buf()
+            .map_err(|err| ToolError::ExecutionFailed(format!("failed to read file: {err}")))?;
+        if binary_mime(head).is_some() {
+            debug!("grep skipping binary file");
+            return Ok(None);
+        }
 
-    for (index, line_result) in reader.lines().enumerate() {
-        let line = match line_result {
-            Ok(line) => line,
-            Err(err) => {
-                if err.kind() == std::io::ErrorKind::InvalidData {
-                    return Ok(());
+        let display_path = relative_display(&ctx.services.workspace_root, path);
+        let mut matches = Vec::new();
+        let mut before = VecDeque::with_capacity(self.context);
+        // Matches still collecting trailing context lines.
+        let mut open = Vec::new();
+        for (index, line_result) in reader.lines().enumerate() {
+            let line = match line_result {
+                Ok(line) => line,
+                Err(err) => {
+                    if err.kind() == std::io::ErrorKind::InvalidData {
+                        return Ok(None);
+                    }
+                    return Err(ToolError::ExecutionFailed(format!(
+                        "failed to read line: {err}"
+                    )));
                 }
-                return Err(ToolError::ExecutionFailed(format!(
-                    "failed to read line: {err}"
-                )));
-            }
-        };
-        if regex.is_match(&line) {
-            matches.push(json!({
-                "path": relative_display(&ctx.services.workspace_root, path),
-                "line": index + 1,
-                "text": line,
-            }));
-            if matches.len() >= max_results {
-                *truncated = true;
+            };
+            open.retain(|&open_index| {
+                let Some(Value::Array(after)) = matches
+                    .get_mut(open_index)
+                    .and_then(|found: &mut Value| found.get_mut("after"))
+                else {
+                    return false;
+                };
+                after.push(Value::from(line.as_str()));
+                after.len() < self.context
+            });
+            let full = matches.len() >= self.max_results;
+            if full && open.is_empty() {
                 break;
             }
+            if !full && self.regex.is_match(&line) {
+                let mut found = json!({
+                    "path": display_path,
+                    "line": index + 1,
+                    "text": line,
+                });
+                if self.context > 0 {
+                    found["before"] = json!(before);
+                    found["after"] = json!([]);
+                    open.push(matches.len());
+                }
+                matches.push(found);
+            }
+            if self.context > 0 {
+                if before.len() == self.context {
+                    before.pop_front();
+                }
+                before.push_back(line);
+            }
         }
-    }
 
-    Ok(())
+        Ok(Some(matches))
+    }
 }
 
 /// Arguments for ReadTool.
@@ -473,6 +748,9 @@ struct ReadArgs {
     #[input(description = "Maximum number of bytes to read.")]
     #[serde(default)]
     max_bytes: Option<usize>,
+    #[input(description = "Maximum number of lines to read.")]
+    #[serde(default)]
+    max_lines: Option<usize>,
 }
 
 /// Arguments for WriteTool.
@@ -485,6 +763,11 @@ struct WriteArgs {
     #[input(description = "Overwrite the file if it already exists.")]
     #[serde(default)]
     overwrite: bool,
+    #[input(
+        description = "Hash of the file as last read; the write fails if the file has changed since."
+    )]
+    #[serde(default)]
+    expected_hash: Option<String>,
 }
 
 /// Arguments for EditTool.
@@ -499,6 +782,11 @@ struct EditArgs {
     #[input(description = "Replace all occurrences instead of the first match.")]
     #[serde(default)]
     replace_all: bool,
+    #[input(
+        description = "Hash of the file as last read; the edit fails if the file has changed since."
+    )]
+    #[serde(default)]
+    expected_hash: Option<String>,
 }
 
 /// Arguments for GlobTool.
@@ -528,6 +816,9 @@ struct GrepArgs {
     #[input(description = "Case sensitive search when true.")]
     #[serde(default)]
     case_sensitive: Option<bool>,
+    #[input(description = "Lines of context to return before and after each match.")]
+    #[serde(default)]
+    context: Option<usize>,
     #[input(description = "Maximum number of results to return.")]
     #[serde(default)]
     max_results: Option<usize>,
@@ -536,36 +827,82 @@ struct GrepArgs {
 #[cfg(test)]
 mod tests {
     use super::{EditTool, GlobTool, GrepTool, ReadTool, WriteTool};
-    use crate::{Tool, ToolContext, TurnServices};
-    use odyssey_rs_protocol::ToolError;
+    use crate::builtins::utils::content_hash;
+    use crate::{
+        PermissionChecker, PermissionContext, PermissionOutcome, Tool, ToolContext, ToolDeltaSink,
+        ToolOutputPolicy, ToolScheduler,
+    };
+    use async_trait::async_trait;
+    use odyssey_rs_protocol::{PermissionRequest, ToolError};
     use pretty_assertions::assert_eq;
     use serde_json::json;
     use std::sync::Arc;
     use tempfile::tempdir;
-    use uuid::Uuid;
+    use tokio::sync::Notify;
 
     fn context_for_root(root: &std::path::Path) -> ToolContext {
-        ToolContext {
-            session_id: Uuid::nil(),
-            agent_id: "agent".to_string(),
-            turn_id: None,
-            tool_call_id: None,
-            tool_name: None,
-            services: Arc::new(TurnServices {
-                cwd: root.to_path_buf(),
-                workspace_root: root.to_path_buf(),
-                output_policy: None,
-                sandbox: None,
-                web: None,
-                event_sink: None,
-                skill_provider: None,
-                question_handler: None,
-                permission_checker: None,
-                tool_result_handler: None,
-            }),
+        context_with_simulation(root, false)
+    }
+
+    fn context_with_simulation(root: &std::path::Path, simulation: bool) -> ToolContext {
+        ToolContext::builder()
+            .with_workspace_root(root)
+            .with_simulation(simulation)
+            .build()
+    }
+
+    /// Holds path approvals for `slow.txt` until released.
+    #[derive(Default)]
+    struct GatedPermissions {
+        release: Notify,
+    }
+
+    #[async_trait]
+    impl PermissionChecker for GatedPermissions {
+        async fn authorize(
+            &self,
+            _ctx: &PermissionContext,
+            request: PermissionRequest,
+        ) -> Result<PermissionOutcome, ToolError> {
+            if let PermissionRequest::Path { path, .. } = &request
+                && path.ends_with("slow.txt")
+            {
+                self.release.notified().await;
+            }
+            Ok(PermissionOutcome {
+                allowed: true,
+                reason: None,
+            })
         }
     }
 
+    #[tokio::test]
+    async fn pending_approval_does_not_hold_the_write_group() {
+        let temp = tempdir().expect("tempdir");
+        let permissions = Arc::new(GatedPermissions::default());
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .with_permission_checker(permissions.clone())
+            .with_scheduler(Arc::new(ToolScheduler::new()))
+            .build();
+
+        let slow = WriteTool.call(&ctx, json!({ "path": "slow.txt", "content": "slow" }));
+        let fast = async {
+            WriteTool
+                .call(&ctx, json!({ "path": "fast.txt", "content": "fast" }))
+                .await
+                .expect("fast write");
+            assert_eq!(temp.path().join("slow.txt").exists(), false);
+            permissions.release.notify_one();
+        };
+        let (slow, ()) = tokio::join!(slow, fast);
+        slow.expect("slow write");
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("slow.txt")).expect("slow"),
+            "slow"
+        );
+    }
+
     #[tokio::test]
     async fn read_tool_reads_file() {
         let temp = tempdir().expect("tempdir");
@@ -636,6 +973,172 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn edit_tool_reports_diff_and_hashes() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("edit.txt"), "hello\nworld\n").expect("write");
+        let ctx = context_for_root(temp.path());
+
+        let read = ReadTool
+            .call(&ctx, json!({ "path": "edit.txt" }))
+            .await
+            .expect("read");
+        let result = EditTool
+            .call(
+                &ctx,
+                json!({
+                    "path": "edit.txt",
+                    "old_text": "world",
+                    "new_text": "odyssey",
+                }),
+            )
+            .await
+            .expect("edit");
+
+        assert_eq!(result["previous_hash"], read["hash"]);
+        assert_eq!(
+            result["diff"],
+            "--- a/edit.txt\n+++ b/edit.txt\n@@ -1,2 +1,2 @@\n hello\n-world\n+odyssey\n"
+        );
+        let reread = ReadTool
+            .call(&ctx, json!({ "path": "edit.txt" }))
+            .await
+            .expect("read");
+        assert_eq!(result["hash"], reread["hash"]);
+    }
+
+    #[tokio::test]
+    async fn edit_tool_rejects_files_changed_since_read() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("edit.txt");
+        std::fs::write(&path, "hello world").expect("write");
+        let ctx = context_for_root(temp.path());
+        let args = json!({
+            "path": "edit.txt",
+            "old_text": "hello",
+            "new_text": "goodbye",
+        });
+
+        ReadTool
+            .call(&ctx, json!({ "path": "edit.txt" }))
+            .await
+            .expect("read");
+        std::fs::write(&path, "hello world, edited by the user").expect("external edit");
+        let err = EditTool.call(&ctx, args.clone()).await.expect_err("stale");
+        let ToolError::Conflict(message) = err else {
+            panic!("expected conflict");
+        };
+        assert_eq!(
+            message.starts_with("file changed since it was last read"),
+            true
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("read"),
+            "hello world, edited by the user"
+        );
+
+        ReadTool
+            .call(&ctx, json!({ "path": "edit.txt" }))
+            .await
+            .expect("reread");
+        EditTool.call(&ctx, args).await.expect("edit after reread");
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("read"),
+            "goodbye world, edited by the user"
+        );
+    }
+
+    #[tokio::test]
+    async fn write_tool_checks_expected_hash() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("out.txt"), "old").expect("write");
+        let ctx = context_for_root(temp.path());
+
+        let err = WriteTool
+            .call(
+                &ctx,
+                json!({
+                    "path": "out.txt",
+                    "content": "new",
+                    "overwrite": true,
+                    "expected_hash": "0000000000000000",
+                }),
+            )
+            .await
+            .expect_err("mismatch");
+        assert!(matches!(err, ToolError::Conflict(_)));
+
+        let result = WriteTool
+            .call(
+                &ctx,
+                json!({ "path": "out.txt", "content": "new", "overwrite": true }),
+            )
+            .await
+            .expect("overwrite");
+        assert_eq!(
+            result["diff"],
+            "--- a/out.txt\n+++ b/out.txt\n@@ -1 +1 @@\n-old\n\\ No newline at end of file\n+new\n\\ No newline at end of file\n"
+        );
+        assert_eq!(result["previous_hash"].is_string(), true);
+    }
+
+    #[tokio::test]
+    async fn write_tool_simulation_leaves_filesystem_untouched() {
+        let temp = tempdir().expect("tempdir");
+        let ctx = context_with_simulation(temp.path(), true);
+        let tool = WriteTool;
+
+        let result = tool
+            .call(
+                &ctx,
+                json!({
+                    "path": "nested/out.txt",
+                    "content": "data",
+                    "overwrite": false
+                }),
+            )
+            .await
+            .expect("simulated write");
+
+        assert_eq!(
+            result,
+            json!({
+                "simulated": true,
+                "action": "write",
+                "path": "nested/out.txt",
+                "bytes": 4,
+                "overwrite": false,
+            })
+        );
+        assert_eq!(temp.path().join("nested").exists(), false);
+    }
+
+    #[tokio::test]
+    async fn edit_tool_simulation_reports_replacements() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("edit.txt");
+        std::fs::write(&path, "one two one").expect("write");
+        let ctx = context_with_simulation(temp.path(), true);
+        let tool = EditTool;
+
+        let result = tool
+            .call(
+                &ctx,
+                json!({
+                    "path": "edit.txt",
+                    "old_text": "one",
+                    "new_text": "three",
+                    "replace_all": true
+                }),
+            )
+            .await
+            .expect("simulated edit");
+
+        assert_eq!(result["simulated"], true);
+        assert_eq!(result["replacements"], 2);
+        assert_eq!(std::fs::read_to_string(path).expect("read"), "one two one");
+    }
+
     #[tokio::test]
     async fn glob_tool_finds_matches() {
         let temp = tempdir().expect("tempdir");
@@ -684,6 +1187,108 @@ mod tests {
         assert_eq!(matches[0]["line"], 2);
     }
 
+    #[derive(Default)]
+    struct CollectingDeltas {
+        deltas: parking_lot::Mutex<Vec<serde_json::Value>>,
+    }
+
+    impl ToolDeltaSink for CollectingDeltas {
+        fn push(&self, delta: serde_json::Value) {
+            self.deltas.lock().push(delta);
+        }
+    }
+
+    #[tokio::test]
+    async fn grep_tool_streams_matches_per_file() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("a.txt"), "needle one").expect("write");
+        std::fs::write(temp.path().join("b.txt"), "hay").expect("write");
+        std::fs::write(temp.path().join("c.txt"), "needle two").expect("write");
+        let ctx = context_for_root(temp.path());
+        let deltas = CollectingDeltas::default();
+
+        let result = GrepTool
+            .call_streaming(&ctx, json!({ "pattern": "needle" }), &deltas)
+            .await
+            .expect("grep");
+
+        let streamed = deltas
+            .deltas
+            .lock()
+            .iter()
+            .flat_map(|delta| delta["matches"].as_array().cloned().unwrap_or_default())
+            .count();
+        assert_eq!(deltas.deltas.lock().len(), 2);
+        assert_eq!(streamed, result["matches"].as_array().expect("array").len());
+    }
+
+    #[tokio::test]
+    async fn grep_tool_skips_ignored_and_binary_files_and_returns_context() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::create_dir_all(temp.path().join("src")).expect("mkdir");
+        std::fs::create_dir_all(temp.path().join("target")).expect("mkdir");
+        std::fs::write(temp.path().join(".gitignore"), "target/\n").expect("write");
+        std::fs::write(
+            temp.path().join("src/lib.rs"),
+            "one\ntwo\nneedle\nthree\nfour\n",
+        )
+        .expect("write");
+        std::fs::write(temp.path().join("target/out.rs"), "needle").expect("write");
+        std::fs::write(temp.path().join("blob.bin"), b"needle\0\x01").expect("write");
+        let ctx = context_for_root(temp.path());
+
+        let result = GrepTool
+            .call(&ctx, json!({ "pattern": "needle", "context": 2 }))
+            .await
+            .expect("grep");
+        assert_eq!(
+            result["matches"],
+            json!([{
+                "path": "src/lib.rs",
+                "line": 3,
+                "text": "needle",
+                "before": ["one", "two"],
+                "after": ["three", "four"],
+            }])
+        );
+
+        let result = GlobTool
+            .call(&ctx, json!({ "pattern": "**/*.rs" }))
+            .await
+            .expect("glob");
+        assert_eq!(result["matches"], json!(["src/lib.rs"]));
+    }
+
+    #[tokio::test]
+    async fn grep_tool_caps_results_across_files() {
+        let temp = tempdir().expect("tempdir");
+        for index in 0..20 {
+            std::fs::write(
+                temp.path().join(format!("file{index:02}.txt")),
+                "needle\nneedle\n",
+            )
+            .expect("write");
+        }
+        let ctx = context_for_root(temp.path());
+
+        let result = GrepTool
+            .call(&ctx, json!({ "pattern": "needle", "max_results": 5 }))
+            .await
+            .expect("grep");
+        assert_eq!(result["matches"].as_array().expect("array").len(), 5);
+        assert_eq!(result["truncated"], true);
+
+        let result = GrepTool
+            .call(&ctx, json!({ "pattern": "needle" }))
+            .await
+            .expect("grep");
+        let matches = result["matches"].as_array().expect("array");
+        assert_eq!(matches.len(), 40);
+        assert_eq!(matches[0]["path"], "file00.txt");
+        assert_eq!(matches[39]["path"], "file19.txt");
+        assert_eq!(result["truncated"], false);
+    }
+
     #[tokio::test]
     async fn read_tool_rejects_directory() {
         let temp = tempdir().expect("tempdir");
@@ -702,6 +1307,125 @@ mod tests {
         assert_eq!(message, "path is a directory");
     }
 
+    #[tokio::test]
+    async fn read_tool_reports_missing_files_as_not_found() {
+        let temp = tempdir().expect("tempdir");
+        let ctx = context_for_root(temp.path());
+        let err = ReadTool
+            .call(&ctx, json!({ "path": "missing.txt" }))
+            .await
+            .expect_err("missing");
+        assert!(matches!(err, ToolError::NotFound(_)));
+        assert_eq!(err.retryable(), false);
+    }
+
+    #[tokio::test]
+    async fn read_tool_describes_binary_files_and_inlines_images() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(
+            temp.path().join("logo.png"),
+            b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR",
+        )
+        .expect("write png");
+        std::fs::write(temp.path().join("doc.pdf"), b"%PDF-1.7\n%\xe2\xe3").expect("write pdf");
+        let mut ctx = context_for_root(temp.path());
+
+        let result = ReadTool
+            .call(&ctx, json!({ "path": "logo.png" }))
+            .await
+            .expect("read png");
+        assert_eq!(result["binary"], true);
+        assert_eq!(result["mime_type"], "image/png");
+        assert_eq!(result["size"], 16);
+        assert_eq!(result.get("content"), None);
+        assert_eq!(result.get("image"), None);
+
+        Arc::get_mut(&mut ctx.services)
+            .expect("services")
+            .output_policy = Some(ToolOutputPolicy {
+            max_image_bytes: Some(1024),
+            ..ToolOutputPolicy::default()
+        });
+        let result = ReadTool
+            .call(&ctx, json!({ "path": "logo.png" }))
+            .await
+            .expect("read png");
+        assert_eq!(
+            result["image"],
+            json!({ "type": "image", "mime_type": "image/png", "data": "iVBORw0KGgoAAAANSUhEUg==" })
+        );
+
+        let result = ReadTool
+            .call(&ctx, json!({ "path": "doc.pdf" }))
+            .await
+            .expect("read pdf");
+        assert_eq!(result["mime_type"], "application/pdf");
+        assert_eq!(result.get("image"), None);
+    }
+
+    #[tokio::test]
+    async fn read_tool_stops_at_binary_data_in_large_sparse_files() {
+        let temp = tempdir().expect("tempdir");
+        let file = std::fs::File::create(temp.path().join("disk.img")).expect("create");
+        file.set_len(8 << 30).expect("sparse file");
+        let ctx = context_for_root(temp.path());
+
+        let result = ReadTool
+            .call(&ctx, json!({ "path": "disk.img" }))
+            .await
+            .expect("read sparse file");
+        assert_eq!(result["binary"], true);
+        assert_eq!(result["mime_type"], "application/octet-stream");
+        assert_eq!(result["size"], 8_u64 << 30);
+        assert_eq!(result.get("hash"), None);
+    }
+
+    #[tokio::test]
+    async fn read_tool_streams_text_up_to_the_budget() {
+        let temp = tempdir().expect("tempdir");
+        let text = "line\n".repeat(100_000);
+        std::fs::write(temp.path().join("big.log"), &text).expect("write");
+        let ctx = context_for_root(temp.path());
+
+        let result = ReadTool
+            .call(&ctx, json!({ "path": "big.log" }))
+            .await
+            .expect("read");
+        assert_eq!(result["bytes_read"], 200_000);
+        assert_eq!(result["truncated"], true);
+        assert_eq!(result["hash"], json!(null));
+        let edit = json!({ "path": "big.log", "old_text": "line", "new_text": "row", "replace_all": true });
+        let err = EditTool
+            .call(&ctx, edit.clone())
+            .await
+            .expect_err("partial read");
+        assert_eq!(matches!(err, ToolError::Conflict(_)), true);
+        let result = ReadTool
+            .call(&ctx, json!({ "path": "big.log", "max_bytes": 1_000_000 }))
+            .await
+            .expect("full read");
+        assert_eq!(result["hash"], content_hash(text.as_bytes()));
+        EditTool
+            .call(&ctx, edit)
+            .await
+            .expect("edit after full read");
+
+        let result = ReadTool
+            .call(&ctx, json!({ "path": "big.log", "max_lines": 2 }))
+            .await
+            .expect("read lines");
+        assert_eq!(result["content"], "line\nline\n");
+        assert_eq!(result["truncated"], true);
+
+        std::fs::write(temp.path().join("short.txt"), "a\nb\n").expect("write");
+        let result = ReadTool
+            .call(&ctx, json!({ "path": "short.txt", "max_lines": 2 }))
+            .await
+            .expect("read exact lines");
+        assert_eq!(result["content"], "a\nb\n");
+        assert_eq!(result["truncated"], false);
+    }
+
     #[tokio::test]
     async fn write_tool_rejects_existing_without_overwrite() {
         let temp = tempdir().expect("tempdir");
@@ -721,8 +1445,8 @@ mod tests {
             )
             .await
             .expect_err("exists");
-        let ToolError::ExecutionFailed(message) = err else {
-            panic!("expected execution failed");
+        let ToolError::Conflict(message) = err else {
+            panic!("expected conflict");
         };
         assert_eq!(message, "file exists; set overwrite to true to replace");
     }
@@ -0,0 +1,1182 @@
+//! Built-in git tools (status, diff, commit with guarded push).
+//!
+//! Git runs through the sandbox in the session cwd, and its porcelain output
+//! is parsed into JSON. Every invocation disables the fsmonitor and hooks.
+//! GitStatus and GitDiff also blank every configured filter driver, and diffs
+//! skip textconv and external diff drivers, so repository config and
+//! attributes cannot make read-only tools run commands. Commits are authorized
+//! as a `git commit` command and keep filters such as Git LFS; pushes use a
+//! dedicated `PermissionRequest::GitPush` so permission rules can gate them
+//! separately.
+
+use crate::builtins::filesystem::WORKSPACE_WRITE_GROUP;
+use crate::builtins::utils::{ResolveMode, parse_args, resolve_workspace_path};
+use crate::{Tool, ToolContext};
+use async_trait::async_trait;
+use autoagents_core::tool::ToolInputT;
+use autoagents_derive::ToolInput;
+use log::{debug, info, warn};
+use odyssey_rs_protocol::{PathAccess, PermissionRequest, ToolError, ToolRisk};
+use odyssey_rs_sandbox::{AccessMode, CommandResult, CommandSpec};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Remote used when GitCommit pushes without naming one.
+const DEFAULT_REMOTE: &str = "origin";
+/// Config overrides that stop repository settings from running commands.
+const SAFE_CONFIG: [&str; 4] = [
+    "-c",
+    "core.fsmonitor=false",
+    "-c",
+    "core.hooksPath=/dev/null",
+];
+
+/// Tool for reporting working tree status.
+#[derive(Debug, Default)]
+pub struct GitStatusTool;
+
+#[async_trait]
+impl Tool for GitStatusTool {
+    fn name(&self) -> &str {
+        "GitStatus"
+    }
+
+    fn description(&self) -> &str {
+        "Show the current branch, upstream tracking, and changed files of the git repository"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = GitStatusArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn supports_parallel(&self) -> bool {
+        true
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let input: GitStatusArgs = parse_args(args)?;
+        let mut git_args = vec![
+            "status".to_string(),
+            "--porcelain=v1".to_string(),
+            "-z".to_string(),
+            "--branch".to_string(),
+            "--untracked-files=all".to_string(),
+        ];
+        if let Some(path) = input.path.as_deref() {
+            git_args.push("--".to_string());
+            git_args.push(authorize_pathspec(ctx, path).await?);
+        }
+        let output = run_read_only_git(ctx, git_args).await?;
+        let status = parse_status(&output);
+        info!("git status (files={})", status.files.len());
+        Ok(status.into_json())
+    }
+}
+
+/// Tool for showing changes as structured hunks.
+#[derive(Debug, Default)]
+pub struct GitDiffTool;
+
+#[async_trait]
+impl Tool for GitDiffTool {
+    fn name(&self) -> &str {
+        "GitDiff"
+    }
+
+    fn description(&self) -> &str {
+        "Show unstaged, staged, or revision changes in the git repository as files and hunks"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = GitDiffArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn supports_parallel(&self) -> bool {
+        true
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let input: GitDiffArgs = parse_args(args)?;
+        let mut git_args = vec![
+            "-c".to_string(),
+            "core.quotePath=false".to_string(),
+            "diff".to_string(),
+            "--no-color".to_string(),
+            "--no-ext-diff".to_string(),
+            "--no-textconv".to_string(),
+            "-M".to_string(),
+        ];
+        if input.staged {
+            git_args.push("--cached".to_string());
+        }
+        if let Some(revision) = input.revision {
+            ensure_not_option("revision", &revision)?;
+            git_args.push(revision);
+        }
+        git_args.push("--".to_string());
+        if let Some(path) = input.path.as_deref() {
+            git_args.push(authorize_pathspec(ctx, path).await?);
+        }
+        let output = run_read_only_git(ctx, git_args).await?;
+        let files = parse_diff(&output);
+        let additions = files.iter().map(|file| file.additions).sum::<usize>();
+        let deletions = files.iter().map(|file| file.deletions).sum::<usize>();
+        info!("git diff (files={})", files.len());
+        Ok(json!({
+            "files": files.iter().map(DiffFile::to_json).collect::<Vec<_>>(),
+            "additions": additions,
+            "deletions": deletions,
+        }))
+    }
+}
+
+/// Tool for committing changes and optionally pushing them.
+#[derive(Debug, Default)]
+pub struct GitCommitTool;
+
+#[async_trait]
+impl Tool for GitCommitTool {
+    fn name(&self) -> &str {
+        "GitCommit"
+    }
+
+    fn description(&self) -> &str {
+        "Stage changes and create a git commit; pushing requires a separate git push approval"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = GitCommitArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn exclusion_group(&self) -> Option<&str> {
+        Some(WORKSPACE_WRITE_GROUP)
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::WorkspaceMutating
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let input: GitCommitArgs = parse_args(args)?;
+        if input.message.trim().is_empty() {
+            return Err(ToolError::InvalidArguments(
+                "message cannot be empty".to_string(),
+            ));
+        }
+        if input.all && !input.paths.is_empty() {
+            return Err(ToolError::InvalidArguments(
+                "paths cannot be combined with all".to_string(),
+            ));
+        }
+        if input.force && !input.push {
+            return Err(ToolError::InvalidArguments(
+                "force requires push".to_string(),
+            ));
+        }
+        let remote = input.remote.unwrap_or_else(|| DEFAULT_REMOTE.to_string());
+        ensure_not_option("remote", &remote)?;
+        let mut pathspecs = Vec::with_capacity(input.paths.len());
+        for path in &input.paths {
+            pathspecs.push(authorize_pathspec(ctx, path).await?);
+        }
+
+        if ctx.services.simulation {
+            info!("simulated git commit (paths={})", pathspecs.len());
+            return Ok(json!({
+                "simulated": true,
+                "action": "commit",
+                "message": input.message,
+                "paths": input.paths,
+                "all": input.all,
+                "push": input.push,
+                "force": input.force,
+                "remote": remote,
+            }));
+        }
+
+        // Ask before committing so a denied commit or push leaves the repository untouched.
+        ctx.authorize_command(vec![
+            "git".to_string(),
+            "commit".to_string(),
+            "-m".to_string(),
+            input.message.clone(),
+        ])
+        .await?;
+        let branch = if input.push {
+            let branch = current_branch(ctx).await?;
+            ctx.authorize(PermissionRequest::GitPush {
+                remote: remote.clone(),
+                branch: branch.clone(),
+                force: input.force,
+            })
+            .await?;
+            Some(branch)
+        } else {
+            None
+        };
+        let _exclusive = ctx.exclusive(self).await;
+
+        if input.all {
+            run_git(ctx, vec!["add".to_string(), "-A".to_string()]).await?;
+        } else if !pathspecs.is_empty() {
+            let mut git_args = vec!["add".to_string(), "--".to_string()];
+            git_args.extend(pathspecs);
+            run_git(ctx, git_args).await?;
+        }
+        run_git(
+            ctx,
+            vec!["commit".to_string(), "-m".to_string(), input.message],
+        )
+        .await?;
+
+        let commit = run_git(ctx, vec!["rev-parse".to_string(), "HEAD".to_string()])
+            .await?
+            .trim()
+            .to_string();
+        let files = run_git(
+            ctx,
+            vec![
+                "diff-tree".to_string(),
+                "--root".to_string(),
+                "--no-commit-id".to_string(),
+                "--name-only".to_string(),
+                "-r".to_string(),
+                "-z".to_string(),
+                "HEAD".to_string(),
+            ],
+        )
+        .await?;
+        let files = files
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .collect::<Vec<_>>();
+        info!("git commit created (files={})", files.len());
+
+        let mut result = json!({
+            "commit": commit,
+            "files": files,
+            "pushed": false,
+        });
+        if let Some(branch) = branch {
+            result["remote"] = json!(remote);
+            result["branch"] = json!(branch);
+            let mut git_args = vec!["push".to_string()];
+            if input.force {
+                git_args.push("--force-with-lease".to_string());
+            }
+            git_args.push(remote);
+            git_args.push(branch);
+            // The commit already exists, so a failed push is reported, not raised.
+            match run_git(ctx, git_args).await {
+                Ok(_) => result["pushed"] = json!(true),
+                Err(err) => {
+                    warn!("git push failed: {err}");
+                    result["push_error"] = json!(err.to_string());
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Run git in the session cwd through the sandbox and return its stdout.
+async fn run_git(ctx: &ToolContext, args: Vec<String>) -> Result<String, ToolError> {
+    let subcommand = args
+        .iter()
+        .find(|arg| !arg.starts_with('-') && !arg.contains('='))
+        .cloned()
+        .unwrap_or_default();
+    let result = git_output(ctx, args).await?;
+    if result.status_code == Some(0) {
+        return Ok(result.stdout);
+    }
+    debug!(
+        "git failed (subcommand={subcommand}, status={:?})",
+        result.status_code
+    );
+    let message = if result.stderr.trim().is_empty() {
+        result.stdout.trim()
+    } else {
+        result.stderr.trim()
+    };
+    Err(ToolError::ExecutionFailed(format!(
+        "git {subcommand} failed: {message}"
+    )))
+}
+
+/// Run git with every configured filter driver blanked, so `.gitattributes`
+/// cannot run a clean or process filter while status or diff reads the worktree.
+async fn run_read_only_git(ctx: &ToolContext, args: Vec<String>) -> Result<String, ToolError> {
+    let result = git_output(
+        ctx,
+        vec![
+            "config".to_string(),
+            "-z".to_string(),
+            "--get-regexp".to_string(),
+            r"^filter\.".to_string(),
+        ],
+    )
+    .await?;
+    // Status 1 means no filter is configured.
+    let config = match result.status_code {
+        Some(0) => result.stdout,
+        _ => String::new(),
+    };
+    let mut git_args = filter_overrides(&config);
+    git_args.extend(args);
+    run_git(ctx, git_args).await
+}
+
+/// `-c` overrides that disable each filter driver named in `git config -z --get-regexp` output.
+fn filter_overrides(config: &str) -> Vec<String> {
+    let mut drivers = config
+        .split('\0')
+        .filter_map(|entry| {
+            let key = entry.split_once('\n').map_or(entry, |(key, _)| key);
+            let (driver, _) = key.strip_prefix("filter.")?.rsplit_once('.')?;
+            Some(driver.to_string())
+        })
+        .collect::<Vec<_>>();
+    drivers.sort();
+    drivers.dedup();
+    drivers
+        .iter()
+        .flat_map(|driver| {
+            ["clean=", "smudge=", "process=", "required=false"]
+                .into_iter()
+                .flat_map(move |setting| ["-c".to_string(), format!("filter.{driver}.{setting}")])
+        })
+        .collect()
+}
+
+/// Run git without interpreting its exit status.
+async fn git_output(ctx: &ToolContext, args: Vec<String>) -> Result<CommandResult, ToolError> {
+    let sandbox =
+        ctx.services.sandbox.as_ref().ok_or_else(|| {
+            ToolError::ExecutionFailed("sandbox provider not configured".to_string())
+        })?;
+    let mut spec = CommandSpec::new(PathBuf::from("git"));
+    spec.args = SAFE_CONFIG.iter().map(|arg| arg.to_string()).collect();
+    spec.args.extend(args);
+    spec.cwd = Some(ctx.cwd());
+    // Never block on a credential prompt nobody can answer.
+    spec.env = BTreeMap::from([("GIT_TERMINAL_PROMPT".to_string(), "0".to_string())]);
+    sandbox
+        .provider
+        .run_command(&sandbox.handle, spec)
+        .await
+        .map_err(|err| ToolError::ExecutionFailed(err.to_string()))
+}
+
+/// Name of the checked-out branch; fails on a detached HEAD.
+async fn current_branch(ctx: &ToolContext) -> Result<String, ToolError> {
+    let result = git_output(
+        ctx,
+        vec![
+            "symbolic-ref".to_string(),
+            "--short".to_string(),
+            "-q".to_string(),
+            "HEAD".to_string(),
+        ],
+    )
+    .await?;
+    let branch = result.stdout.trim();
+    if result.status_code != Some(0) || branch.is_empty() {
+        return Err(ToolError::ExecutionFailed(
+            "cannot push from a detached HEAD".to_string(),
+        ));
+    }
+    Ok(branch.to_string())
+}
+
+/// Resolve a path argument inside the workspace and authorize reading it.
+async fn authorize_pathspec(ctx: &ToolContext, path: &str) -> Result<String, ToolError> {
+    let resolved = resolve_workspace_path(ctx, path, ResolveMode::AllowMissing)?;
+    ctx.authorize_path(&resolved, PathAccess::Read).await?;
+    ctx.check_access(&resolved, AccessMode::Read)?;
+    Ok(resolved.display().to_string())
+}
+
+/// Reject values git would parse as an option.
+fn ensure_not_option(field: &str, value: &str) -> Result<(), ToolError> {
+    if value.trim().is_empty() {
+        return Err(ToolError::InvalidArguments(format!(
+            "{field} cannot be empty"
+        )));
+    }
+    if value.starts_with('-') {
+        return Err(ToolError::InvalidArguments(format!(
+            "{field} cannot start with '-'"
+        )));
+    }
+    Ok(())
+}
+
+/// Parsed `git status --porcelain=v1 -z --branch` output.
+#[derive(Debug, Default, PartialEq)]
+struct GitStatus {
+    branch: Option<String>,
+    upstream: Option<String>,
+    ahead: u64,
+    behind: u64,
+    files: Vec<StatusEntry>,
+}
+
+/// One changed path from `git status`.
+#[derive(Debug, PartialEq)]
+struct StatusEntry {
+    path: String,
+    original_path: Option<String>,
+    index: char,
+    worktree: char,
+}
+
+impl GitStatus {
+    fn into_json(self) -> Value {
+        let files = self
+            .files
+            .iter()
+            .map(StatusEntry::to_json)
+            .collect::<Vec<_>>();
+        json!({
+            "branch": self.branch,
+            "upstream": self.upstream,
+            "ahead": self.ahead,
+            "behind": self.behind,
+            "clean": files.is_empty(),
+            "files": files,
+        })
+    }
+}
+
+impl StatusEntry {
+    /// Return true for unmerged paths (`U` on either side, or both added/deleted).
+    fn is_conflicted(&self) -> bool {
+        self.index == 'U'
+            || self.worktree == 'U'
+            || (self.index == 'A' && self.worktree == 'A')
+            || (self.index == 'D' && self.worktree == 'D')
+    }
+
+    /// Overall status, preferring the staged change when both sides changed.
+    fn status(&self) -> &'static str {
+        if self.is_conflicted() {
+            return "conflicted";
+        }
+        let code = if self.index == ' ' {
+            self.worktree
+        } else {
+            self.index
+        };
+        match code {
+            'M' => "modified",
+            'T' => "type_changed",
+            'A' => "added",
+            'D' => "deleted",
+            'R' => "renamed",
+            'C' => "copied",
+            '?' => "untracked",
+            '!' => "ignored",
+            _ => "unknown",
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let staged = !self.is_conflicted() && !matches!(self.index, ' ' | '?' | '!');
+        let mut entry = Map::new();
+        entry.insert("path".to_string(), json!(self.path));
+        if let Some(original_path) = &self.original_path {
+            entry.insert("original_path".to_string(), json!(original_path));
+        }
+        entry.insert("index".to_string(), json!(self.index.to_string()));
+        entry.insert("worktree".to_string(), json!(self.worktree.to_string()));
+        entry.insert("status".to_string(), json!(self.status()));
+        entry.insert("staged".to_string(), json!(staged));
+        Value::Object(entry)
+    }
+}
+
+/// Parse NUL-separated porcelain v1 status output.
+fn parse_status(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut records = output.split('\0').filter(|record| !record.is_empty());
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("## ") {
+            parse_branch_header(header, &mut status);
+            continue;
+        }
+        let mut codes = record.chars();
+        let (Some(index), Some(worktree)) = (codes.next(), codes.next()) else {
+            continue;
+        };
+        let Some(path) = record.get(3..) else {
+            continue;
+        };
+        // Renames and copies are followed by a record holding the source path.
+        let original_path = if matches!(index, 'R' | 'C') || matches!(worktree, 'R' | 'C') {
+            records.next().map(str::to_string)
+        } else {
+            None
+        };
+        status.files.push(StatusEntry {
+            path: path.to_string(),
+            original_path,
+            index,
+            worktree,
+        });
+    }
+    status
+}
+
+/// Parse the `## branch...upstream [ahead N, behind M]` header.
+fn parse_branch_header(header: &str, status: &mut GitStatus) {
+    if let Some(branch) = header
+        .strip_prefix("No commits yet on ")
+        .or_else(|| header.strip_prefix("Initial commit on "))
+    {
+        status.branch = Some(branch.to_string());
+        return;
+    }
+    if header.starts_with("HEAD (no branch)") {
+        return;
+    }
+    let (refs, tracking) = match header.split_once(" [") {
+        Some((refs, tracking)) => (refs, tracking.trim_end_matches(']')),
+        None => (header, ""),
+    };
+    match refs.split_once("...") {
+        Some((branch, upstream)) => {
+            status.branch = Some(branch.to_string());
+            status.upstream = Some(upstream.to_string());
+        }
+        None => status.branch = Some(refs.to_string()),
+    }
+    for part in tracking.split(", ") {
+        if let Some(count) = part.strip_prefix("ahead ") {
+            status.ahead = count.parse().unwrap_or_default();
+        } else if let Some(count) = part.strip_prefix("behind ") {
+            status.behind = count.parse().unwrap_or_default();
+        }
+    }
+}
+
+/// One file section of a unified diff.
+#[derive(Debug, Default, PartialEq)]
+struct DiffFile {
+    path: String,
+    old_path: Option<String>,
+    status: &'static str,
+    binary: bool,
+    additions: usize,
+    deletions: usize,
+    hunks: Vec<DiffHunk>,
+}
+
+/// One `@@` hunk with its raw lines (prefix included).
+#[derive(Debug, PartialEq)]
+struct DiffHunk {
+    header: String,
+    old_start: u64,
+    old_lines: u64,
+    new_start: u64,
+    new_lines: u64,
+    lines: Vec<String>,
+}
+
+impl DiffFile {
+    fn to_json(&self) -> Value {
+        let mut file = Map::new();
+        file.insert("path".to_string(), json!(self.path));
+        if let Some(old_path) = &self.old_path {
+            file.insert("old_path".to_string(), json!(old_path));
+        }
+        file.insert("status".to_string(), json!(self.status));
+        file.insert("binary".to_string(), json!(self.binary));
+        file.insert("additions".to_string(), json!(self.additions));
+        file.insert("deletions".to_string(), json!(self.deletions));
+        let hunks = self
+            .hunks
+            .iter()
+            .map(|hunk| {
+                json!({
+                    "header": hunk.header,
+                    "old_start": hunk.old_start,
+                    "old_lines": hunk.old_lines,
+                    "new_start": hunk.new_start,
+                    "new_lines": hunk.new_lines,
+                    "lines": hunk.lines,
+                })
+            })
+            .collect::<Vec<_>>();
+        file.insert("hunks".to_string(), Value::Array(hunks));
+        Value::Object(file)
+    }
+}
+
+/// Parse `git diff` patch output into per-file hunks.
+fn parse_diff(output: &str) -> Vec<DiffFile> {
+    let mut files: Vec<DiffFile> = Vec::new();
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            files.push(DiffFile {
+                path: header_path(header),
+                status: "modified",
+                ..DiffFile::default()
+            });
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        // Inside a hunk every line carries a prefix, so `--- x` is a removed line.
+        if let Some(hunk) = file.hunks.last_mut()
+            && let Some(prefix @ ('+' | '-' | ' ' | '\\')) = line.chars().next()
+        {
+            match prefix {
+                '+' => file.additions += 1,
+                '-' => file.deletions += 1,
+                _ => {}
+            }
+            hunk.lines.push(line.to_string());
+            continue;
+        }
+        if line.starts_with("@@ ") {
+            if let Some(hunk) = parse_hunk_header(line) {
+                file.hunks.push(hunk);
+            }
+        } else if line.starts_with("new file mode") {
+            file.status = "added";
+        } else if line.starts_with("deleted file mode") {
+            file.status = "deleted";
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            file.status = "renamed";
+            file.old_path = Some(unquote_path(path));
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            file.path = unquote_path(path);
+        } else if let Some(path) = line.strip_prefix("copy from ") {
+            file.status = "copied";
+            file.old_path = Some(unquote_path(path));
+        } else if let Some(path) = line.strip_prefix("copy to ") {
+            file.path = unquote_path(path);
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            if let Some(path) = strip_diff_prefix(path, "b/") {
+                file.path = path;
+            }
+        } else if let Some(path) = line.strip_prefix("--- ")
+            && let Some(path) = strip_diff_prefix(path, "a/")
+            && file.status == "deleted"
+        {
+            file.path = path;
+        }
+    }
+    files
+}
+
+/// Parse `@@ -old_start,old_lines +new_start,new_lines @@ context`.
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let ranges = line.strip_prefix("@@ ")?;
+    let (ranges, _) = ranges.split_once(" @@")?;
+    let (old, new) = ranges.split_once(' ')?;
+    let (old_start, old_lines) = parse_range(old.strip_prefix('-')?)?;
+    let (new_start, new_lines) = parse_range(new.strip_prefix('+')?)?;
+    Some(DiffHunk {
+        header: line.to_string(),
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        lines: Vec::new(),
+    })
+}
+
+/// Parse `start[,count]`; a missing count means one line.
+fn parse_range(range: &str) -> Option<(u64, u64)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// Path named by a `diff --git a/x b/x` header.
+///
+/// Used for sections without `---`/`+++` lines (binary or mode-only changes);
+/// both sides are the same there, so the header splits evenly.
+fn header_path(header: &str) -> String {
+    if let Some(quoted) = header.strip_prefix('"') {
+        let old = match quoted.find("\" ") {
+            Some(end) => &header[..end + 2],
+            None => header,
+        };
+        return strip_diff_prefix(old, "a/").unwrap_or_default();
+    }
+    let half = header.len() / 2;
+    if header.len() % 2 == 1
+        && header.is_char_boundary(half)
+        && let (Some(old), Some(new)) = (
+            header[..half].strip_prefix("a/"),
+            header[half + 1..].strip_prefix("b/"),
+        )
+        && old == new
+    {
+        return new.to_string();
+    }
+    header
+        .split_once(" b/")
+        .map(|(_, path)| path.to_string())
+        .unwrap_or_else(|| header.to_string())
+}
+
+/// Strip the `a/`/`b/` prefix from a diff path; `None` for `/dev/null`.
+fn strip_diff_prefix(path: &str, prefix: &str) -> Option<String> {
+    let path = unquote_path(path.trim_end_matches('\t'));
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).unwrap_or(&path).to_string())
+}
+
+/// Undo git's C-style quoting of unusual paths.
+fn unquote_path(path: &str) -> String {
+    let Some(inner) = path
+        .strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+    else {
+        return path.to_string();
+    };
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('n') => bytes.push(b'\n'),
+            Some('t') => bytes.push(b'\t'),
+            Some('r') => bytes.push(b'\r'),
+            Some(digit @ '0'..='7') => {
+                let mut value = digit.to_digit(8).unwrap_or_default();
+                for _ in 0..2 {
+                    let Some(next) = chars.clone().next().and_then(|next| next.to_digit(8)) else {
+                        break;
+                    };
+                    chars.next();
+                    value = value * 8 + next;
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => {
+                let mut buf = [0; 4];
+                bytes.extend_from_slice(other.encode_utf8(&mut buf).as_bytes());
+            }
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Arguments for GitStatusTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+#[serde(deny_unknown_fields)]
+struct GitStatusArgs {
+    #[input(description = "Optional path to limit the status to.")]
+    #[serde(default)]
+    path: Option<String>,
+}
+
+/// Arguments for GitDiffTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+#[serde(deny_unknown_fields)]
+struct GitDiffArgs {
+    #[input(description = "Optional path to limit the diff to.")]
+    #[serde(default)]
+    path: Option<String>,
+    #[input(description = "Show staged changes instead of unstaged ones.")]
+    #[serde(default)]
+    staged: bool,
+    #[input(
+        description = "Optional revision or range to diff against, such as HEAD~1 or main..HEAD."
+    )]
+    #[serde(default)]
+    revision: Option<String>,
+}
+
+/// Arguments for GitCommitTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+#[serde(deny_unknown_fields)]
+struct GitCommitArgs {
+    #[input(description = "Commit message.")]
+    message: String,
+    #[input(
+        description = "Paths to stage before committing; staged changes are committed as-is when empty."
+    )]
+    #[serde(default)]
+    paths: Vec<String>,
+    #[input(description = "Stage all changes, including untracked files, before committing.")]
+    #[serde(default)]
+    all: bool,
+    #[input(description = "Push the current branch after committing; needs git push approval.")]
+    #[serde(default)]
+    push: bool,
+    #[input(description = "Force-push with lease; needs force push approval.")]
+    #[serde(default)]
+    force: bool,
+    #[input(description = "Remote to push to (defaults to origin).")]
+    #[serde(default)]
+    remote: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        GitCommitTool, GitDiffTool, GitStatusTool, filter_overrides, parse_diff, parse_status,
+    };
+    use crate::{
+        PermissionChecker, PermissionContext, PermissionOutcome, Tool, ToolContext, ToolSandbox,
+    };
+    use async_trait::async_trait;
+    use odyssey_rs_protocol::{PermissionRequest, ToolError};
+    use odyssey_rs_sandbox::{
+        LocalSandboxProvider, SandboxContext, SandboxPolicy, SandboxProvider,
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::path::Path;
+    use std::process::Command;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    /// Allows everything except git pushes.
+    struct DenyPushPermissions;
+
+    #[async_trait]
+    impl PermissionChecker for DenyPushPermissions {
+        async fn authorize(
+            &self,
+            _ctx: &PermissionContext,
+            request: PermissionRequest,
+        ) -> Result<PermissionOutcome, ToolError> {
+            let denied = matches!(request, PermissionRequest::GitPush { .. });
+            Ok(PermissionOutcome {
+                allowed: !denied,
+                reason: denied.then(|| "push not allowed".to_string()),
+            })
+        }
+    }
+
+    /// Allows everything except commands.
+    struct DenyCommandPermissions;
+
+    #[async_trait]
+    impl PermissionChecker for DenyCommandPermissions {
+        async fn authorize(
+            &self,
+            _ctx: &PermissionContext,
+            request: PermissionRequest,
+        ) -> Result<PermissionOutcome, ToolError> {
+            let denied = matches!(request, PermissionRequest::Command { .. });
+            Ok(PermissionOutcome {
+                allowed: !denied,
+                reason: denied.then(|| "commands not allowed".to_string()),
+            })
+        }
+    }
+
+    fn git(root: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .status()
+            .expect("run git");
+        assert_eq!(status.success(), true);
+    }
+
+    fn init_repo(root: &Path) {
+        git(root, &["init", "-q", "-b", "main"]);
+        git(root, &["config", "user.name", "Test"]);
+        git(root, &["config", "user.email", "test@example.com"]);
+        git(root, &["config", "commit.gpgsign", "false"]);
+    }
+
+    async fn context_for_root(root: &Path) -> ToolContext {
+        context_with_permissions(root, Arc::new(DenyPushPermissions)).await
+    }
+
+    async fn context_with_permissions(
+        root: &Path,
+        permissions: Arc<dyn PermissionChecker>,
+    ) -> ToolContext {
+        let provider = LocalSandboxProvider::new();
+        let handle = provider
+            .prepare(&SandboxContext {
+                workspace_root: root.to_path_buf(),
+                mode: odyssey_rs_protocol::SandboxMode::WorkspaceWrite,
+                policy: SandboxPolicy::default(),
+            })
+            .await
+            .expect("prepare");
+        ToolContext::builder()
+            .with_workspace_root(root)
+            .with_sandbox(ToolSandbox {
+                provider: Arc::new(provider),
+                handle,
+                lease: None,
+            })
+            .with_permission_checker(permissions)
+            .build()
+    }
+
+    #[test]
+    fn parses_status_records() {
+        let output = "## main...origin/main [ahead 2, behind 1]\0M  src/lib.rs\0 M README.md\0R  new.rs\0old.rs\0?? notes.txt\0UU merge.rs\0";
+        let status = parse_status(output).into_json();
+        assert_eq!(
+            status,
+            json!({
+                "branch": "main",
+                "upstream": "origin/main",
+                "ahead": 2,
+                "behind": 1,
+                "clean": false,
+                "files": [
+                    {"path": "src/lib.rs", "index": "M", "worktree": " ", "status": "modified", "staged": true},
+                    {"path": "README.md", "index": " ", "worktree": "M", "status": "modified", "staged": false},
+                    {"path": "new.rs", "original_path": "old.rs", "index": "R", "worktree": " ", "status": "renamed", "staged": true},
+                    {"path": "notes.txt", "index": "?", "worktree": "?", "status": "untracked", "staged": false},
+                    {"path": "merge.rs", "index": "U", "worktree": "U", "status": "conflicted", "staged": false},
+                ],
+            })
+        );
+        let unborn = parse_status("## No commits yet on main\0").into_json();
+        assert_eq!(unborn["branch"], "main");
+        assert_eq!(unborn["clean"], true);
+    }
+
+    #[test]
+    fn parses_diff_files_and_hunks() {
+        let output = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@ fn main() {
+ keep
+--- removed line that looks like a header
++added
+diff --git a/gone.txt b/gone.txt
+deleted file mode 100644
+index 3333333..0000000
+--- a/gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+diff --git a/old name.rs b/new name.rs
+similarity index 100%
+rename from old name.rs
+rename to new name.rs
+diff --git a/logo.png b/logo.png
+new file mode 100644
+index 0000000..4444444
+Binary files /dev/null and b/logo.png differ
+";
+        let files = parse_diff(output);
+        let summary = files
+            .iter()
+            .map(|file| {
+                (
+                    file.path.as_str(),
+                    file.old_path.as_deref(),
+                    file.status,
+                    file.binary,
+                    file.additions,
+                    file.deletions,
+                    file.hunks.len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("src/lib.rs", None, "modified", false, 1, 1, 1),
+                ("gone.txt", None, "deleted", false, 0, 1, 1),
+                (
+                    "new name.rs",
+                    Some("old name.rs"),
+                    "renamed",
+                    false,
+                    0,
+                    0,
+                    0
+                ),
+                ("logo.png", None, "added", true, 0, 0, 0),
+            ]
+        );
+        let hunk = &files[0].hunks[0];
+        assert_eq!(
+            (
+                hunk.old_start,
+                hunk.old_lines,
+                hunk.new_start,
+                hunk.new_lines
+            ),
+            (1, 3, 1, 3)
+        );
+        assert_eq!(hunk.lines.len(), 3);
+        assert_eq!(
+            (files[1].hunks[0].new_start, files[1].hunks[0].new_lines),
+            (0, 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn commits_changes_and_guards_push() {
+        let workspace = tempdir().expect("workspace");
+        let root = workspace.path();
+        init_repo(root);
+        std::fs::write(root.join("a.txt"), "one\n").expect("write");
+        let ctx = context_for_root(root).await;
+
+        let status = GitStatusTool.call(&ctx, json!({})).await.expect("status");
+        assert_eq!(status["files"][0]["status"], "untracked");
+
+        let commit = GitCommitTool
+            .call(&ctx, json!({ "message": "add a", "all": true }))
+            .await
+            .expect("commit");
+        assert_eq!(commit["files"], json!(["a.txt"]));
+        assert_eq!(commit["commit"].as_str().map(str::len), Some(40));
+        assert_eq!(commit["pushed"], false);
+
+        std::fs::write(root.join("a.txt"), "one\ntwo\n").expect("write");
+        let diff = GitDiffTool.call(&ctx, json!({})).await.expect("diff");
+        assert_eq!(diff["additions"], 1);
+        assert_eq!(diff["files"][0]["path"], "a.txt");
+        assert_eq!(
+            diff["files"][0]["hunks"][0]["lines"],
+            json!([" one", "+two"])
+        );
+
+        let err = GitCommitTool
+            .call(
+                &ctx,
+                json!({ "message": "add two", "paths": ["a.txt"], "push": true }),
+            )
+            .await
+            .expect_err("push denied");
+        assert_eq!(matches!(err, ToolError::PermissionDenied(_)), true);
+        let status = GitStatusTool.call(&ctx, json!({})).await.expect("status");
+        assert_eq!(status["files"][0]["staged"], false);
+
+        let err = GitDiffTool
+            .call(&ctx, json!({ "revision": "--output=x" }))
+            .await
+            .expect_err("option revision");
+        assert_eq!(matches!(err, ToolError::InvalidArguments(_)), true);
+    }
+
+    #[tokio::test]
+    async fn repository_config_cannot_run_commands() {
+        let workspace = tempdir().expect("workspace");
+        let root = workspace.path();
+        init_repo(root);
+        git(root, &["config", "core.fsmonitor", "touch fsmonitor-ran"]);
+        git(
+            root,
+            &["config", "diff.evil.textconv", "touch textconv-ran; cat"],
+        );
+        std::fs::write(root.join(".gitattributes"), "a.txt diff=evil\n").expect("write");
+        let hook = root.join(".git").join("hooks").join("pre-commit");
+        std::fs::write(&hook, "#!/bin/sh\ntouch hook-ran\n").expect("write hook");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).expect("chmod");
+        }
+        std::fs::write(root.join("a.txt"), "one\n").expect("write");
+        let ctx = context_for_root(root).await;
+
+        GitStatusTool.call(&ctx, json!({})).await.expect("status");
+        GitCommitTool
+            .call(&ctx, json!({ "message": "add a", "all": true }))
+            .await
+            .expect("commit");
+        std::fs::write(root.join("a.txt"), "one\ntwo\n").expect("write");
+        GitDiffTool.call(&ctx, json!({})).await.expect("diff");
+        for marker in ["fsmonitor-ran", "textconv-ran", "hook-ran"] {
+            assert_eq!(root.join(marker).exists(), false, "{marker}");
+        }
+
+        let ctx = context_with_permissions(root, Arc::new(DenyCommandPermissions)).await;
+        let err = GitCommitTool
+            .call(&ctx, json!({ "message": "add two", "all": true }))
+            .await
+            .expect_err("commit denied");
+        assert_eq!(matches!(err, ToolError::PermissionDenied(_)), true);
+    }
+
+    #[tokio::test]
+    async fn attribute_filters_cannot_run_commands() {
+        let workspace = tempdir().expect("workspace");
+        let root = workspace.path();
+        init_repo(root);
+        git(
+            root,
+            &["config", "filter.evil.clean", "touch clean-ran; cat"],
+        );
+        git(root, &["config", "filter.evil.required", "true"]);
+        std::fs::write(root.join(".gitattributes"), "a.txt filter=evil\n").expect("write");
+        std::fs::write(root.join("a.txt"), "one\n").expect("write");
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "init"]);
+        std::fs::remove_file(root.join("clean-ran")).expect("remove marker");
+        let ctx = context_for_root(root).await;
+
+        // Same content with a new mtime makes status re-read the file through the filter.
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(3600);
+        std::fs::File::options()
+            .write(true)
+            .open(root.join("a.txt"))
+            .and_then(|file| file.set_modified(later))
+            .expect("touch");
+        let status = GitStatusTool.call(&ctx, json!({})).await.expect("status");
+        assert_eq!(status["files"], json!([]));
+        std::fs::write(root.join("a.txt"), "one\ntwo\n").expect("write");
+        let diff = GitDiffTool.call(&ctx, json!({})).await.expect("diff");
+        assert_eq!(diff["additions"], 1);
+        assert_eq!(root.join("clean-ran").exists(), false);
+
+        assert_eq!(
+            filter_overrides("filter.lfs.clean\ngit-lfs clean\0filter.lfs.process\nx\0"),
+            [
+                "-c",
+                "filter.lfs.clean=",
+                "-c",
+                "filter.lfs.smudge=",
+                "-c",
+                "filter.lfs.process=",
+                "-c",
+                "filter.lfs.required=false",
+            ]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Binary content detection and inline images for file tools.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::{Value, json};
+
+/// Bytes inspected when deciding whether content is text.
+pub(super) const SNIFF_BYTES: usize = 8 * 1024;
+
+/// File signatures and their MIME types.
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"\x7fELF", "application/x-elf"),
+    (b"\0asm", "application/wasm"),
+];
+
+/// Image types vision models accept inline.
+const INLINE_IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// MIME type of binary content, or `None` when the content is UTF-8 text.
+pub(super) fn binary_mime(bytes: &[u8]) -> Option<&'static str> {
+    if let Some(mime) = sniff_signature(bytes) {
+        return Some(mime);
+    }
+    is_binary(bytes).then_some("application/octet-stream")
+}
+
+/// Return true when images of this type can be sent to a vision model.
+pub(super) fn is_inline_image(mime: &str) -> bool {
+    INLINE_IMAGE_TYPES.contains(&mime)
+}
+
+/// Inline image attachment carried in a tool result.
+pub(super) fn image_attachment(mime: &str, bytes: &[u8]) -> Value {
+    json!({
+        "type": "image",
+        "mime_type": mime,
+        "data": STANDARD.encode(bytes),
+    })
+}
+
+fn sniff_signature(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    SIGNATURES
+        .iter()
+        .find(|(signature, _)| bytes.starts_with(signature))
+        .map(|(_, mime)| *mime)
+}
+
+/// Content is binary when it contains NUL bytes or is not valid UTF-8.
+fn is_binary(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(SNIFF_BYTES)];
+    if sample.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(sample) {
+        Ok(_) => false,
+        // No error length means a character was cut off at the end of the sample.
+        Err(err) => err.error_len().is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{binary_mime, is_inline_image};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn detects_binary_content() {
+        assert_eq!(binary_mime(b"plain text\n"), None);
+        assert_eq!(binary_mime("héllo".as_bytes()), None);
+        assert_eq!(binary_mime(b"\x89PNG\r\n\x1a\n\0\0"), Some("image/png"));
+        assert_eq!(binary_mime(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(binary_mime(b"%PDF-1.7\n"), Some("application/pdf"));
+        assert_eq!(binary_mime(b"ab\0cd"), Some("application/octet-stream"));
+        assert_eq!(
+            binary_mime(b"\xff\xfe\xfd"),
+            Some("application/octet-stream")
+        );
+        assert_eq!(is_inline_image("image/png"), true);
+        assert_eq!(is_inline_image("application/pdf"), false);
+    }
+
+    #[test]
+    fn keeps_text_cut_mid_character() {
+        let mut bytes = "a".repeat(super::SNIFF_BYTES - 1).into_bytes();
+        bytes.extend_from_slice("é".as_bytes());
+        assert_eq!(binary_mime(&bytes), None);
+    }
+}
@@ -1,22 +1,40 @@
 //! Built-in tools bundled with Odyssey.
 
+mod artifact;
 mod bash;
+mod cwd;
+mod download;
+mod env;
 mod filesystem;
+mod git;
+mod media;
+mod notebook;
+#[cfg(feature = "outline")]
+mod outline;
 mod question;
 mod skill;
 // mod task;
 mod utils;
+mod walk;
 mod web;
 
 use crate::ToolRegistry;
 use log::info;
 use std::sync::Arc;
 
+pub use artifact::{ArtifactTool, PublishArtifactTool};
 pub use bash::BashTool;
+pub use cwd::SetCwdTool;
+pub use download::DownloadTool;
+pub use env::EnvInfoTool;
 pub use filesystem::{EditTool, GlobTool, GrepTool, ReadTool, WriteTool};
+pub use git::{GitCommitTool, GitDiffTool, GitStatusTool};
+pub use notebook::{NotebookEditTool, NotebookReadTool};
+#[cfg(feature = "outline")]
+pub use outline::OutlineTool;
 pub use question::AskUserQuestionTool;
 pub use skill::SkillTool;
-pub use web::{WebFetchTool, WebSearchTool};
+pub use web::{HttpRequestTool, WebFetchTool, WebSearchTool};
 
 /// Register all built-in tools with the provided registry.
 pub fn register_builtin_tools(registry: &ToolRegistry) {
@@ -24,12 +42,25 @@ pub fn register_builtin_tools(registry: &ToolRegistry) {
     registry.register(Arc::new(WriteTool));
     registry.register(Arc::new(EditTool));
     registry.register(Arc::new(BashTool {}));
+    registry.register(Arc::new(SetCwdTool));
+    registry.register(Arc::new(EnvInfoTool));
     registry.register(Arc::new(GlobTool));
     registry.register(Arc::new(GrepTool));
+    #[cfg(feature = "outline")]
+    registry.register(Arc::new(OutlineTool));
+    registry.register(Arc::new(NotebookReadTool));
+    registry.register(Arc::new(NotebookEditTool));
+    registry.register(Arc::new(GitStatusTool));
+    registry.register(Arc::new(GitDiffTool));
+    registry.register(Arc::new(GitCommitTool));
     registry.register(Arc::new(WebSearchTool));
     registry.register(Arc::new(WebFetchTool));
+    registry.register(Arc::new(HttpRequestTool));
+    registry.register(Arc::new(DownloadTool));
     registry.register(Arc::new(AskUserQuestionTool));
     registry.register(Arc::new(SkillTool));
+    registry.register(Arc::new(PublishArtifactTool));
+    registry.register(Arc::new(ArtifactTool));
     // registry.register(Arc::new(TaskTool));
     info!("registered built-in tools");
 }
@@ -0,0 +1,631 @@
+//! Built-in Jupyter notebook tools (read/edit cells).
+//!
+//! Notebooks are edited as JSON so cell ids, metadata, and the nbformat
+//! version survive edits. Files are written back the way Jupyter writes them:
+//! sorted keys, one-space indentation, and a trailing newline.
+
+use crate::builtins::filesystem::WORKSPACE_WRITE_GROUP;
+use crate::builtins::utils::{
+    ResolveMode, content_hash, ensure_unchanged, io_error, parse_args, relative_display,
+    resolve_workspace_path,
+};
+use crate::{Tool, ToolContext};
+use async_trait::async_trait;
+use autoagents_core::tool::ToolInputT;
+use autoagents_derive::ToolInput;
+use log::info;
+use odyssey_rs_protocol::PathAccess;
+use odyssey_rs_protocol::{ToolError, ToolRisk};
+use odyssey_rs_sandbox::AccessMode;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::fs;
+use uuid::Uuid;
+
+/// Default maximum characters kept from each cell output.
+const DEFAULT_MAX_OUTPUT_CHARS: usize = 2_000;
+/// Cell types accepted by NotebookEdit.
+const CELL_TYPES: &[&str] = &["code", "markdown", "raw"];
+
+/// Tool for reading notebook cells.
+#[derive(Debug, Default)]
+pub struct NotebookReadTool;
+
+#[async_trait]
+impl Tool for NotebookReadTool {
+    fn name(&self) -> &str {
+        "NotebookRead"
+    }
+
+    fn description(&self) -> &str {
+        "Read the cells of a Jupyter notebook (.ipynb) with their indices and truncated outputs"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = NotebookReadArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn supports_parallel(&self) -> bool {
+        true
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let input: NotebookReadArgs = parse_args(args)?;
+        let path = resolve_workspace_path(ctx, &input.path, ResolveMode::Existing)?;
+        ctx.authorize_path(&path, PathAccess::Read).await?;
+        ctx.check_access(&path, AccessMode::Read)?;
+
+        let bytes = fs::read(&path)
+            .map_err(|err| io_error(&err, format!("failed to read {path:?}: {err}")))?;
+        let hash = content_hash(&bytes);
+        let notebook = parse_notebook(&bytes)?;
+        ctx.services
+            .file_versions
+            .record(path.clone(), hash.clone());
+
+        let max_output_chars = input.max_output_chars.unwrap_or(DEFAULT_MAX_OUTPUT_CHARS);
+        let cells = notebook_cells(&notebook)?
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| render_cell(index, cell, input.include_outputs, max_output_chars))
+            .collect::<Vec<_>>();
+        info!("read notebook (cells={})", cells.len());
+
+        Ok(json!({
+            "path": relative_display(&ctx.services.workspace_root, &path),
+            "language": notebook_language(&notebook),
+            "cells": cells,
+            "hash": hash,
+        }))
+    }
+}
+
+/// Tool for replacing, inserting, or deleting notebook cells.
+#[derive(Debug, Default)]
+pub struct NotebookEditTool;
+
+#[async_trait]
+impl Tool for NotebookEditTool {
+    fn name(&self) -> &str {
+        "NotebookEdit"
+    }
+
+    fn description(&self) -> &str {
+        "Replace, insert, or delete a cell in a Jupyter notebook (.ipynb)"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = NotebookEditArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn exclusion_group(&self) -> Option<&str> {
+        Some(WORKSPACE_WRITE_GROUP)
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::WorkspaceMutating
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let input: NotebookEditArgs = parse_args(args)?;
+        let edit = CellEdit::from_args(&input)?;
+        let path = resolve_workspace_path(ctx, &input.path, ResolveMode::Existing)?;
+        ctx.authorize_path(&path, PathAccess::Read).await?;
+        if !ctx.services.simulation {
+            ctx.authorize_path(&path, PathAccess::Write).await?;
+        }
+        ctx.check_access(&path, AccessMode::Read)?;
+        ctx.check_access(&path, AccessMode::Write)?;
+        let _exclusive = ctx.exclusive(self).await;
+
+        let bytes = fs::read(&path)
+            .map_err(|err| io_error(&err, format!("failed to read {path:?}: {err}")))?;
+        let previous_hash = content_hash(&bytes);
+        ensure_unchanged(ctx, &path, &previous_hash, input.expected_hash.as_deref())?;
+        let mut notebook = parse_notebook(&bytes)?;
+        apply_edit(&mut notebook, &edit)?;
+
+        if ctx.services.simulation {
+            info!("simulated notebook edit (action={})", input.action);
+            return Ok(json!({
+                "simulated": true,
+                "action": "notebook_edit",
+                "path": relative_display(&ctx.services.workspace_root, &path),
+                "edit": input.action,
+                "index": input.index,
+            }));
+        }
+
+        let updated = serialize_notebook(&notebook)?;
+        fs::write(&path, updated.as_bytes())
+            .map_err(|err| ToolError::ExecutionFailed(format!("failed to write file: {err}")))?;
+        let hash = content_hash(updated.as_bytes());
+        ctx.services
+            .file_versions
+            .record(path.clone(), hash.clone());
+        let cell_count = notebook_cells(&notebook)?.len();
+        info!(
+            "edited notebook (action={}, index={}, cells={})",
+            input.action, input.index, cell_count
+        );
+
+        Ok(json!({
+            "path": relative_display(&ctx.services.workspace_root, &path),
+            "edit": input.action,
+            "index": input.index,
+            "cells": cell_count,
+            "previous_hash": previous_hash,
+            "hash": hash,
+        }))
+    }
+}
+
+/// A validated cell-level edit.
+#[derive(Debug)]
+enum CellEdit {
+    /// Replace a cell's source, optionally changing its type.
+    Replace {
+        index: usize,
+        source: String,
+        cell_type: Option<String>,
+    },
+    /// Insert a new cell before `index` (or at the end when `index` equals the cell count).
+    Insert {
+        index: usize,
+        source: String,
+        cell_type: String,
+    },
+    /// Delete a cell.
+    Delete { index: usize },
+}
+
+impl CellEdit {
+    fn from_args(args: &NotebookEditArgs) -> Result<Self, ToolError> {
+        if let Some(cell_type) = &args.cell_type
+            && !CELL_TYPES.contains(&cell_type.as_str())
+        {
+            return Err(ToolError::InvalidArguments(format!(
+                "unknown cell_type {cell_type:?}; expected code, markdown, or raw"
+            )));
+        }
+        let source = || {
+            args.source.clone().ok_or_else(|| {
+                ToolError::InvalidArguments(format!("source is required for {}", args.action))
+            })
+        };
+        match args.action.as_str() {
+            "replace" => Ok(CellEdit::Replace {
+                index: args.index,
+                source: source()?,
+                cell_type: args.cell_type.clone(),
+            }),
+            "insert" => Ok(CellEdit::Insert {
+                index: args.index,
+                source: source()?,
+                cell_type: args.cell_type.clone().unwrap_or_else(|| "code".to_string()),
+            }),
+            "delete" => Ok(CellEdit::Delete { index: args.index }),
+            other => Err(ToolError::InvalidArguments(format!(
+                "unknown action {other:?}; expected replace, insert, or delete"
+            ))),
+        }
+    }
+}
+
+/// Parse notebook JSON, requiring a top-level `cells` array.
+fn parse_notebook(bytes: &[u8]) -> Result<Value, ToolError> {
+    let notebook: Value = serde_json::from_slice(bytes)
+        .map_err(|err| ToolError::ExecutionFailed(format!("invalid notebook JSON: {err}")))?;
+    notebook_cells(&notebook)?;
+    Ok(notebook)
+}
+
+fn notebook_cells(notebook: &Value) -> Result<&Vec<Value>, ToolError> {
+    notebook
+        .get("cells")
+        .and_then(Value::as_array)
+        .ok_or_else(|| ToolError::ExecutionFailed("notebook has no cells array".to_string()))
+}
+
+fn notebook_cells_mut(notebook: &mut Value) -> Result<&mut Vec<Value>, ToolError> {
+    notebook
+        .get_mut("cells")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| ToolError::ExecutionFailed("notebook has no cells array".to_string()))
+}
+
+/// Kernel language from the notebook metadata, if recorded.
+fn notebook_language(notebook: &Value) -> Option<&str> {
+    let metadata = notebook.get("metadata")?;
+    metadata
+        .pointer("/language_info/name")
+        .or_else(|| metadata.pointer("/kernelspec/language"))
+        .and_then(Value::as_str)
+}
+
+/// Cell as presented to the agent: index, type, source, and outputs as text.
+fn render_cell(
+    index: usize,
+    cell: &Value,
+    include_outputs: bool,
+    max_output_chars: usize,
+) -> Value {
+    let mut rendered = json!({
+        "index": index,
+        "cell_type": cell.get("cell_type").and_then(Value::as_str).unwrap_or("unknown"),
+        "source": multiline_text(cell.get("source")),
+    });
+    if let Some(count) = cell.get("execution_count").filter(|count| !count.is_null()) {
+        rendered["execution_count"] = count.clone();
+    }
+    if include_outputs
+        && let Some(outputs) = cell.get("outputs").and_then(Value::as_array)
+        && !outputs.is_empty()
+    {
+        rendered["outputs"] = outputs
+            .iter()
+            .map(|output| render_output(output, max_output_chars))
+            .collect();
+    }
+    rendered
+}
+
+/// Output reduced to its type and plain text; rich data is named, not inlined.
+fn render_output(output: &Value, max_chars: usize) -> Value {
+    let output_type = output
+        .get("output_type")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    let text = match output_type {
+        "stream" => multiline_text(output.get("text")),
+        "error" => {
+            let name = output
+                .get("ename")
+                .and_then(Value::as_str)
+                .unwrap_or("Error");
+            let value = output.get("evalue").and_then(Value::as_str).unwrap_or("");
+            format!("{name}: {value}")
+        }
+        _ => match output.get("data").and_then(Value::as_object) {
+            Some(data) => match data.get("text/plain") {
+                Some(text) => multiline_text(Some(text)),
+                None => data
+                    .keys()
+                    .map(|mime| format!("[{mime} output]"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            },
+            None => String::new(),
+        },
+    };
+    json!({
+        "output_type": output_type,
+        "text": truncate_chars(&text, max_chars),
+    })
+}
+
+/// Join nbformat multiline text (a string or a list of lines).
+fn multiline_text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+        Some(_) | None => String::new(),
+    }
+}
+
+/// Split text into nbformat source lines, keeping line endings.
+fn source_lines(source: &str) -> Value {
+    source.split_inclusive('\n').collect()
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => {
+            let omitted = text[end..].chars().count();
+            format!("{}... [{omitted} more chars]", &text[..end])
+        }
+        None => text.to_string(),
+    }
+}
+
+/// Apply an edit to the notebook's cells, leaving notebook metadata untouched.
+fn apply_edit(notebook: &mut Value, edit: &CellEdit) -> Result<(), ToolError> {
+    let needs_ids = cells_need_ids(notebook);
+    let cells = notebook_cells_mut(notebook)?;
+    let len = cells.len();
+    let out_of_range = |index: usize| {
+        ToolError::InvalidArguments(format!(
+            "cell index {index} is out of range (notebook has {len} cells)"
+        ))
+    };
+    match edit {
+        CellEdit::Replace {
+            index,
+            source,
+            cell_type,
+        } => {
+            let cell = cells
+                .get_mut(*index)
+                .and_then(Value::as_object_mut)
+                .ok_or_else(|| out_of_range(*index))?;
+            if let Some(cell_type) = cell_type {
+                cell.insert("cell_type".to_string(), json!(cell_type));
+            }
+            cell.insert("source".to_string(), source_lines(source));
+            reset_execution_state(cell);
+        }
+        CellEdit::Insert {
+            index,
+            source,
+            cell_type,
+        } => {
+            if *index > len {
+                return Err(out_of_range(*index));
+            }
+            let mut cell = Map::new();
+            cell.insert("cell_type".to_string(), json!(cell_type));
+            cell.insert("metadata".to_string(), json!({}));
+            cell.insert("source".to_string(), source_lines(source));
+            if needs_ids {
+                let id = Uuid::new_v4().simple().to_string();
+                cell.insert("id".to_string(), json!(&id[..8]));
+            }
+            reset_execution_state(&mut cell);
+            cells.insert(*index, Value::Object(cell));
+        }
+        CellEdit::Delete { index } => {
+            if *index >= len {
+                return Err(out_of_range(*index));
+            }
+            cells.remove(*index);
+        }
+    }
+    Ok(())
+}
+
+/// Cells carry ids from nbformat 4.5 on.
+fn cells_need_ids(notebook: &Value) -> bool {
+    let major = notebook
+        .get("nbformat")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    let minor = notebook
+        .get("nbformat_minor")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    major > 4 || (major == 4 && minor >= 5)
+}
+
+/// Clear outputs of a code cell whose source changed; other cell types have none.
+fn reset_execution_state(cell: &mut Map<String, Value>) {
+    if cell.get("cell_type").and_then(Value::as_str) == Some("code") {
+        cell.insert("execution_count".to_string(), Value::Null);
+        cell.insert("outputs".to_string(), json!([]));
+    } else {
+        cell.remove("execution_count");
+        cell.remove("outputs");
+    }
+}
+
+/// Serialize like Jupyter: one-space indentation and a trailing newline.
+fn serialize_notebook(notebook: &Value) -> Result<String, ToolError> {
+    let mut buffer = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    notebook
+        .serialize(&mut serializer)
+        .map_err(|err| ToolError::ExecutionFailed(format!("failed to encode notebook: {err}")))?;
+    buffer.push(b'\n');
+    String::from_utf8(buffer)
+        .map_err(|err| ToolError::ExecutionFailed(format!("failed to encode notebook: {err}")))
+}
+
+/// Arguments for NotebookReadTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+struct NotebookReadArgs {
+    #[input(description = "Path to the .ipynb file to read.")]
+    path: String,
+    #[input(description = "Include cell outputs (as truncated text).")]
+    #[serde(default = "default_include_outputs")]
+    include_outputs: bool,
+    #[input(description = "Maximum characters kept from each cell output.")]
+    #[serde(default)]
+    max_output_chars: Option<usize>,
+}
+
+/// Outputs are included unless the caller opts out.
+fn default_include_outputs() -> bool {
+    true
+}
+
+/// Arguments for NotebookEditTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+struct NotebookEditArgs {
+    #[input(description = "Path to the .ipynb file to edit.")]
+    path: String,
+    #[input(description = "Edit to apply: replace, insert, or delete.")]
+    action: String,
+    #[input(
+        description = "Cell index from NotebookRead; insert places the new cell before it (use the cell count to append)."
+    )]
+    index: usize,
+    #[input(description = "New cell source for replace and insert.")]
+    #[serde(default)]
+    source: Option<String>,
+    #[input(description = "Cell type: code, markdown, or raw (insert defaults to code).")]
+    #[serde(default)]
+    cell_type: Option<String>,
+    #[input(
+        description = "Hash of the notebook as last read; the edit fails if the file has changed since."
+    )]
+    #[serde(default)]
+    expected_hash: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NotebookEditTool, NotebookReadTool};
+    use crate::{Tool, ToolContext};
+    use odyssey_rs_protocol::ToolError;
+    use pretty_assertions::assert_eq;
+    use serde_json::{Value, json};
+    use tempfile::tempdir;
+
+    fn context_for_root(root: &std::path::Path) -> ToolContext {
+        ToolContext::builder().with_workspace_root(root).build()
+    }
+
+    fn sample_notebook() -> Value {
+        json!({
+            "cells": [
+                {
+                    "cell_type": "markdown",
+                    "id": "intro",
+                    "metadata": {},
+                    "source": ["# Title\n", "Notes"]
+                },
+                {
+                    "cell_type": "code",
+                    "execution_count": 3,
+                    "id": "calc",
+                    "metadata": { "tags": ["keep"] },
+                    "outputs": [
+                        { "output_type": "stream", "name": "stdout", "text": ["0123456789\n"] },
+                        {
+                            "output_type": "display_data",
+                            "data": { "image/png": "iVBORw0KGgo=" },
+                            "metadata": {}
+                        }
+                    ],
+                    "source": ["print(1)\n", "plot()"]
+                }
+            ],
+            "metadata": { "kernelspec": { "language": "python", "name": "python3" } },
+            "nbformat": 4,
+            "nbformat_minor": 5
+        })
+    }
+
+    #[tokio::test]
+    async fn notebook_read_lists_cells_with_truncated_outputs() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("nb.ipynb"), sample_notebook().to_string()).expect("write");
+        let ctx = context_for_root(temp.path());
+
+        let result = NotebookReadTool
+            .call(&ctx, json!({ "path": "nb.ipynb", "max_output_chars": 4 }))
+            .await
+            .expect("read");
+
+        assert_eq!(result["language"], "python");
+        assert_eq!(
+            result["cells"],
+            json!([
+                { "index": 0, "cell_type": "markdown", "source": "# Title\nNotes" },
+                {
+                    "index": 1,
+                    "cell_type": "code",
+                    "source": "print(1)\nplot()",
+                    "execution_count": 3,
+                    "outputs": [
+                        { "output_type": "stream", "text": "0123... [7 more chars]" },
+                        { "output_type": "display_data", "text": "[ima... [14 more chars]" }
+                    ]
+                }
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn notebook_edit_replaces_inserts_and_deletes_cells() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("nb.ipynb");
+        std::fs::write(&path, sample_notebook().to_string()).expect("write");
+        let ctx = context_for_root(temp.path());
+        let tool = NotebookEditTool;
+
+        tool.call(
+            &ctx,
+            json!({ "path": "nb.ipynb", "action": "replace", "index": 1, "source": "x = 2\nx" }),
+        )
+        .await
+        .expect("replace");
+        tool.call(
+            &ctx,
+            json!({ "path": "nb.ipynb", "action": "insert", "index": 2, "source": "Done", "cell_type": "markdown" }),
+        )
+        .await
+        .expect("insert");
+        let result = tool
+            .call(
+                &ctx,
+                json!({ "path": "nb.ipynb", "action": "delete", "index": 0 }),
+            )
+            .await
+            .expect("delete");
+        assert_eq!(result["cells"], 2);
+
+        let written = std::fs::read_to_string(&path).expect("read");
+        assert!(written.starts_with("{\n \"cells\": ["));
+        assert!(written.ends_with("}\n"));
+        let notebook: Value = serde_json::from_str(&written).expect("json");
+        assert_eq!(notebook["metadata"], sample_notebook()["metadata"]);
+        assert_eq!(notebook["nbformat_minor"], 5);
+        let cells = notebook["cells"].as_array().expect("cells");
+        assert_eq!(cells[0]["id"], "calc");
+        assert_eq!(cells[0]["metadata"], json!({ "tags": ["keep"] }));
+        assert_eq!(cells[0]["source"], json!(["x = 2\n", "x"]));
+        assert_eq!(cells[0]["outputs"], json!([]));
+        assert_eq!(cells[0]["execution_count"], Value::Null);
+        assert_eq!(cells[1]["cell_type"], "markdown");
+        assert_eq!(cells[1]["source"], json!(["Done"]));
+        assert_eq!(cells[1]["id"].as_str().map(str::len), Some(8));
+        assert_eq!(cells[1].get("outputs"), None);
+    }
+
+    #[tokio::test]
+    async fn notebook_edit_rejects_bad_edits() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("nb.ipynb"), sample_notebook().to_string()).expect("write");
+        let ctx = context_for_root(temp.path());
+        let tool = NotebookEditTool;
+
+        let err = tool
+            .call(
+                &ctx,
+                json!({ "path": "nb.ipynb", "action": "delete", "index": 2 }),
+            )
+            .await
+            .expect_err("out of range");
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+        let err = tool
+            .call(
+                &ctx,
+                json!({ "path": "nb.ipynb", "action": "insert", "index": 0 }),
+            )
+            .await
+            .expect_err("missing source");
+        assert_eq!(
+            err.to_string(),
+            "invalid arguments: source is required for insert"
+        );
+        let err = tool
+            .call(
+                &ctx,
+                json!({ "path": "nb.ipynb", "action": "replace", "index": 0, "source": "x", "cell_type": "sql" }),
+            )
+            .await
+            .expect_err("bad cell type");
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+    }
+}
@@ -0,0 +1,492 @@
+//! Built-in tool that outlines source files with tree-sitter grammars.
+
+use crate::builtins::utils::{ResolveMode, parse_args, relative_display, resolve_workspace_path};
+use crate::{Tool, ToolContext};
+use async_trait::async_trait;
+use autoagents_core::tool::ToolInputT;
+use autoagents_derive::ToolInput;
+use log::{debug, info};
+use odyssey_rs_protocol::{PathAccess, ToolError, ToolRisk};
+use odyssey_rs_sandbox::AccessMode;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+use walkdir::WalkDir;
+
+/// Default maximum number of files outlined for a directory.
+const DEFAULT_MAX_FILES: usize = 100;
+/// Files larger than this are skipped.
+const MAX_OUTLINE_FILE_BYTES: u64 = 1_000_000;
+/// Longest signature line reported for a symbol.
+const MAX_SIGNATURE_CHARS: usize = 160;
+
+/// Tool for listing the symbols defined in a file or directory.
+#[derive(Debug, Default)]
+pub struct OutlineTool;
+
+#[async_trait]
+impl Tool for OutlineTool {
+    fn name(&self) -> &str {
+        "Outline"
+    }
+
+    fn description(&self) -> &str {
+        "List the functions, classes, types, and impls (with line ranges) defined in a source file or directory without reading the full contents"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = OutlineArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn supports_parallel(&self) -> bool {
+        true
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let input: OutlineArgs = parse_args(args)?;
+        let path = resolve_workspace_path(ctx, &input.path, ResolveMode::Existing)?;
+        ctx.authorize_path(&path, PathAccess::Read).await?;
+        ctx.check_access(&path, AccessMode::Read)?;
+        let root = &ctx.services.workspace_root;
+
+        if path.is_file() {
+            let language = OutlineLanguage::from_path(&path).ok_or_else(|| {
+                ToolError::InvalidArguments(format!(
+                    "no outline grammar for {}",
+                    relative_display(root, &path)
+                ))
+            })?;
+            let source = read_source(&path)?;
+            let symbols = outline_source(language, &source)?;
+            info!("outlined file (symbols={})", symbols.len());
+            return Ok(json!({
+                "path": relative_display(root, &path),
+                "language": language.name(),
+                "symbols": symbols,
+            }));
+        }
+
+        let max_files = input.max_files.unwrap_or(DEFAULT_MAX_FILES);
+        let mut files = Vec::new();
+        let mut skipped = 0;
+        let mut truncated = false;
+        let entries = WalkDir::new(&path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| entry.depth() == 0 || !is_hidden(entry.file_name()));
+        for entry in entries {
+            let entry = entry.map_err(|err| {
+                ToolError::ExecutionFailed(format!("failed to walk directory: {err}"))
+            })?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Some(language) = OutlineLanguage::from_path(entry.path()) else {
+                continue;
+            };
+            if files.len() >= max_files {
+                truncated = true;
+                break;
+            }
+            ctx.check_access(entry.path(), AccessMode::Read)?;
+            let too_large = entry
+                .metadata()
+                .map(|metadata| metadata.len() > MAX_OUTLINE_FILE_BYTES)
+                .unwrap_or(true);
+            let outlined = if too_large {
+                None
+            } else {
+                read_source(entry.path())
+                    .and_then(|source| outline_source(language, &source))
+                    .ok()
+            };
+            let Some(symbols) = outlined else {
+                debug!("skipped file in outline (too_large={too_large})");
+                skipped += 1;
+                continue;
+            };
+            files.push(json!({
+                "path": relative_display(root, entry.path()),
+                "language": language.name(),
+                "symbols": symbols,
+            }));
+        }
+        info!(
+            "outlined directory (files={}, skipped={skipped}, truncated={truncated})",
+            files.len()
+        );
+        Ok(json!({
+            "path": relative_display(root, &path),
+            "files": files,
+            "skipped": skipped,
+            "truncated": truncated,
+        }))
+    }
+}
+
+/// Languages with a bundled grammar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutlineLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+impl OutlineLanguage {
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        match extension {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+            Self::Tsx => "tsx",
+            Self::Go => "go",
+        }
+    }
+
+    fn grammar(self) -> tree_sitter::Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    /// Symbol kind for a node, or `None` when the node is not a definition.
+    fn symbol_kind(self, node: Node<'_>) -> Option<SymbolKind> {
+        let kind = match (self, node.kind()) {
+            (Self::Rust, "function_item" | "function_signature_item") => SymbolKind::Function,
+            (Self::Rust, "struct_item") => SymbolKind::Struct,
+            (Self::Rust, "enum_item") => SymbolKind::Enum,
+            (Self::Rust, "union_item") => SymbolKind::Union,
+            (Self::Rust, "trait_item") => SymbolKind::Trait,
+            (Self::Rust, "impl_item") => SymbolKind::Impl,
+            (Self::Rust, "mod_item") => SymbolKind::Module,
+            (Self::Rust, "const_item") => SymbolKind::Const,
+            (Self::Rust, "static_item") => SymbolKind::Static,
+            (Self::Rust, "type_item") => SymbolKind::Type,
+            (Self::Rust, "macro_definition") => SymbolKind::Macro,
+            (Self::Python, "function_definition") => SymbolKind::Function,
+            (Self::Python, "class_definition") => SymbolKind::Class,
+            (
+                Self::JavaScript | Self::TypeScript | Self::Tsx,
+                "function_declaration" | "generator_function_declaration" | "function_signature",
+            ) => SymbolKind::Function,
+            (
+                Self::JavaScript | Self::TypeScript | Self::Tsx,
+                "class_declaration" | "abstract_class_declaration",
+            ) => SymbolKind::Class,
+            (
+                Self::JavaScript | Self::TypeScript | Self::Tsx,
+                "method_definition" | "method_signature" | "abstract_method_signature",
+            ) => SymbolKind::Method,
+            (Self::JavaScript | Self::TypeScript | Self::Tsx, "variable_declarator") => {
+                let value = node.child_by_field_name("value")?;
+                match value.kind() {
+                    "arrow_function" | "function_expression" | "function" => SymbolKind::Function,
+                    "class" => SymbolKind::Class,
+                    _ => return None,
+                }
+            }
+            (Self::TypeScript | Self::Tsx, "interface_declaration") => SymbolKind::Interface,
+            (Self::TypeScript | Self::Tsx, "type_alias_declaration") => SymbolKind::Type,
+            (Self::TypeScript | Self::Tsx, "enum_declaration") => SymbolKind::Enum,
+            (Self::TypeScript | Self::Tsx, "internal_module" | "module") => SymbolKind::Module,
+            (Self::Go, "function_declaration") => SymbolKind::Function,
+            (Self::Go, "method_declaration") => SymbolKind::Method,
+            (Self::Go, "type_spec") => match node.child_by_field_name("type")?.kind() {
+                "struct_type" => SymbolKind::Struct,
+                "interface_type" => SymbolKind::Interface,
+                _ => SymbolKind::Type,
+            },
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+/// Kind of outlined symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Union,
+    Interface,
+    Trait,
+    Impl,
+    Module,
+    Type,
+    Const,
+    Static,
+    Macro,
+}
+
+impl SymbolKind {
+    /// Whether nested definitions are listed as children.
+    fn is_container(self) -> bool {
+        matches!(
+            self,
+            Self::Class | Self::Interface | Self::Trait | Self::Impl | Self::Module
+        )
+    }
+}
+
+/// Symbol in a file outline.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct OutlineSymbol {
+    kind: SymbolKind,
+    name: String,
+    /// First line of the definition, trimmed.
+    signature: String,
+    /// 1-based first line.
+    start_line: usize,
+    /// 1-based last line.
+    end_line: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<OutlineSymbol>,
+}
+
+fn read_source(path: &Path) -> Result<String, ToolError> {
+    let bytes = fs::read(path)
+        .map_err(|err| ToolError::ExecutionFailed(format!("failed to read file: {err}")))?;
+    String::from_utf8(bytes)
+        .map_err(|_| ToolError::ExecutionFailed("file is not valid UTF-8".to_string()))
+}
+
+/// Parse `source` and return its top-level symbols with nested members.
+fn outline_source(
+    language: OutlineLanguage,
+    source: &str,
+) -> Result<Vec<OutlineSymbol>, ToolError> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language.grammar())
+        .map_err(|err| ToolError::ExecutionFailed(format!("failed to load grammar: {err}")))?;
+    let tree = parser
+        .parse(source, None)
+        .ok_or_else(|| ToolError::ExecutionFailed("failed to parse file".to_string()))?;
+    let mut symbols = Vec::new();
+    collect_symbols(language, tree.root_node(), source, None, &mut symbols);
+    Ok(symbols)
+}
+
+/// Collect definitions below `node`; function bodies are not searched.
+fn collect_symbols(
+    language: OutlineLanguage,
+    node: Node<'_>,
+    source: &str,
+    container: Option<SymbolKind>,
+    symbols: &mut Vec<OutlineSymbol>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let Some(kind) = language.symbol_kind(child) else {
+            collect_symbols(language, child, source, container, symbols);
+            continue;
+        };
+        let mut children = Vec::new();
+        if kind.is_container() {
+            collect_symbols(language, child, source, Some(kind), &mut children);
+        }
+        let kind = match (kind, container) {
+            (
+                SymbolKind::Function,
+                Some(
+                    SymbolKind::Class
+                    | SymbolKind::Interface
+                    | SymbolKind::Trait
+                    | SymbolKind::Impl,
+                ),
+            ) => SymbolKind::Method,
+            (kind, _) => kind,
+        };
+        symbols.push(OutlineSymbol {
+            kind,
+            name: symbol_name(language, child, source),
+            signature: signature(child, source),
+            start_line: child.start_position().row + 1,
+            end_line: child.end_position().row + 1,
+            children,
+        });
+    }
+}
+
+fn symbol_name(language: OutlineLanguage, node: Node<'_>, source: &str) -> String {
+    let field = |name: &str| {
+        node.child_by_field_name(name)
+            .and_then(|field| field.utf8_text(source.as_bytes()).ok())
+    };
+    match (language, node.kind()) {
+        (OutlineLanguage::Rust, "impl_item") => {
+            let target = field("type").unwrap_or_default();
+            match field("trait") {
+                Some(trait_name) => format!("{trait_name} for {target}"),
+                None => target.to_string(),
+            }
+        }
+        (OutlineLanguage::Go, "method_declaration") => {
+            let receiver = field("receiver")
+                .and_then(|receiver| receiver.trim_matches(['(', ')']).split_whitespace().last())
+                .map(|receiver| receiver.trim_start_matches('*'));
+            let name = field("name").unwrap_or_default();
+            match receiver {
+                Some(receiver) => format!("{receiver}.{name}"),
+                None => name.to_string(),
+            }
+        }
+        _ => field("name").unwrap_or("<anonymous>").to_string(),
+    }
+}
+
+fn signature(node: Node<'_>, source: &str) -> String {
+    let text = node.utf8_text(source.as_bytes()).unwrap_or_default();
+    let line = text.lines().next().unwrap_or_default().trim();
+    match line.char_indices().nth(MAX_SIGNATURE_CHARS) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+fn is_hidden(name: &std::ffi::OsStr) -> bool {
+    name.to_str().is_some_and(|name| name.starts_with('.'))
+}
+
+/// Arguments for OutlineTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+#[serde(deny_unknown_fields)]
+struct OutlineArgs {
+    #[input(description = "File or directory to outline.")]
+    path: String,
+    #[input(description = "Maximum number of files to outline for a directory (defaults to 100).")]
+    #[serde(default)]
+    max_files: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutlineLanguage, OutlineTool, SymbolKind, outline_source};
+    use crate::{Tool, ToolContext};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn outlines_rust_items_with_nested_methods() {
+        let source = "pub struct Foo;\n\nimpl Display for Foo {\n    fn fmt(&self) {\n        fn inner() {}\n    }\n}\n";
+        let symbols = outline_source(OutlineLanguage::Rust, source).expect("outline");
+
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].kind, SymbolKind::Struct);
+        assert_eq!(symbols[1].name, "Display for Foo");
+        assert_eq!((symbols[1].start_line, symbols[1].end_line), (3, 7));
+        let methods = &symbols[1].children;
+        assert_eq!(methods.len(), 1);
+        assert_eq!(methods[0].kind, SymbolKind::Method);
+        assert_eq!(methods[0].signature, "fn fmt(&self) {");
+        assert_eq!(methods[0].children.is_empty(), true);
+    }
+
+    #[test]
+    fn outlines_python_classes_and_decorated_methods() {
+        let source =
+            "class A:\n    @property\n    def p(self):\n        return 1\n\ndef top():\n    pass\n";
+        let symbols = outline_source(OutlineLanguage::Python, source).expect("outline");
+
+        assert_eq!(symbols[0].kind, SymbolKind::Class);
+        assert_eq!(symbols[0].children[0].kind, SymbolKind::Method);
+        assert_eq!(symbols[0].children[0].name, "p");
+        assert_eq!(symbols[1].kind, SymbolKind::Function);
+        assert_eq!(symbols[1].name, "top");
+    }
+
+    #[tokio::test]
+    async fn outline_walks_supported_files_in_directory() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::create_dir_all(temp.path().join("src")).expect("mkdir");
+        std::fs::create_dir_all(temp.path().join(".hidden")).expect("mkdir");
+        std::fs::write(
+            temp.path().join("src/main.go"),
+            "package main\nfunc main() {}\n",
+        )
+        .expect("write");
+        std::fs::write(temp.path().join("src/app.ts"), "export interface App {}\n").expect("write");
+        std::fs::write(temp.path().join("src/notes.txt"), "text").expect("write");
+        std::fs::write(temp.path().join(".hidden/x.rs"), "fn x() {}").expect("write");
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .build();
+
+        let result = OutlineTool
+            .call(&ctx, json!({ "path": "." }))
+            .await
+            .expect("outline");
+
+        assert_eq!(
+            result["files"],
+            json!([
+                {
+                    "path": "src/app.ts",
+                    "language": "typescript",
+                    "symbols": [{
+                        "kind": "interface",
+                        "name": "App",
+                        "signature": "interface App {}",
+                        "start_line": 1,
+                        "end_line": 1,
+                    }],
+                },
+                {
+                    "path": "src/main.go",
+                    "language": "go",
+                    "symbols": [{
+                        "kind": "function",
+                        "name": "main",
+                        "signature": "func main() {}",
+                        "start_line": 2,
+                        "end_line": 2,
+                    }],
+                },
+            ])
+        );
+        assert_eq!(result["truncated"], false);
+
+        let err = OutlineTool
+            .call(&ctx, json!({ "path": "src/notes.txt" }))
+            .await
+            .expect_err("unsupported");
+        assert_eq!(
+            matches!(err, odyssey_rs_protocol::ToolError::InvalidArguments(_)),
+            true
+        );
+    }
+}
@@ -7,7 +7,7 @@ use async_trait::async_trait;
 use autoagents_core::tool::ToolInputT;
 use autoagents_derive::ToolInput;
 use log::info;
-use odyssey_rs_protocol::ToolError;
+use odyssey_rs_protocol::{ToolError, ToolRisk};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 
@@ -30,6 +30,10 @@ impl Tool for AskUserQuestionTool {
         serde_json::from_str(params_str).expect("Error parsing tool parameters")
     }
 
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
+
     async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
         let input: AskUserQuestionArgs = parse_args(args)?;
         if input.prompt.trim().is_empty() {
@@ -91,14 +95,13 @@ struct AskUserQuestionArgs {
 mod tests {
     use super::AskUserQuestionTool;
     use crate::question::{Question, QuestionAnswer, QuestionHandler};
-    use crate::{Tool, ToolContext, TurnServices};
+    use crate::{Tool, ToolContext};
     use async_trait::async_trait;
     use odyssey_rs_protocol::ToolError;
     use pretty_assertions::assert_eq;
     use serde_json::json;
     use std::sync::Arc;
     use tempfile::tempdir;
-    use uuid::Uuid;
 
     struct DummyHandler;
 
@@ -118,25 +121,7 @@ mod tests {
     }
 
     fn base_context(root: &std::path::Path) -> ToolContext {
-        ToolContext {
-            session_id: Uuid::nil(),
-            agent_id: "agent".to_string(),
-            turn_id: None,
-            tool_call_id: None,
-            tool_name: None,
-            services: Arc::new(TurnServices {
-                cwd: root.to_path_buf(),
-                workspace_root: root.to_path_buf(),
-                output_policy: None,
-                sandbox: None,
-                web: None,
-                event_sink: None,
-                skill_provider: None,
-                question_handler: None,
-                permission_checker: None,
-                tool_result_handler: None,
-            }),
-        }
+        ToolContext::builder().with_workspace_root(root).build()
     }
 
     #[tokio::test]
@@ -230,21 +215,10 @@ mod tests {
     #[tokio::test]
     async fn ask_user_returns_answer() {
         let temp = tempdir().expect("tempdir");
-        let ctx = ToolContext {
-            services: Arc::new(TurnServices {
-                cwd: temp.path().to_path_buf(),
-                workspace_root: temp.path().to_path_buf(),
-                output_policy: None,
-                sandbox: None,
-                web: None,
-                event_sink: None,
-                skill_provider: None,
-                question_handler: Some(Arc::new(DummyHandler)),
-                permission_checker: None,
-                tool_result_handler: None,
-            }),
-            ..base_context(temp.path())
-        };
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .with_question_handler(Arc::new(DummyHandler))
+            .build();
         let tool = AskUserQuestionTool;
         let result = tool
             .call(
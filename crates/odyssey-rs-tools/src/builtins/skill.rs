@@ -6,7 +6,7 @@ use async_trait::async_trait;
 use autoagents_core::tool::ToolInputT;
 use autoagents_derive::ToolInput;
 use log::info;
-use odyssey_rs_protocol::ToolError;
+use odyssey_rs_protocol::{ToolError, ToolRisk};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 
@@ -37,6 +37,10 @@ impl Tool for SkillTool {
         serde_json::from_str(params_str).expect("Error parsing tool parameters")
     }
 
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ReadOnly
+    }
+
     async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
         let Some(provider) = ctx.services.skill_provider.as_ref() else {
             return Err(ToolError::ExecutionFailed(
@@ -72,7 +76,7 @@ impl Tool for SkillTool {
 #[cfg(test)]
 mod tests {
     use super::SkillTool;
-    use crate::{Tool, ToolContext, TurnServices};
+    use crate::{Tool, ToolContext};
     use async_trait::async_trait;
     use odyssey_rs_protocol::{SkillProvider, SkillSummary, ToolError};
     use pretty_assertions::assert_eq;
@@ -80,7 +84,6 @@ mod tests {
     use std::path::PathBuf;
     use std::sync::Arc;
     use tempfile::tempdir;
-    use uuid::Uuid;
 
     #[derive(Default)]
     struct DummySkillProvider {
@@ -99,25 +102,7 @@ mod tests {
     }
 
     fn base_context(root: &std::path::Path) -> ToolContext {
-        ToolContext {
-            session_id: Uuid::nil(),
-            agent_id: "agent".to_string(),
-            turn_id: None,
-            tool_call_id: None,
-            tool_name: None,
-            services: Arc::new(TurnServices {
-                cwd: root.to_path_buf(),
-                workspace_root: root.to_path_buf(),
-                output_policy: None,
-                sandbox: None,
-                web: None,
-                event_sink: None,
-                skill_provider: None,
-                question_handler: None,
-                permission_checker: None,
-                tool_result_handler: None,
-            }),
-        }
+        ToolContext::builder().with_workspace_root(root).build()
     }
 
     #[tokio::test]
@@ -142,21 +127,10 @@ mod tests {
                 path: PathBuf::from("/tmp/alpha.md"),
             }],
         };
-        let ctx = ToolContext {
-            services: Arc::new(TurnServices {
-                cwd: temp.path().to_path_buf(),
-                workspace_root: temp.path().to_path_buf(),
-                output_policy: None,
-                sandbox: None,
-                web: None,
-                event_sink: None,
-                skill_provider: Some(Arc::new(provider)),
-                question_handler: None,
-                permission_checker: None,
-                tool_result_handler: None,
-            }),
-            ..base_context(temp.path())
-        };
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .with_skill_provider(Arc::new(provider))
+            .build();
         let tool = SkillTool;
         let result = tool.call(&ctx, json!({})).await.expect("list");
         let skills = result["skills"].as_array().expect("skills");
@@ -168,21 +142,10 @@ mod tests {
     async fn skill_tool_loads_content() {
         let temp = tempdir().expect("tempdir");
         let provider = DummySkillProvider::default();
-        let ctx = ToolContext {
-            services: Arc::new(TurnServices {
-                cwd: temp.path().to_path_buf(),
-                workspace_root: temp.path().to_path_buf(),
-                output_policy: None,
-                sandbox: None,
-                web: None,
-                event_sink: None,
-                skill_provider: Some(Arc::new(provider)),
-                question_handler: None,
-                permission_checker: None,
-                tool_result_handler: None,
-            }),
-            ..base_context(temp.path())
-        };
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .with_skill_provider(Arc::new(provider))
+            .build();
         let tool = SkillTool;
         let result = tool
             .call(&ctx, json!({ "name": "alpha" }))
@@ -1,7 +1,9 @@
 //! Utility helpers shared by built-in tools.
 
 use crate::ToolContext;
+pub(super) use crate::context::{ContentHasher, content_hash};
 use odyssey_rs_protocol::ToolError;
+use odyssey_rs_sandbox::has_symlink_below;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::ffi::OsString;
@@ -21,7 +23,7 @@ pub(super) fn parse_args<T: DeserializeOwned>(args: Value) -> Result<T, ToolErro
     serde_json::from_value(args).map_err(|err| ToolError::InvalidArguments(err.to_string()))
 }
 
-/// Resolve a workspace-relative path and validate it.
+/// Resolve a path relative to the session cwd and validate it stays in the workspace.
 pub(super) fn resolve_workspace_path(
     ctx: &ToolContext,
     input: &str,
@@ -33,11 +35,66 @@ pub(super) fn resolve_workspace_path(
         ));
     }
     let root = &ctx.services.workspace_root;
-    let resolved = normalize_relative_path(root, input)?;
+    let resolved = normalize_relative_path(root, &ctx.cwd(), input)?;
+    if !ctx.services.follow_symlinks && has_symlink_below(&resolved, root) {
+        return Err(ToolError::PermissionDenied(
+            "path traverses a symlink and symlinks are not followed".to_string(),
+        ));
+    }
     ensure_within_root(root, &resolved, mode)?;
     Ok(resolved)
 }
 
+/// Tool error for a failed filesystem operation, categorized by the I/O error kind.
+pub(super) fn io_error(err: &std::io::Error, message: String) -> ToolError {
+    match err.kind() {
+        std::io::ErrorKind::NotFound => ToolError::NotFound(message),
+        std::io::ErrorKind::PermissionDenied => ToolError::PermissionDenied(message),
+        std::io::ErrorKind::TimedOut => ToolError::Timeout(message),
+        std::io::ErrorKind::Interrupted | std::io::ErrorKind::WouldBlock => {
+            ToolError::Transient(message)
+        }
+        _ => ToolError::ExecutionFailed(message),
+    }
+}
+
+/// Reject a write when the file no longer matches the version the agent saw.
+///
+/// `expected` comes from the tool arguments; otherwise the hash recorded by
+/// the last Read/Write/Edit in the session is used. Files the agent never saw
+/// are not checked.
+pub(super) fn ensure_unchanged(
+    ctx: &ToolContext,
+    path: &Path,
+    current: &str,
+    expected: Option<&str>,
+) -> Result<(), ToolError> {
+    let expected = match expected {
+        Some(expected) => Some(expected.to_string()),
+        None if ctx.services.file_versions.is_partial(path) => {
+            return Err(ToolError::Conflict(
+                "only part of the file was read; read it in full before editing".to_string(),
+            ));
+        }
+        None => ctx.services.file_versions.get(path),
+    };
+    match expected {
+        Some(expected) if expected != current => Err(ToolError::Conflict(format!(
+            "file changed since it was last read (expected hash {expected}, found {current}); read it again before editing"
+        ))),
+        Some(_) | None => Ok(()),
+    }
+}
+
+/// Unified diff between two versions of a file.
+pub(super) fn unified_diff(display_path: &str, before: &str, after: &str) -> String {
+    similar::TextDiff::from_lines(before, after)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{display_path}"), &format!("b/{display_path}"))
+        .to_string()
+}
+
 /// Format a path relative to a root for display.
 pub(super) fn relative_display(root: &Path, path: &Path) -> String {
     path.strip_prefix(root)
@@ -46,8 +103,10 @@ pub(super) fn relative_display(root: &Path, path: &Path) -> String {
         .to_string()
 }
 
-/// Normalize a relative path while preventing root escape.
-fn normalize_relative_path(root: &Path, input: &str) -> Result<PathBuf, ToolError> {
+/// Normalize a path relative to `cwd` while preventing root escape.
+///
+/// A cwd outside the workspace is ignored and paths resolve from the root.
+fn normalize_relative_path(root: &Path, cwd: &Path, input: &str) -> Result<PathBuf, ToolError> {
     let path = Path::new(input);
     if path.is_absolute() {
         return Err(ToolError::InvalidArguments(
@@ -55,7 +114,18 @@ fn normalize_relative_path(root: &Path, input: &str) -> Result<PathBuf, ToolErro
         ));
     }
 
-    let mut parts: Vec<OsString> = Vec::new();
+    let mut parts: Vec<OsString> = cwd
+        .strip_prefix(root)
+        .map(|relative| {
+            relative
+                .components()
+                .filter_map(|component| match component {
+                    Component::Normal(part) => Some(part.to_os_string()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
     for component in path.components() {
         match component {
             Component::Normal(part) => parts.push(part.to_os_string()),
@@ -83,15 +153,18 @@ fn normalize_relative_path(root: &Path, input: &str) -> Result<PathBuf, ToolErro
 }
 
 /// Ensure a resolved path stays within the workspace root.
+///
+/// Symlinks are resolved before comparing, so links pointing outside the
+/// workspace are rejected even though their own location is inside it.
 fn ensure_within_root(root: &Path, path: &Path, mode: ResolveMode) -> Result<(), ToolError> {
     let root = root.canonicalize().map_err(|err| {
         ToolError::ExecutionFailed(format!("failed to resolve workspace root: {err}"))
     })?;
 
     let target = match mode {
-        ResolveMode::Existing => path.canonicalize().map_err(|err| {
-            ToolError::ExecutionFailed(format!("failed to resolve path {path:?}: {err}"))
-        })?,
+        ResolveMode::Existing => path
+            .canonicalize()
+            .map_err(|err| io_error(&err, format!("failed to resolve path {path:?}: {err}")))?,
         ResolveMode::AllowMissing => {
             let existing = find_existing_parent(path).ok_or_else(|| {
                 ToolError::ExecutionFailed("path has no existing parent".to_string())
@@ -112,10 +185,13 @@ fn ensure_within_root(root: &Path, path: &Path, mode: ResolveMode) -> Result<(),
 }
 
 /// Find the nearest existing parent path for a non-existent target.
+///
+/// Dangling symlinks count as existing so that canonicalizing them fails
+/// instead of letting a write follow the link outside the workspace.
 fn find_existing_parent(path: &Path) -> Option<&Path> {
     let mut current = Some(path);
     while let Some(candidate) = current {
-        if candidate.exists() {
+        if candidate.symlink_metadata().is_ok() {
             return Some(candidate);
         }
         current = candidate.parent();
@@ -126,35 +202,22 @@ fn find_existing_parent(path: &Path) -> Option<&Path> {
 #[cfg(test)]
 mod tests {
     use super::{ResolveMode, parse_args, relative_display, resolve_workspace_path};
-    use crate::{ToolContext, TurnServices};
+    use crate::ToolContext;
     use odyssey_rs_protocol::ToolError;
     use pretty_assertions::assert_eq;
     use serde::Deserialize;
     use std::path::{Path, PathBuf};
-    use std::sync::Arc;
     use tempfile::tempdir;
-    use uuid::Uuid;
 
     fn context_for_root(root: &Path) -> ToolContext {
-        ToolContext {
-            session_id: Uuid::nil(),
-            agent_id: "agent".to_string(),
-            turn_id: None,
-            tool_call_id: None,
-            tool_name: None,
-            services: Arc::new(TurnServices {
-                cwd: root.to_path_buf(),
-                workspace_root: root.to_path_buf(),
-                output_policy: None,
-                sandbox: None,
-                web: None,
-                event_sink: None,
-                skill_provider: None,
-                question_handler: None,
-                permission_checker: None,
-                tool_result_handler: None,
-            }),
-        }
+        context_with_symlinks(root, true)
+    }
+
+    fn context_with_symlinks(root: &Path, follow_symlinks: bool) -> ToolContext {
+        ToolContext::builder()
+            .with_workspace_root(root)
+            .with_follow_symlinks(follow_symlinks)
+            .build()
     }
 
     #[test]
@@ -205,6 +268,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn resolve_workspace_path_uses_session_cwd() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::create_dir_all(temp.path().join("crates/core")).expect("mkdir");
+        let ctx = context_for_root(temp.path());
+        ctx.services.cwd.set(temp.path().join("crates"));
+
+        let resolved = resolve_workspace_path(&ctx, "core/lib.rs", ResolveMode::AllowMissing)
+            .expect("resolved");
+        assert_eq!(resolved, temp.path().join("crates/core/lib.rs"));
+        let resolved =
+            resolve_workspace_path(&ctx, "../README.md", ResolveMode::AllowMissing).expect("up");
+        assert_eq!(resolved, temp.path().join("README.md"));
+        let err = resolve_workspace_path(&ctx, "../../outside", ResolveMode::AllowMissing)
+            .expect_err("escape");
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+    }
+
     #[test]
     fn relative_display_prefers_relative_path() {
         let root = PathBuf::from("/workspace");
@@ -246,4 +327,48 @@ mod tests {
             other => panic!("unexpected error: {other:?}"),
         }
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_workspace_path_blocks_symlink_escape() {
+        let temp = tempdir().expect("tempdir");
+        let outside = tempdir().expect("tempdir");
+        std::fs::write(outside.path().join("secret.txt"), "secret").expect("write");
+        std::os::unix::fs::symlink(outside.path(), temp.path().join("escape")).expect("symlink");
+        std::os::unix::fs::symlink(
+            outside.path().join("missing.txt"),
+            temp.path().join("dangling"),
+        )
+        .expect("symlink");
+        let ctx = context_for_root(temp.path());
+
+        let err = resolve_workspace_path(&ctx, "escape/secret.txt", ResolveMode::Existing)
+            .expect_err("error");
+        assert!(matches!(err, ToolError::PermissionDenied(_)));
+        let err = resolve_workspace_path(&ctx, "escape/new.txt", ResolveMode::AllowMissing)
+            .expect_err("error");
+        assert!(matches!(err, ToolError::PermissionDenied(_)));
+        assert!(resolve_workspace_path(&ctx, "dangling", ResolveMode::AllowMissing).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_workspace_path_respects_follow_symlinks() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::create_dir(temp.path().join("src")).expect("mkdir");
+        std::fs::write(temp.path().join("src/lib.rs"), "").expect("write");
+        std::os::unix::fs::symlink(temp.path().join("src"), temp.path().join("alias"))
+            .expect("symlink");
+
+        let ctx = context_for_root(temp.path());
+        let resolved =
+            resolve_workspace_path(&ctx, "alias/lib.rs", ResolveMode::Existing).expect("resolved");
+        assert_eq!(resolved, temp.path().join("alias/lib.rs"));
+
+        let ctx = context_with_symlinks(temp.path(), false);
+        let err =
+            resolve_workspace_path(&ctx, "alias/lib.rs", ResolveMode::Existing).expect_err("error");
+        assert!(matches!(err, ToolError::PermissionDenied(_)));
+        resolve_workspace_path(&ctx, "src/lib.rs", ResolveMode::Existing).expect("resolved");
+    }
 }
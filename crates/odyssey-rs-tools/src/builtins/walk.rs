@@ -0,0 +1,73 @@
+//! Parallel workspace walking shared by Glob and Grep.
+
+use ignore::{WalkBuilder, WalkState};
+use log::warn;
+use odyssey_rs_protocol::ToolError;
+use parking_lot::Mutex;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+
+/// Visit regular files under `root` on a thread pool.
+///
+/// The walk honors `.gitignore` (inside or outside a git repository), `.ignore`,
+/// and skips hidden paths. `visit` runs on worker threads and returns a result
+/// per file, or `None` to skip it; `collect` receives results on the calling
+/// thread in completion order and returns false to stop the walk. Unreadable
+/// entries are skipped; the first `visit` error stops the walk and is returned.
+pub(super) fn walk_files<T, V, C>(root: &Path, visit: V, mut collect: C) -> Result<(), ToolError>
+where
+    T: Send,
+    V: Fn(&Path) -> Result<Option<T>, ToolError> + Sync,
+    C: FnMut(T) -> bool,
+{
+    let walker = WalkBuilder::new(root).require_git(false).build_parallel();
+    let quit = AtomicBool::new(false);
+    let failure = Mutex::new(None);
+    std::thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        let (visit, quit, failure) = (&visit, &quit, &failure);
+        scope.spawn(move || {
+            walker.run(|| {
+                let sender = sender.clone();
+                Box::new(move |entry| {
+                    if quit.load(Ordering::Relaxed) {
+                        return WalkState::Quit;
+                    }
+                    let entry = match entry {
+                        Ok(entry) => entry,
+                        Err(err) => {
+                            warn!("skipping unreadable path: {err}");
+                            return WalkState::Continue;
+                        }
+                    };
+                    if !entry.file_type().is_some_and(|kind| kind.is_file()) {
+                        return WalkState::Continue;
+                    }
+                    match visit(entry.path()) {
+                        Ok(Some(item)) => match sender.send(item) {
+                            Ok(()) => WalkState::Continue,
+                            Err(_) => WalkState::Quit,
+                        },
+                        Ok(None) => WalkState::Continue,
+                        Err(err) => {
+                            failure.lock().get_or_insert(err);
+                            quit.store(true, Ordering::Relaxed);
+                            WalkState::Quit
+                        }
+                    }
+                })
+            });
+        });
+        for item in receiver {
+            if !collect(item) {
+                quit.store(true, Ordering::Relaxed);
+                break;
+            }
+        }
+    });
+    match failure.into_inner() {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
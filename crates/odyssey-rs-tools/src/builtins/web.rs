@@ -1,19 +1,27 @@
-//! Built-in tools for web search and fetch.
+//! Built-in tools for web search, fetch, and HTTP requests.
 
 use crate::builtins::utils::parse_args;
-use crate::{Tool, ToolContext};
+use crate::{HttpRequestSpec, Tool, ToolContext, ToolDeltaSink};
 use async_trait::async_trait;
 use autoagents_core::tool::ToolInputT;
 use autoagents_derive::ToolInput;
 use log::info;
-use odyssey_rs_protocol::ToolError;
+use odyssey_rs_protocol::{ToolError, ToolRisk};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 /// Default search result limit.
 const DEFAULT_SEARCH_LIMIT: usize = 5;
 /// Default max bytes for fetch output.
 const DEFAULT_MAX_FETCH_BYTES: usize = 50_000;
+/// Default timeout for HTTP requests.
+const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 30;
+/// Longest timeout an HTTP request may ask for.
+const MAX_HTTP_TIMEOUT_SECS: u64 = 300;
+/// Methods accepted by HttpRequest.
+const HTTP_METHODS: &[&str] = &["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
 
 /// Tool for web search queries.
 #[derive(Debug, Default)]
@@ -38,6 +46,10 @@ impl Tool for WebSearchTool {
         true
     }
 
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ExternalNetwork
+    }
+
     async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
         let input: WebSearchArgs = parse_args(args)?;
         if input.query.trim().is_empty() {
@@ -55,7 +67,14 @@ impl Tool for WebSearchTool {
             input.query.len(),
             limit
         );
-        let results = provider.search(&input.query, limit).await?;
+        let results = match ctx.web_api_key()? {
+            Some(api_key) => {
+                provider
+                    .search_with_key(&input.query, limit, &api_key)
+                    .await?
+            }
+            None => provider.search(&input.query, limit).await?,
+        };
         Ok(json!({
             "query": input.query,
             "results": results,
@@ -86,13 +105,137 @@ impl Tool for WebFetchTool {
         true
     }
 
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ExternalNetwork
+    }
+
     async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
-        let input: WebFetchArgs = parse_args(args)?;
-        if input.url.trim().is_empty() {
-            return Err(ToolError::InvalidArguments(
-                "url cannot be empty".to_string(),
-            ));
+        run_web_fetch(ctx, args, None).await
+    }
+
+    async fn call_streaming(
+        &self,
+        ctx: &ToolContext,
+        args: Value,
+        sink: &dyn ToolDeltaSink,
+    ) -> Result<Value, ToolError> {
+        run_web_fetch(ctx, args, Some(sink)).await
+    }
+}
+
+/// Fetch a URL, optionally pushing body chunks to a tool delta sink.
+async fn run_web_fetch(
+    ctx: &ToolContext,
+    args: Value,
+    deltas: Option<&dyn ToolDeltaSink>,
+) -> Result<Value, ToolError> {
+    let input: WebFetchArgs = parse_args(args)?;
+    if input.url.trim().is_empty() {
+        return Err(ToolError::InvalidArguments(
+            "url cannot be empty".to_string(),
+        ));
+    }
+    let provider = ctx
+        .services
+        .web
+        .as_ref()
+        .ok_or_else(|| ToolError::ExecutionFailed("web provider not configured".to_string()))?;
+    let max_bytes = input.max_bytes.unwrap_or_else(|| {
+        ctx.services
+            .output_policy
+            .as_ref()
+            .map(|policy| policy.max_string_bytes)
+            .unwrap_or(DEFAULT_MAX_FETCH_BYTES)
+    });
+    info!(
+        "web fetch (url_len={}, max_bytes={})",
+        input.url.len(),
+        max_bytes
+    );
+    let result = match deltas {
+        Some(deltas) => {
+            let on_chunk = |chunk: &str| deltas.push(json!({ "text": chunk }));
+            provider
+                .fetch_streaming(&input.url, max_bytes, &on_chunk)
+                .await?
+        }
+        None => provider.fetch(&input.url, max_bytes).await?,
+    };
+    Ok(json!({
+        "url": result.url,
+        "status": result.status,
+        "content_type": result.content_type,
+        "body": result.body,
+        "truncated": result.truncated,
+    }))
+}
+
+/// Tool for sending HTTP requests to allowed hosts.
+#[derive(Debug, Default)]
+pub struct HttpRequestTool;
+
+#[async_trait]
+impl Tool for HttpRequestTool {
+    fn name(&self) -> &str {
+        "HttpRequest"
+    }
+
+    fn description(&self) -> &str {
+        "Send an HTTP request with a method, headers, and body to an allowed host and return the status, headers, and body"
+    }
+
+    fn args_schema(&self) -> Value {
+        let params_str = HttpRequestArgs::io_schema();
+        serde_json::from_str(params_str).expect("Error parsing tool parameters")
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::ExternalNetwork
+    }
+
+    async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError> {
+        let input: HttpRequestArgs = parse_args(args)?;
+        let method = input
+            .method
+            .as_deref()
+            .unwrap_or("GET")
+            .to_ascii_uppercase();
+        if !HTTP_METHODS.contains(&method.as_str()) {
+            return Err(ToolError::InvalidArguments(format!(
+                "unsupported method: {method}"
+            )));
+        }
+        if matches!(method.as_str(), "GET" | "HEAD") && input.body.is_some() {
+            return Err(ToolError::InvalidArguments(format!(
+                "{method} requests cannot have a body"
+            )));
+        }
+        let host = url_host(&input.url)?;
+        let headers = request_headers(input.headers)?;
+        let timeout_secs = input.timeout_secs.unwrap_or(DEFAULT_HTTP_TIMEOUT_SECS);
+        if timeout_secs == 0 || timeout_secs > MAX_HTTP_TIMEOUT_SECS {
+            return Err(ToolError::InvalidArguments(format!(
+                "timeout_secs must be between 1 and {MAX_HTTP_TIMEOUT_SECS}"
+            )));
+        }
+        ctx.check_network(&host)?;
+
+        if ctx.services.simulation {
+            info!("simulated http request (method={method})");
+            return Ok(json!({
+                "simulated": true,
+                "action": "http_request",
+                "method": method,
+                "url": input.url,
+                "headers": headers.keys().collect::<Vec<_>>(),
+                "body_bytes": input.body.as_ref().map_or(0, String::len),
+            }));
         }
+
         let provider =
             ctx.services.web.as_ref().ok_or_else(|| {
                 ToolError::ExecutionFailed("web provider not configured".to_string())
@@ -105,21 +248,92 @@ impl Tool for WebFetchTool {
                 .unwrap_or(DEFAULT_MAX_FETCH_BYTES)
         });
         info!(
-            "web fetch (url_len={}, max_bytes={})",
-            input.url.len(),
-            max_bytes
+            "http request (method={method}, url_len={}, max_bytes={max_bytes})",
+            input.url.len()
         );
-        let result = provider.fetch(&input.url, max_bytes).await?;
+        let response = provider
+            .request(HttpRequestSpec {
+                method: method.clone(),
+                url: input.url.clone(),
+                headers,
+                body: input.body,
+                timeout: Duration::from_secs(timeout_secs),
+                max_bytes,
+            })
+            .await?;
         Ok(json!({
-            "url": result.url,
-            "status": result.status,
-            "content_type": result.content_type,
-            "body": result.body,
-            "truncated": result.truncated,
+            "method": method,
+            "url": input.url,
+            "status": response.status,
+            "headers": response.headers,
+            "content_type": response.content_type,
+            "body": response.body,
+            "truncated": response.truncated,
         }))
     }
 }
 
+/// Lowercase host of an http(s) URL, without credentials or port.
+///
+/// URLs that parsers disagree on (backslashes, percent-encoded or non-ASCII
+/// hosts) are rejected so the checked host is the one the client contacts.
+pub(super) fn url_host(url: &str) -> Result<String, ToolError> {
+    let invalid = || ToolError::InvalidArguments(format!("invalid url: {url}"));
+    let url = url.trim();
+    if url.is_empty() {
+        return Err(ToolError::InvalidArguments(
+            "url cannot be empty".to_string(),
+        ));
+    }
+    let (scheme, rest) = url.split_once("://").ok_or_else(invalid)?;
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return Err(ToolError::InvalidArguments(format!(
+            "unsupported url scheme: {scheme}"
+        )));
+    }
+    if url.contains('\\') {
+        return Err(invalid());
+    }
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host_port)| host_port);
+    let host = match host_port.strip_prefix('[') {
+        Some(bracketed) => bracketed.split_once(']').ok_or_else(invalid)?.0,
+        None => host_port
+            .split_once(':')
+            .map_or(host_port, |(host, _)| host),
+    };
+    let valid = !host.is_empty()
+        && host
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '.' | '_' | ':'));
+    if !valid {
+        return Err(invalid());
+    }
+    Ok(host.to_ascii_lowercase())
+}
+
+/// Collect request headers, rejecting names or values that could split the request.
+fn request_headers(headers: Vec<HttpHeader>) -> Result<BTreeMap<String, String>, ToolError> {
+    let mut collected = BTreeMap::new();
+    for header in headers {
+        let name = header.name.trim();
+        if name.is_empty() || name.contains([':', ' ', '\r', '\n']) {
+            return Err(ToolError::InvalidArguments(format!(
+                "invalid header name: {name:?}"
+            )));
+        }
+        if header.value.contains(['\r', '\n']) {
+            return Err(ToolError::InvalidArguments(format!(
+                "header {name} contains a line break"
+            )));
+        }
+        collected.insert(name.to_string(), header.value);
+    }
+    Ok(collected)
+}
+
 /// Arguments for WebSearchTool.
 #[derive(Debug, Serialize, Deserialize, ToolInput)]
 struct WebSearchArgs {
@@ -140,26 +354,65 @@ struct WebFetchArgs {
     max_bytes: Option<usize>,
 }
 
+/// Single request header for HttpRequestTool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HttpHeader {
+    /// Header name.
+    name: String,
+    /// Header value.
+    value: String,
+}
+
+/// Arguments for HttpRequestTool.
+#[derive(Debug, Serialize, Deserialize, ToolInput)]
+#[serde(deny_unknown_fields)]
+struct HttpRequestArgs {
+    #[input(
+        description = "HTTP method: GET, HEAD, POST, PUT, PATCH, DELETE, or OPTIONS (defaults to GET)."
+    )]
+    #[serde(default)]
+    method: Option<String>,
+    #[input(description = "http or https URL to request.")]
+    url: String,
+    #[input(description = "Request headers as name/value pairs.")]
+    #[serde(default)]
+    headers: Vec<HttpHeader>,
+    #[input(description = "Request body, for methods other than GET and HEAD.")]
+    #[serde(default)]
+    body: Option<String>,
+    #[input(description = "Seconds to wait for the response (defaults to 30, at most 300).")]
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    #[input(description = "Maximum bytes to return from the response body.")]
+    #[serde(default)]
+    max_bytes: Option<usize>,
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{WebFetchTool, WebSearchTool};
+    use super::{HttpRequestTool, WebFetchTool, WebSearchTool, url_host};
     use crate::{
-        Tool, ToolContext, ToolOutputPolicy, TurnServices, WebFetchResult, WebProvider,
-        WebSearchResult,
+        HttpRequestSpec, HttpResponse, SecretResolver, Tool, ToolContext, ToolDeltaSink,
+        ToolOutputPolicy, ToolSandbox, WebFetchResult, WebProvider, WebSearchResult,
     };
     use async_trait::async_trait;
     use odyssey_rs_protocol::ToolError;
+    use odyssey_rs_sandbox::{
+        LocalSandboxProvider, SandboxContext, SandboxNetworkPolicy, SandboxPolicy, SandboxProvider,
+    };
     use parking_lot::Mutex;
     use pretty_assertions::assert_eq;
     use serde_json::json;
+    use std::collections::BTreeMap;
     use std::sync::Arc;
     use tempfile::tempdir;
-    use uuid::Uuid;
 
     #[derive(Default)]
     struct DummyWebProvider {
         last_search: Mutex<Option<(String, usize)>>,
+        last_api_key: Mutex<Option<String>>,
         last_fetch: Mutex<Option<(String, usize)>>,
+        last_request: Mutex<Option<HttpRequestSpec>>,
     }
 
     #[async_trait]
@@ -177,6 +430,16 @@ mod tests {
             }])
         }
 
+        async fn search_with_key(
+            &self,
+            query: &str,
+            limit: usize,
+            api_key: &str,
+        ) -> Result<Vec<WebSearchResult>, ToolError> {
+            *self.last_api_key.lock() = Some(api_key.to_string());
+            self.search(query, limit).await
+        }
+
         async fn fetch(&self, url: &str, max_bytes: usize) -> Result<WebFetchResult, ToolError> {
             *self.last_fetch.lock() = Some((url.to_string(), max_bytes));
             Ok(WebFetchResult {
@@ -187,28 +450,21 @@ mod tests {
                 truncated: false,
             })
         }
+
+        async fn request(&self, request: HttpRequestSpec) -> Result<HttpResponse, ToolError> {
+            *self.last_request.lock() = Some(request);
+            Ok(HttpResponse {
+                status: 201,
+                headers: BTreeMap::from([("location".to_string(), "/items/1".to_string())]),
+                content_type: Some("application/json".to_string()),
+                body: "{}".to_string(),
+                truncated: false,
+            })
+        }
     }
 
     fn base_context(root: &std::path::Path) -> ToolContext {
-        ToolContext {
-            session_id: Uuid::nil(),
-            agent_id: "agent".to_string(),
-            turn_id: None,
-            tool_call_id: None,
-            tool_name: None,
-            services: Arc::new(TurnServices {
-                cwd: root.to_path_buf(),
-                workspace_root: root.to_path_buf(),
-                output_policy: None,
-                sandbox: None,
-                web: None,
-                event_sink: None,
-                skill_provider: None,
-                question_handler: None,
-                permission_checker: None,
-                tool_result_handler: None,
-            }),
-        }
+        ToolContext::builder().with_workspace_root(root).build()
     }
 
     #[tokio::test]
@@ -245,21 +501,10 @@ mod tests {
     async fn web_search_uses_provider_defaults() {
         let temp = tempdir().expect("tempdir");
         let provider = Arc::new(DummyWebProvider::default());
-        let ctx = ToolContext {
-            services: Arc::new(TurnServices {
-                cwd: temp.path().to_path_buf(),
-                workspace_root: temp.path().to_path_buf(),
-                output_policy: None,
-                sandbox: None,
-                web: Some(provider.clone()),
-                event_sink: None,
-                skill_provider: None,
-                question_handler: None,
-                permission_checker: None,
-                tool_result_handler: None,
-            }),
-            ..base_context(temp.path())
-        };
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .with_web(provider.clone())
+            .build();
         let tool = WebSearchTool;
         let result = tool
             .call(&ctx, json!({ "query": "odyssey" }))
@@ -272,6 +517,29 @@ mod tests {
         assert_eq!(limit, 5);
     }
 
+    #[tokio::test]
+    async fn web_search_passes_configured_api_key() {
+        let temp = tempdir().expect("tempdir");
+        let secrets_path = temp.path().join("secrets.json");
+        std::fs::write(&secrets_path, r#"{ "search": "search-key" }"#).expect("write secrets");
+        let provider = Arc::new(DummyWebProvider::default());
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .with_web(provider.clone())
+            .with_secrets(Arc::new(SecretResolver::new(Some(secrets_path), "odyssey")))
+            .with_web_api_key_ref("file:search")
+            .build();
+        WebSearchTool
+            .call(&ctx, json!({ "query": "odyssey" }))
+            .await
+            .expect("search");
+
+        assert_eq!(
+            provider.last_api_key.lock().clone(),
+            Some("search-key".to_string())
+        );
+    }
+
     #[tokio::test]
     async fn web_fetch_rejects_empty_url() {
         let temp = tempdir().expect("tempdir");
@@ -291,28 +559,19 @@ mod tests {
     async fn web_fetch_uses_output_policy_limit() {
         let temp = tempdir().expect("tempdir");
         let provider = Arc::new(DummyWebProvider::default());
-        let ctx = ToolContext {
-            services: Arc::new(TurnServices {
-                cwd: temp.path().to_path_buf(),
-                workspace_root: temp.path().to_path_buf(),
-                output_policy: Some(ToolOutputPolicy {
-                    max_string_bytes: 12,
-                    max_array_len: 8,
-                    max_object_entries: 8,
-                    redact_keys: Vec::new(),
-                    redact_values: Vec::new(),
-                    replacement: "[X]".to_string(),
-                }),
-                sandbox: None,
-                web: Some(provider.clone()),
-                event_sink: None,
-                skill_provider: None,
-                question_handler: None,
-                permission_checker: None,
-                tool_result_handler: None,
-            }),
-            ..base_context(temp.path())
-        };
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .with_output_policy(ToolOutputPolicy {
+                max_string_bytes: 12,
+                max_array_len: 8,
+                max_object_entries: 8,
+                redact_keys: Vec::new(),
+                redact_values: Vec::new(),
+                replacement: "[X]".to_string(),
+                ..ToolOutputPolicy::default()
+            })
+            .with_web(provider.clone())
+            .build();
         let tool = WebFetchTool;
         let result = tool
             .call(&ctx, json!({ "url": "https://example.com" }))
@@ -324,4 +583,111 @@ mod tests {
         assert_eq!(url, "https://example.com".to_string());
         assert_eq!(limit, 12);
     }
+
+    #[derive(Default)]
+    struct CollectingDeltas {
+        deltas: Mutex<Vec<serde_json::Value>>,
+    }
+
+    impl ToolDeltaSink for CollectingDeltas {
+        fn push(&self, delta: serde_json::Value) {
+            self.deltas.lock().push(delta);
+        }
+    }
+
+    #[tokio::test]
+    async fn web_fetch_streams_body_chunks() {
+        let temp = tempdir().expect("tempdir");
+        let provider = Arc::new(DummyWebProvider::default());
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .with_web(provider)
+            .build();
+        let deltas = CollectingDeltas::default();
+        let result = WebFetchTool
+            .call_streaming(&ctx, json!({ "url": "https://example.com" }), &deltas)
+            .await
+            .expect("fetch");
+
+        assert_eq!(result["body"], "ok");
+        assert_eq!(*deltas.deltas.lock(), vec![json!({ "text": "ok" })]);
+    }
+
+    #[test]
+    fn url_host_extracts_checked_host() {
+        assert_eq!(
+            url_host("https://user:pw@API.Example.com:8443/v1?q=1").expect("host"),
+            "api.example.com"
+        );
+        assert_eq!(url_host("http://[::1]:8080/").expect("host"), "::1");
+        for url in [
+            "ftp://example.com",
+            "example.com/path",
+            "http://evil.test\\@example.com",
+            "http://%65vil.test/",
+            "http:///path",
+        ] {
+            let err = url_host(url).expect_err(url);
+            assert_eq!(matches!(err, ToolError::InvalidArguments(_)), true);
+        }
+    }
+
+    #[tokio::test]
+    async fn http_request_enforces_domain_lists() {
+        let temp = tempdir().expect("tempdir");
+        let sandbox_provider = LocalSandboxProvider::new();
+        let handle = sandbox_provider
+            .prepare(&SandboxContext {
+                workspace_root: temp.path().to_path_buf(),
+                mode: odyssey_rs_protocol::SandboxMode::WorkspaceWrite,
+                policy: SandboxPolicy {
+                    network: SandboxNetworkPolicy {
+                        allow_domains: vec!["example.com".to_string()],
+                        deny_domains: Vec::new(),
+                    },
+                    ..SandboxPolicy::default()
+                },
+            })
+            .await
+            .expect("prepare");
+        let provider = Arc::new(DummyWebProvider::default());
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .with_sandbox(ToolSandbox {
+                provider: Arc::new(sandbox_provider),
+                handle,
+                lease: None,
+            })
+            .with_web(provider.clone())
+            .build();
+
+        let result = HttpRequestTool
+            .call(
+                &ctx,
+                json!({
+                    "method": "post",
+                    "url": "https://api.example.com/items",
+                    "headers": [{ "name": "Content-Type", "value": "application/json" }],
+                    "body": "{\"name\":\"a\"}"
+                }),
+            )
+            .await
+            .expect("request");
+        assert_eq!(result["status"], 201);
+        assert_eq!(result["headers"]["location"], "/items/1");
+        let request = provider.last_request.lock().take().expect("request");
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.headers["Content-Type"], "application/json");
+        assert_eq!(request.max_bytes, 50_000);
+
+        let err = HttpRequestTool
+            .call(&ctx, json!({ "url": "https://example.org/" }))
+            .await
+            .expect_err("host not allowed");
+        let ToolError::PermissionDenied(message) = err else {
+            panic!("expected permission denied");
+        };
+        assert_eq!(message, "host example.org is not in allow_domains");
+        assert_eq!(provider.last_request.lock().is_none(), true);
+    }
 }
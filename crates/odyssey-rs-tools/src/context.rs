@@ -1,23 +1,36 @@
 //! Tool execution context and permission helpers.
 
-use crate::Tool;
 use crate::events::EventSink;
+use crate::hooks::{ToolCallHook, ToolHookDecision};
 use crate::output_policy::ToolOutputPolicy;
 use crate::permissions::{PermissionChecker, PermissionContext};
 use crate::question::QuestionHandler;
+use crate::scheduler::{ToolPermit, ToolScheduler};
+use crate::secrets::SecretResolver;
+use crate::tool::{Tool, ToolDeltaSink};
 use crate::web::WebProvider;
 use async_trait::async_trait;
 use chrono::Utc;
-use log::{debug, warn};
+use log::{debug, info, warn};
+use odyssey_rs_protocol::{ArtifactProvider, SkillProvider, ToolError};
 use odyssey_rs_protocol::{EventMsg, EventPayload, PathAccess, PermissionRequest, ToolCallId};
-use odyssey_rs_protocol::{SkillProvider, ToolError};
-use odyssey_rs_sandbox::{AccessDecision, AccessMode, SandboxHandle, SandboxProvider};
+use odyssey_rs_sandbox::{
+    AccessDecision, AccessMode, SandboxHandle, SandboxLease, SandboxProvider,
+};
+use parking_lot::{Mutex, RwLock};
 use serde_json::Value;
 use serde_json::json;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
+/// Retries after a tool fails with [`ToolError::Transient`].
+pub const MAX_TRANSIENT_RETRIES: u32 = 2;
+/// Delay before the first transient retry; doubled for each later retry.
+const TRANSIENT_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
 /// Sandbox handle and provider attached to a tool context.
 #[derive(Clone)]
 pub struct ToolSandbox {
@@ -25,12 +38,150 @@ pub struct ToolSandbox {
     pub provider: Arc<dyn SandboxProvider>,
     /// Provider-specific sandbox handle.
     pub handle: SandboxHandle,
+    /// Pool lease keeping a shared handle alive while the turn runs.
+    pub lease: Option<SandboxLease>,
+}
+
+/// Effective working directory of a session.
+///
+/// Clones share the same directory, so a `cd` in one tool call is visible to
+/// later calls in the turn and, when the orchestrator keeps the value per
+/// session, to later turns as well.
+#[derive(Debug, Clone)]
+pub struct SessionCwd {
+    path: Arc<RwLock<PathBuf>>,
+}
+
+impl SessionCwd {
+    /// Start tracking from an initial directory.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Arc::new(RwLock::new(path.into())),
+        }
+    }
+
+    /// Current directory.
+    pub fn get(&self) -> PathBuf {
+        self.path.read().clone()
+    }
+
+    /// Replace the directory; returns false when it was already current.
+    pub fn set(&self, path: PathBuf) -> bool {
+        let mut current = self.path.write();
+        if *current == path {
+            return false;
+        }
+        *current = path;
+        true
+    }
+}
+
+/// Content hashes of files as the agent last saw them, keyed by absolute path.
+///
+/// Read, Write, and Edit record the hash they observed so later edits can
+/// detect files that changed behind the agent's back.
+#[derive(Debug, Clone, Default)]
+pub struct FileVersions {
+    hashes: Arc<Mutex<HashMap<PathBuf, FileVersion>>>,
+}
+
+/// What the agent last saw of a file.
+#[derive(Debug, Clone)]
+enum FileVersion {
+    /// The whole file, with its hash.
+    Full(String),
+    /// Only the first `len` bytes, with their hash.
+    Partial { len: usize, hash: String },
+}
+
+impl FileVersions {
+    /// Hash recorded for a path, if the agent has seen all of it.
+    pub fn get(&self, path: &Path) -> Option<String> {
+        match self.hashes.lock().get(path)? {
+            FileVersion::Full(hash) => Some(hash.clone()),
+            FileVersion::Partial { .. } => None,
+        }
+    }
+
+    /// Whether the agent has seen any of `path`.
+    pub fn contains(&self, path: &Path) -> bool {
+        self.hashes.lock().contains_key(path)
+    }
+
+    /// Whether the agent has seen only the start of `path`.
+    pub fn is_partial(&self, path: &Path) -> bool {
+        matches!(
+            self.hashes.lock().get(path),
+            Some(FileVersion::Partial { .. })
+        )
+    }
+
+    /// Record the hash the agent last saw for a path.
+    pub fn record(&self, path: PathBuf, hash: String) {
+        self.hashes.lock().insert(path, FileVersion::Full(hash));
+    }
+
+    /// Record that the agent saw only the first `len` bytes of a path, hashing to `hash`.
+    ///
+    /// Write and Edit refuse to change such a file until it is read in full.
+    pub fn record_partial(&self, path: PathBuf, len: usize, hash: String) {
+        self.hashes
+            .lock()
+            .insert(path, FileVersion::Partial { len, hash });
+    }
+
+    /// Return true when the agent has seen `path` and its content has since
+    /// changed or the file is gone.
+    pub fn is_stale(&self, path: &Path) -> bool {
+        let Some(version) = self.hashes.lock().get(path).cloned() else {
+            return false;
+        };
+        match (version, std::fs::read(path)) {
+            (FileVersion::Full(expected), Ok(bytes)) => content_hash(&bytes) != expected,
+            (FileVersion::Partial { len, hash }, Ok(bytes)) => bytes
+                .get(..len)
+                .is_none_or(|prefix| content_hash(prefix) != hash),
+            (_, Err(_)) => true,
+        }
+    }
+}
+
+/// Stable hex digest (64-bit FNV-1a) of file content, reported as `hash` in tool results.
+pub(crate) fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = ContentHasher::default();
+    hasher.update(bytes);
+    hasher.finish()
+}
+
+/// Incremental form of [`content_hash`] for content read in chunks.
+pub(crate) struct ContentHasher(u64);
+
+impl Default for ContentHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl ContentHasher {
+    /// Feed the next chunk of content.
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        self.0 = bytes.iter().fold(self.0, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    }
+
+    /// Hex digest of everything fed so far.
+    pub(crate) fn finish(&self) -> String {
+        format!("{:016x}", self.0)
+    }
 }
 
 /// Shared service dependencies for a turn (constructed once, shared via Arc).
 pub struct TurnServices {
-    /// Current working directory.
-    pub cwd: PathBuf,
+    /// Effective working directory, shared with the session.
+    pub cwd: SessionCwd,
+    /// File hashes last seen by the agent, shared with the session.
+    pub file_versions: FileVersions,
     /// Workspace root directory.
     pub workspace_root: PathBuf,
     /// Output policy applied to tool results.
@@ -43,12 +194,26 @@ pub struct TurnServices {
     pub event_sink: Option<Arc<dyn EventSink>>,
     /// Optional skill provider for skill tools.
     pub skill_provider: Option<Arc<dyn SkillProvider>>,
+    /// Optional artifact store shared across sessions.
+    pub artifact_provider: Option<Arc<dyn ArtifactProvider>>,
     /// Optional question handler for interactive tools.
     pub question_handler: Option<Arc<dyn QuestionHandler>>,
     /// Optional permission checker for gated actions.
     pub permission_checker: Option<Arc<dyn PermissionChecker>>,
     /// Optional handler for recording tool results.
     pub tool_result_handler: Option<Arc<dyn ToolResultHandler>>,
+    /// Optional hook run before and after each tool call.
+    pub tool_hook: Option<Arc<dyn ToolCallHook>>,
+    /// Preview mutating tools instead of executing them.
+    pub simulation: bool,
+    /// Allow workspace paths that traverse symlinks (targets must stay inside the workspace).
+    pub follow_symlinks: bool,
+    /// Optional resolver for secret references such as `keyring:NAME`.
+    pub secrets: Option<Arc<SecretResolver>>,
+    /// Secret reference for the web search API key (`tools.web.api_key_ref`).
+    pub web_api_key_ref: Option<String>,
+    /// Optional scheduler granting exclusion-group permits to tools.
+    pub scheduler: Option<Arc<ToolScheduler>>,
 }
 
 /// Shared context passed to tools during execution.
@@ -86,6 +251,11 @@ pub trait ToolResultHandler: Send + Sync {
 }
 
 impl ToolContext {
+    /// Start building a context with test-friendly defaults.
+    pub fn builder() -> ToolContextBuilder {
+        ToolContextBuilder::default()
+    }
+
     /// Apply the configured output policy to a tool result value.
     pub fn apply_output_policy(&self, value: Value) -> Value {
         match self.services.output_policy.as_ref() {
@@ -94,6 +264,70 @@ impl ToolContext {
         }
     }
 
+    /// Effective working directory for this call.
+    pub fn cwd(&self) -> PathBuf {
+        self.services.cwd.get()
+    }
+
+    /// Change the effective working directory and announce the change.
+    ///
+    /// Callers validate the path; it must be an existing directory inside the
+    /// workspace.
+    pub fn set_cwd(&self, path: PathBuf) {
+        if !self.services.cwd.set(path.clone()) {
+            return;
+        }
+        info!(
+            "changed working directory (session_id={}, cwd={})",
+            self.session_id,
+            path.display()
+        );
+        let (Some(turn_id), Some(sink)) = (self.turn_id, self.services.event_sink.as_ref()) else {
+            return;
+        };
+        sink.emit(EventMsg {
+            id: Uuid::new_v4(),
+            session_id: self.session_id,
+            seq: 0,
+            user_id: None,
+            created_at: Utc::now(),
+            payload: EventPayload::CwdChanged {
+                turn_id,
+                cwd: path.display().to_string(),
+            },
+        });
+    }
+
+    /// Resolve a secret reference such as `keyring:NAME`.
+    pub fn secret(&self, reference: &str) -> Result<String, ToolError> {
+        let resolver = self.services.secrets.as_ref().ok_or_else(|| {
+            ToolError::ExecutionFailed("secret resolver not configured".to_string())
+        })?;
+        resolver
+            .resolve(reference)
+            .map_err(|err| ToolError::ExecutionFailed(err.to_string()))
+    }
+
+    /// Web search API key, when `tools.web.api_key_ref` is configured.
+    pub fn web_api_key(&self) -> Result<Option<String>, ToolError> {
+        self.services
+            .web_api_key_ref
+            .as_deref()
+            .map(|reference| self.secret(reference))
+            .transpose()
+    }
+
+    /// Wait until no other tool of `tool`'s exclusion group runs in this session.
+    ///
+    /// Tools with an exclusion group call this after their permission checks,
+    /// so a pending approval does not block other calls of the group, and hold
+    /// the permit until their changes are written.
+    pub async fn exclusive(&self, tool: &dyn Tool) -> Option<ToolPermit> {
+        let scheduler = self.services.scheduler.as_ref()?;
+        tool.exclusion_group()?;
+        Some(scheduler.acquire_group(self.session_id, tool).await)
+    }
+
     /// Check sandbox access for a filesystem path.
     pub fn check_access(&self, path: &std::path::Path, mode: AccessMode) -> Result<(), ToolError> {
         let Some(sandbox) = &self.services.sandbox else {
@@ -105,6 +339,17 @@ impl ToolContext {
         }
     }
 
+    /// Check a network host against the sandbox domain policy.
+    pub fn check_network(&self, host: &str) -> Result<(), ToolError> {
+        let Some(sandbox) = &self.services.sandbox else {
+            return Ok(());
+        };
+        match sandbox.provider.check_network(&sandbox.handle, host) {
+            AccessDecision::Allow => Ok(()),
+            AccessDecision::Deny(reason) => Err(ToolError::PermissionDenied(reason)),
+        }
+    }
+
     /// Build a permission context for this tool invocation.
     pub fn permission_context(&self) -> PermissionContext {
         PermissionContext {
@@ -195,6 +440,8 @@ impl ToolContext {
         let event = EventMsg {
             id: Uuid::new_v4(),
             session_id: self.session_id,
+            seq: 0,
+            user_id: None,
             created_at: Utc::now(),
             payload: EventPayload::ToolCallStarted {
                 turn_id,
@@ -208,20 +455,46 @@ impl ToolContext {
     }
 
     /// Execute a tool with the full authorization, event, and recording pipeline.
-    pub async fn execute_tool(&mut self, tool: &dyn Tool, args: Value) -> Result<Value, ToolError> {
+    pub async fn execute_tool(
+        &mut self,
+        tool: &dyn Tool,
+        mut args: Value,
+    ) -> Result<Value, ToolError> {
         self.tool_name = Some(tool.name().to_string());
-        self.authorize_tool(tool.name()).await?;
+        if !(self.services.simulation && tool.is_mutating()) {
+            self.authorize_tool(tool.name()).await?;
+        }
+        let hook = self.services.tool_hook.clone();
+        if let Some(hook) = &hook
+            && let ToolHookDecision::Veto { reason } =
+                hook.before_tool(self, tool.name(), &mut args).await
+        {
+            info!(
+                "tool call vetoed by hook (tool_name={}, session_id={}): {}",
+                tool.name(),
+                self.session_id,
+                reason
+            );
+            return Err(ToolError::PermissionDenied(format!(
+                "vetoed by hook: {reason}"
+            )));
+        }
         let tool_call_id = self.emit_tool_started(tool.name(), &args);
         self.tool_call_id = tool_call_id;
 
         let handler = self.services.tool_result_handler.clone();
-        let record_args = if handler.is_some() {
+        let record_args = if handler.is_some() || hook.is_some() {
             Some(args.clone())
         } else {
             None
         };
 
-        match tool.call(self, args).await {
+        let outcome = self.call_with_retries(tool, args, tool_call_id).await;
+        if let (Some(hook), Some(record_args)) = (&hook, &record_args) {
+            hook.after_tool(self, tool.name(), record_args, &outcome)
+                .await;
+        }
+        match outcome {
             Ok(result) => {
                 if let (Some(handler), Some(record_args)) = (handler, record_args)
                     && let Err(err) = handler
@@ -240,12 +513,73 @@ impl ToolContext {
                 Ok(output)
             }
             Err(err) => {
-                self.emit_tool_finished(tool_call_id, json!({ "error": err.to_string() }), false);
+                self.emit_tool_finished(tool_call_id, err.to_result(), false);
                 Err(err)
             }
         }
     }
 
+    /// Call a tool, retrying transient failures up to [`MAX_TRANSIENT_RETRIES`] times.
+    ///
+    /// Output deltas from a failed attempt have already been emitted, so a
+    /// retried streaming call may repeat them.
+    async fn call_with_retries(
+        &self,
+        tool: &dyn Tool,
+        args: Value,
+        tool_call_id: Option<ToolCallId>,
+    ) -> Result<Value, ToolError> {
+        let mut attempt = 0;
+        loop {
+            let outcome = match tool_call_id {
+                Some(tool_call_id) => {
+                    let sink = ToolCallDeltaSink {
+                        ctx: self,
+                        tool_call_id,
+                    };
+                    tool.call_streaming(self, args.clone(), &sink).await
+                }
+                None => tool.call(self, args.clone()).await,
+            };
+            match outcome {
+                Err(ToolError::Transient(message)) if attempt < MAX_TRANSIENT_RETRIES => {
+                    let delay = TRANSIENT_RETRY_BACKOFF * 2u32.pow(attempt);
+                    attempt += 1;
+                    warn!(
+                        "transient tool failure; retrying (tool_name={}, attempt={attempt}, delay_ms={}): {message}",
+                        tool.name(),
+                        delay.as_millis()
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                outcome => return outcome,
+            }
+        }
+    }
+
+    /// Emit a tool-call output delta event.
+    pub fn emit_tool_delta(&self, tool_call_id: ToolCallId, delta: Value) {
+        let Some(turn_id) = self.turn_id else {
+            return;
+        };
+        let Some(sink) = self.services.event_sink.as_ref() else {
+            return;
+        };
+        let event = EventMsg {
+            id: Uuid::new_v4(),
+            session_id: self.session_id,
+            seq: 0,
+            user_id: None,
+            created_at: Utc::now(),
+            payload: EventPayload::ToolCallDelta {
+                turn_id,
+                tool_call_id,
+                delta,
+            },
+        };
+        sink.emit(event);
+    }
+
     /// Emit a tool-call finished event.
     pub fn emit_tool_finished(
         &self,
@@ -263,6 +597,8 @@ impl ToolContext {
         let event = EventMsg {
             id: Uuid::new_v4(),
             session_id: self.session_id,
+            seq: 0,
+            user_id: None,
             created_at: Utc::now(),
             payload: EventPayload::ToolCallFinished {
                 turn_id,
@@ -275,6 +611,227 @@ impl ToolContext {
     }
 }
 
+/// Delta sink that forwards partial tool output as `ToolCallDelta` events.
+struct ToolCallDeltaSink<'a> {
+    ctx: &'a ToolContext,
+    tool_call_id: ToolCallId,
+}
+
+impl ToolDeltaSink for ToolCallDeltaSink<'_> {
+    fn push(&self, delta: Value) {
+        self.ctx.emit_tool_delta(self.tool_call_id, delta);
+    }
+}
+
+/// Builder for [`ToolContext`] with defaults suited to embedders and tests.
+///
+/// Ids start nil, the agent id is `agent`, and the workspace root (and working
+/// directory) is the system temp directory. Every optional service is unset.
+pub struct ToolContextBuilder {
+    session_id: Uuid,
+    agent_id: String,
+    turn_id: Option<Uuid>,
+    tool_call_id: Option<Uuid>,
+    tool_name: Option<String>,
+    cwd: Option<PathBuf>,
+    services: TurnServices,
+}
+
+impl Default for ToolContextBuilder {
+    fn default() -> Self {
+        let workspace_root = std::env::temp_dir();
+        Self {
+            session_id: Uuid::nil(),
+            agent_id: "agent".to_string(),
+            turn_id: None,
+            tool_call_id: None,
+            tool_name: None,
+            cwd: None,
+            services: TurnServices {
+                cwd: SessionCwd::new(workspace_root.clone()),
+                file_versions: FileVersions::default(),
+                workspace_root,
+                output_policy: None,
+                sandbox: None,
+                web: None,
+                event_sink: None,
+                skill_provider: None,
+                artifact_provider: None,
+                question_handler: None,
+                permission_checker: None,
+                tool_result_handler: None,
+                tool_hook: None,
+                simulation: false,
+                follow_symlinks: true,
+                secrets: None,
+                web_api_key_ref: None,
+                scheduler: None,
+            },
+        }
+    }
+}
+
+impl ToolContextBuilder {
+    /// Set the session id.
+    pub fn with_session_id(mut self, session_id: Uuid) -> Self {
+        self.session_id = session_id;
+        self
+    }
+
+    /// Set the agent id.
+    pub fn with_agent_id(mut self, agent_id: impl Into<String>) -> Self {
+        self.agent_id = agent_id.into();
+        self
+    }
+
+    /// Set the turn id; tools only emit events when one is set.
+    pub fn with_turn_id(mut self, turn_id: Uuid) -> Self {
+        self.turn_id = Some(turn_id);
+        self
+    }
+
+    /// Set the tool call id.
+    pub fn with_tool_call_id(mut self, tool_call_id: Uuid) -> Self {
+        self.tool_call_id = Some(tool_call_id);
+        self
+    }
+
+    /// Set the tool name.
+    pub fn with_tool_name(mut self, tool_name: impl Into<String>) -> Self {
+        self.tool_name = Some(tool_name.into());
+        self
+    }
+
+    /// Set the workspace root; the working directory follows it unless set with [`Self::with_cwd`].
+    pub fn with_workspace_root(mut self, workspace_root: impl Into<PathBuf>) -> Self {
+        self.services.workspace_root = workspace_root.into();
+        self
+    }
+
+    /// Set the initial working directory.
+    pub fn with_cwd(mut self, cwd: impl Into<PathBuf>) -> Self {
+        self.cwd = Some(cwd.into());
+        self
+    }
+
+    /// Share file hashes with another context.
+    pub fn with_file_versions(mut self, file_versions: FileVersions) -> Self {
+        self.services.file_versions = file_versions;
+        self
+    }
+
+    /// Apply an output policy to tool results.
+    pub fn with_output_policy(mut self, output_policy: ToolOutputPolicy) -> Self {
+        self.services.output_policy = Some(output_policy);
+        self
+    }
+
+    /// Run commands and check access through a sandbox.
+    pub fn with_sandbox(mut self, sandbox: ToolSandbox) -> Self {
+        self.services.sandbox = Some(sandbox);
+        self
+    }
+
+    /// Set the web provider for network tools.
+    pub fn with_web(mut self, web: Arc<dyn WebProvider>) -> Self {
+        self.services.web = Some(web);
+        self
+    }
+
+    /// Set the sink for tool events.
+    pub fn with_event_sink(mut self, event_sink: Arc<dyn EventSink>) -> Self {
+        self.services.event_sink = Some(event_sink);
+        self
+    }
+
+    /// Set the skill provider for skill tools.
+    pub fn with_skill_provider(mut self, skill_provider: Arc<dyn SkillProvider>) -> Self {
+        self.services.skill_provider = Some(skill_provider);
+        self
+    }
+
+    /// Set the artifact store.
+    pub fn with_artifact_provider(mut self, artifact_provider: Arc<dyn ArtifactProvider>) -> Self {
+        self.services.artifact_provider = Some(artifact_provider);
+        self
+    }
+
+    /// Set the handler for interactive questions.
+    pub fn with_question_handler(mut self, question_handler: Arc<dyn QuestionHandler>) -> Self {
+        self.services.question_handler = Some(question_handler);
+        self
+    }
+
+    /// Gate actions through a permission checker.
+    pub fn with_permission_checker(
+        mut self,
+        permission_checker: Arc<dyn PermissionChecker>,
+    ) -> Self {
+        self.services.permission_checker = Some(permission_checker);
+        self
+    }
+
+    /// Record tool results through a handler.
+    pub fn with_tool_result_handler(
+        mut self,
+        tool_result_handler: Arc<dyn ToolResultHandler>,
+    ) -> Self {
+        self.services.tool_result_handler = Some(tool_result_handler);
+        self
+    }
+
+    /// Run a hook before and after each tool call.
+    pub fn with_tool_hook(mut self, tool_hook: Arc<dyn ToolCallHook>) -> Self {
+        self.services.tool_hook = Some(tool_hook);
+        self
+    }
+
+    /// Preview mutating tools instead of executing them.
+    pub fn with_simulation(mut self, simulation: bool) -> Self {
+        self.services.simulation = simulation;
+        self
+    }
+
+    /// Allow workspace paths that traverse symlinks.
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.services.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Set the resolver for secret references.
+    pub fn with_secrets(mut self, secrets: Arc<SecretResolver>) -> Self {
+        self.services.secrets = Some(secrets);
+        self
+    }
+
+    /// Set the secret reference for the web search API key.
+    pub fn with_web_api_key_ref(mut self, web_api_key_ref: impl Into<String>) -> Self {
+        self.services.web_api_key_ref = Some(web_api_key_ref.into());
+        self
+    }
+
+    /// Serialize exclusion groups through a scheduler.
+    pub fn with_scheduler(mut self, scheduler: Arc<ToolScheduler>) -> Self {
+        self.services.scheduler = Some(scheduler);
+        self
+    }
+
+    /// Build the context.
+    pub fn build(self) -> ToolContext {
+        let mut services = self.services;
+        let cwd = self.cwd.unwrap_or_else(|| services.workspace_root.clone());
+        services.cwd = SessionCwd::new(cwd);
+        ToolContext {
+            session_id: self.session_id,
+            agent_id: self.agent_id,
+            turn_id: self.turn_id,
+            tool_call_id: self.tool_call_id,
+            tool_name: self.tool_name,
+            services: Arc::new(services),
+        }
+    }
+}
+
 impl std::fmt::Debug for ToolContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ToolContext")
@@ -289,18 +846,25 @@ impl std::fmt::Debug for ToolContext {
 
 #[cfg(test)]
 mod tests {
-    use super::{ToolContext, ToolResultHandler, TurnServices};
-    use crate::Tool;
+    use super::{
+        FileVersions, MAX_TRANSIENT_RETRIES, SessionCwd, ToolContext, ToolResultHandler,
+        TurnServices,
+    };
+    use crate::hooks::{ToolCallHook, ToolHookDecision};
     use crate::output_policy::ToolOutputPolicy;
     use crate::permissions::{PermissionChecker, PermissionContext, PermissionOutcome};
+    use crate::{Tool, ToolDeltaSink};
     use async_trait::async_trait;
-    use odyssey_rs_protocol::{EventMsg, PathAccess, PermissionRequest, ToolError};
+    use odyssey_rs_protocol::{
+        EventMsg, EventPayload, PathAccess, PermissionRequest, ToolError, ToolErrorKind,
+    };
     use odyssey_rs_sandbox::{AccessMode, LocalSandboxProvider, SandboxContext, SandboxProvider};
     use pretty_assertions::assert_eq;
     use serde_json::json;
     use std::collections::VecDeque;
     use std::path::PathBuf;
     use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use tempfile::tempdir;
     use uuid::Uuid;
 
@@ -354,16 +918,24 @@ mod tests {
 
     fn base_services(root: PathBuf) -> TurnServices {
         TurnServices {
-            cwd: root.clone(),
+            cwd: SessionCwd::new(root.clone()),
+            file_versions: FileVersions::default(),
             workspace_root: root,
             output_policy: None,
             sandbox: None,
             web: None,
             event_sink: None,
             skill_provider: None,
+            artifact_provider: None,
             question_handler: None,
             permission_checker: None,
             tool_result_handler: Some(Arc::new(NullResultHandler)),
+            tool_hook: None,
+            simulation: false,
+            follow_symlinks: true,
+            secrets: None,
+            web_api_key_ref: None,
+            scheduler: None,
         }
     }
 
@@ -419,6 +991,88 @@ mod tests {
         }
     }
 
+    /// Fails with a transient error until `failures` runs out.
+    #[derive(Debug)]
+    struct FlakyTool {
+        failures: AtomicUsize,
+        calls: AtomicUsize,
+    }
+
+    impl FlakyTool {
+        fn new(failures: usize) -> Self {
+            Self {
+                failures: AtomicUsize::new(failures),
+                calls: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Tool for FlakyTool {
+        fn name(&self) -> &str {
+            "Flaky"
+        }
+
+        fn description(&self) -> &str {
+            "fails transiently"
+        }
+
+        fn args_schema(&self) -> serde_json::Value {
+            json!({})
+        }
+
+        async fn call(
+            &self,
+            _ctx: &ToolContext,
+            _args: serde_json::Value,
+        ) -> Result<serde_json::Value, ToolError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let remaining = self.failures.load(Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, Ordering::SeqCst);
+                return Err(ToolError::Transient("connection reset".to_string()));
+            }
+            Ok(json!({ "ok": true }))
+        }
+    }
+
+    #[derive(Debug)]
+    struct ChunkedTool;
+
+    #[async_trait]
+    impl Tool for ChunkedTool {
+        fn name(&self) -> &str {
+            "Chunked"
+        }
+
+        fn description(&self) -> &str {
+            "streams chunks"
+        }
+
+        fn args_schema(&self) -> serde_json::Value {
+            json!({})
+        }
+
+        async fn call(
+            &self,
+            _ctx: &ToolContext,
+            _args: serde_json::Value,
+        ) -> Result<serde_json::Value, ToolError> {
+            Ok(json!({ "text": "ab" }))
+        }
+
+        async fn call_streaming(
+            &self,
+            ctx: &ToolContext,
+            args: serde_json::Value,
+            sink: &dyn ToolDeltaSink,
+        ) -> Result<serde_json::Value, ToolError> {
+            sink.push(json!({ "text": "a" }));
+            sink.push(json!({ "text": "b" }));
+            self.call(ctx, args).await
+        }
+    }
+
     #[test]
     fn apply_output_policy_redacts() {
         let temp = tempdir().expect("tempdir");
@@ -430,6 +1084,7 @@ mod tests {
             redact_keys: vec!["secret".to_string()],
             redact_values: Vec::new(),
             replacement: "[X]".to_string(),
+            ..ToolOutputPolicy::default()
         });
         let ctx = ToolContext {
             session_id: Uuid::nil(),
@@ -484,6 +1139,7 @@ mod tests {
         services.sandbox = Some(super::ToolSandbox {
             provider: Arc::new(provider),
             handle,
+            lease: None,
         });
         let ctx = ToolContext {
             session_id: Uuid::nil(),
@@ -592,4 +1248,216 @@ mod tests {
         let events = sink.events.lock();
         assert_eq!(events.len(), 2);
     }
+
+    #[tokio::test]
+    async fn execute_tool_retries_transient_failures() {
+        let temp = tempdir().expect("tempdir");
+        let sink = Arc::new(RecordingSink::default());
+        let mut services = base_services(temp.path().to_path_buf());
+        services.permission_checker = Some(Arc::new(StaticPermission { allowed: true }));
+        services.event_sink = Some(sink.clone());
+        let mut ctx = ToolContext {
+            session_id: Uuid::new_v4(),
+            agent_id: "agent".to_string(),
+            turn_id: Some(Uuid::new_v4()),
+            tool_call_id: None,
+            tool_name: None,
+            services: Arc::new(services),
+        };
+
+        let tool = FlakyTool::new(MAX_TRANSIENT_RETRIES as usize);
+        let result = ctx.execute_tool(&tool, json!({})).await.expect("retried");
+        assert_eq!(result, json!({ "ok": true }));
+        assert_eq!(
+            tool.calls.load(Ordering::SeqCst),
+            MAX_TRANSIENT_RETRIES as usize + 1
+        );
+
+        let tool = FlakyTool::new(usize::MAX);
+        let err = ctx
+            .execute_tool(&tool, json!({}))
+            .await
+            .expect_err("retries exhausted");
+        assert_eq!(err.kind(), ToolErrorKind::Transient);
+        assert_eq!(
+            tool.calls.load(Ordering::SeqCst),
+            MAX_TRANSIENT_RETRIES as usize + 1
+        );
+        let events = sink.events.lock();
+        let Some(EventPayload::ToolCallFinished {
+            result, success, ..
+        }) = events.back().map(|event| &event.payload)
+        else {
+            panic!("expected tool finished event");
+        };
+        assert_eq!(*success, false);
+        assert_eq!(
+            *result,
+            json!({
+                "error": "transient failure: connection reset",
+                "kind": "transient",
+                "retryable": true,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_tool_forwards_streaming_deltas() {
+        let temp = tempdir().expect("tempdir");
+        let sink = Arc::new(RecordingSink::default());
+        let mut services = base_services(temp.path().to_path_buf());
+        services.event_sink = Some(sink.clone());
+
+        let mut ctx = ToolContext {
+            session_id: Uuid::new_v4(),
+            agent_id: "agent".to_string(),
+            turn_id: Some(Uuid::new_v4()),
+            tool_call_id: None,
+            tool_name: None,
+            services: Arc::new(services),
+        };
+
+        let result = ctx
+            .execute_tool(&ChunkedTool, json!({}))
+            .await
+            .expect("execute");
+        assert_eq!(result, json!({ "text": "ab" }));
+
+        let events = sink.events.lock();
+        let deltas = events
+            .iter()
+            .filter_map(|event| match &event.payload {
+                EventPayload::ToolCallDelta {
+                    tool_call_id,
+                    delta,
+                    ..
+                } => Some((*tool_call_id, delta.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(events.len(), 4);
+        assert_eq!(
+            deltas
+                .iter()
+                .map(|(_, delta)| delta.clone())
+                .collect::<Vec<_>>(),
+            vec![json!({ "text": "a" }), json!({ "text": "b" })]
+        );
+        assert_eq!(
+            deltas.iter().all(|(id, _)| Some(*id) == ctx.tool_call_id),
+            true
+        );
+    }
+
+    #[derive(Default)]
+    struct GuardHook {
+        calls: parking_lot::Mutex<Vec<(String, serde_json::Value, bool)>>,
+    }
+
+    #[async_trait]
+    impl ToolCallHook for GuardHook {
+        async fn before_tool(
+            &self,
+            _ctx: &ToolContext,
+            _tool_name: &str,
+            args: &mut serde_json::Value,
+        ) -> ToolHookDecision {
+            if args["blocked"] == json!(true) {
+                return ToolHookDecision::Veto {
+                    reason: "blocked arguments".to_string(),
+                };
+            }
+            args["checked"] = json!(true);
+            ToolHookDecision::Continue
+        }
+
+        async fn after_tool(
+            &self,
+            _ctx: &ToolContext,
+            tool_name: &str,
+            args: &serde_json::Value,
+            result: &Result<serde_json::Value, ToolError>,
+        ) {
+            self.calls
+                .lock()
+                .push((tool_name.to_string(), args.clone(), result.is_ok()));
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_tool_runs_hooks_and_honors_veto() {
+        let temp = tempdir().expect("tempdir");
+        let sink = Arc::new(RecordingSink::default());
+        let hook = Arc::new(GuardHook::default());
+        let mut services = base_services(temp.path().to_path_buf());
+        services.event_sink = Some(sink.clone());
+        services.tool_hook = Some(hook.clone());
+
+        let mut ctx = ToolContext {
+            session_id: Uuid::new_v4(),
+            agent_id: "agent".to_string(),
+            turn_id: Some(Uuid::new_v4()),
+            tool_call_id: None,
+            tool_name: None,
+            services: Arc::new(services),
+        };
+
+        ctx.execute_tool(&DummyTool, json!({}))
+            .await
+            .expect("execute");
+        ctx.execute_tool(&FailingTool, json!({}))
+            .await
+            .expect_err("failed");
+        let err = ctx
+            .execute_tool(&DummyTool, json!({ "blocked": true }))
+            .await
+            .expect_err("vetoed");
+        match err {
+            ToolError::PermissionDenied(message) => {
+                assert_eq!(message, "vetoed by hook: blocked arguments")
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+
+        assert_eq!(
+            *hook.calls.lock(),
+            vec![
+                ("Dummy".to_string(), json!({ "checked": true }), true),
+                ("Failing".to_string(), json!({ "checked": true }), false),
+            ]
+        );
+        // Vetoed calls never start, so only the first two calls emit events.
+        assert_eq!(sink.events.lock().len(), 4);
+    }
+
+    #[test]
+    fn builder_fills_defaults_and_setters() {
+        let ctx = ToolContext::builder().build();
+        assert_eq!(ctx.session_id, Uuid::nil());
+        assert_eq!(ctx.agent_id, "agent");
+        assert_eq!(ctx.turn_id, None);
+        assert_eq!(ctx.services.workspace_root, std::env::temp_dir());
+        assert_eq!(ctx.cwd(), std::env::temp_dir());
+        assert_eq!(ctx.services.follow_symlinks, true);
+
+        let temp = tempdir().expect("tempdir");
+        let turn_id = Uuid::new_v4();
+        let ctx = ToolContext::builder()
+            .with_agent_id("reviewer")
+            .with_turn_id(turn_id)
+            .with_workspace_root(temp.path())
+            .with_simulation(true)
+            .build();
+        assert_eq!(ctx.agent_id, "reviewer");
+        assert_eq!(ctx.turn_id, Some(turn_id));
+        assert_eq!(ctx.cwd(), temp.path());
+        assert_eq!(ctx.services.simulation, true);
+
+        let nested = temp.path().join("src");
+        let ctx = ToolContext::builder()
+            .with_workspace_root(temp.path())
+            .with_cwd(&nested)
+            .build();
+        assert_eq!(ctx.cwd(), nested);
+    }
 }
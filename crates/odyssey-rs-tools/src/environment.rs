@@ -0,0 +1,231 @@
+//! Host environment probe shared by the EnvInfo tool and turn metadata.
+
+use log::debug;
+use odyssey_rs_sandbox::SandboxProvider;
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::OnceCell;
+use tokio::task::JoinSet;
+
+/// Interpreters, compilers, and build tools probed with their version flag.
+const TOOLCHAIN_PROBES: &[(&str, &[&str])] = &[
+    ("python3", &["--version"]),
+    ("python", &["--version"]),
+    ("uv", &["--version"]),
+    ("node", &["--version"]),
+    ("npm", &["--version"]),
+    ("deno", &["--version"]),
+    ("bun", &["--version"]),
+    ("ruby", &["--version"]),
+    ("perl", &["--version"]),
+    ("php", &["--version"]),
+    ("go", &["version"]),
+    ("rustc", &["--version"]),
+    ("cargo", &["--version"]),
+    ("java", &["-version"]),
+    ("javac", &["-version"]),
+    ("dotnet", &["--version"]),
+    ("gcc", &["--version"]),
+    ("clang", &["--version"]),
+    ("make", &["--version"]),
+    ("cmake", &["--version"]),
+    ("git", &["--version"]),
+    ("docker", &["--version"]),
+];
+/// Time allowed for a single version probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Longest version line kept per toolchain.
+const MAX_VERSION_CHARS: usize = 120;
+
+/// Host facts probed once per process.
+static HOST: OnceCell<HostEnvironment> = OnceCell::const_new();
+
+/// Installed interpreter, compiler, or build tool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolchainVersion {
+    /// Executable name.
+    pub name: String,
+    /// First line of its version output.
+    pub version: String,
+}
+
+/// Sandbox provider dependency check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandboxDependencies {
+    /// Problems preventing the provider from running commands.
+    pub errors: Vec<String>,
+    /// Problems that degrade isolation or limits.
+    pub warnings: Vec<String>,
+}
+
+/// Snapshot of the machine tools run on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentReport {
+    /// Operating system (`linux`, `macos`, `windows`, ...).
+    pub os: String,
+    /// Distribution or release name, when known.
+    pub os_version: Option<String>,
+    /// CPU architecture.
+    pub arch: String,
+    /// Logical CPUs available to the process.
+    pub cpus: usize,
+    /// Total memory in bytes, when known.
+    pub memory_bytes: Option<u64>,
+    /// Toolchains found on `PATH`.
+    pub toolchains: Vec<ToolchainVersion>,
+    /// Probed toolchains that were not found.
+    pub missing: Vec<String>,
+    /// Dependency report of the sandbox provider, if one is configured.
+    pub sandbox: Option<SandboxDependencies>,
+}
+
+impl EnvironmentReport {
+    /// Probe the host and, when given, the sandbox provider's dependencies.
+    ///
+    /// Host facts and toolchain versions are probed on first use and cached for
+    /// the life of the process; the sandbox report is taken fresh each call.
+    pub async fn probe(sandbox: Option<&dyn SandboxProvider>) -> Self {
+        let host = HOST.get_or_init(HostEnvironment::probe).await.clone();
+        let sandbox = sandbox.map(|provider| {
+            let report = provider.dependency_report();
+            SandboxDependencies {
+                errors: report.errors,
+                warnings: report.warnings,
+            }
+        });
+        Self {
+            os: host.os,
+            os_version: host.os_version,
+            arch: host.arch,
+            cpus: host.cpus,
+            memory_bytes: host.memory_bytes,
+            toolchains: host.toolchains,
+            missing: host.missing,
+            sandbox,
+        }
+    }
+}
+
+/// Cached host portion of an environment report.
+#[derive(Debug, Clone)]
+struct HostEnvironment {
+    os: String,
+    os_version: Option<String>,
+    arch: String,
+    cpus: usize,
+    memory_bytes: Option<u64>,
+    toolchains: Vec<ToolchainVersion>,
+    missing: Vec<String>,
+}
+
+impl HostEnvironment {
+    async fn probe() -> Self {
+        let mut probes = JoinSet::new();
+        for (index, (name, args)) in TOOLCHAIN_PROBES.iter().enumerate() {
+            probes.spawn(async move { (index, toolchain_version(name, args).await) });
+        }
+        let mut versions = vec![None; TOOLCHAIN_PROBES.len()];
+        while let Some(joined) = probes.join_next().await {
+            if let Ok((index, version)) = joined {
+                versions[index] = version;
+            }
+        }
+        let mut toolchains = Vec::new();
+        let mut missing = Vec::new();
+        for ((name, _), version) in TOOLCHAIN_PROBES.iter().zip(versions) {
+            match version {
+                Some(version) => toolchains.push(ToolchainVersion {
+                    name: (*name).to_string(),
+                    version,
+                }),
+                None => missing.push((*name).to_string()),
+            }
+        }
+        debug!(
+            "probed host environment (toolchains={}, missing={})",
+            toolchains.len(),
+            missing.len()
+        );
+        Self {
+            os: std::env::consts::OS.to_string(),
+            os_version: os_version().await,
+            arch: std::env::consts::ARCH.to_string(),
+            cpus: std::thread::available_parallelism().map_or(1, usize::from),
+            memory_bytes: memory_bytes().await,
+            toolchains,
+            missing,
+        }
+    }
+}
+
+/// Run a version probe, returning the first non-empty output line.
+async fn toolchain_version(program: &str, args: &[&str]) -> Option<String> {
+    let output = command_output(program, args).await?;
+    // Some tools (java, older pythons) print their version to stderr.
+    let line = [output.stdout, output.stderr].iter().find_map(|stream| {
+        String::from_utf8_lossy(stream)
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+    })?;
+    Some(line.chars().take(MAX_VERSION_CHARS).collect())
+}
+
+async fn command_output(program: &str, args: &[&str]) -> Option<std::process::Output> {
+    let child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .ok()?;
+    let output = tokio::time::timeout(PROBE_TIMEOUT, child.wait_with_output())
+        .await
+        .ok()?
+        .ok()?;
+    output.status.success().then_some(output)
+}
+
+async fn os_version() -> Option<String> {
+    match std::env::consts::OS {
+        "linux" => {
+            let release = tokio::fs::read_to_string("/etc/os-release").await.ok()?;
+            release.lines().find_map(|line| {
+                line.strip_prefix("PRETTY_NAME=")
+                    .map(|value| value.trim_matches('"').to_string())
+            })
+        }
+        "macos" => {
+            let output = command_output("sw_vers", &["-productVersion"]).await?;
+            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (!version.is_empty()).then(|| format!("macOS {version}"))
+        }
+        _ => None,
+    }
+}
+
+async fn memory_bytes() -> Option<u64> {
+    match std::env::consts::OS {
+        "linux" => {
+            let meminfo = tokio::fs::read_to_string("/proc/meminfo").await.ok()?;
+            let kib = meminfo
+                .lines()
+                .find_map(|line| line.strip_prefix("MemTotal:"))?
+                .trim()
+                .trim_end_matches("kB")
+                .trim()
+                .parse::<u64>()
+                .ok()?;
+            Some(kib * 1024)
+        }
+        "macos" => {
+            let output = command_output("sysctl", &["-n", "hw.memsize"]).await?;
+            String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+        }
+        _ => None,
+    }
+}
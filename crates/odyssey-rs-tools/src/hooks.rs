@@ -0,0 +1,39 @@
+//! Hook interface invoked around every tool call.
+
+use crate::context::ToolContext;
+use async_trait::async_trait;
+use odyssey_rs_protocol::ToolError;
+use serde_json::Value;
+
+/// Whether a tool call may proceed after `before_tool`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolHookDecision {
+    /// Run the tool with the (possibly rewritten) arguments.
+    Continue,
+    /// Skip the tool and fail the call with the given reason.
+    Veto { reason: String },
+}
+
+/// Hook called before and after each tool call in a turn.
+///
+/// Unlike permission checks, hooks see the tool arguments and result, so they
+/// suit logging, billing, and custom guardrails.
+#[async_trait]
+pub trait ToolCallHook: Send + Sync {
+    /// Inspect or rewrite arguments before the tool runs.
+    async fn before_tool(
+        &self,
+        ctx: &ToolContext,
+        tool_name: &str,
+        args: &mut Value,
+    ) -> ToolHookDecision;
+
+    /// Observe the outcome of a tool call before output policies apply.
+    async fn after_tool(
+        &self,
+        ctx: &ToolContext,
+        tool_name: &str,
+        args: &Value,
+        result: &Result<Value, ToolError>,
+    );
+}
@@ -3,31 +3,50 @@
 pub mod adaptor;
 pub mod builtins;
 pub mod context;
+pub mod environment;
 pub mod events;
+pub mod hooks;
 pub mod output_policy;
 pub mod permissions;
 pub mod question;
 pub mod registry;
+pub mod scheduler;
+pub mod secrets;
 pub mod tool;
 pub mod web;
 
 /// Tool adaptor helpers.
-pub use adaptor::{ToolAdaptor, tool_to_adaptor, tools_to_adaptors};
+pub use adaptor::{ToolAdaptor, scheduled_adaptors, tool_to_adaptor, tools_to_adaptors};
 /// Built-in tool registry and registration helper.
 pub use builtins::{builtin_tool_registry, register_builtin_tools};
 /// Tool context and result handling types.
-pub use context::{ToolContext, ToolResultHandler, ToolSandbox, TurnServices};
+pub use context::{
+    FileVersions, MAX_TRANSIENT_RETRIES, SessionCwd, ToolContext, ToolContextBuilder,
+    ToolResultHandler, ToolSandbox, TurnServices,
+};
+/// Host environment probe.
+pub use environment::{EnvironmentReport, SandboxDependencies, ToolchainVersion};
 /// Event sink for streaming events (re-exported from protocol).
 pub use events::EventSink;
-/// Tool output policy.
-pub use output_policy::ToolOutputPolicy;
+/// Hooks invoked around tool calls.
+pub use hooks::{ToolCallHook, ToolHookDecision};
+/// Tool output policy and token estimation.
+pub use output_policy::{ApproxTokenEstimator, TokenEstimator, ToolOutputPolicy};
 /// Permission checking interfaces for tool execution.
 pub use permissions::{PermissionChecker, PermissionContext, PermissionOutcome};
 /// Question prompt types for interactive tools.
 pub use question::{Question, QuestionAnswer, QuestionHandler, QuestionOption};
 /// Tool registry type.
 pub use registry::ToolRegistry;
-/// Tool trait and spec type.
-pub use tool::{Tool, ToolSpec};
+/// Tool concurrency scheduler.
+pub use scheduler::{ToolPermit, ToolScheduler};
+/// Secret providers for `api_key_ref` config entries.
+pub use secrets::{
+    EnvSecrets, FileSecrets, KeyringSecrets, SecretError, SecretResolver, SecretsProvider,
+};
+/// Tool trait, spec type, and streaming delta sink.
+pub use tool::{Tool, ToolDeltaSink, ToolSpec};
 /// Web provider types.
-pub use web::{WebFetchResult, WebProvider, WebSearchResult};
+pub use web::{
+    DownloadResponse, HttpRequestSpec, HttpResponse, WebFetchResult, WebProvider, WebSearchResult,
+};
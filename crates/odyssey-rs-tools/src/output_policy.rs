@@ -1,12 +1,25 @@
 //! Tool output redaction and truncation policy.
 
 use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
 
 /// Policy that redacts and truncates tool outputs for safety.
 #[derive(Debug, Clone)]
 pub struct ToolOutputPolicy {
     /// Maximum size of string fields in bytes.
     pub max_string_bytes: usize,
+    /// Maximum size of string fields in estimated tokens.
+    ///
+    /// When set, strings are budgeted in tokens instead of bytes and keep
+    /// their head and tail around an elision marker.
+    pub max_string_tokens: Option<usize>,
+    /// Estimator used to count tokens for `max_string_tokens`.
+    pub token_estimator: Arc<dyn TokenEstimator>,
+    /// Largest image returned inline (base64) by file tools; `None` disables inline images.
+    ///
+    /// Inline images are sized by this limit and pass through string truncation untouched.
+    pub max_image_bytes: Option<usize>,
     /// Maximum number of elements in arrays.
     pub max_array_len: usize,
     /// Maximum number of object entries.
@@ -24,6 +37,9 @@ impl Default for ToolOutputPolicy {
     fn default() -> Self {
         Self {
             max_string_bytes: 32 * 1024,
+            max_string_tokens: None,
+            token_estimator: Arc::new(ApproxTokenEstimator::default()),
+            max_image_bytes: None,
             max_array_len: 256,
             max_object_entries: 256,
             redact_keys: Vec::new(),
@@ -33,6 +49,93 @@ impl Default for ToolOutputPolicy {
     }
 }
 
+/// Counts how many model tokens a text is expected to use.
+pub trait TokenEstimator: Send + Sync + fmt::Debug {
+    /// Estimate the token count of `text`.
+    fn estimate(&self, text: &str) -> usize;
+}
+
+/// Tokenizer-free estimator modeled on BPE pre-tokenization.
+///
+/// Text is split into pieces the way tiktoken-style tokenizers split it before
+/// merging: runs of letters, digit groups of up to three, punctuation runs, and
+/// whitespace, with a lone whitespace character joining the following piece. Each
+/// piece costs one token per `chars_per_token` characters, and every non-ASCII
+/// character costs a token of its own.
+#[derive(Debug, Clone, Copy)]
+pub struct ApproxTokenEstimator {
+    /// Characters per token within a piece.
+    pub chars_per_token: usize,
+}
+
+impl Default for ApproxTokenEstimator {
+    fn default() -> Self {
+        Self { chars_per_token: 4 }
+    }
+}
+
+/// Character classes used to split text into pieces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Letter,
+    Digit,
+    Punctuation,
+    Whitespace,
+}
+
+impl CharClass {
+    fn of(ch: char) -> Self {
+        if ch.is_ascii_alphabetic() {
+            CharClass::Letter
+        } else if ch.is_ascii_digit() {
+            CharClass::Digit
+        } else if ch.is_ascii_whitespace() {
+            CharClass::Whitespace
+        } else {
+            CharClass::Punctuation
+        }
+    }
+}
+
+impl TokenEstimator for ApproxTokenEstimator {
+    fn estimate(&self, text: &str) -> usize {
+        let chars_per_token = self.chars_per_token.max(1);
+        let mut tokens = 0;
+        // Current piece: its class, class-run length, and total characters.
+        let mut piece: Option<(CharClass, usize, usize)> = None;
+        for ch in text.chars() {
+            if !ch.is_ascii() {
+                if let Some((_, _, chars)) = piece.take() {
+                    tokens += chars.div_ceil(chars_per_token);
+                }
+                tokens += 1;
+                continue;
+            }
+            let class = CharClass::of(ch);
+            piece = match piece {
+                Some((current, run, chars))
+                    if current == class && !(class == CharClass::Digit && run == 3) =>
+                {
+                    Some((class, run + 1, chars + 1))
+                }
+                // A lone whitespace character is merged into the piece after it.
+                Some((CharClass::Whitespace, 1, 1)) if class != CharClass::Whitespace => {
+                    Some((class, 1, 2))
+                }
+                Some((_, _, chars)) => {
+                    tokens += chars.div_ceil(chars_per_token);
+                    Some((class, 1, 1))
+                }
+                None => Some((class, 1, 1)),
+            };
+        }
+        if let Some((_, _, chars)) = piece {
+            tokens += chars.div_ceil(chars_per_token);
+        }
+        tokens
+    }
+}
+
 impl ToolOutputPolicy {
     /// Apply the policy to a JSON value.
     pub fn apply(&self, value: Value) -> Value {
@@ -51,6 +154,7 @@ impl ToolOutputPolicy {
                     .collect();
                 Value::Array(trimmed)
             }
+            Value::Object(values) if is_inline_image(&values) => Value::Object(values),
             Value::Object(values) => {
                 let mut trimmed =
                     serde_json::Map::with_capacity(values.len().min(self.max_object_entries));
@@ -95,8 +199,40 @@ impl ToolOutputPolicy {
         })
     }
 
-    /// Truncate a string to the maximum byte size boundary.
+    /// Truncate a string to the token budget, or the byte limit without one.
     fn truncate_string(&self, value: String) -> String {
+        match self.max_string_tokens {
+            Some(max_tokens) => self.truncate_tokens(value, max_tokens),
+            None => self.truncate_bytes(value),
+        }
+    }
+
+    /// Keep the head and tail of a string within a token budget.
+    fn truncate_tokens(&self, value: String, max_tokens: usize) -> String {
+        let estimator = self.token_estimator.as_ref();
+        let total = estimator.estimate(&value);
+        if total <= max_tokens {
+            return value;
+        }
+        let boundaries = value
+            .char_indices()
+            .map(|(idx, _)| idx)
+            .chain(std::iter::once(value.len()))
+            .collect::<Vec<_>>();
+        let marker_tokens = estimator.estimate(&elision_marker(total));
+        let Some(budget) = max_tokens.checked_sub(marker_tokens) else {
+            let head_end = prefix_end(&value, &boundaries, estimator, max_tokens);
+            return value[..head_end].to_string();
+        };
+        let head_end = prefix_end(&value, &boundaries, estimator, budget.div_ceil(2));
+        let tail_start = suffix_start(&value, &boundaries, estimator, budget / 2).max(head_end);
+        let (head, tail) = (&value[..head_end], &value[tail_start..]);
+        let elided = total.saturating_sub(estimator.estimate(head) + estimator.estimate(tail));
+        format!("{head}{}{tail}", elision_marker(elided))
+    }
+
+    /// Truncate a string to the maximum byte size boundary.
+    fn truncate_bytes(&self, value: String) -> String {
         let max_bytes = self.max_string_bytes;
         if max_bytes == 0 {
             return String::new();
@@ -116,9 +252,44 @@ impl ToolOutputPolicy {
     }
 }
 
+/// Return true for an inline image attachment (`{ "type": "image", "mime_type", "data" }`).
+fn is_inline_image(values: &serde_json::Map<String, Value>) -> bool {
+    values.get("type").and_then(Value::as_str) == Some("image")
+        && values.get("mime_type").is_some_and(Value::is_string)
+        && values.get("data").is_some_and(Value::is_string)
+}
+
+/// Marker inserted where truncated text was removed.
+fn elision_marker(elided_tokens: usize) -> String {
+    format!("\n[... {elided_tokens} tokens elided ...]\n")
+}
+
+/// Largest char boundary whose prefix fits within `max_tokens`.
+fn prefix_end(
+    value: &str,
+    boundaries: &[usize],
+    estimator: &dyn TokenEstimator,
+    max_tokens: usize,
+) -> usize {
+    let fits = boundaries.partition_point(|&end| estimator.estimate(&value[..end]) <= max_tokens);
+    boundaries[fits.saturating_sub(1)]
+}
+
+/// Smallest char boundary whose suffix fits within `max_tokens`.
+fn suffix_start(
+    value: &str,
+    boundaries: &[usize],
+    estimator: &dyn TokenEstimator,
+    max_tokens: usize,
+) -> usize {
+    let too_long =
+        boundaries.partition_point(|&start| estimator.estimate(&value[start..]) > max_tokens);
+    boundaries[too_long.min(boundaries.len() - 1)]
+}
+
 #[cfg(test)]
 mod tests {
-    use super::ToolOutputPolicy;
+    use super::{ApproxTokenEstimator, TokenEstimator, ToolOutputPolicy};
     use pretty_assertions::assert_eq;
     use serde_json::json;
 
@@ -131,6 +302,7 @@ mod tests {
             redact_keys: vec!["secret".to_string()],
             redact_values: vec!["token".to_string()],
             replacement: "[X]".to_string(),
+            ..ToolOutputPolicy::default()
         };
         let input = json!({
             "secret": "value",
@@ -155,6 +327,7 @@ mod tests {
             redact_keys: Vec::new(),
             redact_values: Vec::new(),
             replacement: "[X]".to_string(),
+            ..ToolOutputPolicy::default()
         };
         let input = json!({
             "list": ["first", "second", "third"],
@@ -167,4 +340,47 @@ mod tests {
         });
         assert_eq!(output, expected);
     }
+
+    #[test]
+    fn estimates_tokens_by_piece() {
+        let estimator = ApproxTokenEstimator::default();
+        assert_eq!(estimator.estimate(""), 0);
+        assert_eq!(estimator.estimate("fn main"), 3);
+        assert_eq!(estimator.estimate("1234567"), 3);
+        assert_eq!(estimator.estimate("héllo"), 3);
+    }
+
+    #[test]
+    fn apply_truncates_strings_to_token_budget() {
+        let policy = ToolOutputPolicy {
+            max_string_tokens: Some(20),
+            ..ToolOutputPolicy::default()
+        };
+        let lines = (0..40)
+            .map(|idx| format!("line {idx:02}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let output = policy.apply(json!({ "stdout": lines, "short": "ok" }));
+
+        let stdout = output["stdout"].as_str().expect("stdout");
+        assert!(stdout.starts_with("line 00\nline 01"));
+        assert!(stdout.ends_with("line 38\nline 39"));
+        assert!(stdout.contains(" tokens elided ...]"));
+        assert!(ApproxTokenEstimator::default().estimate(stdout) <= 20);
+        assert_eq!(output["short"], "ok");
+    }
+
+    #[test]
+    fn apply_keeps_inline_images_intact() {
+        let policy = ToolOutputPolicy {
+            max_string_bytes: 4,
+            ..ToolOutputPolicy::default()
+        };
+        let image = json!({ "type": "image", "mime_type": "image/png", "data": "iVBORw0KGgo=" });
+
+        let output = policy.apply(json!({ "mime_type": "image/png", "image": image.clone() }));
+
+        assert_eq!(output, json!({ "mime_type": "imag", "image": image }));
+    }
 }
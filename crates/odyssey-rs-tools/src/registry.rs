@@ -2,6 +2,7 @@
 
 use crate::tool::{Tool, ToolSpec};
 use log::debug;
+use odyssey_rs_protocol::ToolRisk;
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -40,6 +41,15 @@ impl ToolRegistry {
         self.tools.read().values().cloned().collect()
     }
 
+    /// Return the risk class of every registered tool by name.
+    pub fn risks(&self) -> HashMap<String, ToolRisk> {
+        self.tools
+            .read()
+            .iter()
+            .map(|(name, tool)| (name.clone(), tool.risk()))
+            .collect()
+    }
+
     /// Return tool specs for all registered tools.
     pub fn specs(&self) -> Vec<ToolSpec> {
         self.tools.read().values().map(|tool| tool.spec()).collect()
@@ -49,9 +59,9 @@ impl ToolRegistry {
 #[cfg(test)]
 mod tests {
     use super::ToolRegistry;
-    use crate::{Tool, ToolContext};
+    use crate::{Tool, ToolContext, builtin_tool_registry};
     use async_trait::async_trait;
-    use odyssey_rs_protocol::ToolError;
+    use odyssey_rs_protocol::{ToolError, ToolRisk};
     use pretty_assertions::assert_eq;
     use serde_json::json;
     use std::fmt;
@@ -106,4 +116,18 @@ mod tests {
         spec_names.sort();
         assert_eq!(spec_names, vec!["Read", "Write"]);
     }
+
+    #[test]
+    fn registry_reports_tool_risks() {
+        let registry = builtin_tool_registry();
+        registry.register(Arc::new(DummyTool { name: "Custom" }));
+
+        let risks = registry.risks();
+        assert_eq!(risks.get("Read"), Some(&ToolRisk::ReadOnly));
+        assert_eq!(risks.get("GitDiff"), Some(&ToolRisk::ReadOnly));
+        assert_eq!(risks.get("Edit"), Some(&ToolRisk::WorkspaceMutating));
+        assert_eq!(risks.get("WebFetch"), Some(&ToolRisk::ExternalNetwork));
+        assert_eq!(risks.get("Bash"), Some(&ToolRisk::Destructive));
+        assert_eq!(risks.get("Custom"), Some(&ToolRisk::WorkspaceMutating));
+    }
 }
@@ -0,0 +1,242 @@
+//! Concurrency limits and mutual exclusion for tool execution.
+//!
+//! Tools declare a maximum number of concurrent calls and an optional
+//! exclusion group via the `Tool` trait. The scheduler hands out permits so
+//! parallel dispatch never exceeds a tool's limit and a session never runs two
+//! tools from the same group at once. Group permits are scoped per session and
+//! taken by the tool itself (see `ToolContext::exclusive`) once its permission
+//! checks passed, so a pending approval does not hold up other writes.
+
+use crate::Tool;
+use log::debug;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use uuid::Uuid;
+
+/// Scheduler that enforces per-tool concurrency and exclusion groups.
+#[derive(Debug, Default)]
+pub struct ToolScheduler {
+    /// Semaphores keyed by tool name for tools with a concurrency limit.
+    tools: Mutex<HashMap<String, Arc<Semaphore>>>,
+    /// Single-permit semaphores keyed by session and exclusion group.
+    groups: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+/// Permits held for the duration of a tool call.
+#[derive(Debug)]
+pub struct ToolPermit {
+    _group: Option<OwnedSemaphorePermit>,
+    _tool: Option<OwnedSemaphorePermit>,
+}
+
+impl ToolScheduler {
+    /// Create an empty scheduler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until the tool is under its concurrency limit and return the permit
+    /// that reserves its slot.
+    pub async fn acquire(&self, tool: &dyn Tool) -> ToolPermit {
+        let limit = match tool.max_concurrency() {
+            Some(limit) => {
+                let semaphore = semaphore_for(&self.tools, tool.name(), limit.max(1));
+                acquire_owned(semaphore).await
+            }
+            None => None,
+        };
+        ToolPermit {
+            _group: None,
+            _tool: limit,
+        }
+    }
+
+    /// Wait until no other tool of `tool`'s exclusion group runs in `session_id`.
+    ///
+    /// Returns an empty permit for tools without a group.
+    pub async fn acquire_group(&self, session_id: Uuid, tool: &dyn Tool) -> ToolPermit {
+        let group = match tool.exclusion_group() {
+            Some(group) => {
+                let semaphore = {
+                    let mut groups = self.groups.lock();
+                    // Drop semaphores nobody holds or waits on so finished
+                    // sessions do not accumulate.
+                    groups.retain(|_, semaphore| Arc::strong_count(semaphore) > 1);
+                    groups
+                        .entry(format!("{session_id}/{group}"))
+                        .or_insert_with(|| Arc::new(Semaphore::new(1)))
+                        .clone()
+                };
+                debug!(
+                    "waiting for tool exclusion group (tool={}, group={group}, session_id={session_id})",
+                    tool.name()
+                );
+                acquire_owned(semaphore).await
+            }
+            None => None,
+        };
+        ToolPermit {
+            _group: group,
+            _tool: None,
+        }
+    }
+}
+
+/// Fetch or create the semaphore for a key.
+fn semaphore_for(
+    map: &Mutex<HashMap<String, Arc<Semaphore>>>,
+    key: &str,
+    permits: usize,
+) -> Arc<Semaphore> {
+    map.lock()
+        .entry(key.to_string())
+        .or_insert_with(|| Arc::new(Semaphore::new(permits)))
+        .clone()
+}
+
+/// Acquire an owned permit; semaphores are never closed, so failure yields no permit.
+async fn acquire_owned(semaphore: Arc<Semaphore>) -> Option<OwnedSemaphorePermit> {
+    semaphore.acquire_owned().await.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ToolScheduler;
+    use crate::{Tool, ToolContext};
+    use async_trait::async_trait;
+    use odyssey_rs_protocol::ToolError;
+    use pretty_assertions::assert_eq;
+    use serde_json::{Value, json};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    #[derive(Debug, Default)]
+    struct Gauge {
+        active: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    impl Gauge {
+        async fn hold(&self) {
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(active, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.active.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    #[derive(Debug)]
+    struct LimitedTool {
+        name: &'static str,
+        group: Option<&'static str>,
+        limit: Option<usize>,
+    }
+
+    #[async_trait]
+    impl Tool for LimitedTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "limited tool"
+        }
+
+        fn args_schema(&self) -> Value {
+            json!({})
+        }
+
+        fn max_concurrency(&self) -> Option<usize> {
+            self.limit
+        }
+
+        fn exclusion_group(&self) -> Option<&str> {
+            self.group
+        }
+
+        async fn call(&self, _ctx: &ToolContext, _args: Value) -> Result<Value, ToolError> {
+            Ok(json!({}))
+        }
+    }
+
+    async fn run(scheduler: &ToolScheduler, tool: &LimitedTool, gauge: &Gauge) {
+        run_in(scheduler, Uuid::nil(), tool, gauge).await;
+    }
+
+    async fn run_in(
+        scheduler: &ToolScheduler,
+        session_id: Uuid,
+        tool: &LimitedTool,
+        gauge: &Gauge,
+    ) {
+        let _permit = scheduler.acquire(tool).await;
+        let _group = scheduler.acquire_group(session_id, tool).await;
+        gauge.hold().await;
+    }
+
+    #[tokio::test]
+    async fn exclusion_group_serializes_tools() {
+        let scheduler = ToolScheduler::new();
+        let gauge = Gauge::default();
+        let write = LimitedTool {
+            name: "Write",
+            group: Some("fs"),
+            limit: None,
+        };
+        let edit = LimitedTool {
+            name: "Edit",
+            group: Some("fs"),
+            limit: None,
+        };
+        tokio::join!(
+            run(&scheduler, &write, &gauge),
+            run(&scheduler, &edit, &gauge),
+            run(&scheduler, &write, &gauge),
+        );
+        assert_eq!(gauge.peak.load(Ordering::SeqCst), 1);
+
+        let gauge = Gauge::default();
+        tokio::join!(
+            run_in(&scheduler, Uuid::new_v4(), &write, &gauge),
+            run_in(&scheduler, Uuid::new_v4(), &edit, &gauge),
+        );
+        assert_eq!(gauge.peak.load(Ordering::SeqCst), 2);
+        // Idle groups of finished sessions are dropped on the next acquire.
+        drop(scheduler.acquire_group(Uuid::new_v4(), &write).await);
+        assert_eq!(scheduler.groups.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn max_concurrency_caps_parallel_calls() {
+        let scheduler = ToolScheduler::new();
+        let gauge = Gauge::default();
+        let limited = LimitedTool {
+            name: "Fetch",
+            group: None,
+            limit: Some(2),
+        };
+        let free = LimitedTool {
+            name: "Read",
+            group: None,
+            limit: None,
+        };
+        tokio::join!(
+            run(&scheduler, &limited, &gauge),
+            run(&scheduler, &limited, &gauge),
+            run(&scheduler, &limited, &gauge),
+            run(&scheduler, &limited, &gauge),
+        );
+        assert_eq!(gauge.peak.load(Ordering::SeqCst), 2);
+
+        let gauge = Gauge::default();
+        tokio::join!(
+            run(&scheduler, &free, &gauge),
+            run(&scheduler, &free, &gauge),
+            run(&scheduler, &free, &gauge),
+        );
+        assert_eq!(gauge.peak.load(Ordering::SeqCst), 3);
+    }
+}
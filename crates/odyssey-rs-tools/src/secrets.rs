@@ -0,0 +1,225 @@
+//! Secret lookup for `api_key_ref` entries in config.
+//!
+//! A reference names a backend and a key: `env:NAME` reads an environment
+//! variable, `file:NAME` reads an entry of a JSON secrets file, and
+//! `keyring:NAME` reads the OS keyring (with the `keyring` feature).
+//! [`SecretResolver`] routes each reference to its [`SecretsProvider`], so API
+//! keys do not have to live in config files or shell profiles.
+
+use log::debug;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Errors returned while resolving a secret.
+#[derive(Debug, Error)]
+pub enum SecretError {
+    /// The reference is not `scheme:name` or names an unknown backend.
+    #[error("invalid secret reference {0:?}; expected env:NAME, file:NAME, or keyring:NAME")]
+    InvalidReference(String),
+    /// The backend has no secret with this name.
+    #[error("secret not found: {0}")]
+    NotFound(String),
+    /// The backend could not be read.
+    #[error("secret backend unavailable: {0}")]
+    Unavailable(String),
+}
+
+/// Backend that looks secrets up by name.
+pub trait SecretsProvider: Send + Sync {
+    /// Secret stored under `name`.
+    fn get(&self, name: &str) -> Result<String, SecretError>;
+}
+
+/// Reads secrets from environment variables.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EnvSecrets;
+
+impl SecretsProvider for EnvSecrets {
+    fn get(&self, name: &str) -> Result<String, SecretError> {
+        std::env::var(name).map_err(|_| SecretError::NotFound(format!("env:{name}")))
+    }
+}
+
+/// Reads secrets from a JSON object file mapping names to values.
+///
+/// The file is read on every lookup, so edits apply without a restart.
+#[derive(Debug, Clone)]
+pub struct FileSecrets {
+    path: PathBuf,
+}
+
+impl FileSecrets {
+    /// Read secrets from `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl SecretsProvider for FileSecrets {
+    fn get(&self, name: &str) -> Result<String, SecretError> {
+        let unavailable =
+            |err: String| SecretError::Unavailable(format!("{}: {err}", self.path.display()));
+        let contents =
+            std::fs::read_to_string(&self.path).map_err(|err| unavailable(err.to_string()))?;
+        let secrets: BTreeMap<String, Value> =
+            serde_json::from_str(&contents).map_err(|err| unavailable(err.to_string()))?;
+        match secrets.get(name) {
+            Some(Value::String(secret)) => Ok(secret.clone()),
+            Some(_) => Err(unavailable(format!("entry {name:?} is not a string"))),
+            None => Err(SecretError::NotFound(format!("file:{name}"))),
+        }
+    }
+}
+
+/// Reads secrets from the OS keyring (Keychain, Credential Manager, or Secret Service).
+#[derive(Debug, Clone)]
+pub struct KeyringSecrets {
+    service: String,
+}
+
+impl KeyringSecrets {
+    /// Read entries stored under `service`, with the secret name as the user.
+    pub fn new(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+        }
+    }
+}
+
+impl SecretsProvider for KeyringSecrets {
+    #[cfg(feature = "keyring")]
+    fn get(&self, name: &str) -> Result<String, SecretError> {
+        let entry = keyring::Entry::new(&self.service, name)
+            .map_err(|err| SecretError::Unavailable(err.to_string()))?;
+        match entry.get_password() {
+            Ok(secret) => Ok(secret),
+            Err(keyring::Error::NoEntry) => Err(SecretError::NotFound(format!("keyring:{name}"))),
+            Err(err) => Err(SecretError::Unavailable(err.to_string())),
+        }
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    fn get(&self, name: &str) -> Result<String, SecretError> {
+        Err(SecretError::Unavailable(format!(
+            "keyring:{name} (service {}): built without the keyring feature",
+            self.service
+        )))
+    }
+}
+
+/// Resolves `scheme:name` references through the provider registered for the scheme.
+#[derive(Clone)]
+pub struct SecretResolver {
+    providers: BTreeMap<String, Arc<dyn SecretsProvider>>,
+}
+
+impl SecretResolver {
+    /// Resolver for `env:`, `keyring:` (entries under `keyring_service`), and,
+    /// when `file` is set, `file:` references.
+    pub fn new(file: Option<PathBuf>, keyring_service: impl Into<String>) -> Self {
+        let resolver = Self {
+            providers: BTreeMap::new(),
+        }
+        .with_provider("env", Arc::new(EnvSecrets))
+        .with_provider("keyring", Arc::new(KeyringSecrets::new(keyring_service)));
+        match file {
+            Some(path) => resolver.with_provider("file", Arc::new(FileSecrets::new(path))),
+            None => resolver,
+        }
+    }
+
+    /// Register or replace the provider for `scheme`.
+    pub fn with_provider(
+        mut self,
+        scheme: impl Into<String>,
+        provider: Arc<dyn SecretsProvider>,
+    ) -> Self {
+        self.providers.insert(scheme.into(), provider);
+        self
+    }
+
+    /// Resolve a reference such as `keyring:openai`.
+    pub fn resolve(&self, reference: &str) -> Result<String, SecretError> {
+        let invalid = || SecretError::InvalidReference(reference.to_string());
+        let (scheme, name) = reference.split_once(':').ok_or_else(invalid)?;
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        let provider = match self.providers.get(scheme) {
+            Some(provider) => provider,
+            None if scheme == "file" => {
+                return Err(SecretError::Unavailable(format!(
+                    "{reference}: no secrets file configured"
+                )));
+            }
+            None => return Err(invalid()),
+        };
+        debug!("resolving secret (scheme={scheme})");
+        provider.get(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SecretError, SecretResolver, SecretsProvider};
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    struct FixedSecrets;
+
+    impl SecretsProvider for FixedSecrets {
+        fn get(&self, name: &str) -> Result<String, SecretError> {
+            match name {
+                "openai" => Ok("sk-keyring".to_string()),
+                _ => Err(SecretError::NotFound(name.to_string())),
+            }
+        }
+    }
+
+    #[test]
+    fn resolves_references_by_scheme() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("secrets.json");
+        std::fs::write(&path, r#"{ "search": "file-key", "count": 3 }"#).expect("write");
+        let resolver = SecretResolver::new(Some(path), "odyssey")
+            .with_provider("keyring", Arc::new(FixedSecrets));
+
+        assert_eq!(resolver.resolve("file:search").expect("file"), "file-key");
+        assert_eq!(
+            resolver.resolve("keyring:openai").expect("keyring"),
+            "sk-keyring"
+        );
+        assert!(matches!(
+            resolver.resolve("file:missing"),
+            Err(SecretError::NotFound(_))
+        ));
+        assert!(matches!(
+            resolver.resolve("file:count"),
+            Err(SecretError::Unavailable(_))
+        ));
+        assert!(matches!(
+            resolver.resolve("env:ODYSSEY_TEST_UNSET_SECRET"),
+            Err(SecretError::NotFound(_))
+        ));
+        for reference in ["vault:openai", "openai", "env:"] {
+            assert!(matches!(
+                resolver.resolve(reference),
+                Err(SecretError::InvalidReference(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn file_references_need_a_secrets_file() {
+        let resolver = SecretResolver::new(None, "odyssey");
+        let err = resolver.resolve("file:search").expect_err("no file");
+        assert_eq!(
+            err.to_string(),
+            "secret backend unavailable: file:search: no secrets file configured"
+        );
+    }
+}
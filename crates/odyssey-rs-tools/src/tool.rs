@@ -2,7 +2,7 @@
 
 use crate::context::ToolContext;
 use async_trait::async_trait;
-use odyssey_rs_protocol::ToolError;
+use odyssey_rs_protocol::{ToolError, ToolRisk};
 use serde_json::Value;
 use std::fmt::Debug;
 
@@ -17,6 +17,12 @@ pub struct ToolSpec {
     pub args_schema: Value,
 }
 
+/// Receiver for partial tool output pushed before the final result.
+pub trait ToolDeltaSink: Send + Sync {
+    /// Push an incremental output chunk.
+    fn push(&self, delta: Value);
+}
+
 /// Interface for executable tools.
 #[async_trait]
 pub trait Tool: Send + Sync + Debug {
@@ -32,9 +38,50 @@ pub trait Tool: Send + Sync + Debug {
         false
     }
 
+    /// Whether the tool mutates the workspace or runs commands.
+    ///
+    /// Mutating tools return a preview instead of executing in simulation mode.
+    fn is_mutating(&self) -> bool {
+        false
+    }
+
+    /// Static risk class used to pick approval behavior and prompt badges.
+    ///
+    /// Unclassified tools count as workspace-mutating so they are never
+    /// auto-allowed as read-only.
+    fn risk(&self) -> ToolRisk {
+        ToolRisk::WorkspaceMutating
+    }
+
+    /// Maximum number of calls to this tool that may run at once (`None` is unlimited).
+    fn max_concurrency(&self) -> Option<usize> {
+        None
+    }
+
+    /// Exclusion group shared with tools that must never run concurrently with this one
+    /// in the same session.
+    ///
+    /// The permit is taken by calling [`ToolContext::exclusive`] from `call` once the
+    /// permission checks passed.
+    fn exclusion_group(&self) -> Option<&str> {
+        None
+    }
+
     /// Invoke the tool with a context and arguments.
     async fn call(&self, ctx: &ToolContext, args: Value) -> Result<Value, ToolError>;
 
+    /// Invoke the tool, pushing partial results to `sink` as they become available.
+    ///
+    /// The returned value is still the complete result. Defaults to `call`.
+    async fn call_streaming(
+        &self,
+        ctx: &ToolContext,
+        args: Value,
+        _sink: &dyn ToolDeltaSink,
+    ) -> Result<Value, ToolError> {
+        self.call(ctx, args).await
+    }
+
     /// Build a `ToolSpec` describing this tool.
     fn spec(&self) -> ToolSpec {
         ToolSpec {
@@ -3,6 +3,8 @@
 use async_trait::async_trait;
 use odyssey_rs_protocol::ToolError;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 /// Search result returned by a web provider.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,11 +32,107 @@ pub struct WebFetchResult {
     pub truncated: bool,
 }
 
+/// HTTP request issued by the HttpRequest tool.
+#[derive(Debug, Clone)]
+pub struct HttpRequestSpec {
+    /// Upper-case HTTP method.
+    pub method: String,
+    /// Target URL; its host has already passed the domain checks.
+    pub url: String,
+    /// Request headers.
+    pub headers: BTreeMap<String, String>,
+    /// Optional request body.
+    pub body: Option<String>,
+    /// Time allowed for the whole request.
+    pub timeout: Duration,
+    /// Maximum response body bytes to return.
+    pub max_bytes: usize,
+}
+
+/// Response returned for an HttpRequest call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// Response headers.
+    pub headers: BTreeMap<String, String>,
+    /// Optional content type.
+    pub content_type: Option<String>,
+    /// Response body (possibly truncated).
+    pub body: String,
+    /// Whether the body was truncated.
+    pub truncated: bool,
+}
+
+/// Response metadata for a download; the body is streamed to the caller.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// Optional content type.
+    pub content_type: Option<String>,
+    /// Redirect target for 3xx responses.
+    pub location: Option<String>,
+}
+
 /// Web provider interface for search and fetch operations.
 #[async_trait]
 pub trait WebProvider: Send + Sync {
     /// Perform a web search query.
     async fn search(&self, query: &str, limit: usize) -> Result<Vec<WebSearchResult>, ToolError>;
+
+    /// Perform a web search with the API key from `tools.web.api_key_ref`.
+    ///
+    /// Defaults to `search`, ignoring the key.
+    async fn search_with_key(
+        &self,
+        query: &str,
+        limit: usize,
+        _api_key: &str,
+    ) -> Result<Vec<WebSearchResult>, ToolError> {
+        self.search(query, limit).await
+    }
     /// Fetch a URL with a maximum byte limit.
     async fn fetch(&self, url: &str, max_bytes: usize) -> Result<WebFetchResult, ToolError>;
+
+    /// Fetch a URL, reporting body chunks to `on_chunk` as they arrive.
+    ///
+    /// Defaults to `fetch` and reports the whole body as a single chunk.
+    async fn fetch_streaming(
+        &self,
+        url: &str,
+        max_bytes: usize,
+        on_chunk: &(dyn Fn(&str) + Send + Sync),
+    ) -> Result<WebFetchResult, ToolError> {
+        let result = self.fetch(url, max_bytes).await?;
+        if !result.body.is_empty() {
+            on_chunk(&result.body);
+        }
+        Ok(result)
+    }
+
+    /// Send an arbitrary HTTP request.
+    ///
+    /// Implementations must not follow redirects, so every host the request
+    /// reaches has passed the domain checks. Defaults to unsupported.
+    async fn request(&self, _request: HttpRequestSpec) -> Result<HttpResponse, ToolError> {
+        Err(ToolError::ExecutionFailed(
+            "web provider does not support HTTP requests".to_string(),
+        ))
+    }
+
+    /// Download a URL, passing body chunks to `on_chunk` as they arrive.
+    ///
+    /// Returning an error from `on_chunk` aborts the transfer. Like `request`,
+    /// implementations must not follow redirects; the Download tool checks and
+    /// follows `location` itself. Defaults to unsupported.
+    async fn download(
+        &self,
+        _url: &str,
+        _on_chunk: &mut (dyn FnMut(&[u8]) -> Result<(), ToolError> + Send),
+    ) -> Result<DownloadResponse, ToolError> {
+        Err(ToolError::ExecutionFailed(
+            "web provider does not support downloads".to_string(),
+        ))
+    }
 }
@@ -0,0 +1,627 @@
+//! Agent Client Protocol (ACP) adapter.
+//!
+//! Editors that speak ACP (e.g. Zed) launch `odyssey-rs-tui acp` and exchange
+//! newline-delimited JSON-RPC 2.0 messages over stdin/stdout. Prompts are
+//! submitted through the orchestrator's submission queue, orchestrator events
+//! are forwarded as `session/update` notifications, and permission requests are
+//! asked through `session/request_permission`.
+
+use crate::event_bus::EventBus;
+use crate::jsonrpc::{Incoming, RpcError, error_response, response, write_message};
+use anyhow::Context;
+use chrono::Utc;
+use log::{debug, info, warn};
+use odyssey_rs_core::Orchestrator;
+use odyssey_rs_core::error::OdysseyCoreError;
+use odyssey_rs_protocol::{
+    ApprovalDecision, Attachment, EventMsg, EventPayload, PermissionRequest, SessionId,
+    SubmissionEnvelope, SubmissionPayload, TurnContextOverride, TurnId,
+};
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, BufReader};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// ACP protocol version implemented by the adapter.
+pub const PROTOCOL_VERSION: u64 = 1;
+
+/// Permission options offered to the client, by ACP option id.
+const PERMISSION_OPTIONS: [(&str, &str, ApprovalDecision); 3] = [
+    ("allow_once", "Allow once", ApprovalDecision::AllowOnce),
+    (
+        "allow_always",
+        "Always allow",
+        ApprovalDecision::AllowAlways,
+    ),
+    ("reject_once", "Deny", ApprovalDecision::Deny),
+];
+
+/// Serve ACP on `reader`/`writer` until the client closes its end.
+///
+/// Sessions created over ACP use `agent_id`, or the default agent when unset.
+pub async fn serve<R, W>(
+    orchestrator: Arc<Orchestrator>,
+    events: EventBus,
+    agent_id: Option<String>,
+    reader: R,
+    writer: W,
+) -> anyhow::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut server = AcpServer {
+        orchestrator,
+        agent_id,
+        writer,
+        sessions: HashSet::new(),
+        prompts: HashMap::new(),
+        permissions: HashMap::new(),
+        next_request_id: 0,
+    };
+    let mut receiver = events.subscribe();
+    let mut lines = BufReader::new(reader).lines();
+    info!("serving ACP over stdio");
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                match line.context("failed to read from client")? {
+                    Some(line) if line.trim().is_empty() => {}
+                    Some(line) => server.handle_line(&line).await?,
+                    None => break,
+                }
+            }
+            event = receiver.recv() => {
+                match event {
+                    Ok(event) => server.handle_event(event).await?,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("ACP adapter lagged behind the event bus (skipped={skipped})");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+    info!("ACP client disconnected");
+    Ok(())
+}
+
+/// `session/prompt` request waiting for its turn to end.
+struct PendingPrompt {
+    session_id: SessionId,
+    request_id: Value,
+}
+
+struct AcpServer<W> {
+    orchestrator: Arc<Orchestrator>,
+    agent_id: Option<String>,
+    writer: W,
+    /// Sessions created by this client; events for other sessions are ignored.
+    sessions: HashSet<SessionId>,
+    /// Open prompt requests keyed by the turn they started.
+    prompts: HashMap<TurnId, PendingPrompt>,
+    /// Outgoing permission requests keyed by JSON-RPC id, mapped to approval ids.
+    permissions: HashMap<u64, Uuid>,
+    next_request_id: u64,
+}
+
+impl<W: AsyncWrite + Unpin> AcpServer<W> {
+    async fn handle_line(&mut self, line: &str) -> anyhow::Result<()> {
+        let message: Incoming = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(err) => {
+                warn!("invalid ACP message: {err}");
+                return self
+                    .send(&error_response(Value::Null, RpcError::parse(err)))
+                    .await;
+            }
+        };
+        match (message.method, message.id) {
+            (Some(method), Some(id)) => match self.handle_request(&method, message.params, &id) {
+                Ok(Some(result)) => self.send(&response(id, result)).await,
+                Ok(None) => Ok(()),
+                Err(err) => self.send(&error_response(id, err)).await,
+            },
+            (Some(method), None) => {
+                self.handle_notification(&method, &message.params);
+                Ok(())
+            }
+            (None, Some(id)) => {
+                self.handle_response(&id, message.result, message.error);
+                Ok(())
+            }
+            (None, None) => {
+                warn!("ignoring ACP message without method or id");
+                Ok(())
+            }
+        }
+    }
+
+    /// Handle a client request; `Ok(None)` means the response is sent later.
+    fn handle_request(
+        &mut self,
+        method: &str,
+        params: Value,
+        id: &Value,
+    ) -> Result<Option<Value>, RpcError> {
+        debug!("ACP request (method={method})");
+        match method {
+            "initialize" => Ok(Some(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "agentCapabilities": {
+                    "loadSession": false,
+                    "promptCapabilities": {
+                        "image": false,
+                        "audio": false,
+                        "embeddedContext": true,
+                    },
+                },
+                "authMethods": [],
+            }))),
+            "authenticate" => Ok(Some(json!({}))),
+            "session/new" => self.new_session(&params).map(Some),
+            "session/prompt" => self.prompt(&params, id.clone()).map(|()| None),
+            _ => Err(RpcError::method_not_found(method)),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) {
+        match method {
+            "session/cancel" => {
+                let Some(session_id) = session_id_param(params) else {
+                    warn!("session/cancel without a valid sessionId");
+                    return;
+                };
+                let turns: Vec<TurnId> = self
+                    .prompts
+                    .iter()
+                    .filter(|(_, prompt)| prompt.session_id == session_id)
+                    .map(|(turn_id, _)| *turn_id)
+                    .collect();
+                for turn_id in turns {
+                    info!("cancelling ACP prompt (session_id={session_id}, turn_id={turn_id})");
+                    if let Err(err) =
+                        self.submit(session_id, SubmissionPayload::CancelTurn { turn_id })
+                    {
+                        warn!("failed to cancel turn (turn_id={turn_id}): {err}");
+                    }
+                }
+            }
+            _ => debug!("ignoring ACP notification (method={method})"),
+        }
+    }
+
+    /// Resolve the approval behind a `session/request_permission` response.
+    fn handle_response(&mut self, id: &Value, result: Option<Value>, error: Option<Value>) {
+        let Some(request_id) = id.as_u64().and_then(|id| self.permissions.remove(&id)) else {
+            debug!("ignoring response to unknown request (id={id})");
+            return;
+        };
+        let decision = match (result, error) {
+            (Some(result), _) => decision_for_outcome(&result),
+            (None, error) => {
+                warn!("permission request failed (request_id={request_id}): {error:?}");
+                ApprovalDecision::Deny
+            }
+        };
+        if !self.orchestrator.resolve_approval(request_id, decision) {
+            debug!("approval no longer pending (request_id={request_id})");
+        }
+    }
+
+    fn new_session(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let cwd = params
+            .get("cwd")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::invalid_params("session/new requires cwd"))?;
+        if params
+            .get("mcpServers")
+            .and_then(Value::as_array)
+            .is_some_and(|servers| !servers.is_empty())
+        {
+            warn!("ignoring MCP servers passed by the ACP client");
+        }
+        let session_id = self
+            .orchestrator
+            .create_session(self.agent_id.clone())
+            .map_err(RpcError::internal)?;
+        self.submit(
+            session_id,
+            SubmissionPayload::OverrideTurnContext {
+                context: TurnContextOverride {
+                    cwd: Some(cwd.to_string()),
+                    ..TurnContextOverride::default()
+                },
+            },
+        )
+        .map_err(RpcError::internal)?;
+        self.sessions.insert(session_id);
+        info!("ACP session created (session_id={session_id}, cwd={cwd})");
+        Ok(json!({ "sessionId": session_id }))
+    }
+
+    fn prompt(&mut self, params: &Value, request_id: Value) -> Result<(), RpcError> {
+        let session_id = session_id_param(params)
+            .filter(|session_id| self.sessions.contains(session_id))
+            .ok_or_else(|| RpcError::invalid_params("unknown sessionId"))?;
+        let blocks = params
+            .get("prompt")
+            .and_then(Value::as_array)
+            .ok_or_else(|| RpcError::invalid_params("session/prompt requires prompt"))?;
+        let (content, attachments) = prompt_input(blocks);
+        let turn_id = self
+            .submit(
+                session_id,
+                SubmissionPayload::UserMessage {
+                    content,
+                    attachments,
+                },
+            )
+            .map_err(RpcError::internal)?;
+        self.prompts.insert(
+            turn_id,
+            PendingPrompt {
+                session_id,
+                request_id,
+            },
+        );
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: EventMsg) -> anyhow::Result<()> {
+        if !self.sessions.contains(&event.session_id) {
+            return Ok(());
+        }
+        let session_id = event.session_id;
+        if let Some(update) = session_update(&event.payload) {
+            self.send(&json!({
+                "jsonrpc": "2.0",
+                "method": "session/update",
+                "params": { "sessionId": session_id, "update": update },
+            }))
+            .await?;
+        }
+        match event.payload {
+            EventPayload::PermissionRequested {
+                request_id,
+                request,
+                ..
+            } => {
+                self.request_permission(session_id, request_id, &request)
+                    .await
+            }
+            EventPayload::TurnCompleted { turn_id, .. } => {
+                self.finish_prompt(turn_id, Ok("end_turn")).await
+            }
+            EventPayload::TurnCancelled { turn_id } => {
+                self.finish_prompt(turn_id, Ok("cancelled")).await
+            }
+            EventPayload::Error {
+                turn_id: Some(turn_id),
+                message,
+            } => {
+                self.finish_prompt(turn_id, Err(RpcError::internal(message)))
+                    .await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn request_permission(
+        &mut self,
+        session_id: SessionId,
+        request_id: Uuid,
+        request: &PermissionRequest,
+    ) -> anyhow::Result<()> {
+        let id = self.next_request_id;
+        self.next_request_id += 1;
+        self.permissions.insert(id, request_id);
+        let options: Vec<Value> = PERMISSION_OPTIONS
+            .iter()
+            .map(|(option_id, name, _)| {
+                json!({ "optionId": option_id, "name": name, "kind": option_id })
+            })
+            .collect();
+        self.send(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "session/request_permission",
+            "params": {
+                "sessionId": session_id,
+                "toolCall": {
+                    "toolCallId": request_id,
+                    "title": permission_title(request),
+                    "rawInput": request,
+                },
+                "options": options,
+            },
+        }))
+        .await
+    }
+
+    /// Answer the prompt request that started `turn_id`, if any.
+    async fn finish_prompt(
+        &mut self,
+        turn_id: TurnId,
+        outcome: Result<&str, RpcError>,
+    ) -> anyhow::Result<()> {
+        let Some(prompt) = self.prompts.remove(&turn_id) else {
+            return Ok(());
+        };
+        debug!(
+            "ACP prompt finished (turn_id={turn_id}, ok={})",
+            outcome.is_ok()
+        );
+        match outcome {
+            Ok(stop_reason) => {
+                self.send(&response(
+                    prompt.request_id,
+                    json!({ "stopReason": stop_reason }),
+                ))
+                .await
+            }
+            Err(err) => self.send(&error_response(prompt.request_id, err)).await,
+        }
+    }
+
+    /// Submit to the orchestrator queue and return the submission id.
+    fn submit(
+        &self,
+        session_id: SessionId,
+        payload: SubmissionPayload,
+    ) -> Result<Uuid, OdysseyCoreError> {
+        let id = Uuid::new_v4();
+        self.orchestrator.submit(SubmissionEnvelope {
+            id,
+            session_id,
+            created_at: Utc::now(),
+            idempotency_key: None,
+            user_id: None,
+            payload,
+        })?;
+        Ok(id)
+    }
+
+    async fn send(&mut self, message: &Value) -> anyhow::Result<()> {
+        write_message(&mut self.writer, message).await
+    }
+}
+
+fn session_id_param(params: &Value) -> Option<SessionId> {
+    params
+        .get("sessionId")
+        .and_then(Value::as_str)
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+/// Flatten ACP content blocks into prompt text and attachments.
+///
+/// Embedded text resources become attachments; resource links are kept in the
+/// text so the agent can read them with its own tools.
+fn prompt_input(blocks: &[Value]) -> (String, Vec<Attachment>) {
+    let mut text = Vec::new();
+    let mut attachments = Vec::new();
+    for block in blocks {
+        match block.get("type").and_then(Value::as_str) {
+            Some("text") => {
+                if let Some(value) = block.get("text").and_then(Value::as_str) {
+                    text.push(value.to_string());
+                }
+            }
+            Some("resource_link") => {
+                if let Some(uri) = block.get("uri").and_then(Value::as_str) {
+                    text.push(uri_path(uri).to_string());
+                }
+            }
+            Some("resource") => {
+                let resource = block.get("resource");
+                let uri = resource.and_then(|r| r.get("uri")).and_then(Value::as_str);
+                let content = resource.and_then(|r| r.get("text")).and_then(Value::as_str);
+                if let (Some(uri), Some(content)) = (uri, content) {
+                    attachments.push(Attachment {
+                        path: uri_path(uri).to_string(),
+                        content: content.to_string(),
+                        truncated: false,
+                    });
+                } else {
+                    warn!("ignoring ACP resource without text content");
+                }
+            }
+            other => warn!("ignoring unsupported ACP content block (type={other:?})"),
+        }
+    }
+    (text.join("\n"), attachments)
+}
+
+fn uri_path(uri: &str) -> &str {
+    uri.strip_prefix("file://").unwrap_or(uri)
+}
+
+/// Map an orchestrator event onto an ACP `session/update` payload.
+fn session_update(payload: &EventPayload) -> Option<Value> {
+    match payload {
+        EventPayload::AgentMessageDelta { delta, .. } => Some(json!({
+            "sessionUpdate": "agent_message_chunk",
+            "content": { "type": "text", "text": delta },
+        })),
+        EventPayload::ReasoningDelta { delta, .. } => Some(json!({
+            "sessionUpdate": "agent_thought_chunk",
+            "content": { "type": "text", "text": delta },
+        })),
+        EventPayload::ToolCallStarted {
+            tool_call_id,
+            tool_name,
+            arguments,
+            ..
+        } => {
+            let mut update = json!({
+                "sessionUpdate": "tool_call",
+                "toolCallId": tool_call_id,
+                "title": tool_name,
+                "kind": tool_kind(tool_name),
+                "status": "in_progress",
+                "rawInput": arguments,
+            });
+            if let Some(path) = arguments.get("path").and_then(Value::as_str) {
+                update["locations"] = json!([{ "path": path }]);
+            }
+            Some(update)
+        }
+        EventPayload::ToolCallFinished {
+            tool_call_id,
+            result,
+            success,
+            ..
+        } => Some(json!({
+            "sessionUpdate": "tool_call_update",
+            "toolCallId": tool_call_id,
+            "status": if *success { "completed" } else { "failed" },
+            "rawOutput": result,
+        })),
+        EventPayload::TurnStarted { .. }
+        | EventPayload::TurnCompleted { .. }
+        | EventPayload::TurnCancelled { .. }
+        | EventPayload::TurnStalled { .. }
+        | EventPayload::ReasoningSectionBreak { .. }
+        | EventPayload::ToolCallDelta { .. }
+        | EventPayload::ExecCommandBegin { .. }
+        | EventPayload::ExecCommandOutputDelta { .. }
+        | EventPayload::ExecCommandEnd { .. }
+        | EventPayload::CwdChanged { .. }
+        | EventPayload::PermissionRequested { .. }
+        | EventPayload::ApprovalResolved { .. }
+        | EventPayload::ApprovalExpired { .. }
+        | EventPayload::GuardrailTriggered { .. }
+        | EventPayload::PlanUpdate { .. }
+        | EventPayload::TokenUsage { .. }
+        | EventPayload::StreamStats { .. }
+        | EventPayload::BudgetWarning { .. }
+        | EventPayload::Error { .. } => None,
+    }
+}
+
+/// ACP tool kind for a builtin tool, used by editors to pick an icon.
+fn tool_kind(tool_name: &str) -> &'static str {
+    match tool_name {
+        "Read" | "NotebookRead" | "Outline" | "GitStatus" | "GitDiff" | "EnvInfo" => "read",
+        "Write" | "Edit" | "NotebookEdit" => "edit",
+        "Glob" | "Grep" | "WebSearch" => "search",
+        "Bash" | "GitCommit" => "execute",
+        "WebFetch" | "HttpRequest" | "Download" => "fetch",
+        _ => "other",
+    }
+}
+
+fn permission_title(request: &PermissionRequest) -> String {
+    match request {
+        PermissionRequest::Tool { name } => format!("Run tool {name}"),
+        PermissionRequest::Path { path, mode } | PermissionRequest::ExternalPath { path, mode } => {
+            format!("{mode:?} access to {path}")
+        }
+        PermissionRequest::Command { argv } => format!("Run `{}`", argv.join(" ")),
+        PermissionRequest::GitPush {
+            remote,
+            branch,
+            force,
+        } => {
+            let force = if *force { " --force" } else { "" };
+            format!("git push{force} {remote} {branch}")
+        }
+        PermissionRequest::ToolBatch { calls } => {
+            let names: Vec<&str> = calls.iter().map(|call| call.tool_name.as_str()).collect();
+            format!("Run {} tool calls: {}", calls.len(), names.join(", "))
+        }
+    }
+}
+
+/// Approval decision for a `session/request_permission` result; cancelled means deny.
+fn decision_for_outcome(result: &Value) -> ApprovalDecision {
+    let outcome = result.get("outcome");
+    let selected = outcome
+        .filter(|outcome| outcome.get("outcome").and_then(Value::as_str) == Some("selected"))
+        .and_then(|outcome| outcome.get("optionId"))
+        .and_then(Value::as_str);
+    PERMISSION_OPTIONS
+        .iter()
+        .find(|(option_id, _, _)| Some(*option_id) == selected)
+        .map_or(ApprovalDecision::Deny, |(_, _, decision)| *decision)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decision_for_outcome, prompt_input, session_update};
+    use odyssey_rs_protocol::{ApprovalDecision, EventPayload};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn maps_deltas_and_tool_calls_to_session_updates() {
+        let turn_id = Uuid::nil();
+        let update = session_update(&EventPayload::AgentMessageDelta {
+            turn_id,
+            delta: "hi".to_string(),
+        });
+        assert_eq!(
+            update,
+            Some(json!({
+                "sessionUpdate": "agent_message_chunk",
+                "content": { "type": "text", "text": "hi" },
+            }))
+        );
+
+        let tool_call_id = Uuid::new_v4();
+        let update = session_update(&EventPayload::ToolCallStarted {
+            turn_id,
+            tool_call_id,
+            tool_name: "Read".to_string(),
+            arguments: json!({ "path": "src/lib.rs" }),
+        })
+        .expect("tool call update");
+        assert_eq!(update["kind"], "read");
+        assert_eq!(update["locations"], json!([{ "path": "src/lib.rs" }]));
+
+        let update = session_update(&EventPayload::ToolCallFinished {
+            turn_id,
+            tool_call_id,
+            result: json!("boom"),
+            success: false,
+        })
+        .expect("tool call update");
+        assert_eq!(update["status"], "failed");
+        assert_eq!(
+            session_update(&EventPayload::TurnCancelled { turn_id }).is_none(),
+            true
+        );
+    }
+
+    #[test]
+    fn flattens_prompt_blocks_into_text_and_attachments() {
+        let (text, attachments) = prompt_input(&[
+            json!({ "type": "text", "text": "Explain" }),
+            json!({ "type": "resource_link", "uri": "file:///repo/README.md", "name": "README.md" }),
+            json!({
+                "type": "resource",
+                "resource": { "uri": "file:///repo/src/lib.rs", "text": "fn main() {}" },
+            }),
+            json!({ "type": "image", "data": "", "mimeType": "image/png" }),
+        ]);
+        assert_eq!(text, "Explain\n/repo/README.md");
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].path, "/repo/src/lib.rs");
+        assert_eq!(attachments[0].content, "fn main() {}");
+    }
+
+    #[test]
+    fn maps_permission_outcomes_to_decisions() {
+        let selected = json!({ "outcome": { "outcome": "selected", "optionId": "allow_always" } });
+        assert_eq!(
+            decision_for_outcome(&selected),
+            ApprovalDecision::AllowAlways
+        );
+        let cancelled = json!({ "outcome": { "outcome": "cancelled" } });
+        assert_eq!(decision_for_outcome(&cancelled), ApprovalDecision::Deny);
+    }
+}
@@ -0,0 +1,289 @@
+//! Live activity of the active session's turn, fed by protocol events.
+//!
+//! The header shows the turn metrics and the status bar shows a spinner with
+//! the current step, so a long multi-step turn visibly makes progress.
+
+use odyssey_rs_core::RunMetrics;
+use odyssey_rs_protocol::{EventPayload, ExecId, StreamStats, ToolCallId};
+use std::time::{Duration, Instant};
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+/// Longest command line shown in the status bar, in characters.
+const MAX_COMMAND_CHARS: usize = 40;
+
+/// What the running turn is doing right now.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActivityStep {
+    /// Waiting on the model, or streaming reasoning.
+    Thinking,
+    /// Streaming the response text.
+    Responding,
+    /// Running a tool call.
+    Tool(String),
+    /// Running a command, shown as its command line.
+    Command(String),
+}
+
+impl ActivityStep {
+    /// Short label for the status bar.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Thinking => "thinking".to_string(),
+            Self::Responding => "responding".to_string(),
+            Self::Tool(name) => format!("tool {name}"),
+            Self::Command(command) => format!("exec {command}"),
+        }
+    }
+}
+
+/// Live metrics of the active session's turn.
+#[derive(Debug, Default)]
+pub struct TurnActivity {
+    /// When the running turn started; `None` while idle.
+    started: Option<Instant>,
+    /// Duration of the last finished turn.
+    last_elapsed: Option<Duration>,
+    /// Completion tokens reported for finished LLM calls of the turn.
+    completion_tokens: u64,
+    /// Characters streamed since the last token usage report.
+    pending_chars: usize,
+    /// Whether the latest model output was response text rather than reasoning.
+    responding: bool,
+    /// Tool calls in flight, oldest first.
+    tools: Vec<(ToolCallId, String)>,
+    /// Commands in flight, oldest first.
+    commands: Vec<(ExecId, String)>,
+    /// Tool calls and standalone commands started this turn.
+    steps: usize,
+    /// Latest streaming throughput of the running or last turn.
+    stream: Option<StreamStats>,
+    /// Spinner frame, advanced on every tick.
+    frame: usize,
+    /// Running and queued turns across the orchestrator.
+    pub run: RunMetrics,
+}
+
+impl TurnActivity {
+    /// Update the activity from a protocol event of the active session.
+    pub fn record(&mut self, payload: &EventPayload) {
+        match payload {
+            EventPayload::TurnStarted { .. } => {
+                *self = Self {
+                    started: Some(Instant::now()),
+                    run: self.run,
+                    ..Self::default()
+                };
+            }
+            EventPayload::TurnCompleted { .. }
+            | EventPayload::TurnCancelled { .. }
+            | EventPayload::Error { .. } => {
+                if let Some(started) = self.started.take() {
+                    self.last_elapsed = Some(started.elapsed());
+                }
+                self.tools.clear();
+                self.commands.clear();
+            }
+            EventPayload::AgentMessageDelta { delta, .. } => {
+                self.pending_chars += delta.chars().count();
+                self.responding = true;
+            }
+            EventPayload::ReasoningDelta { delta, .. } => {
+                self.pending_chars += delta.chars().count();
+                self.responding = false;
+            }
+            EventPayload::TokenUsage { usage, .. } => {
+                self.completion_tokens += usage.completion_tokens;
+                self.pending_chars = 0;
+            }
+            EventPayload::StreamStats { stats, .. } => {
+                self.stream = Some(stats.clone());
+            }
+            EventPayload::ToolCallStarted {
+                tool_call_id,
+                tool_name,
+                ..
+            } => {
+                self.tools.push((*tool_call_id, tool_name.clone()));
+                self.steps += 1;
+                self.responding = false;
+            }
+            EventPayload::ToolCallFinished { tool_call_id, .. } => {
+                self.tools.retain(|(id, _)| id != tool_call_id);
+            }
+            EventPayload::ExecCommandBegin {
+                exec_id, command, ..
+            } => {
+                // Commands run by a tool call are part of that tool's step.
+                if self.tools.is_empty() {
+                    self.steps += 1;
+                }
+                let command = command.join(" ");
+                let command = match command.char_indices().nth(MAX_COMMAND_CHARS) {
+                    Some((end, _)) => format!("{}…", &command[..end]),
+                    None => command,
+                };
+                self.commands.push((*exec_id, command));
+            }
+            EventPayload::ExecCommandEnd { exec_id, .. } => {
+                self.commands.retain(|(id, _)| id != exec_id);
+            }
+            _ => {}
+        }
+    }
+
+    /// Advance the spinner by one frame.
+    pub fn tick(&mut self) {
+        self.frame = self.frame.wrapping_add(1);
+    }
+
+    /// Current spinner glyph.
+    pub fn spinner(&self) -> char {
+        SPINNER[self.frame % SPINNER.len()]
+    }
+
+    /// Whether a turn of the active session is running.
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// What the running turn is doing, or `None` while idle.
+    pub fn step(&self) -> Option<ActivityStep> {
+        self.started?;
+        Some(if let Some((_, command)) = self.commands.last() {
+            ActivityStep::Command(command.clone())
+        } else if let Some((_, name)) = self.tools.last() {
+            ActivityStep::Tool(name.clone())
+        } else if self.responding {
+            ActivityStep::Responding
+        } else {
+            ActivityStep::Thinking
+        })
+    }
+
+    /// Tool calls and standalone commands started this turn.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Elapsed time of the running turn, else of the last finished one.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.started
+            .map(|started| started.elapsed())
+            .or(self.last_elapsed)
+    }
+
+    /// Tokens streamed this turn: reported completion tokens, plus an estimate of
+    /// about four characters per token for output not yet reported.
+    pub fn streamed_tokens(&self) -> u64 {
+        self.completion_tokens + self.pending_chars.div_ceil(4) as u64
+    }
+
+    /// Streaming throughput and time to first token, e.g. `42 tok/s · ttft 0.6s`.
+    pub fn stream_label(&self) -> Option<String> {
+        self.stream.as_ref().map(|stats| {
+            format!(
+                "{:.0} tok/s · ttft {:.1}s",
+                stats.tokens_per_sec,
+                stats.time_to_first_token_ms as f64 / 1000.0
+            )
+        })
+    }
+
+    /// Name of the most recently started tool call still running.
+    pub fn active_tool(&self) -> Option<&str> {
+        self.tools.last().map(|(_, name)| name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActivityStep, TurnActivity};
+    use odyssey_rs_protocol::{EventPayload, StreamStats, TokenUsage, TurnContext};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn tracks_steps_through_a_multi_step_turn() {
+        let mut activity = TurnActivity::default();
+        let turn_id = Uuid::new_v4();
+        let (tool_call_id, exec_id) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(activity.step(), None);
+
+        activity.record(&EventPayload::TurnStarted {
+            turn_id,
+            context: TurnContext::default(),
+        });
+        assert_eq!(activity.step(), Some(ActivityStep::Thinking));
+
+        activity.record(&EventPayload::ToolCallStarted {
+            turn_id,
+            tool_call_id,
+            tool_name: "Bash".to_string(),
+            arguments: json!({}),
+        });
+        assert_eq!(
+            activity.step(),
+            Some(ActivityStep::Tool("Bash".to_string()))
+        );
+        activity.record(&EventPayload::ExecCommandBegin {
+            turn_id,
+            exec_id,
+            command: vec!["cargo".to_string(), "test".to_string()],
+            cwd: None,
+        });
+        assert_eq!(
+            activity.step(),
+            Some(ActivityStep::Command("cargo test".to_string()))
+        );
+        activity.record(&EventPayload::ExecCommandEnd {
+            turn_id,
+            exec_id,
+            exit_code: 0,
+        });
+        activity.record(&EventPayload::ToolCallFinished {
+            turn_id,
+            tool_call_id,
+            result: json!({ "exit_code": 0 }),
+            success: true,
+        });
+        assert_eq!(activity.steps(), 1);
+
+        activity.record(&EventPayload::AgentMessageDelta {
+            turn_id,
+            delta: "all tests pass".to_string(),
+        });
+        assert_eq!(activity.step(), Some(ActivityStep::Responding));
+        assert_eq!(activity.streamed_tokens(), 4);
+        activity.record(&EventPayload::TokenUsage {
+            turn_id,
+            usage: TokenUsage {
+                prompt_tokens: 100,
+                completion_tokens: 3,
+                cached_prompt_tokens: None,
+                prompt_cache: None,
+            },
+        });
+        assert_eq!(activity.streamed_tokens(), 3);
+        assert_eq!(activity.stream_label(), None);
+        activity.record(&EventPayload::StreamStats {
+            turn_id,
+            stats: StreamStats {
+                tokens: 3,
+                tokens_per_sec: 41.6,
+                time_to_first_token_ms: 640,
+            },
+        });
+        assert_eq!(
+            activity.stream_label().as_deref(),
+            Some("42 tok/s · ttft 0.6s")
+        );
+
+        activity.record(&EventPayload::TurnCompleted {
+            turn_id,
+            message: "all tests pass".to_string(),
+        });
+        assert_eq!(activity.step(), None);
+        assert_eq!(activity.elapsed().is_some(), true);
+    }
+}
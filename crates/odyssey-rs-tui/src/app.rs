@@ -1,17 +1,35 @@
 //! Application state for the Odyssey TUI.
 
-use log::{debug, info};
-use odyssey_rs_core::types::{Message, Role, SessionSummary};
+use crate::activity::TurnActivity;
+use crate::batch::describe_batch;
+use crate::clipboard::{Clipboard, code_blocks};
+use crate::commands::{CopyTarget, PaletteEntry, palette_entries};
+use crate::images::{Graphics, IMAGE_ROWS, ImageRef, find_images};
+use crate::mentions::{active_mention, complete_mention, matching_files};
+use crate::mouse::{ClickMap, TaggedLines};
+use crate::notify::Notifier;
+use crate::plan::{PlanStep, parse_plan};
+use log::{debug, info, warn};
+use odyssey_rs_core::permissions::ApprovalRequest;
+use odyssey_rs_core::types::{AgentInfo, Message, Role, SessionSummary};
+use odyssey_rs_core::{EffectivePrompt, PromptPreset};
 use odyssey_rs_protocol::{
-    ApprovalDecision, EventMsg, EventPayload, PermissionRequest, SkillSummary,
+    ApprovalDecision, EventMsg, EventPayload, GuardrailTarget, PermissionRequest, SkillSummary,
+    ToolCallId, ToolRisk,
 };
 use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
+use serde_json::Value;
 use std::cmp::min;
 use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Instant;
 use sysinfo::{Components, System};
 use uuid::Uuid;
 
+/// Lines of pretty-printed arguments or result shown for an expanded tool call.
+const MAX_TOOL_DETAIL_LINES: usize = 20;
+
 /// Chat roles displayed in the UI.
 #[derive(Debug, Clone)]
 pub enum ChatRole {
@@ -34,6 +52,25 @@ pub struct ChatEntry {
     pub content: String,
     /// Optional color for the message.
     pub color: Option<Color>,
+    /// Tool call shown by this entry, collapsed to a clickable header.
+    pub tool: Option<ToolCallView>,
+}
+
+/// Tool call details behind a transcript header that expands on click.
+#[derive(Debug, Clone)]
+pub struct ToolCallView {
+    /// Tool call id, matching the finished event.
+    pub id: ToolCallId,
+    /// Tool name.
+    pub name: String,
+    /// Arguments as sent by the model.
+    pub arguments: Value,
+    /// Result and success flag once the call finished.
+    pub result: Option<(Value, bool)>,
+    /// Whether the arguments and result are shown.
+    pub expanded: bool,
+    /// Images referenced by the result.
+    pub images: Vec<Arc<ImageRef>>,
 }
 
 /// Pending permission request displayed to the user.
@@ -45,20 +82,46 @@ pub struct PendingPermission {
     pub summary: String,
 }
 
+/// One-shot overrides for the next message, set by `/model-once`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NextTurnOverride {
+    /// Model id used instead of the active model.
+    pub model: Option<String>,
+}
+
+impl NextTurnOverride {
+    /// Badge shown next to the input while an override is pending.
+    pub fn badge(&self) -> Option<String> {
+        self.model
+            .as_ref()
+            .map(|model| format!("next: model {model}"))
+    }
+}
+
 /// Top-level application state for the TUI.
 pub struct App {
-    /// List of available agent ids.
-    pub agents: Vec<String>,
+    /// Registered agents, sorted by id.
+    pub agents: Vec<AgentInfo>,
     /// List of sessions returned by the orchestrator.
     pub sessions: Vec<SessionSummary>,
     /// List of available skills.
     pub skills: Vec<SkillSummary>,
     /// List of available model ids.
     pub models: Vec<String>,
+    /// Prompt presets offered as slash commands.
+    pub prompt_presets: Vec<PromptPreset>,
+    /// System prompt shown by the prompt viewer.
+    pub effective_prompt: Option<EffectivePrompt>,
     /// Index of the selected session in the list.
     pub selected_session: usize,
     /// Index of the selected model in the list.
     pub selected_model: usize,
+    /// Index of the selected agent in the list.
+    pub selected_agent: usize,
+    /// Pending approval requests across all sessions.
+    pub approvals: Vec<ApprovalRequest>,
+    /// Index of the selected approval in the list.
+    pub selected_approval: usize,
     /// Active session id.
     pub active_session: Option<Uuid>,
     /// Active agent id.
@@ -69,14 +132,30 @@ pub struct App {
     pub model_id: String,
     /// Model name used by the default LLM.
     pub model: String,
-    /// Current working directory.
+    /// Overrides applied to the next message only.
+    pub next_turn: NextTurnOverride,
+    /// Effective working directory of the active session.
     pub cwd: String,
+    /// Workspace root, where sessions start before changing directory.
+    pub workspace_root: String,
     /// Chat transcript entries.
     pub messages: Vec<ChatEntry>,
+    /// Cursor for the next older page of the active session; `None` once fully loaded.
+    pub history_cursor: Option<usize>,
     /// Current input buffer.
     pub input: String,
+    /// Turn whose message is being edited; Enter resends it as a retry.
+    pub editing_turn: Option<Uuid>,
     /// Whether to show the slash command palette.
     pub show_slash_commands: bool,
+    /// Index of the selected row in the slash palette.
+    pub selected_slash_command: usize,
+    /// Workspace files offered by the `@` file picker.
+    pub workspace_files: Vec<String>,
+    /// Whether to show the `@` file picker.
+    pub show_file_picker: bool,
+    /// Index of the selected row in the file picker.
+    pub selected_file: usize,
     /// Status line text.
     pub status: String,
     /// Pending permission requests.
@@ -97,9 +176,31 @@ pub struct App {
     pub cpu_usage: f32,
     /// Current GPU temperature (celsius), if available.
     pub gpu_temp: Option<f32>,
+    /// When the event stream is down, the time its next re-subscription is due.
+    pub stream_retry_at: Option<Instant>,
+    /// Live activity of the active session's turn and the orchestrator queue.
+    pub activity: TurnActivity,
+    /// Whether the terminal window has focus.
+    pub terminal_focused: bool,
+    /// Steps of the active session's latest plan update.
+    pub plan: Vec<PlanStep>,
+    /// Whether the plan and tool call panel is shown beside the chat.
+    pub show_side_panel: bool,
+    /// Whether mouse capture is off so the terminal can select text.
+    pub select_mode: bool,
+    /// Inline images drawn in the transcript.
+    pub graphics: Graphics,
+    /// Click targets of the chat transcript as last drawn.
+    pub chat_clicks: ClickMap,
+    /// Click targets of the open viewer as last drawn.
+    pub viewer_clicks: ClickMap,
     sys: System,
     components: Components,
     streamed_turns: HashSet<Uuid>,
+    /// Distance from the bottom to restore after older messages are prepended.
+    scroll_anchor: Option<u16>,
+    clipboard: Clipboard,
+    notifier: Notifier,
 }
 
 impl App {
@@ -110,17 +211,30 @@ impl App {
             sessions: Vec::new(),
             skills: Vec::new(),
             models: Vec::new(),
+            prompt_presets: Vec::new(),
+            effective_prompt: None,
             selected_session: 0,
             selected_model: 0,
+            selected_agent: 0,
+            approvals: Vec::new(),
+            selected_approval: 0,
             active_session: None,
             active_agent: None,
             user_name: "user".to_string(),
             model_id: String::new(),
             model: String::new(),
+            next_turn: NextTurnOverride::default(),
             cwd: String::new(),
+            workspace_root: String::new(),
             messages: Vec::new(),
+            history_cursor: None,
             input: String::new(),
+            editing_turn: None,
             show_slash_commands: false,
+            selected_slash_command: 0,
+            workspace_files: Vec::new(),
+            show_file_picker: false,
+            selected_file: 0,
             status: "idle".to_string(),
             pending_permissions: VecDeque::new(),
             viewer: None,
@@ -131,19 +245,46 @@ impl App {
             chat_max_scroll: 0,
             cpu_usage: 0.0,
             gpu_temp: None,
+            stream_retry_at: None,
+            activity: TurnActivity::default(),
+            terminal_focused: true,
+            plan: Vec::new(),
+            show_side_panel: false,
+            select_mode: false,
+            graphics: Graphics::default(),
+            chat_clicks: ClickMap::default(),
+            viewer_clicks: ClickMap::default(),
             sys: System::new(),
             components: Components::new_with_refreshed_list(),
             streamed_turns: HashSet::new(),
+            scroll_anchor: None,
+            clipboard: Clipboard::new(),
+            notifier: Notifier::default(),
         }
     }
 
-    /// Update the list of available agents.
-    pub fn set_agents(&mut self, agents: Vec<String>) {
+    /// Update the list of available agents, defaulting to the default agent.
+    pub fn set_agents(&mut self, agents: Vec<AgentInfo>) {
         debug!("set agents (count={})", agents.len());
         self.agents = agents;
         if self.active_agent.is_none() {
-            self.active_agent = self.agents.first().cloned();
+            self.active_agent = self
+                .agents
+                .iter()
+                .find(|agent| agent.is_default)
+                .or(self.agents.first())
+                .map(|agent| agent.id.clone());
         }
+        self.selected_agent = self
+            .active_agent
+            .as_ref()
+            .and_then(|id| self.agents.iter().position(|agent| &agent.id == id))
+            .unwrap_or(0);
+    }
+
+    /// Whether an agent with this id is registered.
+    pub fn has_agent(&self, agent_id: &str) -> bool {
+        self.agents.iter().any(|agent| agent.id == agent_id)
     }
 
     /// Update the list of sessions.
@@ -161,6 +302,109 @@ impl App {
         self.skills = skills;
     }
 
+    /// Update the prompt presets offered as slash commands.
+    pub fn set_prompt_presets(&mut self, presets: Vec<PromptPreset>) {
+        debug!("set prompt presets (count={})", presets.len());
+        self.prompt_presets = presets;
+    }
+
+    /// Palette rows matching the current input.
+    pub fn slash_palette_entries(&self) -> Vec<PaletteEntry> {
+        palette_entries(&self.input, &self.prompt_presets)
+    }
+
+    /// Show the slash palette while the input starts with `/` and reset the selection.
+    pub fn refresh_slash_palette(&mut self) {
+        self.show_slash_commands = self.input.trim_start().starts_with('/');
+        self.selected_slash_command = 0;
+    }
+
+    /// Update the slash palette and file picker after the input changes.
+    ///
+    /// The file picker takes over while an `@` mention is being typed, even
+    /// inside slash command arguments.
+    pub fn refresh_input_popups(&mut self) {
+        self.refresh_slash_palette();
+        self.show_file_picker = active_mention(&self.input).is_some();
+        self.selected_file = 0;
+        if self.show_file_picker {
+            self.show_slash_commands = false;
+        }
+    }
+
+    /// Update the workspace files offered by the file picker.
+    pub fn set_workspace_files(&mut self, files: Vec<String>) {
+        debug!("set workspace files (count={})", files.len());
+        self.workspace_files = files;
+    }
+
+    /// File picker rows matching the mention being typed.
+    pub fn file_picker_entries(&self) -> Vec<&str> {
+        active_mention(&self.input)
+            .map(|query| matching_files(query, &self.workspace_files))
+            .unwrap_or_default()
+    }
+
+    /// Move the file picker selection up, wrapping around.
+    pub fn select_prev_file(&mut self) {
+        let len = self.file_picker_entries().len();
+        if len > 0 {
+            self.selected_file = (self.selected_file + len - 1) % len;
+        }
+    }
+
+    /// Move the file picker selection down, wrapping around.
+    pub fn select_next_file(&mut self) {
+        let len = self.file_picker_entries().len();
+        if len > 0 {
+            self.selected_file = (self.selected_file + 1) % len;
+        }
+    }
+
+    /// Insert the selected file into the mention being typed.
+    pub fn complete_file_mention(&mut self) {
+        let entries = self.file_picker_entries();
+        let Some(path) = entries
+            .get(self.selected_file)
+            .or_else(|| entries.first())
+            .map(|path| path.to_string())
+        else {
+            return;
+        };
+        complete_mention(&mut self.input, &path);
+        self.show_file_picker = false;
+        self.selected_file = 0;
+    }
+
+    /// Move the palette selection up, wrapping around.
+    pub fn select_prev_slash_command(&mut self) {
+        let len = self.slash_palette_entries().len();
+        if len > 0 {
+            self.selected_slash_command = (self.selected_slash_command + len - 1) % len;
+        }
+    }
+
+    /// Move the palette selection down, wrapping around.
+    pub fn select_next_slash_command(&mut self) {
+        let len = self.slash_palette_entries().len();
+        if len > 0 {
+            self.selected_slash_command = (self.selected_slash_command + 1) % len;
+        }
+    }
+
+    /// Replace the input with the selected palette command.
+    pub fn complete_slash_command(&mut self) {
+        let entries = self.slash_palette_entries();
+        let Some(entry) = entries
+            .get(self.selected_slash_command)
+            .or_else(|| entries.first())
+        else {
+            return;
+        };
+        self.input = entry.completion();
+        self.selected_slash_command = 0;
+    }
+
     /// Update the list of available model ids.
     pub fn set_models(&mut self, models: Vec<String>) {
         debug!("set models (count={})", models.len());
@@ -178,17 +422,46 @@ impl App {
         }
     }
 
+    /// Update the list of pending approvals, keeping the selection in range.
+    pub fn set_approvals(&mut self, mut approvals: Vec<ApprovalRequest>) {
+        debug!("set approvals (count={})", approvals.len());
+        approvals.sort_by_key(|approval| (approval.session_id, approval.request_id));
+        self.approvals = approvals;
+        if self.selected_approval >= self.approvals.len() {
+            self.selected_approval = self.approvals.len().saturating_sub(1);
+        }
+    }
+
     /// Switch active session and reset scroll state.
     pub fn set_active_session(&mut self, session_id: Uuid, agent_id: String) {
         info!("active session set (session_id={})", session_id);
         self.active_session = Some(session_id);
         self.active_agent = Some(agent_id);
+        self.cwd.clone_from(&self.workspace_root);
         self.messages.clear();
+        self.history_cursor = None;
+        self.scroll_anchor = None;
         self.scroll = 0;
         self.auto_scroll = true;
         self.chat_max_scroll = 0;
         self.streamed_turns.clear();
         self.pending_permissions.clear();
+        self.editing_turn = None;
+        self.stream_retry_at = None;
+        self.plan.clear();
+    }
+
+    /// Replace the pending permission prompts with the active session's
+    /// outstanding approvals, e.g. after events were missed.
+    pub fn restore_pending_permissions(&mut self, approvals: &[ApprovalRequest]) {
+        self.pending_permissions = approvals
+            .iter()
+            .filter(|approval| Some(approval.session_id) == self.active_session)
+            .map(|approval| PendingPermission {
+                request_id: approval.request_id,
+                summary: format_permission_prompt(&approval.request, approval.risk),
+            })
+            .collect();
     }
 
     /// Update the displayed user name.
@@ -217,21 +490,56 @@ impl App {
         self.gpu_temp = find_gpu_temp(&self.components);
     }
 
-    /// Load an existing transcript into the chat view.
-    pub fn load_messages(&mut self, messages: Vec<Message>) {
-        debug!("loading messages (count={})", messages.len());
-        self.messages = messages
-            .into_iter()
-            .map(|message| ChatEntry {
-                role: chat_role_for(&message.role),
-                content: message.content,
-                color: None,
-            })
-            .collect();
+    /// Load the newest part of a transcript into the chat view, hiding superseded
+    /// responses; `older_cursor` fetches the messages before it.
+    pub fn load_messages(&mut self, messages: Vec<Message>, older_cursor: Option<usize>) {
+        debug!(
+            "loading messages (count={}, older_cursor={:?})",
+            messages.len(),
+            older_cursor
+        );
+        self.messages = chat_entries(messages);
+        self.history_cursor = older_cursor;
+        self.scroll_anchor = None;
         self.scroll = 0;
         self.auto_scroll = true;
         self.chat_max_scroll = 0;
         self.streamed_turns.clear();
+        self.activity = TurnActivity {
+            run: self.activity.run,
+            ..TurnActivity::default()
+        };
+    }
+
+    /// Prepend an older page of the transcript, keeping the visible lines in place.
+    pub fn prepend_messages(&mut self, messages: Vec<Message>, older_cursor: Option<usize>) {
+        debug!(
+            "prepending messages (count={}, older_cursor={:?})",
+            messages.len(),
+            older_cursor
+        );
+        let mut entries = chat_entries(messages);
+        entries.append(&mut self.messages);
+        self.messages = entries;
+        self.history_cursor = older_cursor;
+        self.scroll_anchor = Some(self.chat_max_scroll.saturating_sub(self.scroll));
+    }
+
+    /// Load a message into the input for editing before it is resent.
+    pub fn begin_edit(&mut self, turn_id: Uuid, content: String) {
+        debug!("editing message (turn_id={turn_id})");
+        self.input = content;
+        self.editing_turn = Some(turn_id);
+        self.refresh_input_popups();
+        self.push_status("editing last message");
+    }
+
+    /// Abandon an in-progress edit and clear the input.
+    pub fn cancel_edit(&mut self) {
+        self.editing_turn = None;
+        self.input.clear();
+        self.refresh_input_popups();
+        self.push_status("idle");
     }
 
     /// Set the status line.
@@ -239,6 +547,90 @@ impl App {
         self.status = status.into();
     }
 
+    /// Copy transcript content to the clipboard and report it in the status line.
+    pub fn copy_to_clipboard(&mut self, target: CopyTarget) {
+        let text = match target {
+            CopyTarget::LastMessage => self
+                .messages
+                .iter()
+                .rev()
+                .find(|entry| matches!(entry.role, ChatRole::Assistant))
+                .map(|entry| entry.content.clone()),
+            CopyTarget::CodeBlock(index) => self
+                .messages
+                .iter()
+                .filter(|entry| matches!(entry.role, ChatRole::Assistant))
+                .flat_map(|entry| code_blocks(&entry.content))
+                .rev()
+                .nth(index.saturating_sub(1)),
+            CopyTarget::Transcript => (!self.messages.is_empty()).then(|| self.transcript_text()),
+        };
+        let Some(text) = text else {
+            self.push_status(match target {
+                CopyTarget::LastMessage => "no agent message to copy".to_string(),
+                CopyTarget::CodeBlock(index) => format!("no code block #{index} to copy"),
+                CopyTarget::Transcript => "transcript is empty".to_string(),
+            });
+            return;
+        };
+        match self.clipboard.copy(&text) {
+            Ok(backend) => {
+                info!(
+                    "copied to clipboard (chars={}, backend={backend:?})",
+                    text.chars().count()
+                );
+                self.push_status(format!(
+                    "copied {} chars to {}",
+                    text.chars().count(),
+                    backend.label()
+                ));
+            }
+            Err(err) => self.push_status(err),
+        }
+    }
+
+    /// Replace the notifier used for background notifications.
+    pub fn set_notifier(&mut self, notifier: Notifier) {
+        self.notifier = notifier;
+    }
+
+    /// Raise a notification for an event if configured.
+    pub fn notify(&self, payload: &EventPayload) {
+        self.notifier.notify(payload, self.terminal_focused);
+    }
+
+    /// Append system clipboard text to the input box.
+    pub fn paste_from_clipboard(&mut self) {
+        match self.clipboard.paste() {
+            Ok(text) => self.paste_text(&text),
+            Err(err) => self.push_status(err),
+        }
+    }
+
+    /// Append pasted text to the input box, normalizing line endings.
+    pub fn paste_text(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        self.input.push_str(text.trim_end_matches('\n'));
+        self.refresh_input_popups();
+    }
+
+    /// Plain-text transcript with a role label before each entry.
+    fn transcript_text(&self) -> String {
+        self.messages
+            .iter()
+            .map(|entry| {
+                let label = match entry.role {
+                    ChatRole::User => "user",
+                    ChatRole::Assistant => "assistant",
+                    ChatRole::System => "system",
+                    ChatRole::Permission => "permission",
+                };
+                format!("{label}: {}", entry.content)
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
     /// Append a user-authored message to the transcript.
     /// Always enables auto-scroll so the user sees their own message.
     pub fn push_user_message(&mut self, content: String) {
@@ -246,6 +638,7 @@ impl App {
             role: ChatRole::User,
             content,
             color: None,
+            tool: None,
         });
         self.auto_scroll = true;
     }
@@ -256,6 +649,7 @@ impl App {
             role: ChatRole::System,
             content,
             color: None,
+            tool: None,
         });
         self.maybe_enable_auto_scroll();
     }
@@ -266,6 +660,7 @@ impl App {
             role: ChatRole::System,
             content,
             color: Some(color),
+            tool: None,
         });
         self.maybe_enable_auto_scroll();
     }
@@ -276,12 +671,14 @@ impl App {
             role: ChatRole::Permission,
             content,
             color: Some(permission_color()),
+            tool: None,
         });
         self.maybe_enable_auto_scroll();
     }
 
     /// Apply a protocol event to the application state.
     pub fn apply_event(&mut self, event: EventMsg) {
+        self.activity.record(&event.payload);
         match event.payload {
             EventPayload::AgentMessageDelta { turn_id, delta } => {
                 debug!("agent delta (turn_id={})", turn_id);
@@ -295,19 +692,43 @@ impl App {
                 }
                 self.status = "idle".to_string();
             }
+            EventPayload::TurnCancelled { turn_id } => {
+                info!("turn cancelled (turn_id={})", turn_id);
+                self.streamed_turns.remove(&turn_id);
+                self.push_system_message("turn cancelled".to_string());
+                self.status = "idle".to_string();
+            }
+            EventPayload::TurnStalled { turn_id, idle_secs } => {
+                warn!("turn stalled (turn_id={turn_id}, idle_secs={idle_secs})");
+                self.push_system_message(format!(
+                    "no activity for {idle_secs}s; the turn may be stuck"
+                ));
+            }
             EventPayload::ToolCallStarted {
+                tool_call_id,
                 tool_name,
                 arguments,
                 ..
             } => {
                 debug!("tool call started (tool_name={})", tool_name);
-                self.push_system_message_colored(
-                    format!("tool start: {tool_name} {arguments}"),
-                    tool_start_color(),
-                );
+                self.messages.push(ChatEntry {
+                    role: ChatRole::System,
+                    content: format!("tool start: {tool_name} {arguments}"),
+                    color: Some(tool_start_color()),
+                    tool: Some(ToolCallView {
+                        id: tool_call_id,
+                        name: tool_name,
+                        arguments,
+                        result: None,
+                        expanded: false,
+                        images: Vec::new(),
+                    }),
+                });
+                self.maybe_enable_auto_scroll();
             }
             EventPayload::ToolCallFinished {
                 tool_call_id,
+                result,
                 success,
                 ..
             } => {
@@ -321,10 +742,25 @@ impl App {
                 } else {
                     tool_error_color()
                 };
-                self.push_system_message_colored(
-                    format!("tool finished ({label}): {tool_call_id}"),
-                    color,
-                );
+                let started = self.messages.iter_mut().rev().find(|entry| {
+                    entry
+                        .tool
+                        .as_ref()
+                        .is_some_and(|tool| tool.id == tool_call_id)
+                });
+                match started {
+                    Some(entry) => {
+                        entry.color = Some(color);
+                        if let Some(tool) = &mut entry.tool {
+                            tool.images = find_images(&result).into_iter().map(Arc::new).collect();
+                            tool.result = Some((result, success));
+                        }
+                    }
+                    None => self.push_system_message_colored(
+                        format!("tool finished ({label}): {tool_call_id}"),
+                        color,
+                    ),
+                }
             }
             EventPayload::ExecCommandBegin { command, .. } => {
                 debug!("exec command started (argv_len={})", command.len());
@@ -342,16 +778,24 @@ impl App {
                     );
                 }
             }
+            EventPayload::CwdChanged { cwd, .. } => {
+                debug!("working directory changed");
+                self.cwd = cwd;
+            }
             EventPayload::PermissionRequested {
                 request_id,
                 request,
+                risk,
                 ..
             } => {
                 info!("permission requested (request_id={})", request_id);
-                let summary = format_permission_request(&request);
-                self.push_permission_message(format!(
-                    "permission requested: {summary} (y=allow once, a=allow always, n=deny)"
-                ));
+                let summary = format_permission_prompt(&request, risk);
+                // A batch is confirmed as a whole and never remembered.
+                let keys = match request {
+                    PermissionRequest::ToolBatch { .. } => "y=proceed, n=decline",
+                    _ => "y=allow once, a=allow always, n=deny",
+                };
+                self.push_permission_message(format!("permission requested: {summary} ({keys})"));
                 self.pending_permissions.push_back(PendingPermission {
                     request_id,
                     summary,
@@ -361,16 +805,57 @@ impl App {
             EventPayload::ApprovalResolved {
                 decision,
                 request_id,
+                timed_out,
                 ..
             } => {
-                info!("permission resolved (decision={:?})", decision);
+                info!("permission resolved (decision={decision:?}, timed_out={timed_out})");
+                let label = if timed_out {
+                    "permission timed out"
+                } else {
+                    "permission resolved"
+                };
                 self.push_system_message_colored(
-                    format!("permission resolved: {decision:?}"),
+                    format!("{label}: {decision:?}"),
                     approval_color(decision),
                 );
                 self.pending_permissions
                     .retain(|permission| permission.request_id != request_id);
             }
+            EventPayload::ApprovalExpired { request_id, .. } => {
+                info!("permission expired (request_id={})", request_id);
+                self.push_system_message("permission request expired".to_string());
+                self.pending_permissions
+                    .retain(|permission| permission.request_id != request_id);
+                self.approvals
+                    .retain(|approval| approval.request_id != request_id);
+            }
+            EventPayload::GuardrailTriggered {
+                rule,
+                target,
+                tool_name,
+                ..
+            } => {
+                info!("guardrail triggered (rule={})", rule);
+                let blocked = match (target, tool_name) {
+                    (GuardrailTarget::ToolArguments, Some(tool_name)) => {
+                        format!("tool call {tool_name}")
+                    }
+                    (GuardrailTarget::ToolArguments, None) => "tool call".to_string(),
+                    (GuardrailTarget::AgentOutput, _) => "agent output".to_string(),
+                };
+                self.push_system_message_colored(
+                    format!("guardrail {rule} blocked {blocked}"),
+                    tool_error_color(),
+                );
+            }
+            EventPayload::PlanUpdate { plan, .. } => {
+                self.plan = parse_plan(&plan);
+                debug!("plan updated (steps={})", self.plan.len());
+            }
+            EventPayload::BudgetWarning { limit, .. } => {
+                warn!("budget warning: {limit}");
+                self.push_system_message(format!("budget warning: {limit}"));
+            }
             EventPayload::Error { message, .. } => {
                 info!("error event received");
                 self.push_system_message_colored(format!("error: {message}"), tool_error_color());
@@ -400,6 +885,16 @@ impl App {
         self.scroll = 0;
     }
 
+    /// Restore a saved chat scroll offset before the first draw.
+    ///
+    /// The bounds are unknown until the transcript is laid out, so the max is
+    /// left open and the next `update_scroll_bounds` clamps the offset.
+    pub fn restore_scroll(&mut self, scroll: u16) {
+        self.auto_scroll = false;
+        self.scroll = scroll;
+        self.chat_max_scroll = u16::MAX;
+    }
+
     /// Enable auto-scrolling to the bottom.
     pub fn enable_auto_scroll(&mut self) {
         self.auto_scroll = true;
@@ -413,6 +908,11 @@ impl App {
     /// equality check (`>=`) avoids pulling the user back down when they have
     /// scrolled even a single line upward.
     pub fn update_scroll_bounds(&mut self, max_scroll: u16) {
+        if let Some(anchor) = self.scroll_anchor.take() {
+            self.chat_max_scroll = max_scroll;
+            self.scroll = max_scroll.saturating_sub(anchor);
+            return;
+        }
         let was_at_bottom = self.scroll >= self.chat_max_scroll;
         self.chat_max_scroll = max_scroll;
         if self.auto_scroll || was_at_bottom {
@@ -433,8 +933,8 @@ impl App {
     }
 
     /// Render chat messages into styled lines for the UI.
-    pub fn render_lines(&self) -> Vec<Line<'static>> {
-        let mut lines = Vec::new();
+    pub fn render_lines(&self) -> TaggedLines {
+        let mut lines = TaggedLines::default();
 
         if self.messages.is_empty() {
             lines.push(Line::from(Span::styled(
@@ -486,8 +986,26 @@ impl App {
                 },
             };
 
-            // Role badge line
-            lines.push(Line::from(vec![Span::styled(prefix, prefix_style)]));
+            // Role badge line; a click anywhere on a tool call toggles it.
+            let badge = Line::from(vec![Span::styled(prefix, prefix_style)]);
+            match entry.tool {
+                Some(_) => lines.push_item(badge, idx),
+                None => lines.push(badge),
+            }
+
+            if let Some(tool) = &entry.tool {
+                render_tool_call(
+                    &mut lines,
+                    tool,
+                    idx,
+                    content_style,
+                    self.graphics.enabled(),
+                );
+                if idx + 1 < self.messages.len() {
+                    lines.push(Line::from(Span::raw("")));
+                }
+                continue;
+            }
 
             // Content lines with left padding
             let mut content_lines = entry.content.lines();
@@ -526,6 +1044,7 @@ impl App {
             role: ChatRole::Assistant,
             content: delta,
             color: None,
+            tool: None,
         });
         self.maybe_enable_auto_scroll();
     }
@@ -536,10 +1055,24 @@ impl App {
             role: ChatRole::Assistant,
             content: message,
             color: None,
+            tool: None,
         });
         self.maybe_enable_auto_scroll();
     }
 
+    /// Expand or collapse the tool call shown by the transcript entry at `index`.
+    pub fn toggle_tool_call(&mut self, index: usize) {
+        if let Some(tool) = self
+            .messages
+            .get_mut(index)
+            .and_then(|entry| entry.tool.as_mut())
+        {
+            tool.expanded = !tool.expanded;
+            // Keep the clicked header in place rather than following new output.
+            self.auto_scroll = false;
+        }
+    }
+
     /// Open a viewer overlay.
     pub fn open_viewer(&mut self, kind: ViewerKind) {
         self.viewer = Some(kind);
@@ -552,6 +1085,7 @@ impl App {
         self.viewer = None;
         self.viewer_scroll = 0;
         self.viewer_max_scroll = 0;
+        self.viewer_clicks = ClickMap::default();
     }
 
     /// Scroll viewer up by a number of lines.
@@ -575,7 +1109,7 @@ impl App {
 }
 
 /// Render a human-readable permission request summary.
-fn format_permission_request(request: &PermissionRequest) -> String {
+pub fn format_permission_request(request: &PermissionRequest) -> String {
     match request {
         PermissionRequest::Tool { name } => format!("Tool usage requested: {name}"),
         PermissionRequest::Path { path, mode } => {
@@ -588,6 +1122,24 @@ fn format_permission_request(request: &PermissionRequest) -> String {
             let command_line = argv.join(" ");
             format!("Command execution requested: {command_line}")
         }
+        PermissionRequest::GitPush {
+            remote,
+            branch,
+            force,
+        } => {
+            let force = if *force { " (force)" } else { "" };
+            format!("Git push requested: {remote} {branch}{force}")
+        }
+        PermissionRequest::ToolBatch { calls } => describe_batch(calls),
+    }
+}
+
+/// Render a permission request summary prefixed with its risk badge.
+pub fn format_permission_prompt(request: &PermissionRequest, risk: Option<ToolRisk>) -> String {
+    let summary = format_permission_request(request);
+    match risk {
+        Some(risk) => format!("[{}] {summary}", risk.label()),
+        None => summary,
     }
 }
 
@@ -600,12 +1152,85 @@ fn chat_role_for(role: &Role) -> ChatRole {
     }
 }
 
+/// Convert stored messages to chat entries, hiding superseded responses.
+fn chat_entries(messages: Vec<Message>) -> Vec<ChatEntry> {
+    messages
+        .into_iter()
+        .filter(|message| !message.superseded)
+        .map(|message| ChatEntry {
+            role: chat_role_for(&message.role),
+            content: message.content,
+            color: None,
+            tool: None,
+        })
+        .collect()
+}
+
 /// Viewer overlay types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ViewerKind {
     Sessions,
     Skills,
     Models,
+    Agents,
+    Approvals,
+    Prompt,
+    Help,
+}
+
+/// Render a tool call as a clickable header, with its arguments and result
+/// below it when expanded.
+fn render_tool_call(
+    lines: &mut TaggedLines,
+    tool: &ToolCallView,
+    idx: usize,
+    style: Style,
+    draw_images: bool,
+) {
+    let marker = if tool.expanded { '▾' } else { '▸' };
+    let status = match tool.result {
+        None => "running",
+        Some((_, true)) => "ok",
+        Some((_, false)) => "error",
+    };
+    let header = if tool.expanded {
+        format!(" {marker} tool {} · {status}", tool.name)
+    } else {
+        format!(" {marker} tool {} {} · {status}", tool.name, tool.arguments)
+    };
+    lines.push_item(Line::from(Span::styled(header, style)), idx);
+    let muted = Style::default().fg(Color::Rgb(128, 128, 128));
+    for (position, image) in tool.images.iter().enumerate() {
+        lines.push_item(
+            Line::from(Span::styled(format!("   [image] {}", image.label()), muted)),
+            idx,
+        );
+        if draw_images {
+            lines.push_image((tool.id, position), Arc::clone(image), IMAGE_ROWS, idx);
+        }
+    }
+    if !tool.expanded {
+        return;
+    }
+    let mut sections = vec![("arguments", &tool.arguments)];
+    if let Some((result, _)) = &tool.result {
+        sections.push(("result", result));
+    }
+    for (label, value) in sections {
+        lines.push_item(Line::from(Span::styled(format!("   {label}:"), muted)), idx);
+        let pretty = serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+        let total = pretty.lines().count();
+        for line in pretty.lines().take(MAX_TOOL_DETAIL_LINES) {
+            lines.push_item(Line::from(Span::styled(format!("     {line}"), style)), idx);
+        }
+        if total > MAX_TOOL_DETAIL_LINES {
+            let hidden = total - MAX_TOOL_DETAIL_LINES;
+            lines.push_item(
+                Line::from(Span::styled(format!("     … {hidden} more lines"), muted)),
+                idx,
+            );
+        }
+    }
 }
 
 fn permission_color() -> Color {
@@ -0,0 +1,123 @@
+//! Preview of a tool call batch awaiting confirmation.
+//!
+//! In `confirm_batch` permission mode the mutating calls of one model response
+//! are approved together, so the prompt lists what each call will do: the
+//! command line of `Bash`, and the path with a short diff of `Write` and `Edit`.
+
+use odyssey_rs_protocol::ProposedToolCall;
+use serde_json::Value;
+
+/// Most diff lines shown per call before the rest is elided.
+const MAX_DIFF_LINES: usize = 8;
+
+/// Describe a proposed batch, one numbered entry per call.
+pub fn describe_batch(calls: &[ProposedToolCall]) -> String {
+    let plural = if calls.len() == 1 { "" } else { "s" };
+    let mut lines = vec![format!(
+        "Tool batch requested ({} call{plural}):",
+        calls.len()
+    )];
+    for (index, call) in calls.iter().enumerate() {
+        lines.push(format!("  {}. {}", index + 1, headline(call)));
+        lines.extend(diff(call).into_iter().map(|line| format!("     {line}")));
+    }
+    lines.join("\n")
+}
+
+fn str_arg<'a>(call: &'a ProposedToolCall, key: &str) -> Option<&'a str> {
+    call.arguments.get(key).and_then(Value::as_str)
+}
+
+/// One-line summary of a call.
+fn headline(call: &ProposedToolCall) -> String {
+    let name = &call.tool_name;
+    match (
+        name.as_str(),
+        str_arg(call, "command"),
+        str_arg(call, "path"),
+    ) {
+        ("Bash", Some(command), _) => format!("{name}: $ {command}"),
+        ("Write" | "Edit" | "NotebookEdit", _, Some(path)) => format!("{name} {path}"),
+        _ => format!("{name} {}", call.arguments),
+    }
+}
+
+/// Diff lines of a file edit; empty for other calls.
+fn diff(call: &ProposedToolCall) -> Vec<String> {
+    let removed = match call.tool_name.as_str() {
+        "Edit" => str_arg(call, "old_text"),
+        _ => None,
+    };
+    let added = match call.tool_name.as_str() {
+        "Edit" => str_arg(call, "new_text"),
+        "Write" => str_arg(call, "content"),
+        _ => None,
+    };
+    let mut lines: Vec<String> = removed
+        .into_iter()
+        .flat_map(str::lines)
+        .map(|line| format!("- {line}"))
+        .chain(
+            added
+                .into_iter()
+                .flat_map(str::lines)
+                .map(|line| format!("+ {line}")),
+        )
+        .collect();
+    if lines.len() > MAX_DIFF_LINES {
+        let hidden = lines.len() - MAX_DIFF_LINES;
+        lines.truncate(MAX_DIFF_LINES);
+        lines.push(format!("… {hidden} more lines"));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::describe_batch;
+    use odyssey_rs_protocol::ProposedToolCall;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn describes_commands_paths_and_diffs() {
+        let call = |tool_name: &str, arguments| ProposedToolCall {
+            tool_name: tool_name.to_string(),
+            arguments,
+        };
+        let calls = vec![
+            call("Bash", json!({ "command": "cargo fmt" })),
+            call(
+                "Edit",
+                json!({ "path": "src/lib.rs", "old_text": "let a = 1;", "new_text": "let a = 2;" }),
+            ),
+            call(
+                "Write",
+                json!({ "path": "notes.txt", "content": (1..=10).map(|n| n.to_string()).collect::<Vec<_>>().join("\n") }),
+            ),
+            call("GitCommit", json!({ "message": "wip" })),
+        ];
+        assert_eq!(
+            describe_batch(&calls),
+            [
+                "Tool batch requested (4 calls):",
+                "  1. Bash: $ cargo fmt",
+                "  2. Edit src/lib.rs",
+                "     - let a = 1;",
+                "     + let a = 2;",
+                "  3. Write notes.txt",
+                "     + 1",
+                "     + 2",
+                "     + 3",
+                "     + 4",
+                "     + 5",
+                "     + 6",
+                "     + 7",
+                "     + 8",
+                "     … 2 more lines",
+                "  4. GitCommit {\"message\":\"wip\"}",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Helpers for assembling an orchestrator before launching the TUI.
+//!
+//! The `odyssey-rs-tui` binary uses these to load config, pick a sandbox
+//! provider, and register LLM providers; embedders can reuse them so their
+//! setup matches the CLI.
+
+use anyhow::Context;
+#[cfg(feature = "local")]
+use autoagents_llamacpp::{LlamaCppProvider, ModelSource};
+use autoagents_llm::LLMProvider;
+use autoagents_llm::backends::openai::OpenAI;
+use autoagents_llm::builder::LLMBuilder;
+use log::info;
+use odyssey_rs_config::OdysseyConfig;
+use odyssey_rs_core::provider_api_key;
+#[cfg(feature = "local")]
+use odyssey_rs_core::{LLMEntry, ModelManager, Orchestrator};
+#[cfg(target_os = "linux")]
+use odyssey_rs_sandbox::BubblewrapProvider;
+#[cfg(not(target_os = "linux"))]
+use odyssey_rs_sandbox::LocalSandboxProvider;
+use odyssey_rs_sandbox::SandboxProvider;
+use std::path::Path;
+#[cfg(feature = "local")]
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// LLM id the TUI selects by default.
+pub const DEFAULT_LLM_ID: &str = "default_LLM";
+/// LLM id for the llama.cpp provider when another provider owns the default id.
+#[cfg(feature = "local")]
+pub const LOCAL_LLM_ID: &str = "local-llama-cpp";
+/// HuggingFace repo of the local model used when no source is given.
+#[cfg(feature = "local")]
+const DEFAULT_HF_REPO: &str = "Qwen/Qwen2.5-Coder-7B-Instruct-GGUF";
+/// GGUF file of the default local model.
+#[cfg(feature = "local")]
+const DEFAULT_HF_FILENAME: &str = "qwen2.5-coder-7b-instruct-q8_0.gguf";
+
+/// Load config from `path`, or the layered config for `cwd` when no path is given.
+pub fn load_config(path: Option<&Path>, cwd: &Path) -> anyhow::Result<OdysseyConfig> {
+    if let Some(path) = path {
+        info!("loading config from path: {}", path.display());
+        return OdysseyConfig::load_from_path(path).context("failed to load config");
+    }
+    info!("loading layered config from cwd: {}", cwd.display());
+    let layered = OdysseyConfig::load_layered(cwd).context("failed to load layered config")?;
+    Ok(layered.config)
+}
+
+/// Sandbox provider for the current platform.
+pub fn default_sandbox_provider() -> anyhow::Result<Option<Arc<dyn SandboxProvider>>> {
+    #[cfg(target_os = "linux")]
+    {
+        Ok(Some(Arc::new(
+            BubblewrapProvider::new().context("failed to init bubblewrap provider")?,
+        )))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        // Not yet supported.
+        Ok(Some(Arc::new(LocalSandboxProvider::default())))
+    }
+}
+
+/// Environment variable read when `providers.openai.api_key_ref` is not set.
+pub const ENV_OPENAI_API_KEY: &str = "OPENAI_API_KEY";
+
+/// OpenAI API key from `providers.openai.api_key_ref`, falling back to `OPENAI_API_KEY`.
+pub fn openai_api_key(config: &OdysseyConfig) -> anyhow::Result<Option<String>> {
+    if let Some(api_key) = provider_api_key(config, "openai")
+        .context("failed to resolve providers.openai.api_key_ref")?
+    {
+        return Ok(Some(api_key));
+    }
+    Ok(std::env::var(ENV_OPENAI_API_KEY).ok())
+}
+
+/// Build an OpenAI provider for `model_name`.
+pub fn build_openai_llm(api_key: String, model_name: &str) -> anyhow::Result<Arc<dyn LLMProvider>> {
+    info!("building default LLM provider (model={model_name})");
+    let llm: Arc<dyn LLMProvider> = LLMBuilder::<OpenAI>::new()
+        .api_key(api_key)
+        .model(model_name.to_string())
+        .build()
+        .context("failed to build OpenAI LLM provider")?;
+    Ok(llm)
+}
+
+/// Options for the local llama.cpp provider.
+#[cfg(feature = "local")]
+#[derive(Debug, Clone)]
+pub struct LocalLlmOptions {
+    /// Local GGUF model path (mutually exclusive with `hf_repo`).
+    pub gguf: Option<PathBuf>,
+    /// HuggingFace repo id for a GGUF model.
+    pub hf_repo: Option<String>,
+    /// Optional HuggingFace GGUF filename.
+    pub hf_filename: Option<String>,
+    /// Optional HuggingFace mmproj filename.
+    pub hf_mmproj: Option<String>,
+    /// Optional chat template name or inline template.
+    pub chat_template: Option<String>,
+    /// Context size override.
+    pub n_ctx: Option<u32>,
+    /// Thread count override.
+    pub n_threads: Option<i32>,
+    /// Max tokens to generate.
+    pub max_tokens: u32,
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// GPU layers to offload.
+    pub n_gpu_layers: Option<u32>,
+    /// Main GPU index.
+    pub main_gpu: Option<i32>,
+}
+
+#[cfg(feature = "local")]
+impl Default for LocalLlmOptions {
+    fn default() -> Self {
+        Self {
+            gguf: None,
+            hf_repo: None,
+            hf_filename: None,
+            hf_mmproj: None,
+            chat_template: None,
+            n_ctx: None,
+            n_threads: None,
+            max_tokens: 2048,
+            temperature: None,
+            n_gpu_layers: None,
+            main_gpu: None,
+        }
+    }
+}
+
+/// Build the llama.cpp provider and register it under `llm_id`.
+///
+/// HuggingFace models already in the orchestrator's model cache (see
+/// `/models download`) load from there instead of being fetched again.
+/// Returns a display label for the model source, e.g. `gguf:model.gguf`.
+#[cfg(feature = "local")]
+pub async fn register_local_llm(
+    orchestrator: &Orchestrator,
+    options: &LocalLlmOptions,
+    llm_id: &str,
+) -> anyhow::Result<String> {
+    let source = resolve_local_model_source(options, orchestrator.model_manager())?;
+    let label = local_label_from_source(&source);
+    info!("building llama.cpp provider (source={label})");
+    let mut builder = LlamaCppProvider::builder().model_source(source);
+    if let Some(template) = options.chat_template.as_ref() {
+        builder = builder.chat_template(template.clone());
+    }
+    if let Some(n_ctx) = options.n_ctx {
+        builder = builder.n_ctx(n_ctx);
+    }
+    if let Some(n_threads) = options.n_threads {
+        builder = builder.n_threads(n_threads);
+    }
+
+    builder = builder.max_tokens(options.max_tokens);
+
+    if let Some(temperature) = options.temperature {
+        builder = builder.temperature(temperature);
+    }
+    if let Some(n_gpu_layers) = options.n_gpu_layers {
+        builder = builder.n_gpu_layers(n_gpu_layers);
+    }
+    if let Some(main_gpu) = options.main_gpu {
+        builder = builder.main_gpu(main_gpu);
+    }
+    let provider = builder
+        .build()
+        .await
+        .context("failed to build llama.cpp provider")?;
+    let provider: Arc<dyn LLMProvider> = Arc::new(provider);
+    orchestrator.register_local_llm_provider(LLMEntry {
+        id: llm_id.to_string(),
+        provider,
+    })?;
+    info!("registered llama.cpp provider (llm_id={llm_id})");
+    Ok(label)
+}
+
+#[cfg(feature = "local")]
+fn resolve_local_model_source(
+    options: &LocalLlmOptions,
+    models: &ModelManager,
+) -> anyhow::Result<ModelSource> {
+    if options.gguf.is_some() && options.hf_repo.is_some() {
+        anyhow::bail!("use only one of --local-gguf or --local-hf-repo");
+    }
+    if let Some(path) = options.gguf.as_ref() {
+        return Ok(ModelSource::Gguf {
+            model_path: path.display().to_string(),
+        });
+    }
+    let (repo_id, filename, mmproj_filename) = match options.hf_repo.as_ref() {
+        Some(repo_id) => (
+            repo_id.clone(),
+            options.hf_filename.clone(),
+            options.hf_mmproj.clone(),
+        ),
+        None => (
+            DEFAULT_HF_REPO.to_string(),
+            Some(DEFAULT_HF_FILENAME.to_string()),
+            None,
+        ),
+    };
+    if mmproj_filename.is_none()
+        && let Some(path) = filename
+            .as_deref()
+            .and_then(|filename| models.cached_path(&repo_id, filename))
+    {
+        info!("using cached model {}", path.display());
+        return Ok(ModelSource::Gguf {
+            model_path: path.display().to_string(),
+        });
+    }
+    Ok(ModelSource::HuggingFace {
+        repo_id,
+        filename,
+        mmproj_filename,
+    })
+}
+
+#[cfg(feature = "local")]
+fn local_label_from_source(source: &ModelSource) -> String {
+    match source {
+        ModelSource::Gguf { model_path } => {
+            let name = Path::new(model_path)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or(model_path);
+            format!("gguf:{name}")
+        }
+        ModelSource::HuggingFace {
+            repo_id,
+            filename,
+            mmproj_filename: _,
+        } => filename
+            .as_ref()
+            .map(|file| format!("hf:{repo_id}/{file}"))
+            .unwrap_or_else(|| format!("hf:{repo_id}")),
+    }
+}
@@ -1,14 +1,19 @@
 //! Local orchestrator client for the Odyssey TUI.
 
 use crate::event::AppEvent;
-use crate::event_bus::EventBus;
-use anyhow::Result;
+use crate::event_bus::{EventBus, EventFilter};
+use anyhow::{Result, bail};
 use log::{debug, info};
-use odyssey_rs_core::Orchestrator;
-use odyssey_rs_core::types::{Session, SessionSummary};
-use odyssey_rs_protocol::{ApprovalDecision, SkillSummary};
+use odyssey_rs_core::permissions::ApprovalRequest;
+use odyssey_rs_core::types::{AgentInfo, Role, Session, SessionPage, SessionSummary};
+use odyssey_rs_core::{
+    CachedModel, EffectivePrompt, ModelDownloadProgress, ModelDownloadRequest, Orchestrator,
+    PromptPreset, RetriedTurn, RunMetrics,
+};
+use odyssey_rs_memory::ScoredMemoryRecord;
+use odyssey_rs_protocol::{ApprovalDecision, Attachment, SkillSummary};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::broadcast;
 use uuid::Uuid;
 
 /// Local client that wraps an embedded orchestrator.
@@ -16,30 +21,35 @@ use uuid::Uuid;
 pub struct OrchestratorClient {
     orchestrator: Arc<Orchestrator>,
     events: EventBus,
+    /// User that sessions are created for and listed by.
+    user_id: String,
 }
 
 impl OrchestratorClient {
-    /// Create a new local client.
-    pub fn new(orchestrator: Arc<Orchestrator>, events: EventBus) -> Self {
+    /// Create a new local client acting as `user_id`.
+    pub fn new(orchestrator: Arc<Orchestrator>, events: EventBus, user_id: String) -> Self {
         Self {
             orchestrator,
             events,
+            user_id,
         }
     }
 
-    /// List available agent ids.
-    pub async fn list_agents(&self) -> Result<Vec<String>> {
-        Ok(self.orchestrator.list_agents())
+    /// List registered agents with their descriptions and tool policies.
+    pub async fn list_agents(&self) -> Result<Vec<AgentInfo>> {
+        Ok(self.orchestrator.list_agent_info())
     }
 
-    /// List available sessions.
+    /// List the user's sessions and unattributed ones.
     pub async fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
-        Ok(self.orchestrator.list_sessions()?)
+        Ok(self.orchestrator.list_sessions_for_user(&self.user_id)?)
     }
 
-    /// Create a session, optionally for a specific agent.
+    /// Create a session owned by the user, optionally for a specific agent.
     pub async fn create_session(&self, agent_id: Option<String>) -> Result<Uuid> {
-        Ok(self.orchestrator.create_session(agent_id)?)
+        Ok(self
+            .orchestrator
+            .create_session_for_user(agent_id, self.user_id.clone())?)
     }
 
     /// Fetch a session by id.
@@ -47,12 +57,35 @@ impl OrchestratorClient {
         Ok(self.orchestrator.resume_session(session_id)?)
     }
 
+    /// Fetch up to `limit` messages of a session ending before `cursor`, or the newest ones.
+    pub async fn get_session_page(
+        &self,
+        session_id: Uuid,
+        cursor: Option<usize>,
+        limit: usize,
+    ) -> Result<SessionPage> {
+        Ok(self
+            .orchestrator
+            .get_session_page(session_id, cursor, limit)?)
+    }
+
+    /// Running and queued turns across the orchestrator.
+    pub fn run_metrics(&self) -> RunMetrics {
+        self.orchestrator.run_metrics()
+    }
+
+    /// Effective working directory of a session, if it has run a turn.
+    pub async fn session_cwd(&self, session_id: Uuid) -> Option<PathBuf> {
+        self.orchestrator.session_cwd(session_id)
+    }
+
     /// Send a prompt to a session using the streaming path so that
     /// incremental deltas are emitted to the event bus in real time.
     pub async fn send_message(
         &self,
         session_id: Uuid,
         prompt: String,
+        attachments: Vec<Attachment>,
         agent_id: Option<String>,
         llm_id: String,
     ) -> Result<odyssey_rs_core::RunResult> {
@@ -70,18 +103,69 @@ impl OrchestratorClient {
         };
 
         debug!(
-            "streaming session turn (session_id={}, agent_id={}, prompt_len={})",
+            "streaming session turn (session_id={}, agent_id={}, prompt_len={}, attachments={})",
             session_id,
             agent_id,
-            prompt.len()
+            prompt.len(),
+            attachments.len()
         );
         let run_stream = self
             .orchestrator
-            .run_stream_in_session(session_id, &agent_id, &llm_id, prompt)
+            .run_stream_with_attachments(session_id, &agent_id, &llm_id, prompt, &attachments)
             .await?;
         Ok(run_stream.finish().await?)
     }
 
+    /// Turn id and text of the last user message in a session, if it can be edited.
+    pub async fn last_user_turn(&self, session_id: Uuid) -> Result<Option<(Uuid, String)>> {
+        let session = self.orchestrator.resume_session(session_id)?;
+        Ok(session
+            .messages
+            .into_iter()
+            .rev()
+            .find(|message| message.role == Role::User)
+            .and_then(|message| message.turn_id.map(|turn_id| (turn_id, message.content))))
+    }
+
+    /// Re-run a turn with edited input, keeping the discarded branch as a fork.
+    pub async fn retry_turn(
+        &self,
+        session_id: Uuid,
+        turn_id: Uuid,
+        prompt: String,
+        llm_id: String,
+    ) -> Result<RetriedTurn> {
+        if prompt.trim().is_empty() {
+            anyhow::bail!("prompt cannot be empty");
+        }
+        debug!(
+            "retrying session turn (session_id={}, turn_id={}, prompt_len={})",
+            session_id,
+            turn_id,
+            prompt.len()
+        );
+        Ok(self
+            .orchestrator
+            .retry_turn(session_id, turn_id, Some(&llm_id), prompt)
+            .await?)
+    }
+
+    /// Re-run the last user turn of a session with the given model.
+    pub async fn regenerate_turn(
+        &self,
+        session_id: Uuid,
+        llm_id: String,
+    ) -> Result<odyssey_rs_core::RunResult> {
+        debug!(
+            "regenerating session turn (session_id={}, llm_id={})",
+            session_id, llm_id
+        );
+        Ok(self
+            .orchestrator
+            .regenerate_turn(session_id, Some(&llm_id))
+            .await?)
+    }
+
     /// Resolve a permission request.
     pub async fn resolve_permission(
         &self,
@@ -91,40 +175,123 @@ impl OrchestratorClient {
         Ok(self.orchestrator.resolve_approval(request_id, decision))
     }
 
+    /// List pending approval requests across all sessions.
+    pub async fn list_pending_approvals(&self) -> Result<Vec<ApprovalRequest>> {
+        Ok(self.orchestrator.list_pending_approvals())
+    }
+
     /// List skill summaries.
     pub async fn list_skills(&self) -> Result<Vec<SkillSummary>> {
         Ok(self.orchestrator.list_skill_summaries())
     }
 
+    /// List configured prompt presets.
+    pub async fn list_prompt_presets(&self) -> Result<Vec<PromptPreset>> {
+        Ok(self.orchestrator.list_prompt_presets())
+    }
+
+    /// Expand a prompt preset with the text typed after its command.
+    pub async fn expand_prompt_preset(&self, name: &str, input: &str) -> Result<String> {
+        Ok(self.orchestrator.expand_prompt_preset(name, input)?)
+    }
+
+    /// Fetch the system prompt of an agent, or of the default agent.
+    pub async fn effective_prompt(&self, agent_id: Option<&str>) -> Result<EffectivePrompt> {
+        Ok(self.orchestrator.effective_prompt(agent_id)?)
+    }
+
+    /// Score the memories recalled for a session's next turn against `query`.
+    pub async fn explain_memory_recall(
+        &self,
+        session_id: Uuid,
+        query: Option<&str>,
+    ) -> Result<Vec<ScoredMemoryRecord>> {
+        Ok(self
+            .orchestrator
+            .explain_memory_recall(session_id, query)
+            .await?)
+    }
+
     /// List registered model ids.
     pub async fn list_models(&self) -> Result<Vec<String>> {
         Ok(self.orchestrator.list_llm_ids())
     }
 
-    /// Stream events for a session.
+    /// List GGUF models in the local model cache.
+    pub async fn list_cached_models(&self) -> Result<Vec<CachedModel>> {
+        Ok(self.orchestrator.list_cached_models()?)
+    }
+
+    /// Download a GGUF model into the local cache.
+    pub async fn download_model(
+        &self,
+        request: ModelDownloadRequest,
+        on_progress: impl FnMut(ModelDownloadProgress) + Send,
+    ) -> Result<CachedModel> {
+        Ok(self
+            .orchestrator
+            .download_model(&request, on_progress)
+            .await?)
+    }
+
+    /// Re-hash a cached model against `sha256` or its recorded hash.
+    pub async fn verify_model(
+        &self,
+        repo_id: &str,
+        filename: &str,
+        sha256: Option<&str>,
+    ) -> Result<CachedModel> {
+        Ok(self
+            .orchestrator
+            .verify_model(repo_id, filename, sha256)
+            .await?)
+    }
+
+    /// Delete a cached model; returns false when it was not cached.
+    pub async fn delete_model(&self, repo_id: &str, filename: &str) -> Result<bool> {
+        Ok(self.orchestrator.delete_model(repo_id, filename)?)
+    }
+
+    /// Stream events for a session until the app stops listening.
+    ///
+    /// Sends `StreamConnected` once subscribed. Fails when the bus closes or when
+    /// a gap in the session's event sequence shows that events were missed.
     pub async fn stream_events(
         &self,
         session_id: Uuid,
         sender: tokio::sync::mpsc::Sender<AppEvent>,
     ) -> Result<()> {
-        let mut receiver = self.events.subscribe();
+        let mut receiver = self
+            .events
+            .subscribe_filtered(EventFilter::default().session(session_id));
         info!(
             "subscribing to local event stream (session_id={})",
             session_id
         );
-        loop {
-            match receiver.recv().await {
-                Ok(event) => {
-                    if event.session_id != session_id {
-                        continue;
-                    }
-                    let _ = sender.send(AppEvent::Server(event)).await;
-                }
-                Err(broadcast::error::RecvError::Lagged(_)) => continue,
-                Err(broadcast::error::RecvError::Closed) => break,
+        if sender
+            .send(AppEvent::StreamConnected(session_id))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
+        let mut last_seq = None;
+        while let Some(event) = receiver.recv().await {
+            let seq = event.seq;
+            if sender.send(AppEvent::Server(event)).await.is_err() {
+                return Ok(());
+            }
+            // Unsequenced events carry seq 0 and cannot show a gap.
+            if seq == 0 {
+                continue;
+            }
+            if let Some(last) = last_seq.replace(seq)
+                && seq > last + 1
+            {
+                bail!("missed {} events", seq - last - 1);
             }
         }
         info!("event stream closed (session_id={})", session_id);
-        Ok(())
+        bail!("event stream closed")
     }
 }
@@ -0,0 +1,145 @@
+//! Clipboard access for copying transcript content out of the alternate screen.
+//!
+//! Local sessions use the system clipboard. Over SSH, or when no system
+//! clipboard is available, text is sent to the terminal as an OSC 52 escape
+//! sequence so the user's local terminal can place it on their clipboard.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use log::debug;
+use std::io::{self, Write};
+
+const ENV_SSH_CONNECTION: &str = "SSH_CONNECTION";
+const ENV_SSH_TTY: &str = "SSH_TTY";
+const ENV_TMUX: &str = "TMUX";
+
+/// How copied text reached the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardBackend {
+    /// Native system clipboard.
+    System,
+    /// OSC 52 terminal escape sequence.
+    Osc52,
+}
+
+impl ClipboardBackend {
+    /// Short label shown in the status line.
+    pub fn label(self) -> &'static str {
+        match self {
+            ClipboardBackend::System => "clipboard",
+            ClipboardBackend::Osc52 => "terminal clipboard",
+        }
+    }
+}
+
+/// Clipboard handle kept for the lifetime of the app.
+///
+/// On X11 and Wayland the owning process serves clipboard contents, so the
+/// system handle must outlive each copy.
+pub struct Clipboard {
+    system: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    /// Open the system clipboard unless running over SSH.
+    pub fn new() -> Self {
+        if is_remote_session() {
+            debug!("remote session detected; using OSC 52 for copies");
+            return Self { system: None };
+        }
+        let system = arboard::Clipboard::new()
+            .inspect_err(|err| debug!("system clipboard unavailable: {err}"))
+            .ok();
+        Self { system }
+    }
+
+    /// Copy `text`, falling back to OSC 52 when the system clipboard fails.
+    pub fn copy(&mut self, text: &str) -> Result<ClipboardBackend, String> {
+        if let Some(system) = self.system.as_mut() {
+            match system.set_text(text) {
+                Ok(()) => return Ok(ClipboardBackend::System),
+                Err(err) => debug!("system clipboard copy failed: {err}"),
+            }
+        }
+        let sequence = osc52_sequence(text, in_tmux());
+        let mut stdout = io::stdout();
+        stdout
+            .write_all(sequence.as_bytes())
+            .and_then(|()| stdout.flush())
+            .map_err(|err| format!("clipboard write failed: {err}"))?;
+        Ok(ClipboardBackend::Osc52)
+    }
+
+    /// Read text from the system clipboard.
+    pub fn paste(&mut self) -> Result<String, String> {
+        let Some(system) = self.system.as_mut() else {
+            return Err("no system clipboard; paste with your terminal instead".to_string());
+        };
+        system
+            .get_text()
+            .map_err(|err| format!("clipboard read failed: {err}"))
+    }
+}
+
+/// Return true when running over SSH, where native desktop services are remote.
+pub(crate) fn is_remote_session() -> bool {
+    std::env::var_os(ENV_SSH_CONNECTION).is_some() || std::env::var_os(ENV_SSH_TTY).is_some()
+}
+
+/// Return true when running inside tmux.
+pub(crate) fn in_tmux() -> bool {
+    std::env::var_os(ENV_TMUX).is_some()
+}
+
+/// Wrap an escape sequence for tmux passthrough when needed.
+pub(crate) fn tmux_passthrough(sequence: String, tmux: bool) -> String {
+    if tmux {
+        format!("\x1bPtmux;\x1b{sequence}\x1b\\")
+    } else {
+        sequence
+    }
+}
+
+/// OSC 52 sequence that sets the clipboard, wrapped for tmux passthrough when needed.
+fn osc52_sequence(text: &str, tmux: bool) -> String {
+    tmux_passthrough(format!("\x1b]52;c;{}\x07", STANDARD.encode(text)), tmux)
+}
+
+/// Fenced code blocks in `text`, in order, without their fences.
+pub fn code_blocks(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            match current.take() {
+                Some(lines) => blocks.push(lines.join("\n")),
+                None => current = Some(Vec::new()),
+            }
+        } else if let Some(lines) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{code_blocks, osc52_sequence};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn extracts_fenced_code_blocks() {
+        let text = "Run this:\n```bash\ncargo test\ncargo fmt\n```\nthen\n```\nok\n```\n```rust\nunterminated";
+        assert_eq!(code_blocks(text), vec!["cargo test\ncargo fmt", "ok"]);
+        assert_eq!(code_blocks("no code"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn encodes_osc52_sequences() {
+        assert_eq!(osc52_sequence("hi", false), "\x1b]52;c;aGk=\x07");
+        assert_eq!(
+            osc52_sequence("hi", true),
+            "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\"
+        );
+    }
+}
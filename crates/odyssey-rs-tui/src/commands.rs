@@ -0,0 +1,858 @@
+//! Slash command and keyboard shortcut registry for the TUI.
+
+use odyssey_rs_core::PromptPreset;
+use uuid::Uuid;
+
+/// Built-in slash command listed in the palette.
+pub struct CommandSpec {
+    /// Command name without the leading slash.
+    pub name: &'static str,
+    /// Argument placeholder shown after the name, if any.
+    pub args: &'static str,
+    /// Short description shown in the palette.
+    pub description: &'static str,
+}
+
+/// Built-in commands in palette order.
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "new",
+        args: "",
+        description: "Create a new session",
+    },
+    CommandSpec {
+        name: "sessions",
+        args: "",
+        description: "List all sessions",
+    },
+    CommandSpec {
+        name: "skills",
+        args: "",
+        description: "List available skills",
+    },
+    CommandSpec {
+        name: "models",
+        args: "[cached|download|verify|delete]",
+        description: "List models, or manage downloaded GGUF models",
+    },
+    CommandSpec {
+        name: "agents",
+        args: "",
+        description: "List available agents",
+    },
+    CommandSpec {
+        name: "approvals",
+        args: "",
+        description: "Review pending approvals",
+    },
+    CommandSpec {
+        name: "prompt",
+        args: "",
+        description: "Show the agent's system prompt by section",
+    },
+    CommandSpec {
+        name: "memory",
+        args: "why [query]",
+        description: "Explain which memories are recalled and why",
+    },
+    CommandSpec {
+        name: "model",
+        args: "<id>",
+        description: "Select model by id",
+    },
+    CommandSpec {
+        name: "model-once",
+        args: "<id|off>",
+        description: "Use a model for the next message only",
+    },
+    CommandSpec {
+        name: "agent",
+        args: "<id>",
+        description: "Switch agent; starts a new session if it differs",
+    },
+    CommandSpec {
+        name: "regenerate",
+        args: "[model]",
+        description: "Re-run the last message, optionally with another model",
+    },
+    CommandSpec {
+        name: "join",
+        args: "<id>",
+        description: "Join a session by ID",
+    },
+    CommandSpec {
+        name: "copy",
+        args: "[code [n]|all]",
+        description: "Copy the last reply, a code block, or the transcript",
+    },
+    CommandSpec {
+        name: "help",
+        args: "",
+        description: "Show keyboard shortcuts and commands",
+    },
+];
+
+/// Where a keyboard shortcut applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutContext {
+    /// Chat view with the input box.
+    Chat,
+    /// Any viewer panel (sessions, models, agents, approvals, prompt, help).
+    Viewer,
+    /// A permission prompt shown in the chat view.
+    Permission,
+}
+
+impl ShortcutContext {
+    /// Section title in the help overlay.
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Chat => "Chat",
+            Self::Viewer => "Viewers",
+            Self::Permission => "Permission prompts",
+        }
+    }
+}
+
+/// Keyboard shortcut listed in the help overlay.
+pub struct ShortcutSpec {
+    /// Where the shortcut applies.
+    pub context: ShortcutContext,
+    /// Keys as shown to the user, e.g. `Ctrl+N`.
+    pub keys: &'static str,
+    /// What the shortcut does.
+    pub description: &'static str,
+}
+
+/// Keyboard shortcuts in help order.
+pub const SHORTCUTS: &[ShortcutSpec] = &[
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Enter",
+        description: "Send the message or run the slash command",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Tab",
+        description: "Complete the selected command or file mention",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Ctrl+N",
+        description: "Create a new session",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Ctrl+R",
+        description: "Refresh the session list",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Ctrl+S",
+        description: "Open the highlighted session",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Ctrl+E",
+        description: "Edit and resend the last message",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Ctrl+P",
+        description: "Show or hide the plan and tool call panel",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Ctrl+Y",
+        description: "Copy the last agent message",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Ctrl+B",
+        description: "Copy the latest code block",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Ctrl+T",
+        description: "Copy the whole transcript",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Ctrl+V",
+        description: "Paste from the system clipboard",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Up/Down",
+        description: "Scroll the chat, or move through the palette",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "PgUp/PgDn",
+        description: "Scroll the chat by a page",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Home/End",
+        description: "Jump to the start or end of the chat",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "?",
+        description: "Show this help (with an empty input)",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Click",
+        description: "Expand or collapse a tool call",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "F2",
+        description: "Toggle select mode to select text with the mouse",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Esc",
+        description: "Close the palette, cancel an edit, or quit",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Chat,
+        keys: "Ctrl+C",
+        description: "Quit",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Viewer,
+        keys: "Up/Down",
+        description: "Move the selection",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Viewer,
+        keys: "PgUp/PgDn",
+        description: "Scroll by a page",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Viewer,
+        keys: "Enter",
+        description: "Open the selected session, model, or agent",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Viewer,
+        keys: "Click",
+        description: "Select an item; click it again to open it",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Viewer,
+        keys: "y/a/n",
+        description: "Allow once, allow always, or deny the selected approval",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Viewer,
+        keys: "Esc",
+        description: "Close the viewer",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Permission,
+        keys: "y",
+        description: "Allow once",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Permission,
+        keys: "a",
+        description: "Allow always",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Permission,
+        keys: "n",
+        description: "Deny",
+    },
+    ShortcutSpec {
+        context: ShortcutContext::Permission,
+        keys: "Esc",
+        description: "Dismiss the prompt without answering",
+    },
+];
+
+/// Titled group of rows in the help overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpSection {
+    /// Section title.
+    pub title: String,
+    /// Key or usage column, and its description.
+    pub rows: Vec<(String, String)>,
+}
+
+/// Help overlay contents, built from the shortcut and slash command registries
+/// so new entries show up without touching the overlay.
+pub fn help_sections(presets: &[PromptPreset]) -> Vec<HelpSection> {
+    let contexts = [
+        ShortcutContext::Chat,
+        ShortcutContext::Viewer,
+        ShortcutContext::Permission,
+    ];
+    let mut sections = contexts
+        .into_iter()
+        .map(|context| HelpSection {
+            title: context.title().to_string(),
+            rows: SHORTCUTS
+                .iter()
+                .filter(|shortcut| shortcut.context == context)
+                .map(|shortcut| (shortcut.keys.to_string(), shortcut.description.to_string()))
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    let (builtins, presets): (Vec<_>, Vec<_>) = palette_entries("/", presets)
+        .into_iter()
+        .partition(|entry| COMMANDS.iter().any(|spec| spec.name == entry.name));
+    for (title, entries) in [("Slash commands", builtins), ("Prompt presets", presets)] {
+        if !entries.is_empty() {
+            sections.push(HelpSection {
+                title: title.to_string(),
+                rows: entries
+                    .into_iter()
+                    .map(|entry| (entry.usage, entry.description))
+                    .collect(),
+            });
+        }
+    }
+    sections
+}
+
+/// Usage of `/models` and its cache actions.
+const MODELS_USAGE: &str = "usage: /models [cached|download <repo> <file> [sha256]|verify <repo> <file> [sha256]|delete <repo> <file>]";
+/// Maximum edit distance for "did you mean" suggestions.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Parsed slash command from the input box.
+pub enum SlashCommand {
+    New,
+    Join(Uuid),
+    Sessions,
+    Skills,
+    Models,
+    /// Action on the local GGUF model cache.
+    ModelCache(ModelCacheAction),
+    Model(String),
+    /// Model for the next message only; `None` clears it.
+    ModelOnce(Option<String>),
+    Agents,
+    Agent(String),
+    Regenerate(Option<String>),
+    Approvals,
+    Prompt,
+    /// Explain memory recall against a query, or the last message when `None`.
+    MemoryWhy(Option<String>),
+    Copy(CopyTarget),
+    Help,
+    Preset {
+        name: String,
+        args: String,
+    },
+}
+
+/// Action on the local GGUF model cache from `/models`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelCacheAction {
+    /// List cached models.
+    List,
+    /// Download a file from a HuggingFace repo, optionally checking its SHA-256.
+    Download {
+        repo_id: String,
+        filename: String,
+        sha256: Option<String>,
+    },
+    /// Re-hash a cached model against `sha256` or its recorded hash.
+    Verify {
+        repo_id: String,
+        filename: String,
+        sha256: Option<String>,
+    },
+    /// Remove a cached model.
+    Delete { repo_id: String, filename: String },
+}
+
+/// Transcript content copied by `/copy` and the copy shortcuts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyTarget {
+    /// Latest agent message.
+    LastMessage,
+    /// Code block counted back from the latest one, starting at 1.
+    CodeBlock(usize),
+    /// Whole transcript.
+    Transcript,
+}
+
+/// Palette row for a built-in command or prompt preset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteEntry {
+    /// Command name without the leading slash.
+    pub name: String,
+    /// Name with argument placeholders, e.g. `/model <id>`.
+    pub usage: String,
+    /// Short description.
+    pub description: String,
+    /// Whether the command expects arguments.
+    pub takes_args: bool,
+}
+
+impl PaletteEntry {
+    fn builtin(spec: &CommandSpec) -> Self {
+        let usage = if spec.args.is_empty() {
+            format!("/{}", spec.name)
+        } else {
+            format!("/{} {}", spec.name, spec.args)
+        };
+        Self {
+            name: spec.name.to_string(),
+            usage,
+            description: spec.description.to_string(),
+            takes_args: !spec.args.is_empty(),
+        }
+    }
+
+    fn preset(preset: &PromptPreset) -> Self {
+        Self {
+            name: preset.name.clone(),
+            usage: preset.usage(),
+            description: preset.description.clone().unwrap_or_default(),
+            takes_args: !preset.args.is_empty() || preset.template.contains("{{args}}"),
+        }
+    }
+
+    /// Input text after completing this entry.
+    pub fn completion(&self) -> String {
+        if self.takes_args {
+            format!("/{} ", self.name)
+        } else {
+            format!("/{}", self.name)
+        }
+    }
+}
+
+/// Palette rows matching the typed input, best matches first.
+///
+/// Names are matched fuzzily while the command is being typed; prefix matches
+/// rank before subsequence matches. Once arguments follow, only the exact
+/// command remains as a usage hint.
+pub fn palette_entries(input: &str, presets: &[PromptPreset]) -> Vec<PaletteEntry> {
+    let typed = input.trim_start().trim_start_matches('/');
+    let entries = COMMANDS
+        .iter()
+        .map(PaletteEntry::builtin)
+        .chain(presets.iter().map(PaletteEntry::preset));
+    if let Some((command, _)) = typed.split_once(char::is_whitespace) {
+        return entries.filter(|entry| entry.name == command).collect();
+    }
+    let query = typed.to_lowercase();
+    let mut scored = entries
+        .filter_map(|entry| fuzzy_score(&query, &entry.name).map(|score| (score, entry)))
+        .collect::<Vec<_>>();
+    // Stable sort keeps registry order among equal scores.
+    scored.sort_by_key(|(score, _)| *score);
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Parse a slash command from the input line.
+///
+/// Built-in commands take precedence over prompt presets with the same name.
+/// Unknown commands suggest the nearest known name.
+pub fn parse_slash_command(
+    input: &str,
+    presets: &[PromptPreset],
+) -> Result<Option<SlashCommand>, String> {
+    let trimmed = input.trim();
+    if !trimmed.starts_with('/') {
+        return Ok(None);
+    }
+    let mut parts = trimmed.trim_start_matches('/').split_whitespace();
+    let Some(command) = parts.next() else {
+        return Ok(None);
+    };
+    match command.to_lowercase().as_str() {
+        "new" => Ok(Some(SlashCommand::New)),
+        "skills" => Ok(Some(SlashCommand::Skills)),
+        "sessions" => Ok(Some(SlashCommand::Sessions)),
+        "models" => match parts.next() {
+            None | Some("list") => Ok(Some(SlashCommand::Models)),
+            Some(action) => parse_model_cache_action(action, parts)
+                .map(|action| Some(SlashCommand::ModelCache(action))),
+        },
+        "approvals" => Ok(Some(SlashCommand::Approvals)),
+        "prompt" => Ok(Some(SlashCommand::Prompt)),
+        "memory" => match parts.next() {
+            Some("why") => {
+                let query = parts.collect::<Vec<_>>().join(" ");
+                Ok(Some(SlashCommand::MemoryWhy(
+                    (!query.is_empty()).then_some(query),
+                )))
+            }
+            _ => Err("usage: /memory why [query]".to_string()),
+        },
+        "model" => match parts.next() {
+            None => Ok(Some(SlashCommand::Models)),
+            Some("list") => Ok(Some(SlashCommand::Models)),
+            Some(id) => Ok(Some(SlashCommand::Model(id.to_string()))),
+        },
+        "model-once" => match parts.next() {
+            None => Err("usage: /model-once <id|off>".to_string()),
+            Some("off") => Ok(Some(SlashCommand::ModelOnce(None))),
+            Some(id) => Ok(Some(SlashCommand::ModelOnce(Some(id.to_string())))),
+        },
+        "help" => Ok(Some(SlashCommand::Help)),
+        "agents" => Ok(Some(SlashCommand::Agents)),
+        "agent" => match parts.next() {
+            None | Some("list") => Ok(Some(SlashCommand::Agents)),
+            Some(id) => Ok(Some(SlashCommand::Agent(id.to_string()))),
+        },
+        "regenerate" => Ok(Some(SlashCommand::Regenerate(
+            parts.next().map(|id| id.to_string()),
+        ))),
+        "copy" => match (parts.next(), parts.next()) {
+            (None, _) => Ok(Some(SlashCommand::Copy(CopyTarget::LastMessage))),
+            (Some("all"), None) => Ok(Some(SlashCommand::Copy(CopyTarget::Transcript))),
+            (Some("code"), None) => Ok(Some(SlashCommand::Copy(CopyTarget::CodeBlock(1)))),
+            (Some("code"), Some(index)) => match index.parse::<usize>() {
+                Ok(index) if index > 0 => {
+                    Ok(Some(SlashCommand::Copy(CopyTarget::CodeBlock(index))))
+                }
+                _ => Err("usage: /copy code [n] (n counts back from the latest block)".to_string()),
+            },
+            _ => Err("usage: /copy [code [n]|all]".to_string()),
+        },
+        "join" => {
+            let Some(id) = parts.next() else {
+                return Err("usage: /join <session_id>".to_string());
+            };
+            let session_id = Uuid::parse_str(id).map_err(|_| "invalid session id".to_string())?;
+            Ok(Some(SlashCommand::Join(session_id)))
+        }
+        "session" => match parts.next() {
+            Some("new") => Ok(Some(SlashCommand::New)),
+            Some("join") => {
+                let Some(id) = parts.next() else {
+                    return Err("usage: /session join <session_id>".to_string());
+                };
+                let session_id =
+                    Uuid::parse_str(id).map_err(|_| "invalid session id".to_string())?;
+                Ok(Some(SlashCommand::Join(session_id)))
+            }
+            Some("list") => Ok(Some(SlashCommand::Sessions)),
+            Some("skills") => Ok(Some(SlashCommand::Skills)),
+            Some(id) => {
+                let session_id =
+                    Uuid::parse_str(id).map_err(|_| "invalid session id".to_string())?;
+                Ok(Some(SlashCommand::Join(session_id)))
+            }
+            None => Err("usage: /session <id>|new|join <id>".to_string()),
+        },
+        _ if presets.iter().any(|preset| preset.name == command) => {
+            let args = trimmed
+                .trim_start_matches('/')
+                .split_once(char::is_whitespace)
+                .map(|(_, args)| args.trim().to_string())
+                .unwrap_or_default();
+            Ok(Some(SlashCommand::Preset {
+                name: command.to_string(),
+                args,
+            }))
+        }
+        _ => match suggest_command(command, presets) {
+            Some(name) => Err(format!(
+                "unknown command: /{command} (did you mean /{name}?)"
+            )),
+            None => Err(format!("unknown command: /{command}")),
+        },
+    }
+}
+
+/// Parse the arguments of `/models cached|download|verify|delete`.
+fn parse_model_cache_action<'a>(
+    action: &str,
+    mut parts: impl Iterator<Item = &'a str>,
+) -> Result<ModelCacheAction, String> {
+    let action = action.to_lowercase();
+    if action == "cached" {
+        return match parts.next() {
+            None => Ok(ModelCacheAction::List),
+            Some(_) => Err(MODELS_USAGE.to_string()),
+        };
+    }
+    let (Some(repo_id), Some(filename)) = (parts.next(), parts.next()) else {
+        return Err(MODELS_USAGE.to_string());
+    };
+    let (repo_id, filename) = (repo_id.to_string(), filename.to_string());
+    let sha256 = parts.next().map(str::to_string);
+    if parts.next().is_some() {
+        return Err(MODELS_USAGE.to_string());
+    }
+    match (action.as_str(), sha256) {
+        ("download", sha256) => Ok(ModelCacheAction::Download {
+            repo_id,
+            filename,
+            sha256,
+        }),
+        ("verify", sha256) => Ok(ModelCacheAction::Verify {
+            repo_id,
+            filename,
+            sha256,
+        }),
+        ("delete", None) => Ok(ModelCacheAction::Delete { repo_id, filename }),
+        _ => Err(MODELS_USAGE.to_string()),
+    }
+}
+
+/// Nearest built-in or preset name within a small edit distance.
+fn suggest_command<'a>(command: &str, presets: &'a [PromptPreset]) -> Option<&'a str> {
+    let command = command.to_lowercase();
+    COMMANDS
+        .iter()
+        .map(|spec| spec.name)
+        .chain(presets.iter().map(|preset| preset.name.as_str()))
+        .map(|name| (edit_distance(&command, name), name))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        // Ties go to the name closest in length, e.g. `/modle` -> `/model`.
+        .min_by_key(|(distance, name)| (*distance, name.len().abs_diff(command.len())))
+        .map(|(_, name)| name)
+}
+
+/// Rank `candidate` against `query`: 0 for a prefix match, otherwise 1 plus
+/// the characters skipped in a subsequence match. `None` when it does not match.
+pub(crate) fn fuzzy_score(query: &str, candidate: &str) -> Option<usize> {
+    let candidate = candidate.to_lowercase();
+    if candidate.starts_with(query) {
+        return Some(0);
+    }
+    let mut skipped = 0;
+    let mut chars = candidate.chars();
+    for wanted in query.chars() {
+        loop {
+            let ch = chars.next()?;
+            if ch == wanted {
+                break;
+            }
+            skipped += 1;
+        }
+    }
+    Some(1 + skipped)
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(left: &str, right: &str) -> usize {
+    let right = right.chars().collect::<Vec<_>>();
+    let mut previous = (0..=right.len()).collect::<Vec<_>>();
+    for (i, lch) in left.chars().enumerate() {
+        let mut current = vec![i + 1; right.len() + 1];
+        for (j, rch) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(lch != *rch);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[right.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        COMMANDS, CopyTarget, ModelCacheAction, SHORTCUTS, SlashCommand, help_sections,
+        palette_entries, parse_slash_command,
+    };
+    use odyssey_rs_core::PromptPreset;
+    use pretty_assertions::assert_eq;
+
+    fn names(input: &str, presets: &[PromptPreset]) -> Vec<String> {
+        palette_entries(input, presets)
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect()
+    }
+
+    fn review_preset() -> PromptPreset {
+        PromptPreset {
+            name: "review".to_string(),
+            description: Some("Review a file".to_string()),
+            args: vec!["file".to_string()],
+            template: "Review {{file}}.".to_string(),
+        }
+    }
+
+    #[test]
+    fn palette_filters_by_prefix_then_subsequence() {
+        assert_eq!(
+            names("/mo", &[]),
+            vec!["models", "model", "model-once", "memory"]
+        );
+        assert_eq!(names("/ms", &[]), vec!["models"]);
+        assert_eq!(
+            names("/rv", &[review_preset()]),
+            vec!["review", "approvals"]
+        );
+        assert_eq!(names("/", &[]).len(), 15);
+        assert_eq!(names("/model gpt", &[]), vec!["model"]);
+    }
+
+    #[test]
+    fn completion_adds_a_space_for_commands_with_arguments() {
+        let entries = palette_entries("/jo", &[review_preset()]);
+        assert_eq!(entries[0].completion(), "/join ");
+        let entries = palette_entries("/ne", &[]);
+        assert_eq!(entries[0].completion(), "/new");
+    }
+
+    #[test]
+    fn unknown_commands_suggest_the_nearest_name() {
+        let presets = [review_preset()];
+        let err = parse_slash_command("/modle gpt", &presets)
+            .err()
+            .expect("unknown");
+        assert_eq!(err, "unknown command: /modle (did you mean /model?)");
+        let err = parse_slash_command("/reveiw x", &presets)
+            .err()
+            .expect("unknown");
+        assert_eq!(err, "unknown command: /reveiw (did you mean /review?)");
+        let err = parse_slash_command("/deploy", &presets)
+            .err()
+            .expect("unknown");
+        assert_eq!(err, "unknown command: /deploy");
+        assert!(matches!(
+            parse_slash_command("/review src/lib.rs", &presets),
+            Ok(Some(SlashCommand::Preset { name, args })) if name == "review" && args == "src/lib.rs"
+        ));
+    }
+
+    #[test]
+    fn parses_regenerate_with_optional_model() {
+        let regenerate = |input| match parse_slash_command(input, &[]) {
+            Ok(Some(SlashCommand::Regenerate(model))) => Some(model),
+            _ => None,
+        };
+        assert_eq!(regenerate("/regenerate"), Some(None));
+        assert_eq!(
+            regenerate("/regenerate gpt-4o"),
+            Some(Some("gpt-4o".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_next_turn_overrides() {
+        assert!(matches!(
+            parse_slash_command("/model-once gpt-4o", &[]),
+            Ok(Some(SlashCommand::ModelOnce(Some(id)))) if id == "gpt-4o"
+        ));
+        assert!(matches!(
+            parse_slash_command("/model-once off", &[]),
+            Ok(Some(SlashCommand::ModelOnce(None)))
+        ));
+        assert!(parse_slash_command("/model-once", &[]).is_err());
+    }
+
+    #[test]
+    fn help_lists_every_shortcut_and_command() {
+        let sections = help_sections(&[review_preset()]);
+        let titles = sections
+            .iter()
+            .map(|section| section.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec![
+                "Chat",
+                "Viewers",
+                "Permission prompts",
+                "Slash commands",
+                "Prompt presets"
+            ]
+        );
+        let rows = sections
+            .iter()
+            .map(|section| section.rows.len())
+            .sum::<usize>();
+        assert_eq!(rows, SHORTCUTS.len() + COMMANDS.len() + 1);
+        assert_eq!(
+            sections[4].rows,
+            vec![("/review <file>".to_string(), "Review a file".to_string())]
+        );
+        assert_eq!(help_sections(&[]).len(), 4);
+    }
+
+    #[test]
+    fn parses_memory_why() {
+        let why = |input| match parse_slash_command(input, &[]) {
+            Ok(Some(SlashCommand::MemoryWhy(query))) => Ok(query),
+            Ok(_) => Err("not a memory command".to_string()),
+            Err(err) => Err(err),
+        };
+        assert_eq!(why("/memory why"), Ok(None));
+        assert_eq!(
+            why("/memory why docker deploy"),
+            Ok(Some("docker deploy".to_string()))
+        );
+        assert!(why("/memory").is_err());
+    }
+
+    #[test]
+    fn parses_agent_commands() {
+        let agent = |input| match parse_slash_command(input, &[]) {
+            Ok(Some(SlashCommand::Agent(id))) => Some(id),
+            Ok(Some(SlashCommand::Agents)) => None,
+            _ => panic!("not an agent command: {input}"),
+        };
+        assert_eq!(agent("/agent reviewer"), Some("reviewer".to_string()));
+        assert_eq!(agent("/agent"), None);
+        assert_eq!(agent("/agents"), None);
+    }
+
+    #[test]
+    fn parses_copy_targets() {
+        let copy = |input| match parse_slash_command(input, &[]) {
+            Ok(Some(SlashCommand::Copy(target))) => Ok(target),
+            Ok(_) => Err("not a copy command".to_string()),
+            Err(err) => Err(err),
+        };
+        assert_eq!(copy("/copy"), Ok(CopyTarget::LastMessage));
+        assert_eq!(copy("/copy all"), Ok(CopyTarget::Transcript));
+        assert_eq!(copy("/copy code"), Ok(CopyTarget::CodeBlock(1)));
+        assert_eq!(copy("/copy code 3"), Ok(CopyTarget::CodeBlock(3)));
+        assert!(copy("/copy code 0").is_err());
+        assert!(copy("/copy everything").is_err());
+    }
+
+    #[test]
+    fn parses_model_cache_actions() {
+        let cache = |input| match parse_slash_command(input, &[]) {
+            Ok(Some(SlashCommand::ModelCache(action))) => Ok(Some(action)),
+            Ok(Some(SlashCommand::Models)) => Ok(None),
+            Ok(_) => Err("not a models command".to_string()),
+            Err(err) => Err(err),
+        };
+        assert_eq!(cache("/models"), Ok(None));
+        assert_eq!(cache("/models list"), Ok(None));
+        assert_eq!(cache("/models cached"), Ok(Some(ModelCacheAction::List)));
+        assert_eq!(
+            cache("/models download acme/tiny-GGUF tiny.gguf"),
+            Ok(Some(ModelCacheAction::Download {
+                repo_id: "acme/tiny-GGUF".to_string(),
+                filename: "tiny.gguf".to_string(),
+                sha256: None,
+            }))
+        );
+        assert_eq!(
+            cache("/models verify acme/tiny-GGUF tiny.gguf abc"),
+            Ok(Some(ModelCacheAction::Verify {
+                repo_id: "acme/tiny-GGUF".to_string(),
+                filename: "tiny.gguf".to_string(),
+                sha256: Some("abc".to_string()),
+            }))
+        );
+        assert_eq!(
+            cache("/models delete acme/tiny-GGUF tiny.gguf"),
+            Ok(Some(ModelCacheAction::Delete {
+                repo_id: "acme/tiny-GGUF".to_string(),
+                filename: "tiny.gguf".to_string(),
+            }))
+        );
+        assert!(cache("/models download acme/tiny-GGUF").is_err());
+        assert!(cache("/models delete acme/tiny-GGUF tiny.gguf abc").is_err());
+        assert!(cache("/models fetch acme/tiny-GGUF tiny.gguf").is_err());
+    }
+}
@@ -1,7 +1,9 @@
 //! TUI event types for input and orchestration messages.
 
 use crossterm::event::KeyEvent;
-use odyssey_rs_protocol::EventMsg;
+use odyssey_rs_core::ModelDownloadProgress;
+use odyssey_rs_protocol::{EventMsg, SessionId};
+use std::time::Duration;
 
 /// Application event emitted by input handlers or the server stream.
 #[derive(Debug)]
@@ -12,10 +14,26 @@ pub enum AppEvent {
     Tick,
     /// Protocol event emitted by the embedded orchestrator.
     Server(EventMsg),
-    /// Error from the streaming connection.
-    StreamError(String),
+    /// The event stream of a session is subscribed and delivering events.
+    StreamConnected(SessionId),
+    /// The event stream of a session failed; it re-subscribes after `retry_in`.
+    StreamError {
+        session_id: SessionId,
+        message: String,
+        retry_in: Duration,
+    },
     /// Error from an action request.
     ActionError(String),
+    /// Informational result of an action request.
+    ActionNotice(String),
+    /// Progress of a `/models download`.
+    ModelDownload(ModelDownloadProgress),
     /// Scroll event in the chat view.
     Scroll(i16),
+    /// Left mouse click at a terminal cell.
+    Click { column: u16, row: u16 },
+    /// Text pasted into the terminal (bracketed paste).
+    Paste(String),
+    /// Terminal focus changed; true when focus was gained.
+    Focus(bool),
 }
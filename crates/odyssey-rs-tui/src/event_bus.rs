@@ -1,8 +1,9 @@
 //! Local event bus for embedding the orchestrator in the TUI.
 
-use log::debug;
+use log::{debug, warn};
 use odyssey_rs_core::EventSink;
-use odyssey_rs_protocol::EventMsg;
+use odyssey_rs_protocol::{EventKind, EventMsg, SessionId};
+use std::collections::HashSet;
 use tokio::sync::broadcast;
 
 /// Broadcast-backed event bus for the embedded orchestrator.
@@ -23,6 +24,14 @@ impl EventBus {
     pub fn subscribe(&self) -> broadcast::Receiver<EventMsg> {
         self.sender.subscribe()
     }
+
+    /// Subscribe to the events matching `filter`.
+    pub fn subscribe_filtered(&self, filter: EventFilter) -> FilteredReceiver {
+        FilteredReceiver {
+            receiver: self.sender.subscribe(),
+            filter,
+        }
+    }
 }
 
 impl EventSink for EventBus {
@@ -31,3 +40,119 @@ impl EventSink for EventBus {
         let _ = self.sender.send(event);
     }
 }
+
+/// Selects events by session and kind; the default filter matches everything.
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    session_id: Option<SessionId>,
+    kinds: Option<HashSet<EventKind>>,
+}
+
+impl EventFilter {
+    /// Only match events of `session_id`.
+    pub fn session(mut self, session_id: SessionId) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    /// Only match events of the given kinds.
+    pub fn kinds(mut self, kinds: impl IntoIterator<Item = EventKind>) -> Self {
+        self.kinds = Some(kinds.into_iter().collect());
+        self
+    }
+
+    /// Whether `event` passes the filter.
+    pub fn matches(&self, event: &EventMsg) -> bool {
+        if let Some(session_id) = self.session_id
+            && event.session_id != session_id
+        {
+            return false;
+        }
+        self.kinds
+            .as_ref()
+            .is_none_or(|kinds| kinds.contains(&event.kind()))
+    }
+}
+
+/// Subscription that only yields events matching an [`EventFilter`].
+#[derive(Debug)]
+pub struct FilteredReceiver {
+    receiver: broadcast::Receiver<EventMsg>,
+    filter: EventFilter,
+}
+
+impl FilteredReceiver {
+    /// Next matching event, or `None` once the bus is closed.
+    ///
+    /// Events dropped because this subscriber fell behind are skipped.
+    pub async fn recv(&mut self) -> Option<EventMsg> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) if self.filter.matches(&event) => return Some(event),
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("event subscriber lagged (skipped={skipped})");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventBus, EventFilter};
+    use chrono::Utc;
+    use odyssey_rs_core::EventSink;
+    use odyssey_rs_protocol::{EventKind, EventMsg, EventPayload};
+    use pretty_assertions::assert_eq;
+    use uuid::Uuid;
+
+    fn event(session_id: Uuid, payload: EventPayload) -> EventMsg {
+        EventMsg {
+            id: Uuid::new_v4(),
+            session_id,
+            seq: 0,
+            user_id: None,
+            created_at: Utc::now(),
+            payload,
+        }
+    }
+
+    #[tokio::test]
+    async fn filtered_subscriptions_match_session_and_kind() {
+        let bus = EventBus::new(16);
+        let session_id = Uuid::new_v4();
+        let turn_id = Uuid::new_v4();
+        let mut receiver = bus.subscribe_filtered(
+            EventFilter::default()
+                .session(session_id)
+                .kinds([EventKind::TurnLifecycle, EventKind::Error]),
+        );
+
+        bus.emit(event(
+            Uuid::new_v4(),
+            EventPayload::TurnCancelled { turn_id },
+        ));
+        bus.emit(event(
+            session_id,
+            EventPayload::AgentMessageDelta {
+                turn_id,
+                delta: "hi".to_string(),
+            },
+        ));
+        bus.emit(event(
+            session_id,
+            EventPayload::TurnCompleted {
+                turn_id,
+                message: "done".to_string(),
+            },
+        ));
+        drop(bus);
+
+        let received = receiver.recv().await.expect("matching event");
+        assert_eq!(received.kind(), EventKind::TurnLifecycle);
+        assert_eq!(received.session_id, session_id);
+        assert_eq!(receiver.recv().await.is_none(), true);
+    }
+}
@@ -0,0 +1,488 @@
+//! Inline images in the transcript via terminal graphics protocols.
+//!
+//! Tool results carry images either inline (`{ "type": "image", "mime_type", "data" }`)
+//! or as a binary file description with an `image/*` MIME type and a path. The
+//! transcript always shows a placeholder line with the image's path; when the
+//! terminal speaks the kitty, iTerm2, or sixel protocol it also reserves rows
+//! below it, and [`Graphics::present`] draws the image there after each frame.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
+use crossterm::queue;
+use log::{debug, warn};
+use odyssey_rs_protocol::ToolCallId;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+/// Environment variable that forces a protocol: `kitty`, `iterm2`, `sixel`, or `none`.
+const ENV_GRAPHICS: &str = "ODYSSEY_TUI_GRAPHICS";
+/// Transcript rows reserved for each image.
+pub const IMAGE_ROWS: u16 = 12;
+/// Widest image, in cells.
+pub const IMAGE_MAX_COLS: u16 = 60;
+/// Images shown per tool result; further ones only get a placeholder line.
+const MAX_IMAGES_PER_RESULT: usize = 4;
+/// Largest image file read from disk.
+const MAX_IMAGE_FILE_BYTES: u64 = 16 * 1024 * 1024;
+/// Base64 bytes per kitty transmission chunk.
+const KITTY_CHUNK: usize = 4096;
+/// Cell size assumed when the terminal does not report its pixel size.
+const DEFAULT_CELL_PX: (u16, u16) = (8, 16);
+
+/// Terminal graphics protocol used to draw images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// Kitty graphics protocol (kitty, Ghostty); PNG only.
+    Kitty,
+    /// iTerm2 inline images (iTerm2, WezTerm).
+    Iterm2,
+    /// Sixel, encoded by `img2sixel` from libsixel.
+    Sixel,
+}
+
+impl GraphicsProtocol {
+    /// Protocol of the current terminal, if it supports one.
+    pub fn detect() -> Option<Self> {
+        Self::detect_from(|name| std::env::var(name).ok())
+    }
+
+    fn detect_from(env: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        if let Some(forced) = env(ENV_GRAPHICS) {
+            return match forced.to_ascii_lowercase().as_str() {
+                "kitty" => Some(Self::Kitty),
+                "iterm2" => Some(Self::Iterm2),
+                "sixel" => Some(Self::Sixel),
+                _ => None,
+            };
+        }
+        // Multiplexers do not position passthrough graphics reliably.
+        if env("TMUX").is_some() || env("STY").is_some() {
+            return None;
+        }
+        let term = env("TERM").unwrap_or_default();
+        let program = env("TERM_PROGRAM").unwrap_or_default();
+        if env("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty" || program == "ghostty" {
+            Some(Self::Kitty)
+        } else if program == "iTerm.app" || program == "WezTerm" {
+            Some(Self::Iterm2)
+        } else if term.contains("sixel") || term == "foot" || term.starts_with("mlterm") {
+            Some(Self::Sixel)
+        } else {
+            None
+        }
+    }
+}
+
+/// Image referenced by a tool result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageRef {
+    /// MIME type, e.g. `image/png`.
+    pub mime_type: String,
+    /// Workspace-relative path, when the image is a file.
+    pub path: Option<String>,
+    /// Base64 image data, when returned inline.
+    pub data: Option<String>,
+}
+
+impl ImageRef {
+    /// Placeholder text naming the image.
+    pub fn label(&self) -> String {
+        match &self.path {
+            Some(path) => format!("{} {path}", self.mime_type),
+            None => format!("{} (inline)", self.mime_type),
+        }
+    }
+
+    /// Raw image bytes, decoding inline data or reading the file under `root`.
+    fn bytes(&self, root: &Path) -> Option<Vec<u8>> {
+        if let Some(data) = &self.data {
+            return STANDARD.decode(data).ok();
+        }
+        let path = root.join(self.path.as_ref()?);
+        let size = std::fs::metadata(&path).ok()?.len();
+        if size > MAX_IMAGE_FILE_BYTES {
+            debug!(
+                "image too large to draw (path={}, size={size})",
+                path.display()
+            );
+            return None;
+        }
+        std::fs::read(&path)
+            .inspect_err(|err| warn!("failed to read image (path={}): {err}", path.display()))
+            .ok()
+    }
+}
+
+/// Images referenced anywhere in a tool result, in document order.
+pub fn find_images(result: &Value) -> Vec<ImageRef> {
+    let mut images = Vec::new();
+    collect_images(result, &mut images);
+    images.truncate(MAX_IMAGES_PER_RESULT);
+    images
+}
+
+fn collect_images(value: &Value, images: &mut Vec<ImageRef>) {
+    match value {
+        Value::Object(map) => {
+            let mime = map.get("mime_type").and_then(Value::as_str);
+            let inline = |value: &Value| {
+                (value.get("type").and_then(Value::as_str) == Some("image"))
+                    .then(|| value.get("data").and_then(Value::as_str))
+                    .flatten()
+                    .map(str::to_string)
+            };
+            if let Some(data) = inline(value) {
+                images.push(ImageRef {
+                    mime_type: mime.unwrap_or("image/png").to_string(),
+                    path: None,
+                    data: Some(data),
+                });
+            } else if let Some(mime) = mime.filter(|mime| mime.starts_with("image/"))
+                && let Some(path) = map.get("path").and_then(Value::as_str)
+            {
+                images.push(ImageRef {
+                    mime_type: mime.to_string(),
+                    path: Some(path.to_string()),
+                    data: map.get("image").and_then(inline),
+                });
+            } else {
+                map.values().for_each(|child| collect_images(child, images));
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_images(item, images)),
+        _ => {}
+    }
+}
+
+/// Identifies an image: the tool call and its position in the result.
+pub type ImageKey = (ToolCallId, usize);
+
+/// An image to draw at a screen cell, sized in cells.
+#[derive(Debug, Clone)]
+pub struct Placement {
+    pub key: ImageKey,
+    pub image: Arc<ImageRef>,
+    pub x: u16,
+    pub y: u16,
+    pub cols: u16,
+    pub rows: u16,
+}
+
+impl PartialEq for Placement {
+    fn eq(&self, other: &Self) -> bool {
+        (self.key, self.x, self.y, self.cols, self.rows)
+            == (other.key, other.x, other.y, other.cols, other.rows)
+    }
+}
+
+/// Draws queued image placements over the rows the transcript reserved for them.
+#[derive(Debug, Default)]
+pub struct Graphics {
+    protocol: Option<GraphicsProtocol>,
+    /// Placements queued while drawing the current frame.
+    queued: Vec<Placement>,
+    /// Placements on screen.
+    shown: Vec<Placement>,
+    /// Escape sequences per image and size; `None` when the image cannot be drawn.
+    encoded: HashMap<(ImageKey, u16, u16), Option<String>>,
+    /// Kitty image ids of images already transmitted.
+    kitty_ids: HashMap<ImageKey, u32>,
+}
+
+impl Graphics {
+    /// Draw images with `protocol`, or only show placeholders when `None`.
+    pub fn new(protocol: Option<GraphicsProtocol>) -> Self {
+        Self {
+            protocol,
+            ..Self::default()
+        }
+    }
+
+    /// Whether images are drawn, so the transcript reserves rows for them.
+    pub fn enabled(&self) -> bool {
+        self.protocol.is_some()
+    }
+
+    /// Start a frame; placements queued by the previous one are dropped.
+    pub fn begin_frame(&mut self) {
+        self.queued.clear();
+    }
+
+    /// Draw `placement` once the frame is on screen.
+    pub fn queue(&mut self, placement: Placement) {
+        if self.enabled() {
+            self.queued.push(placement);
+        }
+    }
+
+    /// Whether the frame moved, added, or removed images.
+    pub fn changed(&self) -> bool {
+        self.queued != self.shown
+    }
+
+    /// Whether the frame must be fully repainted before presenting, to erase
+    /// images that are drawn into cells rather than on a layer of their own.
+    pub fn needs_repaint(&self) -> bool {
+        self.changed() && !self.shown.is_empty() && self.protocol != Some(GraphicsProtocol::Kitty)
+    }
+
+    /// Draw the queued placements if they changed; images of files are read under `root`.
+    pub fn present(&mut self, root: &Path) -> io::Result<()> {
+        let Some(protocol) = self.protocol else {
+            return Ok(());
+        };
+        if !self.changed() {
+            return Ok(());
+        }
+        let mut out = io::stdout();
+        queue!(out, SavePosition)?;
+        if protocol == GraphicsProtocol::Kitty {
+            // Remove placements but keep transmitted image data for reuse.
+            out.write_all(b"\x1b_Ga=d,d=a,q=2\x1b\\")?;
+        }
+        let cell_px = cell_pixels();
+        for placement in &self.queued {
+            let size_key = (placement.key, placement.cols, placement.rows);
+            let sequence = match self.encoded.get(&size_key) {
+                Some(sequence) => sequence.clone(),
+                None => {
+                    let next_id = self.kitty_ids.len() as u32 + 1;
+                    let kitty_id = *self.kitty_ids.entry(placement.key).or_insert(next_id);
+                    let sequence = placement
+                        .image
+                        .bytes(root)
+                        .and_then(|bytes| encode(protocol, &bytes, placement, cell_px, kitty_id));
+                    if sequence.is_none() {
+                        debug!("image not drawable (label={})", placement.image.label());
+                    }
+                    self.encoded.insert(size_key, sequence.clone());
+                    sequence
+                }
+            };
+            if let Some(sequence) = sequence {
+                queue!(out, MoveTo(placement.x, placement.y))?;
+                out.write_all(sequence.as_bytes())?;
+                // Once transmitted, a kitty image is placed again by id.
+                if let Some(place) = kitty_place(&sequence) {
+                    self.encoded.insert(size_key, Some(place));
+                }
+            }
+        }
+        queue!(out, RestorePosition)?;
+        out.flush()?;
+        self.shown.clone_from(&self.queued);
+        Ok(())
+    }
+}
+
+/// Terminal cell size in pixels.
+fn cell_pixels() -> (u16, u16) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => {
+            (size.width / size.columns, size.height / size.rows)
+        }
+        _ => DEFAULT_CELL_PX,
+    }
+}
+
+/// Pixel size of a PNG from its header.
+fn png_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 24 || &bytes[..8] != b"\x89PNG\r\n\x1a\n" {
+        return None;
+    }
+    let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// Largest cell box within `cols` x `rows` that keeps the image's aspect ratio.
+fn fit(size: (u32, u32), cols: u16, rows: u16, cell_px: (u16, u16)) -> (u16, u16) {
+    let (width, height) = (size.0.max(1) as f64, size.1.max(1) as f64);
+    let (cell_w, cell_h) = (cell_px.0.max(1) as f64, cell_px.1.max(1) as f64);
+    let scale = (cols as f64 * cell_w / width).min(rows as f64 * cell_h / height);
+    let fit_cols = ((width * scale / cell_w).round() as u16).clamp(1, cols);
+    let fit_rows = ((height * scale / cell_h).round() as u16).clamp(1, rows);
+    (fit_cols, fit_rows)
+}
+
+/// Escape sequence that draws `bytes` at the cursor within the placement's cell box.
+fn encode(
+    protocol: GraphicsProtocol,
+    bytes: &[u8],
+    placement: &Placement,
+    cell_px: (u16, u16),
+    kitty_id: u32,
+) -> Option<String> {
+    let (cols, rows) = match png_size(bytes) {
+        Some(size) => fit(size, placement.cols, placement.rows, cell_px),
+        None => (placement.cols, placement.rows),
+    };
+    match protocol {
+        GraphicsProtocol::Kitty => {
+            png_size(bytes)?;
+            Some(kitty_sequence(bytes, kitty_id, cols, rows))
+        }
+        GraphicsProtocol::Iterm2 => Some(format!(
+            "\x1b]1337;File=inline=1;size={};width={cols};height={rows};preserveAspectRatio=1:{}\x07",
+            bytes.len(),
+            STANDARD.encode(bytes)
+        )),
+        GraphicsProtocol::Sixel => sixel_sequence(bytes, cols * cell_px.0, rows * cell_px.1),
+    }
+}
+
+/// Transmit the PNG under `id` (a=T also places it), without moving the cursor.
+fn kitty_sequence(bytes: &[u8], id: u32, cols: u16, rows: u16) -> String {
+    let data = STANDARD.encode(bytes);
+    let chunks = data.as_bytes().chunks(KITTY_CHUNK).collect::<Vec<_>>();
+    let mut sequence = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(index + 1 < chunks.len());
+        let chunk = String::from_utf8_lossy(chunk);
+        if index == 0 {
+            sequence.push_str(&format!(
+                "\x1b_Ga=T,f=100,i={id},c={cols},r={rows},C=1,q=2,m={more};{chunk}\x1b\\"
+            ));
+        } else {
+            sequence.push_str(&format!("\x1b_Gm={more};{chunk}\x1b\\"));
+        }
+    }
+    sequence
+}
+
+/// Placement command reusing the image transmitted by a kitty `a=T` sequence.
+fn kitty_place(sequence: &str) -> Option<String> {
+    let params = sequence.strip_prefix("\x1b_Ga=T,")?.split(';').next()?;
+    let params = params
+        .split(',')
+        .filter(|param| ["i=", "c=", "r="].iter().any(|key| param.starts_with(key)))
+        .collect::<Vec<_>>()
+        .join(",");
+    Some(format!("\x1b_Ga=p,{params},C=1,q=2\x1b\\"))
+}
+
+/// Sixel data from `img2sixel`, scaled to fit `width` x `height` pixels.
+fn sixel_sequence(bytes: &[u8], width: u16, height: u16) -> Option<String> {
+    let mut child = Command::new("img2sixel")
+        .args(["-w", &width.to_string(), "-h", &height.to_string()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .inspect_err(|err| debug!("img2sixel unavailable: {err}"))
+        .ok()?;
+    let mut stdin = child.stdin.take()?;
+    let input = bytes.to_vec();
+    // Write on a thread so a large image cannot deadlock against a full stdout pipe.
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output().ok()?;
+    let _ = writer.join();
+    if !output.status.success() {
+        debug!("img2sixel failed (status={})", output.status);
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GraphicsProtocol, ImageRef, find_images, fit, kitty_place, kitty_sequence};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn detects_protocol_from_environment() {
+        let detect = |vars: &[(&str, &str)]| {
+            GraphicsProtocol::detect_from(|name| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty")]),
+            Some(GraphicsProtocol::Kitty)
+        );
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "iTerm.app")]),
+            Some(GraphicsProtocol::Iterm2)
+        );
+        assert_eq!(detect(&[("TERM", "foot")]), Some(GraphicsProtocol::Sixel));
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/tmux")]),
+            None
+        );
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), None);
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty"), ("ODYSSEY_TUI_GRAPHICS", "none")]),
+            None
+        );
+        assert_eq!(
+            detect(&[("ODYSSEY_TUI_GRAPHICS", "sixel")]),
+            Some(GraphicsProtocol::Sixel)
+        );
+    }
+
+    #[test]
+    fn finds_inline_and_file_images_in_tool_results() {
+        let result = json!({
+            "files": [
+                {
+                    "path": "shots/login.png",
+                    "binary": true,
+                    "mime_type": "image/png",
+                    "image": { "type": "image", "mime_type": "image/png", "data": "iVBORw0KGgo=" },
+                },
+                { "path": "shots/home.jpg", "binary": true, "mime_type": "image/jpeg" },
+                { "path": "notes.bin", "binary": true, "mime_type": "application/octet-stream" },
+            ],
+            "screenshot": { "type": "image", "mime_type": "image/webp", "data": "UklGRg==" },
+        });
+        assert_eq!(
+            find_images(&result),
+            vec![
+                ImageRef {
+                    mime_type: "image/png".to_string(),
+                    path: Some("shots/login.png".to_string()),
+                    data: Some("iVBORw0KGgo=".to_string()),
+                },
+                ImageRef {
+                    mime_type: "image/jpeg".to_string(),
+                    path: Some("shots/home.jpg".to_string()),
+                    data: None,
+                },
+                ImageRef {
+                    mime_type: "image/webp".to_string(),
+                    path: None,
+                    data: Some("UklGRg==".to_string()),
+                },
+            ]
+        );
+        assert_eq!(find_images(&json!({ "exit_code": 0 })), Vec::new());
+    }
+
+    #[test]
+    fn sizes_and_chunks_kitty_images() {
+        // A wide image fills the columns; a tall one fills the rows.
+        assert_eq!(fit((1600, 400), 60, 12, (8, 16)), (60, 8));
+        assert_eq!(fit((400, 1600), 60, 12, (8, 16)), (6, 12));
+
+        let sequence = kitty_sequence(&[0u8; 4000], 7, 20, 10);
+        assert_eq!(
+            sequence.starts_with("\x1b_Ga=T,f=100,i=7,c=20,r=10,C=1,q=2,m=1;"),
+            true
+        );
+        assert_eq!(sequence.matches("\x1b_G").count(), 2);
+        assert_eq!(sequence.contains("\x1b_Gm=0;"), true);
+        assert_eq!(
+            kitty_place(&sequence).as_deref(),
+            Some("\x1b_Ga=p,i=7,c=20,r=10,C=1,q=2\x1b\\")
+        );
+        assert_eq!(kitty_place("\x1b_Ga=p,i=7,C=1\x1b\\"), None);
+    }
+}
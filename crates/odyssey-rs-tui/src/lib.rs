@@ -1,52 +1,80 @@
 //! Library entry point for the Odyssey TUI.
 //!
 //! Provides a reusable [`run`] function that launches the Ratatui terminal UI
-//! against a pre-configured [`Orchestrator`].
+//! against a pre-configured [`Orchestrator`]. The [`bootstrap`] module holds
+//! the setup helpers used by the `odyssey-rs-tui` binary. [`acp`] serves the
+//! orchestrator to editors over the Agent Client Protocol, and [`mcp`] exposes
+//! its tools and agents as an MCP server. UI state such as the
+//! active session and model is saved per workspace in `.odyssey/tui-state.json`.
 
+pub mod acp;
+mod activity;
 mod app;
+mod batch;
+pub mod bootstrap;
 mod client;
+mod clipboard;
+mod commands;
 mod event;
 mod event_bus;
+mod images;
+mod jsonrpc;
+pub mod mcp;
+mod mentions;
+mod model_cache;
+mod mouse;
+mod notify;
+mod plan;
+mod recall;
+mod state;
 mod ui;
 
-pub use event_bus::EventBus;
+pub use event_bus::{EventBus, EventFilter, FilteredReceiver};
 
 use anyhow::anyhow;
-use app::{App, PendingPermission, ViewerKind};
+use app::{App, ChatRole, PendingPermission, ViewerKind};
 use client::OrchestratorClient;
+use commands::{CopyTarget, ModelCacheAction, SlashCommand, parse_slash_command};
 use crossterm::event::{
-    DisableMouseCapture, EnableMouseCapture, Event as CrosstermEvent, KeyCode, KeyEvent,
-    KeyModifiers, MouseEventKind,
+    DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+    EnableFocusChange, EnableMouseCapture, Event as CrosstermEvent, KeyCode, KeyEvent,
+    KeyModifiers, MouseButton, MouseEventKind,
 };
 use crossterm::execute;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
 use event::AppEvent;
+use images::{Graphics, GraphicsProtocol};
 use log::{debug, info, warn};
-use odyssey_rs_core::Orchestrator;
-use odyssey_rs_protocol::ApprovalDecision;
+use mentions::{index_workspace_files, mentioned_paths};
+use model_cache::{describe_cached_models, describe_progress, progress_changed};
+use notify::Notifier;
+use odyssey_rs_config::NotificationsConfig;
+use odyssey_rs_core::attachments::read_attachment;
+use odyssey_rs_core::types::{Message, Role};
+use odyssey_rs_core::{ModelDownloadRequest, Orchestrator};
+use odyssey_rs_protocol::{ApprovalDecision, Attachment};
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
+use recall::describe_recall;
+use state::TuiState;
 use std::io::{self, Stdout};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use uuid::Uuid;
 
 const ENV_USER: &str = "USER";
 const ENV_USERNAME: &str = "USERNAME";
-
-/// Supported slash commands in the TUI input box.
-enum SlashCommand {
-    New,
-    Join(Uuid),
-    Sessions,
-    Skills,
-    Models,
-    Model(String),
-}
+/// Messages loaded per page when opening a session or scrolling back through it.
+const HISTORY_PAGE_SIZE: usize = 200;
+/// First delay before re-subscribing a failed event stream; doubles per failure.
+const STREAM_RETRY_BASE: Duration = Duration::from_millis(500);
+/// Longest delay between event stream re-subscriptions.
+const STREAM_RETRY_MAX: Duration = Duration::from_secs(30);
 
 /// Configuration for the Odyssey TUI session.
 #[derive(Debug, Clone, Default)]
@@ -57,10 +85,14 @@ pub struct TuiConfig {
     pub model_id: String,
     /// Default agent ID to use when creating sessions.
     pub agent_id: Option<String>,
-    /// Display name for the current user.
+    /// Name of the current user, shown in the header and recorded on sessions.
     pub user_name: Option<String>,
     /// Current working directory (shown in header).
     pub cwd: Option<std::path::PathBuf>,
+    /// Start a new session instead of resuming the one saved for the workspace.
+    pub new_session: bool,
+    /// Background notifications for finished turns and approval requests.
+    pub notifications: NotificationsConfig,
 }
 
 /// Launch the Odyssey TUI against a pre-configured orchestrator.
@@ -83,9 +115,18 @@ pub async fn run(
         .or_else(|| std::env::current_dir().ok())
         .ok_or_else(|| anyhow::anyhow!("cannot determine working directory"))?;
 
-    let client = Arc::new(OrchestratorClient::new(orchestrator, events));
+    let user_name = config.user_name.clone().unwrap_or_else(resolve_user_name);
+    let client = Arc::new(OrchestratorClient::new(
+        orchestrator,
+        events,
+        user_name.clone(),
+    ));
 
     let mut app = App::new();
+    app.set_notifier(Notifier::new(config.notifications.clone()));
+    app.graphics = Graphics::new(GraphicsProtocol::detect());
+    let saved = TuiState::load(&cwd);
+    app.show_side_panel = saved.side_panel;
 
     // Load and validate agents
     let agents = client.list_agents().await?;
@@ -97,6 +138,10 @@ pub async fn run(
 
     if let Some(agent_id) = config.agent_id.clone() {
         app.active_agent = Some(agent_id);
+    } else if let Some(agent_id) = saved.agent_id.clone()
+        && app.has_agent(&agent_id)
+    {
+        app.active_agent = Some(agent_id);
     }
 
     // Load and validate models
@@ -119,6 +164,13 @@ pub async fn run(
         format!("{} ({})", config.model_id, config.model_name)
     };
     app.model = app_model;
+    if let Some(model_id) = saved.model_id.clone()
+        && model_id != config.model_id
+        && app.models.contains(&model_id)
+    {
+        debug!("restoring saved model (model_id={model_id})");
+        app.set_active_model(model_id);
+    }
 
     if let Ok(sessions) = client.list_sessions().await {
         debug!("loaded sessions (count={})", sessions.len());
@@ -133,9 +185,18 @@ pub async fn run(
         app.set_skills(skills);
     }
 
-    let user_name = config.user_name.clone().unwrap_or_else(resolve_user_name);
+    if let Ok(presets) = client.list_prompt_presets().await {
+        app.set_prompt_presets(presets);
+    }
+
     app.set_user_name(user_name);
-    app.cwd = cwd.display().to_string();
+    app.workspace_root = cwd.display().to_string();
+    app.cwd.clone_from(&app.workspace_root);
+    let index_root = cwd.clone();
+    match tokio::task::spawn_blocking(move || index_workspace_files(&index_root)).await {
+        Ok(files) => app.set_workspace_files(files),
+        Err(err) => warn!("failed to index workspace files: {err}"),
+    }
 
     let mut terminal = setup_terminal()?;
     let (tx, mut rx) = mpsc::channel(256);
@@ -143,6 +204,29 @@ pub async fn run(
     spawn_tick(tx.clone());
 
     let mut stream_handle: Option<JoinHandle<()>> = None;
+    if !config.new_session
+        && let Some(session_id) = saved.active_session
+        && app.sessions.iter().any(|session| session.id == session_id)
+    {
+        match join_session(
+            &client,
+            &mut app,
+            session_id,
+            tx.clone(),
+            &mut stream_handle,
+        )
+        .await
+        {
+            Ok(()) => {
+                if let Some(scroll) = saved.scroll {
+                    app.restore_scroll(scroll);
+                }
+            }
+            Err(err) => {
+                warn!("failed to resume saved session (session_id={session_id}): {err}");
+            }
+        }
+    }
     if app.active_session.is_none()
         && let Err(err) = create_session(&client, &mut app, tx.clone(), &mut stream_handle).await
     {
@@ -151,6 +235,14 @@ pub async fn run(
 
     loop {
         terminal.draw(|frame| ui::draw(frame, &mut app))?;
+        if app.graphics.needs_repaint() {
+            // Erase images drawn into cells before drawing them at their new position.
+            terminal.clear()?;
+            terminal.draw(|frame| ui::draw(frame, &mut app))?;
+        }
+        if let Err(err) = app.graphics.present(Path::new(&app.workspace_root)) {
+            warn!("failed to draw inline images: {err}");
+        }
         let event = rx
             .recv()
             .await
@@ -161,6 +253,9 @@ pub async fn run(
     }
 
     restore_terminal(&mut terminal)?;
+    if let Err(err) = TuiState::capture(&app).save(&cwd) {
+        warn!("failed to save tui state: {err}");
+    }
     Ok(())
 }
 
@@ -174,6 +269,12 @@ async fn handle_app_event(
 ) -> anyhow::Result<bool> {
     match event {
         AppEvent::Input(key) => handle_input(key, client, app, sender, stream_handle).await,
+        AppEvent::Paste(text) => {
+            if app.viewer.is_none() && app.pending_permissions.is_empty() {
+                app.paste_text(&text);
+            }
+            Ok(false)
+        }
         AppEvent::Server(event) => {
             let Some(active_session) = app.active_session else {
                 return Ok(false);
@@ -181,11 +282,34 @@ async fn handle_app_event(
             if event.session_id != active_session {
                 return Ok(false);
             }
+            app.notify(&event.payload);
             app.apply_event(event);
             Ok(false)
         }
-        AppEvent::StreamError(message) => {
-            app.push_system_message(format!("stream error: {message}"));
+        AppEvent::Focus(focused) => {
+            app.terminal_focused = focused;
+            Ok(false)
+        }
+        AppEvent::StreamConnected(session_id) => {
+            if app.active_session == Some(session_id) && app.stream_retry_at.take().is_some() {
+                info!("event stream reconnected (session_id={session_id})");
+                resync_session(client, app).await?;
+                app.push_status("reconnected");
+            }
+            Ok(false)
+        }
+        AppEvent::StreamError {
+            session_id,
+            message,
+            retry_in,
+        } => {
+            if app.active_session != Some(session_id) {
+                return Ok(false);
+            }
+            if app.stream_retry_at.is_none() {
+                app.push_system_message(format!("stream error: {message}; reconnecting"));
+            }
+            app.stream_retry_at = Some(Instant::now() + retry_in);
             Ok(false)
         }
         AppEvent::ActionError(message) => {
@@ -193,6 +317,14 @@ async fn handle_app_event(
             app.push_status("idle");
             Ok(false)
         }
+        AppEvent::ActionNotice(message) => {
+            app.push_system_message(message);
+            Ok(false)
+        }
+        AppEvent::ModelDownload(progress) => {
+            app.push_status(describe_progress(&progress));
+            Ok(false)
+        }
         AppEvent::Scroll(delta) => {
             if app.viewer.is_some() {
                 if delta < 0 {
@@ -202,13 +334,22 @@ async fn handle_app_event(
                 }
             } else if delta < 0 {
                 app.scroll_up((-delta) as u16);
+                backfill_history(client, app).await?;
             } else if delta > 0 {
                 app.scroll_down(delta as u16);
             }
             Ok(false)
         }
+        AppEvent::Click { column, row } => {
+            handle_click(column, row, client, app, sender, stream_handle).await
+        }
         AppEvent::Tick => {
             app.refresh_cpu();
+            app.activity.run = client.run_metrics();
+            app.activity.tick();
+            if app.viewer == Some(ViewerKind::Approvals) {
+                refresh_approvals(client, app).await?;
+            }
             Ok(false)
         }
     }
@@ -230,12 +371,22 @@ async fn handle_viewer_input(
                     app.selected_session -= 1;
                 }
             }
-            ViewerKind::Skills => app.viewer_scroll_up(1),
+            ViewerKind::Skills | ViewerKind::Prompt | ViewerKind::Help => app.viewer_scroll_up(1),
             ViewerKind::Models => {
                 if app.selected_model > 0 {
                     app.selected_model -= 1;
                 }
             }
+            ViewerKind::Agents => {
+                if app.selected_agent > 0 {
+                    app.selected_agent -= 1;
+                }
+            }
+            ViewerKind::Approvals => {
+                if app.selected_approval > 0 {
+                    app.selected_approval -= 1;
+                }
+            }
         },
         KeyCode::Down => match kind {
             ViewerKind::Sessions => {
@@ -243,31 +394,116 @@ async fn handle_viewer_input(
                     app.selected_session += 1;
                 }
             }
-            ViewerKind::Skills => app.viewer_scroll_down(1),
+            ViewerKind::Skills | ViewerKind::Prompt | ViewerKind::Help => app.viewer_scroll_down(1),
             ViewerKind::Models => {
                 if app.selected_model + 1 < app.models.len() {
                     app.selected_model += 1;
                 }
             }
+            ViewerKind::Agents => {
+                if app.selected_agent + 1 < app.agents.len() {
+                    app.selected_agent += 1;
+                }
+            }
+            ViewerKind::Approvals => {
+                if app.selected_approval + 1 < app.approvals.len() {
+                    app.selected_approval += 1;
+                }
+            }
         },
         KeyCode::PageUp => app.viewer_scroll_up(5),
         KeyCode::PageDown => app.viewer_scroll_down(5),
         KeyCode::Home => app.viewer_scroll_up(u16::MAX),
         KeyCode::End => app.viewer_scroll_down(u16::MAX),
         KeyCode::Enter => {
-            if matches!(kind, ViewerKind::Sessions) {
-                activate_selected_session(client, app, sender, stream_handle).await?;
-                app.close_viewer();
-            } else if matches!(kind, ViewerKind::Models) {
-                activate_selected_model(app)?;
-                app.close_viewer();
-            }
+            activate_viewer_selection(kind, client, app, sender, stream_handle).await?;
+        }
+        KeyCode::Char(ch @ ('y' | 'a' | 'n')) if matches!(kind, ViewerKind::Approvals) => {
+            resolve_selected_approval(client, app, ch).await?;
         }
         _ => {}
     }
     Ok(false)
 }
 
+/// Act on the selected viewer item and close the viewer, as Enter does.
+async fn activate_viewer_selection(
+    kind: ViewerKind,
+    client: &Arc<OrchestratorClient>,
+    app: &mut App,
+    sender: mpsc::Sender<AppEvent>,
+    stream_handle: &mut Option<JoinHandle<()>>,
+) -> anyhow::Result<()> {
+    match kind {
+        ViewerKind::Sessions => {
+            activate_selected_session(client, app, sender, stream_handle).await?;
+            app.close_viewer();
+        }
+        ViewerKind::Models => {
+            activate_selected_model(app)?;
+            app.close_viewer();
+        }
+        ViewerKind::Agents => {
+            if let Some(agent) = app.agents.get(app.selected_agent) {
+                let agent_id = agent.id.clone();
+                if let Err(err) = switch_agent(client, app, agent_id, sender, stream_handle).await {
+                    app.push_status(err);
+                }
+            }
+            app.close_viewer();
+        }
+        ViewerKind::Skills | ViewerKind::Prompt | ViewerKind::Help | ViewerKind::Approvals => {}
+    }
+    Ok(())
+}
+
+/// Handle a left click: in a viewer the first click selects the item under the
+/// cursor and a second click activates it; in the chat view a click on a tool
+/// call expands or collapses it.
+async fn handle_click(
+    column: u16,
+    row: u16,
+    client: &Arc<OrchestratorClient>,
+    app: &mut App,
+    sender: mpsc::Sender<AppEvent>,
+    stream_handle: &mut Option<JoinHandle<()>>,
+) -> anyhow::Result<bool> {
+    let Some(kind) = app.viewer else {
+        if let Some(index) = app.chat_clicks.target_at(column, row) {
+            app.toggle_tool_call(index);
+        }
+        return Ok(false);
+    };
+    let Some(item) = app.viewer_clicks.target_at(column, row) else {
+        return Ok(false);
+    };
+    let selected = match kind {
+        ViewerKind::Sessions => &mut app.selected_session,
+        ViewerKind::Models => &mut app.selected_model,
+        ViewerKind::Agents => &mut app.selected_agent,
+        ViewerKind::Approvals => &mut app.selected_approval,
+        ViewerKind::Skills | ViewerKind::Prompt | ViewerKind::Help => return Ok(false),
+    };
+    if *selected == item {
+        activate_viewer_selection(kind, client, app, sender, stream_handle).await?;
+    } else {
+        *selected = item;
+    }
+    Ok(false)
+}
+
+/// Turn mouse capture off so the terminal can select text, or back on.
+fn set_select_mode(app: &mut App, enabled: bool) -> anyhow::Result<()> {
+    if enabled {
+        execute!(io::stdout(), DisableMouseCapture)?;
+    } else {
+        execute!(io::stdout(), EnableMouseCapture)?;
+    }
+    debug!("select mode (enabled={enabled})");
+    app.select_mode = enabled;
+    Ok(())
+}
+
 /// Handle keyboard input in the default (non-viewer) state.
 async fn handle_default_input(
     key: KeyEvent,
@@ -286,25 +522,59 @@ async fn handle_default_input(
         KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
             activate_selected_session(client, app, sender, stream_handle).await?;
         }
+        KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.copy_to_clipboard(CopyTarget::LastMessage);
+        }
+        KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.copy_to_clipboard(CopyTarget::CodeBlock(1));
+        }
+        KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.copy_to_clipboard(CopyTarget::Transcript);
+        }
+        KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.paste_from_clipboard();
+        }
+        KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            edit_last_message(client, app).await?;
+        }
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.show_side_panel = !app.show_side_panel;
+        }
         KeyCode::PageUp => {
             app.scroll_up(5);
+            backfill_history(client, app).await?;
         }
         KeyCode::PageDown => {
             app.scroll_down(5);
         }
+        KeyCode::Up if app.show_file_picker => {
+            app.select_prev_file();
+        }
+        KeyCode::Down if app.show_file_picker => {
+            app.select_next_file();
+        }
+        KeyCode::Up if app.show_slash_commands => {
+            app.select_prev_slash_command();
+        }
+        KeyCode::Down if app.show_slash_commands => {
+            app.select_next_slash_command();
+        }
         KeyCode::Up => {
             app.scroll_up(1);
+            backfill_history(client, app).await?;
         }
         KeyCode::Down => {
             app.scroll_down(1);
         }
         KeyCode::Home => {
             app.scroll_to_top();
+            backfill_history(client, app).await?;
         }
         KeyCode::End => {
             app.enable_auto_scroll();
         }
         KeyCode::Enter => {
+            app.show_file_picker = false;
             if app.input.trim().is_empty() {
                 app.show_slash_commands = false;
                 return Ok(false);
@@ -317,19 +587,32 @@ async fn handle_default_input(
                 {
                     app.push_system_message(err);
                 }
+            } else if let Some(turn_id) = app.editing_turn.take() {
+                app.show_slash_commands = false;
+                retry_message(client, app, turn_id, sender.clone()).await?;
             } else {
                 app.show_slash_commands = false;
                 send_message(client, app, sender.clone()).await?;
             }
         }
+        KeyCode::Tab => {
+            if app.show_file_picker {
+                app.complete_file_mention();
+            } else if app.show_slash_commands {
+                app.complete_slash_command();
+            }
+        }
         KeyCode::Backspace => {
             app.input.pop();
-            app.show_slash_commands = app.input.trim_start().starts_with('/');
+            app.refresh_input_popups();
+        }
+        KeyCode::Char('?') if app.input.is_empty() => {
+            app.open_viewer(ViewerKind::Help);
         }
         KeyCode::Char(ch) => {
             if !key.modifiers.contains(KeyModifiers::CONTROL) {
                 app.input.push(ch);
-                app.show_slash_commands = app.input.trim_start().starts_with('/');
+                app.refresh_input_popups();
             }
         }
         _ => {}
@@ -349,20 +632,37 @@ async fn handle_input(
     if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
         return Ok(true);
     }
+    if key.code == KeyCode::F(2) {
+        set_select_mode(app, !app.select_mode)?;
+        return Ok(false);
+    }
     if key.code == KeyCode::Esc {
+        if app.select_mode {
+            set_select_mode(app, false)?;
+            return Ok(false);
+        }
         if app.viewer.is_some() {
             app.close_viewer();
             return Ok(false);
         }
+        if app.show_file_picker {
+            app.show_file_picker = false;
+            return Ok(false);
+        }
         if app.show_slash_commands {
             app.show_slash_commands = false;
             app.input.clear();
             return Ok(false);
         }
+        if app.editing_turn.is_some() {
+            app.cancel_edit();
+            return Ok(false);
+        }
         return Ok(true);
     }
 
     if let Some(permission) = app.pending_permissions.front().cloned()
+        && app.viewer != Some(ViewerKind::Approvals)
         && matches!(
             key.code,
             KeyCode::Char('y') | KeyCode::Char('a') | KeyCode::Char('n')
@@ -429,6 +729,47 @@ async fn refresh_sessions(client: &Arc<OrchestratorClient>, app: &mut App) -> an
     Ok(())
 }
 
+/// Refresh the agent list from the orchestrator.
+async fn refresh_agents(client: &Arc<OrchestratorClient>, app: &mut App) -> anyhow::Result<()> {
+    debug!("refreshing agents");
+    let agents = client.list_agents().await?;
+    app.set_agents(agents);
+    Ok(())
+}
+
+/// Refresh the pending approval list from the orchestrator.
+async fn refresh_approvals(client: &Arc<OrchestratorClient>, app: &mut App) -> anyhow::Result<()> {
+    let approvals = client.list_pending_approvals().await?;
+    app.set_approvals(approvals);
+    Ok(())
+}
+
+/// Resolve the approval selected in the approvals viewer.
+async fn resolve_selected_approval(
+    client: &Arc<OrchestratorClient>,
+    app: &mut App,
+    key: char,
+) -> anyhow::Result<()> {
+    let Some(approval) = app.approvals.get(app.selected_approval) else {
+        return Ok(());
+    };
+    let request_id = approval.request_id;
+    let decision = match key {
+        'y' => ApprovalDecision::AllowOnce,
+        'a' => ApprovalDecision::AllowAlways,
+        _ => ApprovalDecision::Deny,
+    };
+    info!("resolving approval from viewer (request_id={request_id}, decision={decision:?})");
+    if client.resolve_permission(request_id, decision).await? {
+        app.push_status("permission sent");
+    } else {
+        app.push_status("permission request not found");
+    }
+    app.pending_permissions
+        .retain(|permission| permission.request_id != request_id);
+    refresh_approvals(client, app).await
+}
+
 /// Refresh the model list from the orchestrator.
 async fn refresh_models(client: &Arc<OrchestratorClient>, app: &mut App) -> anyhow::Result<()> {
     debug!("refreshing models");
@@ -448,7 +789,7 @@ async fn create_session(
     let agent_id = app
         .active_agent
         .clone()
-        .or_else(|| app.agents.first().cloned());
+        .or_else(|| app.agents.first().map(|agent| agent.id.clone()));
     info!(
         "creating session (agent_id={})",
         agent_id.as_deref().unwrap_or("default")
@@ -476,8 +817,15 @@ async fn activate_selected_session(
         let agent_id = session.agent_id;
         info!("activating session (session_id={})", session_id);
         app.set_active_session(session_id, agent_id);
-        if let Ok(session_detail) = client.get_session(session_id).await {
-            app.load_messages(session_detail.messages);
+        if let Ok(page) = client
+            .get_session_page(session_id, None, HISTORY_PAGE_SIZE)
+            .await
+        {
+            let older_cursor = page.older_cursor();
+            app.load_messages(page.messages, older_cursor);
+        }
+        if let Some(cwd) = client.session_cwd(session_id).await {
+            app.cwd = cwd.display().to_string();
         }
         app.push_status("session selected");
         spawn_stream(client.clone(), session_id, sender, stream_handle);
@@ -496,6 +844,48 @@ fn activate_selected_model(app: &mut App) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Load the newest page of a full transcript, leaving older messages to backfill.
+fn load_transcript_tail(app: &mut App, mut messages: Vec<Message>) {
+    let start = messages.len().saturating_sub(HISTORY_PAGE_SIZE);
+    let tail = messages.split_off(start);
+    app.load_messages(tail, (start > 0).then_some(start));
+}
+
+/// Prepend the next older page once the chat view is scrolled to the top.
+async fn backfill_history(client: &Arc<OrchestratorClient>, app: &mut App) -> anyhow::Result<()> {
+    let (Some(session_id), Some(cursor)) = (app.active_session, app.history_cursor) else {
+        return Ok(());
+    };
+    if app.scroll > 0 {
+        return Ok(());
+    }
+    let page = client
+        .get_session_page(session_id, Some(cursor), HISTORY_PAGE_SIZE)
+        .await?;
+    let older_cursor = page.older_cursor();
+    app.prepend_messages(page.messages, older_cursor);
+    Ok(())
+}
+
+/// Reload the active session's newest messages and pending approvals from the
+/// session journal after its event stream missed events.
+async fn resync_session(client: &Arc<OrchestratorClient>, app: &mut App) -> anyhow::Result<()> {
+    let Some(session_id) = app.active_session else {
+        return Ok(());
+    };
+    let page = client
+        .get_session_page(session_id, None, HISTORY_PAGE_SIZE)
+        .await?;
+    let older_cursor = page.older_cursor();
+    app.load_messages(page.messages, older_cursor);
+    if let Some(cwd) = client.session_cwd(session_id).await {
+        app.cwd = cwd.display().to_string();
+    }
+    let approvals = client.list_pending_approvals().await?;
+    app.restore_pending_permissions(&approvals);
+    Ok(())
+}
+
 /// Join a session by id and load its transcript.
 async fn join_session(
     client: &Arc<OrchestratorClient>,
@@ -505,9 +895,15 @@ async fn join_session(
     stream_handle: &mut Option<JoinHandle<()>>,
 ) -> anyhow::Result<()> {
     info!("joining session (session_id={})", session_id);
-    let session = client.get_session(session_id).await?;
-    app.set_active_session(session.id, session.agent_id);
-    app.load_messages(session.messages);
+    let page = client
+        .get_session_page(session_id, None, HISTORY_PAGE_SIZE)
+        .await?;
+    app.set_active_session(page.id, page.agent_id.clone());
+    let older_cursor = page.older_cursor();
+    app.load_messages(page.messages, older_cursor);
+    if let Some(cwd) = client.session_cwd(session_id).await {
+        app.cwd = cwd.display().to_string();
+    }
     app.push_status("session joined");
     spawn_stream(client.clone(), session_id, sender, stream_handle);
     Ok(())
@@ -521,7 +917,7 @@ async fn handle_slash_command(
     stream_handle: &mut Option<JoinHandle<()>>,
     input: String,
 ) -> Result<(), String> {
-    let command = parse_slash_command(&input)?;
+    let command = parse_slash_command(&input, &app.prompt_presets)?;
     let Some(command) = command else {
         return Ok(());
     };
@@ -547,61 +943,72 @@ async fn handle_slash_command(
                 .map_err(|err| err.to_string())?;
             app.open_viewer(ViewerKind::Models);
         }
+        SlashCommand::ModelCache(action) => {
+            manage_model_cache(client, app, sender, action).await?;
+        }
         SlashCommand::Model(model_id) => {
             set_model_by_id(client, app, model_id).await?;
         }
-    }
-    Ok(())
-}
-
-/// Parse a slash command from the input line.
-fn parse_slash_command(input: &str) -> Result<Option<SlashCommand>, String> {
-    let trimmed = input.trim();
-    if !trimmed.starts_with('/') {
-        return Ok(None);
-    }
-    let mut parts = trimmed.trim_start_matches('/').split_whitespace();
-    let Some(command) = parts.next() else {
-        return Ok(None);
-    };
-    match command.to_lowercase().as_str() {
-        "new" => Ok(Some(SlashCommand::New)),
-        "skills" => Ok(Some(SlashCommand::Skills)),
-        "sessions" => Ok(Some(SlashCommand::Sessions)),
-        "models" => Ok(Some(SlashCommand::Models)),
-        "model" => match parts.next() {
-            None => Ok(Some(SlashCommand::Models)),
-            Some("list") => Ok(Some(SlashCommand::Models)),
-            Some(id) => Ok(Some(SlashCommand::Model(id.to_string()))),
-        },
-        "join" => {
-            let Some(id) = parts.next() else {
-                return Err("usage: /join <session_id>".to_string());
+        SlashCommand::ModelOnce(model_id) => {
+            set_next_turn_model(client, app, model_id).await?;
+        }
+        SlashCommand::Agents => {
+            refresh_agents(client, app)
+                .await
+                .map_err(|err| err.to_string())?;
+            app.open_viewer(ViewerKind::Agents);
+        }
+        SlashCommand::Agent(agent_id) => {
+            switch_agent(client, app, agent_id, sender, stream_handle).await?;
+        }
+        SlashCommand::Regenerate(model_id) => {
+            regenerate_message(client, app, model_id, sender).await?;
+        }
+        SlashCommand::Approvals => {
+            refresh_approvals(client, app)
+                .await
+                .map_err(|err| err.to_string())?;
+            app.open_viewer(ViewerKind::Approvals);
+        }
+        SlashCommand::Prompt => {
+            let prompt = client
+                .effective_prompt(app.active_agent.as_deref())
+                .await
+                .map_err(|err| err.to_string())?;
+            app.effective_prompt = Some(prompt);
+            app.open_viewer(ViewerKind::Prompt);
+        }
+        SlashCommand::MemoryWhy(query) => {
+            let Some(session_id) = app.active_session else {
+                return Err("no active session".to_string());
             };
-            let session_id = Uuid::parse_str(id).map_err(|_| "invalid session id".to_string())?;
-            Ok(Some(SlashCommand::Join(session_id)))
-        }
-        "session" => match parts.next() {
-            Some("new") => Ok(Some(SlashCommand::New)),
-            Some("join") => {
-                let Some(id) = parts.next() else {
-                    return Err("usage: /session join <session_id>".to_string());
-                };
-                let session_id =
-                    Uuid::parse_str(id).map_err(|_| "invalid session id".to_string())?;
-                Ok(Some(SlashCommand::Join(session_id)))
-            }
-            Some("list") => Ok(Some(SlashCommand::Sessions)),
-            Some("skills") => Ok(Some(SlashCommand::Skills)),
-            Some(id) => {
-                let session_id =
-                    Uuid::parse_str(id).map_err(|_| "invalid session id".to_string())?;
-                Ok(Some(SlashCommand::Join(session_id)))
-            }
-            None => Err("usage: /session <id>|new|join <id>".to_string()),
-        },
-        _ => Err(format!("unknown command: {command}")),
+            let query = query.or_else(|| {
+                app.messages
+                    .iter()
+                    .rev()
+                    .find(|message| matches!(message.role, ChatRole::User))
+                    .map(|message| message.content.clone())
+            });
+            let records = client
+                .explain_memory_recall(session_id, query.as_deref())
+                .await
+                .map_err(|err| err.to_string())?;
+            app.push_system_message(describe_recall(query.as_deref(), &records));
+        }
+        SlashCommand::Copy(target) => app.copy_to_clipboard(target),
+        SlashCommand::Help => app.open_viewer(ViewerKind::Help),
+        SlashCommand::Preset { name, args } => {
+            let prompt = client
+                .expand_prompt_preset(&name, &args)
+                .await
+                .map_err(|err| err.to_string())?;
+            app.input = prompt;
+            send_message(client, app, sender)
+                .await
+                .map_err(|err| err.to_string())?;
+        }
     }
+    Ok(())
 }
 
 async fn set_model_by_id(
@@ -623,6 +1030,207 @@ async fn set_model_by_id(
     Ok(())
 }
 
+/// Run a `/models` cache action; downloads and verification continue in the background.
+async fn manage_model_cache(
+    client: &Arc<OrchestratorClient>,
+    app: &mut App,
+    sender: mpsc::Sender<AppEvent>,
+    action: ModelCacheAction,
+) -> Result<(), String> {
+    match action {
+        ModelCacheAction::List => {
+            let models = client
+                .list_cached_models()
+                .await
+                .map_err(|err| err.to_string())?;
+            app.push_system_message(describe_cached_models(&models));
+        }
+        ModelCacheAction::Download {
+            repo_id,
+            filename,
+            sha256,
+        } => {
+            info!("downloading model {repo_id}/{filename}");
+            app.push_status(format!("downloading {filename}"));
+            let client = client.clone();
+            tokio::spawn(async move {
+                let progress_sender = sender.clone();
+                let mut last_percent = None;
+                let request = ModelDownloadRequest {
+                    repo_id,
+                    filename,
+                    revision: None,
+                    sha256,
+                };
+                let result = client
+                    .download_model(request, |progress| {
+                        if progress_changed(&progress, &mut last_percent) {
+                            // Dropped updates are superseded by the next chunk.
+                            let _ = progress_sender.try_send(AppEvent::ModelDownload(progress));
+                        }
+                    })
+                    .await;
+                let event = match result {
+                    Ok(model) => AppEvent::ActionNotice(format!(
+                        "downloaded {}/{} to {}",
+                        model.repo_id,
+                        model.filename,
+                        model.path.display()
+                    )),
+                    Err(err) => AppEvent::ActionError(format!("model download failed: {err}")),
+                };
+                let _ = sender.send(event).await;
+            });
+        }
+        ModelCacheAction::Verify {
+            repo_id,
+            filename,
+            sha256,
+        } => {
+            app.push_status(format!("verifying {filename}"));
+            let client = client.clone();
+            tokio::spawn(async move {
+                let event = match client
+                    .verify_model(&repo_id, &filename, sha256.as_deref())
+                    .await
+                {
+                    Ok(model) => AppEvent::ActionNotice(format!(
+                        "{repo_id}/{filename} verified (sha256 {})",
+                        model.sha256.unwrap_or_default()
+                    )),
+                    Err(err) => AppEvent::ActionError(format!("model verification failed: {err}")),
+                };
+                let _ = sender.send(event).await;
+            });
+        }
+        ModelCacheAction::Delete { repo_id, filename } => {
+            let deleted = client
+                .delete_model(&repo_id, &filename)
+                .await
+                .map_err(|err| err.to_string())?;
+            app.push_system_message(if deleted {
+                format!("deleted {repo_id}/{filename}")
+            } else {
+                format!("{repo_id}/{filename} is not cached")
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Use `model_id` for the next message only, or clear the one-shot model.
+async fn set_next_turn_model(
+    client: &Arc<OrchestratorClient>,
+    app: &mut App,
+    model_id: Option<String>,
+) -> Result<(), String> {
+    let Some(model_id) = model_id else {
+        app.next_turn.model = None;
+        app.push_status("next message model cleared");
+        return Ok(());
+    };
+    let mut models = client.list_models().await.map_err(|err| err.to_string())?;
+    models.sort();
+    if !models.contains(&model_id) {
+        return Err(format!("unknown model: {model_id}"));
+    }
+    app.set_models(models);
+    app.push_status(format!("next message model: {model_id}"));
+    app.next_turn.model = Some(model_id);
+    Ok(())
+}
+
+/// Make `agent_id` the active agent for subsequent messages.
+///
+/// Sessions belong to one agent, so a new session is created when the active
+/// session runs a different agent.
+async fn switch_agent(
+    client: &Arc<OrchestratorClient>,
+    app: &mut App,
+    agent_id: String,
+    sender: mpsc::Sender<AppEvent>,
+    stream_handle: &mut Option<JoinHandle<()>>,
+) -> Result<(), String> {
+    refresh_agents(client, app)
+        .await
+        .map_err(|err| err.to_string())?;
+    if !app.has_agent(&agent_id) {
+        return Err(format!("unknown agent: {agent_id}"));
+    }
+    let session_agent = match app.active_session {
+        Some(session_id) => Some(
+            client
+                .get_session(session_id)
+                .await
+                .map_err(|err| err.to_string())?
+                .agent_id,
+        ),
+        None => None,
+    };
+    info!("switching agent (agent_id={agent_id})");
+    app.active_agent = Some(agent_id.clone());
+    if let Some(index) = app.agents.iter().position(|agent| agent.id == agent_id) {
+        app.selected_agent = index;
+    }
+    if session_agent.as_deref() == Some(agent_id.as_str()) {
+        app.push_status(format!("agent set: {agent_id}"));
+        return Ok(());
+    }
+    create_session(client, app, sender, stream_handle)
+        .await
+        .map_err(|err| err.to_string())?;
+    app.push_status(format!("agent set: {agent_id} (new session)"));
+    Ok(())
+}
+
+/// Re-run the last message of the active session, optionally with another model.
+///
+/// The active model is unchanged; the replaced response stays in the session as a
+/// superseded alternative.
+async fn regenerate_message(
+    client: &Arc<OrchestratorClient>,
+    app: &mut App,
+    model_id: Option<String>,
+    sender: mpsc::Sender<AppEvent>,
+) -> Result<(), String> {
+    let Some(session_id) = app.active_session else {
+        app.push_status("no active session");
+        return Ok(());
+    };
+    let llm_id = model_id.unwrap_or_else(|| app.model_id.clone());
+    let models = client.list_models().await.map_err(|err| err.to_string())?;
+    if !models.contains(&llm_id) {
+        return Err(format!("unknown model: {llm_id}"));
+    }
+    let mut messages = client
+        .get_session(session_id)
+        .await
+        .map_err(|err| err.to_string())?
+        .messages;
+    let Some(index) = messages
+        .iter()
+        .rposition(|message| message.role == Role::User)
+    else {
+        return Err("no message to regenerate".to_string());
+    };
+    info!(
+        "regenerating last turn (session_id={}, llm_id={})",
+        session_id, llm_id
+    );
+    messages.truncate(index + 1);
+    load_transcript_tail(app, messages);
+    app.push_status("running");
+    let client = client.clone();
+    tokio::spawn(async move {
+        if let Err(err) = client.regenerate_turn(session_id, llm_id).await {
+            let _ = sender
+                .send(AppEvent::ActionError(format!("regenerate failed: {err}")))
+                .await;
+        }
+    });
+    Ok(())
+}
+
 /// Send a message to the active session.
 async fn send_message(
     client: &Arc<OrchestratorClient>,
@@ -636,6 +1244,8 @@ async fn send_message(
             return Ok(());
         }
     };
+    // The one-shot model from `/model-once` applies to this message only.
+    let next_turn = std::mem::take(&mut app.next_turn);
     let prompt = std::mem::take(&mut app.input);
     info!(
         "sending message (session_id={}, prompt_len={})",
@@ -643,15 +1253,120 @@ async fn send_message(
         prompt.len()
     );
     app.push_user_message(prompt.clone());
+    let attachments = resolve_attachments(app, &prompt);
+    if !attachments.is_empty() {
+        let names = attachments
+            .iter()
+            .map(|attachment| {
+                if attachment.truncated {
+                    format!("{} (excerpt)", attachment.path)
+                } else {
+                    attachment.path.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        app.push_system_message(format!("attached: {names}"));
+    }
     app.enable_auto_scroll();
     let agent_id = app.active_agent.clone();
+    let llm_id = next_turn.model.unwrap_or_else(|| app.model_id.clone());
+    app.push_status("running");
+    spawn_send_message(
+        client.clone(),
+        session_id,
+        prompt,
+        attachments,
+        agent_id,
+        llm_id,
+        sender,
+    );
+    Ok(())
+}
+
+/// Load the last user message of the active session into the input for editing.
+async fn edit_last_message(client: &Arc<OrchestratorClient>, app: &mut App) -> anyhow::Result<()> {
+    let Some(session_id) = app.active_session else {
+        app.push_status("no active session");
+        return Ok(());
+    };
+    match client.last_user_turn(session_id).await? {
+        Some((turn_id, content)) => app.begin_edit(turn_id, content),
+        None => app.push_status("no message to edit"),
+    }
+    Ok(())
+}
+
+/// Resend an edited message, replacing its turn and everything after it.
+async fn retry_message(
+    client: &Arc<OrchestratorClient>,
+    app: &mut App,
+    turn_id: Uuid,
+    sender: mpsc::Sender<AppEvent>,
+) -> anyhow::Result<()> {
+    let Some(session_id) = app.active_session else {
+        app.push_status("no active session");
+        return Ok(());
+    };
+    let prompt = std::mem::take(&mut app.input);
+    info!(
+        "retrying turn (session_id={}, turn_id={}, prompt_len={})",
+        session_id,
+        turn_id,
+        prompt.len()
+    );
+    let mut messages = client.get_session(session_id).await?.messages;
+    if let Some(index) = messages
+        .iter()
+        .position(|message| message.role == Role::User && message.turn_id == Some(turn_id))
+    {
+        messages.truncate(index);
+    }
+    load_transcript_tail(app, messages);
+    app.push_user_message(prompt.clone());
     let llm_id = app.model_id.clone();
     app.push_status("running");
-    spawn_send_message(client.clone(), session_id, prompt, agent_id, llm_id, sender);
+    let client = client.clone();
+    tokio::spawn(async move {
+        match client.retry_turn(session_id, turn_id, prompt, llm_id).await {
+            Ok(retried) => {
+                let _ = sender
+                    .send(AppEvent::ActionNotice(format!(
+                        "previous branch kept in session {}",
+                        retried.fork_session_id
+                    )))
+                    .await;
+            }
+            Err(err) => {
+                let _ = sender
+                    .send(AppEvent::ActionError(format!("retry failed: {err}")))
+                    .await;
+            }
+        }
+    });
     Ok(())
 }
 
+/// Read the files mentioned with `@path`; mentions that are not files stay plain text.
+fn resolve_attachments(app: &mut App, prompt: &str) -> Vec<Attachment> {
+    let root = PathBuf::from(&app.workspace_root);
+    let mut attachments = Vec::new();
+    for path in mentioned_paths(prompt) {
+        if !root.join(&path).is_file() {
+            continue;
+        }
+        match read_attachment(&root, &path) {
+            Ok(attachment) => attachments.push(attachment),
+            Err(err) => app.push_system_message(format!("cannot attach {path}: {err}")),
+        }
+    }
+    attachments
+}
+
 /// Spawn a task to stream events for a session.
+///
+/// A failed stream is re-subscribed with exponential backoff; the app is told
+/// about each failure so it can show the outage and backfill once reconnected.
 fn spawn_stream(
     client: Arc<OrchestratorClient>,
     session_id: Uuid,
@@ -663,18 +1378,50 @@ fn spawn_stream(
     }
     debug!("starting event stream (session_id={})", session_id);
     let handle = tokio::spawn(async move {
-        if let Err(err) = client.stream_events(session_id, sender.clone()).await {
-            let _ = sender.send(AppEvent::StreamError(err.to_string())).await;
+        let mut attempt = 0;
+        loop {
+            let connected_at = Instant::now();
+            let err = match client.stream_events(session_id, sender.clone()).await {
+                Ok(()) => return,
+                Err(err) => err,
+            };
+            // A stream that stayed up for a while starts the backoff over.
+            if connected_at.elapsed() >= STREAM_RETRY_MAX {
+                attempt = 0;
+            }
+            let retry_in = stream_retry_delay(attempt);
+            attempt = attempt.saturating_add(1);
+            warn!(
+                "event stream failed (session_id={session_id}, retry_in_ms={}): {err}",
+                retry_in.as_millis()
+            );
+            let failed = AppEvent::StreamError {
+                session_id,
+                message: err.to_string(),
+                retry_in,
+            };
+            if sender.send(failed).await.is_err() {
+                return;
+            }
+            tokio::time::sleep(retry_in).await;
         }
     });
     *stream_handle = Some(handle);
 }
 
+/// Backoff before re-subscribing after `attempt` consecutive stream failures.
+fn stream_retry_delay(attempt: u32) -> Duration {
+    STREAM_RETRY_BASE
+        .saturating_mul(1 << attempt.min(6))
+        .min(STREAM_RETRY_MAX)
+}
+
 /// Spawn a task to send a message asynchronously.
 fn spawn_send_message(
     client: Arc<OrchestratorClient>,
     session_id: Uuid,
     prompt: String,
+    attachments: Vec<Attachment>,
     agent_id: Option<String>,
     llm_id: String,
     sender: mpsc::Sender<AppEvent>,
@@ -687,7 +1434,7 @@ fn spawn_send_message(
             session_id, prompt_len, agent_set
         );
         if let Err(err) = client
-            .send_message(session_id, prompt, agent_id, llm_id)
+            .send_message(session_id, prompt, attachments, agent_id, llm_id)
             .await
         {
             let _ = sender
@@ -712,6 +1459,15 @@ fn spawn_input_handler(sender: mpsc::Sender<AppEvent>) {
                         CrosstermEvent::Key(key) => {
                             let _ = sender.send(AppEvent::Input(key)).await;
                         }
+                        CrosstermEvent::Paste(text) => {
+                            let _ = sender.send(AppEvent::Paste(text)).await;
+                        }
+                        CrosstermEvent::FocusGained => {
+                            let _ = sender.send(AppEvent::Focus(true)).await;
+                        }
+                        CrosstermEvent::FocusLost => {
+                            let _ = sender.send(AppEvent::Focus(false)).await;
+                        }
                         CrosstermEvent::Mouse(mouse) => match mouse.kind {
                             MouseEventKind::ScrollUp => {
                                 let lines = if mouse.modifiers.contains(KeyModifiers::SHIFT) {
@@ -729,6 +1485,14 @@ fn spawn_input_handler(sender: mpsc::Sender<AppEvent>) {
                                 };
                                 let _ = sender.send(AppEvent::Scroll(lines)).await;
                             }
+                            MouseEventKind::Down(MouseButton::Left) => {
+                                let _ = sender
+                                    .send(AppEvent::Click {
+                                        column: mouse.column,
+                                        row: mouse.row,
+                                    })
+                                    .await;
+                            }
                             _ => {}
                         },
                         _ => {}
@@ -761,7 +1525,13 @@ fn setup_terminal() -> anyhow::Result<Terminal<CrosstermBackend<Stdout>>> {
     debug!("setting up terminal");
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste,
+        EnableFocusChange
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -774,7 +1544,9 @@ fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> anyhow
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        DisableFocusChange
     )?;
     terminal.show_cursor()?;
     Ok(())
@@ -3,34 +3,32 @@
 
 use anyhow::{Context, bail};
 use autoagents_core::agent::prebuilt::executor::ReActAgent;
-#[cfg(feature = "local")]
-use autoagents_llamacpp::{LlamaCppProvider, ModelSource};
 use autoagents_llm::LLMProvider;
-use autoagents_llm::backends::openai::OpenAI;
-use autoagents_llm::builder::LLMBuilder;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use log::info;
-use odyssey_rs_config::OdysseyConfig;
+use odyssey_rs_config::{ApprovalPolicyProfile, ConfigDoctor, LayeredConfigOptions, OdysseyConfig};
 use odyssey_rs_core::orchestrator::prompt::PromptProfile;
 use odyssey_rs_core::skills::SkillStore;
 use odyssey_rs_core::{
     AgentBuilder, DEFAULT_AGENT_ID, LLMEntry, OdysseyAgent, Orchestrator, PromptBuilder,
 };
 use odyssey_rs_memory::FileMemoryProvider;
-#[cfg(target_os = "linux")]
-use odyssey_rs_sandbox::BubblewrapProvider;
-#[cfg(not(target_os = "linux"))]
-use odyssey_rs_sandbox::LocalSandboxProvider;
-use odyssey_rs_sandbox::SandboxProvider;
 use odyssey_rs_tools::builtin_tool_registry;
-use odyssey_rs_tui::{EventBus, TuiConfig};
-use std::path::PathBuf;
+use odyssey_rs_tui::bootstrap::{
+    DEFAULT_LLM_ID, build_openai_llm, default_sandbox_provider, load_config, openai_api_key,
+};
+#[cfg(feature = "local")]
+use odyssey_rs_tui::bootstrap::{LOCAL_LLM_ID, LocalLlmOptions, register_local_llm};
+use odyssey_rs_tui::{EventBus, TuiConfig, acp, mcp};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Command-line options for the TUI client.
 #[derive(Parser)]
 #[command(name = "odyssey-rs-tui", version)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// Optional path to an odyssey.json5 config file
     #[arg(long)]
     config: Option<PathBuf>,
@@ -40,6 +38,24 @@ struct Cli {
     /// Default agent id
     #[arg(long)]
     agent: Option<String>,
+    /// Deny writes and run tools in a read-only sandbox
+    #[arg(long)]
+    read_only: bool,
+    /// Run without network access: hide network tools and use only the local model
+    #[arg(long)]
+    offline: bool,
+    /// Start a new session instead of resuming the last one in this workspace
+    #[arg(long)]
+    new_session: bool,
+    /// Take over the workspace lock held by another Odyssey process
+    #[arg(long)]
+    force: bool,
+    /// Approval policy profile (interactive, ci-safe, ci-trusted); overrides config
+    #[arg(long)]
+    approval_profile: Option<ApprovalPolicyProfile>,
+    /// System prompt profile of the default agent (built in or from prompt_profiles); overrides config
+    #[arg(long)]
+    prompt_profile: Option<String>,
     /// Enable the local llama.cpp provider
     #[cfg(feature = "local")]
     #[arg(long)]
@@ -90,9 +106,33 @@ struct Cli {
     local_main_gpu: Option<i32>,
 }
 
-const DEFAULT_LLM_ID: &str = "default_LLM";
-#[cfg(feature = "local")]
-const LOCAL_LLM_ID: &str = "local-llama-cpp";
+/// Commands that run instead of the interactive UI.
+#[derive(Subcommand)]
+enum Command {
+    /// Inspect configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Run one prompt without the UI and print the reply
+    Run {
+        /// Prompt sent to the agent
+        prompt: String,
+        /// Print every event as one JSON line instead of the reply
+        #[arg(long)]
+        json: bool,
+    },
+    /// Serve the Agent Client Protocol on stdin/stdout for editors such as Zed
+    Acp,
+    /// Serve the agent's tools and a `run_agent` tool as an MCP server on stdin/stdout
+    Mcp,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Check the config and show which layer set each value
+    Doctor,
+    /// Print the JSON Schema for odyssey.json5
+    Schema,
+}
 
 /// Entry point for the Odyssey TUI client.
 #[tokio::main]
@@ -109,16 +149,29 @@ async fn main() -> anyhow::Result<()> {
         cli.model.is_some(),
         cli.agent.is_some()
     );
-    let config = if let Some(path) = cli.config.as_ref() {
-        info!("loading config from path: {}", path.display());
-        OdysseyConfig::load_from_path(path).context("failed to load config")?
-    } else {
-        let cwd = std::env::current_dir().context("cwd")?;
-        info!("loading layered config from cwd: {}", cwd.display());
-        let layered = OdysseyConfig::load_layered(&cwd).context("failed to load layered config")?;
-        layered.config
-    };
+    let cwd = std::env::current_dir().context("failed to resolve current working directory")?;
+    if let Some(Command::Config(command)) = &cli.command {
+        return run_config_command(command, cli.config.as_deref(), &cwd);
+    }
+    let mut config = load_config(cli.config.as_deref(), &cwd)?;
+    if let Some(profile) = cli.approval_profile {
+        info!("approval profile set from command line (profile={profile})");
+        config.permissions.profile = profile;
+    }
+    if let Some(profile) = &cli.prompt_profile {
+        info!("prompt profile set from command line (profile={profile})");
+        config.orchestrator.prompt_profile = Some(profile.clone());
+    }
+
+    if cli.offline {
+        info!("offline mode set from command line");
+        config.offline = true;
+    }
 
+    if cli.force {
+        info!("workspace lock takeover requested from command line");
+        config.sessions.force_lock = true;
+    }
     let local_enabled = local_enabled(&cli);
     let model_name = cli
         .model
@@ -126,18 +179,15 @@ async fn main() -> anyhow::Result<()> {
         .cloned()
         .or_else(|| std::env::var("OPENAI_MODEL").ok())
         .unwrap_or_else(|| "gpt-5.2".to_string());
-    let api_key = std::env::var("OPENAI_API_KEY").ok();
     let mut openai_llm: Option<Arc<dyn LLMProvider>> = None;
-    if let Some(api_key) = api_key {
-        info!("building default LLM provider (model={})", model_name);
-        let llm: Arc<dyn LLMProvider> = LLMBuilder::<OpenAI>::new()
-            .api_key(api_key)
-            .model(model_name.clone())
-            .build()
-            .context("failed to build OpenAI LLM provider")?;
-        openai_llm = Some(llm);
+    if config.offline {
+        if !local_enabled {
+            bail!("offline mode requires the local llama.cpp provider (--local)");
+        }
+    } else if let Some(api_key) = openai_api_key(&config)? {
+        openai_llm = Some(build_openai_llm(api_key, &model_name)?);
     } else if !local_enabled {
-        bail!("OPENAI_API_KEY is required to run the TUI");
+        bail!("OPENAI_API_KEY or providers.openai.api_key_ref is required to run the TUI");
     }
 
     let tools = builtin_tool_registry();
@@ -151,26 +201,15 @@ async fn main() -> anyhow::Result<()> {
         FileMemoryProvider::new(PathBuf::from(memory_root))
             .context("failed to create memory provider")?,
     );
-    let cwd = std::env::current_dir().context("failed to resolve current working directory")?;
-    let sandbox: Option<Arc<dyn SandboxProvider>> = {
-        #[cfg(target_os = "linux")]
-        {
-            Some(Arc::new(
-                BubblewrapProvider::new().context("failed to init bubblewrap provider")?,
-            ))
-        }
-        #[cfg(not(target_os = "linux"))]
-        {
-            // Not yet supported.
-            Some(Arc::new(LocalSandboxProvider::default()))
-        }
-    };
+    let sandbox = default_sandbox_provider()?;
     let skill_store =
         Arc::new(SkillStore::load(&config.skills, &cwd).context("failed to load skills")?);
-    let system_prompt = PromptBuilder::new(memory.clone(), Some(skill_store.clone()))
-        .build_system_prompt("", &config.memory, PromptProfile::OrchestratorDefault)
+    let prompt_sections = PromptBuilder::new(memory.clone(), Some(skill_store.clone()))
+        .with_locale(&config.locale)
+        .build_sections("", &config.memory, PromptProfile::from_config(&config)?)
         .await
         .context("failed to build system prompt")?;
+    let notifications = config.ui.notifications.clone();
     let orchestrator = Arc::new(Orchestrator::new(
         config,
         tools,
@@ -179,6 +218,10 @@ async fn main() -> anyhow::Result<()> {
         Some(skill_store.clone()),
         Some(Arc::new(events.clone())),
     )?);
+    if cli.read_only {
+        info!("read-only mode enabled");
+        orchestrator.set_read_only(true);
+    }
     let mut openai_registered = false;
     if let Some(llm) = openai_llm.as_ref() {
         orchestrator.register_llm_provider(LLMEntry {
@@ -188,17 +231,45 @@ async fn main() -> anyhow::Result<()> {
         openai_registered = true;
     }
     #[cfg(feature = "local")]
-    let local_registration = if local_enabled {
-        Some(register_local_llm(&cli, &orchestrator, openai_registered).await?)
+    let local_label = if local_enabled {
+        let llm_id = if openai_registered {
+            LOCAL_LLM_ID
+        } else {
+            DEFAULT_LLM_ID
+        };
+        Some(register_local_llm(&orchestrator, &local_options(&cli), llm_id).await?)
     } else {
         None
     };
     let default_agent = AgentBuilder::new(
         DEFAULT_AGENT_ID.to_string(),
-        ReActAgent::new(OdysseyAgent::new(system_prompt, Vec::new())),
+        ReActAgent::new(OdysseyAgent::new(prompt_sections.render(), Vec::new())),
         memory,
-    );
+    )
+    .with_prompt_sections(prompt_sections);
     orchestrator.register_agent(default_agent)?;
+    if let Some(Command::Run { prompt, json }) = &cli.command {
+        return run_headless(&orchestrator, cli.agent.as_deref(), prompt, *json).await;
+    }
+    if let Some(Command::Acp) = &cli.command {
+        return acp::serve(
+            orchestrator,
+            events,
+            cli.agent.clone(),
+            tokio::io::stdin(),
+            tokio::io::stdout(),
+        )
+        .await;
+    }
+    if let Some(Command::Mcp) = &cli.command {
+        return mcp::serve(
+            orchestrator,
+            cli.agent.clone(),
+            tokio::io::stdin(),
+            tokio::io::stdout(),
+        )
+        .await;
+    }
 
     let app_model_name = {
         #[cfg(not(feature = "local"))]
@@ -213,10 +284,8 @@ async fn main() -> anyhow::Result<()> {
         {
             if openai_registered {
                 model_name.clone()
-            } else if let Some(local) = local_registration.as_ref() {
-                local.label.clone()
             } else {
-                String::default()
+                local_label.clone().unwrap_or_default()
             }
         }
     };
@@ -226,12 +295,63 @@ async fn main() -> anyhow::Result<()> {
         model_id: DEFAULT_LLM_ID.to_string(),
         agent_id: cli.agent.clone(),
         cwd: Some(cwd),
+        new_session: cli.new_session,
+        notifications,
         ..Default::default()
     };
 
     odyssey_rs_tui::run(Arc::clone(&orchestrator), events, tui_config).await
 }
 
+/// Run a `config` subcommand; the doctor fails when the config would not load.
+fn run_config_command(
+    command: &ConfigCommand,
+    config_path: Option<&Path>,
+    cwd: &Path,
+) -> anyhow::Result<()> {
+    match command {
+        ConfigCommand::Doctor => {
+            let doctor = match config_path {
+                Some(path) => ConfigDoctor::for_file(path),
+                None => ConfigDoctor::new(LayeredConfigOptions::new(cwd)),
+            };
+            let report = doctor.run();
+            print!("{report}");
+            if report.has_errors() {
+                bail!("config has errors");
+            }
+        }
+        ConfigCommand::Schema => {
+            let schema = serde_json::to_string_pretty(&OdysseyConfig::json_schema())
+                .context("failed to render config schema")?;
+            println!("{schema}");
+        }
+    }
+    Ok(())
+}
+
+/// Run one prompt in a new session; `--json` streams events to stdout as JSON lines.
+async fn run_headless(
+    orchestrator: &Orchestrator,
+    agent_id: Option<&str>,
+    prompt: &str,
+    json: bool,
+) -> anyhow::Result<()> {
+    let stream = orchestrator
+        .run_stream(agent_id, Some(DEFAULT_LLM_ID), prompt)
+        .await?;
+    info!(
+        "running headless turn (session_id={}, json={json})",
+        stream.session_id
+    );
+    if json {
+        stream.into_jsonl_writer(tokio::io::stdout()).await?;
+    } else {
+        println!("{}", stream.finish().await?.response);
+    }
+    Ok(())
+}
+
 fn local_enabled(cli: &Cli) -> bool {
     #[cfg(feature = "local")]
     {
@@ -245,102 +365,18 @@ fn local_enabled(cli: &Cli) -> bool {
 }
 
 #[cfg(feature = "local")]
-struct LocalLlmRegistration {
-    label: String,
-}
-
-#[cfg(feature = "local")]
-async fn register_local_llm(
-    cli: &Cli,
-    orchestrator: &Arc<Orchestrator>,
-    openai_registered: bool,
-) -> anyhow::Result<LocalLlmRegistration> {
-    let source = resolve_local_model_source(cli)?;
-    let label = local_label_from_source(&source);
-    info!("building llama.cpp provider (source={label})");
-    let mut builder = LlamaCppProvider::builder().model_source(source);
-    if let Some(template) = cli.local_chat_template.as_ref() {
-        builder = builder.chat_template(template.clone());
-    }
-    if let Some(n_ctx) = cli.local_n_ctx {
-        builder = builder.n_ctx(n_ctx);
-    }
-    if let Some(n_threads) = cli.local_n_threads {
-        builder = builder.n_threads(n_threads);
-    }
-
-    builder = builder.max_tokens(cli.local_max_tokens);
-
-    if let Some(temperature) = cli.local_temperature {
-        builder = builder.temperature(temperature);
-    }
-    if let Some(n_gpu_layers) = cli.local_n_gpu_layers {
-        builder = builder.n_gpu_layers(n_gpu_layers);
-    }
-    if let Some(main_gpu) = cli.local_main_gpu {
-        builder = builder.main_gpu(main_gpu);
-    }
-    let provider = builder
-        .build()
-        .await
-        .context("failed to build llama.cpp provider")?;
-    let provider: Arc<dyn LLMProvider> = Arc::new(provider);
-    let llm_id = if openai_registered {
-        LOCAL_LLM_ID.to_string()
-    } else {
-        DEFAULT_LLM_ID.to_string()
-    };
-    orchestrator.register_llm_provider(LLMEntry {
-        id: llm_id.clone(),
-        provider,
-    })?;
-    info!("registered llama.cpp provider (llm_id={llm_id})");
-    Ok(LocalLlmRegistration { label })
-}
-
-#[cfg(feature = "local")]
-fn resolve_local_model_source(cli: &Cli) -> anyhow::Result<ModelSource> {
-    let has_gguf = cli.local_gguf.is_some();
-    let has_hf = cli.local_hf_repo.is_some();
-    if has_gguf && has_hf {
-        bail!("use only one of --local-gguf or --local-hf-repo");
-    }
-    if let Some(path) = cli.local_gguf.as_ref() {
-        return Ok(ModelSource::Gguf {
-            model_path: path.display().to_string(),
-        });
-    }
-    if let Some(repo_id) = cli.local_hf_repo.as_ref() {
-        return Ok(ModelSource::HuggingFace {
-            repo_id: repo_id.clone(),
-            filename: cli.local_hf_filename.clone(),
-            mmproj_filename: cli.local_hf_mmproj.clone(),
-        });
-    }
-    Ok(ModelSource::HuggingFace {
-        repo_id: "Qwen/Qwen2.5-Coder-7B-Instruct-GGUF".to_string(),
-        filename: Some("qwen2.5-coder-7b-instruct-q8_0.gguf".to_string()),
-        mmproj_filename: None,
-    })
-}
-
-#[cfg(feature = "local")]
-fn local_label_from_source(source: &ModelSource) -> String {
-    match source {
-        ModelSource::Gguf { model_path } => {
-            let name = std::path::Path::new(model_path)
-                .file_name()
-                .and_then(|name| name.to_str())
-                .unwrap_or(model_path);
-            format!("gguf:{name}")
-        }
-        ModelSource::HuggingFace {
-            repo_id,
-            filename,
-            mmproj_filename: _,
-        } => filename
-            .as_ref()
-            .map(|file| format!("hf:{repo_id}/{file}"))
-            .unwrap_or_else(|| format!("hf:{repo_id}")),
+fn local_options(cli: &Cli) -> LocalLlmOptions {
+    LocalLlmOptions {
+        gguf: cli.local_gguf.clone(),
+        hf_repo: cli.local_hf_repo.clone(),
+        hf_filename: cli.local_hf_filename.clone(),
+        hf_mmproj: cli.local_hf_mmproj.clone(),
+        chat_template: cli.local_chat_template.clone(),
+        n_ctx: cli.local_n_ctx,
+        n_threads: cli.local_n_threads,
+        max_tokens: cli.local_max_tokens,
+        temperature: cli.local_temperature,
+        n_gpu_layers: cli.local_n_gpu_layers,
+        main_gpu: cli.local_main_gpu,
     }
 }
@@ -0,0 +1,290 @@
+//! Model Context Protocol (MCP) server mode.
+//!
+//! `odyssey-rs-tui mcp` serves MCP over stdin/stdout (newline-delimited
+//! JSON-RPC 2.0) so other agent frameworks can delegate work to Odyssey. It
+//! lists the agent's registered tools plus a `run_agent` tool that runs a whole
+//! task on an Odyssey agent. Tool calls go through [`Orchestrator::call_tool`],
+//! so permission rules, approvals, hooks, and the sandbox still apply.
+
+use crate::jsonrpc::{Incoming, RpcError, error_response, response, write_message};
+use anyhow::Context;
+use log::{debug, info, warn};
+use odyssey_rs_core::Orchestrator;
+use odyssey_rs_core::error::OdysseyCoreError;
+use odyssey_rs_protocol::{SessionId, ToolError};
+use odyssey_rs_tools::ToolSpec;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, BufReader};
+use tokio::task::JoinSet;
+
+/// Latest MCP protocol revision implemented by the server.
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+/// Revisions accepted from clients; others are answered with [`PROTOCOL_VERSION`].
+const SUPPORTED_VERSIONS: [&str; 3] = ["2024-11-05", "2025-03-26", PROTOCOL_VERSION];
+/// Name of the tool that delegates a task to an Odyssey agent.
+pub const RUN_AGENT_TOOL: &str = "run_agent";
+
+/// Serve MCP on `reader`/`writer` until the client closes its end.
+///
+/// Tools are those of `agent_id`, or of the default agent when unset. Direct
+/// tool calls share one session, so `cd` and file versions carry over between
+/// calls; each `run_agent` call gets a fresh session.
+pub async fn serve<R, W>(
+    orchestrator: Arc<Orchestrator>,
+    agent_id: Option<String>,
+    reader: R,
+    mut writer: W,
+) -> anyhow::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let session_id = orchestrator.create_session(agent_id.clone())?;
+    let server = Arc::new(McpServer {
+        orchestrator,
+        agent_id,
+        session_id,
+    });
+    let mut calls = JoinSet::new();
+    let mut lines = BufReader::new(reader).lines();
+    info!("serving MCP over stdio (session_id={session_id})");
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line.context("failed to read from client")? else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let message: Incoming = match serde_json::from_str(&line) {
+                    Ok(message) => message,
+                    Err(err) => {
+                        warn!("invalid MCP message: {err}");
+                        write_message(&mut writer, &error_response(Value::Null, RpcError::parse(err)))
+                            .await?;
+                        continue;
+                    }
+                };
+                match (message.method, message.id) {
+                    (Some(method), Some(id)) => {
+                        // Requests run concurrently; `run_agent` can take minutes.
+                        let server = server.clone();
+                        calls.spawn(async move {
+                            match server.handle_request(&method, message.params).await {
+                                Ok(result) => response(id, result),
+                                Err(err) => error_response(id, err),
+                            }
+                        });
+                    }
+                    (Some(method), None) => debug!("ignoring MCP notification (method={method})"),
+                    (None, _) => debug!("ignoring MCP response from client"),
+                }
+            }
+            Some(joined) = calls.join_next() => {
+                match joined {
+                    Ok(message) => write_message(&mut writer, &message).await?,
+                    Err(err) => warn!("MCP request task failed: {err}"),
+                }
+            }
+        }
+    }
+    info!("MCP client disconnected");
+    Ok(())
+}
+
+struct McpServer {
+    orchestrator: Arc<Orchestrator>,
+    agent_id: Option<String>,
+    /// Session shared by direct tool calls.
+    session_id: SessionId,
+}
+
+impl McpServer {
+    async fn handle_request(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        debug!("MCP request (method={method})");
+        match method {
+            "initialize" => {
+                let requested = params.get("protocolVersion").and_then(Value::as_str);
+                let version = requested
+                    .filter(|version| SUPPORTED_VERSIONS.contains(version))
+                    .unwrap_or(PROTOCOL_VERSION);
+                Ok(json!({
+                    "protocolVersion": version,
+                    "capabilities": { "tools": { "listChanged": false } },
+                    "serverInfo": { "name": "odyssey-rs", "version": env!("CARGO_PKG_VERSION") },
+                }))
+            }
+            "ping" => Ok(json!({})),
+            "tools/list" => {
+                let specs = self
+                    .orchestrator
+                    .tool_specs(self.agent_id.as_deref())
+                    .map_err(RpcError::internal)?;
+                let mut tools: Vec<Value> = specs.iter().map(tool_descriptor).collect();
+                tools.push(run_agent_descriptor());
+                Ok(json!({ "tools": tools }))
+            }
+            "tools/call" => self.call_tool(&params).await,
+            _ => Err(RpcError::method_not_found(method)),
+        }
+    }
+
+    async fn call_tool(&self, params: &Value) -> Result<Value, RpcError> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::invalid_params("tools/call requires name"))?;
+        let arguments = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        if name == RUN_AGENT_TOOL {
+            return self.run_agent(&arguments).await;
+        }
+        match self
+            .orchestrator
+            .call_tool(self.session_id, name, arguments)
+            .await
+        {
+            Ok(result) => Ok(tool_result(result)),
+            Err(OdysseyCoreError::Tool(ToolError::ToolNotFound(_))) => {
+                Err(RpcError::invalid_params(format!("unknown tool: {name}")))
+            }
+            Err(err) => {
+                info!("MCP tool call failed (tool_name={name}): {err}");
+                Ok(tool_error(&err))
+            }
+        }
+    }
+
+    async fn run_agent(&self, arguments: &Value) -> Result<Value, RpcError> {
+        let prompt = arguments
+            .get("prompt")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::invalid_params("run_agent requires prompt"))?;
+        let agent_id = arguments
+            .get("agent_id")
+            .and_then(Value::as_str)
+            .or(self.agent_id.as_deref());
+        info!(
+            "running agent for MCP client (agent_id={agent_id:?}, prompt_len={})",
+            prompt.len()
+        );
+        match self.orchestrator.run(agent_id, None, prompt).await {
+            Ok(result) => Ok(json!({
+                "content": [{ "type": "text", "text": result.response }],
+                "isError": false,
+            })),
+            Err(err) => Ok(tool_error(&err)),
+        }
+    }
+}
+
+/// MCP tool descriptor for a registered tool.
+fn tool_descriptor(spec: &ToolSpec) -> Value {
+    let mut input_schema = spec.args_schema.clone();
+    // MCP requires an object schema; tools without arguments may report `{}`.
+    if let Some(schema) = input_schema.as_object_mut() {
+        schema
+            .entry("type")
+            .or_insert_with(|| Value::String("object".to_string()));
+    } else {
+        input_schema = json!({ "type": "object" });
+    }
+    json!({
+        "name": spec.name,
+        "description": spec.description,
+        "inputSchema": input_schema,
+    })
+}
+
+fn run_agent_descriptor() -> Value {
+    json!({
+        "name": RUN_AGENT_TOOL,
+        "description": "Run a task on an Odyssey agent in a new session and return its final response.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "prompt": { "type": "string", "description": "Task for the agent." },
+                "agent_id": {
+                    "type": "string",
+                    "description": "Registered agent id; defaults to the server's agent.",
+                },
+            },
+            "required": ["prompt"],
+        },
+    })
+}
+
+/// Successful `tools/call` result; objects are also returned as structured content.
+fn tool_result(result: Value) -> Value {
+    let text = match &result {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    let mut body = json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": false,
+    });
+    if result.is_object() {
+        body["structuredContent"] = result;
+    }
+    body
+}
+
+/// Failed `tools/call` result, reported to the model rather than as a protocol error.
+fn tool_error(err: &OdysseyCoreError) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": err.to_string() }],
+        "isError": true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tool_descriptor, tool_error, tool_result};
+    use odyssey_rs_core::error::OdysseyCoreError;
+    use odyssey_rs_protocol::ToolError;
+    use odyssey_rs_tools::ToolSpec;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn tool_descriptors_always_use_object_schemas() {
+        let spec = ToolSpec {
+            name: "EnvInfo".to_string(),
+            description: "Report the environment".to_string(),
+            args_schema: json!({}),
+        };
+        assert_eq!(
+            tool_descriptor(&spec),
+            json!({
+                "name": "EnvInfo",
+                "description": "Report the environment",
+                "inputSchema": { "type": "object" },
+            })
+        );
+    }
+
+    #[test]
+    fn tool_results_carry_text_and_structured_content() {
+        let result = tool_result(json!({ "exit_code": 0 }));
+        assert_eq!(result["content"][0]["text"], "{\"exit_code\":0}");
+        assert_eq!(result["structuredContent"], json!({ "exit_code": 0 }));
+        assert_eq!(result["isError"], false);
+
+        let result = tool_result(json!("plain"));
+        assert_eq!(result["content"][0]["text"], "plain");
+        assert_eq!(result.get("structuredContent").is_none(), true);
+
+        let denied = OdysseyCoreError::Tool(ToolError::PermissionDenied("no".to_string()));
+        let result = tool_error(&denied);
+        assert_eq!(result["isError"], true);
+        assert_eq!(
+            result["content"][0]["text"],
+            "tool error: permission denied: no"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! `@path` file mentions in the input box.
+
+use crate::commands::fuzzy_score;
+use ignore::WalkBuilder;
+use std::path::Path;
+
+/// Upper bound on indexed workspace files.
+const MAX_INDEXED_FILES: usize = 20_000;
+/// Rows offered by the file picker.
+const MAX_FILE_MATCHES: usize = 50;
+/// Characters stripped from the end of a mention, e.g. `see @src/lib.rs,`.
+const TRAILING_PUNCTUATION: &[char] = &[',', ';', ':', '!', '?', ')', '"', '\''];
+
+/// Workspace files relative to `root`, skipping gitignored and hidden paths.
+pub fn index_workspace_files(root: &Path) -> Vec<String> {
+    let mut files = WalkBuilder::new(root)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()))
+        .filter_map(|entry| {
+            entry
+                .path()
+                .strip_prefix(root)
+                .ok()
+                .map(|path| path.to_string_lossy().replace('\\', "/"))
+        })
+        .take(MAX_INDEXED_FILES)
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+/// Partial mention at the end of the input, without the `@`.
+pub fn active_mention(input: &str) -> Option<&str> {
+    if input.ends_with(char::is_whitespace) {
+        return None;
+    }
+    let token = input
+        .rsplit_once(char::is_whitespace)
+        .map_or(input, |(_, token)| token);
+    token.strip_prefix('@')
+}
+
+/// Files matching a partial mention, best matches first.
+///
+/// Prefix matches on the path or file name rank first, then fuzzy matches;
+/// shorter paths win ties.
+pub fn matching_files<'a>(query: &str, files: &'a [String]) -> Vec<&'a str> {
+    let query = query.to_lowercase();
+    let mut scored = files
+        .iter()
+        .filter_map(|path| {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            let score = match (fuzzy_score(&query, path), fuzzy_score(&query, name)) {
+                (Some(path_score), Some(name_score)) => Some(path_score.min(name_score)),
+                (score, None) | (None, score) => score,
+            };
+            score.map(|score| (score, path.len(), path.as_str()))
+        })
+        .collect::<Vec<_>>();
+    scored.sort();
+    scored
+        .into_iter()
+        .take(MAX_FILE_MATCHES)
+        .map(|(_, _, path)| path)
+        .collect()
+}
+
+/// Replace the mention at the end of the input with `@path `.
+pub fn complete_mention(input: &mut String, path: &str) {
+    if let Some(partial) = active_mention(input) {
+        let keep = input.len() - partial.len();
+        input.truncate(keep);
+        input.push_str(path);
+        input.push(' ');
+    }
+}
+
+/// Paths mentioned in a message, in order and without duplicates.
+pub fn mentioned_paths(input: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    for token in input.split_whitespace() {
+        let Some(path) = token.strip_prefix('@') else {
+            continue;
+        };
+        let path = path.trim_end_matches(TRAILING_PUNCTUATION);
+        if !path.is_empty() && !paths.iter().any(|seen| seen == path) {
+            paths.push(path.to_string());
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        active_mention, complete_mention, index_workspace_files, matching_files, mentioned_paths,
+    };
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn tracks_the_mention_being_typed() {
+        assert_eq!(active_mention("explain @src/li"), Some("src/li"));
+        assert_eq!(active_mention("@"), Some(""));
+        assert_eq!(active_mention("explain @src/lib.rs "), None);
+        assert_eq!(active_mention("mail me@example.com"), None);
+
+        let mut input = "explain @li".to_string();
+        complete_mention(&mut input, "src/lib.rs");
+        assert_eq!(input, "explain @src/lib.rs ");
+    }
+
+    #[test]
+    fn ranks_file_name_prefixes_before_fuzzy_matches() {
+        let files = vec![
+            "Cargo.toml".to_string(),
+            "crates/core/src/lib.rs".to_string(),
+            "src/lib.rs".to_string(),
+            "src/main.rs".to_string(),
+        ];
+        assert_eq!(
+            matching_files("lib", &files),
+            vec!["src/lib.rs", "crates/core/src/lib.rs"]
+        );
+        assert_eq!(matching_files("smain", &files), vec!["src/main.rs"]);
+        assert_eq!(matching_files("", &files).len(), 4);
+    }
+
+    #[test]
+    fn collects_mentioned_paths() {
+        assert_eq!(
+            mentioned_paths("compare @src/a.rs, @src/b.rs and @src/a.rs (see @docs/x.md)"),
+            vec!["src/a.rs", "src/b.rs", "docs/x.md"]
+        );
+        assert_eq!(
+            mentioned_paths("email me@example.com @"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn index_skips_gitignored_files() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::create_dir_all(temp.path().join(".git")).expect("git dir");
+        std::fs::create_dir_all(temp.path().join("src")).expect("src dir");
+        std::fs::create_dir_all(temp.path().join("target")).expect("target dir");
+        std::fs::write(temp.path().join(".gitignore"), "target/\n").expect("gitignore");
+        std::fs::write(temp.path().join("src/lib.rs"), "").expect("lib");
+        std::fs::write(temp.path().join("target/out.bin"), "").expect("out");
+        assert_eq!(index_workspace_files(temp.path()), vec!["src/lib.rs"]);
+    }
+}
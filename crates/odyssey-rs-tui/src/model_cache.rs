@@ -0,0 +1,122 @@
+//! Reports for `/models cached` and `/models download`.
+
+use odyssey_rs_core::{CachedModel, ModelDownloadProgress};
+
+/// Hex digits of a SHA-256 shown in reports.
+const SHA256_PREVIEW: usize = 12;
+
+/// Describe cached GGUF models with their size and recorded hash.
+pub fn describe_cached_models(models: &[CachedModel]) -> String {
+    if models.is_empty() {
+        return "No cached models; download one with /models download <repo> <file>".to_string();
+    }
+    let mut lines = vec![format!("Cached models ({}):", models.len())];
+    for model in models {
+        let sha256 = model.sha256.as_deref().map_or_else(
+            || "unverified".to_string(),
+            |sha256| format!("sha256 {}", &sha256[..SHA256_PREVIEW.min(sha256.len())]),
+        );
+        lines.push(format!(
+            "  {}/{} · {} · {sha256}",
+            model.repo_id,
+            model.filename,
+            format_size(model.size_bytes)
+        ));
+        lines.push(format!("     {}", model.path.display()));
+    }
+    lines.join("\n")
+}
+
+/// Status line for a download in progress.
+pub fn describe_progress(progress: &ModelDownloadProgress) -> String {
+    let filename = &progress.filename;
+    let downloaded = format_size(progress.downloaded_bytes);
+    match (progress.percent(), progress.total_bytes) {
+        (Some(100), _) => format!("verifying {filename}"),
+        (Some(percent), Some(total)) => format!(
+            "downloading {filename} {percent}% ({downloaded} of {})",
+            format_size(total)
+        ),
+        _ => format!("downloading {filename} ({downloaded})"),
+    }
+}
+
+/// Whether `progress` should update the status line after `last_percent`.
+pub fn progress_changed(progress: &ModelDownloadProgress, last_percent: &mut Option<u8>) -> bool {
+    let percent = progress.percent();
+    if percent.is_some() && percent == *last_percent {
+        return false;
+    }
+    *last_percent = percent;
+    true
+}
+
+/// Size in the largest binary unit that keeps the value at least 1.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{value:.1} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe_cached_models, describe_progress, format_size, progress_changed};
+    use odyssey_rs_core::{CachedModel, ModelDownloadProgress};
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn describes_models_and_progress() {
+        let models = [CachedModel {
+            repo_id: "acme/tiny-GGUF".to_string(),
+            filename: "tiny-q4.gguf".to_string(),
+            path: PathBuf::from("/models/acme--tiny-GGUF/tiny-q4.gguf"),
+            size_bytes: 4_920_000_000,
+            sha256: Some("ab".repeat(32)),
+        }];
+        assert_eq!(
+            describe_cached_models(&models),
+            [
+                "Cached models (1):",
+                "  acme/tiny-GGUF/tiny-q4.gguf · 4.6 GiB · sha256 abababababab",
+                "     /models/acme--tiny-GGUF/tiny-q4.gguf",
+            ]
+            .join("\n")
+        );
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+
+        let mut progress = ModelDownloadProgress {
+            repo_id: "acme/tiny-GGUF".to_string(),
+            filename: "tiny-q4.gguf".to_string(),
+            downloaded_bytes: 512 * 1024,
+            total_bytes: Some(2 * 1024 * 1024),
+        };
+        assert_eq!(
+            describe_progress(&progress),
+            "downloading tiny-q4.gguf 25% (512.0 KiB of 2.0 MiB)"
+        );
+        let mut last = None;
+        assert_eq!(progress_changed(&progress, &mut last), true);
+        assert_eq!(progress_changed(&progress, &mut last), false);
+        progress.downloaded_bytes = 2 * 1024 * 1024;
+        assert_eq!(progress_changed(&progress, &mut last), true);
+        assert_eq!(describe_progress(&progress), "verifying tiny-q4.gguf");
+        progress.total_bytes = None;
+        assert_eq!(
+            describe_progress(&progress),
+            "downloading tiny-q4.gguf (2.0 MiB)"
+        );
+    }
+}
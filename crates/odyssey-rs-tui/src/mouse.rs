@@ -0,0 +1,139 @@
+//! Mouse hit-testing for the chat transcript and viewer panels.
+//!
+//! Renderers tag the lines a click can target; after layout, a [`ClickMap`]
+//! maps each screen row of the wrapped, scrolled paragraph back to its tag, and
+//! locates the rows reserved for inline images.
+
+use crate::images::{ImageKey, ImageRef};
+use ratatui::layout::{Position, Rect};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Wrap};
+use std::sync::Arc;
+
+/// Lines to render, each optionally tagged with the item a click on it targets.
+#[derive(Debug, Default)]
+pub struct TaggedLines {
+    /// Lines in render order.
+    pub lines: Vec<Line<'static>>,
+    /// Click target of each line.
+    pub targets: Vec<Option<usize>>,
+    /// Images drawn over blank rows, with the index of the first row's line.
+    pub images: Vec<(usize, ImageKey, Arc<ImageRef>)>,
+}
+
+impl TaggedLines {
+    /// Append a line that is not clickable.
+    pub fn push(&mut self, line: impl Into<Line<'static>>) {
+        self.lines.push(line.into());
+        self.targets.push(None);
+    }
+
+    /// Append a line that targets `item` when clicked.
+    pub fn push_item(&mut self, line: impl Into<Line<'static>>, item: usize) {
+        self.lines.push(line.into());
+        self.targets.push(Some(item));
+    }
+
+    /// Reserve `rows` blank lines targeting `item` for an image drawn over them.
+    pub fn push_image(&mut self, key: ImageKey, image: Arc<ImageRef>, rows: u16, item: usize) {
+        self.images.push((self.lines.len(), key, image));
+        for _ in 0..rows {
+            self.push_item(Line::default(), item);
+        }
+    }
+}
+
+impl From<Vec<Line<'static>>> for TaggedLines {
+    fn from(lines: Vec<Line<'static>>) -> Self {
+        let targets = vec![None; lines.len()];
+        Self {
+            lines,
+            targets,
+            images: Vec::new(),
+        }
+    }
+}
+
+/// Click targets of the screen rows of a wrapped, scrolled paragraph.
+#[derive(Debug, Clone, Default)]
+pub struct ClickMap {
+    area: Rect,
+    scroll: u16,
+    rows: Vec<Option<usize>>,
+    /// First wrapped row of each line.
+    starts: Vec<usize>,
+}
+
+impl ClickMap {
+    /// Map `lines` rendered into `area` with word wrapping, scrolled by `scroll` rows.
+    pub fn new(lines: &TaggedLines, area: Rect, scroll: u16) -> Self {
+        let mut rows = Vec::new();
+        let mut starts = Vec::with_capacity(lines.lines.len());
+        for (line, target) in lines.lines.iter().zip(&lines.targets) {
+            let height = Paragraph::new(line.clone())
+                .wrap(Wrap { trim: false })
+                .line_count(area.width)
+                .max(1);
+            starts.push(rows.len());
+            rows.extend(std::iter::repeat_n(*target, height));
+        }
+        Self {
+            area,
+            scroll,
+            rows,
+            starts,
+        }
+    }
+
+    /// Screen cell where `line` starts, if `height` rows from it are all visible.
+    pub fn line_origin(&self, line: usize, height: u16) -> Option<Position> {
+        let row = self
+            .starts
+            .get(line)?
+            .checked_sub(usize::from(self.scroll))?;
+        let bottom = row + usize::from(height);
+        (bottom <= usize::from(self.area.height))
+            .then(|| Position::new(self.area.x, self.area.y + row as u16))
+    }
+
+    /// Item under the cell at `column`, `row`, if any.
+    pub fn target_at(&self, column: u16, row: u16) -> Option<usize> {
+        if !self.area.contains(Position::new(column, row)) {
+            return None;
+        }
+        let index = usize::from(row - self.area.y) + usize::from(self.scroll);
+        self.rows.get(index).copied().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClickMap, TaggedLines};
+    use pretty_assertions::assert_eq;
+    use ratatui::layout::{Position, Rect};
+
+    #[test]
+    fn maps_wrapped_and_scrolled_rows_to_items() {
+        let mut lines = TaggedLines::default();
+        lines.push("header");
+        lines.push_item("first item", 0);
+        lines.push_item("second item wraps onto the next row", 1);
+        lines.push_item("third", 2);
+        let area = Rect::new(2, 5, 20, 3);
+
+        let map = ClickMap::new(&lines, area, 0);
+        assert_eq!(map.target_at(3, 5), None);
+        assert_eq!(map.target_at(3, 6), Some(0));
+        assert_eq!(map.target_at(21, 7), Some(1));
+        assert_eq!(map.target_at(1, 6), None);
+        assert_eq!(map.target_at(3, 8), None);
+
+        let map = ClickMap::new(&lines, area, 2);
+        assert_eq!(map.target_at(3, 5), Some(1));
+        assert_eq!(map.target_at(3, 6), Some(1));
+        assert_eq!(map.target_at(3, 7), Some(2));
+        assert_eq!(map.line_origin(3, 1), Some(Position::new(2, 7)));
+        assert_eq!(map.line_origin(3, 2), None);
+        assert_eq!(map.line_origin(1, 1), None);
+    }
+}
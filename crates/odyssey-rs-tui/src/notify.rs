@@ -0,0 +1,205 @@
+//! Notifications for turns that finish or need approval while the terminal is in the background.
+//!
+//! Desktop notifications need the `notifications` feature. Without it, or over
+//! SSH, the `auto` and `desktop` methods fall back to an OSC 9 escape sequence
+//! that most terminal emulators turn into a native notification.
+
+use crate::app::format_permission_request;
+use crate::clipboard::{in_tmux, is_remote_session, tmux_passthrough};
+use log::debug;
+use odyssey_rs_config::{NotificationEvent, NotificationMethod, NotificationsConfig};
+use odyssey_rs_protocol::{EventPayload, PermissionAction};
+use std::io::{self, Write};
+
+/// Longest notification body, in characters.
+const MAX_BODY_CHARS: usize = 120;
+const APP_NAME: &str = "Odyssey";
+
+/// Notification raised for a protocol event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub event: NotificationEvent,
+    pub title: &'static str,
+    pub body: String,
+}
+
+/// Sends notifications according to the `ui.notifications` config.
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    config: NotificationsConfig,
+}
+
+impl Notifier {
+    /// Create a notifier from config; it stays silent unless enabled.
+    pub fn new(config: NotificationsConfig) -> Self {
+        Self { config }
+    }
+
+    /// Notify about `payload` if it is a configured event.
+    pub fn notify(&self, payload: &EventPayload, focused: bool) {
+        if !self.config.enabled || (focused && self.config.only_when_unfocused) {
+            return;
+        }
+        let Some(notification) = notification_for(payload) else {
+            return;
+        };
+        if !self.config.events.contains(&notification.event) {
+            return;
+        }
+        debug!(
+            "sending notification (event={:?}, method={:?})",
+            notification.event, self.config.method
+        );
+        let result = match self.config.method {
+            NotificationMethod::Auto | NotificationMethod::Desktop => {
+                if desktop_available() {
+                    show_desktop(notification);
+                    Ok(())
+                } else {
+                    write_terminal(&osc9_sequence(&notification, in_tmux()))
+                }
+            }
+            NotificationMethod::Osc9 => write_terminal(&osc9_sequence(&notification, in_tmux())),
+            NotificationMethod::Bell => write_terminal("\x07"),
+        };
+        if let Err(err) = result {
+            debug!("notification write failed: {err}");
+        }
+    }
+}
+
+/// Notification for an event, or `None` when the event is not notifiable.
+pub fn notification_for(payload: &EventPayload) -> Option<Notification> {
+    match payload {
+        EventPayload::TurnCompleted { message, .. } => Some(Notification {
+            event: NotificationEvent::TurnCompleted,
+            title: "Turn finished",
+            body: summarize(message),
+        }),
+        EventPayload::Error { message, .. } => Some(Notification {
+            event: NotificationEvent::Error,
+            title: "Turn failed",
+            body: summarize(message),
+        }),
+        EventPayload::PermissionRequested {
+            action: PermissionAction::Ask,
+            request,
+            ..
+        } => Some(Notification {
+            event: NotificationEvent::ApprovalRequested,
+            title: "Approval needed",
+            body: summarize(&format_permission_request(request)),
+        }),
+        _ => None,
+    }
+}
+
+/// First non-empty line of `text`, truncated to [`MAX_BODY_CHARS`].
+fn summarize(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    if line.chars().count() <= MAX_BODY_CHARS {
+        return line.to_string();
+    }
+    let mut summary = line.chars().take(MAX_BODY_CHARS - 1).collect::<String>();
+    summary.push('…');
+    summary
+}
+
+/// OSC 9 sequence carrying the notification text, without control characters.
+fn osc9_sequence(notification: &Notification, tmux: bool) -> String {
+    let text = format!("{APP_NAME}: {}: {}", notification.title, notification.body)
+        .chars()
+        .filter(|ch| !ch.is_control())
+        .collect::<String>();
+    tmux_passthrough(format!("\x1b]9;{text}\x07"), tmux)
+}
+
+fn write_terminal(sequence: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()
+}
+
+fn desktop_available() -> bool {
+    cfg!(feature = "notifications") && !is_remote_session()
+}
+
+/// Show a desktop notification off the UI thread, since the platform call can block.
+#[cfg(feature = "notifications")]
+fn show_desktop(notification: Notification) {
+    std::thread::spawn(move || {
+        let result = notify_rust::Notification::new()
+            .appname(APP_NAME)
+            .summary(&format!("{APP_NAME}: {}", notification.title))
+            .body(&notification.body)
+            .show();
+        if let Err(err) = result {
+            debug!("desktop notification failed: {err}");
+        }
+    });
+}
+
+#[cfg(not(feature = "notifications"))]
+fn show_desktop(_notification: Notification) {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Notification, notification_for, osc9_sequence, summarize};
+    use odyssey_rs_config::NotificationEvent;
+    use odyssey_rs_protocol::{EventPayload, PermissionAction, PermissionRequest};
+    use pretty_assertions::assert_eq;
+    use uuid::Uuid;
+
+    #[test]
+    fn maps_events_to_notifications() {
+        let turn_id = Uuid::new_v4();
+        assert_eq!(
+            notification_for(&EventPayload::TurnCompleted {
+                turn_id,
+                message: "\nDone: tests pass.\nDetails follow.".to_string(),
+            }),
+            Some(Notification {
+                event: NotificationEvent::TurnCompleted,
+                title: "Turn finished",
+                body: "Done: tests pass.".to_string(),
+            })
+        );
+        let request = |action| EventPayload::PermissionRequested {
+            turn_id,
+            request_id: Uuid::new_v4(),
+            action,
+            request: PermissionRequest::Tool {
+                name: "Bash".to_string(),
+            },
+            risk: None,
+        };
+        assert_eq!(
+            notification_for(&request(PermissionAction::Ask)).map(|n| n.body),
+            Some("Tool usage requested: Bash".to_string())
+        );
+        assert_eq!(notification_for(&request(PermissionAction::Allow)), None);
+        assert_eq!(
+            notification_for(&EventPayload::TurnCancelled { turn_id }),
+            None
+        );
+    }
+
+    #[test]
+    fn builds_osc9_sequences() {
+        let notification = Notification {
+            event: NotificationEvent::Error,
+            title: "Turn failed",
+            body: "bad\x1b]0;title\x07 input".to_string(),
+        };
+        assert_eq!(
+            osc9_sequence(&notification, false),
+            "\x1b]9;Odyssey: Turn failed: bad]0;title input\x07"
+        );
+        assert!(osc9_sequence(&notification, true).starts_with("\x1bPtmux;\x1b\x1b]9;"));
+        assert_eq!(summarize(&"x".repeat(200)).chars().count(), 120);
+    }
+}
@@ -0,0 +1,120 @@
+//! Plan steps parsed from `PlanUpdate` events for the side panel.
+//!
+//! Plans arrive as free-form JSON; the usual shape is a list of
+//! `{ "step": ..., "status": ... }` objects, optionally wrapped in a `steps` or
+//! `plan` field. Plain strings are read as pending steps.
+
+use serde_json::Value;
+
+/// Progress of one plan step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanStatus {
+    /// Not started yet.
+    Pending,
+    /// Being worked on.
+    InProgress,
+    /// Done.
+    Completed,
+}
+
+impl PlanStatus {
+    fn parse(status: &str) -> Self {
+        match status {
+            "in_progress" | "in-progress" | "running" | "active" => Self::InProgress,
+            "completed" | "complete" | "done" => Self::Completed,
+            _ => Self::Pending,
+        }
+    }
+
+    /// Checkbox glyph shown before the step.
+    pub fn marker(self) -> &'static str {
+        match self {
+            Self::Pending => "[ ]",
+            Self::InProgress => "[~]",
+            Self::Completed => "[x]",
+        }
+    }
+}
+
+/// One step of the current plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanStep {
+    /// Step description.
+    pub step: String,
+    /// Step progress.
+    pub status: PlanStatus,
+}
+
+/// Read the steps of a plan update; unrecognized entries are skipped.
+pub fn parse_plan(plan: &Value) -> Vec<PlanStep> {
+    let items = match plan {
+        Value::Array(items) => items,
+        Value::Object(map) => match map.get("steps").or_else(|| map.get("plan")) {
+            Some(Value::Array(items)) => items,
+            _ => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Value::String(step) => Some(PlanStep {
+                step: step.clone(),
+                status: PlanStatus::Pending,
+            }),
+            Value::Object(map) => {
+                let step = ["step", "title", "content"]
+                    .iter()
+                    .find_map(|key| map.get(*key).and_then(Value::as_str))?;
+                let status = map
+                    .get("status")
+                    .and_then(Value::as_str)
+                    .map_or(PlanStatus::Pending, PlanStatus::parse);
+                Some(PlanStep {
+                    step: step.to_string(),
+                    status,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlanStatus, PlanStep, parse_plan};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn parses_plan_shapes() {
+        let steps = parse_plan(&json!([
+            { "step": "Read the code", "status": "completed" },
+            { "title": "Run tests", "status": "in_progress" },
+            "Write docs",
+            42,
+        ]));
+        assert_eq!(
+            steps,
+            vec![
+                PlanStep {
+                    step: "Read the code".to_string(),
+                    status: PlanStatus::Completed,
+                },
+                PlanStep {
+                    step: "Run tests".to_string(),
+                    status: PlanStatus::InProgress,
+                },
+                PlanStep {
+                    step: "Write docs".to_string(),
+                    status: PlanStatus::Pending,
+                },
+            ]
+        );
+        assert_eq!(
+            parse_plan(&json!({ "steps": [{ "step": "Ship" }] })).len(),
+            1
+        );
+        assert_eq!(parse_plan(&json!("not a plan")), Vec::new());
+    }
+}
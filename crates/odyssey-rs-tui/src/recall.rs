@@ -0,0 +1,100 @@
+//! Report for `/memory why`: which memories the next turn recalls, and why.
+
+use odyssey_rs_memory::ScoredMemoryRecord;
+
+/// Characters of each record shown in the report.
+const PREVIEW_CHARS: usize = 60;
+
+/// Describe recalled records, highest score first.
+pub fn describe_recall(query: Option<&str>, records: &[ScoredMemoryRecord]) -> String {
+    let heading = match query {
+        Some(query) => format!("Memory recall for \"{query}\""),
+        None => "Memory recall".to_string(),
+    };
+    if records.is_empty() {
+        return format!("{heading}: no memories recalled");
+    }
+    let mut ranked: Vec<&ScoredMemoryRecord> = records.iter().collect();
+    ranked.sort_by(|a, b| b.explanation.score.total_cmp(&a.explanation.score));
+    let mut lines = vec![format!("{heading} ({} records):", records.len())];
+    for (index, scored) in ranked.into_iter().enumerate() {
+        let explanation = &scored.explanation;
+        let content = scored.record.content.replace('\n', " ");
+        let mut preview: String = content.chars().take(PREVIEW_CHARS).collect();
+        if content.chars().count() > PREVIEW_CHARS {
+            preview.push('…');
+        }
+        let matched = if explanation.matched_terms.is_empty() {
+            "no terms".to_string()
+        } else {
+            explanation.matched_terms.join(", ")
+        };
+        let vector = explanation.vector_similarity.map_or_else(
+            || "n/a".to_string(),
+            |similarity| format!("{similarity:.2}"),
+        );
+        lines.push(format!(
+            "  {}. [{:.2}] {}: {preview}",
+            index + 1,
+            explanation.score,
+            scored.record.role
+        ));
+        lines.push(format!(
+            "     matched: {matched} · text {:.2} · vector {vector} · recency +{:.2}",
+            explanation.text_score, explanation.recency_boost
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::describe_recall;
+    use chrono::Utc;
+    use odyssey_rs_memory::{MemoryRecord, RecallExplanation, ScoredMemoryRecord};
+    use pretty_assertions::assert_eq;
+    use uuid::Uuid;
+
+    fn scored(content: &str, score: f32, matched_terms: &[&str]) -> ScoredMemoryRecord {
+        ScoredMemoryRecord {
+            record: MemoryRecord {
+                id: Uuid::new_v4(),
+                session_id: Uuid::new_v4(),
+                role: "user".to_string(),
+                content: content.to_string(),
+                metadata: serde_json::json!({}),
+                created_at: Utc::now(),
+            },
+            explanation: RecallExplanation {
+                score,
+                matched_terms: matched_terms.iter().map(|term| term.to_string()).collect(),
+                text_score: score - 0.05,
+                vector_similarity: None,
+                recency_boost: 0.05,
+            },
+        }
+    }
+
+    #[test]
+    fn ranks_records_and_lists_reasons() {
+        let records = [
+            scored("set up CI", 0.05, &[]),
+            scored("deploy with docker\nthen tag", 1.05, &["deploy", "docker"]),
+        ];
+        assert_eq!(
+            describe_recall(Some("docker deploy"), &records),
+            [
+                "Memory recall for \"docker deploy\" (2 records):",
+                "  1. [1.05] user: deploy with docker then tag",
+                "     matched: deploy, docker · text 1.00 · vector n/a · recency +0.05",
+                "  2. [0.05] user: set up CI",
+                "     matched: no terms · text 0.00 · vector n/a · recency +0.05",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            describe_recall(None, &[]),
+            "Memory recall: no memories recalled"
+        );
+    }
+}
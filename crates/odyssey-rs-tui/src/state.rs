@@ -0,0 +1,114 @@
+//! Per-workspace UI state restored when the TUI reopens.
+
+use crate::app::App;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+const STATE_DIR: &str = ".odyssey";
+const STATE_FILENAME: &str = "tui-state.json";
+
+/// UI state saved to `.odyssey/tui-state.json` in the workspace.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TuiState {
+    /// Session that was active on exit.
+    pub active_session: Option<Uuid>,
+    /// Agent that was active on exit.
+    pub agent_id: Option<String>,
+    /// Selected LLM provider id.
+    pub model_id: Option<String>,
+    /// Chat scroll offset, or `None` when following new output.
+    pub scroll: Option<u16>,
+    /// Whether the plan and tool call panel was shown.
+    pub side_panel: bool,
+}
+
+impl TuiState {
+    /// State file location for a workspace.
+    pub fn path(workspace: &Path) -> PathBuf {
+        workspace.join(STATE_DIR).join(STATE_FILENAME)
+    }
+
+    /// Load the saved state; a missing or unreadable file yields the default.
+    pub fn load(workspace: &Path) -> Self {
+        let path = Self::path(workspace);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(err) => {
+                warn!("failed to read tui state (path={}): {err}", path.display());
+                return Self::default();
+            }
+        };
+        serde_json::from_str(&text).unwrap_or_else(|err| {
+            warn!("invalid tui state ignored (path={}): {err}", path.display());
+            Self::default()
+        })
+    }
+
+    /// Write the state, replacing the previous file atomically.
+    pub fn save(&self, workspace: &Path) -> anyhow::Result<()> {
+        let path = Self::path(workspace);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Snapshot the parts of the app worth restoring.
+    pub fn capture(app: &App) -> Self {
+        Self {
+            active_session: app.active_session,
+            agent_id: app.active_agent.clone(),
+            model_id: (!app.model_id.is_empty()).then(|| app.model_id.clone()),
+            scroll: (!app.auto_scroll).then_some(app.scroll),
+            side_panel: app.show_side_panel,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TuiState;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+    use uuid::Uuid;
+
+    #[test]
+    fn saves_and_restores_state() {
+        let temp = tempdir().expect("tempdir");
+        let state = TuiState {
+            active_session: Some(Uuid::new_v4()),
+            agent_id: Some("odyssey-orchestrator".to_string()),
+            model_id: Some("default_LLM".to_string()),
+            scroll: Some(12),
+            side_panel: true,
+        };
+        state.save(temp.path()).expect("save");
+        assert_eq!(
+            TuiState::path(temp.path()),
+            temp.path().join(".odyssey").join("tui-state.json")
+        );
+        assert_eq!(TuiState::load(temp.path()), state);
+    }
+
+    #[test]
+    fn missing_or_invalid_state_falls_back_to_default() {
+        let temp = tempdir().expect("tempdir");
+        assert_eq!(TuiState::load(temp.path()), TuiState::default());
+        std::fs::create_dir_all(temp.path().join(".odyssey")).expect("dir");
+        std::fs::write(TuiState::path(temp.path()), "not json").expect("write");
+        assert_eq!(TuiState::load(temp.path()), TuiState::default());
+        std::fs::write(TuiState::path(temp.path()), r#"{"model_id":"local"}"#).expect("write");
+        assert_eq!(
+            TuiState::load(temp.path()).model_id.as_deref(),
+            Some("local")
+        );
+    }
+}
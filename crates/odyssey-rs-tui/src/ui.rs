@@ -1,6 +1,12 @@
 //! Rendering routines for the Odyssey TUI.
 
-use crate::app::{App, ViewerKind};
+use crate::app::{App, ViewerKind, format_permission_request};
+use crate::commands::help_sections;
+use crate::images::{IMAGE_MAX_COLS, IMAGE_ROWS, Placement};
+use crate::mouse::{ClickMap, TaggedLines};
+use crate::plan::PlanStatus;
+use odyssey_rs_config::ToolPolicy;
+use odyssey_rs_protocol::ToolRisk;
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
@@ -8,6 +14,8 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{
     Block, BorderType, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap,
 };
+use std::sync::Arc;
+use std::time::Instant;
 // ── Theme colors from theme.json (dark mode) ──────────────────────────
 
 const PRIMARY: Color = Color::Rgb(236, 91, 43); // #EC5B2B
@@ -18,8 +26,10 @@ const BORDER: Color = Color::Rgb(60, 60, 60); // #3c3c3c (borderSubtle)
 const BORDER_ACTIVE: Color = Color::Rgb(238, 121, 72); // #EE7948
 const YELLOW: Color = Color::Rgb(229, 192, 123); // #e5c07b
 
-const SLASH_PALETTE_HEIGHT: u16 = 12;
+const SLASH_PALETTE_HEIGHT: u16 = 20;
 const HEADER_HEIGHT: u16 = 9; // 7 inner lines + 2 border lines
+/// Narrowest terminal that shows the side panel; narrower ones keep the full-width chat.
+const SPLIT_MIN_WIDTH: u16 = 120;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -31,6 +41,7 @@ const HERO_ART: [&str; 2] = [
 /// Draw the entire TUI frame.
 pub fn draw(frame: &mut Frame<'_>, app: &mut App) {
     let area = frame.area();
+    app.graphics.begin_frame();
 
     if app.viewer.is_some() {
         let root = Layout::default()
@@ -59,16 +70,28 @@ pub fn draw(frame: &mut Frame<'_>, app: &mut App) {
             .split(area);
 
         draw_header(frame, app, root[0]);
-        draw_chat(frame, app, root[1]);
+        let chat_area = if app.show_side_panel && root[1].width >= SPLIT_MIN_WIDTH {
+            let panes = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+                .split(root[1]);
+            draw_side_panel(frame, app, panes[1]);
+            panes[0]
+        } else {
+            root[1]
+        };
+        draw_chat(frame, app, chat_area);
         if app.show_slash_commands {
-            draw_slash_palette(frame, root[1]);
+            draw_slash_palette(frame, app, chat_area);
+        } else if app.show_file_picker {
+            draw_file_picker(frame, app, chat_area);
         }
         draw_input(frame, app, root[2]);
         draw_status_bar(frame, app, root[3]);
     }
 }
 
-/// Draw the header with ASCII art centered vertically, info below, CPU gauge on right.
+/// Draw the header with ASCII art centered vertically, info below, activity metrics on right.
 fn draw_header(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let session = app
         .active_session
@@ -82,11 +105,14 @@ fn draw_header(frame: &mut Frame<'_>, app: &App, area: Rect) {
         .clone()
         .unwrap_or_else(|| "none".to_string());
 
-    // Outer split: [left content] [right CPU widget]
-    let cpu_widget_width: u16 = 22;
+    // Outer split: [left content] [right activity widget]
+    let activity_widget_width: u16 = 24;
     let cols = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(0), Constraint::Length(cpu_widget_width)])
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(activity_widget_width),
+        ])
         .split(area);
 
     let left_block = Block::default()
@@ -140,6 +166,22 @@ fn draw_header(frame: &mut Frame<'_>, app: &App, area: Rect) {
         Span::styled(agent, value_style),
     ];
 
+    if let Some(retry_at) = app.stream_retry_at {
+        let retry_in = retry_at.saturating_duration_since(Instant::now());
+        let label = match retry_in.as_secs() {
+            0 => " disconnected · reconnecting ".to_string(),
+            secs => format!(" disconnected · retry in {secs}s "),
+        };
+        session_spans.push(Span::styled("  ", Style::default()));
+        session_spans.push(Span::styled(
+            label,
+            Style::default()
+                .fg(Color::Rgb(10, 10, 10))
+                .bg(Color::Rgb(255, 110, 110))
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
     if let Some(permission) = app.pending_permissions.front() {
         session_spans.push(Span::styled("  ", Style::default()));
         session_spans.push(Span::styled(
@@ -173,26 +215,18 @@ fn draw_header(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let text = Paragraph::new(lines);
     frame.render_widget(text, centered_area);
 
-    // ── Right: CPU usage widget ──
-    draw_cpu_widget(frame, app, cols[1]);
+    // ── Right: activity widget ──
+    draw_activity_widget(frame, app, cols[1]);
 }
 
-/// Draw a compact CPU usage widget with a vertical bar gauge.
-fn draw_cpu_widget(frame: &mut Frame<'_>, app: &App, area: Rect) {
-    let cpu = app.cpu_usage;
-    let cpu_color = if cpu < 50.0 {
-        Color::Rgb(120, 220, 140) // green
-    } else if cpu < 80.0 {
-        YELLOW
-    } else {
-        Color::Rgb(255, 110, 110) // red
-    };
-
+/// Draw the activity widget: turn elapsed time, streamed tokens, running tool,
+/// queue depth, and a compact host CPU reading.
+fn draw_activity_widget(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let block = Block::default()
         .borders(Borders::TOP | Borders::RIGHT | Borders::BOTTOM)
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(BORDER))
-        .title(Span::styled(" CPU ", Style::default().fg(TEXT_MUTED)));
+        .title(Span::styled(" Activity ", Style::default().fg(TEXT_MUTED)));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
@@ -201,45 +235,96 @@ fn draw_cpu_widget(frame: &mut Frame<'_>, app: &App, area: Rect) {
         return;
     }
 
-    // Bar gauge: fill proportional to CPU usage
-    let bar_width = inner.width.saturating_sub(2); // padding
-    let filled = ((cpu / 100.0) * bar_width as f32).round() as u16;
-    let empty = bar_width.saturating_sub(filled);
+    let activity = &app.activity;
+    let label_style = Style::default().fg(TEXT_MUTED);
+    let value_style = Style::default().fg(TEXT);
+    let metric = |label: &'static str, value: String, style: Style| {
+        Line::from(vec![
+            Span::styled(format!(" {label:<7}"), label_style),
+            Span::styled(value, style),
+        ])
+    };
 
-    let mut lines: Vec<Line<'_>> = Vec::new();
+    let turn_style = if activity.is_running() {
+        Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD)
+    } else {
+        value_style
+    };
+    let turn = match activity.elapsed() {
+        Some(elapsed) if activity.is_running() => format_elapsed(elapsed.as_secs()),
+        Some(elapsed) => format!("{} idle", format_elapsed(elapsed.as_secs())),
+        None => "idle".to_string(),
+    };
+    let tool_width = inner.width.saturating_sub(8) as usize;
+    let tool = activity
+        .active_tool()
+        .map(|name| name.chars().take(tool_width).collect::<String>())
+        .unwrap_or_else(|| "-".to_string());
+    let queued = activity.run.queued_turns;
+    let queue_style = if queued > 0 {
+        Style::default().fg(YELLOW)
+    } else {
+        value_style
+    };
 
-    // Percentage label
-    lines.push(Line::from(Span::styled(
-        format!(" {cpu:5.1}%"),
-        Style::default().fg(cpu_color).add_modifier(Modifier::BOLD),
-    )));
+    let mut lines = vec![
+        metric("turn", turn, turn_style),
+        metric(
+            "tokens",
+            format_count(activity.streamed_tokens()),
+            value_style,
+        ),
+        metric("tool", tool, value_style),
+        metric(
+            "queue",
+            format!("{queued} ({} run)", activity.run.running_turns),
+            queue_style,
+        ),
+        metric(
+            "cpu",
+            format!("{:.1}%", app.cpu_usage),
+            Style::default().fg(load_color(app.cpu_usage, 50.0, 80.0)),
+        ),
+    ];
+    if let Some(temp) = app.gpu_temp {
+        lines.push(metric(
+            "gpu",
+            format!("{temp:.1}°C"),
+            Style::default().fg(load_color(temp, 60.0, 80.0)),
+        ));
+    }
 
-    // Bar
-    let bar_filled = "█".repeat(filled as usize);
-    let bar_empty = "░".repeat(empty as usize);
-    lines.push(Line::from(vec![
-        Span::styled(" ", Style::default()),
-        Span::styled(bar_filled, Style::default().fg(cpu_color)),
-        Span::styled(bar_empty, Style::default().fg(BORDER)),
-    ]));
+    frame.render_widget(Paragraph::new(lines), inner);
+}
 
-    if let Some(temp) = app.gpu_temp {
-        let gpu_color = if temp < 60.0 {
-            Color::Rgb(120, 220, 140)
-        } else if temp < 80.0 {
-            YELLOW
-        } else {
-            Color::Rgb(255, 110, 110)
-        };
-        lines.push(Line::from(""));
-        lines.push(Line::from(Span::styled(
-            format!(" GPU {temp:5.1}°C"),
-            Style::default().fg(gpu_color).add_modifier(Modifier::BOLD),
-        )));
+/// Green below `warn`, yellow below `high`, red otherwise.
+fn load_color(value: f32, warn: f32, high: f32) -> Color {
+    if value < warn {
+        Color::Rgb(120, 220, 140) // green
+    } else if value < high {
+        YELLOW
+    } else {
+        Color::Rgb(255, 110, 110) // red
+    }
+}
+
+/// Format seconds as `m:ss`, or `h:mm:ss` past an hour.
+fn format_elapsed(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
     }
+}
 
-    let gauge = Paragraph::new(lines);
-    frame.render_widget(gauge, inner);
+/// Format a count compactly, e.g. `950`, `1.2k`, `3.4M`.
+fn format_count(count: u64) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", count as f64 / 1_000.0),
+        _ => format!("{:.1}M", count as f64 / 1_000_000.0),
+    }
 }
 
 /// Draw the chat transcript with border and scrollbar.
@@ -258,7 +343,7 @@ fn draw_chat(frame: &mut Frame<'_>, app: &mut App, area: Rect) {
 
     // Use ratatui's own line_count to get the exact wrapped line total,
     // avoiding any mismatch with a hand-written wrap estimator.
-    let total_lines = Paragraph::new(lines.clone())
+    let total_lines = Paragraph::new(lines.lines.clone())
         .wrap(Wrap { trim: false })
         .line_count(content_width)
         .max(1);
@@ -271,8 +356,25 @@ fn draw_chat(frame: &mut Frame<'_>, app: &mut App, area: Rect) {
         width: inner.width.saturating_sub(1),
         ..inner
     };
+    app.chat_clicks = ClickMap::new(&lines, chat_inner, scroll);
+    // Popups cover the transcript, so images wait until they close.
+    if !app.show_slash_commands && !app.show_file_picker {
+        let cols = chat_inner.width.saturating_sub(4).min(IMAGE_MAX_COLS);
+        for (line, key, image) in &lines.images {
+            if let Some(origin) = app.chat_clicks.line_origin(*line, IMAGE_ROWS) {
+                app.graphics.queue(Placement {
+                    key: *key,
+                    image: Arc::clone(image),
+                    x: origin.x + 3,
+                    y: origin.y,
+                    cols,
+                    rows: IMAGE_ROWS,
+                });
+            }
+        }
+    }
 
-    let chat = Paragraph::new(lines)
+    let chat = Paragraph::new(lines.lines)
         .wrap(Wrap { trim: false })
         .scroll((scroll, 0));
 
@@ -301,6 +403,84 @@ fn draw_chat(frame: &mut Frame<'_>, app: &mut App, area: Rect) {
     }
 }
 
+/// Draw the side panel: the current plan above the most recent tool calls.
+fn draw_side_panel(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let panes = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let block = |title: &'static str| {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(BORDER))
+            .title(Span::styled(title, Style::default().fg(TEXT_MUTED)))
+    };
+
+    let mut plan_lines = Vec::new();
+    if app.plan.is_empty() {
+        plan_lines.push(Line::from(Span::styled(
+            " No plan yet.",
+            Style::default().fg(TEXT_MUTED),
+        )));
+    }
+    for step in &app.plan {
+        let style = match step.status {
+            PlanStatus::Pending => Style::default().fg(TEXT),
+            PlanStatus::InProgress => Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            PlanStatus::Completed => Style::default().fg(TEXT_MUTED),
+        };
+        plan_lines.push(Line::from(vec![
+            Span::styled(format!(" {} ", step.status.marker()), style),
+            Span::styled(step.step.clone(), style),
+        ]));
+    }
+    frame.render_widget(
+        Paragraph::new(plan_lines)
+            .wrap(Wrap { trim: false })
+            .block(block(" Plan ")),
+        panes[0],
+    );
+
+    // Newest first, as many as fit.
+    let rows = usize::from(panes[1].height.saturating_sub(2));
+    let mut tool_lines = app
+        .messages
+        .iter()
+        .rev()
+        .filter_map(|entry| Some((entry.tool.as_ref()?, entry.color)))
+        .take(rows)
+        .map(|(tool, color)| {
+            let status = match tool.result {
+                None => "running",
+                Some((_, true)) => "ok",
+                Some((_, false)) => "error",
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!(" {status:<7} "),
+                    Style::default().fg(color.unwrap_or(TEXT_MUTED)),
+                ),
+                Span::styled(tool.name.clone(), Style::default().fg(TEXT)),
+                Span::styled(
+                    format!(" {}", tool.arguments),
+                    Style::default().fg(TEXT_MUTED),
+                ),
+            ])
+        })
+        .collect::<Vec<_>>();
+    if tool_lines.is_empty() {
+        tool_lines.push(Line::from(Span::styled(
+            " No tool calls yet.",
+            Style::default().fg(TEXT_MUTED),
+        )));
+    }
+    frame.render_widget(
+        Paragraph::new(tool_lines).block(block(" Tool calls ")),
+        panes[1],
+    );
+}
+
 /// Draw the input box with border and cursor.
 fn draw_input(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let is_active = app.pending_permissions.is_empty();
@@ -311,7 +491,7 @@ fn draw_input(frame: &mut Frame<'_>, app: &App, area: Rect) {
         " Input "
     };
 
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(border_color))
@@ -319,10 +499,21 @@ fn draw_input(frame: &mut Frame<'_>, app: &App, area: Rect) {
             title,
             Style::default().fg(if is_active { SECONDARY } else { PRIMARY }),
         ));
+    if let Some(badge) = app.next_turn.badge() {
+        block = block.title(
+            Line::from(Span::styled(
+                format!(" {badge} "),
+                Style::default().fg(YELLOW),
+            ))
+            .right_aligned(),
+        );
+    }
 
     let inner = block.inner(area);
 
     let prompt_style = Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD);
+    // Pasted line breaks are kept in the message but shown inline.
+    let display_input = app.input.replace('\n', "↵");
     let input_text = if app.input.is_empty() && is_active {
         Line::from(vec![
             Span::styled(" ", prompt_style),
@@ -331,7 +522,7 @@ fn draw_input(frame: &mut Frame<'_>, app: &App, area: Rect) {
     } else {
         Line::from(vec![
             Span::styled(" ", prompt_style),
-            Span::styled(app.input.as_str(), Style::default().fg(TEXT)),
+            Span::styled(display_input.as_str(), Style::default().fg(TEXT)),
         ])
     };
 
@@ -341,33 +532,64 @@ fn draw_input(frame: &mut Frame<'_>, app: &App, area: Rect) {
 
     // Position cursor after input text
     if is_active {
-        frame.set_cursor_position((inner.x + 2 + app.input.len() as u16, inner.y));
+        let input_width = display_input.chars().count() as u16;
+        frame.set_cursor_position((inner.x + 2 + input_width, inner.y));
     }
 }
 
 /// Draw the status bar at the bottom.
 fn draw_status_bar(frame: &mut Frame<'_>, app: &App, area: Rect) {
-    let status_color = match app.status.as_str() {
-        "running" => PRIMARY,
-        "idle" => TEXT_MUTED,
-        _ => YELLOW,
+    // While a turn runs, a spinner and its current step replace the status text.
+    let activity = &app.activity;
+    let (right_text, status_color) = match activity.step() {
+        Some(step) => {
+            let steps = match activity.steps() {
+                0 => String::new(),
+                steps => format!(" · step {steps}"),
+            };
+            let label = step.label();
+            (format!(" {} {label}{steps} ", activity.spinner()), PRIMARY)
+        }
+        None if app.status == "running" => (format!(" {} waiting ", activity.spinner()), PRIMARY),
+        None if app.status == "idle" => (format!(" {} ", app.status), TEXT_MUTED),
+        None => (format!(" {} ", app.status), YELLOW),
     };
 
-    let shortcuts = vec![
-        Span::styled(" Ctrl+C", Style::default().fg(TEXT_MUTED)),
-        Span::styled(" quit", Style::default().fg(BORDER)),
-        Span::styled("  Ctrl+N", Style::default().fg(TEXT_MUTED)),
-        Span::styled(" new", Style::default().fg(BORDER)),
-        Span::styled("  /", Style::default().fg(TEXT_MUTED)),
-        Span::styled(" commands", Style::default().fg(BORDER)),
-        Span::styled("  PgUp/PgDn", Style::default().fg(TEXT_MUTED)),
-        Span::styled(" scroll", Style::default().fg(BORDER)),
-    ];
+    // Mouse capture is off in select mode, so point the user back out of it.
+    let shortcuts = if app.select_mode {
+        vec![
+            Span::styled(" select mode", Style::default().fg(YELLOW)),
+            Span::styled(" drag to select text", Style::default().fg(TEXT_MUTED)),
+            Span::styled("  F2/Esc", Style::default().fg(TEXT_MUTED)),
+            Span::styled(" resume mouse", Style::default().fg(BORDER)),
+        ]
+    } else {
+        vec![
+            Span::styled(" Ctrl+C", Style::default().fg(TEXT_MUTED)),
+            Span::styled(" quit", Style::default().fg(BORDER)),
+            Span::styled("  Ctrl+N", Style::default().fg(TEXT_MUTED)),
+            Span::styled(" new", Style::default().fg(BORDER)),
+            Span::styled("  Ctrl+E", Style::default().fg(TEXT_MUTED)),
+            Span::styled(" edit", Style::default().fg(BORDER)),
+            Span::styled("  /", Style::default().fg(TEXT_MUTED)),
+            Span::styled(" commands", Style::default().fg(BORDER)),
+            Span::styled("  PgUp/PgDn", Style::default().fg(TEXT_MUTED)),
+            Span::styled(" scroll", Style::default().fg(BORDER)),
+            Span::styled("  Ctrl+Y", Style::default().fg(TEXT_MUTED)),
+            Span::styled(" copy", Style::default().fg(BORDER)),
+            Span::styled("  ?", Style::default().fg(TEXT_MUTED)),
+            Span::styled(" help", Style::default().fg(BORDER)),
+        ]
+    };
 
-    let right_text = format!(" {} ", app.status);
+    // Streaming throughput of the running or last turn, kept subtle.
+    let stream = activity
+        .stream_label()
+        .map(|label| format!(" {label} "))
+        .unwrap_or_default();
 
     // Calculate how much space the right side needs
-    let right_len = right_text.len() as u16;
+    let right_len = (stream.chars().count() + right_text.chars().count()) as u16;
     let left_area = Rect {
         width: area.width.saturating_sub(right_len),
         ..area
@@ -379,57 +601,55 @@ fn draw_status_bar(frame: &mut Frame<'_>, app: &App, area: Rect) {
     };
 
     let left = Paragraph::new(Line::from(shortcuts));
-    let right = Paragraph::new(Line::from(Span::styled(
-        right_text,
-        Style::default().fg(status_color),
-    )));
+    let right = Paragraph::new(Line::from(vec![
+        Span::styled(stream, Style::default().fg(BORDER)),
+        Span::styled(right_text, Style::default().fg(status_color)),
+    ]));
 
     frame.render_widget(left, left_area);
     frame.render_widget(right, right_area);
 }
 
-fn draw_slash_palette(frame: &mut Frame<'_>, area: Rect) {
+fn draw_slash_palette(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let cmd_style = Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD);
     let desc_style = Style::default().fg(TEXT_MUTED);
+    let selected_style = Style::default().bg(Color::Rgb(40, 40, 40));
     let hint_style = Style::default()
         .fg(TEXT_MUTED)
         .add_modifier(Modifier::ITALIC);
 
-    let lines = vec![
-        Line::from(vec![]),
-        Line::from(vec![
-            Span::styled("  /new", cmd_style),
-            Span::styled("            ", desc_style),
-            Span::styled("Create a new session", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  /sessions", cmd_style),
-            Span::styled("       ", desc_style),
-            Span::styled("List all sessions", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  /skills", cmd_style),
-            Span::styled("         ", desc_style),
-            Span::styled("List available skills", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  /models", cmd_style),
-            Span::styled("        ", desc_style),
-            Span::styled("List available models", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  /model <id>", cmd_style),
-            Span::styled("    ", desc_style),
-            Span::styled("Select model by id", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  /join <id>", cmd_style),
-            Span::styled("      ", desc_style),
-            Span::styled("Join a session by ID", desc_style),
-        ]),
-        Line::from(vec![]),
-        Line::from(Span::styled("  Esc to close", hint_style)),
-    ];
+    let entries = app.slash_palette_entries();
+    // Blank line, rows, blank line, hint and border.
+    let max_rows = usize::from(SLASH_PALETTE_HEIGHT.min(area.height).saturating_sub(5)).max(1);
+    let selected = app
+        .selected_slash_command
+        .min(entries.len().saturating_sub(1));
+    let first = selected.saturating_sub(max_rows - 1);
+
+    let mut lines = vec![Line::from(vec![])];
+    if entries.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No matching commands",
+            desc_style,
+        )));
+    }
+    for (idx, entry) in entries.iter().enumerate().skip(first).take(max_rows) {
+        let usage = format!("  {}", entry.usage);
+        let line = Line::from(vec![
+            Span::styled(format!("{usage:<18}"), cmd_style),
+            Span::styled(entry.description.clone(), desc_style),
+        ]);
+        lines.push(if idx == selected {
+            line.style(selected_style)
+        } else {
+            line
+        });
+    }
+    lines.push(Line::from(vec![]));
+    lines.push(Line::from(Span::styled(
+        "  ↑/↓ select · Tab complete · Esc close",
+        hint_style,
+    )));
 
     let height = SLASH_PALETTE_HEIGHT
         .min(area.height)
@@ -456,14 +676,76 @@ fn draw_slash_palette(frame: &mut Frame<'_>, area: Rect) {
     frame.render_widget(palette, palette_area);
 }
 
+/// Draw the `@` file picker above the input.
+fn draw_file_picker(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let path_style = Style::default().fg(TEXT);
+    let muted_style = Style::default().fg(TEXT_MUTED);
+    let selected_style = Style::default().bg(Color::Rgb(40, 40, 40));
+    let hint_style = Style::default()
+        .fg(TEXT_MUTED)
+        .add_modifier(Modifier::ITALIC);
+
+    let entries = app.file_picker_entries();
+    // Blank line, rows, blank line, hint and border.
+    let max_rows = usize::from(SLASH_PALETTE_HEIGHT.min(area.height).saturating_sub(5)).max(1);
+    let selected = app.selected_file.min(entries.len().saturating_sub(1));
+    let first = selected.saturating_sub(max_rows - 1);
+
+    let mut lines = vec![Line::from(vec![])];
+    if entries.is_empty() {
+        lines.push(Line::from(Span::styled("  No matching files", muted_style)));
+    }
+    for (idx, path) in entries.iter().enumerate().skip(first).take(max_rows) {
+        let line = Line::from(Span::styled(format!("  {path}"), path_style));
+        lines.push(if idx == selected {
+            line.style(selected_style)
+        } else {
+            line
+        });
+    }
+    lines.push(Line::from(vec![]));
+    lines.push(Line::from(Span::styled(
+        "  ↑/↓ select · Tab insert · Esc close",
+        hint_style,
+    )));
+
+    let height = SLASH_PALETTE_HEIGHT
+        .min(area.height)
+        .min(lines.len() as u16 + 2); // +2 for border
+
+    let picker_area = Rect {
+        x: area.x + 1,
+        y: area.y + area.height.saturating_sub(height),
+        width: area.width.saturating_sub(2).min(70),
+        height,
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(PRIMARY))
+        .title(Span::styled(
+            " Files ",
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        ))
+        .style(Style::default().bg(Color::Rgb(20, 20, 20)));
+
+    let picker = Paragraph::new(lines).block(block);
+    frame.render_widget(picker, picker_area);
+}
+
 fn draw_viewer(frame: &mut Frame<'_>, app: &mut App, area: Rect) {
     let Some(kind) = app.viewer else {
         return;
     };
     let (title, lines) = match kind {
         ViewerKind::Sessions => (" Sessions ", render_session_lines(app)),
-        ViewerKind::Skills => (" Skills ", render_skill_lines(app)),
+        ViewerKind::Skills => (" Skills ", render_skill_lines(app).into()),
         ViewerKind::Models => (" Models ", render_model_lines(app)),
+        ViewerKind::Agents => (" Agents ", render_agent_lines(app)),
+        ViewerKind::Approvals => (" Pending approvals ", render_approval_lines(app)),
+        ViewerKind::Prompt => (" System prompt ", render_prompt_lines(app).into()),
+        ViewerKind::Help => (" Help ", render_help_lines(app).into()),
     };
 
     let block = Block::default()
@@ -479,7 +761,7 @@ fn draw_viewer(frame: &mut Frame<'_>, app: &mut App, area: Rect) {
     let content_width = inner.width.saturating_sub(1);
     let content_height = inner.height as usize;
 
-    let total_lines = Paragraph::new(lines.clone())
+    let total_lines = Paragraph::new(lines.lines.clone())
         .wrap(Wrap { trim: false })
         .line_count(content_width)
         .max(1);
@@ -491,8 +773,9 @@ fn draw_viewer(frame: &mut Frame<'_>, app: &mut App, area: Rect) {
         width: inner.width.saturating_sub(1),
         ..inner
     };
+    app.viewer_clicks = ClickMap::new(&lines, viewer_inner, scroll);
 
-    let viewer = Paragraph::new(lines)
+    let viewer = Paragraph::new(lines.lines)
         .wrap(Wrap { trim: false })
         .scroll((scroll, 0));
 
@@ -524,7 +807,14 @@ fn draw_viewer_footer(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let hint = match app.viewer {
         Some(ViewerKind::Sessions) => "Up/Down to navigate  Enter to select  Esc to close",
         Some(ViewerKind::Models) => "Up/Down to navigate  Enter to select  Esc to close",
-        _ => "Esc to close",
+        Some(ViewerKind::Agents) => "Up/Down to navigate  Enter to switch agent  Esc to close",
+        Some(ViewerKind::Approvals) => {
+            "Up/Down to navigate  y allow once  a allow always  n deny  Esc to close"
+        }
+        Some(ViewerKind::Skills | ViewerKind::Prompt | ViewerKind::Help) => {
+            "Up/Down to scroll  Esc to close"
+        }
+        None => "Esc to close",
     };
 
     let block = Block::default()
@@ -542,8 +832,8 @@ fn draw_viewer_footer(frame: &mut Frame<'_>, app: &App, area: Rect) {
     frame.render_widget(paragraph, area);
 }
 
-fn render_session_lines(app: &App) -> Vec<Line<'static>> {
-    let mut lines = Vec::new();
+fn render_session_lines(app: &App) -> TaggedLines {
+    let mut lines = TaggedLines::default();
 
     if app.sessions.is_empty() {
         lines.push(Line::from(Span::styled(
@@ -603,16 +893,19 @@ fn render_session_lines(app: &App) -> Vec<Line<'static>> {
             )
         };
 
-        lines.push(Line::from(vec![
-            prefix,
-            Span::styled(format!("{:<12}", id_str), style),
-            Span::styled(format!("{:<15}", session.agent_id), style),
-            Span::styled(
-                format!("{:<12}", format!("{} msgs", session.message_count)),
-                style,
-            ),
-            Span::styled(session.created_at.format("%Y-%m-%d").to_string(), style),
-        ]));
+        lines.push_item(
+            Line::from(vec![
+                prefix,
+                Span::styled(format!("{:<12}", id_str), style),
+                Span::styled(format!("{:<15}", session.agent_id), style),
+                Span::styled(
+                    format!("{:<12}", format!("{} msgs", session.message_count)),
+                    style,
+                ),
+                Span::styled(session.created_at.format("%Y-%m-%d").to_string(), style),
+            ]),
+            idx,
+        );
     }
     lines
 }
@@ -653,8 +946,61 @@ fn render_skill_lines(app: &App) -> Vec<Line<'static>> {
     lines
 }
 
-fn render_model_lines(app: &App) -> Vec<Line<'static>> {
+fn render_prompt_lines(app: &App) -> Vec<Line<'static>> {
     let mut lines = Vec::new();
+    let Some(prompt) = &app.effective_prompt else {
+        lines.push(Line::from(Span::styled(
+            " No system prompt loaded.",
+            Style::default().fg(TEXT_MUTED),
+        )));
+        return lines;
+    };
+
+    let push_text = |lines: &mut Vec<Line<'static>>, text: &str| {
+        for line in text.lines() {
+            lines.push(Line::from(Span::styled(
+                format!("   {line}"),
+                Style::default().fg(TEXT),
+            )));
+        }
+    };
+    lines.push(Line::from(Span::styled(
+        format!(" Agent {}", prompt.agent_id),
+        Style::default().fg(TEXT_MUTED),
+    )));
+    lines.push(Line::from(Span::raw("")));
+    if prompt.sections.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " prompt (registered as plain text, no sections)",
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        )));
+        push_text(&mut lines, &prompt.text);
+        return lines;
+    }
+    for section in &prompt.sections {
+        let cache = if section.cacheable {
+            "cacheable"
+        } else {
+            "dynamic"
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(" {}", section.kind.name()),
+                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  {cache}, ~{} tokens", section.tokens),
+                Style::default().fg(TEXT_MUTED),
+            ),
+        ]));
+        push_text(&mut lines, &section.content);
+        lines.push(Line::from(Span::raw("")));
+    }
+    lines
+}
+
+fn render_model_lines(app: &App) -> TaggedLines {
+    let mut lines = TaggedLines::default();
 
     if app.models.is_empty() {
         lines.push(Line::from(Span::styled(
@@ -680,12 +1026,196 @@ fn render_model_lines(app: &App) -> Vec<Line<'static>> {
         let marker = if is_selected { ">" } else { " " };
         let active_tag = if is_active { " (active)" } else { "" };
 
-        lines.push(Line::from(vec![
-            Span::styled(format!(" {marker} "), line_style),
-            Span::styled(model_id.clone(), line_style),
-            Span::styled(active_tag, active_style),
-        ]));
+        lines.push_item(
+            Line::from(vec![
+                Span::styled(format!(" {marker} "), line_style),
+                Span::styled(model_id.clone(), line_style),
+                Span::styled(active_tag, active_style),
+            ]),
+            idx,
+        );
+    }
+
+    lines
+}
+
+fn render_agent_lines(app: &App) -> TaggedLines {
+    let mut lines = TaggedLines::default();
+
+    if app.agents.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " No agents registered.",
+            Style::default().fg(TEXT_MUTED),
+        )));
+        return lines;
+    }
+
+    for (idx, agent) in app.agents.iter().enumerate() {
+        let is_selected = idx == app.selected_agent;
+        let is_active = app.active_agent.as_deref() == Some(agent.id.as_str());
+        let line_style = if is_selected {
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(TEXT)
+        };
+        let tag_style = if is_selected {
+            Style::default().fg(PRIMARY)
+        } else {
+            Style::default().fg(SECONDARY)
+        };
+        let marker = if is_selected { ">" } else { " " };
+        let mut tags = Vec::new();
+        if agent.is_default {
+            tags.push("default");
+        }
+        if is_active {
+            tags.push("active");
+        }
+        let tags = if tags.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", tags.join(", "))
+        };
+
+        lines.push_item(
+            Line::from(vec![
+                Span::styled(format!(" {marker} "), line_style),
+                Span::styled(agent.id.clone(), line_style),
+                Span::styled(tags, tag_style),
+            ]),
+            idx,
+        );
+        if let Some(description) = &agent.description {
+            lines.push_item(
+                Line::from(Span::styled(
+                    format!("   {description}"),
+                    Style::default().fg(TEXT),
+                )),
+                idx,
+            );
+        }
+        let mut details = vec![tool_policy_summary(&agent.tool_policy)];
+        if let Some(model) = &agent.model {
+            details.push(format!("model {}/{}", model.provider, model.name));
+        }
+        if let Some(mode) = agent.permission_mode {
+            details.push(format!("permissions {mode:?}"));
+        }
+        lines.push_item(
+            Line::from(Span::styled(
+                format!("   {}", details.join("  ·  ")),
+                Style::default().fg(TEXT_MUTED),
+            )),
+            idx,
+        );
+        lines.push(Line::from(Span::raw("")));
+    }
+    lines
+}
+
+/// One-line summary of an agent's tool allow/deny lists.
+fn tool_policy_summary(policy: &ToolPolicy) -> String {
+    // An empty allow list allows every tool, as in the tool router.
+    let allowed = if policy.allow.is_empty() || policy.allow.iter().any(|tool| tool == "*") {
+        "all tools".to_string()
+    } else {
+        format!("tools {}", policy.allow.join(", "))
+    };
+    if policy.deny.is_empty() {
+        allowed
+    } else {
+        format!("{allowed} except {}", policy.deny.join(", "))
+    }
+}
+
+fn render_help_lines(app: &App) -> Vec<Line<'static>> {
+    let sections = help_sections(&app.prompt_presets);
+    let key_width = sections
+        .iter()
+        .flat_map(|section| &section.rows)
+        .map(|(keys, _)| keys.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = Vec::new();
+    for section in sections {
+        lines.push(Line::from(Span::styled(
+            format!(" {}", section.title),
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        )));
+        for (keys, description) in section.rows {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("   {keys:<key_width$}  "),
+                    Style::default().fg(SECONDARY),
+                ),
+                Span::styled(description, Style::default().fg(TEXT)),
+            ]));
+        }
+        lines.push(Line::from(Span::raw("")));
+    }
+    lines
+}
+
+/// Badge color of a tool risk class.
+fn risk_color(risk: ToolRisk) -> Color {
+    match risk {
+        ToolRisk::ReadOnly => TEXT_MUTED,
+        ToolRisk::WorkspaceMutating => YELLOW,
+        ToolRisk::ExternalNetwork => SECONDARY,
+        ToolRisk::Destructive => Color::Rgb(255, 110, 110),
+    }
+}
+
+fn render_approval_lines(app: &App) -> TaggedLines {
+    let mut lines = TaggedLines::default();
+
+    if app.approvals.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " No pending approvals.",
+            Style::default().fg(TEXT_MUTED),
+        )));
+        return lines;
     }
 
+    for (idx, approval) in app.approvals.iter().enumerate() {
+        let is_selected = idx == app.selected_approval;
+        let style = if is_selected {
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(TEXT)
+        };
+        let marker = if is_selected { ">" } else { " " };
+        let session = approval.session_id.to_string();
+        let active_tag = if app.active_session == Some(approval.session_id) {
+            " (active)"
+        } else {
+            ""
+        };
+
+        let mut spans = vec![Span::styled(format!(" {marker} "), style)];
+        if let Some(risk) = approval.risk {
+            spans.push(Span::styled(
+                format!("[{}] ", risk.label()),
+                Style::default().fg(risk_color(risk)),
+            ));
+        }
+        spans.push(Span::styled(
+            format_permission_request(&approval.request),
+            style,
+        ));
+        lines.push_item(Line::from(spans), idx);
+        lines.push_item(
+            Line::from(vec![Span::styled(
+                format!(
+                    "   session {}{active_tag}  agent {}",
+                    &session[..8.min(session.len())],
+                    approval.agent_id
+                ),
+                Style::default().fg(TEXT_MUTED),
+            )]),
+            idx,
+        );
+    }
     lines
 }